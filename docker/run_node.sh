@@ -39,6 +39,9 @@ export RUST_LOG=${RUST_LOG:-debug,error,info}
 export LOG_SIMPLE=${LOG_SIMPLE:-true}
 export LOG_ALL=${LOG_ALL:-1}
 
+# Tool Permissions (JSON, see docs/DEPLOYMENT.md). Unrestricted when unset
+export TOOL_PERMISSIONS_POLICY=${TOOL_PERMISSIONS_POLICY:-}
+
 # AI Provider Configuration
 export EMBEDDINGS_SERVER_URL=${EMBEDDINGS_SERVER_URL:-}
 export PROXY_IDENTITY=${PROXY_IDENTITY:-@@relayer_pub_01.sep-hanzo}
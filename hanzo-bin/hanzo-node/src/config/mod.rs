@@ -48,11 +48,9 @@ pub struct EmbeddingsConfig {
     pub use_native_embeddings: bool,
     pub use_gpu: bool,
     pub default_embedding_model: String,
-    pub reranker_model: Option<String>,
     pub embeddings_server_url: Option<String>,
     pub embeddings_server_api_key: Option<String>,
     pub native_model_path: Option<String>,
-    pub reranker_model_path: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -191,11 +189,9 @@ impl Config {
                     .unwrap_or(true),
                 default_embedding_model: env::var("DEFAULT_EMBEDDING_MODEL")
                     .unwrap_or_else(|_| "qwen3-embedding-8b".to_string()),
-                reranker_model: env::var("RERANKER_MODEL").ok(),
                 embeddings_server_url: env::var("EMBEDDINGS_SERVER_URL").ok(),
                 embeddings_server_api_key: env::var("EMBEDDINGS_SERVER_API_KEY").ok(),
                 native_model_path: env::var("NATIVE_MODEL_PATH").ok(),
-                reranker_model_path: env::var("RERANKER_MODEL_PATH").ok(),
             },
             engine: EngineConfig {
                 use_local_engine: env::var("USE_LOCAL_ENGINE")
@@ -2,6 +2,7 @@
 pub mod cron_tasks;
 pub mod llm_provider;
 pub mod managers;
+pub mod monitoring;
 pub mod network;
 pub mod runner;
 pub mod tools;
@@ -85,6 +85,7 @@ pub enum LLMProviderError {
     MessageTooLargeForLLM { max_tokens: usize, used_tokens: usize },
     SomeError(String),
     APIError(String),
+    ProviderHttpError(u16, String),
     DatabaseError(String),
     ImageProcessingError(String),
 }
@@ -180,6 +181,7 @@ impl fmt::Display for LLMProviderError {
             },
             LLMProviderError::SomeError(s) => write!(f, "{}", s),
             LLMProviderError::APIError(s) => write!(f, "{}", s),
+            LLMProviderError::ProviderHttpError(_, s) => write!(f, "{}", s),
             LLMProviderError::DatabaseError(s) => write!(f, "{}", s),
             LLMProviderError::ImageProcessingError(s) => write!(f, "Image processing error: {}", s),
         }
@@ -262,13 +264,33 @@ impl LLMProviderError {
             LLMProviderError::AgentNotFound(_) => "AgentNotFound",
             LLMProviderError::MessageTooLargeForLLM { .. } => "MessageTooLargeForLLM",
             LLMProviderError::SomeError(_) => "SomeError",
-            LLMProviderError::APIError(_) => "APIError",
+            LLMProviderError::APIError(_) | LLMProviderError::ProviderHttpError(..) => "APIError",
             LLMProviderError::DatabaseError(_) => "DatabaseError",
             LLMProviderError::ImageProcessingError(_) => "ImageProcessingError",
         };
 
         format!("Error {} with message: {}", error_name, self)
     }
+
+    /// Whether the provider was rate limited (429), failed on its side (5xx) or could not be
+    /// reached, i.e. the same request may well succeed on another provider
+    pub fn is_transient(&self) -> bool {
+        let is_transient_status = |status: u16| status == 429 || (500..600).contains(&status);
+        match self {
+            LLMProviderError::ReqwestError(err) => match err.status() {
+                Some(status) => is_transient_status(status.as_u16()),
+                None => err.is_connect() || err.is_timeout() || err.is_request() || err.is_body(),
+            },
+            LLMProviderError::ProviderHttpError(status, _) => is_transient_status(*status),
+            LLMProviderError::HanzoBackendUnexpectedStatusCode(status) => {
+                u16::try_from(*status).map_or(false, is_transient_status)
+            }
+            LLMProviderError::NetworkError(_)
+            | LLMProviderError::LLMServiceInferenceLimitReached(_)
+            | LLMProviderError::HanzoBackendInferenceLimitReached(_) => true,
+            _ => false,
+        }
+    }
 }
 
 impl From<AnyhowError> for LLMProviderError {
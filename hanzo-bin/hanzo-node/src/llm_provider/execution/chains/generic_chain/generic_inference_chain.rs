@@ -1,6 +1,6 @@
 use crate::llm_provider::error::LLMProviderError;
 use crate::llm_provider::execution::chains::inference_chain_trait::{
-    InferenceChain, InferenceChainContext, InferenceChainContextTrait, InferenceChainResult,
+    FunctionCall, InferenceChain, InferenceChainContext, InferenceChainContextTrait, InferenceChainResult,
 };
 use crate::llm_provider::execution::prompts::general_prompts::JobPromptGenerator;
 use crate::llm_provider::execution::user_message_parser::ParsedUserMessage;
@@ -10,6 +10,7 @@ use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::llm_provider::providers::shared::shared_model_logic::send_tool_ws_update_with_status;
 use crate::managers::model_capabilities_manager::ModelCapabilitiesManager;
 use crate::managers::tool_router::{ToolCallFunctionResponse, ToolRouter};
+use crate::monitoring::telemetry::in_span;
 use crate::network::agent_payments_manager::external_agent_offerings_manager::ExtAgentOfferingsManager;
 use crate::network::agent_payments_manager::my_agent_offerings_manager::MyAgentOfferingsManager;
 use hanzo_fs::hanzo_file_manager::HanzoFileManager;
@@ -23,6 +24,7 @@ use hanzo_fs::hanzo_fs_error::HanzoFsError;
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job::{Job, JobLike};
 use hanzo_messages::schemas::llm_providers::common_agent_llm_provider::ProviderOrAgent;
+use hanzo_messages::schemas::llm_usage::{LLMUsageGroupBy, LLMUsageQuery};
 use hanzo_messages::schemas::hanzo_fs::HanzoFileChunkCollection;
 use hanzo_messages::schemas::hanzo_name::HanzoName;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
@@ -34,6 +36,7 @@ use hanzo_db_sqlite::SqliteManager;
 
 use base64::Engine;
 use chrono;
+use opentelemetry::KeyValue;
 use serde_json::json;
 use std::fmt;
 use std::path::PathBuf;
@@ -412,7 +415,7 @@ impl GenericInferenceChain {
         ext_agent_payments_manager: Option<Arc<Mutex<ExtAgentOfferingsManager>>>,
         job_callback_manager: Option<Arc<Mutex<JobCallbackManager>>>,
         llm_stopper: Arc<LLMStopper>,
-        _node_env: NodeEnvironment,
+        node_env: NodeEnvironment,
     ) -> Result<InferenceChainResult, LLMProviderError> {
         hanzo_log(
             HanzoLogOption::JobExecution,
@@ -539,38 +542,52 @@ impl GenericInferenceChain {
             &format!("start_generic_inference_chain> audio files: {:?}", audio_files.keys()),
         );
 
+        let mut job_config = full_job.config();
+        if let ProviderOrAgent::Agent(agent) = &llm_provider {
+            job_config = agent.config.as_ref();
+        }
+
+        hanzo_log(
+            HanzoLogOption::JobExecution,
+            HanzoLogLevel::Info,
+            &format!("job_config: {:?}", job_config),
+        );
+
         if !scope_is_empty
             || !merged_fs_files_paths.is_empty()
             || !merged_fs_folder_paths.is_empty()
             || !job_filenames.is_empty()
         {
-            let ret = JobManager::search_for_chunks_in_resources(
-                merged_fs_files_paths.clone(),
-                merged_fs_folder_paths.clone(),
-                job_filenames.clone(),
-                full_job.job_id.clone(),
-                full_job.scope(),
-                db.clone(),
-                user_message.clone(),
-                20,
-                max_tokens_in_prompt,
-                generator.clone(),
+            let ret = in_span(
+                "retrieval",
+                vec![KeyValue::new("job.id", full_job.job_id.clone())],
+                JobManager::search_for_chunks_in_resources(
+                    merged_fs_files_paths.clone(),
+                    merged_fs_folder_paths.clone(),
+                    job_filenames.clone(),
+                    full_job.job_id.clone(),
+                    full_job.scope(),
+                    db.clone(),
+                    user_message.clone(),
+                    20,
+                    max_tokens_in_prompt,
+                    generator.clone(),
+                    job_config.and_then(|config| config.keyword_search_weight),
+                    JobManager::reranker_from_env(&node_env),
+                    node_env.rerank_candidates_multiplier,
+                ),
             )
             .await?;
             ret_nodes = ret;
         }
 
-        // 2) Vector search for tooling / workflows if the workflow / tooling scope isn't empty
-        let mut job_config = full_job.config();
-        if let ProviderOrAgent::Agent(agent) = &llm_provider {
-            job_config = agent.config.as_ref();
+        // Resources of remote MCP servers attached to the scope are always added in full
+        if !full_job.scope().mcp_resources.is_empty() {
+            let mcp_resources = JobManager::retrieve_mcp_resources_in_job_scope(full_job.scope(), &db).await;
+            ret_nodes.chunks.extend(mcp_resources.chunks);
         }
 
-        hanzo_log(
-            HanzoLogOption::JobExecution,
-            HanzoLogLevel::Info,
-            &format!("job_config: {:?}", job_config),
-        );
+        // 2) Vector search for tooling / workflows if the workflow / tooling scope isn't empty
         let mut tools = vec![];
 
         // Decision Process for Tool Selection:
@@ -1092,41 +1109,42 @@ impl GenericInferenceChain {
         loop {
             // Check if max_iterations is reached
             if iteration_count >= max_iterations {
-                let answer_duration_ms = Some(format!("{:.2}", start_time.elapsed().as_millis()));
                 let max_iterations_message = format!(
                     "Maximum iterations ({}) reached. Process stopped after {} tool calls.",
                     max_iterations,
                     tool_calls_history.len()
                 );
+                return Ok(Self::stopped_chain_result(
+                    max_iterations_message,
+                    &all_llm_messages,
+                    &all_reasoning_content,
+                    start_time,
+                    &tool_calls_history,
+                    &all_generated_files,
+                ));
+            }
 
-                // Use the accumulator to show the full conversation if max_iterations is reached
-                let full_conversation = all_llm_messages
-                    .iter()
-                    .map(|msg: &String| msg.trim())
-                    .filter(|msg| !msg.is_empty())
-                    .collect::<Vec<&str>>()
-                    .join("\n\n");
-
-                let full_reasoning_content = all_reasoning_content
-                    .iter()
-                    .map(|msg: &String| msg.trim())
-                    .filter(|msg| !msg.is_empty())
-                    .collect::<Vec<&str>>()
-                    .join("\n\n");
-
-                let inference_result = InferenceChainResult::with_full_details(
-                    format!("{}\n\n{}", full_conversation, max_iterations_message),
-                    Some(full_reasoning_content),
-                    None,
-                    answer_duration_ms,
-                    Some(tool_calls_history.clone()),
-                    all_generated_files.clone(),
+            // 4) Call LLM, unless the job or the agent ran out of budget
+            if let Some(reason) = Self::exhausted_spending_budget(&db, &full_job, &llm_provider) {
+                hanzo_log(
+                    HanzoLogOption::JobExecution,
+                    HanzoLogLevel::Info,
+                    &format!("Stopping job {}: {}", full_job.job_id, reason),
                 );
-
-                return Ok(inference_result);
+                let budget_message = format!(
+                    "{}. Process stopped after {} tool calls.",
+                    reason,
+                    tool_calls_history.len()
+                );
+                return Ok(Self::stopped_chain_result(
+                    budget_message,
+                    &all_llm_messages,
+                    &all_reasoning_content,
+                    start_time,
+                    &tool_calls_history,
+                    &all_generated_files,
+                ));
             }
-
-            // 4) Call LLM
             let inbox_name: Option<InboxName> = match InboxName::get_job_inbox_name_from_params(full_job.job_id.clone())
             {
                 Ok(name) => Some(name),
@@ -1523,6 +1541,108 @@ impl GenericInferenceChain {
         }
     }
 
+    /// Builds the result of a chain stopped before the LLM gave a final answer: the conversation so
+    /// far followed by the reason it was stopped.
+    fn stopped_chain_result(
+        stop_message: String,
+        all_llm_messages: &[String],
+        all_reasoning_content: &[String],
+        start_time: Instant,
+        tool_calls_history: &[FunctionCall],
+        all_generated_files: &[HanzoPath],
+    ) -> InferenceChainResult {
+        let answer_duration_ms = Some(format!("{:.2}", start_time.elapsed().as_millis()));
+
+        // Use the accumulator to show the full conversation if the process is stopped
+        let full_conversation = all_llm_messages
+            .iter()
+            .map(|msg: &String| msg.trim())
+            .filter(|msg| !msg.is_empty())
+            .collect::<Vec<&str>>()
+            .join("\n\n");
+
+        let full_reasoning_content = all_reasoning_content
+            .iter()
+            .map(|msg: &String| msg.trim())
+            .filter(|msg| !msg.is_empty())
+            .collect::<Vec<&str>>()
+            .join("\n\n");
+
+        InferenceChainResult::with_full_details(
+            format!("{}\n\n{}", full_conversation, stop_message),
+            Some(full_reasoning_content),
+            None,
+            answer_duration_ms,
+            Some(tool_calls_history.to_vec()),
+            all_generated_files.to_vec(),
+        )
+    }
+
+    /// Checks the budget of the job and the one of the agent answering it against the usage recorded
+    /// so far. Every limit of a budget is measured over the same usage: everything recorded for the
+    /// job, or everything recorded for the agent during the current UTC day. Returns which budget is
+    /// exhausted, or None while the chain can keep calling the LLM. A budget whose usage can't be
+    /// read counts as exhausted, so a failing database doesn't let a job spend without limits.
+    fn exhausted_spending_budget(db: &SqliteManager, full_job: &Job, llm_provider: &ProviderOrAgent) -> Option<String> {
+        let used = |query: LLMUsageQuery| match db.get_llm_usage_summary(&query) {
+            Ok(summaries) => Ok(summaries
+                .iter()
+                .fold((0, 0.0, 0), |(tokens, cost, tool_calls), summary| {
+                    (
+                        tokens + summary.input_tokens + summary.output_tokens,
+                        cost + summary.cost,
+                        tool_calls + summary.tool_calls,
+                    )
+                })),
+            Err(e) => {
+                hanzo_log(
+                    HanzoLogOption::JobExecution,
+                    HanzoLogLevel::Error,
+                    &format!("Failed to read the LLM usage for the budget check: {:?}", e),
+                );
+                Err("Spending budget could not be checked".to_string())
+            }
+        };
+
+        if let Some(budget) = full_job.config().and_then(|config| config.budget.as_ref()) {
+            let (tokens, cost, tool_calls) = match used(LLMUsageQuery {
+                group_by: LLMUsageGroupBy::Job,
+                job_id: Some(full_job.job_id.clone()),
+                agent_id: None,
+                llm_provider_id: None,
+                start_date: None,
+                end_date: None,
+            }) {
+                Ok(used) => used,
+                Err(reason) => return Some(reason),
+            };
+            if let Some(reason) = budget.exhausted_reason(tokens, cost, tool_calls) {
+                return Some(format!("Job {}", reason));
+            }
+        }
+
+        if let ProviderOrAgent::Agent(agent) = llm_provider {
+            if let Some(budget) = agent.config.as_ref().and_then(|config| config.budget.as_ref()) {
+                let (tokens, cost, tool_calls) = match used(LLMUsageQuery {
+                    group_by: LLMUsageGroupBy::Agent,
+                    job_id: None,
+                    agent_id: Some(agent.agent_id.clone()),
+                    llm_provider_id: None,
+                    start_date: Some(chrono::Utc::now().format("%Y-%m-%d").to_string()),
+                    end_date: None,
+                }) {
+                    Ok(used) => used,
+                    Err(reason) => return Some(reason),
+                };
+                if let Some(reason) = budget.exhausted_reason(tokens, cost, tool_calls) {
+                    return Some(format!("Agent {} daily {}", agent.agent_id, reason));
+                }
+            }
+        }
+
+        None
+    }
+
     /// Triggers a WebSocket update after receiving a function response.
     async fn trigger_ws_update(
         ws_manager_trait: &Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
@@ -13,6 +13,7 @@ use hanzo_embed::embedding_generator::RemoteEmbeddingGenerator;
 use hanzo_messages::schemas::job::Job;
 use hanzo_messages::schemas::llm_providers::common_agent_llm_provider::ProviderOrAgent;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::schemas::hanzo_name::HanzoName;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_messages::hanzo_message::hanzo_message_schemas::FunctionCallMetadata;
@@ -433,6 +434,10 @@ pub struct LLMInferenceResponse {
     pub generated_files: Vec<HanzoPath>,
     pub json: JsonValue,
     pub tps: Option<f64>,
+    /// Token usage reported by the provider, when it sends one
+    pub usage: Option<TokenUsage>,
+    /// Provider that answered, when it isn't the one that was called (members of provider groups)
+    pub served_by: Option<SerializedLLMProvider>,
 }
 
 impl LLMInferenceResponse {
@@ -451,9 +456,21 @@ impl LLMInferenceResponse {
             function_calls,
             generated_files,
             tps,
+            usage: None,
+            served_by: None,
         }
     }
 
+    pub fn with_usage(mut self, usage: Option<TokenUsage>) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    pub fn with_served_by(mut self, served_by: SerializedLLMProvider) -> Self {
+        self.served_by = Some(served_by);
+        self
+    }
+
     pub fn is_function_calls_empty(&self) -> bool {
         self.function_calls.is_empty()
     }
@@ -6,6 +6,7 @@ use crate::llm_provider::llm_stopper::LLMStopper;
 
 use crate::managers::model_capabilities_manager::{ModelCapabilitiesManager, ModelCapability};
 use crate::managers::tool_router::ToolRouter;
+use crate::monitoring::telemetry::{end_span, start_trace, trace_id};
 use crate::network::agent_payments_manager::external_agent_offerings_manager::ExtAgentOfferingsManager;
 use crate::network::agent_payments_manager::my_agent_offerings_manager::MyAgentOfferingsManager;
 use ed25519_dalek::SigningKey;
@@ -14,6 +15,7 @@ use base64::Engine;
 use hanzo_embed::embedding_generator::RemoteEmbeddingGenerator;
 use hanzo_fs::hanzo_file_manager::HanzoFileManager;
 use hanzo_job_queue_manager::job_queue_manager::{JobForProcessing, JobQueueManager};
+use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job::{Job, JobLike};
 use hanzo_messages::schemas::llm_providers::common_agent_llm_provider::ProviderOrAgent;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
@@ -25,6 +27,9 @@ use hanzo_messages::{
     hanzo_utils::{hanzo_message_builder::HanzoMessageBuilder, signatures::clone_signature_secret_key},
 };
 use hanzo_db_sqlite::SqliteManager;
+use opentelemetry::trace::FutureExt;
+use opentelemetry::KeyValue;
+use serde_json::json;
 use std::result::Result::Ok;
 use std::sync::Weak;
 use std::time::Instant;
@@ -86,6 +91,27 @@ impl JobManager {
         )
         .unwrap();
 
+        // One trace per job message, the LLM, tool, retrieval and payment spans are its children
+        let mut trace_attributes = vec![
+            KeyValue::new("job.id", job_id.clone()),
+            KeyValue::new("user.profile", user_profile.to_string()),
+        ];
+        if let Some(message_id) = &job_message.message_hash_id {
+            trace_attributes.push(KeyValue::new("message.id", message_id.clone()));
+        }
+        let trace_context = start_trace("job_message", trace_attributes);
+        if let (Some(message_id), Some(trace_id)) = (&job_message.message_hash_id, trace_id(&trace_context)) {
+            let inbox_name = InboxName::get_job_inbox_name_from_params(job_id.clone()).ok();
+            if let Err(e) = db.add_tracing(
+                message_id,
+                inbox_name.as_ref().map(|i| i.get_value()).as_deref(),
+                "otel_trace",
+                &json!({ "trace_id": trace_id }),
+            ) {
+                eprintln!("failed to add otel trace: {:?}", e);
+            }
+        }
+
         let inference_chain_result = JobManager::process_inference_chain(
             db.clone(),
             clone_signature_secret_key(&identity_secret_key),
@@ -103,7 +129,9 @@ impl JobManager {
             // sqlite_logger.clone(),
             llm_stopper.clone(),
         )
+        .with_context(trace_context.clone())
         .await;
+        end_span(&trace_context, inference_chain_result.as_ref().err());
 
         if let Err(e) = inference_chain_result {
             return Self::handle_error(&db, Some(user_profile), &job_id, &identity_secret_key, e, ws_manager).await;
@@ -7,10 +7,13 @@ use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job::Job;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::llm_providers::common_agent_llm_provider::ProviderOrAgent;
+use hanzo_messages::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
+use hanzo_messages::schemas::llm_usage::{LLMUsageRecord, TokenUsage};
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::hanzo_name::HanzoName;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
+use hanzo_messages::hanzo_utils::utils::count_tokens_from_message_llama3;
 use hanzo_db_sqlite::errors::SqliteManagerError;
 use hanzo_db_sqlite::SqliteManager;
 use std::result::Result::Ok;
@@ -31,10 +34,16 @@ impl JobManager {
     ) -> Result<LLMInferenceResponse, LLMProviderError> {
         let llm_provider_cloned = llm_provider.clone();
         let prompt_cloned = filled_prompt.clone();
+        let agent_id = match &llm_provider {
+            ProviderOrAgent::Agent(agent) => Some(agent.agent_id.clone()),
+            ProviderOrAgent::LLMProvider(_) => None,
+        };
+        let job_id = inbox_name.as_ref().and_then(|inbox| inbox.get_job_id());
+        let message_id = tracing_message_id.clone();
 
         let task_response = tokio::spawn(async move {
             let llm_provider = LLMProvider::from_provider_or_agent(llm_provider_cloned, db.clone()).await?;
-            llm_provider
+            let response = llm_provider
                 .inference(
                     prompt_cloned,
                     inbox_name,
@@ -43,7 +52,28 @@ impl JobManager {
                     llm_stopper,
                     tracing_message_id,
                 )
-                .await
+                .await?;
+
+            // Providers that don't report usage are charged an estimate, or budgets would never stop them
+            let usage = response
+                .usage
+                .clone()
+                .unwrap_or_else(|| Self::estimate_token_usage(&filled_prompt, &response));
+            let serialized_llm_provider = response
+                .served_by
+                .clone()
+                .unwrap_or_else(|| llm_provider.to_serialized_llm_provider());
+            let tool_calls = response.function_calls.len() as u64;
+            Self::record_llm_usage(
+                &db,
+                &serialized_llm_provider,
+                agent_id,
+                job_id,
+                message_id,
+                usage,
+                tool_calls,
+            );
+            Ok::<_, LLMProviderError>(response)
         })
         .await;
 
@@ -57,6 +87,59 @@ impl JobManager {
         response
     }
 
+    /// Estimates the token usage of an inference call from its prompt and response, for providers
+    /// that don't report one.
+    fn estimate_token_usage(prompt: &Prompt, response: &LLMInferenceResponse) -> TokenUsage {
+        let input_tokens = prompt
+            .generate_single_output_string()
+            .map(|prompt| count_tokens_from_message_llama3(&prompt))
+            .unwrap_or_default();
+        let mut output_tokens = count_tokens_from_message_llama3(&response.response_string);
+        if !response.function_calls.is_empty() {
+            let function_calls = serde_json::to_string(&response.function_calls).unwrap_or_default();
+            output_tokens += count_tokens_from_message_llama3(&function_calls);
+        }
+        TokenUsage {
+            input_tokens: input_tokens as u64,
+            output_tokens: output_tokens as u64,
+            cached_tokens: 0,
+            reasoning_tokens: 0,
+        }
+    }
+
+    /// Records the token usage and requested tool calls of an inference call, so LLM spend can be
+    /// reported per job, agent and provider. A failure is only logged, it shouldn't fail the inference.
+    fn record_llm_usage(
+        db: &SqliteManager,
+        serialized_llm_provider: &SerializedLLMProvider,
+        agent_id: Option<String>,
+        job_id: Option<String>,
+        message_id: Option<String>,
+        usage: TokenUsage,
+        tool_calls: u64,
+    ) {
+        let record = LLMUsageRecord {
+            job_id,
+            message_id,
+            agent_id,
+            llm_provider_id: serialized_llm_provider.id.clone(),
+            provider: serialized_llm_provider.get_provider_string(),
+            model: serialized_llm_provider.get_model_string(),
+            usage,
+            tool_calls,
+            cost: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        if let Err(e) = db.add_llm_usage(record) {
+            hanzo_log(
+                HanzoLogOption::JobExecution,
+                HanzoLogLevel::Error,
+                format!("Failed to record LLM usage: {}", e).as_str(),
+            );
+        }
+    }
+
     /// Fetches boilerplate/relevant data required for a job to process a step
     /// it may return an outdated node_name
     pub async fn fetch_relevant_job_data(
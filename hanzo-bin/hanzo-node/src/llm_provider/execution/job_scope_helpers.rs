@@ -1,6 +1,7 @@
 use crate::llm_provider::job_manager::JobManager;
+use crate::network::mcp_manager::{read_mcp_server_resource, resource_contents_text};
 use hanzo_fs::hanzo_file_manager::HanzoFileManager;
-use hanzo_messages::schemas::hanzo_fs::HanzoFileChunkCollection;
+use hanzo_messages::schemas::hanzo_fs::{HanzoFileChunk, HanzoFileChunkCollection};
 use hanzo_messages::hanzo_utils::job_scope::MinimalJobScope;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
 use hanzo_messages::hanzo_utils::hanzo_path::HanzoPath;
@@ -48,9 +49,76 @@ impl JobManager {
             }
         }
 
+        // Retrieve the resources of remote MCP servers
+        let mcp_collection = JobManager::retrieve_mcp_resources_in_job_scope(scope, sqlite_manager).await;
+        if !mcp_collection.chunks.is_empty() {
+            collections.push(mcp_collection);
+        }
+
         Ok(collections)
     }
 
+    /// Reads the MCP resources attached to the job scope from their servers. Each resource becomes a chunk;
+    /// resources that can't be read or have no text content are skipped.
+    pub async fn retrieve_mcp_resources_in_job_scope(
+        scope: &MinimalJobScope,
+        sqlite_manager: &SqliteManager,
+    ) -> HanzoFileChunkCollection {
+        let mut chunks = Vec::new();
+        for resource in &scope.mcp_resources {
+            let server = match sqlite_manager.get_mcp_server(resource.mcp_server_id) {
+                Ok(Some(server)) if server.is_enabled => server,
+                Ok(_) => {
+                    hanzo_log(
+                        HanzoLogOption::JobExecution,
+                        HanzoLogLevel::Error,
+                        &format!(
+                            "MCP server {} of resource {} not found or disabled",
+                            resource.mcp_server_id, resource.uri
+                        ),
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    hanzo_log(
+                        HanzoLogOption::JobExecution,
+                        HanzoLogLevel::Error,
+                        &format!("Error retrieving MCP server {}: {:?}", resource.mcp_server_id, e),
+                    );
+                    continue;
+                }
+            };
+
+            match read_mcp_server_resource(&server, resource.uri.clone()).await {
+                Ok(result) => {
+                    let text: Vec<String> = result.contents.iter().filter_map(resource_contents_text).collect();
+                    if text.is_empty() {
+                        continue;
+                    }
+                    chunks.push(HanzoFileChunk {
+                        chunk_id: None,
+                        parsed_file_id: 0,
+                        position: chunks.len() as i64,
+                        content: format!("{} ({}):\n{}", resource.uri, server.name, text.join("\n")),
+                        metadata: None,
+                    });
+                }
+                Err(e) => {
+                    hanzo_log(
+                        HanzoLogOption::JobExecution,
+                        HanzoLogLevel::Error,
+                        &format!(
+                            "Error reading MCP resource {} from {}: {}",
+                            resource.uri, server.name, e
+                        ),
+                    );
+                }
+            }
+        }
+
+        HanzoFileChunkCollection { chunks, paths: None }
+    }
+
     /// Static function to retrieve file chunks for a given path.
     pub async fn retrieve_file_chunks(
         path: &HanzoPath,
@@ -1,5 +1,7 @@
 use crate::llm_provider::job_manager::JobManager;
+use crate::utils::environment::NodeEnvironment;
 use hanzo_embed::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
+use hanzo_embed::reranker::{RemoteReranker, Reranker};
 use hanzo_fs::hanzo_file_manager::HanzoFileManager;
 use hanzo_messages::schemas::hanzo_fs::{HanzoFileChunk, HanzoFileChunkCollection};
 use hanzo_messages::hanzo_utils::job_scope::MinimalJobScope;
@@ -15,6 +17,37 @@ use std::result::Result::Ok;
 use std::sync::Arc;
 
 impl JobManager {
+    /// The reranker configured for the node, if any
+    pub fn reranker_from_env(node_env: &NodeEnvironment) -> Option<Box<dyn Reranker>> {
+        node_env.reranker_server_url.as_ref().map(|url| {
+            Box::new(RemoteReranker::new(url, node_env.reranker_server_api_key.clone())) as Box<dyn Reranker>
+        })
+    }
+
+    /// Reorders search results by the reranker's relevance scores and keeps the `limit` best.
+    /// Scores in the returned results are the reranker's (higher is better).
+    async fn rerank_chunks(
+        reranker: &dyn Reranker,
+        query_text: &str,
+        search_results: &[(HanzoFileChunk, f64)],
+        limit: usize,
+    ) -> Result<Vec<(HanzoFileChunk, f64)>, SqliteManagerError> {
+        let documents = search_results.iter().map(|(chunk, _)| chunk.content.clone()).collect();
+        let ranking = reranker
+            .rerank(query_text, documents, Some(limit))
+            .await
+            .map_err(|e| SqliteManagerError::SomeError(format!("Reranking failed: {}", e)))?;
+
+        Ok(ranking
+            .into_iter()
+            .filter_map(|(index, score)| {
+                search_results
+                    .get(index)
+                    .map(|(chunk, _)| (chunk.clone(), score as f64))
+            })
+            .collect())
+    }
+
     /// Helper function to process folders and collect file information
     async fn process_folder_contents(
         folder: &HanzoPath,
@@ -68,6 +101,9 @@ impl JobManager {
     }
 
     /// Searches all resources in the given job scope and returns the search results.
+    /// With a `keyword_search_weight` above 0, vector results are fused with BM25 keyword matches.
+    /// With a `reranker`, `rerank_candidates_multiplier` times more results are fetched and the
+    /// reranker picks the best `num_of_top_results` of them.
     pub async fn search_for_chunks_in_resources(
         fs_files_paths: Vec<HanzoPath>,
         fs_folder_paths: Vec<HanzoPath>,
@@ -79,6 +115,9 @@ impl JobManager {
         num_of_top_results: usize,
         max_tokens_in_prompt: usize,
         embedding_generator: RemoteEmbeddingGenerator,
+        keyword_search_weight: Option<f64>,
+        reranker: Option<Box<dyn Reranker>>,
+        rerank_candidates_multiplier: usize,
     ) -> Result<HanzoFileChunkCollection, SqliteManagerError> {
         let mut parsed_file_ids = Vec::new();
         let mut paths_map = HashMap::new();
@@ -197,8 +236,41 @@ impl JobManager {
             });
         }
 
-        // Perform a vector search on all parsed files
-        let search_results = sqlite_manager.search_chunks(&parsed_file_ids, query_embedding, num_of_top_results)?;
+        // Over-fetch candidates when they are going to be reranked
+        let num_of_candidates = match reranker {
+            Some(_) => num_of_top_results * rerank_candidates_multiplier.max(1),
+            None => num_of_top_results,
+        };
+
+        // Perform a vector search on all parsed files, hybrid if the job asks for keyword matches
+        let mut search_results = match keyword_search_weight {
+            Some(weight) if weight > 0.0 => sqlite_manager.search_chunks_hybrid(
+                &parsed_file_ids,
+                query_embedding,
+                &query_text,
+                num_of_candidates,
+                weight,
+            )?,
+            _ => sqlite_manager.search_chunks(&parsed_file_ids, query_embedding, num_of_candidates)?,
+        };
+
+        if let Some(reranker) = reranker {
+            let reranked =
+                Self::rerank_chunks(reranker.as_ref(), &query_text, &search_results, num_of_top_results).await;
+            search_results = match reranked {
+                Ok(reranked) => reranked,
+                Err(e) => {
+                    // Keep the search order rather than failing the job
+                    hanzo_log(
+                        HanzoLogOption::JobExecution,
+                        HanzoLogLevel::Error,
+                        &format!("{}, using search results as is", e),
+                    );
+                    search_results.truncate(num_of_top_results);
+                    search_results
+                }
+            };
+        }
 
         // If there are no initial results, just return early
         if search_results.is_empty() {
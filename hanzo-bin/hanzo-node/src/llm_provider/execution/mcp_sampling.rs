@@ -0,0 +1,182 @@
+use std::sync::{Arc, Weak};
+
+use async_trait::async_trait;
+use hanzo_db_sqlite::SqliteManager;
+use hanzo_mcp::client_handler::{McpSamplingHandler, McpSamplingRequest};
+use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
+use hanzo_messages::schemas::job_config::JobConfig;
+use hanzo_messages::schemas::llm_providers::common_agent_llm_provider::ProviderOrAgent;
+use hanzo_messages::schemas::mcp_server::{MCPSamplingPolicy, MCPServer};
+use hanzo_messages::schemas::prompts::Prompt;
+use hanzo_messages::schemas::subprompts::SubPromptType;
+use rmcp::model::{Content, CreateMessageRequestParam, CreateMessageResult, ErrorData, Role, SamplingMessage};
+
+use crate::llm_provider::job_manager::JobManager;
+use crate::llm_provider::llm_stopper::LLMStopper;
+
+/// Answers `sampling/createMessage` requests from MCP servers with the LLM provider of the job
+/// that is using the server, as allowed by the server's `MCPSamplingPolicy`.
+pub struct McpJobSamplingHandler {
+    db: Weak<SqliteManager>,
+    llm_stopper: Arc<LLMStopper>,
+}
+
+impl McpJobSamplingHandler {
+    pub fn new(db: Weak<SqliteManager>, llm_stopper: Arc<LLMStopper>) -> Self {
+        Self { db, llm_stopper }
+    }
+
+    fn find_server(db: &SqliteManager, server_key: &str) -> Result<MCPServer, ErrorData> {
+        db.get_all_mcp_servers()
+            .map_err(|e| ErrorData::internal_error(format!("Failed to get MCP servers: {}", e), None))?
+            .into_iter()
+            .find(|server| server.get_command_hash() == server_key)
+            .ok_or_else(|| ErrorData::invalid_request("Sampling is not allowed for unknown MCP servers", None))
+    }
+
+    /// The job's provider, or the default LLM provider when the request is not tied to a job.
+    async fn resolve_provider(db: Arc<SqliteManager>, job_id: Option<&str>) -> Result<ProviderOrAgent, ErrorData> {
+        if let Some(job_id) = job_id {
+            let (_, provider, _, _) = JobManager::fetch_relevant_job_data(job_id, db)
+                .await
+                .map_err(|e| ErrorData::internal_error(format!("Failed to get job {}: {}", job_id, e), None))?;
+            return provider
+                .ok_or_else(|| ErrorData::internal_error(format!("Job {} has no LLM provider", job_id), None));
+        }
+
+        let default_provider_id = db
+            .get_preference::<String>("default_llm_provider")
+            .ok()
+            .flatten()
+            .ok_or_else(|| ErrorData::internal_error("No default LLM provider is configured", None))?;
+        JobManager::get_all_agents_and_llm_providers(db)
+            .await
+            .map_err(|e| ErrorData::internal_error(format!("Failed to get LLM providers: {}", e), None))?
+            .into_iter()
+            .find(|provider| provider.get_id() == default_provider_id)
+            .ok_or_else(|| {
+                ErrorData::internal_error(format!("Default LLM provider {} not found", default_provider_id), None)
+            })
+    }
+
+    /// Builds a node prompt from the messages sent by the server. Only text content is supported.
+    fn build_prompt(params: &CreateMessageRequestParam) -> Prompt {
+        let mut prompt = Prompt::new();
+        if let Some(system_prompt) = &params.system_prompt {
+            prompt.add_content(system_prompt.clone(), SubPromptType::System, 98);
+        }
+        let last_index = params.messages.len().saturating_sub(1);
+        for (index, message) in params.messages.iter().enumerate() {
+            let Some(text) = message.content.as_text().map(|t| t.text.clone()) else {
+                continue;
+            };
+            let prompt_type = match message.role {
+                Role::Assistant => SubPromptType::Assistant,
+                Role::User if index == last_index => SubPromptType::UserLastMessage,
+                Role::User => SubPromptType::User,
+            };
+            prompt.add_content(text, prompt_type, 100);
+        }
+        prompt
+    }
+}
+
+#[async_trait]
+impl McpSamplingHandler for McpJobSamplingHandler {
+    async fn create_message(&self, request: McpSamplingRequest) -> Result<CreateMessageResult, ErrorData> {
+        let db = self
+            .db
+            .upgrade()
+            .ok_or_else(|| ErrorData::internal_error("Database is not available", None))?;
+
+        let server = Self::find_server(&db, &request.server_key)?;
+        let policy = db
+            .get_mcp_server_sampling_policy(server.id.unwrap_or_default())
+            .map_err(|e| ErrorData::internal_error(format!("Failed to get sampling policy: {}", e), None))?;
+        let approved = match policy {
+            MCPSamplingPolicy::Deny => false,
+            MCPSamplingPolicy::AllowDuringJob => request.job_id.is_some(),
+            MCPSamplingPolicy::Allow => true,
+        };
+        hanzo_log(
+            HanzoLogOption::JobExecution,
+            HanzoLogLevel::Info,
+            &format!(
+                "MCP server {} requested sampling (job: {:?}, policy: {:?}, approved: {})",
+                server.name, request.job_id, policy, approved
+            ),
+        );
+        if !approved {
+            return Err(ErrorData::invalid_request(
+                format!("Sampling is not allowed for MCP server {}", server.name),
+                None,
+            ));
+        }
+
+        let provider = Self::resolve_provider(db.clone(), request.job_id.as_deref()).await?;
+        let model = provider.get_llm_provider_id().to_string();
+        let prompt = Self::build_prompt(&request.params);
+        // The server's token limit is a hard cap, unlike the rest of its model preferences
+        let config = JobConfig {
+            max_tokens: Some(request.params.max_tokens as u64),
+            ..JobConfig::empty()
+        };
+        let response = JobManager::inference_with_llm_provider(
+            provider,
+            prompt,
+            None,
+            None,
+            Some(config),
+            self.llm_stopper.clone(),
+            db,
+            None,
+        )
+        .await
+        .map_err(|e| ErrorData::internal_error(format!("Sampling failed: {}", e), None))?;
+
+        Ok(CreateMessageResult {
+            model,
+            stop_reason: Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+            message: SamplingMessage {
+                role: Role::Assistant,
+                content: Content::text(response.response_string),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_prompt_maps_roles() {
+        let params: CreateMessageRequestParam = serde_json::from_value(json!({
+            "systemPrompt": "Be brief",
+            "maxTokens": 100,
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Hi" } },
+                { "role": "assistant", "content": { "type": "text", "text": "Hello" } },
+                { "role": "user", "content": { "type": "text", "text": "Summarize" } }
+            ]
+        }))
+        .unwrap();
+
+        let prompt = McpJobSamplingHandler::build_prompt(&params);
+        let types: Vec<SubPromptType> = prompt
+            .sub_prompts
+            .iter()
+            .map(|sub_prompt| sub_prompt.extract_generic_subprompt_data().0)
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                SubPromptType::System,
+                SubPromptType::User,
+                SubPromptType::Assistant,
+                SubPromptType::UserLastMessage
+            ]
+        );
+    }
+}
@@ -3,5 +3,6 @@ pub mod job_execution_core;
 pub mod job_execution_helpers;
 pub mod job_scope_helpers;
 pub mod job_vector_search;
+pub mod mcp_sampling;
 pub mod prompts;
 pub mod user_message_parser;
@@ -89,6 +89,7 @@ impl JobCallbackManager {
             "".to_string(),
             available_tools.clone(),
             db.clone(),
+            None,
         )
         .await?;
 
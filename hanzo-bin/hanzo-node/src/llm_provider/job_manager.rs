@@ -3,6 +3,7 @@ use super::job_callback_manager::JobCallbackManager;
 use super::llm_stopper::LLMStopper;
 use crate::managers::tool_router::ToolRouter;
 use crate::managers::IdentityManager;
+use crate::monitoring::{record_job_processing, update_job_queue_size};
 use crate::network::agent_payments_manager::external_agent_offerings_manager::ExtAgentOfferingsManager;
 use crate::network::agent_payments_manager::my_agent_offerings_manager::MyAgentOfferingsManager;
 use ed25519_dalek::SigningKey;
@@ -28,6 +29,7 @@ use std::env;
 use std::pin::Pin;
 use std::result::Result::Ok;
 use std::sync::Weak;
+use std::time::Instant;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::{Mutex, Semaphore};
 
@@ -230,6 +232,8 @@ impl JobManager {
                     let mgr = queue_immediate.lock().await;
                     let all_imm = mgr.get_all_elements_interleave().await.unwrap_or_else(|_| vec![]);
                     drop(mgr);
+                    update_job_queue_size("immediate", all_imm.len());
+                    update_job_queue_size("processing", processing_lock.len());
 
                     all_imm
                         .into_iter()
@@ -264,7 +268,8 @@ impl JobManager {
                         let in_progress = processing_jobs.clone();
 
                         tokio::spawn(async move {
-                            let _ = (job_processing_fn)(
+                            let started = Instant::now();
+                            let result = (job_processing_fn)(
                                 job,
                                 db_clone,
                                 node_profile_name,
@@ -279,6 +284,7 @@ impl JobManager {
                                 llm_stopper,
                             )
                             .await;
+                            record_job_processing("immediate", started.elapsed(), result.is_ok());
 
                             let _ = queue_immediate.lock().await.dequeue(&job_id).await;
                             let mut inprog = in_progress.lock().await;
@@ -296,6 +302,7 @@ impl JobManager {
                     let mgr = queue_normal.lock().await;
                     let all_norm = mgr.get_all_elements_interleave().await.unwrap_or_else(|_| vec![]);
                     drop(mgr);
+                    update_job_queue_size("normal", all_norm.len());
 
                     all_norm
                         .into_iter()
@@ -363,7 +370,8 @@ impl JobManager {
                                     let in_progress = processing_jobs.clone();
 
                                     tokio::spawn(async move {
-                                        (job_processing_fn)(
+                                        let started = Instant::now();
+                                        let result = (job_processing_fn)(
                                             job,
                                             db_clone,
                                             node_profile_name,
@@ -378,6 +386,7 @@ impl JobManager {
                                             llm_stopper,
                                         )
                                         .await;
+                                        record_job_processing("normal", started.elapsed(), result.is_ok());
 
                                         let _ = queue_normal.lock().await.dequeue(&job_id).await;
                                         let mut inprog = in_progress.lock().await;
@@ -415,7 +424,8 @@ impl JobManager {
                                         let in_progress = processing_jobs.clone();
 
                                         tokio::spawn(async move {
-                                            (job_processing_fn)(
+                                            let started = Instant::now();
+                                            let result = (job_processing_fn)(
                                                 imm_job,
                                                 db_clone,
                                                 node_profile_name,
@@ -430,6 +440,7 @@ impl JobManager {
                                                 llm_stopper,
                                             )
                                             .await;
+                                            record_job_processing("immediate", started.elapsed(), result.is_ok());
 
                                             let _ = queue_immediate.lock().await.dequeue(&imm_id).await;
                                             let mut inprog = in_progress.lock().await;
@@ -1,9 +1,13 @@
 use std::sync::Arc;
+use std::time::Instant;
 
 use super::error::LLMProviderError;
 use super::execution::chains::inference_chain_trait::LLMInferenceResponse;
 use super::llm_stopper::LLMStopper;
+use super::provider_group::ProviderGroupRouter;
 use super::providers::LLMService;
+use crate::monitoring::record_llm_request;
+use crate::monitoring::telemetry::{in_span, set_span_attributes};
 use reqwest::Client;
 use serde_json::{Map, Value as JsonValue};
 use hanzo_messages::schemas::inbox_name::InboxName;
@@ -17,6 +21,7 @@ use hanzo_messages::schemas::{
     hanzo_name::HanzoName,
 };
 use hanzo_db_sqlite::SqliteManager;
+use opentelemetry::KeyValue;
 use tokio::sync::Mutex;
 
 #[derive(Debug, Clone)]
@@ -104,7 +109,99 @@ impl LLMProvider {
         llm_stopper: Arc<LLMStopper>,
         tracing_message_id: Option<String>,
     ) -> Result<LLMInferenceResponse, LLMProviderError> {
-        let response = match &self.model {
+        if let LLMProviderInterface::ProviderGroup(provider_group) = &self.model {
+            return ProviderGroupRouter::inference(
+                self,
+                provider_group,
+                prompt,
+                inbox_name,
+                ws_manager_trait,
+                config,
+                llm_stopper,
+                tracing_message_id,
+            )
+            .await;
+        }
+
+        self.inference_with_provider(
+            prompt,
+            inbox_name,
+            ws_manager_trait,
+            config,
+            llm_stopper,
+            tracing_message_id,
+        )
+        .await
+    }
+
+    /// Calls the API of this provider. Provider groups are resolved by `inference` instead.
+    pub(crate) async fn inference_with_provider(
+        &self,
+        prompt: Prompt,
+        inbox_name: Option<InboxName>,
+        ws_manager_trait: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        config: Option<JobConfig>,
+        llm_stopper: Arc<LLMStopper>,
+        tracing_message_id: Option<String>,
+    ) -> Result<LLMInferenceResponse, LLMProviderError> {
+        let serialized_llm_provider = self.to_serialized_llm_provider();
+        let provider = serialized_llm_provider.get_provider_string();
+        let model = serialized_llm_provider.get_model_string();
+        let span_attributes = vec![
+            KeyValue::new("llm.provider_id", self.id.clone()),
+            KeyValue::new("llm.provider", provider.clone()),
+            KeyValue::new("llm.model", model.clone()),
+        ];
+
+        let started = Instant::now();
+        let response = in_span("llm_inference", span_attributes, async {
+            let response = self
+                .call_provider_api(
+                    prompt,
+                    inbox_name,
+                    ws_manager_trait,
+                    config,
+                    llm_stopper,
+                    tracing_message_id,
+                )
+                .await;
+            if let Some(usage) = response.as_ref().ok().and_then(|response| response.usage.as_ref()) {
+                set_span_attributes(vec![
+                    KeyValue::new("llm.input_tokens", usage.input_tokens as i64),
+                    KeyValue::new("llm.output_tokens", usage.output_tokens as i64),
+                ]);
+            }
+            response
+        })
+        .await;
+
+        let usage = response
+            .as_ref()
+            .ok()
+            .and_then(|response| response.usage.clone())
+            .unwrap_or_default();
+        record_llm_request(
+            &provider,
+            &model,
+            started.elapsed(),
+            response.is_ok(),
+            usage.input_tokens,
+            usage.output_tokens,
+        );
+
+        response
+    }
+
+    async fn call_provider_api(
+        &self,
+        prompt: Prompt,
+        inbox_name: Option<InboxName>,
+        ws_manager_trait: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        config: Option<JobConfig>,
+        llm_stopper: Arc<LLMStopper>,
+        tracing_message_id: Option<String>,
+    ) -> Result<LLMInferenceResponse, LLMProviderError> {
+        match &self.model {
             LLMProviderInterface::OpenAI(openai) => {
                 openai
                     .call_api(
@@ -323,8 +420,11 @@ impl LLMProvider {
                     )
                     .await
             }
-        }?;
-        Ok(response)
+            LLMProviderInterface::ProviderGroup(provider_group) => Err(LLMProviderError::InvalidModelType(format!(
+                "Provider groups can't be members of other provider groups: {}",
+                provider_group.model_type()
+            ))),
+        }
     }
 }
 
@@ -346,6 +446,18 @@ impl LLMProvider {
         )
     }
 
+    pub fn to_serialized_llm_provider(&self) -> SerializedLLMProvider {
+        SerializedLLMProvider {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            full_identity_name: self.full_identity_name.clone(),
+            external_url: self.external_url.clone(),
+            api_key: self.api_key.clone(),
+            model: self.model.clone(),
+        }
+    }
+
     pub async fn from_provider_or_agent(
         provider_or_agent: ProviderOrAgent,
         db: Arc<SqliteManager>,
@@ -7,4 +7,5 @@ pub mod llm_provider;
 pub mod llm_provider_to_serialization;
 pub mod llm_stopper;
 pub mod parsing_helper;
+pub mod provider_group;
 pub mod providers;
@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use dashmap::DashMap;
+use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
+use hanzo_messages::schemas::inbox_name::InboxName;
+use hanzo_messages::schemas::job_config::JobConfig;
+use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{
+    LLMProviderInterface, ProviderGroup, ProviderGroupMember, ProviderGroupStrategy, SerializedLLMProvider,
+};
+use hanzo_messages::schemas::prompts::Prompt;
+use hanzo_messages::schemas::ws_types::{WSMessageType, WSTopic, WSUpdateHandler};
+use lazy_static::lazy_static;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use super::error::LLMProviderError;
+use super::execution::chains::inference_chain_trait::LLMInferenceResponse;
+use super::llm_provider::LLMProvider;
+use super::llm_stopper::LLMStopper;
+
+/// Consecutive failures after which the circuit of a member opens
+const CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 3;
+/// How long a member with an open circuit is skipped before it gets a new chance
+const CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(60);
+/// Weight of the newest measurement in the moving average of the latency of a member
+const LATENCY_SMOOTHING_FACTOR: f64 = 0.3;
+
+lazy_static! {
+    static ref PROVIDER_GROUP_ROUTER: ProviderGroupRouter = ProviderGroupRouter::new();
+}
+
+#[derive(Debug, Default)]
+struct MemberHealth {
+    consecutive_failures: u32,
+    circuit_open_until: Option<Instant>,
+    /// Moving average of the time the member takes to answer, in seconds
+    latency: Option<f64>,
+}
+
+/// Picks the members of provider groups that serve each call. Keeps the health and the recent
+/// latency of every member provider and the round-robin state of every group for the lifetime of
+/// the node.
+#[derive(Default)]
+pub struct ProviderGroupRouter {
+    members: DashMap<String, MemberHealth>,
+    round_robin_weights: DashMap<String, HashMap<String, i64>>,
+}
+
+impl ProviderGroupRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls the members of the group in the order picked by its strategy until one of them
+    /// answers. Only rate limits, server errors and transport failures move on to the next member:
+    /// any other error would fail the same way everywhere. A member that already streamed part of
+    /// its answer is not replaced either, as the client would get two answers mixed up. Every
+    /// failed member is recorded in the message traces.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn inference(
+        group_provider: &LLMProvider,
+        provider_group: &ProviderGroup,
+        prompt: Prompt,
+        inbox_name: Option<InboxName>,
+        ws_manager_trait: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        config: Option<JobConfig>,
+        llm_stopper: Arc<LLMStopper>,
+        tracing_message_id: Option<String>,
+    ) -> Result<LLMInferenceResponse, LLMProviderError> {
+        let router = &*PROVIDER_GROUP_ROUTER;
+        let attempt_order = router.attempt_order(&group_provider.id, provider_group, Instant::now());
+        let db = group_provider.db.clone();
+
+        let mut last_error = None;
+        for (index, member) in attempt_order.iter().enumerate() {
+            let (error, circuit_opened) = match Self::get_member_provider(group_provider, member) {
+                Ok(member_provider) => {
+                    let llm_provider = LLMProvider::from_serialized_llm_provider(member_provider.clone(), db.clone());
+                    let streamed = Arc::new(AtomicBool::new(false));
+                    let member_ws_manager = ws_manager_trait.clone().map(|ws_manager| {
+                        Arc::new(Mutex::new(StreamTracker {
+                            ws_manager,
+                            streamed: streamed.clone(),
+                        })) as Arc<Mutex<dyn WSUpdateHandler + Send>>
+                    });
+
+                    let started = Instant::now();
+                    let response = llm_provider
+                        .inference_with_provider(
+                            prompt.clone(),
+                            inbox_name.clone(),
+                            member_ws_manager,
+                            config.clone(),
+                            llm_stopper.clone(),
+                            tracing_message_id.clone(),
+                        )
+                        .await;
+
+                    match response {
+                        Ok(response) => {
+                            router.record_success(&member.llm_provider_id, started.elapsed());
+                            return Ok(response.with_served_by(member_provider));
+                        }
+                        // The request itself was refused, e.g. it is invalid or too large
+                        Err(error) if !error.is_transient() => return Err(error),
+                        Err(error) => {
+                            let circuit_opened = router.record_failure(&member.llm_provider_id, Instant::now());
+                            if streamed.load(Ordering::SeqCst) {
+                                return Err(error);
+                            }
+                            (error, circuit_opened)
+                        }
+                    }
+                }
+                // The member is missing or misconfigured, which says nothing about the request
+                Err(error) => (error, false),
+            };
+
+            let next_member = attempt_order
+                .get(index + 1)
+                .map(|member| member.llm_provider_id.clone());
+            Self::trace_failover(
+                group_provider,
+                provider_group,
+                member,
+                &error,
+                circuit_opened,
+                next_member,
+                &tracing_message_id,
+                &inbox_name,
+            );
+            last_error = Some(error);
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            LLMProviderError::InvalidModelType(format!("Provider group {} has no members", group_provider.id))
+        }))
+    }
+
+    fn get_member_provider(
+        group_provider: &LLMProvider,
+        member: &ProviderGroupMember,
+    ) -> Result<SerializedLLMProvider, LLMProviderError> {
+        let member_provider = group_provider
+            .db
+            .get_llm_provider(&member.llm_provider_id, &group_provider.full_identity_name)
+            .ok()
+            .flatten()
+            .ok_or_else(|| LLMProviderError::AgentNotFound(member.llm_provider_id.clone()))?;
+
+        if let LLMProviderInterface::ProviderGroup(_) = member_provider.model {
+            return Err(LLMProviderError::InvalidModelType(format!(
+                "Provider groups can't be members of other provider groups: {}",
+                member.llm_provider_id
+            )));
+        }
+        Ok(member_provider)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn trace_failover(
+        group_provider: &LLMProvider,
+        provider_group: &ProviderGroup,
+        failed_member: &ProviderGroupMember,
+        error: &LLMProviderError,
+        circuit_opened: bool,
+        next_member: Option<String>,
+        tracing_message_id: &Option<String>,
+        inbox_name: &Option<InboxName>,
+    ) {
+        hanzo_log(
+            HanzoLogOption::JobExecution,
+            HanzoLogLevel::Error,
+            &format!(
+                "Provider group {}: member {} failed ({}), next member: {:?}",
+                group_provider.id, failed_member.llm_provider_id, error, next_member
+            ),
+        );
+
+        if let Some(msg_id) = tracing_message_id {
+            let trace_info = json!({
+                "provider_group": group_provider.id,
+                "strategy": provider_group.strategy.as_str(),
+                "failed_provider": failed_member.llm_provider_id,
+                "error": error.to_string(),
+                "circuit_opened": circuit_opened,
+                "next_provider": next_member,
+            });
+            if let Err(e) = group_provider.db.add_tracing(
+                msg_id,
+                inbox_name.as_ref().map(|i| i.get_value()).as_deref(),
+                "provider_group_failover",
+                &trace_info,
+            ) {
+                eprintln!("failed to add provider group failover trace: {:?}", e);
+            }
+        }
+    }
+
+    /// Returns the members of the group in the order they should be tried. Members with an open
+    /// circuit are left out, unless no member is available, in which case all of them are tried
+    /// rather than failing the call without trying.
+    fn attempt_order(&self, group_id: &str, provider_group: &ProviderGroup, now: Instant) -> Vec<ProviderGroupMember> {
+        let available: Vec<ProviderGroupMember> = provider_group
+            .members
+            .iter()
+            .filter(|member| self.is_available(&member.llm_provider_id, now))
+            .cloned()
+            .collect();
+        let mut members = if available.is_empty() {
+            provider_group.members.clone()
+        } else {
+            available
+        };
+
+        match provider_group.strategy {
+            ProviderGroupStrategy::Failover => {}
+            ProviderGroupStrategy::WeightedRoundRobin => {
+                let selected = self.next_round_robin_index(group_id, &members);
+                let selected_member = members.remove(selected);
+                members.insert(0, selected_member);
+            }
+            ProviderGroupStrategy::LowestLatency => {
+                // Members without a measurement go first so they get measured
+                members.sort_by(|a, b| {
+                    let a_latency = self.latency(&a.llm_provider_id).unwrap_or(f64::NEG_INFINITY);
+                    let b_latency = self.latency(&b.llm_provider_id).unwrap_or(f64::NEG_INFINITY);
+                    a_latency.total_cmp(&b_latency)
+                });
+            }
+        }
+
+        members
+    }
+
+    /// Smooth weighted round-robin: every member gains its weight, the member with the highest
+    /// current weight is picked and loses the total weight. Spreads the calls evenly instead of
+    /// sending bursts to the heaviest member.
+    fn next_round_robin_index(&self, group_id: &str, members: &[ProviderGroupMember]) -> usize {
+        let mut current_weights = self.round_robin_weights.entry(group_id.to_string()).or_default();
+        let total_weight: i64 = members.iter().map(|member| member.weight as i64).sum();
+
+        let mut selected = 0;
+        let mut selected_weight = i64::MIN;
+        for (index, member) in members.iter().enumerate() {
+            let current_weight = current_weights.entry(member.llm_provider_id.clone()).or_insert(0);
+            *current_weight += member.weight as i64;
+            if *current_weight > selected_weight {
+                selected = index;
+                selected_weight = *current_weight;
+            }
+        }
+        if let Some(current_weight) = current_weights.get_mut(&members[selected].llm_provider_id) {
+            *current_weight -= total_weight;
+        }
+
+        selected
+    }
+
+    fn is_available(&self, llm_provider_id: &str, now: Instant) -> bool {
+        match self
+            .members
+            .get(llm_provider_id)
+            .and_then(|health| health.circuit_open_until)
+        {
+            Some(open_until) => now >= open_until,
+            None => true,
+        }
+    }
+
+    fn latency(&self, llm_provider_id: &str) -> Option<f64> {
+        self.members.get(llm_provider_id).and_then(|health| health.latency)
+    }
+
+    /// Closes the circuit of the member and adds the time it took to answer to its latency.
+    /// Measured here rather than taken from the provider, as not every provider reports timings.
+    fn record_success(&self, llm_provider_id: &str, latency: Duration) {
+        let mut health = self.members.entry(llm_provider_id.to_string()).or_default();
+        health.consecutive_failures = 0;
+        health.circuit_open_until = None;
+        let latency = latency.as_secs_f64();
+        health.latency = Some(match health.latency {
+            Some(average) => average + LATENCY_SMOOTHING_FACTOR * (latency - average),
+            None => latency,
+        });
+    }
+
+    /// Counts a failure of the member and returns whether its circuit opened. Once the cooldown
+    /// is over the member gets one more call, and a new failure opens the circuit again.
+    fn record_failure(&self, llm_provider_id: &str, now: Instant) -> bool {
+        let mut health = self.members.entry(llm_provider_id.to_string()).or_default();
+        health.consecutive_failures += 1;
+        if health.consecutive_failures >= CIRCUIT_BREAKER_FAILURE_THRESHOLD {
+            health.circuit_open_until = Some(now + CIRCUIT_BREAKER_COOLDOWN);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Forwards the WebSocket updates of a member and notes whether part of its answer reached the
+/// client, after which the call can't fail over anymore
+struct StreamTracker {
+    ws_manager: Arc<Mutex<dyn WSUpdateHandler + Send>>,
+    streamed: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl WSUpdateHandler for StreamTracker {
+    async fn queue_message(
+        &self,
+        topic: WSTopic,
+        subtopic: String,
+        update: String,
+        metadata: WSMessageType,
+        is_stream: bool,
+    ) {
+        if is_stream && !update.is_empty() {
+            self.streamed.store(true, Ordering::SeqCst);
+        }
+        self.ws_manager
+            .lock()
+            .await
+            .queue_message(topic, subtopic, update, metadata, is_stream)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(strategy: ProviderGroupStrategy, members: &[(&str, u32)]) -> ProviderGroup {
+        ProviderGroup {
+            strategy,
+            members: members
+                .iter()
+                .map(|(llm_provider_id, weight)| ProviderGroupMember {
+                    llm_provider_id: llm_provider_id.to_string(),
+                    weight: *weight,
+                })
+                .collect(),
+        }
+    }
+
+    fn first_member(router: &ProviderGroupRouter, provider_group: &ProviderGroup, now: Instant) -> String {
+        router.attempt_order("group", provider_group, now)[0]
+            .llm_provider_id
+            .clone()
+    }
+
+    #[test]
+    fn test_weighted_round_robin() {
+        let router = ProviderGroupRouter::new();
+        let provider_group = group(ProviderGroupStrategy::WeightedRoundRobin, &[("a", 3), ("b", 1)]);
+        let now = Instant::now();
+
+        let picks: Vec<String> = (0..8).map(|_| first_member(&router, &provider_group, now)).collect();
+        assert_eq!(picks, vec!["a", "a", "b", "a", "a", "a", "b", "a"]);
+    }
+
+    #[test]
+    fn test_lowest_latency() {
+        let router = ProviderGroupRouter::new();
+        let provider_group = group(
+            ProviderGroupStrategy::LowestLatency,
+            &[("slow", 1), ("fast", 1), ("new", 1)],
+        );
+        router.record_success("slow", Duration::from_secs(4));
+        router.record_success("fast", Duration::from_secs(1));
+        let now = Instant::now();
+
+        let order: Vec<String> = router
+            .attempt_order("group", &provider_group, now)
+            .into_iter()
+            .map(|member| member.llm_provider_id)
+            .collect();
+        assert_eq!(order, vec!["new", "fast", "slow"]);
+
+        // A slow answer moves the average, not the whole ranking at once
+        router.record_success("fast", Duration::from_secs(6));
+        assert!((router.latency("fast").unwrap() - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let router = ProviderGroupRouter::new();
+        let provider_group = group(ProviderGroupStrategy::Failover, &[("primary", 1), ("backup", 1)]);
+        let now = Instant::now();
+
+        assert!(!router.record_failure("primary", now));
+        assert!(!router.record_failure("primary", now));
+        assert_eq!(first_member(&router, &provider_group, now), "primary");
+        assert!(router.record_failure("primary", now));
+        assert_eq!(first_member(&router, &provider_group, now), "backup");
+        assert_eq!(router.attempt_order("group", &provider_group, now).len(), 1);
+
+        // After the cooldown the primary gets another chance, and closes the circuit on success
+        let later = now + CIRCUIT_BREAKER_COOLDOWN;
+        assert_eq!(first_member(&router, &provider_group, later), "primary");
+        router.record_success("primary", Duration::from_secs(1));
+        assert!(!router.record_failure("primary", later));
+
+        // With every circuit open, all the members are tried anyway
+        for _ in 0..CIRCUIT_BREAKER_FAILURE_THRESHOLD {
+            router.record_failure("primary", later);
+            router.record_failure("backup", later);
+        }
+        assert_eq!(router.attempt_order("group", &provider_group, later).len(), 2);
+    }
+
+    #[test]
+    fn test_only_transient_errors_fail_over() {
+        assert!(LLMProviderError::ProviderHttpError(429, "Rate limited".to_string()).is_transient());
+        assert!(LLMProviderError::ProviderHttpError(503, "Overloaded".to_string()).is_transient());
+        assert!(LLMProviderError::NetworkError("Connection reset".to_string()).is_transient());
+        assert!(!LLMProviderError::ProviderHttpError(400, "Invalid request".to_string()).is_transient());
+        assert!(!LLMProviderError::APIError("Invalid request".to_string()).is_transient());
+        assert!(!LLMProviderError::TokenLimit("Too long".to_string()).is_transient());
+    }
+}
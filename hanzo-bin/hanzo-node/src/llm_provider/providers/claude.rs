@@ -12,6 +12,7 @@ use hanzo_messages::{
         inbox_name::InboxName,
         job_config::JobConfig,
         llm_providers::serialized_llm_provider::{Claude, LLMProviderInterface},
+        llm_usage::TokenUsage,
         prompts::Prompt,
     },
     hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption},
@@ -25,6 +26,7 @@ use crate::llm_provider::{
     error::LLMProviderError, execution::chains::inference_chain_trait::LLMInferenceResponse, llm_stopper::LLMStopper,
 };
 use crate::managers::model_capabilities_manager::PromptResultEnum;
+use crate::monitoring::telemetry::RequestTraceExt;
 
 use super::shared::claude_api::claude_prepare_messages;
 use super::shared::shared_model_logic::{send_tool_ws_update, send_ws_update};
@@ -227,6 +229,7 @@ async fn handle_streaming_response(
 ) -> Result<LLMInferenceResponse, LLMProviderError> {
     let res = client
         .post(url)
+        .with_traceparent()
         .header("anthropic-version", "2023-06-01")
         .header("x-api-key", api_key)
         .header("content-type", "application/json")
@@ -235,15 +238,18 @@ async fn handle_streaming_response(
         .await?;
 
     // Check if it's an error response
-    if !res.status().is_success() {
-        let error_json: serde_json::Value = res.json().await?;
+    let status = res.status();
+    if !status.is_success() {
+        let error_json: serde_json::Value = res.json().await.unwrap_or_default();
         if let Some(error) = error_json.get("error") {
             let error_message = error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
-            return Err(LLMProviderError::APIError(
+            return Err(LLMProviderError::ProviderHttpError(
+                status.as_u16(),
                 "AI Provider API Error: ".to_string() + error_message,
             ));
         }
-        return Err(LLMProviderError::APIError(
+        return Err(LLMProviderError::ProviderHttpError(
+            status.as_u16(),
             "AI Provider API Error: Unknown error occurred".to_string(),
         ));
     }
@@ -278,6 +284,7 @@ async fn handle_streaming_response(
     let mut processed_tool: Option<ProcessedTool> = None;
     let mut function_calls = Vec::new();
     let mut buffer = String::new();
+    let mut usage: Option<TokenUsage> = None;
 
     while let Some(item) = stream.next().await {
         // Check if we need to stop the LLM job
@@ -298,14 +305,10 @@ async fn handle_streaming_response(
                 )
                 .await;
 
-                return Ok(LLMInferenceResponse::new(
-                    response_text,
-                    None,
-                    json!({}),
-                    Vec::new(),
-                    Vec::new(),
-                    None,
-                ));
+                return Ok(
+                    LLMInferenceResponse::new(response_text, None, json!({}), Vec::new(), Vec::new(), None)
+                        .with_usage(usage),
+                );
             }
         }
 
@@ -327,6 +330,10 @@ async fn handle_streaming_response(
                             response_text.push_str(&processed_chunk.partial_text);
                             thinking_text.push_str(&processed_chunk.thinking_text);
 
+                            if let Some(ref chunk_usage) = processed_chunk.usage {
+                                usage.get_or_insert_with(TokenUsage::default).merge(chunk_usage);
+                            }
+
                             // Handle tool use
                             if let Some(tool_use) = processed_chunk.tool_use {
                                 match processed_tool {
@@ -444,7 +451,8 @@ async fn handle_streaming_response(
         function_calls,
         Vec::new(),
         None,
-    ))
+    )
+    .with_usage(usage))
 }
 
 async fn handle_non_streaming_response(
@@ -460,6 +468,7 @@ async fn handle_non_streaming_response(
     let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(500));
     let response_fut = client
         .post(url)
+        .with_traceparent()
         .header("anthropic-version", "2023-06-01")
         .header("x-api-key", api_key)
         .header("content-type", "application/json")
@@ -484,15 +493,19 @@ async fn handle_non_streaming_response(
                 let res = response?;
 
                 // Check if it's an error response
-                if !res.status().is_success() {
-                    let error_json: serde_json::Value = res.json().await?;
+                let status = res.status();
+                if !status.is_success() {
+                    let error_json: serde_json::Value = res.json().await.unwrap_or_default();
                     if let Some(error) = error_json.get("error") {
                         let error_message = error.get("message")
                             .and_then(|m| m.as_str())
                             .unwrap_or("Unknown error");
-                        return Err(LLMProviderError::APIError(error_message.to_string()));
+                        return Err(LLMProviderError::ProviderHttpError(status.as_u16(), error_message.to_string()));
                     }
-                    return Err(LLMProviderError::APIError("Unknown error occurred".to_string()));
+                    return Err(LLMProviderError::ProviderHttpError(
+                        status.as_u16(),
+                        "Unknown error occurred".to_string(),
+                    ));
                 }
 
                 let response_body = res.text().await?;
@@ -555,6 +568,8 @@ async fn handle_non_streaming_response(
                         }
                     }
 
+                    let usage = response_json.get("usage").and_then(TokenUsage::from_anthropic_usage);
+
                     break Ok(LLMInferenceResponse::new(
                         response_text,
                         if thinking_text.is_empty() { None } else { Some(thinking_text) },
@@ -562,7 +577,8 @@ async fn handle_non_streaming_response(
                         function_calls,
                         Vec::new(),
                         None,
-                    ));
+                    )
+                    .with_usage(usage));
                 } else {
                     break Err(LLMProviderError::UnexpectedResponseFormat(
                         "No content field in message".to_string(),
@@ -692,6 +708,7 @@ struct ProcessedChunk {
     tool_use: Option<ProcessedTool>,
     is_done: bool,
     done_reason: Option<String>,
+    usage: Option<TokenUsage>,
 }
 
 #[derive(Debug, Clone)]
@@ -725,6 +742,7 @@ fn parse_entire_sse_block(block: &str) -> Result<ProcessedChunk, LLMProviderErro
     let mut text_blocks = Vec::new();
     let mut is_done = false;
     let mut done_reason = None;
+    let mut usage = None;
     let mut content_block_type = String::new();
     let mut _content_block_index: Option<u64> = None;
     let mut current_tool: Option<ProcessedTool> = None;
@@ -741,6 +759,7 @@ fn parse_entire_sse_block(block: &str) -> Result<ProcessedChunk, LLMProviderErro
             tool_use: None,
             is_done: false,
             done_reason: None,
+            usage: None,
         });
     }
 
@@ -748,6 +767,14 @@ fn parse_entire_sse_block(block: &str) -> Result<ProcessedChunk, LLMProviderErro
     let event_data = event_rows[1].trim_start_matches("data: ");
 
     match event_type {
+        "message_start" => {
+            if let Ok(data_json) = serde_json::from_str::<serde_json::Value>(event_data) {
+                usage = data_json
+                    .get("message")
+                    .and_then(|message| message.get("usage"))
+                    .and_then(TokenUsage::from_anthropic_usage);
+            }
+        }
         "content_block_start" => {
             if let Ok(data_json) = serde_json::from_str::<serde_json::Value>(event_data) {
                 // Extract index from the event data
@@ -825,6 +852,7 @@ fn parse_entire_sse_block(block: &str) -> Result<ProcessedChunk, LLMProviderErro
                         is_done = true;
                     }
                 }
+                usage = data_json.get("usage").and_then(TokenUsage::from_anthropic_usage);
             }
         }
         "message_stop" => {
@@ -838,6 +866,7 @@ fn parse_entire_sse_block(block: &str) -> Result<ProcessedChunk, LLMProviderErro
                 tool_use: None,
                 is_done: false,
                 done_reason: None,
+                usage: None,
             });
         }
         _ => {}
@@ -849,6 +878,7 @@ fn parse_entire_sse_block(block: &str) -> Result<ProcessedChunk, LLMProviderErro
         tool_use: current_tool,
         is_done,
         done_reason,
+        usage,
     })
 }
 
@@ -861,6 +891,7 @@ fn process_chunk(chunk: &[u8]) -> Result<ProcessedChunk, LLMProviderError> {
     let mut final_tool_use: Option<ProcessedTool> = None;
     let mut final_is_done = false;
     let mut final_done_reason = None;
+    let mut final_usage: Option<TokenUsage> = None;
 
     // Process each event in the chunk
     while !buffer.is_empty() {
@@ -891,6 +922,10 @@ fn process_chunk(chunk: &[u8]) -> Result<ProcessedChunk, LLMProviderError> {
                     }
                 }
 
+                if let Some(usage) = parsed_block.usage {
+                    final_usage.get_or_insert_with(TokenUsage::default).merge(&usage);
+                }
+
                 // Update done status
                 if parsed_block.is_done {
                     final_is_done = true;
@@ -918,6 +953,7 @@ fn process_chunk(chunk: &[u8]) -> Result<ProcessedChunk, LLMProviderError> {
         tool_use: final_tool_use,
         is_done: final_is_done,
         done_reason: final_done_reason,
+        usage: final_usage,
     })
 }
 
@@ -1167,6 +1203,11 @@ data: {"type":"message_stop"}
         // Verify completion status
         assert!(result.is_done);
         assert_eq!(result.done_reason.unwrap(), "end_turn");
+
+        // Verify usage from message_start and message_delta is combined
+        let usage = result.usage.unwrap();
+        assert_eq!(usage.input_tokens, 93);
+        assert_eq!(usage.output_tokens, 52);
     }
 
     #[tokio::test]
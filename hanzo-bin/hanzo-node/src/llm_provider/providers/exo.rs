@@ -4,6 +4,7 @@ use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::llm_provider::providers::shared::ollama_api::ollama_prepare_messages;
 use crate::llm_provider::providers::shared::shared_model_logic::send_ws_update;
 use crate::managers::model_capabilities_manager::PromptResultEnum;
+use crate::monitoring::telemetry::RequestTraceExt;
 
 use super::ollama::truncate_image_content_in_payload;
 use super::LLMService;
@@ -17,6 +18,7 @@ use serde_json::Value as JsonValue;
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{Exo, LLMProviderInterface};
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
@@ -35,6 +37,8 @@ pub struct ExoAPIStreamingResponse {
     pub model: String,
     pub system_fingerprint: String,
     pub choices: Vec<ExoChoice>,
+    #[serde(default)]
+    pub usage: Option<JsonValue>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -103,6 +107,7 @@ impl LLMService for Exo {
                 "model": self.model_type,
                 "messages": messages_json,
                 "stream": true, // Yeah let's go wild and stream the response
+                "stream_options": { "include_usage": true },
                 // Include any other optional parameters as needed
                 // https://github.com/jmorganca/ollama/blob/main/docs/api.md#request-json-mode
             });
@@ -130,7 +135,7 @@ impl LLMService for Exo {
                 }
             }
 
-            let res = client.post(url).json(&payload).send().await?;
+            let res = client.post(url).with_traceparent().json(&payload).send().await?;
 
             hanzo_log(
                 HanzoLogOption::JobExecution,
@@ -141,6 +146,7 @@ impl LLMService for Exo {
             let mut stream = res.bytes_stream();
             let mut response_text = String::new();
             let mut previous_json_chunk: String = String::new();
+            let mut usage: Option<TokenUsage> = None;
             while let Some(item) = stream.next().await {
                 match item {
                     Ok(chunk) => {
@@ -155,6 +161,7 @@ impl LLMService for Exo {
                         match data_resp {
                             Ok(data) => {
                                 previous_json_chunk = "".to_string();
+                                usage = data.usage.as_ref().and_then(TokenUsage::from_openai_usage).or(usage);
                                 if let Some(choice) = data.choices.get(0) {
                                     response_text.push_str(&choice.delta.content);
 
@@ -195,14 +202,10 @@ impl LLMService for Exo {
             );
 
             // Return response_text with an empty JSON object and empty function calls vector
-            Ok(LLMInferenceResponse::new(
-                response_text,
-                None,
-                json!({}),
-                Vec::new(),
-                Vec::new(),
-                None,
-            ))
+            Ok(
+                LLMInferenceResponse::new(response_text, None, json!({}), Vec::new(), Vec::new(), None)
+                    .with_usage(usage),
+            )
         } else {
             Err(LLMProviderError::UrlNotSet)
         }
@@ -311,3 +314,75 @@ fn add_options_to_payload(payload: &mut serde_json::Value) {
         payload["options"] = serde_json::Value::Object(options);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use hanzo_messages::schemas::subprompts::SubPromptType;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = std::path::PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_exo_usage() {
+        let mut server = mockito::Server::new_async().await;
+        let _chat = server
+            .mock("POST", "/v1/chat/completions")
+            .with_header("content-type", "text/event-stream")
+            .with_body(format!(
+                "data: {}",
+                json!({
+                    "id": "chatcmpl-123",
+                    "object": "chat.completion",
+                    "created": 1727000000,
+                    "model": "llama-3.2-1b",
+                    "system_fingerprint": "exo_0.0.1",
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "Hello" },
+                        "logprobs": null,
+                        "finish_reason": "stop",
+                        "delta": { "role": "assistant", "content": "Hello" }
+                    }],
+                    "usage": { "prompt_tokens": 8, "completion_tokens": 1, "total_tokens": 9 }
+                })
+            ))
+            .create_async()
+            .await;
+
+        let model = Exo {
+            model_type: "llama-3.2-1b".to_string(),
+        };
+        let mut prompt = Prompt::new();
+        prompt.add_content("Hi".to_string(), SubPromptType::UserLastMessage, 100);
+
+        let response = model
+            .call_api(
+                &Client::new(),
+                Some(&server.url()),
+                None,
+                prompt,
+                LLMProviderInterface::Exo(model.clone()),
+                None,
+                None,
+                None,
+                Arc::new(LLMStopper::new()),
+                Arc::new(setup_test_db()),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 8);
+        assert_eq!(usage.output_tokens, 1);
+    }
+}
@@ -7,6 +7,7 @@ use super::LLMService;
 use crate::llm_provider::execution::chains::inference_chain_trait::{FunctionCall, LLMInferenceResponse};
 use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::managers::model_capabilities_manager::PromptResultEnum;
+use crate::monitoring::telemetry::RequestTraceExt;
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
@@ -16,6 +17,7 @@ use serde_json::Value as JsonValue;
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{Gemini, LLMProviderInterface};
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
@@ -238,6 +240,7 @@ impl LLMService for Gemini {
 
                 let res = client
                     .post(&url)
+                    .with_traceparent()
                     .header("Content-Type", "application/json")
                     .json(&payload)
                     .send()
@@ -258,6 +261,7 @@ impl LLMService for Gemini {
                 let mut function_calls = Vec::new();
                 let mut in_thinking = false;
                 let mut thinking_started = false;
+                let mut usage = None;
 
                 while let Some(item) = stream.next().await {
                     match item {
@@ -277,6 +281,7 @@ impl LLMService for Gemini {
                                 &mut function_calls,
                                 &mut in_thinking,
                                 &mut thinking_started,
+                                &mut usage,
                             )
                             .await?;
                         }
@@ -303,7 +308,8 @@ impl LLMService for Gemini {
                     function_calls,
                     generated_files,
                     None,
-                ))
+                )
+                .with_usage(usage))
             } else {
                 Err(LLMProviderError::ApiKeyNotSet)
             }
@@ -329,6 +335,7 @@ async fn process_chunk(
     function_calls: &mut Vec<FunctionCall>,
     in_thinking: &mut bool,
     thinking_started: &mut bool,
+    usage: &mut Option<TokenUsage>,
 ) -> Result<(), LLMProviderError> {
     let chunk_str = String::from_utf8_lossy(chunk);
     buffer.push_str(&chunk_str);
@@ -352,10 +359,13 @@ async fn process_chunk(
             for value in array {
                 // First check if this is an error response
                 if let Ok(error_response) = serde_json::from_value::<GeminiErrorResponse>(value.clone()) {
-                    return Err(LLMProviderError::NetworkError(format!(
-                        "Gemini API error ({}): {} - Status: {}",
-                        error_response.error.code, error_response.error.message, error_response.error.status
-                    )));
+                    return Err(LLMProviderError::ProviderHttpError(
+                        u16::try_from(error_response.error.code).unwrap_or_default(),
+                        format!(
+                            "Gemini API error ({}): {} - Status: {}",
+                            error_response.error.code, error_response.error.message, error_response.error.status
+                        ),
+                    ));
                 }
 
                 process_gemini_response(
@@ -372,6 +382,7 @@ async fn process_chunk(
                     function_calls,
                     in_thinking,
                     thinking_started,
+                    usage,
                 )
                 .await?;
             }
@@ -422,7 +433,16 @@ async fn process_gemini_response(
     function_calls: &mut Vec<FunctionCall>,
     in_thinking: &mut bool,
     thinking_started: &mut bool,
+    usage: &mut Option<TokenUsage>,
 ) -> Result<(), LLMProviderError> {
+    // Each chunk carries the usage of the stream so far
+    if let Some(chunk_usage) = value
+        .get("usageMetadata")
+        .and_then(TokenUsage::from_gemini_usage_metadata)
+    {
+        usage.get_or_insert_with(TokenUsage::default).merge(&chunk_usage);
+    }
+
     if let Ok(response) = serde_json::from_value::<GeminiStreamingResponse>(value) {
         for candidate in &response.candidates {
             // Always update finish reason from candidate
@@ -579,6 +599,7 @@ mod tests {
         let mut function_calls = Vec::new();
         let mut in_thinking = false;
         let mut thinking_started = false;
+        let mut usage = None;
 
         process_chunk(
             chunk,
@@ -595,6 +616,7 @@ mod tests {
             &mut function_calls,
             &mut in_thinking,
             &mut thinking_started,
+            &mut usage,
         )
         .await
         .unwrap();
@@ -602,6 +624,15 @@ mod tests {
         assert_eq!(regular_content, "The");
         assert!(!is_done);
         assert_eq!(finish_reason, Some("STOP".to_string()));
+        assert_eq!(
+            usage,
+            Some(TokenUsage {
+                input_tokens: 41,
+                output_tokens: 1,
+                cached_tokens: 0,
+                reasoning_tokens: 0
+            })
+        );
     }
 
     #[tokio::test]
@@ -660,6 +691,7 @@ mod tests {
         let mut function_calls = Vec::new();
         let mut in_thinking = false;
         let mut thinking_started = false;
+        let mut usage = None;
 
         process_chunk(
             chunk,
@@ -676,6 +708,7 @@ mod tests {
             &mut function_calls,
             &mut in_thinking,
             &mut thinking_started,
+            &mut usage,
         )
         .await
         .unwrap();
@@ -744,6 +777,7 @@ mod tests {
         let mut function_calls = Vec::new();
         let mut in_thinking = false;
         let mut thinking_started = false;
+        let mut usage = None;
 
         process_chunk(
             chunk,
@@ -760,6 +794,7 @@ mod tests {
             &mut function_calls,
             &mut in_thinking,
             &mut thinking_started,
+            &mut usage,
         )
         .await
         .unwrap();
@@ -836,6 +871,7 @@ mod tests {
         let mut finish_reason = None;
         let mut in_thinking = false;
         let mut thinking_started = false;
+        let mut usage = None;
 
         // Process each chunk sequentially
         let chunks: Vec<&[u8]> = vec![chunk1, chunk2, chunk3];
@@ -855,6 +891,7 @@ mod tests {
                 &mut function_calls,
                 &mut in_thinking,
                 &mut thinking_started,
+                &mut usage,
             )
             .await
             .unwrap();
@@ -982,6 +1019,7 @@ mod tests {
         let mut finish_reason = None;
         let mut in_thinking = false;
         let mut thinking_started = false;
+        let mut usage = None;
 
         // Process each chunk sequentially
         let chunks: Vec<&[u8]> = vec![chunk1, chunk2, chunk3, chunk4];
@@ -1001,6 +1039,7 @@ mod tests {
                 &mut function_calls,
                 &mut in_thinking,
                 &mut thinking_started,
+                &mut usage,
             )
             .await
             .unwrap();
@@ -1040,6 +1079,7 @@ mod tests {
         let mut function_calls = Vec::new();
         let mut in_thinking = false;
         let mut thinking_started = false;
+        let mut usage = None;
 
         let result = process_chunk(
             chunk,
@@ -1056,6 +1096,7 @@ mod tests {
             &mut function_calls,
             &mut in_thinking,
             &mut thinking_started,
+            &mut usage,
         )
         .await;
 
@@ -1063,11 +1104,12 @@ mod tests {
         assert!(result.is_err());
         if let Err(err) = result {
             match err {
-                LLMProviderError::NetworkError(msg) => {
+                LLMProviderError::ProviderHttpError(status, msg) => {
+                    assert_eq!(status, 503);
                     assert!(msg.contains("The model is overloaded"));
                     assert!(msg.contains("503"));
                 }
-                _ => panic!("Expected NetworkError variant"),
+                _ => panic!("Expected ProviderHttpError variant"),
             }
         }
     }
@@ -1142,6 +1184,7 @@ mod tests {
         let mut function_calls = Vec::new();
         let mut in_thinking = false;
         let mut thinking_started = false;
+        let mut usage = None;
 
         process_chunk(
             chunk,
@@ -1158,6 +1201,7 @@ mod tests {
             &mut function_calls,
             &mut in_thinking,
             &mut thinking_started,
+            &mut usage,
         )
         .await
         .unwrap();
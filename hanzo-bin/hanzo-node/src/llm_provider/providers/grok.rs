@@ -9,6 +9,7 @@ use super::LLMService;
 use crate::llm_provider::execution::chains::inference_chain_trait::{FunctionCall, LLMInferenceResponse};
 use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::managers::model_capabilities_manager::PromptResultEnum;
+use crate::monitoring::telemetry::RequestTraceExt;
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
@@ -18,6 +19,7 @@ use serde_json::{self};
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{Grok, LLMProviderInterface};
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
@@ -103,6 +105,11 @@ impl LLMService for Grok {
                     }
                 }
 
+                // Ask for the token usage in the last chunk of the stream
+                if is_stream {
+                    payload["stream_options"] = json!({ "include_usage": true });
+                }
+
                 // Add options to payload
                 add_options_to_payload(&mut payload, config.as_ref());
 
@@ -171,6 +178,7 @@ async fn handle_streaming_response(
 ) -> Result<LLMInferenceResponse, LLMProviderError> {
     let res = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .json(&payload)
@@ -190,13 +198,16 @@ async fn handle_streaming_response(
         if let Ok(error_json) = serde_json::from_str::<serde_json::Value>(&response_text) {
             if let Some(error) = error_json.get("error") {
                 let error_message = error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
-                return Err(LLMProviderError::APIError(format!("Grok API Error: {}", error_message)));
+                return Err(LLMProviderError::ProviderHttpError(
+                    status.as_u16(),
+                    format!("Grok API Error: {}", error_message),
+                ));
             }
         }
-        return Err(LLMProviderError::APIError(format!(
-            "Grok API Error ({}): {}",
-            status, response_text
-        )));
+        return Err(LLMProviderError::ProviderHttpError(
+            status.as_u16(),
+            format!("Grok API Error ({}): {}", status, response_text),
+        ));
     }
 
     // Check content type to determine if it's a stream
@@ -225,6 +236,7 @@ async fn handle_streaming_response(
             }
 
             // If we got valid JSON but expected streaming, return the response anyway
+            let usage = response_json.get("usage").and_then(TokenUsage::from_openai_usage);
             if let Ok(data) = serde_json::from_value::<OpenAIResponse>(response_json.clone()) {
                 let response_string: String = data
                     .choices
@@ -236,14 +248,10 @@ async fn handle_streaming_response(
                     .collect::<Vec<String>>()
                     .join(" ");
 
-                return Ok(LLMInferenceResponse::new(
-                    response_string,
-                    None,
-                    json!({}),
-                    Vec::new(),
-                    Vec::new(),
-                    None,
-                ));
+                return Ok(
+                    LLMInferenceResponse::new(response_string, None, json!({}), Vec::new(), Vec::new(), None)
+                        .with_usage(usage),
+                );
             }
         }
 
@@ -257,6 +265,7 @@ async fn handle_streaming_response(
     let mut response_text = String::new();
     let mut buffer = String::new();
     let mut function_calls: Vec<FunctionCall> = Vec::new();
+    let mut usage: Option<TokenUsage> = None;
 
     while let Some(item) = stream.next().await {
         // Check if we need to stop the LLM job
@@ -319,6 +328,9 @@ async fn handle_streaming_response(
                                         )));
                                     }
 
+                                    // With `stream_options.include_usage` the last chunk carries the usage of the request
+                                    usage = data_json.get("usage").and_then(TokenUsage::from_openai_usage).or(usage);
+
                                     if let Some(choices) = data_json.get("choices") {
                                         for choice in choices.as_array().unwrap_or(&vec![]) {
                                             if let Some(delta) = choice.get("delta") {
@@ -453,14 +465,7 @@ async fn handle_streaming_response(
     )
     .await;
 
-    Ok(LLMInferenceResponse::new(
-        response_text,
-        None,
-        json!({}),
-        function_calls,
-        Vec::new(),
-        None,
-    ))
+    Ok(LLMInferenceResponse::new(response_text, None, json!({}), function_calls, Vec::new(), None).with_usage(usage))
 }
 
 async fn handle_non_streaming_response(
@@ -477,6 +482,7 @@ async fn handle_non_streaming_response(
     let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(500));
     let response_fut = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .json(&payload)
@@ -534,6 +540,7 @@ async fn handle_non_streaming_response(
                             });
                         }
 
+                        let usage = value.get("usage").and_then(TokenUsage::from_openai_usage);
                         let data: OpenAIResponse = serde_json::from_value(value).map_err(LLMProviderError::SerdeError)?;
 
                         let response_string: String = data
@@ -618,7 +625,8 @@ async fn handle_non_streaming_response(
                             function_calls,
                             Vec::new(),
                             None,
-                        ));
+                        )
+                        .with_usage(usage));
                     }
                     Err(e) => {
                         hanzo_log(
@@ -682,3 +690,79 @@ fn add_options_to_payload(payload: &mut serde_json::Value, config: Option<&JobCo
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_grok_usage() {
+        let mut server = mockito::Server::new_async().await;
+
+        // With `stream_options.include_usage` the usage comes in a last chunk without choices
+        let _stream = server
+            .mock("POST", "/stream")
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hello\"},\"finish_reason\":null}]}\n\n",
+                "data: {\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+                "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":10,\"completion_tokens\":2,\"total_tokens\":12}}\n\n",
+                "data: [DONE]\n\n",
+            ))
+            .create_async()
+            .await;
+        let _chat = server
+            .mock("POST", "/chat")
+            .with_body(
+                json!({
+                    "id": "chatcmpl-123",
+                    "object": "chat.completion",
+                    "created": 1727000000,
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "Hello" },
+                        "finish_reason": "stop"
+                    }],
+                    "usage": { "prompt_tokens": 8, "completion_tokens": 1, "total_tokens": 9 }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let response = handle_streaming_response(
+            &Client::new(),
+            format!("{}/stream", server.url()),
+            json!({}),
+            "api_key".to_string(),
+            None,
+            None,
+            Arc::new(LLMStopper::new()),
+            "session_id".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 10);
+        assert_eq!(usage.output_tokens, 2);
+
+        let response = handle_non_streaming_response(
+            &Client::new(),
+            format!("{}/chat", server.url()),
+            json!({}),
+            "api_key".to_string(),
+            None,
+            Arc::new(LLMStopper::new()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 8);
+        assert_eq!(usage.output_tokens, 1);
+    }
+}
@@ -9,6 +9,7 @@ use crate::llm_provider::execution::chains::inference_chain_trait::{FunctionCall
 use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::llm_provider::providers::shared::groq_api::groq_prepare_messages;
 use crate::managers::model_capabilities_manager::{ModelCapabilitiesManager, PromptResultEnum};
+use crate::monitoring::telemetry::RequestTraceExt;
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
@@ -18,6 +19,7 @@ use serde_json::{self};
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{Groq, LLMProviderInterface};
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
@@ -217,6 +219,7 @@ async fn handle_streaming_response(
 ) -> Result<LLMInferenceResponse, LLMProviderError> {
     let res = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .json(&payload)
@@ -224,15 +227,18 @@ async fn handle_streaming_response(
         .await?;
 
     // Check if it's an error response
-    if !res.status().is_success() {
-        let error_json: serde_json::Value = res.json().await?;
+    let status = res.status();
+    if !status.is_success() {
+        let error_json: serde_json::Value = res.json().await.unwrap_or_default();
         if let Some(error) = error_json.get("error") {
             let error_message = error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
-            return Err(LLMProviderError::APIError(
+            return Err(LLMProviderError::ProviderHttpError(
+                status.as_u16(),
                 "AI Provider API Error: ".to_string() + error_message,
             ));
         }
-        return Err(LLMProviderError::APIError(
+        return Err(LLMProviderError::ProviderHttpError(
+            status.as_u16(),
             "AI Provider API Error: Unknown error occurred".to_string(),
         ));
     }
@@ -266,6 +272,7 @@ async fn handle_streaming_response(
     let mut buffer = String::new();
     let mut function_calls: Vec<FunctionCall> = Vec::new();
     let mut reasoning_content = String::new();
+    let mut usage: Option<TokenUsage> = None;
 
     while let Some(item) = stream.next().await {
         // Check if we need to stop the LLM job
@@ -332,6 +339,13 @@ async fn handle_streaming_response(
                                         )));
                                     }
 
+                                    // Groq sends the usage of the request in `x_groq` of the last chunk
+                                    usage = data_json
+                                        .get("usage")
+                                        .or_else(|| data_json.pointer("/x_groq/usage"))
+                                        .and_then(TokenUsage::from_openai_usage)
+                                        .or(usage);
+
                                     if let Some(choices) = data_json.get("choices") {
                                         for choice in choices.as_array().unwrap_or(&vec![]) {
                                             if let Some(delta) = choice.get("delta") {
@@ -497,7 +511,8 @@ async fn handle_streaming_response(
         function_calls,
         Vec::new(),
         None,
-    ))
+    )
+    .with_usage(usage))
 }
 
 async fn handle_non_streaming_response(
@@ -514,6 +529,7 @@ async fn handle_non_streaming_response(
     let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(500));
     let response_fut = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .json(&payload)
@@ -588,6 +604,7 @@ async fn handle_non_streaming_response(
                             })
                             .unwrap_or_default();
 
+                        let usage = value.get("usage").and_then(TokenUsage::from_openai_usage);
                         let data: OpenAIResponse = serde_json::from_value(value).map_err(LLMProviderError::SerdeError)?;
 
                         let response_string: String = data
@@ -667,7 +684,8 @@ async fn handle_non_streaming_response(
                             function_calls,
                             Vec::new(),
                             None,
-                        ));
+                        )
+                        .with_usage(usage));
                     }
                     Err(e) => {
                         hanzo_log(
@@ -738,3 +756,78 @@ fn add_options_to_payload(payload: &mut serde_json::Value, config: Option<&JobCo
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_groq_usage() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _stream = server
+            .mock("POST", "/stream")
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hello\"},\"finish_reason\":null}]}\n\n",
+                "data: {\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"stop\"}],",
+                "\"x_groq\":{\"id\":\"req_123\",\"usage\":{\"prompt_tokens\":10,\"completion_tokens\":2,\"total_tokens\":12}}}\n\n",
+                "data: [DONE]\n\n",
+            ))
+            .create_async()
+            .await;
+        let _chat = server
+            .mock("POST", "/chat")
+            .with_body(
+                json!({
+                    "id": "chatcmpl-123",
+                    "object": "chat.completion",
+                    "created": 1727000000,
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "Hello" },
+                        "finish_reason": "stop"
+                    }],
+                    "usage": { "prompt_tokens": 8, "completion_tokens": 1, "total_tokens": 9 }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let response = handle_streaming_response(
+            &Client::new(),
+            format!("{}/stream", server.url()),
+            json!({}),
+            "api_key".to_string(),
+            None,
+            None,
+            Arc::new(LLMStopper::new()),
+            "session_id".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 10);
+        assert_eq!(usage.output_tokens, 2);
+
+        let response = handle_non_streaming_response(
+            &Client::new(),
+            format!("{}/chat", server.url()),
+            json!({}),
+            "api_key".to_string(),
+            None,
+            Arc::new(LLMStopper::new()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 8);
+        assert_eq!(usage.output_tokens, 1);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::monitoring::telemetry::RequestTraceExt;
 use anyhow::Error;
 use reqwest::Client;
 use serde_json::Value;
@@ -28,7 +29,7 @@ pub fn make_cancellable_request(
     let cancellation_token = CancellationToken::new();
     let child_token = cancellation_token.child_token();
 
-    let request = client.post(url.clone()).json(&payload);
+    let request = client.post(url.clone()).with_traceparent().json(&payload);
 
     let future = async move {
         tokio::select! {
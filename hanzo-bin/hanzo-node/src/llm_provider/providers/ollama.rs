@@ -18,6 +18,7 @@ use serde_json::Value as JsonValue;
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{LLMProviderInterface, Ollama};
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
@@ -248,6 +249,7 @@ async fn process_stream(
     let mut previous_json_chunk: String = String::new();
     let mut final_eval_count = None;
     let mut final_eval_duration = None;
+    let mut final_usage = None;
     let mut final_function_calls = Vec::new();
 
     while let Some(item) = stream.next().await {
@@ -418,6 +420,14 @@ async fn process_stream(
                         if data.done {
                             final_eval_count = data.eval_count;
                             final_eval_duration = data.eval_duration;
+                            // The last message of the stream carries the token counts of the request
+                            if data.prompt_eval_count.is_some() || data.eval_count.is_some() {
+                                final_usage = Some(TokenUsage {
+                                    input_tokens: data.prompt_eval_count.unwrap_or(0) as u64,
+                                    output_tokens: data.eval_count.unwrap_or(0) as u64,
+                                    ..Default::default()
+                                });
+                            }
                         }
 
                         let _ = send_ws_update(
@@ -491,7 +501,8 @@ async fn process_stream(
         final_function_calls,
         Vec::new(),
         tps,
-    ))
+    )
+    .with_usage(final_usage))
 }
 
 async fn handle_non_streaming_response(
@@ -625,7 +636,8 @@ async fn handle_non_streaming_response(
                                 function_calls,
                                 Vec::new(),
                                 tps,
-                            ));
+                            )
+                            .with_usage(TokenUsage::from_ollama_response(&response_json)));
                         } else {
                             break Err(LLMProviderError::UnexpectedResponseFormat(
                                 "Content is not a string".to_string(),
@@ -761,3 +773,66 @@ fn add_options_to_payload(
         payload["options"] = serde_json::Value::Object(options);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ollama_usage() {
+        // The last message of a stream has the token counts of the request
+        let chunks: Vec<Result<Vec<u8>, std::io::Error>> = vec![
+            Ok(br#"{"model":"llama3.1","created_at":"2024-07-22T20:33:28.123Z","message":{"role":"assistant","content":"Hello"},"done":false}"#.to_vec()),
+            Ok(br#"{"model":"llama3.1","created_at":"2024-07-22T20:33:28.456Z","message":{"role":"assistant","content":""},"done":true,"done_reason":"stop","prompt_eval_count":26,"eval_count":290,"eval_duration":4709213000}"#.to_vec()),
+        ];
+        let response = process_stream(
+            futures::stream::iter(chunks),
+            None,
+            None,
+            Arc::new(LLMStopper::new()),
+            "session_id".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 26);
+        assert_eq!(usage.output_tokens, 290);
+
+        let mut server = mockito::Server::new_async().await;
+        let _chat = server
+            .mock("POST", "/api/chat")
+            .with_body(
+                json!({
+                    "model": "llama3.1",
+                    "created_at": "2024-07-22T20:33:28.456Z",
+                    "message": { "role": "assistant", "content": "Hello" },
+                    "done": true,
+                    "done_reason": "stop",
+                    "prompt_eval_count": 12,
+                    "eval_count": 3,
+                    "eval_duration": 1000000
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let response = handle_non_streaming_response(
+            &Client::new(),
+            format!("{}/api/chat", server.url()),
+            json!({}),
+            None,
+            None,
+            Arc::new(LLMStopper::new()),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 12);
+        assert_eq!(usage.output_tokens, 3);
+    }
+}
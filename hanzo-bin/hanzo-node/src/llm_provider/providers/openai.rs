@@ -8,6 +8,7 @@ use super::LLMService;
 use crate::llm_provider::execution::chains::inference_chain_trait::{FunctionCall, LLMInferenceResponse};
 use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::managers::model_capabilities_manager::{ModelCapabilitiesManager, PromptResultEnum};
+use crate::monitoring::telemetry::RequestTraceExt;
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
@@ -17,6 +18,7 @@ use serde_json::{self};
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{LLMProviderInterface, OpenAILegacy};
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
@@ -54,7 +56,8 @@ pub struct PartialFunctionCall {
     pub is_accumulating: bool, // Track if we're currently accumulating a function call
     pub id: Option<String>,
     pub call_type: Option<String>,
-    pub reasoning_started: bool, // Track if we've started reasoning content
+    pub reasoning_started: bool,   // Track if we've started reasoning content
+    pub usage: Option<TokenUsage>, // Usage reported at the end of the stream
 }
 
 #[async_trait]
@@ -117,6 +120,11 @@ impl LLMService for OpenAILegacy {
                     payload["tools"] = serde_json::Value::Array(tools_json.clone());
                 }
 
+                // Ask for the token usage in the last chunk of the stream
+                if is_stream {
+                    payload["stream_options"] = json!({ "include_usage": true });
+                }
+
                 // Only add options to payload for non-reasoning models, add reasoning_effort if thinking is enabled and the model has reasoning capabilities
                 if ModelCapabilitiesManager::has_reasoning_capabilities(&model) {
                     let thinking_enabled = config.as_ref().and_then(|c| c.thinking).unwrap_or(false);
@@ -504,6 +512,11 @@ pub async fn parse_openai_stream_chunk(
                     continue;
                 }
 
+                // With `stream_options.include_usage` the last chunk carries the usage of the request
+                if let Some(usage) = json_data.get("usage").and_then(TokenUsage::from_openai_usage) {
+                    partial_fc.usage = Some(usage);
+                }
+
                 // Otherwise, look for "choices"
                 if let Some(choices) = json_data.get("choices") {
                     // Each item in "choices" may have "delta": { "content": "..."} or
@@ -726,6 +739,7 @@ pub async fn handle_streaming_response(
 ) -> Result<LLMInferenceResponse, LLMProviderError> {
     let res = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .header(
@@ -780,14 +794,16 @@ pub async fn handle_streaming_response(
         .await?;
 
     // Check if it's an error response
-    if !res.status().is_success() {
-        let error_json: serde_json::Value = res.json().await?;
+    let status = res.status();
+    if !status.is_success() {
+        let error_json: serde_json::Value = res.json().await.unwrap_or_default();
 
         // Case 1: error is an object with message field (standard OpenAI format)
         if let Some(error) = error_json.get("error") {
             if error.is_object() {
                 let error_message = error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
-                return Err(LLMProviderError::APIError(
+                return Err(LLMProviderError::ProviderHttpError(
+                    status.as_u16(),
                     "AI Provider API Error: ".to_string() + error_message,
                 ));
             } else if error.is_string() {
@@ -800,12 +816,16 @@ pub async fn handle_streaming_response(
                     "AI Provider API Error: ".to_string()
                 };
 
-                return Err(LLMProviderError::APIError(error_prefix + error_message));
+                return Err(LLMProviderError::ProviderHttpError(
+                    status.as_u16(),
+                    error_prefix + error_message,
+                ));
             }
         }
 
         // Fall back to generic error if we can't parse the specific format
-        return Err(LLMProviderError::APIError(
+        return Err(LLMProviderError::ProviderHttpError(
+            status.as_u16(),
             "AI Provider API Error: Unknown error occurred".to_string(),
         ));
     }
@@ -875,6 +895,7 @@ pub async fn handle_streaming_response(
         id: None,
         call_type: None,
         reasoning_started: false,
+        usage: None,
     };
 
     while let Some(item) = stream.next().await {
@@ -937,7 +958,8 @@ pub async fn handle_streaming_response(
                     function_calls.clone(),
                     Vec::new(),
                     None,
-                );
+                )
+                .with_usage(partial_fc.usage.clone());
 
                 // Log the response if LOG_REQUESTS is enabled
                 log_response_to_file(&response_text, &function_calls, true);
@@ -1012,7 +1034,8 @@ pub async fn handle_streaming_response(
         function_calls.clone(),
         Vec::new(),
         None,
-    );
+    )
+    .with_usage(partial_fc.usage);
 
     // Log the response if LOG_REQUESTS is enabled
     log_response_to_file(&response_text, &function_calls, false);
@@ -1034,6 +1057,7 @@ pub async fn handle_non_streaming_response(
     let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(500));
     let response_fut = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .header(
@@ -1170,6 +1194,7 @@ pub async fn handle_non_streaming_response(
                             });
                         }
 
+                        let usage = value.get("usage").and_then(TokenUsage::from_openai_usage);
                         let data: OpenAIResponse = serde_json::from_value(value).map_err(LLMProviderError::SerdeError)?;
 
                         let response_string: String = data
@@ -1234,7 +1259,8 @@ pub async fn handle_non_streaming_response(
                             function_call.map_or_else(Vec::new, |fc| vec![fc]),
                             Vec::new(),
                             None,
-                        ));
+                        )
+                        .with_usage(usage));
                     }
                     Err(e) => {
                         hanzo_log(
@@ -1373,6 +1399,7 @@ mod tests {
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = None;
         let ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>> = None;
@@ -1424,6 +1451,7 @@ mod tests {
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = Some(vec![serde_json::json!({
             "name": "test_function",
@@ -1494,6 +1522,7 @@ mod tests {
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = None;
         let mut reasoning_content = String::new();
@@ -1585,6 +1614,7 @@ mod tests {
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = None;
         let mut reasoning_content = String::new();
@@ -1682,6 +1712,7 @@ mod tests {
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = Some(vec![serde_json::json!({
             "name": "hanzo_tool_config_updater",
@@ -1825,6 +1856,7 @@ mod tests {
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = Some(vec![serde_json::json!({
             "name": "duckduckgo_search",
@@ -2006,6 +2038,7 @@ mod tests {
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = None;
         let ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>> = None;
@@ -2058,6 +2091,7 @@ mod tests {
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = Some(vec![serde_json::json!({
             "name": "stagehand_runner",
@@ -8,6 +8,7 @@ use async_trait::async_trait;
 use crate::llm_provider::execution::chains::inference_chain_trait::{FunctionCall, LLMInferenceResponse};
 use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::managers::model_capabilities_manager::{ModelCapabilitiesManager, PromptResultEnum};
+use crate::monitoring::telemetry::RequestTraceExt;
 use futures::StreamExt;
 use reqwest::Client;
 use serde_json::json;
@@ -16,6 +17,7 @@ use serde_json::{self};
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{LLMProviderInterface, OpenAI};
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_db_sqlite::SqliteManager;
@@ -183,25 +185,34 @@ async fn handle_non_streaming_response_responses(
 ) -> Result<LLMInferenceResponse, LLMProviderError> {
     let res = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .json(&payload)
         .send()
         .await?;
 
-    if !res.status().is_success() {
+    let status = res.status();
+    if !status.is_success() {
         // Try to parse error structure from Responses API
         let error_json: serde_json::Value = res.json().await.unwrap_or(json!({"error":"Unknown error"}));
         if let Some(err) = error_json.get("error") {
             // Could be object or string
             if let Some(msg) = err.get("message").and_then(|m| m.as_str()) {
-                return Err(LLMProviderError::APIError(format!("AI Provider API Error: {}", msg)));
+                return Err(LLMProviderError::ProviderHttpError(
+                    status.as_u16(),
+                    format!("AI Provider API Error: {}", msg),
+                ));
             }
             if let Some(msg) = err.as_str() {
-                return Err(LLMProviderError::APIError(format!("AI Provider API Error: {}", msg)));
+                return Err(LLMProviderError::ProviderHttpError(
+                    status.as_u16(),
+                    format!("AI Provider API Error: {}", msg),
+                ));
             }
         }
-        return Err(LLMProviderError::APIError(
+        return Err(LLMProviderError::ProviderHttpError(
+            status.as_u16(),
             "AI Provider API Error: Unknown error occurred".to_string(),
         ));
     }
@@ -334,7 +345,8 @@ async fn handle_non_streaming_response_responses(
         function_calls,
         Vec::new(),
         None,
-    ))
+    )
+    .with_usage(response_json.get("usage").and_then(TokenUsage::from_openai_usage)))
 }
 
 async fn handle_streaming_response_responses(
@@ -349,6 +361,7 @@ async fn handle_streaming_response_responses(
 ) -> Result<LLMInferenceResponse, LLMProviderError> {
     let res = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .header("Accept", "text/event-stream")
@@ -356,17 +369,22 @@ async fn handle_streaming_response_responses(
         .send()
         .await?;
 
-    if !res.status().is_success() {
+    let status = res.status();
+    if !status.is_success() {
         // Surface API error body
         let text = res.text().await.unwrap_or_else(|_| "".to_string());
         if let Ok(v) = serde_json::from_str::<serde_json::Value>(&text) {
             if let Some(err) = v.get("error") {
                 if let Some(msg) = err.get("message").and_then(|m| m.as_str()) {
-                    return Err(LLMProviderError::APIError(format!("AI Provider API Error: {}", msg)));
+                    return Err(LLMProviderError::ProviderHttpError(
+                        status.as_u16(),
+                        format!("AI Provider API Error: {}", msg),
+                    ));
                 }
             }
         }
-        return Err(LLMProviderError::APIError(
+        return Err(LLMProviderError::ProviderHttpError(
+            status.as_u16(),
             "AI Provider API Error: Unknown error occurred".to_string(),
         ));
     }
@@ -385,6 +403,7 @@ async fn handle_streaming_response_responses(
     }
     let mut tools_map: HashMap<String, ToolAccum> = HashMap::new();
     let mut function_calls: Vec<FunctionCall> = Vec::new();
+    let mut usage: Option<TokenUsage> = None;
 
     // SSE parsing loop
     while let Some(item) = stream.next().await {
@@ -770,6 +789,11 @@ async fn handle_streaming_response_responses(
                         }
                         // Completion end
                         "response.completed" => {
+                            // The completed response carries the usage of the request
+                            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&data_buf) {
+                                usage = v.pointer("/response/usage").and_then(TokenUsage::from_openai_usage);
+                            }
+
                             // finalize any remaining tool calls
                             let ids: Vec<String> = tools_map.keys().cloned().collect();
                             for id in ids {
@@ -913,7 +937,8 @@ async fn handle_streaming_response_responses(
         function_calls,
         Vec::new(),
         None,
-    ))
+    )
+    .with_usage(usage))
 }
 fn add_options_to_payload_responses(
     payload: &mut serde_json::Value,
@@ -1204,7 +1229,7 @@ fn transform_input_messages_for_responses(messages_json: serde_json::Value) -> s
 
 #[cfg(test)]
 mod tests {
-    use serde_json::json;
+    use super::*;
 
     #[test]
     fn test_extract_reasoning_summary_from_output() {
@@ -1553,4 +1578,73 @@ mod tests {
             });
         assert_eq!(extracted_id, None);
     }
+
+    #[tokio::test]
+    async fn test_responses_usage() {
+        let mut server = mockito::Server::new_async().await;
+
+        let _stream = server
+            .mock("POST", "/stream")
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "event: response.output_text.delta\n",
+                "data: {\"type\":\"response.output_text.delta\",\"delta\":\"Hello\"}\n\n",
+                "event: response.completed\n",
+                "data: {\"type\":\"response.completed\",\"response\":{\"id\":\"resp_123\",\"status\":\"completed\",",
+                "\"usage\":{\"input_tokens\":10,\"output_tokens\":2,\"total_tokens\":12}}}\n\n",
+            ))
+            .create_async()
+            .await;
+        let _responses = server
+            .mock("POST", "/responses")
+            .with_body(
+                json!({
+                    "id": "resp_123",
+                    "object": "response",
+                    "status": "completed",
+                    "output": [{
+                        "type": "message",
+                        "role": "assistant",
+                        "content": [{ "type": "output_text", "text": "Hello" }]
+                    }],
+                    "usage": { "input_tokens": 8, "output_tokens": 1, "total_tokens": 9 }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let response = handle_streaming_response_responses(
+            &Client::new(),
+            format!("{}/stream", server.url()),
+            json!({}),
+            "api_key".to_string(),
+            None,
+            None,
+            Arc::new(LLMStopper::new()),
+            "session_id".to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 10);
+        assert_eq!(usage.output_tokens, 2);
+
+        let response = handle_non_streaming_response_responses(
+            &Client::new(),
+            format!("{}/responses", server.url()),
+            json!({}),
+            "api_key".to_string(),
+            None,
+            None,
+            "session_id".to_string(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 8);
+        assert_eq!(usage.output_tokens, 1);
+    }
 }
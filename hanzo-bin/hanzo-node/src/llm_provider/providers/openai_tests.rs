@@ -20,6 +20,7 @@ mod tests {
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = Some(vec![serde_json::json!({
             "name": "youtube_search_api",
@@ -92,6 +93,7 @@ data: [DONE]"#,
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = None;
         let ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>> = None;
@@ -191,6 +193,7 @@ data: {"id":"chatcmpl-BLGOkzXc864uH3CZxRW9stxqzlpOl","object":"chat.completion.c
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = Some(vec![
             serde_json::json!({
@@ -289,6 +292,7 @@ data: {"id":"chatcmpl-BLGOkzXc864uH3CZxRW9stxqzlpOl","object":"chat.completion.c
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = Some(vec![serde_json::json!({
             "name": "youtube_transcript_fetcher",
@@ -413,6 +417,7 @@ data: [DONE]"#,
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
 
         // Register the CSV categorization tool the same way as other tools
@@ -565,6 +570,7 @@ data: [DONE]"#,
             id: None,
             call_type: None,
             reasoning_started: false,
+            usage: None,
         };
         let tools = None;
         let ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>> = None;
@@ -608,4 +614,51 @@ data: [DONE]"#,
         // No response text should be added
         assert!(response_text.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_parse_openai_stream_chunk_usage() {
+        let mut buffer = String::new();
+        let mut response_text = String::new();
+        let mut function_calls = Vec::new();
+        let mut partial_fc = PartialFunctionCall {
+            name: None,
+            arguments: String::new(),
+            is_accumulating: false,
+            id: None,
+            call_type: None,
+            reasoning_started: false,
+            usage: None,
+        };
+        let ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>> = None;
+        let mut reasoning_content = String::new();
+
+        // With include_usage, every chunk has `"usage": null` and an extra chunk without choices has the usage
+        buffer.push_str(
+            r#"data: {"id":"chatcmpl-1","object":"chat.completion.chunk","model":"gpt-4o-mini","choices":[{"index":0,"delta":{"content":"Hello"},"finish_reason":null}],"usage":null}
+data: {"id":"chatcmpl-1","object":"chat.completion.chunk","model":"gpt-4o-mini","choices":[{"index":0,"delta":{},"finish_reason":"stop"}],"usage":null}
+data: {"id":"chatcmpl-1","object":"chat.completion.chunk","model":"gpt-4o-mini","choices":[],"usage":{"prompt_tokens":20,"completion_tokens":5,"total_tokens":25,"prompt_tokens_details":{"cached_tokens":10}}}
+data: [DONE]
+"#,
+        );
+
+        let result = parse_openai_stream_chunk(
+            &mut buffer,
+            &mut response_text,
+            &mut reasoning_content,
+            &mut function_calls,
+            &mut partial_fc,
+            &None,
+            &ws_manager,
+            None,
+            "session_id",
+        )
+        .await;
+        assert!(result.is_ok());
+
+        assert_eq!(response_text, "Hello");
+        let usage = partial_fc.usage.unwrap();
+        assert_eq!(usage.input_tokens, 20);
+        assert_eq!(usage.output_tokens, 5);
+        assert_eq!(usage.cached_tokens, 10);
+    }
 }
@@ -8,6 +8,7 @@ use super::LLMService;
 use crate::llm_provider::execution::chains::inference_chain_trait::{FunctionCall, LLMInferenceResponse};
 use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::managers::model_capabilities_manager::PromptResultEnum;
+use crate::monitoring::telemetry::RequestTraceExt;
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
@@ -17,6 +18,7 @@ use serde_json::{self};
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{LLMProviderInterface, OpenRouter};
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
@@ -96,6 +98,11 @@ impl LLMService for OpenRouter {
                     payload["tools"] = serde_json::Value::Array(tools_json.clone());
                 }
 
+                // Ask for the token usage in the last chunk of the stream
+                if is_stream {
+                    payload["stream_options"] = json!({ "include_usage": true });
+                }
+
                 // Add options to payload
                 add_options_to_payload(&mut payload, config.as_ref());
 
@@ -172,6 +179,7 @@ async fn handle_streaming_response(
 ) -> Result<LLMInferenceResponse, LLMProviderError> {
     let res = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .header("X-Title", "Hanzo")
@@ -180,15 +188,18 @@ async fn handle_streaming_response(
         .await?;
 
     // Check if it's an error response
-    if !res.status().is_success() {
-        let error_json: serde_json::Value = res.json().await?;
+    let status = res.status();
+    if !status.is_success() {
+        let error_json: serde_json::Value = res.json().await.unwrap_or_default();
         if let Some(error) = error_json.get("error") {
             let error_message = error.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
-            return Err(LLMProviderError::APIError(
+            return Err(LLMProviderError::ProviderHttpError(
+                status.as_u16(),
                 "AI Provider API Error: ".to_string() + error_message,
             ));
         }
-        return Err(LLMProviderError::APIError(
+        return Err(LLMProviderError::ProviderHttpError(
+            status.as_u16(),
             "AI Provider API Error: Unknown error occurred".to_string(),
         ));
     }
@@ -221,6 +232,7 @@ async fn handle_streaming_response(
     let mut response_text = String::new();
     let mut previous_json_chunk: String = String::new();
     let mut function_calls: Vec<FunctionCall> = Vec::new();
+    let mut usage: Option<TokenUsage> = None;
     let mut is_done_sent = false; // Track if any WS message with is_done: true has been sent
 
     while let Some(item) = stream.next().await {
@@ -275,6 +287,8 @@ async fn handle_streaming_response(
                                 Ok(data) => {
                                     let new_content =
                                         process_streaming_chunk(&data, &mut response_text, &mut function_calls, &tools);
+                                    // With `stream_options.include_usage` the last chunk carries the usage of the request
+                                    usage = data.get("usage").and_then(TokenUsage::from_openai_usage).or(usage);
 
                                     // Check for finish_reason to determine if stream is done
                                     let is_finished = data
@@ -334,6 +348,7 @@ async fn handle_streaming_response(
                             previous_json_chunk = "".to_string();
                             let new_content =
                                 process_streaming_chunk(&data, &mut response_text, &mut function_calls, &tools);
+                            usage = data.get("usage").and_then(TokenUsage::from_openai_usage).or(usage);
 
                             // Check for finish_reason to determine if stream is done
                             let is_finished = data
@@ -402,14 +417,7 @@ async fn handle_streaming_response(
         .await;
     }
 
-    Ok(LLMInferenceResponse::new(
-        response_text,
-        None,
-        json!({}),
-        function_calls,
-        Vec::new(),
-        None,
-    ))
+    Ok(LLMInferenceResponse::new(response_text, None, json!({}), function_calls, Vec::new(), None).with_usage(usage))
 }
 
 async fn handle_non_streaming_response(
@@ -425,6 +433,7 @@ async fn handle_non_streaming_response(
     let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(500));
     let response_fut = client
         .post(url)
+        .with_traceparent()
         .bearer_auth(api_key)
         .header("Content-Type", "application/json")
         .header("X-Title", "Hanzo")
@@ -472,6 +481,7 @@ async fn handle_non_streaming_response(
                             });
                         }
 
+                        let usage = value.get("usage").and_then(TokenUsage::from_openai_usage);
                         let data: OpenAIResponse = serde_json::from_value(value).map_err(LLMProviderError::SerdeError)?;
 
                         let response_string: String = data
@@ -536,7 +546,8 @@ async fn handle_non_streaming_response(
                             function_call.map_or_else(Vec::new, |fc| vec![fc]),
                             Vec::new(),
                             None,
-                        ));
+                        )
+                        .with_usage(usage));
                     }
                     Err(e) => {
                         hanzo_log(
@@ -717,3 +728,79 @@ fn add_options_to_payload(payload: &mut serde_json::Value, config: Option<&JobCo
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_openrouter_usage() {
+        let mut server = mockito::Server::new_async().await;
+
+        // With `stream_options.include_usage` the usage comes in a last chunk without choices
+        let _stream = server
+            .mock("POST", "/stream")
+            .with_header("content-type", "text/event-stream")
+            .with_body(concat!(
+                "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hello\"},\"finish_reason\":null}]}\n\n",
+                "data: {\"choices\":[{\"index\":0,\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n",
+                "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":10,\"completion_tokens\":2,\"total_tokens\":12}}\n\n",
+                "data: [DONE]\n\n",
+            ))
+            .create_async()
+            .await;
+        let _chat = server
+            .mock("POST", "/chat")
+            .with_body(
+                json!({
+                    "id": "gen-123",
+                    "object": "chat.completion",
+                    "created": 1727000000,
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "Hello" },
+                        "finish_reason": "stop"
+                    }],
+                    "usage": { "prompt_tokens": 8, "completion_tokens": 1, "total_tokens": 9 }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let response = handle_streaming_response(
+            &Client::new(),
+            format!("{}/stream", server.url()),
+            json!({}),
+            "api_key".to_string(),
+            None,
+            None,
+            Arc::new(LLMStopper::new()),
+            "session_id".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 10);
+        assert_eq!(usage.output_tokens, 2);
+
+        let response = handle_non_streaming_response(
+            &Client::new(),
+            format!("{}/chat", server.url()),
+            json!({}),
+            "api_key".to_string(),
+            None,
+            Arc::new(LLMStopper::new()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 8);
+        assert_eq!(usage.output_tokens, 1);
+    }
+}
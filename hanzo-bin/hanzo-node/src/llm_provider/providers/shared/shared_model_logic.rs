@@ -119,7 +119,7 @@ pub async fn save_image_file(
     // Decode base64 image data
     let image_data = BASE64
         .decode(base64_data)
-        .map_err(|e| LLMProviderError::NetworkError(format!("Failed to decode base64 image data: {}", e)))?;
+        .map_err(|e| LLMProviderError::ImageProcessingError(format!("Failed to decode base64 image data: {}", e)))?;
 
     // Create a unique filename for the image
     let timestamp = std::time::SystemTime::now()
@@ -142,13 +142,13 @@ pub async fn save_image_file(
         match inbox.get_job_id() {
             Some(job_id) => job_id,
             None => {
-                return Err(LLMProviderError::NetworkError(
+                return Err(LLMProviderError::ImageProcessingError(
                     "Inbox is not a job inbox - cannot save images".to_string(),
                 ));
             }
         }
     } else {
-        return Err(LLMProviderError::NetworkError(
+        return Err(LLMProviderError::ImageProcessingError(
             "Inbox name is required for saving images".to_string(),
         ));
     };
@@ -164,9 +164,10 @@ pub async fn save_image_file(
         db,
         FileProcessingMode::NoParsing, // Images don't contain parseable text, but file is still saved to job context
         &embedding_generator,
+        None,
     )
     .await
-    .map_err(|e| LLMProviderError::NetworkError(format!("Failed to save image file: {}", e)))?;
+    .map_err(|e| LLMProviderError::ImageProcessingError(format!("Failed to save image file: {}", e)))?;
 
     Ok(hanzo_path)
 }
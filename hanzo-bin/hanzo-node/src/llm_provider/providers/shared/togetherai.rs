@@ -25,6 +25,8 @@ pub struct Args {
 #[derive(Serialize, Deserialize)]
 pub struct Output {
     pub choices: Vec<Choice>,
+    #[serde(default)]
+    pub usage: Option<serde_json::Value>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use crate::llm_provider::execution::chains::inference_chain_trait::LLMInferenceResponse;
 use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::managers::model_capabilities_manager::ModelCapabilitiesManager;
+use crate::monitoring::telemetry::RequestTraceExt;
 use hanzo_messages::schemas::job_config::JobConfig;
 use hanzo_messages::schemas::prompts::Prompt;
 use hanzo_messages::schemas::ws_types::WSUpdateHandler;
@@ -18,6 +19,7 @@ use serde_json;
 use serde_json::json;
 use hanzo_messages::schemas::inbox_name::InboxName;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{LLMProviderInterface, TogetherAI};
+use hanzo_messages::schemas::llm_usage::TokenUsage;
 use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
 use tokio::sync::Mutex;
 
@@ -96,6 +98,7 @@ impl LLMService for TogetherAI {
 
                 let res = client
                     .post(url)
+                    .with_traceparent()
                     .bearer_auth(key)
                     .header("Content-Type", "application/json")
                     .json(&payload)
@@ -128,6 +131,7 @@ impl LLMService for TogetherAI {
                             .map(|choice| choice.text.clone())
                             .unwrap_or_else(String::new);
 
+                        let usage = data.output.usage.as_ref().and_then(TokenUsage::from_openai_usage);
                         return Ok(LLMInferenceResponse::new(
                             response_string,
                             None,
@@ -135,7 +139,8 @@ impl LLMService for TogetherAI {
                             vec![],
                             Vec::new(),
                             None,
-                        ));
+                        )
+                        .with_usage(usage));
                     }
                     Err(e) => {
                         hanzo_log(
@@ -154,3 +159,79 @@ impl LLMService for TogetherAI {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use hanzo_messages::schemas::subprompts::SubPromptType;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = std::path::PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_togetherai_usage() {
+        let mut server = mockito::Server::new_async().await;
+        let _inference = server
+            .mock("POST", "/inference")
+            .with_body(
+                json!({
+                    "status": "finished",
+                    "prompt": ["Hi"],
+                    "model": "meta-llama/Llama-3-8b-chat-hf",
+                    "model_owner": "",
+                    "num_returns": 1,
+                    "args": {
+                        "model": "meta-llama/Llama-3-8b-chat-hf",
+                        "prompt": "Hi",
+                        "temperature": 0.7,
+                        "top_p": 0.7,
+                        "top_k": 50,
+                        "max_tokens": 100
+                    },
+                    "subjobs": [],
+                    "output": {
+                        "choices": [{ "finish_reason": "eos", "index": 0, "text": "Hello" }],
+                        "usage": { "prompt_tokens": 8, "completion_tokens": 1, "total_tokens": 9 }
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let model = TogetherAI {
+            model_type: "meta-llama/Llama-3-8b-chat-hf".to_string(),
+        };
+        let mut prompt = Prompt::new();
+        prompt.add_content("Hi".to_string(), SubPromptType::UserLastMessage, 100);
+
+        let response = model
+            .call_api(
+                &Client::new(),
+                Some(&server.url()),
+                Some(&"api_key".to_string()),
+                prompt,
+                LLMProviderInterface::TogetherAI(model.clone()),
+                None,
+                None,
+                None,
+                Arc::new(LLMStopper::new()),
+                Arc::new(setup_test_db()),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.response_string, "Hello");
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 8);
+        assert_eq!(usage.output_tokens, 1);
+    }
+}
@@ -3,6 +3,7 @@
 mod cron_tasks;
 mod llm_provider;
 mod managers;
+mod monitoring;
 mod network;
 mod runner;
 mod tools;
@@ -0,0 +1,269 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use hanzo_db_sqlite::SqliteManager;
+use hanzo_embed::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
+use hanzo_fs::hanzo_file_manager::HanzoFileManager;
+use hanzo_fs::hanzo_fs_error::HanzoFsError;
+use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
+use hanzo_messages::schemas::hanzo_fs::{FolderSyncReport, WatchedFolder};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::sync::{Mutex, Notify};
+
+lazy_static::lazy_static! {
+    /// Only one sync runs at a time, so the watcher and syncs requested through the API
+    /// never process the same files concurrently.
+    static ref FOLDER_SYNC_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Wakes the watcher when folders are added or removed, so it updates what it watches
+    static ref WATCHED_FOLDERS_CHANGED: Notify = Notify::new();
+}
+
+/// How long filesystem events must settle before the changed paths are synced, so a file that is
+/// still being written or a directory that is being copied in is synced once
+const EVENT_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Keeps the watched folders mirrored into the VecFS. Registrations live in the database, so
+/// watching resumes after a restart.
+pub struct FolderSyncManager;
+
+impl FolderSyncManager {
+    /// Spawns the task that watches the registered host directories and syncs the paths that
+    /// change. Every folder is fully synced once at startup to pick up the changes made while
+    /// the node was not running.
+    pub fn start(
+        db: Weak<SqliteManager>,
+        embedding_generator: Arc<Mutex<RemoteEmbeddingGenerator>>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let (event_sender, mut events) = mpsc::unbounded_channel();
+            let mut watcher = match notify::recommended_watcher(move |event: notify::Result<Event>| {
+                let _ = event_sender.send(event);
+            }) {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    hanzo_log(
+                        HanzoLogOption::Node,
+                        HanzoLogLevel::Error,
+                        &format!("Failed to start the watched folders watcher: {}", e),
+                    );
+                    return;
+                }
+            };
+            let mut watched = HashMap::new();
+
+            let Some(strong_db) = db.upgrade() else {
+                return;
+            };
+            Self::update_watches(&strong_db, &mut watcher, &mut watched);
+            let generator = embedding_generator.lock().await.clone();
+            for watched_folder in watched.values() {
+                let _ = Self::sync_folder(&strong_db, watched_folder, &generator).await;
+            }
+            drop(strong_db);
+
+            loop {
+                let first_event = tokio::select! {
+                    _ = WATCHED_FOLDERS_CHANGED.notified() => None,
+                    event = events.recv() => match event {
+                        Some(event) => Some(event),
+                        None => return,
+                    },
+                };
+                let mut changed_paths = HashSet::new();
+                let rescan = match first_event {
+                    Some(event) => Some(Self::collect_events(event, &mut events, &mut changed_paths).await),
+                    None => None,
+                };
+
+                let Some(db) = db.upgrade() else {
+                    hanzo_log(
+                        HanzoLogOption::Node,
+                        HanzoLogLevel::Error,
+                        "Database is gone, stopping the watched folders watcher",
+                    );
+                    return;
+                };
+                let Some(rescan) = rescan else {
+                    Self::update_watches(&db, &mut watcher, &mut watched);
+                    continue;
+                };
+
+                let generator = embedding_generator.lock().await.clone();
+                for watched_folder in watched.values() {
+                    if rescan {
+                        let _ = Self::sync_folder(&db, watched_folder, &generator).await;
+                        continue;
+                    }
+                    let host_paths: Vec<PathBuf> = changed_paths
+                        .iter()
+                        .filter(|path| path.starts_with(&watched_folder.host_path))
+                        .cloned()
+                        .collect();
+                    if !host_paths.is_empty() {
+                        let _ = Self::sync_paths(&db, watched_folder, &host_paths, &generator).await;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Tells the watcher that folders were added or removed
+    pub fn watched_folders_changed() {
+        WATCHED_FOLDERS_CHANGED.notify_one();
+    }
+
+    /// Syncs one watched folder and records the outcome on its registration
+    pub async fn sync_folder(
+        db: &SqliteManager,
+        watched_folder: &WatchedFolder,
+        generator: &dyn EmbeddingGenerator,
+    ) -> Result<FolderSyncReport, HanzoFsError> {
+        let _guard = FOLDER_SYNC_LOCK.lock().await;
+
+        let result = HanzoFileManager::sync_watched_folder(watched_folder, db, generator).await;
+        Self::record_sync_result(db, watched_folder, &result);
+        result
+    }
+
+    /// Syncs the changed paths of a watched folder and records the outcome on its registration
+    async fn sync_paths(
+        db: &SqliteManager,
+        watched_folder: &WatchedFolder,
+        host_paths: &[PathBuf],
+        generator: &dyn EmbeddingGenerator,
+    ) -> Result<FolderSyncReport, HanzoFsError> {
+        let _guard = FOLDER_SYNC_LOCK.lock().await;
+
+        let result = HanzoFileManager::sync_watched_paths(watched_folder, host_paths, db, generator).await;
+        Self::record_sync_result(db, watched_folder, &result);
+        result
+    }
+
+    fn record_sync_result(
+        db: &SqliteManager,
+        watched_folder: &WatchedFolder,
+        result: &Result<FolderSyncReport, HanzoFsError>,
+    ) {
+        let sync_error = match result {
+            Ok(report) => {
+                if report.has_changes() {
+                    hanzo_log(
+                        HanzoLogOption::Node,
+                        HanzoLogLevel::Info,
+                        &format!(
+                            "Synced watched folder {} into {}: {} added, {} updated, {} removed",
+                            watched_folder.host_path,
+                            watched_folder.vecfs_path,
+                            report.added.len(),
+                            report.updated.len(),
+                            report.removed.len()
+                        ),
+                    );
+                }
+                if report.failed.is_empty() {
+                    None
+                } else {
+                    Some(format!("Failed to sync {}", report.failed.join(", ")))
+                }
+            }
+            Err(e) => {
+                hanzo_log(
+                    HanzoLogOption::Node,
+                    HanzoLogLevel::Error,
+                    &format!("Failed to sync watched folder {}: {}", watched_folder.host_path, e),
+                );
+                Some(e.to_string())
+            }
+        };
+
+        if let Err(e) = db.set_watched_folder_sync_result(watched_folder.id, sync_error.as_deref()) {
+            hanzo_log(
+                HanzoLogOption::Node,
+                HanzoLogLevel::Error,
+                &format!(
+                    "Failed to record the sync result of watched folder {}: {}",
+                    watched_folder.host_path, e
+                ),
+            );
+        }
+    }
+
+    /// Watches the folders registered in the database and stops watching the removed ones
+    fn update_watches(db: &SqliteManager, watcher: &mut RecommendedWatcher, watched: &mut HashMap<i64, WatchedFolder>) {
+        let watched_folders = match db.get_all_watched_folders() {
+            Ok(watched_folders) => watched_folders,
+            Err(e) => {
+                hanzo_log(
+                    HanzoLogOption::Node,
+                    HanzoLogLevel::Error,
+                    &format!("Failed to get watched folders: {}", e),
+                );
+                return;
+            }
+        };
+
+        let registered: HashSet<i64> = watched_folders.iter().map(|folder| folder.id).collect();
+        watched.retain(|id, watched_folder| {
+            if registered.contains(id) {
+                return true;
+            }
+            // Fails if the directory is already gone, which stops the watch as well
+            let _ = watcher.unwatch(Path::new(&watched_folder.host_path));
+            false
+        });
+
+        for watched_folder in watched_folders {
+            if watched.contains_key(&watched_folder.id) {
+                continue;
+            }
+            match watcher.watch(Path::new(&watched_folder.host_path), RecursiveMode::Recursive) {
+                Ok(()) => {
+                    watched.insert(watched_folder.id, watched_folder);
+                }
+                Err(e) => hanzo_log(
+                    HanzoLogOption::Node,
+                    HanzoLogLevel::Error,
+                    &format!("Failed to watch folder {}: {}", watched_folder.host_path, e),
+                ),
+            }
+        }
+    }
+
+    /// Collects the paths changed by `first` and the events following it until they settle.
+    /// Returns true if the watcher lost track of changes, in which case every folder is rescanned.
+    async fn collect_events(
+        first: notify::Result<Event>,
+        events: &mut UnboundedReceiver<notify::Result<Event>>,
+        changed_paths: &mut HashSet<PathBuf>,
+    ) -> bool {
+        let mut rescan = Self::collect_event(first, changed_paths);
+        while let Ok(Some(event)) = tokio::time::timeout(EVENT_DEBOUNCE, events.recv()).await {
+            rescan |= Self::collect_event(event, changed_paths);
+        }
+        rescan
+    }
+
+    fn collect_event(event: notify::Result<Event>, changed_paths: &mut HashSet<PathBuf>) -> bool {
+        match event {
+            Ok(event) if event.need_rescan() => true,
+            Ok(event) => {
+                if !matches!(event.kind, EventKind::Access(_)) {
+                    changed_paths.extend(event.paths);
+                }
+                false
+            }
+            Err(e) => {
+                hanzo_log(
+                    HanzoLogOption::Node,
+                    HanzoLogLevel::Error,
+                    &format!("Watched folders watcher error, rescanning: {}", e),
+                );
+                true
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod identity_manager;
 pub use identity_manager::IdentityManager;
+pub mod folder_sync_manager;
 pub mod galxe_quests;
 pub mod identity_network_manager;
 pub mod model_capabilities_manager;
@@ -171,6 +171,8 @@ impl ModelCapabilitiesManager {
             LLMProviderInterface::Claude(_) => vec![ModelCapability::ImageAnalysis, ModelCapability::TextInference],
             LLMProviderInterface::DeepSeek(_) => vec![ModelCapability::TextInference],
             LLMProviderInterface::LocalRegex(_) => vec![ModelCapability::ImageAnalysis, ModelCapability::TextInference],
+            // The members of a group aren't known here, so only text inference is assumed
+            LLMProviderInterface::ProviderGroup(_) => vec![ModelCapability::TextInference],
         }
     }
 
@@ -569,6 +571,7 @@ impl ModelCapabilitiesManager {
                 _ => ModelCost::Unknown,
             },
             LLMProviderInterface::LocalRegex(_) => ModelCost::Free,
+            LLMProviderInterface::ProviderGroup(_) => ModelCost::Unknown,
         }
     }
 
@@ -595,6 +598,7 @@ impl ModelCapabilitiesManager {
             LLMProviderInterface::Claude(_) => ModelPrivacy::RemoteGreedy,
             LLMProviderInterface::DeepSeek(_) => ModelPrivacy::RemoteGreedy,
             LLMProviderInterface::LocalRegex(_) => ModelPrivacy::Local,
+            LLMProviderInterface::ProviderGroup(_) => ModelPrivacy::Unknown,
         }
     }
 
@@ -719,6 +723,9 @@ impl ModelCapabilitiesManager {
                     llama_prepare_messages(model, local_regex.clone().model_type, prompt, total_tokens)?;
                 Ok(messages_string)
             }
+            LLMProviderInterface::ProviderGroup(provider_group) => Err(ModelCapabilitiesManagerError::NotImplemented(
+                provider_group.model_type(),
+            )),
         }
     }
 
@@ -794,6 +801,8 @@ impl ModelCapabilitiesManager {
             LLMProviderInterface::Claude(_) => 200_000, // All Claude models now have 200K context window
             LLMProviderInterface::DeepSeek(_) => 64_000,
             LLMProviderInterface::LocalRegex(_) => 128_000,
+            // Groups are expected to wrap interchangeable models, the most common context window is assumed
+            LLMProviderInterface::ProviderGroup(_) => 128_000,
         }
     }
 
@@ -1036,6 +1045,7 @@ impl ModelCapabilitiesManager {
             }
             LLMProviderInterface::DeepSeek(_) => 8192,
             LLMProviderInterface::LocalRegex(_) => 128_000,
+            LLMProviderInterface::ProviderGroup(_) => 8192,
         }
     }
 
@@ -1234,6 +1244,7 @@ impl ModelCapabilitiesManager {
             LLMProviderInterface::Gemini(model) => Self::gemini_has_tool_capabilities(model.model_type.as_str()),
             LLMProviderInterface::DeepSeek(_) => true,
             LLMProviderInterface::Grok(_) => true, // All Grok models support tool calling
+            LLMProviderInterface::ProviderGroup(_) => true, // Groups are meant to wrap tool capable models
             _ => false,
         }
     }
@@ -3,6 +3,9 @@ use crate::llm_provider::error::LLMProviderError;
 use crate::llm_provider::execution::chains::generic_chain::generic_inference_chain::GenericInferenceChain;
 use crate::llm_provider::execution::chains::inference_chain_trait::{FunctionCall, InferenceChainContextTrait};
 use crate::llm_provider::job_manager::JobManager;
+use crate::monitoring::record_tool_execution;
+use crate::monitoring::telemetry::in_span;
+use crate::network::agent_payments_manager::external_agent_offerings_manager::AgentOfferingManagerError;
 use crate::network::node_shareable_logic::ZipFileContents;
 use crate::network::zip_export_import::zip_export_import::{
     get_agent_from_zip, get_tool_from_zip, import_agent, import_tool,
@@ -14,6 +17,7 @@ use crate::tools::tool_execution::{
     execution_coordinator::override_tool_config,
     execution_custom::try_to_execute_rust_tool,
     execution_header_generator::{check_tool, generate_execution_environment},
+    execution_wasm::execute_wasm_tool,
 };
 use crate::utils::environment::{fetch_node_environment, NodeEnvironment};
 use ed25519_dalek::SigningKey;
@@ -27,12 +31,12 @@ use hanzo_messages::schemas::wallet_mixed::AddressBalanceList;
 use hanzo_messages::schemas::x402_types::Network;
 use hanzo_messages::schemas::{
     indexable_version::IndexableVersion,
-    invoices::{Invoice, InvoiceStatusEnum},
+    invoices::{Invoice, InvoiceNetworkErrorKind, InvoiceStatusEnum},
     job::JobLike,
     llm_providers::common_agent_llm_provider::ProviderOrAgent,
     hanzo_name::HanzoName,
     hanzo_preferences::HanzoInternalComms,
-    hanzo_tool_offering::{ToolPrice, UsageType, UsageTypeInquiry},
+    hanzo_tool_offering::{ToolPrice, UsageType},
     tool_router_key::ToolRouterKey,
     ws_types::{PaymentMetadata, WSMessageType, WidgetMetadata},
     x402_types::PaymentRequirements,
@@ -53,6 +57,7 @@ use hanzo_tools::tools::{
     tool_config::ToolConfig,
     tool_output_arg::ToolOutputArg,
 };
+use opentelemetry::KeyValue;
 use std::env;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -827,6 +832,37 @@ impl ToolRouter {
         context: &dyn InferenceChainContextTrait,
         hanzo_tool: &HanzoTool,
         node_name: HanzoName,
+    ) -> Result<ToolCallFunctionResponse, LLMProviderError> {
+        let span_attributes = vec![
+            KeyValue::new("tool.type", hanzo_tool.tool_type()),
+            KeyValue::new("tool.name", hanzo_tool.name()),
+            KeyValue::new(
+                "tool.router_key",
+                hanzo_tool.tool_router_key().to_string_without_version(),
+            ),
+        ];
+        let started = Instant::now();
+        let result = in_span(
+            "tool_call",
+            span_attributes,
+            self.execute_function(function_call, context, hanzo_tool, node_name),
+        )
+        .await;
+        record_tool_execution(
+            hanzo_tool.tool_type(),
+            &hanzo_tool.name(),
+            started.elapsed(),
+            result.is_ok(),
+        );
+        result
+    }
+
+    async fn execute_function(
+        &self,
+        function_call: FunctionCall,
+        context: &dyn InferenceChainContextTrait,
+        hanzo_tool: &HanzoTool,
+        node_name: HanzoName,
     ) -> Result<ToolCallFunctionResponse, LLMProviderError> {
         let _function_name = function_call.name.clone();
         let function_args = function_call.arguments.clone();
@@ -894,8 +930,9 @@ impl ToolRouter {
                 })?;
                 let mcp_server = self.sqlite_manager.get_mcp_server(mcp_server_ref)?;
                 if let Some(mcp_server) = mcp_server {
+                    let job_id = context.full_job().job_id().to_string();
                     let result = mcp_server_tool
-                        .run(mcp_server, function_args, function_config_vec)
+                        .run(mcp_server, function_args, function_config_vec, Some(job_id))
                         .await?;
                     let result_str = serde_json::to_string(&result)
                         .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
@@ -984,6 +1021,62 @@ impl ToolRouter {
                     function_call,
                 });
             }
+            HanzoTool::Wasm(wasm_tool, _is_enabled) => {
+                let node_storage_path = fetch_node_environment()
+                    .node_storage_path
+                    .ok_or_else(|| ToolError::ExecutionError("Node storage path is not set".to_string()))?;
+
+                // Get app_id from Cron UI if present, otherwise use job_id
+                let app_id = match context.full_job().associated_ui().as_ref() {
+                    Some(AssociatedUI::Cron(cron_id)) => cron_id.clone(),
+                    _ => context.full_job().job_id().to_string(),
+                };
+
+                let tool_router_key = hanzo_tool.tool_router_key();
+                let tool_id = tool_router_key.to_string_without_version();
+
+                check_tool(
+                    tool_id.clone(),
+                    wasm_tool.config.clone(),
+                    function_args.clone(),
+                    wasm_tool.input_args.clone(),
+                    &None,
+                )?;
+
+                // The config of the call overrides the one stored with the tool
+                let mut config = wasm_tool.config.clone();
+                config.extend(function_config_vec);
+
+                let wasm_bytes = wasm_tool.read_module(&node_storage_path, &tool_router_key)?;
+                let db = context.db();
+                let bearer = db.read_api_v2_key().unwrap_or_default().unwrap_or_default();
+                let result = execute_wasm_tool(
+                    bearer,
+                    db,
+                    node_name,
+                    function_args,
+                    config,
+                    None,
+                    tool_id,
+                    app_id,
+                    agent_id,
+                    context.agent().get_llm_provider_id().to_string(),
+                    wasm_bytes,
+                    wasm_tool.entrypoint.clone(),
+                    wasm_tool.capabilities.clone(),
+                    wasm_tool.asset_paths(&node_storage_path, &tool_router_key),
+                    Some(all_files),
+                    None,
+                    None,
+                )
+                .await?;
+                let result_str = serde_json::to_string(&result)
+                    .map_err(|e| LLMProviderError::FunctionExecutionError(e.to_string()))?;
+                return Ok(ToolCallFunctionResponse {
+                    response: result_str,
+                    function_call,
+                });
+            }
             HanzoTool::Rust(rust_tool, _is_enabled) => {
                 // Get app_id from Cron UI if present, otherwise use job_id
                 let app_id = match context.full_job().associated_ui().as_ref() {
@@ -1091,6 +1184,8 @@ impl ToolRouter {
             }
             HanzoTool::Deno(deno_tool, _is_enabled) => {
                 let node_env = fetch_node_environment();
+                // The policy of the node may have changed since the tool was installed
+                node_env.tool_permissions_policy.check(deno_tool.permissions.as_ref())?;
                 let node_storage_path = node_env
                     .node_storage_path
                     .clone()
@@ -1169,6 +1264,67 @@ impl ToolRouter {
                 eprintln!("network tool with name {:?}", network_tool.name);
 
                 let agent_payments_manager = context.my_agent_payments_manager();
+
+                // Calls covered by a subscription or prepaid credits skip the invoice. Once the provider
+                // refuses the entitlement (expired or out of credits) a new one is bought below.
+                if network_tool.usage_type.grants_entitlement() && !network_tool.usage_type.is_free() {
+                    if let Some(manager) = &agent_payments_manager {
+                        let tool_key = network_tool.tool_router_key();
+                        let entitled_call = {
+                            let manager = manager.lock().await;
+                            let entitlement = manager.get_tool_entitlement(&tool_key).map_err(|e| {
+                                LLMProviderError::FunctionExecutionError(format!(
+                                    "Failed to read the entitlement to {}: {}",
+                                    tool_key, e
+                                ))
+                            })?;
+                            match entitlement {
+                                Some(_) => Some(
+                                    manager
+                                        .send_entitled_tool_call(
+                                            &network_tool,
+                                            Value::Object(function_args.clone()),
+                                            context.message_hash_id(),
+                                        )
+                                        .await
+                                        .map_err(|e| {
+                                            LLMProviderError::FunctionExecutionError(format!(
+                                                "Failed to call {} with its entitlement: {}",
+                                                tool_key, e
+                                            ))
+                                        })?,
+                                ),
+                                None => None,
+                            }
+                        };
+
+                        if let Some(invoice) = entitled_call {
+                            match Self::wait_for_entitled_call(context.db(), &invoice.invoice_id).await? {
+                                Some(invoice_result) => {
+                                    // Only a call the provider processed takes a use of the entitlement
+                                    if let Err(e) = manager.lock().await.consume_tool_entitlement(&tool_key) {
+                                        hanzo_log(
+                                            HanzoLogOption::Node,
+                                            HanzoLogLevel::Error,
+                                            &format!("Failed to take a use of the entitlement to {}: {}", tool_key, e),
+                                        );
+                                    }
+                                    return Ok(ToolCallFunctionResponse {
+                                        response: Self::invoice_response(&invoice_result),
+                                        function_call,
+                                    });
+                                }
+                                None => manager.lock().await.forget_tool_entitlement(&tool_key).map_err(|e| {
+                                    LLMProviderError::FunctionExecutionError(format!(
+                                        "Failed to forget the entitlement to {}: {}",
+                                        tool_key, e
+                                    ))
+                                })?,
+                            }
+                        }
+                    }
+                }
+
                 let (internal_invoice_request, wallet_balances) = {
                     // Start invoice request
                     let my_agent_payments_manager = match &agent_payments_manager {
@@ -1187,7 +1343,7 @@ impl ToolRouter {
                     };
 
                     // Retrieve wallet balances only if the tool is not free
-                    let balances = if matches!(network_tool.usage_type, UsageType::PerUse(ToolPrice::Free)) {
+                    let balances = if network_tool.usage_type.is_free() {
                         AddressBalanceList {
                             data: vec![],
                             has_more: false,
@@ -1216,7 +1372,7 @@ impl ToolRouter {
                     let invoice_request = match my_agent_payments_manager
                         .network_request_invoice(
                             network_tool.clone(),
-                            UsageTypeInquiry::PerUse,
+                            network_tool.usage_type.inquiry(),
                             context.message_hash_id(),
                         )
                         .await
@@ -1330,8 +1486,43 @@ impl ToolRouter {
                     }
                 };
 
+                // Pay right away when the spending policy allows it. Otherwise the invoice stays pending
+                // until a user pays it through the API.
+                let mut paid_by_policy = false;
+                if !network_tool.usage_type.is_free() {
+                    if let Some(manager) = &agent_payments_manager {
+                        let payment_result = manager
+                            .lock()
+                            .await
+                            .pay_invoice_and_send_receipt(
+                                internal_invoice_request.unique_id.clone(),
+                                Value::Object(function_args.clone()),
+                                node_name.clone(),
+                                context.message_hash_id(),
+                                false,
+                            )
+                            .await;
+                        match payment_result {
+                            Ok(_) => paid_by_policy = true,
+                            Err(AgentOfferingManagerError::PaymentPendingApproval(reason)) => {
+                                hanzo_log(
+                                    HanzoLogOption::Node,
+                                    HanzoLogLevel::Info,
+                                    &format!("Invoice waiting for approval: {}", reason),
+                                );
+                            }
+                            Err(e) => {
+                                return Err(LLMProviderError::FunctionExecutionError(format!(
+                                    "Failed to pay invoice: {}",
+                                    e
+                                )));
+                            }
+                        }
+                    }
+                }
+
                 // Get the ws from the context
-                {
+                if !paid_by_policy {
                     let ws_manager = context.ws_manager_trait();
 
                     if let Some(ws_manager) = &ws_manager {
@@ -1427,19 +1618,7 @@ impl ToolRouter {
 
                 eprintln!("invoice_result: {:?}", invoice_result);
 
-                // Try to parse the result_str and extract the "data" field
-                let response = match serde_json::from_str::<serde_json::Value>(
-                    &invoice_result.result_str.clone().unwrap_or_default(),
-                ) {
-                    Ok(parsed) => {
-                        if let Some(data) = parsed.get("data") {
-                            data.to_string()
-                        } else {
-                            invoice_result.result_str.clone().unwrap_or_default()
-                        }
-                    }
-                    Err(_) => invoice_result.result_str.clone().unwrap_or_default(),
-                };
+                let response = Self::invoice_response(&invoice_result);
 
                 eprintln!("parsed response: {:?}", response);
 
@@ -1451,6 +1630,63 @@ impl ToolRouter {
         }
     }
 
+    /// The result of a processed invoice, its "data" field when it has one
+    fn invoice_response(invoice_result: &Invoice) -> String {
+        let result_str = invoice_result.result_str.clone().unwrap_or_default();
+        match serde_json::from_str::<serde_json::Value>(&result_str) {
+            Ok(parsed) => match parsed.get("data") {
+                Some(data) => data.to_string(),
+                None => result_str,
+            },
+            Err(_) => result_str,
+        }
+    }
+
+    /// Waits for the provider to process a call made under an entitlement. None when the provider
+    /// has no entitlement of this node or it ran out, any other failure of the call is an error.
+    async fn wait_for_entitled_call(
+        db: Arc<SqliteManager>,
+        invoice_id: &str,
+    ) -> Result<Option<Invoice>, LLMProviderError> {
+        let start_time = std::time::Instant::now();
+        let timeout = std::time::Duration::from_secs(300); // 5 minutes
+        let interval = std::time::Duration::from_millis(100); // 100ms
+
+        loop {
+            if start_time.elapsed() > timeout {
+                return Err(LLMProviderError::FunctionExecutionError(
+                    "Timeout while waiting for the tool result".to_string(),
+                ));
+            }
+
+            if let Ok(invoice) = db.get_invoice(invoice_id) {
+                if invoice.status == InvoiceStatusEnum::Processed {
+                    return Ok(Some(invoice));
+                }
+            }
+            if let Ok(network_error) = db.get_invoice_network_error(invoice_id) {
+                return match network_error.error_kind {
+                    Some(
+                        InvoiceNetworkErrorKind::ToolEntitlementNotFound
+                        | InvoiceNetworkErrorKind::ToolEntitlementExhausted,
+                    ) => {
+                        hanzo_log(
+                            HanzoLogOption::Network,
+                            HanzoLogLevel::Info,
+                            &format!("Entitlement refused by the provider: {:?}", network_error),
+                        );
+                        Ok(None)
+                    }
+                    None => Err(LLMProviderError::FunctionExecutionError(
+                        network_error.user_error_message.unwrap_or(network_error.error_message),
+                    )),
+                };
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+
     /// This function is used to call a JS function directly
     /// It's very handy for agent-to-agent communication
     pub async fn call_js_function(
@@ -1475,6 +1711,7 @@ impl ToolRouter {
         };
 
         let node_env = fetch_node_environment();
+        node_env.tool_permissions_policy.check(js_tool.permissions.as_ref())?;
         let node_storage_path = node_env
             .node_storage_path
             .clone()
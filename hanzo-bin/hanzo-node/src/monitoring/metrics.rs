@@ -5,14 +5,14 @@
 
 use prometheus::{
     register_counter_vec, register_gauge_vec, register_histogram_vec,
-    Counter, CounterVec, Gauge, GaugeVec, Histogram, HistogramVec,
+    CounterVec, Gauge, GaugeVec, Histogram, HistogramVec,
     TextEncoder, Encoder, Registry,
 };
 use lazy_static::lazy_static;
 use std::time::{Duration, Instant};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use log::{debug, error, info};
+use log::{debug, info};
 
 // ⚡ MATRIX METRICS - SEE EVERYTHING
 lazy_static! {
@@ -237,6 +237,12 @@ pub struct MetricsCollector {
     collection_interval: Duration,
 }
 
+impl Default for MetricsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MetricsCollector {
     /// Create a new metrics collector
     pub fn new() -> Self {
@@ -4,6 +4,7 @@
 //! for the Hanzo node, enabling real-time optimization and bottleneck identification.
 
 pub mod metrics;
+pub mod telemetry;
 
 pub use metrics::{
     init_metrics,
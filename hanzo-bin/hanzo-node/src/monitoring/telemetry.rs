@@ -0,0 +1,152 @@
+//! OpenTelemetry tracing of job execution
+//!
+//! Every job message gets its own trace, with child spans for the LLM calls, tool calls,
+//! retrieval and payments made while answering it. Spans are exported over OTLP when
+//! `OTEL_EXPORTER_OTLP_ENDPOINT` is set, otherwise the global tracer is a no-op.
+//! The trace context is handed to the tool runners through the `TRACEPARENT` and
+//! `TRACESTATE` env vars and to outbound HTTP requests through the `traceparent` header.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+
+use opentelemetry::global;
+use opentelemetry::trace::{FutureExt, Status, TraceContextExt, Tracer};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+
+const TRACER_NAME: &str = "hanzo-node";
+
+/// Installs the OTLP exporter. Returns false when no OTLP endpoint is configured.
+pub fn init_telemetry() -> Result<bool, Box<dyn std::error::Error>> {
+    if std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_err() {
+        return Ok(false);
+    }
+
+    // The exporter reads the endpoint, headers and timeout from the OTEL_EXPORTER_OTLP_* env vars
+    let exporter = SpanExporter::builder().with_tonic().build()?;
+    let service_name = std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| TRACER_NAME.to_string());
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new(vec![KeyValue::new("service.name", service_name)]))
+        .build();
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    global::set_tracer_provider(provider);
+    Ok(true)
+}
+
+/// Starts a new trace, not attached to the current context, and returns a context holding its root span
+pub fn start_trace(name: &'static str, attributes: Vec<KeyValue>) -> Context {
+    let tracer = global::tracer(TRACER_NAME);
+    let span = tracer
+        .span_builder(name)
+        .with_attributes(attributes)
+        .start_with_context(&tracer, &Context::new());
+    Context::new().with_span(span)
+}
+
+/// Starts a child span of the current span and returns a context holding it
+pub fn start_span(name: &'static str, attributes: Vec<KeyValue>) -> Context {
+    let tracer = global::tracer(TRACER_NAME);
+    let span = tracer.span_builder(name).with_attributes(attributes).start(&tracer);
+    Context::current_with_span(span)
+}
+
+/// Ends the span of `cx`, marking it as failed when an error is given
+pub fn end_span<E: Display>(cx: &Context, error: Option<&E>) {
+    let span = cx.span();
+    if let Some(error) = error {
+        span.set_status(Status::error(error.to_string()));
+    }
+    span.end();
+}
+
+/// Runs `future` inside a child span of the current span
+pub async fn in_span<F, T, E>(name: &'static str, attributes: Vec<KeyValue>, future: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    let cx = start_span(name, attributes);
+    let result = future.with_context(cx.clone()).await;
+    end_span(&cx, result.as_ref().err());
+    result
+}
+
+/// Adds attributes to the current span
+pub fn set_span_attributes(attributes: Vec<KeyValue>) {
+    Context::map_current(|cx| cx.span().set_attributes(attributes));
+}
+
+/// Hex trace id of `cx`, None when tracing is disabled
+pub fn trace_id(cx: &Context) -> Option<String> {
+    let span_context = cx.span().span_context().clone();
+    span_context.is_valid().then(|| span_context.trace_id().to_string())
+}
+
+/// W3C trace context headers (`traceparent` and `tracestate`) of the current span
+pub fn trace_context_headers() -> HashMap<String, String> {
+    let mut headers = HashMap::new();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&Context::current(), &mut headers));
+    headers
+}
+
+/// Trace context of the current span as env vars (`TRACEPARENT` and `TRACESTATE`) for the tool runners
+pub fn trace_context_env() -> HashMap<String, String> {
+    trace_context_headers()
+        .into_iter()
+        .map(|(key, value)| (key.to_uppercase(), value))
+        .collect()
+}
+
+pub trait RequestTraceExt {
+    /// Adds the `traceparent` header of the current span to the request
+    fn with_traceparent(self) -> Self;
+}
+
+impl RequestTraceExt for reqwest::RequestBuilder {
+    fn with_traceparent(self) -> Self {
+        trace_context_headers()
+            .into_iter()
+            .fold(self, |request, (key, value)| request.header(key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_trace_context_propagation() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let provider = TracerProvider::builder().build();
+        global::set_tracer_provider(provider);
+
+        let cx = start_trace("job_message", vec![KeyValue::new("job.id", "job_1")]);
+        let trace_id = trace_id(&cx).expect("trace id of a recorded trace");
+
+        let env = async { trace_context_env() }.with_context(cx.clone()).await;
+        let traceparent = env.get("TRACEPARENT").expect("traceparent env var");
+        assert!(traceparent.starts_with(&format!("00-{}-", trace_id)));
+
+        // Child spans stay in the trace of their parent
+        let child_trace_id =
+            async { in_span("tool_call", vec![], async { Ok::<_, String>(trace_id_of_current()) }).await }
+                .with_context(cx.clone())
+                .await
+                .unwrap();
+        assert_eq!(child_trace_id, Some(trace_id));
+        end_span::<String>(&cx, None);
+
+        // Without a span there is nothing to propagate
+        assert!(trace_context_env().is_empty());
+    }
+
+    fn trace_id_of_current() -> Option<String> {
+        trace_id(&Context::current())
+    }
+}
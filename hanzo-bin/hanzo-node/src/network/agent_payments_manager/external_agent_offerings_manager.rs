@@ -1,4 +1,3 @@
-use crate::llm_provider::error::LLMProviderError;
 use crate::managers::identity_manager::IdentityManagerTrait;
 use crate::managers::tool_router::ToolRouter;
 use crate::network::libp2p_manager::NetworkEvent;
@@ -6,16 +5,17 @@ use crate::network::network_manager_utils::send_message_to_peer;
 use crate::network::node::ProxyConnectionInfo;
 use crate::wallet::wallet_error;
 use crate::wallet::wallet_manager::WalletManager;
+use base64::Engine;
 use chrono::{Duration, Utc};
 use ed25519_dalek::SigningKey;
 use futures::Future;
 use hanzo_job_queue_manager::job_queue_manager::JobQueueManager;
 use hanzo_messages::schemas::invoices::{
-    Invoice, InvoiceError, InvoiceRequest, InvoiceRequestNetworkError, InvoiceStatusEnum,
+    Invoice, InvoiceError, InvoiceNetworkErrorKind, InvoiceRequest, InvoiceRequestNetworkError, InvoiceStatusEnum,
 };
 use hanzo_messages::schemas::hanzo_name::HanzoName;
 use hanzo_messages::schemas::hanzo_tool_offering::{
-    HanzoToolOffering, ToolPrice, UsageType, UsageTypeInquiry,
+    HanzoToolOffering, ToolPrice, UsageTypeInquiry,
 };
 use hanzo_messages::schemas::tool_router_key::ToolRouterKey;
 use hanzo_messages::hanzo_message::hanzo_message::ExternalMetadata;
@@ -28,7 +28,7 @@ use hanzo_runtime::functions::x402;
 use hanzo_runtime::functions::x402::settle_payment::settle_payment;
 use hanzo_runtime::functions::x402::settle_payment::Input as SettleInput;
 use hanzo_runtime::functions::x402::verify_payment::verify_payment;
-use hanzo_db_sqlite::SqliteManager;
+use hanzo_db_sqlite::{SqliteManager, SqliteManagerError};
 use hanzo_tools::tools::network_tool::NetworkTool;
 use std::collections::HashSet;
 use std::pin::Pin;
@@ -46,6 +46,14 @@ use x25519_dalek::StaticSecret as EncryptionStaticKey;
 pub enum AgentOfferingManagerError {
     OperationFailed(String),
     InvalidUsageType(String),
+    // The spending policy refuses the payment
+    PaymentDenied(String),
+    // The payment needs the approval of a user
+    PaymentPendingApproval(String),
+    // The buyer has no entitlement to the tool
+    ToolEntitlementNotFound(String),
+    // The subscription of the buyer expired or its credits ran out
+    ToolEntitlementExhausted(String),
 }
 
 impl fmt::Display for AgentOfferingManagerError {
@@ -53,6 +61,44 @@ impl fmt::Display for AgentOfferingManagerError {
         match self {
             AgentOfferingManagerError::OperationFailed(msg) => write!(f, "Operation failed: {}", msg),
             AgentOfferingManagerError::InvalidUsageType(msg) => write!(f, "Invalid usage type: {}", msg),
+            AgentOfferingManagerError::PaymentDenied(msg) => {
+                write!(f, "Payment denied by the spending policy: {}", msg)
+            }
+            AgentOfferingManagerError::PaymentPendingApproval(msg) => {
+                write!(f, "Payment waiting for approval: {}", msg)
+            }
+            AgentOfferingManagerError::ToolEntitlementNotFound(msg) => {
+                write!(f, "No entitlement to the tool: {}", msg)
+            }
+            AgentOfferingManagerError::ToolEntitlementExhausted(msg) => {
+                write!(f, "Entitlement to the tool used up: {}", msg)
+            }
+        }
+    }
+}
+
+impl AgentOfferingManagerError {
+    /// Keeps apart the entitlement errors the buyer acts upon from the other database errors
+    pub(crate) fn from_entitlement_error(error: SqliteManagerError) -> Self {
+        match error {
+            SqliteManagerError::ToolEntitlementNotFound(msg) => AgentOfferingManagerError::ToolEntitlementNotFound(msg),
+            SqliteManagerError::ToolEntitlementExhausted(msg) => {
+                AgentOfferingManagerError::ToolEntitlementExhausted(msg)
+            }
+            e => AgentOfferingManagerError::OperationFailed(format!("Entitlement error: {:?}", e)),
+        }
+    }
+
+    /// The kind sent back to the requester, for the failures it handles by itself
+    pub fn network_error_kind(&self) -> Option<InvoiceNetworkErrorKind> {
+        match self {
+            AgentOfferingManagerError::ToolEntitlementNotFound(_) => {
+                Some(InvoiceNetworkErrorKind::ToolEntitlementNotFound)
+            }
+            AgentOfferingManagerError::ToolEntitlementExhausted(_) => {
+                Some(InvoiceNetworkErrorKind::ToolEntitlementExhausted)
+            }
+            _ => None,
         }
     }
 }
@@ -69,6 +115,20 @@ impl From<InvoiceError> for AgentOfferingManagerError {
     }
 }
 
+/// Hash of the onchain transaction in an x402 settlement response header, a base64 encoded
+/// JSON object `{ success, transaction, network, payer }`
+pub(crate) fn settlement_transaction_hash(payment_response: &str) -> Option<String> {
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(payment_response)
+        .ok()?;
+    let response: serde_json::Value = serde_json::from_slice(&decoded).ok()?;
+    response
+        .get("transaction")
+        .and_then(|transaction| transaction.as_str())
+        .filter(|transaction| !transaction.is_empty())
+        .map(|transaction| transaction.to_string())
+}
+
 // TODO: for the hash maybe we could use public_key + nonce
 // and then that hash it is used to produce another hash that's shared
 // this way we never share our public key + nonce
@@ -544,16 +604,15 @@ impl ExtAgentOfferingsManager {
             .get_tool_offering(&actual_tool_key_name)
             .map_err(|e| AgentOfferingManagerError::OperationFailed(format!("Failed to get tool offering: {:?}", e)))?;
 
-        let usage_type = match invoice_request.usage_type_inquiry {
-            UsageTypeInquiry::PerUse => match hanzo_offering.usage_type {
-                UsageType::PerUse(price) => UsageType::PerUse(price),
-                _ => {
-                    return Err(AgentOfferingManagerError::InvalidUsageType(
-                        "Invalid usage type for PerUse inquiry".to_string(),
-                    ))
-                }
-            },
-        };
+        if hanzo_offering.usage_type.inquiry() != invoice_request.usage_type_inquiry {
+            return Err(AgentOfferingManagerError::InvalidUsageType(format!(
+                "{} is not offered for {}, it is sold {}",
+                invoice_request.usage_type_inquiry,
+                actual_tool_key_name,
+                hanzo_offering.usage_type.inquiry()
+            )));
+        }
+        let usage_type = hanzo_offering.usage_type;
 
         // Check if an invoice with the same ID already exists
         if db.get_invoice(&invoice_request.unique_id).is_ok() {
@@ -603,6 +662,60 @@ impl ExtAgentOfferingsManager {
         Ok(invoice)
     }
 
+    /// Sends an InvoiceRequestNetworkError back to the requester of an invoice
+    async fn send_invoice_network_error(
+        &self,
+        requester_node_name: HanzoName,
+        network_error: InvoiceRequestNetworkError,
+        external_metadata: Option<ExternalMetadata>,
+    ) -> Result<(), AgentOfferingManagerError> {
+        if let Some(identity_manager_arc) = self.identity_manager.upgrade() {
+            let identity_manager = identity_manager_arc.lock().await;
+            let standard_identity = identity_manager
+                .external_profile_to_global_identity(&network_error.requester_name.to_string(), None)
+                .await
+                .map_err(|e| AgentOfferingManagerError::OperationFailed(e))?;
+            drop(identity_manager);
+            let receiver_public_key = standard_identity.node_encryption_public_key;
+
+            let receiver_node_name = if network_error
+                .requester_name
+                .get_node_name_string()
+                .starts_with("@@localhost.")
+            {
+                requester_node_name.to_string()
+            } else {
+                network_error.requester_name.to_string()
+            };
+
+            let error_message = HanzoMessageBuilder::create_generic_invoice_message(
+                network_error,
+                MessageSchemaType::InvoiceRequestNetworkError,
+                clone_static_secret_key(&self.my_encryption_secret_key),
+                clone_signature_secret_key(&self.my_signature_secret_key),
+                receiver_public_key,
+                self.node_name.to_string(),
+                "".to_string(),
+                receiver_node_name,
+                "main".to_string(),
+                external_metadata,
+            )
+            .map_err(|e| AgentOfferingManagerError::OperationFailed(e.to_string()))?;
+
+            send_message_to_peer(
+                error_message,
+                self.db.clone(),
+                standard_identity,
+                self.my_encryption_secret_key.clone(),
+                self.identity_manager.clone(),
+                self.proxy_connection_info.clone(),
+                self.libp2p_event_sender.clone(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
     ///
     /// Requests an invoice from the network.
     ///
@@ -645,53 +758,12 @@ impl ExtAgentOfferingsManager {
                     response_date_time: Utc::now(),
                     user_error_message: Some(format!("{:?}", e)),
                     error_message: format!("{:?}", e),
+                    error_kind: e.network_error_kind(),
                 };
 
                 // Send the InvoiceRequestNetworkError back to the requester
-                if let Some(identity_manager_arc) = self.identity_manager.upgrade() {
-                    let identity_manager = identity_manager_arc.lock().await;
-                    let standard_identity = identity_manager
-                        .external_profile_to_global_identity(&invoice_request.requester_name.to_string(), None)
-                        .await
-                        .map_err(|e| AgentOfferingManagerError::OperationFailed(e))?;
-                    drop(identity_manager);
-                    let receiver_public_key = standard_identity.node_encryption_public_key;
-
-                    let receiver_node_name = if invoice_request
-                        .requester_name
-                        .get_node_name_string()
-                        .starts_with("@@localhost.")
-                    {
-                        requester_node_name.to_string()
-                    } else {
-                        invoice_request.requester_name.to_string()
-                    };
-
-                    let error_message = HanzoMessageBuilder::create_generic_invoice_message(
-                        network_error.clone(),
-                        MessageSchemaType::InvoiceRequestNetworkError,
-                        clone_static_secret_key(&self.my_encryption_secret_key),
-                        clone_signature_secret_key(&self.my_signature_secret_key),
-                        receiver_public_key,
-                        self.node_name.to_string(),
-                        "".to_string(),
-                        receiver_node_name,
-                        "main".to_string(),
-                        external_metadata,
-                    )
-                    .map_err(|e| AgentOfferingManagerError::OperationFailed(e.to_string()))?;
-
-                    send_message_to_peer(
-                        error_message,
-                        self.db.clone(),
-                        standard_identity,
-                        self.my_encryption_secret_key.clone(),
-                        self.identity_manager.clone(),
-                        self.proxy_connection_info.clone(),
-                        self.libp2p_event_sender.clone(),
-                    )
+                self.send_invoice_network_error(requester_node_name, network_error, external_metadata)
                     .await?;
-                }
 
                 return Err(e);
             }
@@ -756,6 +828,61 @@ impl ExtAgentOfferingsManager {
         Ok(invoice)
     }
 
+    /// Builds the invoice of a call made under a subscription or prepaid credits and takes one use
+    /// from the entitlement of the buyer. Also returns whether a credit was taken, to give it back
+    /// if the call fails.
+    fn entitled_invoice(
+        &self,
+        db: &SqliteManager,
+        requester_node_name: &HanzoName,
+        invoice: &Invoice,
+    ) -> Result<(Invoice, bool), AgentOfferingManagerError> {
+        let local_tool_key = invoice
+            .hanzo_offering
+            .convert_tool_to_local()
+            .map_err(AgentOfferingManagerError::OperationFailed)?;
+        let hanzo_offering = db
+            .get_tool_offering(&local_tool_key)
+            .map_err(|e| AgentOfferingManagerError::OperationFailed(format!("Failed to get tool offering: {:?}", e)))?;
+        if hanzo_offering.usage_type.inquiry() != invoice.usage_type_inquiry {
+            return Err(AgentOfferingManagerError::InvalidUsageType(format!(
+                "{} is not offered for {}",
+                invoice.usage_type_inquiry, local_tool_key
+            )));
+        }
+
+        let buyer_name = requester_node_name.get_node_name_string();
+        let (entitlement, credit_taken) = db
+            .consume_tool_entitlement(&buyer_name, &local_tool_key, Utc::now())
+            .map_err(AgentOfferingManagerError::from_entitlement_error)?;
+        hanzo_log(
+            HanzoLogOption::ExtSubscriptions,
+            HanzoLogLevel::Info,
+            &format!(
+                "{} used its entitlement to {} (expires at: {:?}, credits left: {:?})",
+                buyer_name, local_tool_key, entitlement.expires_at, entitlement.remaining_credits
+            ),
+        );
+
+        let local_invoice = Invoice {
+            provider_name: self.node_name.clone(),
+            hanzo_offering: HanzoToolOffering {
+                tool_key: invoice.hanzo_offering.tool_key.clone(),
+                usage_type: hanzo_offering.usage_type,
+                meta_description: None,
+            },
+            status: InvoiceStatusEnum::Paid,
+            payment: None,
+            tool_data: None,
+            result_str: None,
+            response_date_time: None,
+            ..invoice.clone()
+        };
+        db.set_invoice(&local_invoice)
+            .map_err(|e| AgentOfferingManagerError::OperationFailed(format!("Failed to store invoice: {:?}", e)))?;
+        Ok((local_invoice, credit_taken))
+    }
+
     ///
     /// Confirms the payment of an invoice and processes it.
     ///
@@ -769,7 +896,7 @@ impl ExtAgentOfferingsManager {
     /// * `Result<Invoice, AgentOfferingManagerError>` - The processed invoice or an error.
     pub async fn confirm_invoice_payment_and_process(
         &mut self,
-        _requester_node_name: HanzoName,
+        requester_node_name: HanzoName,
         invoice: Invoice,
         // prehash_validation: String, // TODO: connect later on
     ) -> Result<Invoice, AgentOfferingManagerError> {
@@ -779,20 +906,28 @@ impl ExtAgentOfferingsManager {
             .upgrade()
             .ok_or_else(|| AgentOfferingManagerError::OperationFailed("Failed to upgrade db reference".to_string()))?;
 
-        let mut local_invoice = db
-            .get_invoice(&invoice.invoice_id)
-            .map_err(|e| AgentOfferingManagerError::OperationFailed(format!("Failed to get invoice: {:?}", e)))?;
+        // Calls covered by a subscription or prepaid credits come without an invoice request nor a payment
+        let (mut local_invoice, is_entitled_call, credit_taken) = match db.get_invoice(&invoice.invoice_id) {
+            Ok(local_invoice) => (local_invoice, false, false),
+            Err(_) if invoice.payment.is_none() && invoice.usage_type_inquiry != UsageTypeInquiry::PerUse => {
+                let (local_invoice, credit_taken) = self.entitled_invoice(&db, &requester_node_name, &invoice)?;
+                (local_invoice, true, credit_taken)
+            }
+            Err(e) => {
+                return Err(AgentOfferingManagerError::OperationFailed(format!(
+                    "Failed to get invoice: {:?}",
+                    e
+                )))
+            }
+        };
 
         println!("local_invoice: {:?}", local_invoice);
         println!("received invoice: {:?}", invoice);
 
-        let is_free_tool = matches!(
-            local_invoice.hanzo_offering.usage_type,
-            UsageType::PerUse(ToolPrice::Free)
-        );
+        let needs_payment = !is_entitled_call && !local_invoice.hanzo_offering.usage_type.is_free();
 
-        // Step 2: verify that the invoice is actually paid (skip for free tools)
-        let output_opt = if !is_free_tool {
+        // Step 2: verify that the invoice is actually paid (skip for free tools and entitled calls)
+        let output_opt = if needs_payment {
             let payment_payload = invoice
                 .payment
                 .as_ref()
@@ -800,8 +935,8 @@ impl ExtAgentOfferingsManager {
             let transaction_signed = Some(payment_payload.transaction_signed.clone());
 
             // Extract payment requirements from local_invoice
-            let payment_requirements = match &local_invoice.hanzo_offering.usage_type {
-                UsageType::PerUse(ToolPrice::Payment(reqs)) => reqs.get(0).ok_or_else(|| {
+            let payment_requirements = match local_invoice.hanzo_offering.usage_type.price() {
+                ToolPrice::Payment(reqs) => reqs.get(0).ok_or_else(|| {
                     AgentOfferingManagerError::OperationFailed("No payment requirements found".to_string())
                 })?,
                 _ => {
@@ -856,7 +991,7 @@ impl ExtAgentOfferingsManager {
             println!("\n\ninput for payment verification: {:?}", input);
 
             let output = verify_payment(input).await.map_err(|e| {
-                AgentOfferingManagerError::OperationFailed(format!("Payment verification failed: {:?}", e))
+                AgentOfferingManagerError::OperationFailed(format!("Payment verification failed: {}", e))
             })?;
 
             println!("\noutput of payment verification: {:?}", output);
@@ -877,25 +1012,43 @@ impl ExtAgentOfferingsManager {
             .tool_data
             .and_then(|args_value: serde_json::Value| args_value.as_object().cloned())
             .unwrap_or_else(|| serde_json::Map::new());
+
+        // js tool name
+        let local_tool_key = local_invoice.hanzo_offering.convert_tool_to_local().map_err(|e| {
+            AgentOfferingManagerError::OperationFailed(format!("Failed to convert tool_key to local tool_key: {:?}", e))
+        })?;
         {
             let tool_router = self.tool_router.upgrade().ok_or_else(|| {
                 AgentOfferingManagerError::OperationFailed("Failed to upgrade tool_router reference".to_string())
             })?;
 
-            // js tool name
-            let local_tool_key = local_invoice.hanzo_offering.convert_tool_to_local().map_err(|e| {
-                AgentOfferingManagerError::OperationFailed(format!(
-                    "Failed to convert tool_key to local tool_key: {:?}",
-                    e
-                ))
-            })?;
-
-            let result = tool_router
-                .call_js_function(data_payload, _requester_node_name, &local_tool_key)
+            let result = match tool_router
+                .call_js_function(data_payload, requester_node_name.clone(), &local_tool_key)
                 .await
-                .map_err(|e: LLMProviderError| {
-                    AgentOfferingManagerError::OperationFailed(format!("LLMProviderError: {:?}", e))
-                })?;
+            {
+                Ok(result) => result,
+                Err(e) => {
+                    // A failed call does not use up the credit of the buyer
+                    if credit_taken {
+                        let buyer_name = requester_node_name.get_node_name_string();
+                        if let Err(refund_error) = db.refund_tool_entitlement(&buyer_name, &local_tool_key, Utc::now())
+                        {
+                            hanzo_log(
+                                HanzoLogOption::ExtSubscriptions,
+                                HanzoLogLevel::Error,
+                                &format!(
+                                    "Failed to give back the credit of {} to {}: {:?}",
+                                    buyer_name, local_tool_key, refund_error
+                                ),
+                            );
+                        }
+                    }
+                    return Err(AgentOfferingManagerError::OperationFailed(format!(
+                        "LLMProviderError: {:?}",
+                        e
+                    )));
+                }
+            };
 
             println!("result: {:?}", result);
 
@@ -910,13 +1063,13 @@ impl ExtAgentOfferingsManager {
         // Step 4: if we got a successful result, we settle the payment
         // For testing maybe we can add a flag to avoid this step
         let is_testing = std::env::var("IS_TESTING").ok().map(|v| v == "1").unwrap_or(false);
-        if !is_testing && !is_free_tool {
+        if !is_testing && needs_payment {
             let output = output_opt.as_ref().expect("Missing verification output");
             // Extract decoded_payment for settlement
             let decoded_payment = output.valid.as_ref().unwrap().decoded_payment.clone();
 
-            let payment_requirements = match &local_invoice.hanzo_offering.usage_type {
-                UsageType::PerUse(ToolPrice::Payment(reqs)) => reqs.clone(),
+            let payment_requirements = match local_invoice.hanzo_offering.usage_type.price() {
+                ToolPrice::Payment(reqs) => reqs.clone(),
                 _ => {
                     return Err(AgentOfferingManagerError::OperationFailed(
                         "Unsupported usage type for settlement".to_string(),
@@ -929,7 +1082,7 @@ impl ExtAgentOfferingsManager {
                 facilitator: FacilitatorConfig::default(),
             };
             let settle_result = settle_payment(settle_input).await.map_err(|e| {
-                AgentOfferingManagerError::OperationFailed(format!("Payment settlement failed: {:?}", e))
+                AgentOfferingManagerError::OperationFailed(format!("Payment settlement failed: {}", e))
             })?;
             if settle_result.valid.is_none() {
                 local_invoice.status = InvoiceStatusEnum::Failed;
@@ -943,6 +1096,39 @@ impl ExtAgentOfferingsManager {
                     "Payment settlement failed".to_string(),
                 ));
             }
+
+            // Keep the settlement transaction so the payment reconciler can follow it to confirmation
+            let transaction_hash = settle_result
+                .valid
+                .as_ref()
+                .and_then(|valid| settlement_transaction_hash(&valid.payment_response));
+            if let Some(mut payment) = invoice.payment.clone() {
+                payment.transaction_hash = transaction_hash;
+                local_invoice.payment = Some(payment);
+                db.set_invoice(&local_invoice).map_err(|e| {
+                    AgentOfferingManagerError::OperationFailed(format!(
+                        "Failed to set invoice after settlement: {:?}",
+                        e
+                    ))
+                })?;
+            }
+        }
+
+        // Step 5: a paid subscription or pack of credits is granted to the buyer, this call being its first use
+        if needs_payment && local_invoice.hanzo_offering.usage_type.grants_entitlement() {
+            let buyer_name = requester_node_name.get_node_name_string();
+            let now = Utc::now();
+            db.grant_tool_entitlement(
+                &buyer_name,
+                &local_tool_key,
+                &local_invoice.hanzo_offering.usage_type,
+                &local_invoice.invoice_id,
+                now,
+            )
+            .and_then(|_| db.consume_tool_entitlement(&buyer_name, &local_tool_key, now))
+            .map_err(|e| {
+                AgentOfferingManagerError::OperationFailed(format!("Failed to grant tool entitlement: {:?}", e))
+            })?;
         }
 
         // Old stuff below
@@ -979,9 +1165,34 @@ impl ExtAgentOfferingsManager {
     ) -> Result<(), AgentOfferingManagerError> {
         eprintln!("💸 network_confirm_invoice_payment_and_process, requester_node_name: {:?}, invoice: {:?}, external_metadata: {:?}", requester_node_name, invoice, external_metadata);
         // Call confirm_invoice_payment_and_process to process the invoice
-        let local_invoice = self
+        let local_invoice = match self
             .confirm_invoice_payment_and_process(requester_node_name.clone(), invoice.clone())
-            .await?;
+            .await
+        {
+            Ok(local_invoice) => local_invoice,
+            Err(e) => {
+                hanzo_log(
+                    HanzoLogOption::ExtSubscriptions,
+                    HanzoLogLevel::Error,
+                    &format!("Failed to process invoice {}: {:?}", invoice.invoice_id, e),
+                );
+
+                // Lets the requester stop waiting, e.g. to buy a new entitlement once its own ran out
+                let network_error = InvoiceRequestNetworkError {
+                    invoice_id: invoice.invoice_id.clone(),
+                    provider_name: self.node_name.clone(),
+                    requester_name: invoice.requester_name.clone(),
+                    request_date_time: invoice.request_date_time,
+                    response_date_time: Utc::now(),
+                    user_error_message: Some(e.to_string()),
+                    error_message: format!("{:?}", e),
+                    error_kind: e.network_error_kind(),
+                };
+                self.send_invoice_network_error(requester_node_name, network_error, external_metadata)
+                    .await?;
+                return Err(e);
+            }
+        };
 
         // Continue
         if let Some(identity_manager_arc) = self.identity_manager.upgrade() {
@@ -1239,4 +1450,22 @@ mod tests {
     fn node_name() -> HanzoName {
         HanzoName::new("@@localhost.sep-hanzo".to_string()).unwrap()
     }
+
+    #[test]
+    fn test_settlement_transaction_hash() {
+        let header = base64::engine::general_purpose::STANDARD.encode(
+            serde_json::json!({
+                "success": true,
+                "transaction": "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060",
+                "network": "base-sepolia",
+                "payer": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+            })
+            .to_string(),
+        );
+        assert_eq!(
+            settlement_transaction_hash(&header).as_deref(),
+            Some("0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060")
+        );
+        assert_eq!(settlement_transaction_hash("not base64"), None);
+    }
 }
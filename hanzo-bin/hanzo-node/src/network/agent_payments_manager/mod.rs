@@ -1,3 +1,4 @@
 pub mod crypto_invoice_manager;
 pub mod external_agent_offerings_manager;
 pub mod my_agent_offerings_manager;
+pub mod x402_paywall;
@@ -1,6 +1,6 @@
 use std::sync::{Arc, Weak};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use ed25519_dalek::SigningKey;
 use serde_json::{json, Value};
 
@@ -9,11 +9,14 @@ use hanzo_messages::schemas::agent_network_offering::AgentNetworkOfferingRequest
 use hanzo_messages::schemas::tool_router_key::ToolRouterKey;
 use hanzo_messages::{
     schemas::{
-        invoices::{InternalInvoiceRequest, Invoice, InvoiceStatusEnum, Payment},
+        invoices::{generate_x402_nonce, InternalInvoiceRequest, Invoice, InvoiceStatusEnum, Payment},
         hanzo_name::HanzoName,
         hanzo_proxy_builder_info::HanzoProxyBuilderInfo,
         hanzo_tool_offering::{HanzoToolOffering, ToolPrice, UsageTypeInquiry},
-        wallet_mixed::{AddressBalanceList, Asset},
+        tool_entitlement::ToolEntitlement,
+        wallet_mixed::{AddressBalanceList, Asset, AssetType},
+        wallet_spending_policy::{SpendingDecision, SpendingLedgerEntry, SpendingRequest, SpentAmounts},
+        x402_types::PaymentRequirements,
     },
     hanzo_message::hanzo_message_schemas::MessageSchemaType,
     hanzo_utils::{
@@ -21,15 +24,17 @@ use hanzo_messages::{
         signatures::clone_signature_secret_key,
     },
 };
-use hanzo_db_sqlite::SqliteManager;
+use hanzo_db_sqlite::{SqliteManager, SqliteManagerError};
 use hanzo_tools::tools::{
     network_tool::NetworkTool, parameters::Parameters, hanzo_tool::HanzoToolHeader, tool_output_arg::ToolOutputArg,
 };
+use opentelemetry::KeyValue;
 use tokio::sync::Mutex;
 use x25519_dalek::StaticSecret as EncryptionStaticKey;
 
 use crate::{
     managers::{identity_manager::IdentityManagerTrait, tool_router::ToolRouter},
+    monitoring::telemetry::in_span,
     network::{
         libp2p_manager::NetworkEvent,
         network_manager_utils::{get_proxy_builder_info_static, send_message_to_peer},
@@ -273,6 +278,7 @@ impl MyAgentOfferingsManager {
     /// # Arguments
     ///
     /// * `invoice` - The invoice to be paid.
+    /// * `approved_by_user` - Whether a user approved the payment, otherwise it must be under the approval threshold of the spending policy.
     ///
     /// # Returns
     ///
@@ -281,15 +287,15 @@ impl MyAgentOfferingsManager {
         &self,
         invoice: &Invoice,
         node_name: HanzoName,
+        approved_by_user: bool,
     ) -> Result<Payment, AgentOfferingManagerError> {
         // Mocking the payment process
         println!("Initiating payment for invoice ID: {}", invoice.invoice_id);
 
         // Determine the price for this invoice
-        let usage_type_inquiry = UsageTypeInquiry::PerUse;
         let price = invoice
             .hanzo_offering
-            .get_price_for_usage(&usage_type_inquiry)
+            .get_price_for_usage(&invoice.usage_type_inquiry)
             .ok_or_else(|| {
                 AgentOfferingManagerError::OperationFailed("Failed to get price for usage type".to_string())
             })?;
@@ -342,6 +348,8 @@ impl MyAgentOfferingsManager {
             }
         };
 
+        self.check_spending_policy(invoice, asset_payment, approved_by_user)?;
+
         let my_address = wallet.payment_wallet.get_address();
 
         // Create the Asset struct
@@ -386,7 +394,17 @@ impl MyAgentOfferingsManager {
             ));
         }
 
-        let payment = match wallet.pay_invoice(invoice.clone(), node_name.clone()).await {
+        let span_attributes = vec![
+            KeyValue::new("invoice.id", invoice.invoice_id.clone()),
+            KeyValue::new("invoice.amount", format!("{:?}", price)),
+        ];
+        let payment_result = in_span(
+            "payment",
+            span_attributes,
+            wallet.pay_invoice(invoice.clone(), node_name.clone()),
+        )
+        .await;
+        let payment = match payment_result {
             Ok(payment) => {
                 println!("Payment successful: {:?}", payment);
 
@@ -411,6 +429,14 @@ impl MyAgentOfferingsManager {
             }
             Err(e) => {
                 eprintln!("Error paying invoice: {:?}", e);
+                if let Err(ledger_err) = self.record_spending_decision(
+                    invoice,
+                    asset_payment,
+                    SpendingDecision::PaymentFailed,
+                    Some(e.to_string()),
+                ) {
+                    eprintln!("failed to record payment failure: {}", ledger_err);
+                }
 
                 // Add tracing for payment error
                 if let Some(db) = self.db.upgrade() {
@@ -441,6 +467,80 @@ impl MyAgentOfferingsManager {
         Ok(payment)
     }
 
+    /// Checks a payment against the wallet spending policy and records the decision in the spending ledger
+    fn check_spending_policy(
+        &self,
+        invoice: &Invoice,
+        requirements: &PaymentRequirements,
+        approved_by_user: bool,
+    ) -> Result<(), AgentOfferingManagerError> {
+        let db = self
+            .db
+            .upgrade()
+            .ok_or_else(|| AgentOfferingManagerError::OperationFailed("Failed to upgrade db reference".to_string()))?;
+
+        let amount = requirements.max_amount_required.parse::<u128>().map_err(|e| {
+            AgentOfferingManagerError::OperationFailed(format!("Failed to parse required amount: {}", e))
+        })?;
+        let asset_type = AssetType::from_asset(&requirements.asset, &requirements.network);
+        let policy = db.get_wallet_spending_policy().map_err(|e| {
+            AgentOfferingManagerError::OperationFailed(format!("Failed to get spending policy: {:?}", e))
+        })?;
+        let spent = match &asset_type {
+            Some(asset_type) => db.get_spent_amounts(asset_type, Utc::now()).map_err(|e| {
+                AgentOfferingManagerError::OperationFailed(format!("Failed to get spent amounts: {:?}", e))
+            })?,
+            None => SpentAmounts::default(),
+        };
+
+        let request = SpendingRequest {
+            counterparty: invoice.provider_name.get_node_name_string(),
+            asset: asset_type,
+            amount,
+            approved_by_user,
+        };
+        let (decision, reason) = policy.evaluate(&request, spent);
+        self.record_spending_decision(invoice, requirements, decision, reason.clone())?;
+
+        match decision {
+            SpendingDecision::Approved | SpendingDecision::ApprovedByUser => Ok(()),
+            SpendingDecision::PendingApproval => Err(AgentOfferingManagerError::PaymentPendingApproval(
+                reason.unwrap_or_default(),
+            )),
+            _ => Err(AgentOfferingManagerError::PaymentDenied(reason.unwrap_or_default())),
+        }
+    }
+
+    /// Appends a decision taken on the payment of an invoice to the spending ledger
+    fn record_spending_decision(
+        &self,
+        invoice: &Invoice,
+        requirements: &PaymentRequirements,
+        decision: SpendingDecision,
+        reason: Option<String>,
+    ) -> Result<(), AgentOfferingManagerError> {
+        let db = self
+            .db
+            .upgrade()
+            .ok_or_else(|| AgentOfferingManagerError::OperationFailed("Failed to upgrade db reference".to_string()))?;
+
+        let entry = SpendingLedgerEntry {
+            invoice_id: invoice.invoice_id.clone(),
+            counterparty: invoice.provider_name.get_node_name_string(),
+            tool_key: invoice.hanzo_offering.tool_key.clone(),
+            asset_type: AssetType::from_asset(&requirements.asset, &requirements.network),
+            asset: requirements.asset.clone(),
+            network: requirements.network.clone(),
+            amount: requirements.max_amount_required.clone(),
+            decision,
+            reason,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        db.add_spending_ledger_entry(&entry).map_err(|e| {
+            AgentOfferingManagerError::OperationFailed(format!("Failed to record spending decision: {:?}", e))
+        })
+    }
+
     /// Store the quote invoice (this invoice doesn't contain the result -- it's just the quote)
     ///
     /// # Arguments
@@ -489,9 +589,142 @@ impl MyAgentOfferingsManager {
             .ok_or_else(|| AgentOfferingManagerError::OperationFailed("Failed to upgrade db reference".to_string()))?;
         let db_write = db;
 
+        // Keep the payment as we made it, the provider only adds the hash of the settlement transaction
+        let mut invoice = invoice.clone();
+        let mut paid_by_us = false;
+        if let Ok(local_invoice) = db_write.get_invoice(&invoice.invoice_id) {
+            if let Some(mut payment) = local_invoice.payment {
+                let settled_hash = invoice.payment.as_ref().and_then(|p| p.transaction_hash.clone());
+                payment.transaction_hash = payment.transaction_hash.or(settled_hash);
+                invoice.payment = Some(payment);
+                paid_by_us = true;
+            }
+        }
+
         db_write
-            .set_invoice(invoice)
-            .map_err(|e| AgentOfferingManagerError::OperationFailed(format!("Failed to store invoice: {:?}", e)))
+            .set_invoice(&invoice)
+            .map_err(|e| AgentOfferingManagerError::OperationFailed(format!("Failed to store invoice: {:?}", e)))?;
+
+        // A processed purchase of a subscription or credits covers the next calls, minus this one
+        if paid_by_us
+            && invoice.status == InvoiceStatusEnum::Processed
+            && invoice.hanzo_offering.usage_type.grants_entitlement()
+        {
+            db_write
+                .top_up_purchased_tool_entitlement(
+                    &self.node_name.get_node_name_string(),
+                    &invoice.hanzo_offering.tool_key,
+                    &invoice.hanzo_offering.usage_type,
+                    &invoice.invoice_id,
+                    Utc::now(),
+                )
+                .map_err(|e| {
+                    AgentOfferingManagerError::OperationFailed(format!("Failed to store tool entitlement: {:?}", e))
+                })?;
+        }
+        Ok(())
+    }
+
+    /// The entitlement bought to a tool, if it still covers a call. The provider keeps the authoritative
+    /// copy, this one only saves asking for an invoice while it lasts.
+    pub fn get_tool_entitlement(&self, tool_key: &str) -> Result<Option<ToolEntitlement>, AgentOfferingManagerError> {
+        let db = self
+            .db
+            .upgrade()
+            .ok_or_else(|| AgentOfferingManagerError::OperationFailed("Failed to upgrade db reference".to_string()))?;
+        let now = Utc::now();
+        let entitlement = db
+            .get_purchased_tool_entitlement(&self.node_name.get_node_name_string(), tool_key)
+            .map_err(AgentOfferingManagerError::from_entitlement_error)?;
+        Ok(entitlement.filter(|entitlement| entitlement.is_active(now)))
+    }
+
+    /// Takes one use of the entitlement bought to a tool, once the provider processed the call
+    pub fn consume_tool_entitlement(&self, tool_key: &str) -> Result<ToolEntitlement, AgentOfferingManagerError> {
+        let db = self
+            .db
+            .upgrade()
+            .ok_or_else(|| AgentOfferingManagerError::OperationFailed("Failed to upgrade db reference".to_string()))?;
+        db.consume_purchased_tool_entitlement(&self.node_name.get_node_name_string(), tool_key, Utc::now())
+            .map_err(AgentOfferingManagerError::from_entitlement_error)
+    }
+
+    /// Drops the entitlement bought to a tool, once the provider refused it
+    pub fn forget_tool_entitlement(&self, tool_key: &str) -> Result<(), AgentOfferingManagerError> {
+        let db = self
+            .db
+            .upgrade()
+            .ok_or_else(|| AgentOfferingManagerError::OperationFailed("Failed to upgrade db reference".to_string()))?;
+        match db.remove_purchased_tool_entitlement(&self.node_name.get_node_name_string(), tool_key) {
+            Ok(()) | Err(SqliteManagerError::ToolEntitlementNotFound(_)) => Ok(()),
+            Err(e) => Err(AgentOfferingManagerError::from_entitlement_error(e)),
+        }
+    }
+
+    /// Calls a network tool under the subscription or prepaid credits bought earlier, without an invoice
+    /// request nor a payment. The provider answers with the result, or with an InvoiceRequestNetworkError
+    /// when the entitlement ran out on its side. The use is only taken once the call is processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `network_tool` - The network tool to call.
+    /// * `tool_data` - The data related to the tool.
+    /// * `parent_message_id` - Optional parent message ID to link this call.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Invoice, AgentOfferingManagerError>` - The invoice sent to the provider or an error.
+    pub async fn send_entitled_tool_call(
+        &self,
+        network_tool: &NetworkTool,
+        tool_data: Value,
+        parent_message_id: Option<String>,
+    ) -> Result<Invoice, AgentOfferingManagerError> {
+        let tool_key = network_tool.tool_router_key();
+        let now = Utc::now();
+
+        let address = {
+            let wallet_manager = self.wallet_manager.upgrade().ok_or_else(|| {
+                AgentOfferingManagerError::OperationFailed("Failed to upgrade wallet_manager reference".to_string())
+            })?;
+            let wallet_manager_lock = wallet_manager.lock().await;
+            let wallet = wallet_manager_lock.as_ref().ok_or_else(|| {
+                AgentOfferingManagerError::OperationFailed("Failed to get wallet manager lock".to_string())
+            })?;
+            wallet.payment_wallet.get_payment_address()
+        };
+
+        let invoice = Invoice {
+            invoice_id: generate_x402_nonce(),
+            parent_message_id,
+            provider_name: network_tool.provider.clone(),
+            requester_name: self.node_name.clone(),
+            usage_type_inquiry: network_tool.usage_type.inquiry(),
+            hanzo_offering: HanzoToolOffering {
+                tool_key,
+                usage_type: network_tool.usage_type.clone(),
+                meta_description: None,
+            },
+            request_date_time: now,
+            invoice_date_time: now,
+            expiration_time: now + Duration::hours(12),
+            status: InvoiceStatusEnum::Paid,
+            payment: None,
+            address,
+            tool_data: Some(tool_data),
+            response_date_time: None,
+            result_str: None,
+        };
+
+        let db = self
+            .db
+            .upgrade()
+            .ok_or_else(|| AgentOfferingManagerError::OperationFailed("Failed to upgrade db reference".to_string()))?;
+        db.set_invoice(&invoice)
+            .map_err(|e| AgentOfferingManagerError::OperationFailed(format!("Failed to store invoice: {:?}", e)))?;
+
+        self.send_receipt_and_data_to_provider(&invoice).await?;
+        Ok(invoice)
     }
 
     pub async fn request_agent_network_offering(
@@ -559,6 +792,7 @@ impl MyAgentOfferingsManager {
     ///
     /// * `invoice_id` - The ID of the invoice to be paid.
     /// * `tool_data` - The data related to the tool.
+    /// * `approved_by_user` - Whether a user approved the payment (see `pay_invoice`).
     ///
     /// # Returns
     ///
@@ -569,6 +803,7 @@ impl MyAgentOfferingsManager {
         tool_data: Value,
         node_name: HanzoName,
         tracing_message_id: Option<String>,
+        approved_by_user: bool,
     ) -> Result<Invoice, AgentOfferingManagerError> {
         // TODO: check that the invoice is valid (exists) and still valid (not expired)
 
@@ -591,7 +826,7 @@ impl MyAgentOfferingsManager {
         }
 
         // Step 2: Pay the invoice
-        let payment = self.pay_invoice(&invoice, node_name.clone()).await?;
+        let payment = self.pay_invoice(&invoice, node_name.clone(), approved_by_user).await?;
 
         // Create a new updated invoice with the payment information
         let mut updated_invoice = invoice.clone();
@@ -675,7 +910,7 @@ impl MyAgentOfferingsManager {
         }
 
         // Step 2: Pay the invoice
-        let payment = self.pay_invoice(&invoice, node_name.clone()).await?;
+        let payment = self.pay_invoice(&invoice, node_name.clone(), false).await?;
 
         // Create a new updated invoice with the payment information
         let mut updated_invoice = invoice.clone();
@@ -780,6 +1015,14 @@ impl MyAgentOfferingsManager {
         db.set_invoice(&invoice)
             .map_err(|e| AgentOfferingManagerError::OperationFailed(format!("Failed to store invoice: {:?}", e)))?;
 
+        if let Some(ToolPrice::Payment(payments)) =
+            invoice.hanzo_offering.get_price_for_usage(&invoice.usage_type_inquiry)
+        {
+            if let Some(requirements) = payments.first() {
+                self.record_spending_decision(&invoice, requirements, SpendingDecision::Rejected, reason)?;
+            }
+        }
+
         Ok(invoice)
     }
     /// Add a network tool
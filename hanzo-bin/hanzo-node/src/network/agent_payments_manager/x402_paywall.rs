@@ -0,0 +1,311 @@
+use chrono::{DateTime, Utc};
+use hanzo_db_sqlite::{errors::SqliteManagerError, SqliteManager};
+use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
+use hanzo_messages::schemas::x402_paywall::{
+    PaymentRequiredResponse, PaywallDecision, PaywallResource, X402Receipt, X402ReceiptStatus,
+};
+use hanzo_messages::schemas::x402_types::FacilitatorConfig;
+use hanzo_runtime::functions::x402::{settle_payment, verify_payment};
+
+use super::external_agent_offerings_manager::settlement_transaction_hash;
+use crate::utils::environment::fetch_node_environment;
+
+pub const X402_VERSION: u32 = 1;
+
+/// Facilitator set with X402_FACILITATOR_URL, the public x402.org one otherwise
+pub fn facilitator_config() -> FacilitatorConfig {
+    match fetch_node_environment().x402_facilitator_url {
+        Some(url) => FacilitatorConfig { url },
+        None => FacilitatorConfig::default(),
+    }
+}
+
+/// Checks the X-PAYMENT header sent for a resource against its price. A verified payment is
+/// recorded as a receipt, and only settled once the resource was served.
+pub async fn check_payment(
+    db: &SqliteManager,
+    facilitator: FacilitatorConfig,
+    resource: &PaywallResource,
+    payment: Option<String>,
+    now: DateTime<Utc>,
+) -> Result<PaywallDecision, String> {
+    let price = match db.get_paywall_price(resource) {
+        Ok(Some(price)) => price,
+        Ok(None) => return Ok(PaywallDecision::NotPriced),
+        Err(e) => return Err(format!("Failed to get the price of {}: {}", resource, e)),
+    };
+
+    let input = verify_payment::Input {
+        price: price.price.clone(),
+        network: price.network.clone(),
+        pay_to: price.pay_to.clone(),
+        payment,
+        x402_version: X402_VERSION,
+        facilitator,
+    };
+    let output = verify_payment::verify_payment(input)
+        .await
+        .map_err(|e| format!("Failed to verify the payment for {}: {}", resource, e))?;
+
+    if let Some(invalid) = output.invalid {
+        let accepts = invalid
+            .accepts
+            .into_iter()
+            .map(|requirements| requirements.with_description(price.description.clone()))
+            .collect();
+        return Ok(PaywallDecision::PaymentRequired(PaymentRequiredResponse {
+            x402_version: invalid.x402_version,
+            error: invalid.error,
+            accepts,
+            payer: invalid.payer,
+        }));
+    }
+    let valid = output
+        .valid
+        .ok_or_else(|| "Payment verification returned no result".to_string())?;
+
+    let receipt = X402Receipt {
+        receipt_id: valid.decoded_payment.payload.authorization.nonce.clone(),
+        resource: resource.clone(),
+        payer: valid.decoded_payment.payload.authorization.from.clone(),
+        payment: valid.decoded_payment,
+        requirements: valid.selected_payment_requirements,
+        status: X402ReceiptStatus::Verified,
+        transaction: None,
+        error: None,
+        created_at: now,
+        updated_at: now,
+    };
+    match db.add_x402_receipt(&receipt) {
+        Ok(()) => Ok(PaywallDecision::Verified(receipt)),
+        Err(SqliteManagerError::X402ReceiptAlreadyExists(_)) => {
+            Ok(PaywallDecision::PaymentRequired(PaymentRequiredResponse {
+                x402_version: X402_VERSION,
+                error: "Payment already used".to_string(),
+                accepts: vec![receipt.requirements],
+                payer: Some(receipt.payer),
+            }))
+        }
+        Err(e) => Err(format!("Failed to record the payment: {}", e)),
+    }
+}
+
+/// Settles the payment of a receipt once its resource was served, returning the
+/// X-PAYMENT-RESPONSE header. A resource that could not be served is not charged.
+pub async fn settle_receipt(
+    db: &SqliteManager,
+    facilitator: FacilitatorConfig,
+    receipt_id: &str,
+    served: bool,
+    now: DateTime<Utc>,
+) -> Result<Option<String>, String> {
+    let mut receipt = db
+        .get_x402_receipt(receipt_id)
+        .map_err(|e| format!("Failed to get receipt {}: {}", receipt_id, e))?
+        .ok_or_else(|| format!("Receipt {} not found", receipt_id))?;
+    if receipt.status != X402ReceiptStatus::Verified {
+        return Err(format!("Receipt {} was already settled", receipt_id));
+    }
+
+    let result = if served {
+        let input = settle_payment::Input {
+            payment: receipt.payment.clone(),
+            accepts: vec![receipt.requirements.clone()],
+            facilitator,
+        };
+        match settle_payment::settle_payment(input).await {
+            Ok(output) => match (output.valid, output.invalid) {
+                (Some(valid), _) => Ok(valid.payment_response),
+                (None, Some(invalid)) => Err(invalid.error),
+                (None, None) => Err("Payment settlement returned no result".to_string()),
+            },
+            Err(e) => Err(e.to_string()),
+        }
+    } else {
+        Err("The resource could not be served".to_string())
+    };
+
+    receipt.updated_at = now;
+    match &result {
+        Ok(payment_response) => {
+            receipt.status = X402ReceiptStatus::Settled;
+            receipt.transaction = settlement_transaction_hash(payment_response);
+        }
+        Err(error) => {
+            receipt.status = X402ReceiptStatus::Failed;
+            receipt.error = Some(error.clone());
+            hanzo_log(
+                HanzoLogOption::Node,
+                HanzoLogLevel::Error,
+                &format!(
+                    "x402 payment {} for {} not settled: {}",
+                    receipt_id, receipt.resource, error
+                ),
+            );
+        }
+    }
+    db.update_x402_receipt(&receipt)
+        .map_err(|e| format!("Failed to update receipt {}: {}", receipt_id, e))?;
+
+    match result {
+        Ok(payment_response) => Ok(Some(payment_response)),
+        Err(_) if !served => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use hanzo_messages::schemas::x402_paywall::PaywallPrice;
+    use hanzo_messages::schemas::x402_types::{Network, Price};
+    use hanzo_runtime::functions::x402::create_payment;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    // First anvil account, never funded on a real network
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const PAY_TO: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    async fn pay(accepts: Vec<hanzo_messages::schemas::x402_types::PaymentRequirements>) -> String {
+        create_payment::create_payment(create_payment::Input {
+            accepts,
+            x402_version: X402_VERSION,
+            private_key: PRIVATE_KEY.to_string(),
+        })
+        .await
+        .unwrap()
+        .payment
+    }
+
+    #[tokio::test]
+    async fn test_paid_resource_flow() {
+        let db = setup_test_db();
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(json!({ "isValid": true }).to_string())
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_body(json!({ "success": true, "transaction": "0xabc", "network": "base-sepolia" }).to_string())
+            .create_async()
+            .await;
+        let facilitator = FacilitatorConfig { url: server.url() };
+        let resource = PaywallResource::agent_chat("my_agent");
+        let now = Utc::now();
+
+        let decision = check_payment(&db, facilitator.clone(), &resource, None, now)
+            .await
+            .unwrap();
+        assert!(matches!(decision, PaywallDecision::NotPriced));
+
+        db.set_paywall_price(&PaywallPrice {
+            resource: resource.clone(),
+            price: Price::Money(0.01),
+            network: Network::BaseSepolia,
+            pay_to: PAY_TO.to_string(),
+            description: "Chat with my agent".to_string(),
+        })
+        .unwrap();
+        let required = match check_payment(&db, facilitator.clone(), &resource, None, now)
+            .await
+            .unwrap()
+        {
+            PaywallDecision::PaymentRequired(required) => required,
+            other => panic!("unexpected decision {:?}", other),
+        };
+        assert_eq!(required.error, "No payment provided");
+        assert_eq!(required.accepts[0].max_amount_required, "10000");
+        assert_eq!(required.accepts[0].description, "Chat with my agent");
+
+        let payment = pay(required.accepts).await;
+        let receipt = match check_payment(&db, facilitator.clone(), &resource, Some(payment.clone()), now)
+            .await
+            .unwrap()
+        {
+            PaywallDecision::Verified(receipt) => receipt,
+            other => panic!("unexpected decision {:?}", other),
+        };
+
+        // A payment only pays once
+        assert!(matches!(
+            check_payment(&db, facilitator.clone(), &resource, Some(payment), now)
+                .await
+                .unwrap(),
+            PaywallDecision::PaymentRequired(_)
+        ));
+
+        let payment_response = settle_receipt(&db, facilitator.clone(), &receipt.receipt_id, true, now)
+            .await
+            .unwrap();
+        assert!(payment_response.is_some());
+        let settled = db.get_x402_receipt(&receipt.receipt_id).unwrap().unwrap();
+        assert_eq!(settled.status, X402ReceiptStatus::Settled);
+        assert_eq!(settled.transaction.as_deref(), Some("0xabc"));
+        assert!(settle_receipt(&db, facilitator, &receipt.receipt_id, true, now)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unserved_resource_is_not_settled() {
+        let db = setup_test_db();
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(json!({ "isValid": true }).to_string())
+            .create_async()
+            .await;
+        let settle = server.mock("POST", "/settle").expect(0).create_async().await;
+        let facilitator = FacilitatorConfig { url: server.url() };
+        let resource = PaywallResource::Tool("local:::__official_hanzo:::echo".to_string());
+        db.set_paywall_price(&PaywallPrice {
+            resource: resource.clone(),
+            price: Price::Money(0.01),
+            network: Network::BaseSepolia,
+            pay_to: PAY_TO.to_string(),
+            description: String::new(),
+        })
+        .unwrap();
+
+        let now = Utc::now();
+        let accepts = match check_payment(&db, facilitator.clone(), &resource, None, now)
+            .await
+            .unwrap()
+        {
+            PaywallDecision::PaymentRequired(required) => required.accepts,
+            other => panic!("unexpected decision {:?}", other),
+        };
+        let payment = pay(accepts).await;
+        let PaywallDecision::Verified(receipt) = check_payment(&db, facilitator.clone(), &resource, Some(payment), now)
+            .await
+            .unwrap()
+        else {
+            panic!("payment not verified");
+        };
+
+        let payment_response = settle_receipt(&db, facilitator, &receipt.receipt_id, false, now)
+            .await
+            .unwrap();
+        assert!(payment_response.is_none());
+        settle.assert_async().await;
+        let failed = db.get_x402_receipt(&receipt.receipt_id).unwrap().unwrap();
+        assert_eq!(failed.status, X402ReceiptStatus::Failed);
+    }
+}
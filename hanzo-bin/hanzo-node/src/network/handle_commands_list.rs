@@ -513,6 +513,12 @@ impl Node {
                     let _ = res.send(node_name.node_name).await;
                 });
             }
+            NodeCommand::GetMetrics { res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::api_get_metrics(db_clone, res).await;
+                });
+            }
             // NodeCommand::GetLastMessagesFromInboxWithBranches { inbox_name, limit, offset_key, res } =>
             // self.local_get_last_messages_from_inbox_with_branches(inbox_name, limit, offset_key, res).await,
             NodeCommand::GetLastMessagesFromInboxWithBranches {
@@ -975,6 +981,7 @@ impl Node {
                 file,
                 path,
                 file_datetime,
+                chunking_strategy,
                 res,
             } => {
                 let db_clone = Arc::clone(&self.db);
@@ -995,6 +1002,7 @@ impl Node {
                         file,
                         path,
                         file_datetime,
+                        chunking_strategy,
                         res,
                     )
                     .await;
@@ -1006,6 +1014,7 @@ impl Node {
                 filename,
                 file,
                 file_datetime,
+                chunking_strategy,
                 res,
             } => {
                 let db_clone = Arc::clone(&self.db);
@@ -1025,11 +1034,60 @@ impl Node {
                         filename,
                         file,
                         file_datetime,
+                        chunking_strategy,
                         res,
                     )
                     .await;
                 });
             }
+            NodeCommand::V2ApiSetFolderChunkingStrategy { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_set_folder_chunking_strategy(db_clone, payload, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetFolderChunkingStrategy { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_get_folder_chunking_strategy(db_clone, payload, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiAddWatchedFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let embedding_generator_ref = Arc::clone(&self.embedding_generator);
+                tokio::spawn(async move {
+                    let embedding_generator = {
+                        let generator_guard = embedding_generator_ref.lock().await;
+                        generator_guard.clone()
+                    };
+                    let _ = Node::v2_add_watched_folder(db_clone, Arc::new(embedding_generator), payload, bearer, res)
+                        .await;
+                });
+            }
+            NodeCommand::V2ApiRemoveWatchedFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_remove_watched_folder(db_clone, payload, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiListWatchedFolders { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_list_watched_folders(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSyncWatchedFolder { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let embedding_generator_ref = Arc::clone(&self.embedding_generator);
+                tokio::spawn(async move {
+                    let embedding_generator = {
+                        let generator_guard = embedding_generator_ref.lock().await;
+                        generator_guard.clone()
+                    };
+                    let _ = Node::v2_sync_watched_folder(db_clone, Arc::new(embedding_generator), payload, bearer, res)
+                        .await;
+                });
+            }
             NodeCommand::V2ApiRetrieveFile { bearer, payload, res } => {
                 let db_clone = Arc::clone(&self.db);
 
@@ -1219,12 +1277,14 @@ impl Node {
                 let embedding_generator_clone = Arc::clone(&self.embedding_generator);
                 let default_embedding_model_clone = Arc::clone(&self.default_embedding_model);
                 let is_migration_in_progress_clone = Arc::clone(&self.is_migration_in_progress);
+                let ws_manager_clone = self.ws_manager_trait.clone();
                 tokio::spawn(async move {
                     let _ = Node::v2_api_trigger_embedding_migration(
                         db_clone,
                         embedding_generator_clone,
                         default_embedding_model_clone,
                         is_migration_in_progress_clone,
+                        ws_manager_clone,
                         bearer,
                         payload,
                         res,
@@ -1240,6 +1300,30 @@ impl Node {
                         Node::v2_api_get_migration_status(db_clone, is_migration_in_progress_clone, bearer, res).await;
                 });
             }
+            NodeCommand::V2ApiGetLLMUsage { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_get_llm_usage(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetLLMModelPrices { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_get_llm_model_prices(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetLLMModelPrice { bearer, payload, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_set_llm_model_price(db_clone, bearer, payload, res).await;
+                });
+            }
+            NodeCommand::V2ApiRemoveLLMModelPrice { bearer, model, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_remove_llm_model_price(db_clone, bearer, model, res).await;
+                });
+            }
             NodeCommand::V2ApiScanOllamaModels { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
                 tokio::spawn(async move {
@@ -1277,6 +1361,24 @@ impl Node {
                     .await;
                 });
             }
+            NodeCommand::V2ApiListAllMcpPrompts { res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_list_all_mcp_prompts(db_clone, res).await;
+                });
+            }
+            NodeCommand::V2ApiListAllMcpResources { res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_list_all_mcp_resources(db_clone, res).await;
+                });
+            }
+            NodeCommand::V2ApiReadMcpResource { path, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_read_mcp_resource(db_clone, path, res).await;
+                });
+            }
             NodeCommand::V2ApiListAllNetworkHanzoTools { bearer, res } => {
                 let db_clone = Arc::clone(&self.db);
                 let tool_router_clone = self.tool_router.clone();
@@ -1660,6 +1762,99 @@ impl Node {
                     let _ = Node::v2_api_list_invoices(db_clone, bearer, res).await;
                 });
             }
+            NodeCommand::V2ApiGetWalletSpendingPolicy { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_get_wallet_spending_policy(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetWalletSpendingPolicy { bearer, policy, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_set_wallet_spending_policy(db_clone, bearer, policy, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetWalletSpendingLedger {
+                bearer,
+                invoice_id,
+                limit,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_get_wallet_spending_ledger(db_clone, bearer, invoice_id, limit, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetX402Prices { bearer, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_get_x402_prices(db_clone, bearer, res).await;
+                });
+            }
+            NodeCommand::V2ApiSetX402Price { bearer, price, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_set_x402_price(db_clone, bearer, price, res).await;
+                });
+            }
+            NodeCommand::V2ApiRemoveX402Price { bearer, resource, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_remove_x402_price(db_clone, bearer, resource, res).await;
+                });
+            }
+            NodeCommand::V2ApiGetX402Receipts {
+                bearer,
+                resource,
+                limit,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_get_x402_receipts(db_clone, bearer, resource, limit, res).await;
+                });
+            }
+            NodeCommand::V2ApiCheckX402Payment { resource, payment, res } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_check_x402_payment(db_clone, resource, payment, res).await;
+                });
+            }
+            NodeCommand::V2ApiSettleX402Payment {
+                receipt_id,
+                served,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_settle_x402_payment(db_clone, receipt_id, served, res).await;
+                });
+            }
+            NodeCommand::V2ApiX402AgentChat { agent_id, message, res } => {
+                let db_clone = Arc::clone(&self.db);
+                let node_name = self.node_name.clone();
+                let job_manager = self.job_manager.clone().unwrap();
+                let identity_manager = self.identity_manager.clone();
+                let encryption_secret_key = self.encryption_secret_key.clone();
+                let encryption_public_key = self.encryption_public_key;
+                let signing_secret_key = self.identity_secret_key.clone();
+
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_x402_agent_chat(
+                        node_name,
+                        db_clone,
+                        agent_id,
+                        message,
+                        identity_manager,
+                        job_manager,
+                        encryption_secret_key,
+                        encryption_public_key,
+                        signing_secret_key,
+                        res,
+                    )
+                    .await;
+                });
+            }
             NodeCommand::V2ApiAddCustomPrompt { bearer, prompt, res } => {
                 let db_clone = Arc::clone(&self.db);
                 tokio::spawn(async move {
@@ -1916,6 +2111,7 @@ impl Node {
                 mounts,
                 runner,
                 operating_system,
+                permissions,
                 res,
             } => {
                 let db_clone = Arc::clone(&self.db);
@@ -1944,6 +2140,7 @@ impl Node {
                         mounts,
                         runner,
                         operating_system,
+                        permissions,
                         identity_manager_clone,
                         job_manager_clone,
                         encryption_secret_key_clone,
@@ -2691,6 +2888,52 @@ impl Node {
                 });
             }
 
+            NodeCommand::V2ApiImportMCPServerPrompts {
+                bearer,
+                mcp_server_id,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_import_mcp_server_prompts(db_clone, bearer, mcp_server_id, res).await;
+                });
+            }
+
+            NodeCommand::V2ApiListMCPServerResources {
+                bearer,
+                mcp_server_id,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_list_mcp_server_resources(db_clone, bearer, mcp_server_id, res).await;
+                });
+            }
+
+            NodeCommand::V2ApiGetMCPServerSamplingPolicy {
+                bearer,
+                mcp_server_id,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ = Node::v2_api_get_mcp_server_sampling_policy(db_clone, bearer, mcp_server_id, res).await;
+                });
+            }
+
+            NodeCommand::V2ApiSetMCPServerSamplingPolicy {
+                bearer,
+                mcp_server_id,
+                policy,
+                res,
+            } => {
+                let db_clone = Arc::clone(&self.db);
+                tokio::spawn(async move {
+                    let _ =
+                        Node::v2_api_set_mcp_server_sampling_policy(db_clone, bearer, mcp_server_id, policy, res).await;
+                });
+            }
+
             NodeCommand::V2ApiGetAllMCPServerTools {
                 bearer,
                 mcp_server_id,
@@ -5,10 +5,12 @@ use crate::utils::github_mcp::{
     GitHubMcpError, GitHubRepo,
 };
 use reqwest::Client;
-use rmcp::model::Tool;
+use rmcp::model::{GetPromptResult, Prompt, PromptMessageContent, ReadResourceResult, Resource, ResourceContents, Tool};
 use serde_json::Value;
 use hanzo_http_api::api_v2::api_v2_handlers_mcp_servers::AddMCPServerRequest;
-use hanzo_messages::schemas::mcp_server::MCPServerType;
+use hanzo_mcp::error::McpError;
+use hanzo_mcp::session_manager::MCP_SESSION_MANAGER;
+use hanzo_messages::schemas::mcp_server::{MCPServer, MCPServerType};
 use hanzo_tools::tools::{
     mcp_server_tool::MCPServerTool,
     parameters::{Parameters, Property},
@@ -102,6 +104,76 @@ pub fn convert_to_hanzo_tool(
     HanzoTool::MCPServer(mcp_tool, true)
 }
 
+/// Lists the tools of an MCP server through the shared session manager, so the session
+/// opened here is reused by later tool calls instead of spawning the server again.
+pub async fn list_mcp_server_tools(server: &MCPServer) -> Result<Vec<Tool>, McpError> {
+    let target = MCPServerTool::session_target(server);
+    MCP_SESSION_MANAGER.list_tools(&server.get_command_hash(), &target).await
+}
+
+pub async fn list_mcp_server_prompts(server: &MCPServer) -> Result<Vec<Prompt>, McpError> {
+    let target = MCPServerTool::session_target(server);
+    MCP_SESSION_MANAGER.list_prompts(&server.get_command_hash(), &target).await
+}
+
+pub async fn get_mcp_server_prompt(
+    server: &MCPServer,
+    name: String,
+    arguments: Option<serde_json::Map<String, Value>>,
+) -> Result<GetPromptResult, McpError> {
+    let target = MCPServerTool::session_target(server);
+    MCP_SESSION_MANAGER
+        .get_prompt(&server.get_command_hash(), &target, name, arguments)
+        .await
+}
+
+pub async fn list_mcp_server_resources(server: &MCPServer) -> Result<Vec<Resource>, McpError> {
+    let target = MCPServerTool::session_target(server);
+    MCP_SESSION_MANAGER.list_resources(&server.get_command_hash(), &target).await
+}
+
+pub async fn read_mcp_server_resource(server: &MCPServer, uri: String) -> Result<ReadResourceResult, McpError> {
+    let target = MCPServerTool::session_target(server);
+    MCP_SESSION_MANAGER
+        .read_resource(&server.get_command_hash(), &target, uri)
+        .await
+}
+
+/// Fetches a remote prompt as a template for the prompt library. Its arguments are filled with
+/// `{{argument}}` placeholders, the same syntax the node uses when publishing prompts over MCP.
+pub async fn fetch_mcp_prompt_template(server: &MCPServer, prompt: &Prompt) -> Result<String, McpError> {
+    let arguments = prompt.arguments.as_ref().map(|arguments| {
+        arguments
+            .iter()
+            .map(|argument| (argument.name.clone(), Value::String(format!("{{{{{}}}}}", argument.name))))
+            .collect::<serde_json::Map<String, Value>>()
+    });
+    let result = get_mcp_server_prompt(server, prompt.name.clone(), arguments).await?;
+    Ok(result
+        .messages
+        .iter()
+        .filter_map(|message| match &message.content {
+            PromptMessageContent::Text { text } => Some(text.clone()),
+            PromptMessageContent::Resource { resource } => resource_contents_text(&resource.resource),
+            _ => None,
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n"))
+}
+
+/// Text of a resource, binary contents are skipped.
+pub fn resource_contents_text(contents: &ResourceContents) -> Option<String> {
+    match contents {
+        ResourceContents::TextResourceContents { text, .. } => Some(text.clone()),
+        ResourceContents::BlobResourceContents { .. } => None,
+    }
+}
+
+/// Shuts down the pooled session of an MCP server, e.g. after it was updated, disabled or deleted.
+pub async fn close_mcp_server_session(server: &MCPServer) {
+    MCP_SESSION_MANAGER.close(&server.get_command_hash()).await;
+}
+
 fn sanitize_mcp_server_name(name: String) -> String {
     name.replace("@", "")
         .trim()
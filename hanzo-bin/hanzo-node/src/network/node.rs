@@ -6,7 +6,9 @@ use super::network_manager::libp2p_message_handler::HanzoMessageHandler;
 use super::node_error::NodeError;
 use super::ws_manager::WebSocketManager;
 use crate::cron_tasks::cron_manager::CronManager;
+use crate::managers::folder_sync_manager::FolderSyncManager;
 use crate::llm_provider::job_callback_manager::JobCallbackManager;
+use crate::llm_provider::execution::mcp_sampling::McpJobSamplingHandler;
 use crate::llm_provider::job_manager::JobManager;
 use crate::llm_provider::llm_stopper::LLMStopper;
 use crate::managers::identity_manager::IdentityManagerTrait;
@@ -16,6 +18,7 @@ use crate::managers::IdentityManager;
 use crate::network::libp2p_manager::verifying_key_to_peer_id;
 use crate::network::ws_routes::run_ws_api;
 use crate::wallet::coinbase_mpc_wallet::CoinbaseMPCWallet;
+use crate::wallet::payment_reconciler::PaymentReconciler;
 use crate::wallet::wallet_manager::WalletManager;
 use async_channel::Receiver;
 use base64::Engine;
@@ -30,6 +33,8 @@ use hanzo_embed::embedding_generator::RemoteEmbeddingGenerator;
 use hanzo_embed::model_type::EmbeddingModelType;
 use hanzo_http_api::node_api_router::APIError;
 use hanzo_http_api::node_commands::NodeCommand;
+use hanzo_mcp::session_manager::MCP_SESSION_MANAGER;
+use hanzo_messages::schemas::embedding_migration::EmbeddingMigrationStatus;
 use hanzo_messages::schemas::llm_providers::serialized_llm_provider::SerializedLLMProvider;
 
 use hanzo_messages::schemas::hanzo_name::HanzoName;
@@ -424,6 +429,12 @@ impl Node {
         };
         self.job_manager = Some(job_manager.clone());
 
+        // MCP servers may ask to sample from an LLM while running a tool, answered per their sampling policy
+        MCP_SESSION_MANAGER.set_sampling_handler(Arc::new(McpJobSamplingHandler::new(
+            Arc::downgrade(&self.db),
+            self.llm_stopper.clone(),
+        )));
+
         hanzo_log(
             HanzoLogOption::Node,
             HanzoLogLevel::Info,
@@ -455,6 +466,19 @@ impl Node {
         // Initialize embedding models
         self.initialize_embedding_models().await?;
 
+        // Pick up an embedding migration interrupted by a restart
+        self.resume_embedding_migration().await;
+
+        // Keep the watched folders mirrored into the VecFS
+        FolderSyncManager::start(db_weak.clone(), self.embedding_generator.clone());
+
+        // Follow the settlement transactions of the payments made and received
+        PaymentReconciler::start(
+            db_weak.clone(),
+            Arc::downgrade(&self.wallet_manager),
+            self.node_name.clone(),
+        );
+
         {
             // Starting the WebSocket server
             if let (Some(ws_manager), Some(ws_address)) = (&self.ws_manager, self.ws_address) {
@@ -802,6 +826,44 @@ impl Node {
         Ok(())
     }
 
+    // Resumes the last embedding migration if the node stopped while it was running
+    async fn resume_embedding_migration(&self) {
+        let progress = match self.db.get_embedding_migration_progress() {
+            Ok(Some(progress)) if progress.status == EmbeddingMigrationStatus::Running => progress,
+            _ => return,
+        };
+        let target_model = match EmbeddingModelType::from_string(&progress.target_model) {
+            Ok(model) => model,
+            Err(_) => return,
+        };
+
+        hanzo_log(
+            HanzoLogOption::Node,
+            HanzoLogLevel::Info,
+            &format!("Resuming embedding migration to {}", target_model),
+        );
+
+        let embedding_generator = self.embedding_generator.lock().await.clone();
+        if let Err(e) = Self::internal_trigger_embedding_migration(
+            self.db.clone(),
+            embedding_generator,
+            target_model,
+            false,
+            self.is_migration_in_progress.clone(),
+            false,
+            Some((self.embedding_generator.clone(), self.default_embedding_model.clone())),
+            self.ws_manager_trait.clone(),
+        )
+        .await
+        {
+            hanzo_log(
+                HanzoLogOption::Node,
+                HanzoLogLevel::Error,
+                &format!("Failed to resume embedding migration: {}", e),
+            );
+        }
+    }
+
     // Static function to get the address from a HanzoName identity
     async fn get_address_from_identity(
         identity_manager: Arc<Mutex<IdentityManager>>,
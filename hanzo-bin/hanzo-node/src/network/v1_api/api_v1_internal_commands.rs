@@ -459,6 +459,7 @@ impl Node {
                                 vector_fs_items: vec![],
                                 vector_fs_folders: vec![hanzo_folder_fs],
                                 vector_search_mode: VectorSearchMode::FillUpTo25k,
+                                mcp_resources: vec![],
                             };
                             let job_creation = JobCreationInfo {
                                 scope: job_scope,
@@ -1,6 +1,7 @@
 use crate::llm_provider::providers::hanzo_backend::check_quota;
 use crate::managers::galxe_quests::{compute_quests, generate_proof};
 use crate::managers::tool_router::ToolRouter;
+use crate::monitoring::{update_db_pool_metrics, MetricsCollector};
 use crate::network::node_shareable_logic::download_zip_from_url;
 use crate::network::zip_export_import::zip_export_import::{
     generate_agent_zip, get_agent_from_zip, import_agent, import_dependencies_tools,
@@ -26,13 +27,15 @@ use hanzo_http_api::api_v2::api_v2_handlers_mcp_servers::{
 };
 use hanzo_http_api::node_api_router::APIUseRegistrationCodeSuccessResponse;
 use hanzo_http_api::{
+    api_sse::notify_mcp_prompt_list_changed,
     api_v2::api_v2_handlers_general::InitialRegistrationRequest,
     node_api_router::{APIError, GetPublicKeysResponse},
     node_commands::EmbeddingMigrationRequest,
 };
-use hanzo_mcp::mcp_methods::{list_tools_via_command, list_tools_via_http, list_tools_via_sse};
 use hanzo_messages::schemas::llm_providers::hanzo_backend::QuotaResponse;
-use hanzo_messages::schemas::mcp_server::{MCPServer, MCPServerType};
+use hanzo_messages::schemas::custom_prompt::CustomPrompt;
+use hanzo_messages::schemas::llm_usage::{LLMModelPrice, LLMUsageQuery, LLMUsageSummary};
+use hanzo_messages::schemas::mcp_server::{MCPSamplingPolicy, MCPServer, MCPServerType};
 use hanzo_messages::schemas::hanzo_preferences::HanzoInternalComms;
 use hanzo_messages::{
     schemas::ws_types::WSUpdateHandler,
@@ -57,6 +60,7 @@ use hanzo_messages::{
     },
     hanzo_utils::{job_scope::MinimalJobScope, hanzo_time::HanzoStringTime},
 };
+use hanzo_db_sqlite::errors::SqliteManagerError;
 use hanzo_db_sqlite::regex_pattern_manager::RegexPattern;
 use hanzo_db_sqlite::SqliteManager;
 use hanzo_tools::tools::mcp_server_tool::MCPServerTool;
@@ -830,11 +834,38 @@ impl Node {
         Ok(())
     }
 
+    pub async fn api_get_metrics(
+        db: Arc<SqliteManager>,
+        res: Sender<Result<String, APIError>>,
+    ) -> Result<(), NodeError> {
+        // The pool gauges are sampled at scrape time
+        let (main_pool, fts_pool) = db.pool_states();
+        for (pool_type, state) in [("main", main_pool), ("fts", fts_pool)] {
+            let active = state.connections.saturating_sub(state.idle_connections);
+            update_db_pool_metrics(pool_type, state.connections as usize, active as usize);
+        }
+
+        let metrics = MetricsCollector::export_metrics().await.map_err(|e| e.to_string());
+        let _ = match metrics {
+            Ok(metrics) => res.send(Ok(metrics)).await,
+            Err(e) => {
+                res.send(Err(APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to export metrics: {}", e),
+                }))
+                .await
+            }
+        };
+        Ok(())
+    }
+
     pub async fn v2_api_trigger_embedding_migration(
         db: Arc<SqliteManager>,
         node_embedding_generator: Arc<Mutex<RemoteEmbeddingGenerator>>,
         node_default_embedding_model: Arc<Mutex<EmbeddingModelType>>,
         is_migration_in_progress: Arc<AtomicBool>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
         bearer: String,
         payload: EmbeddingMigrationRequest,
         res: Sender<Result<serde_json::Value, APIError>>,
@@ -889,6 +920,7 @@ impl Node {
                 Arc::clone(&node_embedding_generator),
                 Arc::clone(&node_default_embedding_model),
             )), // Pass node references for update
+            ws_manager,
         )
         .await
         {
@@ -930,7 +962,9 @@ impl Node {
     }
 
     /// Internal helper function for triggering embedding migrations
-    /// Used by both startup migration and API endpoint
+    /// Used by both startup migration and API endpoint.
+    /// The migration checkpoints every item, so a migration interrupted by a restart is resumed
+    /// by triggering it again with the same target model.
     pub async fn internal_trigger_embedding_migration(
         db: Arc<SqliteManager>,
         embedding_generator: RemoteEmbeddingGenerator,
@@ -939,6 +973,7 @@ impl Node {
         is_migration_in_progress: Arc<AtomicBool>,
         check_ollama_availability: bool,
         node_state_refs: Option<(Arc<Mutex<RemoteEmbeddingGenerator>>, Arc<Mutex<EmbeddingModelType>>)>,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
     ) -> Result<(), String> {
         // Check if model is available in Ollama (if requested)
         if check_ollama_availability {
@@ -973,8 +1008,15 @@ impl Node {
 
         // Spawn migration task
         tokio::spawn(async move {
+            // The node's live embedding generator and default model are switched together with the tables
             match db_clone
-                .migrate_embeddings_to_new_model(&target_embedding_generator, &target_model_clone, force)
+                .migrate_embeddings_to_new_model(
+                    &target_embedding_generator,
+                    &target_model_clone,
+                    force,
+                    ws_manager,
+                    node_state_refs,
+                )
                 .await
             {
                 Ok(_) => {
@@ -983,27 +1025,6 @@ impl Node {
                         HanzoLogLevel::Info,
                         &format!("Embedding migration to {} completed successfully", target_model_clone),
                     );
-
-                    // Update node's live state after successful migration
-                    if let Some((node_embedding_generator_ref, node_default_model_ref)) = node_state_refs {
-                        // Update the node's embedding generator to use the new model
-                        {
-                            let mut generator_guard = node_embedding_generator_ref.lock().await;
-                            generator_guard.set_model_type(target_model_clone.clone());
-                        }
-
-                        // Update the node's default embedding model
-                        {
-                            let mut model_guard = node_default_model_ref.lock().await;
-                            *model_guard = target_model_clone.clone();
-                        }
-
-                        hanzo_log(
-                            HanzoLogOption::Node,
-                            HanzoLogLevel::Info,
-                            "Updated node's live embedding generator and default model to match migration",
-                        );
-                    }
                 }
                 Err(e) => {
                     hanzo_log(
@@ -1038,18 +1059,131 @@ impl Node {
             .get_default_embedding_model()
             .unwrap_or_else(|_| EmbeddingModelType::default());
 
+        // Per-table progress of the last migration, if any
+        let progress = db.get_embedding_migration_progress().unwrap_or_default();
+
         let _ = res
             .send(Ok(serde_json::json!({
                 "migration_in_progress": is_migrating,
                 "ready": !is_migrating,
                 "current_embedding_model": current_model.to_string(),
-                "status": if is_migrating { "migrating" } else { "ready" }
+                "status": if is_migrating { "migrating" } else { "ready" },
+                "progress": progress
             })))
             .await;
 
         Ok(())
     }
 
+    pub async fn v2_api_get_llm_usage(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        payload: LLMUsageQuery,
+        res: Sender<Result<Vec<LLMUsageSummary>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.get_llm_usage_summary(&payload) {
+            Ok(summaries) => {
+                let _ = res.send(Ok(summaries)).await;
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get LLM usage: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn v2_api_get_llm_model_prices(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        res: Sender<Result<Vec<LLMModelPrice>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.get_all_llm_model_prices() {
+            Ok(prices) => {
+                let _ = res.send(Ok(prices)).await;
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get model prices: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn v2_api_set_llm_model_price(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        payload: LLMModelPrice,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = match db.set_llm_model_price(&payload) {
+            Ok(()) => Ok(()),
+            Err(SqliteManagerError::ValidationError(message)) => Err(APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error: "Bad Request".to_string(),
+                message,
+            }),
+            Err(e) => Err(APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to set model price: {}", e),
+            }),
+        };
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_remove_llm_model_price(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        model: String,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = match db.remove_llm_model_price(&model) {
+            Ok(()) => Ok(()),
+            Err(SqliteManagerError::DataNotFound) => Err(APIError {
+                code: StatusCode::NOT_FOUND.as_u16(),
+                error: "Not Found".to_string(),
+                message: format!("No price set for model: {}", model),
+            }),
+            Err(e) => Err(APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to remove model price: {}", e),
+            }),
+        };
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
     pub async fn v2_api_scan_ollama_models(
         db: Arc<SqliteManager>,
         bearer: String,
@@ -2712,7 +2846,7 @@ impl Node {
                             command_str
                         );
 
-                        log::info!("Attempting to list tools for command: '{}' (Note: this runs the command separately for listing tools)", command_str);
+                        log::info!("Attempting to list tools for command: '{}'", command_str);
                         let mut tools_config: Vec<ToolConfig> = vec![];
                         // Iterate over server config and add each key-value pair as a BasicConfig
                         if let Some(env) = &server.env {
@@ -2726,7 +2860,7 @@ impl Node {
                                 }));
                             }
                         }
-                        match list_tools_via_command(command_str, server.env.clone()).await {
+                        match mcp_manager::list_mcp_server_tools(&server).await {
                             Ok(tools) => {
                                 for tool in tools {
                                     // Use the new function from mcp_manager instead of inline conversion
@@ -2747,7 +2881,7 @@ impl Node {
                             }
                             Err(e) => {
                                 log::error!(
-                                    "Failed to list tools for command '{}' via list_tools_via_command: {:?}",
+                                    "Failed to list tools for command '{}' via the MCP session manager: {:?}",
                                     command_str,
                                     e
                                 );
@@ -2764,7 +2898,7 @@ impl Node {
                     }
                 } else if server.r#type == MCPServerType::Sse && server.is_enabled {
                     if let Some(url) = &server.url {
-                        match list_tools_via_sse(url, None).await {
+                        match mcp_manager::list_mcp_server_tools(&server).await {
                             Ok(tools) => {
                                 for tool in tools {
                                     // Use the new function from mcp_manager instead of inline conversion
@@ -2784,7 +2918,7 @@ impl Node {
                                 }
                             }
                             Err(e) => {
-                                log::error!("Failed to list tools for sse '{}' via list_tools_via_sse: {:?}", url, e);
+                                log::error!("Failed to list tools for sse '{}' via the MCP session manager: {:?}", url, e);
                             }
                         }
                     } else {
@@ -2792,7 +2926,7 @@ impl Node {
                     }
                 } else if server.r#type == MCPServerType::Http && server.is_enabled {
                     if let Some(url) = &server.url {
-                        match list_tools_via_http(url, None).await {
+                        match mcp_manager::list_mcp_server_tools(&server).await {
                             Ok(tools) => {
                                 for tool in tools {
                                     let server_id = server.id.as_ref().expect("Server ID should exist").to_string();
@@ -2812,7 +2946,7 @@ impl Node {
                             }
                             Err(e) => {
                                 log::error!(
-                                    "Failed to list tools for http '{}' via list_tools_via_http: {:?}",
+                                    "Failed to list tools for http '{}' via the MCP session manager: {:?}",
                                     url,
                                     e
                                 );
@@ -2936,6 +3070,10 @@ impl Node {
                 .await;
             return Ok(());
         }
+        // The command, url or env may change, so the pooled session must not outlive the old config
+        if let Some(previous_mcp_server) = &mcp_server_found {
+            mcp_manager::close_mcp_server_session(previous_mcp_server).await;
+        }
         let updated_mcp_server = db.update_mcp_server(
             mcp_server.id,
             mcp_server.name.clone().unwrap_or(mcp_server_found.unwrap().name),
@@ -2980,7 +3118,7 @@ impl Node {
                 match updated_mcp_server.r#type {
                     MCPServerType::Command => {
                         if let Some(cmd) = &mcp_server.command {
-                            log::info!("Attempting to list tools for command: '{}' (Note: this runs the command separately for listing tools)", cmd);
+                            log::info!("Attempting to list tools for command: '{}'", cmd);
                             let mut tools_config: Vec<ToolConfig> = vec![];
                             // Iterate over server config and add each key-value pair as a BasicConfig
                             if let Some(env) = &mcp_server.env {
@@ -2994,7 +3132,7 @@ impl Node {
                                     }));
                                 }
                             }
-                            match list_tools_via_command(cmd, mcp_server.env.clone()).await {
+                            match mcp_manager::list_mcp_server_tools(&updated_mcp_server).await {
                                 Ok(tools) => {
                                     for tool in tools {
                                         let hanzo_tool = mcp_manager::convert_to_hanzo_tool(
@@ -3012,7 +3150,7 @@ impl Node {
                                 }
                                 Err(e) => {
                                     log::error!(
-                                        "Failed to list tools for command '{}' via list_tools_via_command: {:?}",
+                                        "Failed to list tools for command '{}' via the MCP session manager: {:?}",
                                         cmd,
                                         e
                                     );
@@ -3022,7 +3160,7 @@ impl Node {
                     }
                     MCPServerType::Sse => {
                         if let Some(url) = &mcp_server.url {
-                            match list_tools_via_sse(url, None).await {
+                            match mcp_manager::list_mcp_server_tools(&updated_mcp_server).await {
                                 Ok(tools) => {
                                     for tool in tools {
                                         let hanzo_tool = mcp_manager::convert_to_hanzo_tool(
@@ -3040,7 +3178,7 @@ impl Node {
                                 }
                                 Err(e) => {
                                     log::error!(
-                                        "Failed to list tools for sse '{}' via list_tools_via_sse: {:?}",
+                                        "Failed to list tools for sse '{}' via the MCP session manager: {:?}",
                                         url,
                                         e
                                     );
@@ -3050,7 +3188,7 @@ impl Node {
                     }
                     MCPServerType::Http => {
                         if let Some(url) = &mcp_server.url {
-                            match list_tools_via_http(url, None).await {
+                            match mcp_manager::list_mcp_server_tools(&updated_mcp_server).await {
                                 Ok(tools) => {
                                     for tool in tools {
                                         let hanzo_tool = mcp_manager::convert_to_hanzo_tool(
@@ -3068,7 +3206,7 @@ impl Node {
                                 }
                                 Err(e) => {
                                     log::error!(
-                                        "Failed to list tools for http '{}' via list_tools_via_http: {:?}",
+                                        "Failed to list tools for http '{}' via the MCP session manager: {:?}",
                                         url,
                                         e
                                     );
@@ -3179,6 +3317,9 @@ impl Node {
             return Ok(());
         }
         let _ = db.delete_mcp_server(mcp_server_id);
+        if let Some(deleted_mcp_server) = &mcp_server {
+            mcp_manager::close_mcp_server_session(deleted_mcp_server).await;
+        }
         let rows_deleted_result =
             db.delete_all_tools_from_mcp_server(mcp_server.clone().unwrap().id.unwrap_or_default().to_string());
 
@@ -3231,6 +3372,9 @@ impl Node {
         // Update the MCP server's enabled status
         match db.update_mcp_server_enabled_status(mcp_server_id, is_enabled) {
             Ok(updated_server) => {
+                if !is_enabled {
+                    mcp_manager::close_mcp_server_session(&updated_server).await;
+                }
                 let _ = res.send(Ok(updated_server)).await;
             }
             Err(err) => {
@@ -3245,6 +3389,199 @@ impl Node {
         Ok(())
     }
 
+    pub async fn v2_api_import_mcp_server_prompts(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        mcp_server_id: i64,
+        res: Sender<Result<Vec<CustomPrompt>, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        let mcp_server = match db.get_mcp_server(mcp_server_id)? {
+            Some(mcp_server) => mcp_server,
+            None => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::BAD_REQUEST.as_u16(),
+                        error: "Invalid MCP Server ID".to_string(),
+                        message: format!("No MCP Server found with ID: {}", mcp_server_id),
+                    }))
+                    .await;
+                return Ok(());
+            }
+        };
+
+        let prompts = match mcp_manager::list_mcp_server_prompts(&mcp_server).await {
+            Ok(prompts) => prompts,
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error: "Failed to list MCP server prompts".to_string(),
+                        message: format!("Error listing prompts of MCP server {}: {}", mcp_server.name, err),
+                    }))
+                    .await;
+                return Ok(());
+            }
+        };
+
+        // Prompts are saved as "<server> - <prompt>" so importing again updates them in place
+        let mut imported = Vec::new();
+        for mcp_prompt in prompts {
+            let template = match mcp_manager::fetch_mcp_prompt_template(&mcp_server, &mcp_prompt).await {
+                Ok(template) => template,
+                Err(err) => {
+                    hanzo_log(
+                        HanzoLogOption::Node,
+                        HanzoLogLevel::Error,
+                        &format!("Skipping MCP prompt {} of {}: {}", mcp_prompt.name, mcp_server.name, err),
+                    );
+                    continue;
+                }
+            };
+            let name = format!("{} - {}", mcp_server.name, mcp_prompt.name);
+            let existing = db.get_prompts(Some(&name), None, None).unwrap_or_default().into_iter().next();
+            let mut prompt = CustomPrompt {
+                rowid: None,
+                name,
+                prompt: template,
+                is_system: false,
+                is_enabled: true,
+                version: "1".to_string(),
+                is_favorite: false,
+            };
+            let prompt_name = prompt.name.clone();
+            let result = match existing {
+                Some(existing) => {
+                    prompt.rowid = existing.rowid;
+                    prompt.is_enabled = existing.is_enabled;
+                    prompt.is_favorite = existing.is_favorite;
+                    db.update_prompt(&prompt).await.map(|_| prompt)
+                }
+                None => db.add_prompt(&prompt).await,
+            };
+            match result {
+                Ok(prompt) => imported.push(prompt),
+                Err(err) => {
+                    let _ = res
+                        .send(Err(APIError {
+                            code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                            error: "Failed to import MCP server prompts".to_string(),
+                            message: format!("Error saving prompt {}: {}", prompt_name, err),
+                        }))
+                        .await;
+                    return Ok(());
+                }
+            }
+        }
+
+        notify_mcp_prompt_list_changed();
+        let _ = res.send(Ok(imported)).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_list_mcp_server_resources(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        mcp_server_id: i64,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        let mcp_server = match db.get_mcp_server(mcp_server_id)? {
+            Some(mcp_server) => mcp_server,
+            None => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::BAD_REQUEST.as_u16(),
+                        error: "Invalid MCP Server ID".to_string(),
+                        message: format!("No MCP Server found with ID: {}", mcp_server_id),
+                    }))
+                    .await;
+                return Ok(());
+            }
+        };
+
+        match mcp_manager::list_mcp_server_resources(&mcp_server).await {
+            Ok(resources) => {
+                let _ = res.send(Ok(json!(resources))).await;
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error: "Failed to list MCP server resources".to_string(),
+                        message: format!("Error listing resources of MCP server {}: {}", mcp_server.name, err),
+                    }))
+                    .await;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn v2_api_get_mcp_server_sampling_policy(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        mcp_server_id: i64,
+        res: Sender<Result<MCPSamplingPolicy, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        if db.get_mcp_server(mcp_server_id)?.is_none() {
+            let _ = res
+                .send(Err(APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error: "Invalid MCP Server ID".to_string(),
+                    message: format!("No MCP Server found with ID: {}", mcp_server_id),
+                }))
+                .await;
+            return Ok(());
+        }
+        let policy = db.get_mcp_server_sampling_policy(mcp_server_id)?;
+        let _ = res.send(Ok(policy)).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_set_mcp_server_sampling_policy(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        mcp_server_id: i64,
+        policy: MCPSamplingPolicy,
+        res: Sender<Result<MCPSamplingPolicy, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+        if db.get_mcp_server(mcp_server_id)?.is_none() {
+            let _ = res
+                .send(Err(APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error: "Invalid MCP Server ID".to_string(),
+                    message: format!("No MCP Server found with ID: {}", mcp_server_id),
+                }))
+                .await;
+            return Ok(());
+        }
+        match db.set_mcp_server_sampling_policy(mcp_server_id, &policy) {
+            Ok(_) => {
+                let _ = res.send(Ok(policy)).await;
+            }
+            Err(err) => {
+                let _ = res
+                    .send(Err(APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error: "Failed to update MCP server sampling policy".to_string(),
+                        message: format!("Error updating MCP server ID {}: {}", mcp_server_id, err),
+                    }))
+                    .await;
+            }
+        }
+        Ok(())
+    }
+
     pub async fn v2_api_docker_status(res: Sender<Result<serde_json::Value, APIError>>) -> Result<(), NodeError> {
         let docker_status = match hanzo_tools_runner::tools::container_utils::is_docker_available() {
             hanzo_tools_runner::tools::container_utils::DockerStatus::NotInstalled => "not-installed",
@@ -833,6 +833,8 @@ impl Node {
                     thinking: None,
                     reasoning_effort: None,
                     web_search_enabled: None,
+                    keyword_search_weight: None,
+                    budget: None,
                 });
                 let _ = res.send(Ok(config)).await;
                 Ok(())
@@ -1327,6 +1329,11 @@ impl Node {
 
         match db.get_traces_by_parent_message_id(&message_id) {
             Ok(traces) => {
+                // OpenTelemetry trace the message was processed in, None when OTLP export is disabled
+                let trace_id = traces
+                    .iter()
+                    .find(|t| t.trace_name == "otel_trace")
+                    .and_then(|t| t.trace_info.get("trace_id").cloned());
                 let traces_json: Vec<Value> = traces
                     .into_iter()
                     .map(|t| {
@@ -1337,6 +1344,7 @@ impl Node {
                             "datetime": t.datetime,
                             "trace_name": t.trace_name,
                             "trace_info": t.trace_info,
+                            "trace_id": trace_id,
                         })
                     })
                     .collect();
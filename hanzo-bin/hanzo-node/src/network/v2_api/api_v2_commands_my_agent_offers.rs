@@ -213,7 +213,7 @@ impl Node {
         let payment = match my_agent_offerings_manager
             .lock()
             .await
-            .pay_invoice_and_send_receipt(invoice_id, data_for_tool, node_name.clone(), None)
+            .pay_invoice_and_send_receipt(invoice_id, data_for_tool, node_name.clone(), None, true)
             .await
         {
             Ok(payment) => payment,
@@ -3,7 +3,7 @@ use std::{sync::Arc, time::Instant};
 use async_channel::Sender;
 use reqwest::StatusCode;
 
-use hanzo_http_api::node_api_router::APIError;
+use hanzo_http_api::{api_sse::notify_mcp_prompt_list_changed, node_api_router::APIError};
 use hanzo_messages::schemas::custom_prompt::CustomPrompt;
 use hanzo_db_sqlite::SqliteManager;
 
@@ -24,6 +24,7 @@ impl Node {
         // Save the new prompt to the LanceHanzoDb
         match db.add_prompt(&prompt).await {
             Ok(_) => {
+                notify_mcp_prompt_list_changed();
                 let _ = res.send(Ok(prompt)).await;
                 Ok(())
             }
@@ -60,6 +61,7 @@ impl Node {
                 // Delete the prompt from the LanceHanzoDb
                 match db.remove_prompt(&prompt_name) {
                     Ok(_) => {
+                        notify_mcp_prompt_list_changed();
                         let _ = res.send(Ok(prompt)).await;
                         Ok(())
                     }
@@ -123,6 +125,32 @@ impl Node {
         }
     }
 
+    pub async fn v2_api_list_all_mcp_prompts(
+        db: Arc<SqliteManager>,
+        res: Sender<Result<Vec<CustomPrompt>, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Internal MCP server command: only requires that the node has been set up
+        let _bearer = Self::get_bearer_token(db.clone(), &res).await?;
+
+        // Only enabled prompts are published to MCP clients
+        match db.get_all_prompts() {
+            Ok(prompts) => {
+                let prompts = prompts.into_iter().filter(|p| p.is_enabled).collect();
+                let _ = res.send(Ok(prompts)).await;
+                Ok(())
+            }
+            Err(err) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get all custom prompts from SqliteManager: {}", err),
+                };
+                let _ = res.send(Err(api_error)).await;
+                Ok(())
+            }
+        }
+    }
+
     pub async fn v2_api_get_custom_prompt(
         db: Arc<SqliteManager>,
         bearer: String,
@@ -224,6 +252,7 @@ impl Node {
         // Update the prompt in the LanceHanzoDb
         match db.update_prompt(&prompt).await {
             Ok(_) => {
+                notify_mcp_prompt_list_changed();
                 let _ = res.send(Ok(prompt)).await;
                 Ok(())
             }
@@ -53,6 +53,7 @@ use hanzo_tools::tools::{
     hanzo_tool::{HanzoTool, HanzoToolWithAssets},
     tool_config::{OAuth, ToolConfig},
     tool_output_arg::ToolOutputArg,
+    tool_permissions::ToolPermissions,
     tool_playground::{ToolPlayground, ToolPlaygroundMetadata},
     tool_types::{OperatingSystem, RunnerType, ToolResult},
 };
@@ -1184,7 +1185,16 @@ impl Node {
                     runner: payload.metadata.runner,
                     operating_system: payload.metadata.operating_system,
                     tool_set: payload.metadata.tool_set,
+                    permissions: payload.metadata.permissions.clone(),
                 };
+                node_env
+                    .tool_permissions_policy
+                    .check(tool.permissions.as_ref())
+                    .map_err(|e| APIError {
+                        code: StatusCode::FORBIDDEN.as_u16(),
+                        error: "Permissions Not Allowed".to_string(),
+                        message: e.to_string(),
+                    })?;
                 HanzoTool::Deno(tool, false)
             }
             CodeLanguage::Python => {
@@ -1645,6 +1655,7 @@ impl Node {
         mounts: Option<Vec<String>>,
         runner: Option<RunnerType>,
         operating_system: Option<Vec<OperatingSystem>>,
+        permissions: Option<ToolPermissions>,
         identity_manager_clone: Arc<Mutex<IdentityManager>>,
         job_manager_clone: Arc<Mutex<JobManager>>,
         encryption_secret_key_clone: EncryptionStaticKey,
@@ -1680,6 +1691,7 @@ impl Node {
             mounts,
             runner,
             operating_system,
+            permissions,
             identity_manager_clone,
             job_manager_clone,
             encryption_secret_key_clone,
@@ -3289,6 +3301,14 @@ impl Node {
             error: "Not Found".to_string(),
             message: format!("Tool not found: {}", tool_key_path),
         })?;
+        // WASM tools are shipped compiled, they have no code to fork into a playground
+        if let HanzoTool::Wasm(_, _) = original_tool {
+            return Err(APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error: "Bad Request".to_string(),
+                message: format!("WASM tool {} can't be duplicated", tool_key_path),
+            });
+        }
 
         let llm_providers = db.get_all_llm_providers().map_err(|_| APIError {
             code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
@@ -3415,6 +3435,7 @@ impl Node {
                         runner: new_tool.get_runner(),
                         operating_system: new_tool.get_operating_system(),
                         tool_set: new_tool.get_tool_set(),
+                        permissions: new_tool.get_permissions(),
                     },
                     tool_router_key: Some(new_tool.tool_router_key().to_string_without_version()),
                     job_id: Self::create_job_for_duplicate_tool(
@@ -11,13 +11,17 @@ use hanzo_fs::{
     hanzo_file_manager::{FileProcessingMode, HanzoFileManager},
     hanzo_fs_error::HanzoFsError,
 };
-use hanzo_http_api::node_api_router::APIError;
+use hanzo_http_api::{
+    api_sse::{notify_mcp_resource_list_changed, notify_mcp_resource_updated},
+    node_api_router::APIError,
+};
 use hanzo_messages::{
-    schemas::hanzo_fs::HanzoFileChunkCollection,
+    schemas::hanzo_fs::{ChunkingStrategy, HanzoFileChunkCollection, WatchedFolder},
     hanzo_message::hanzo_message_schemas::{
-        APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder, APIVecFsDeleteItem,
-        APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsRetrieveSourceFile,
-        APIVecFsSearchItems,
+        APIVecFsAddWatchedFolder, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder,
+        APIVecFsDeleteItem, APIVecFsGetFolderChunkingStrategy, APIVecFsMoveFolder, APIVecFsMoveItem,
+        APIVecFsRemoveWatchedFolder, APIVecFsRetrievePathSimplifiedJson, APIVecFsRetrieveSourceFile,
+        APIVecFsSearchItems, APIVecFsSetFolderChunkingStrategy, APIVecFsSyncWatchedFolder,
     },
     hanzo_utils::hanzo_path::HanzoPath,
 };
@@ -25,7 +29,7 @@ use hanzo_db_sqlite::SqliteManager;
 use tokio::sync::Mutex;
 
 use crate::{
-    managers::IdentityManager,
+    managers::{folder_sync_manager::FolderSyncManager, IdentityManager},
     network::{node_error::NodeError, Node},
 };
 
@@ -116,6 +120,7 @@ impl Node {
         // Create the folder using HanzoFileManager
         match HanzoFileManager::create_folder(full_path) {
             Ok(_) => {
+                notify_mcp_resource_list_changed();
                 let _ = res.send(Ok("Folder created successfully".to_string())).await;
             }
             Err(e) => {
@@ -169,6 +174,7 @@ impl Node {
         // Move the file using HanzoFileManager
         match HanzoFileManager::move_file(origin_path, destination_path, &db) {
             Ok(_) => {
+                notify_mcp_resource_list_changed();
                 let success_message = format!("Item moved successfully to {}", input_payload.destination_path);
                 let _ = res.send(Ok(success_message)).await;
             }
@@ -214,6 +220,7 @@ impl Node {
         // Copy the file using HanzoFileManager
         match HanzoFileManager::copy_file(origin_path, destination_path) {
             Ok(_) => {
+                notify_mcp_resource_list_changed();
                 let success_message = format!("Item copied successfully to {}", input_payload.destination_path);
                 let _ = res.send(Ok(success_message)).await;
             }
@@ -267,6 +274,7 @@ impl Node {
         // Move the folder using HanzoFileManager
         match HanzoFileManager::move_folder(origin_path, destination_path, &db) {
             Ok(_) => {
+                notify_mcp_resource_list_changed();
                 let success_message = format!("Folder moved successfully to {}", input_payload.destination_path);
                 let _ = res.send(Ok(success_message)).await;
             }
@@ -395,6 +403,7 @@ impl Node {
         // Delete the folder using HanzoFileManager
         match HanzoFileManager::remove_folder(folder_path, &db) {
             Ok(_) => {
+                notify_mcp_resource_list_changed();
                 let success_message = format!("Folder successfully deleted: {}", input_payload.path);
                 let _ = res.send(Ok(success_message)).await;
             }
@@ -448,6 +457,7 @@ impl Node {
         // Delete the file using HanzoFileManager
         match HanzoFileManager::remove_file(item_path, &db) {
             Ok(_) => {
+                notify_mcp_resource_list_changed();
                 let success_message = format!("File successfully deleted: {}", input_payload.path);
                 let _ = res.send(Ok(success_message)).await;
             }
@@ -672,6 +682,7 @@ impl Node {
         file: Vec<u8>,
         path: String,
         _file_datetime: Option<DateTime<Utc>>,
+        chunking_strategy: Option<ChunkingStrategy>,
         res: Sender<Result<Value, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
@@ -694,10 +705,13 @@ impl Node {
             &db,
             FileProcessingMode::Auto,
             &*embedding_generator,
+            chunking_strategy,
         )
         .await
         {
             Ok(_) => {
+                notify_mcp_resource_updated(&full_path_str);
+                notify_mcp_resource_list_changed();
                 let success_message = format!("File uploaded and processed successfully: {}", full_path_str);
                 let _ = res.send(Ok(serde_json::json!({ "message": success_message }))).await;
             }
@@ -805,6 +819,7 @@ impl Node {
         filename: String,
         file: Vec<u8>,
         _file_datetime: Option<DateTime<Utc>>,
+        chunking_strategy: Option<ChunkingStrategy>,
         res: Sender<Result<Value, APIError>>,
     ) -> Result<(), NodeError> {
         // Validate the bearer token
@@ -820,6 +835,7 @@ impl Node {
             &db,
             FileProcessingMode::Auto,
             &*embedding_generator,
+            chunking_strategy,
         )
         .await
         {
@@ -923,6 +939,287 @@ impl Node {
         Ok(())
     }
 
+    pub async fn v2_set_folder_chunking_strategy(
+        db: Arc<SqliteManager>,
+        payload: APIVecFsSetFolderChunkingStrategy,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let folder_path = HanzoPath::from_string(payload.path.clone());
+        if !folder_path.exists() || !folder_path.as_path().is_dir() {
+            let api_error = APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error: "Bad Request".to_string(),
+                message: format!("Folder does not exist: {}", payload.path),
+            };
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        // Only files processed from now on are affected; already indexed files keep their strategy
+        match db.set_folder_chunking_strategy(folder_path.relative_path(), payload.chunking_strategy.as_ref()) {
+            Ok(_) => {
+                let message = "Chunking strategy updated successfully";
+                let _ = res.send(Ok(serde_json::json!({ "message": message }))).await;
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to set chunking strategy: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the strategy set on the folder itself and the effective one, which may come from a parent folder.
+    pub async fn v2_get_folder_chunking_strategy(
+        db: Arc<SqliteManager>,
+        payload: APIVecFsGetFolderChunkingStrategy,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let folder_path = HanzoPath::from_string(payload.path.clone());
+        let rel_path = folder_path.relative_path();
+        let strategies = db.get_folder_chunking_strategy(rel_path).and_then(|own| match own {
+            Some(own) => Ok((Some(own.clone()), Some(own))),
+            None => Ok((None, db.get_chunking_strategy_for_path(rel_path)?)),
+        });
+
+        match strategies {
+            Ok((own, effective)) => {
+                let _ = res
+                    .send(Ok(serde_json::json!({
+                        "chunking_strategy": own,
+                        "effective_chunking_strategy": effective,
+                    })))
+                    .await;
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to get chunking strategy: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Registers a host directory to mirror into a VecFS folder and starts its first sync in the background.
+    pub async fn v2_add_watched_folder(
+        db: Arc<SqliteManager>,
+        embedding_generator: Arc<dyn EmbeddingGenerator>,
+        payload: APIVecFsAddWatchedFolder,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let host_path = match std::fs::canonicalize(&payload.host_path) {
+            Ok(host_path) if host_path.is_dir() => host_path.to_string_lossy().to_string(),
+            _ => {
+                let api_error = APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error: "Bad Request".to_string(),
+                    message: format!("Host directory does not exist: {}", payload.host_path),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let already_watched = db
+            .get_all_watched_folders()
+            .map(|folders| folders.iter().any(|folder| folder.host_path == host_path))
+            .unwrap_or(false);
+        if already_watched {
+            let api_error = APIError {
+                code: StatusCode::CONFLICT.as_u16(),
+                error: "Conflict".to_string(),
+                message: format!("Host directory is already watched: {}", host_path),
+            };
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let vecfs_path = HanzoPath::from_string(payload.path.clone());
+        let watched_folder = HanzoFileManager::create_folder(vecfs_path.clone())
+            .map_err(|e| e.to_string())
+            .and_then(|_| {
+                db.add_watched_folder(&host_path, vecfs_path.relative_path())
+                    .and_then(|id| db.get_watched_folder(id))
+                    .map_err(|e| e.to_string())
+            })
+            .and_then(|watched_folder| watched_folder.ok_or_else(|| "registration not found".to_string()));
+
+        match watched_folder {
+            Ok(watched_folder) => {
+                let _ = res
+                    .send(Ok(serde_json::to_value(&watched_folder).unwrap_or_default()))
+                    .await;
+
+                FolderSyncManager::watched_folders_changed();
+                tokio::spawn(async move {
+                    let _ = FolderSyncManager::sync_folder(&db, &watched_folder, &*embedding_generator).await;
+                });
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to add watched folder: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stops watching a folder. The mirrored files stay in the VecFS unless `remove_files` is set.
+    pub async fn v2_remove_watched_folder(
+        db: Arc<SqliteManager>,
+        payload: APIVecFsRemoveWatchedFolder,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(watched_folder) = Self::get_watched_folder_or_send_error(&db, payload.id, &res).await else {
+            return Ok(());
+        };
+
+        let result = if payload.remove_files {
+            HanzoFileManager::remove_watched_folder_files(&watched_folder, &db).map_err(|e| e.to_string())
+        } else {
+            Ok(())
+        };
+        match result.and_then(|_| db.remove_watched_folder(watched_folder.id).map_err(|e| e.to_string())) {
+            Ok(_) => {
+                FolderSyncManager::watched_folders_changed();
+                let message = "Watched folder removed successfully";
+                let _ = res.send(Ok(serde_json::json!({ "message": message }))).await;
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to remove watched folder: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn v2_list_watched_folders(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        match db.get_all_watched_folders() {
+            Ok(watched_folders) => {
+                let _ = res
+                    .send(Ok(serde_json::to_value(&watched_folders).unwrap_or_default()))
+                    .await;
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to list watched folders: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Syncs a watched folder right away, rehashing every file, e.g. to pick up changes the watcher missed.
+    pub async fn v2_sync_watched_folder(
+        db: Arc<SqliteManager>,
+        embedding_generator: Arc<dyn EmbeddingGenerator>,
+        payload: APIVecFsSyncWatchedFolder,
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Validate the bearer token
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let Some(watched_folder) = Self::get_watched_folder_or_send_error(&db, payload.id, &res).await else {
+            return Ok(());
+        };
+
+        match FolderSyncManager::sync_folder(&db, &watched_folder, &*embedding_generator).await {
+            Ok(report) => {
+                let _ = res.send(Ok(serde_json::to_value(&report).unwrap_or_default())).await;
+            }
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to sync watched folder: {}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_watched_folder_or_send_error(
+        db: &SqliteManager,
+        id: i64,
+        res: &Sender<Result<Value, APIError>>,
+    ) -> Option<WatchedFolder> {
+        let api_error = match db.get_watched_folder(id) {
+            Ok(Some(watched_folder)) => return Some(watched_folder),
+            Ok(None) => APIError {
+                code: StatusCode::NOT_FOUND.as_u16(),
+                error: "Not Found".to_string(),
+                message: format!("Watched folder not found: {}", id),
+            },
+            Err(e) => APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to get watched folder: {}", e),
+            },
+        };
+        let _ = res.send(Err(api_error)).await;
+        None
+    }
+
     pub async fn v2_api_search_files_by_name(
         db: Arc<SqliteManager>,
         _identity_manager: Arc<Mutex<IdentityManager>>,
@@ -956,4 +1253,237 @@ impl Node {
 
         Ok(())
     }
+
+    pub async fn v2_api_list_all_mcp_resources(
+        db: Arc<SqliteManager>,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        // Internal MCP server command: only requires that the node has been set up
+        let _bearer = Self::get_bearer_token(db.clone(), &res).await?;
+
+        // Only the folders the operator published are listed, nothing if MCP resources are disabled
+        let mut contents = Vec::new();
+        for folder in Self::mcp_resource_folders(&db) {
+            let folder_path = HanzoPath::from_string(folder);
+            if !folder_path.exists() || folder_path.is_file() {
+                continue;
+            }
+            match HanzoFileManager::list_directory_contents_with_depth(folder_path, &db, usize::MAX) {
+                Ok(folder_contents) => contents.extend(folder_contents),
+                Err(e) => {
+                    let api_error = APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to retrieve directory contents: {}", e),
+                    };
+                    let _ = res.send(Err(api_error)).await;
+                    return Ok(());
+                }
+            }
+        }
+
+        let json_contents = serde_json::to_value(contents).map_err(|e| NodeError::from(e))?;
+        let _ = res.send(Ok(json_contents)).await;
+        Ok(())
+    }
+
+    /// VecFS folders published to MCP clients as resources. Nothing is published unless the
+    /// `mcp_resources_enabled` preference is true, and then only the folders listed in the
+    /// `mcp_resources_folders` preference.
+    fn mcp_resource_folders(db: &SqliteManager) -> Vec<String> {
+        if !matches!(db.get_preference::<bool>("mcp_resources_enabled"), Ok(Some(true))) {
+            return Vec::new();
+        }
+        db.get_preference::<Vec<String>>("mcp_resources_folders")
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    /// Whether a VecFS path is one of the published folders or inside one. `/` publishes the whole tree.
+    fn is_mcp_resource_path(path: &str, folders: &[String]) -> bool {
+        if path.split('/').any(|component| component == "..") {
+            return false;
+        }
+        let path = path.trim_matches('/');
+        folders.iter().any(|folder| {
+            let folder = folder.trim_matches('/');
+            folder.is_empty() || path == folder || path.starts_with(&format!("{}/", folder))
+        })
+    }
+
+    /// Reads a VecFS item for the MCP server. Folders return their listing as JSON, text files their
+    /// content, and binary files the text of their parsed chunks or, if never processed, the raw bytes in base64.
+    pub async fn v2_api_read_mcp_resource(
+        db: Arc<SqliteManager>,
+        path: String,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        let _bearer = Self::get_bearer_token(db.clone(), &res).await?;
+
+        if !Self::is_mcp_resource_path(&path, &Self::mcp_resource_folders(&db)) {
+            let api_error = APIError {
+                code: StatusCode::FORBIDDEN.as_u16(),
+                error: "Forbidden".to_string(),
+                message: format!("Path is not published as an MCP resource: {}", path),
+            };
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        let hanzo_path = HanzoPath::from_string(path.clone());
+        if !hanzo_path.exists() {
+            let api_error = APIError {
+                code: StatusCode::NOT_FOUND.as_u16(),
+                error: "Not Found".to_string(),
+                message: format!("Path does not exist: {}", path),
+            };
+            let _ = res.send(Err(api_error)).await;
+            return Ok(());
+        }
+
+        if !hanzo_path.is_file() {
+            match HanzoFileManager::list_directory_contents_with_depth(hanzo_path, &db, 1) {
+                Ok(contents) => {
+                    let listing = serde_json::to_string_pretty(&contents).map_err(|e| NodeError::from(e))?;
+                    let _ = res
+                        .send(Ok(serde_json::json!({ "mime_type": "application/json", "text": listing })))
+                        .await;
+                }
+                Err(e) => {
+                    let api_error = APIError {
+                        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                        error: "Internal Server Error".to_string(),
+                        message: format!("Failed to retrieve directory contents: {}", e),
+                    };
+                    let _ = res.send(Err(api_error)).await;
+                }
+            }
+            return Ok(());
+        }
+
+        let file_content = match std::fs::read(hanzo_path.as_path()) {
+            Ok(content) => content,
+            Err(e) => {
+                let api_error = APIError {
+                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                    error: "Internal Server Error".to_string(),
+                    message: format!("Failed to read file content: {:?}", e),
+                };
+                let _ = res.send(Err(api_error)).await;
+                return Ok(());
+            }
+        };
+
+        let response = match String::from_utf8(file_content) {
+            Ok(text) => serde_json::json!({ "text": text }),
+            Err(e) => {
+                // Documents such as PDFs are served through the text extracted when they were processed
+                let chunks = match db.get_parsed_file_by_hanzo_path(&hanzo_path) {
+                    Ok(Some(parsed_file)) => {
+                        let chunks = match parsed_file.id {
+                            Some(id) => db.get_chunks_for_parsed_file(id).map_err(|e| e.to_string()),
+                            None => Err(format!("{} has no parsed file id", path)),
+                        };
+                        match chunks {
+                            Ok(chunks) => chunks,
+                            Err(e) => {
+                                let api_error = APIError {
+                                    code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                                    error: "Internal Server Error".to_string(),
+                                    message: format!("Failed to retrieve file chunks: {}", e),
+                                };
+                                let _ = res.send(Err(api_error)).await;
+                                return Ok(());
+                            }
+                        }
+                    }
+                    _ => Vec::new(),
+                };
+                if chunks.is_empty() {
+                    let encoded = base64::engine::general_purpose::STANDARD.encode(e.into_bytes());
+                    serde_json::json!({ "blob": encoded })
+                } else {
+                    let mut chunks = chunks;
+                    chunks.sort_by_key(|c| c.position);
+                    let text: String = chunks.into_iter().map(|c| c.content).collect();
+                    serde_json::json!({ "mime_type": "text/plain", "text": text })
+                }
+            }
+        };
+
+        let _ = res.send(Ok(response)).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        let db = SqliteManager::new(db_path, api_url, model_type).unwrap();
+        db.set_api_v2_key("test_key").unwrap();
+        db
+    }
+
+    async fn read_mcp_resource(db: &Arc<SqliteManager>, path: &str) -> Result<Value, APIError> {
+        let (res, rx) = async_channel::bounded(1);
+        Node::v2_api_read_mcp_resource(db.clone(), path.to_string(), res)
+            .await
+            .unwrap();
+        rx.recv().await.unwrap()
+    }
+
+    async fn list_mcp_resources(db: &Arc<SqliteManager>) -> Value {
+        let (res, rx) = async_channel::bounded(1);
+        Node::v2_api_list_all_mcp_resources(db.clone(), res).await.unwrap();
+        rx.recv().await.unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_is_mcp_resource_path() {
+        let folders = vec!["/shared".to_string(), "reports/2026/".to_string()];
+
+        assert!(Node::is_mcp_resource_path("/shared", &folders));
+        assert!(Node::is_mcp_resource_path("/shared/notes.txt", &folders));
+        assert!(Node::is_mcp_resource_path("/reports/2026/q1.pdf", &folders));
+        assert!(!Node::is_mcp_resource_path("/shared_private/notes.txt", &folders));
+        assert!(!Node::is_mcp_resource_path("/reports/2025/q1.pdf", &folders));
+        assert!(!Node::is_mcp_resource_path("/shared/../private/notes.txt", &folders));
+        assert!(!Node::is_mcp_resource_path("/shared/notes.txt", &[]));
+        assert!(Node::is_mcp_resource_path("/private/notes.txt", &["/".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_mcp_resources_outside_the_allowlist_are_refused() {
+        let db = Arc::new(setup_test_db());
+
+        // Disabled by default, even for listed folders
+        db.set_preference("mcp_resources_folders", &vec!["/shared"], None)
+            .unwrap();
+        let error = read_mcp_resource(&db, "/shared/notes.txt").await.unwrap_err();
+        assert_eq!(error.code, StatusCode::FORBIDDEN.as_u16());
+        assert_eq!(list_mcp_resources(&db).await, serde_json::json!([]));
+
+        db.set_preference("mcp_resources_enabled", &true, None).unwrap();
+        for path in ["/private/notes.txt", "/shared/../private/notes.txt", "/"] {
+            let error = read_mcp_resource(&db, path).await.unwrap_err();
+            assert_eq!(error.code, StatusCode::FORBIDDEN.as_u16(), "{} should be refused", path);
+        }
+
+        // Allowed paths get past the check, this one just doesn't exist
+        let error = read_mcp_resource(&db, "/shared/missing_file_for_mcp_test.txt")
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, StatusCode::NOT_FOUND.as_u16());
+    }
 }
@@ -10,9 +10,10 @@ use hanzo_messages::schemas::{
     hanzo_name::HanzoName,
     wallet_complementary::{WalletRole, WalletSource},
     wallet_mixed::{Asset, AssetType},
+    wallet_spending_policy::{SpendingLedgerEntry, WalletSpendingPolicy},
     x402_types::Network,
 };
-use hanzo_db_sqlite::SqliteManager;
+use hanzo_db_sqlite::{errors::SqliteManagerError, SqliteManager};
 use tokio::sync::Mutex;
 
 use crate::{
@@ -386,4 +387,71 @@ impl Node {
 
         Ok(())
     }
+
+    pub async fn v2_api_get_wallet_spending_policy(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        res: Sender<Result<WalletSpendingPolicy, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = db.get_wallet_spending_policy().map_err(|e| APIError {
+            code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            error: "Internal Server Error".to_string(),
+            message: format!("Failed to get wallet spending policy: {}", e),
+        });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_set_wallet_spending_policy(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        policy: WalletSpendingPolicy,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = match db.set_wallet_spending_policy(&policy) {
+            Ok(()) => Ok(()),
+            Err(SqliteManagerError::ValidationError(message)) => Err(APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error: "Bad Request".to_string(),
+                message,
+            }),
+            Err(e) => Err(APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to set wallet spending policy: {}", e),
+            }),
+        };
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_get_wallet_spending_ledger(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        invoice_id: Option<String>,
+        limit: Option<u64>,
+        res: Sender<Result<Vec<SpendingLedgerEntry>, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = db
+            .get_spending_ledger(invoice_id.as_deref(), limit)
+            .map_err(|e| APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to get spending ledger: {}", e),
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
 }
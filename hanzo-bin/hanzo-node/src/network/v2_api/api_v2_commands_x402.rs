@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use async_channel::Sender;
+use chrono::Utc;
+use ed25519_dalek::SigningKey;
+use reqwest::StatusCode;
+use serde_json::{Map, Value};
+use tokio::sync::Mutex;
+use x25519_dalek::{PublicKey as EncryptionPublicKey, StaticSecret as EncryptionStaticKey};
+
+use hanzo_db_sqlite::{errors::SqliteManagerError, SqliteManager};
+use hanzo_http_api::node_api_router::APIError;
+use hanzo_messages::schemas::{
+    hanzo_name::HanzoName,
+    x402_paywall::{PaywallDecision, PaywallPrice, PaywallResource, X402Receipt},
+};
+
+use crate::{
+    llm_provider::job_manager::JobManager,
+    managers::IdentityManager,
+    network::{agent_payments_manager::x402_paywall, node_error::NodeError, Node},
+    tools::tool_execution::execute_agent_dynamic::execute_agent_tool,
+};
+
+const DEFAULT_X402_RECEIPTS_LIMIT: u64 = 100;
+
+impl Node {
+    pub async fn v2_api_get_x402_prices(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        res: Sender<Result<Vec<PaywallPrice>, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = db.get_all_paywall_prices().map_err(|e| APIError {
+            code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            error: "Internal Server Error".to_string(),
+            message: format!("Failed to get x402 prices: {}", e),
+        });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_set_x402_price(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        price: PaywallPrice,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = match db.set_paywall_price(&price) {
+            Ok(()) => Ok(()),
+            Err(SqliteManagerError::ValidationError(message)) => Err(APIError {
+                code: StatusCode::BAD_REQUEST.as_u16(),
+                error: "Bad Request".to_string(),
+                message,
+            }),
+            Err(e) => Err(APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to set x402 price: {}", e),
+            }),
+        };
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_remove_x402_price(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        resource: PaywallResource,
+        res: Sender<Result<(), APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = match db.remove_paywall_price(&resource) {
+            Ok(()) => Ok(()),
+            Err(SqliteManagerError::X402PriceNotFound(_)) => Err(APIError {
+                code: StatusCode::NOT_FOUND.as_u16(),
+                error: "Not Found".to_string(),
+                message: format!("{} has no x402 price", resource),
+            }),
+            Err(e) => Err(APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to remove x402 price: {}", e),
+            }),
+        };
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_get_x402_receipts(
+        db: Arc<SqliteManager>,
+        bearer: String,
+        resource: Option<PaywallResource>,
+        limit: Option<u64>,
+        res: Sender<Result<Vec<X402Receipt>, APIError>>,
+    ) -> Result<(), NodeError> {
+        if Self::validate_bearer_token(&bearer, db.clone(), &res).await.is_err() {
+            return Ok(());
+        }
+
+        let result = db
+            .get_x402_receipts(resource.as_ref(), limit.unwrap_or(DEFAULT_X402_RECEIPTS_LIMIT))
+            .map_err(|e| APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error: "Internal Server Error".to_string(),
+                message: format!("Failed to get x402 receipts: {}", e),
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_check_x402_payment(
+        db: Arc<SqliteManager>, // No Bearer token needed, the payment is what grants access
+        resource: PaywallResource,
+        payment: Option<String>,
+        res: Sender<Result<PaywallDecision, APIError>>,
+    ) -> Result<(), NodeError> {
+        let facilitator = x402_paywall::facilitator_config();
+        let result = x402_paywall::check_payment(&db, facilitator, &resource, payment, Utc::now())
+            .await
+            .map_err(|e| APIError {
+                code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+                error: "Internal Server Error".to_string(),
+                message: e,
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    pub async fn v2_api_settle_x402_payment(
+        db: Arc<SqliteManager>,
+        receipt_id: String,
+        served: bool,
+        res: Sender<Result<Option<String>, APIError>>,
+    ) -> Result<(), NodeError> {
+        let facilitator = x402_paywall::facilitator_config();
+        let result = x402_paywall::settle_receipt(&db, facilitator, &receipt_id, served, Utc::now())
+            .await
+            .map_err(|e| APIError {
+                code: StatusCode::PAYMENT_REQUIRED.as_u16(),
+                error: "Payment Required".to_string(),
+                message: e,
+            });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+
+    /// Single turn chat with an agent, served once the x402 payment for it is verified
+    pub async fn v2_api_x402_agent_chat(
+        node_name: HanzoName,
+        db: Arc<SqliteManager>,
+        agent_id: String,
+        message: String,
+        identity_manager: Arc<Mutex<IdentityManager>>,
+        job_manager: Arc<Mutex<JobManager>>,
+        encryption_secret_key: EncryptionStaticKey,
+        encryption_public_key: EncryptionPublicKey,
+        signing_secret_key: SigningKey,
+        res: Sender<Result<Value, APIError>>,
+    ) -> Result<(), NodeError> {
+        let bearer = Self::get_bearer_token(db.clone(), &res).await?;
+
+        let mut parameters = Map::new();
+        parameters.insert("agent_id".to_string(), Value::String(agent_id.clone()));
+        parameters.insert("prompt".to_string(), Value::String(message));
+        let result = execute_agent_tool(
+            bearer,
+            db,
+            parameters,
+            node_name,
+            identity_manager,
+            job_manager,
+            encryption_secret_key,
+            encryption_public_key,
+            signing_secret_key,
+        )
+        .await
+        .map_err(|e| APIError {
+            code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+            error: "Internal Server Error".to_string(),
+            message: format!("Failed to chat with agent {}: {}", agent_id, e),
+        });
+        let _ = res.send(result).await;
+        Ok(())
+    }
+}
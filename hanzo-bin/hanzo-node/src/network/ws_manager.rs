@@ -31,6 +31,7 @@ use x25519_dalek::StaticSecret as EncryptionStaticKey;
 
 use super::Node;
 use crate::managers::identity_manager::IdentityManagerTrait;
+use crate::monitoring::{record_ws_message, update_ws_connections};
 
 pub struct WebSocketManager {
     connections: HashMap<String, Arc<Mutex<SplitSink<WebSocket, Message>>>>,
@@ -188,6 +189,7 @@ impl WebSocketManager {
             WSTopic::Sheet => true,
             WSTopic::SheetList => true,
             WSTopic::Widget => true,
+            WSTopic::EmbeddingMigration => true,
         }
     }
 
@@ -203,6 +205,7 @@ impl WebSocketManager {
         );
 
         // eprintln!("ws_message: {:?}", ws_message);
+        record_ws_message("received", "subscription");
 
         // Validate shared_key if it exists
         if let Some(shared_key) = &ws_message.shared_key {
@@ -252,7 +255,13 @@ impl WebSocketManager {
         }
 
         // Add the connection and shared key to the manager
-        self.connections.insert(hanzo_profile_name.clone(), connection);
+        if self
+            .connections
+            .insert(hanzo_profile_name.clone(), connection)
+            .is_none()
+        {
+            update_ws_connections(1);
+        }
 
         if let Some(key) = shared_key {
             self.shared_keys.insert(hanzo_profile_name.clone(), key);
@@ -288,6 +297,23 @@ impl WebSocketManager {
         Ok(())
     }
 
+    /// Forgets a connection once its socket is closed. A profile that already reconnected on a
+    /// new socket keeps its new connection.
+    pub fn remove_connection(&mut self, connection: &Arc<Mutex<SplitSink<WebSocket, Message>>>) {
+        let closed: Vec<String> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| Arc::ptr_eq(c, connection))
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in closed {
+            self.connections.remove(&id);
+            self.subscriptions.remove(&id);
+            self.shared_keys.remove(&id);
+            update_ws_connections(-1);
+        }
+    }
+
     fn is_valid_hex_key(key: &str) -> bool {
         // Check if the key is a valid hexadecimal string
         if key.len() != 64 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
@@ -455,11 +481,14 @@ impl WebSocketManager {
                 };
 
                 match connection.send(Message::text(message_to_send.clone())).await {
-                    Ok(_) => hanzo_log(
-                        HanzoLogOption::WsAPI,
-                        HanzoLogLevel::Debug,
-                        format!("Successfully sent update to connection {}", id).as_str(),
-                    ),
+                    Ok(_) => {
+                        record_ws_message("sent", &topic.to_string());
+                        hanzo_log(
+                            HanzoLogOption::WsAPI,
+                            HanzoLogLevel::Debug,
+                            format!("Successfully sent update to connection {}", id).as_str(),
+                        )
+                    }
                     Err(e) => hanzo_log(
                         HanzoLogOption::WsAPI,
                         HanzoLogLevel::Error,
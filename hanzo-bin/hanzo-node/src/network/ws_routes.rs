@@ -99,7 +99,7 @@ pub async fn ws_handler(ws: WebSocket, manager: Arc<Mutex<WebSocketManager>>) {
         }
     }
 
-    // Optionally, you can perform any cleanup here if necessary
+    manager.lock().await.remove_connection(&ws_tx);
     hanzo_log(
         HanzoLogOption::WsAPI,
         HanzoLogLevel::Info,
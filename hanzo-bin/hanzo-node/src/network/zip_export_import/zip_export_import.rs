@@ -8,9 +8,8 @@ use serde_json::{json, Value};
 use hanzo_embed::embedding_generator::EmbeddingGenerator;
 use hanzo_fs::hanzo_file_manager::{FileProcessingMode, HanzoFileManager};
 use hanzo_http_api::node_api_router::APIError;
-use hanzo_mcp::mcp_methods::{list_tools_via_command, list_tools_via_http, list_tools_via_sse};
 use hanzo_messages::schemas::llm_providers::agent::Agent;
-use hanzo_messages::schemas::mcp_server::{MCPServer, MCPServerType};
+use hanzo_messages::schemas::mcp_server::MCPServer;
 use hanzo_messages::schemas::hanzo_name::HanzoName;
 use hanzo_messages::hanzo_utils::hanzo_path::HanzoPath;
 use hanzo_db_sqlite::SqliteManager;
@@ -89,6 +88,7 @@ async fn calculate_zip_dependencies(
                 return Ok(());
             }
             HanzoTool::Network(_, _) => (),
+            HanzoTool::Wasm(_, _) => (),
             HanzoTool::MCPServer(mcp_server_tool, _) => {
                 let mcp_server = match db.get_mcp_server(mcp_server_tool.mcp_server_ref.parse::<i64>().unwrap()) {
                     Ok(mcp_server) => mcp_server,
@@ -242,6 +242,7 @@ async fn get_dependencies_for_zip(
             }
             HanzoTool::Network(_, _) => (),
             HanzoTool::MCPServer(_, _) => (),
+            HanzoTool::Wasm(_, _) => (),
         }
 
         let tool_bytes = match Box::pin(generate_tool_zip(
@@ -761,6 +762,7 @@ async fn import_agent_knowledge(
                     &db,
                     FileProcessingMode::Auto,
                     &*embedding_generator,
+                    None,
                 )
                 .await
                 .map_err(|e| APIError {
@@ -942,26 +944,9 @@ pub async fn import_mcp_server(
             message: format!("Failed to save MCP server to database: {}", e),
         })?;
     println!("[IMPORTING MCP SERVER]: {}", mcp_server.name);
-    let tools = match mcp_server.r#type {
-        MCPServerType::Command => {
-            let tools = list_tools_via_command(&mcp_server.command.clone().unwrap_or_default().to_string(), None)
-                .await
-                .map_err(|e| println!("Failed to list tools: {:?}", e));
-            tools
-        }
-        MCPServerType::Sse => {
-            let tools = list_tools_via_sse(&mcp_server.url.clone().unwrap_or_default().to_string(), None)
-                .await
-                .map_err(|e| println!("Failed to list tools: {:?}", e));
-            tools
-        }
-        MCPServerType::Http => {
-            let tools = list_tools_via_http(&mcp_server.url.clone().unwrap_or_default().to_string(), None)
-                .await
-                .map_err(|e| println!("Failed to list tools: {:?}", e));
-            tools
-        }
-    };
+    let tools = mcp_manager::list_mcp_server_tools(&mcp_server)
+        .await
+        .map_err(|e| println!("Failed to list tools: {:?}", e));
     if let Ok(tools) = tools {
         for tool in tools {
             println!("[IMPORTING TOOL]: {}", tool.name);
@@ -1002,7 +987,17 @@ pub async fn import_tool(
 
     let tool_router_key = tool.tool_router_key().to_string_without_version();
     match tool.clone() {
-        HanzoTool::Deno(_, _) => {}
+        HanzoTool::Deno(deno_tool, _) => {
+            // Tools asking for more than the node allows are not installed
+            node_env
+                .tool_permissions_policy
+                .check(deno_tool.permissions.as_ref())
+                .map_err(|e| APIError {
+                    code: StatusCode::FORBIDDEN.as_u16(),
+                    error: "Permissions Not Allowed".to_string(),
+                    message: e.to_string(),
+                })?;
+        }
         HanzoTool::Python(_, _) => {}
         HanzoTool::Network(_, _) => {}
         HanzoTool::Rust(_, _) => {
@@ -1025,6 +1020,16 @@ pub async fn import_tool(
             }));
         }
         HanzoTool::MCPServer(_, _) => {}
+        HanzoTool::Wasm(wasm_tool, _) => {
+            // The module is an asset of the tool, the tool can't run without it
+            if zip_contents.archive.index_for_name(&wasm_tool.module).is_none() {
+                return Err(APIError {
+                    code: StatusCode::BAD_REQUEST.as_u16(),
+                    error: "Invalid Tool Archive".to_string(),
+                    message: format!("Archive does not contain the WASM module {}", wasm_tool.module),
+                });
+            }
+        }
     }
 
     // check if any version of the tool exists in the database
@@ -1043,6 +1048,7 @@ pub async fn import_tool(
                 "status": "success",
                 "message": "Tool already up-to-date",
                 "tool_key": tool.tool_router_key().to_string_without_version(),
+                "permissions": tool.get_permissions(),
                 "tool": tool.clone()
             }));
         }
@@ -1067,6 +1073,7 @@ pub async fn import_tool(
         "status": "success",
         "message": "Tool imported successfully",
         "tool_key": tool.tool_router_key().to_string_without_version(),
+        "permissions": tool.get_permissions(),
         "tool": tool
     }))
 }
@@ -1420,6 +1427,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_b_name = "Tool B";
@@ -1455,6 +1463,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_c_name = "Tool C";
@@ -1490,6 +1499,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
         tool_a
             .tools
@@ -1605,6 +1615,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_b_name = "Tool B";
@@ -1640,6 +1651,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
         tool_a
             .tools
@@ -1720,6 +1732,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_b_name = "Tool B";
@@ -1755,6 +1768,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         // Add tools to database
@@ -1,5 +1,7 @@
 use super::network::Node;
 use super::utils::environment::NodeEnvironment;
+use crate::monitoring::telemetry::init_telemetry;
+use crate::monitoring::{init_metrics, record_db_query, MetricsCollector};
 use crate::utils::args::parse_args;
 use crate::utils::cli::cli_handle_create_message;
 use crate::utils::environment::{fetch_llm_provider_env, fetch_node_environment};
@@ -255,6 +257,35 @@ pub async fn initialize_node() -> Result<
     // Copy of node commands center
     let node_commands_sender_copy = node_commands_sender.clone();
 
+    // Job execution spans are exported when an OTLP endpoint is configured
+    match init_telemetry() {
+        Ok(true) => hanzo_log(
+            HanzoLogOption::Node,
+            HanzoLogLevel::Info,
+            "OpenTelemetry tracing enabled",
+        ),
+        Ok(false) => {}
+        Err(e) => hanzo_log(
+            HanzoLogOption::Node,
+            HanzoLogLevel::Error,
+            &format!("Failed to initialize OpenTelemetry tracing: {}", e),
+        ),
+    }
+
+    // Metrics are only collected and served on /metrics when METRICS_ENABLED is set
+    let metrics_enabled = node_env.metrics_enabled;
+    if metrics_enabled {
+        if let Err(e) = init_metrics() {
+            hanzo_log(
+                HanzoLogOption::Node,
+                HanzoLogLevel::Error,
+                &format!("Failed to initialize metrics: {}", e),
+            );
+        }
+        Arc::new(MetricsCollector::new()).start().await;
+        hanzo_db_sqlite::set_query_observer(record_db_query);
+    }
+
     // Setup API Server task
     let api_listen_address = node_env.clone().api_listen_address;
     let api_https_listen_address = node_env.clone().api_https_listen_address;
@@ -268,6 +299,7 @@ pub async fn initialize_node() -> Result<
             global_identity_name.clone().to_string(),
             node_keys.private_https_certificate.clone(),
             node_keys.public_https_certificate.clone(),
+            metrics_enabled,
         )
         .await
         {
@@ -141,6 +141,7 @@ pub async fn generate_tool_definitions(
         let tool_result = match tool_data.clone() {
             HanzoTool::Deno(deno_tool, _) => deno_tool.result,
             HanzoTool::Python(python_tool, _) => python_tool.result,
+            HanzoTool::Wasm(wasm_tool, _) => wasm_tool.result,
             HanzoTool::Rust(rust_tool, _) => {
                 let json_str = if rust_tool.output_arg.json.trim().is_empty() {
                     "{}"
@@ -28,9 +28,9 @@ pub async fn execute_mcp_server_dynamic(
                 .map_err(|_| ToolError::ExecutionError("Failed to get MCP server from database".to_string()))?
                 .ok_or_else(|| ToolError::ExecutionError("MCP server not found in database".to_string()))?;
 
-            // Run the tool using the MCP server
+            // Run the tool using the MCP server, reusing its pooled session if one is open
             let result = tool
-                .run(mcp_server, parameters, extra_config)
+                .run(mcp_server, parameters, extra_config, None)
                 .await
                 .map_err(|e| ToolError::ExecutionError(format!("Failed to run MCP server tool: {}", e)))?;
 
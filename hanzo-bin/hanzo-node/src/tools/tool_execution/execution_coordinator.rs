@@ -7,6 +7,7 @@ use crate::tools::tool_execution::execution_custom::try_to_execute_rust_tool;
 use crate::tools::tool_execution::execution_deno_dynamic::{check_deno_tool, execute_deno_tool};
 use crate::tools::tool_execution::execution_header_generator::{check_tool, generate_execution_environment};
 use crate::tools::tool_execution::execution_python_dynamic::execute_python_tool;
+use crate::tools::tool_execution::execution_wasm::execute_wasm_tool;
 use crate::utils::environment::fetch_node_environment;
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use chrono::Utc;
@@ -26,6 +27,7 @@ use hanzo_db_sqlite::SqliteManager;
 use hanzo_tools::tools::error::ToolError;
 use hanzo_tools::tools::hanzo_tool::HanzoTool;
 use hanzo_tools::tools::tool_config::{BasicConfig, OAuth, ToolConfig};
+use hanzo_tools::tools::tool_permissions::ToolPermissions;
 use hanzo_tools::tools::tool_types::{OperatingSystem, RunnerType};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -337,7 +339,7 @@ pub async fn execute_tool_cmd(
                 .map_err(|e| ToolError::ExecutionError(format!("Failed to get MCP server: {}", e)))?;
             if let Some(mcp_server) = mcp_server {
                 mcp_server_tool
-                    .run(mcp_server, parameters, extra_config)
+                    .run(mcp_server, parameters, extra_config, None)
                     .await
                     .map(|result| json!(result.data))
             } else {
@@ -465,6 +467,8 @@ pub async fn execute_tool_cmd(
                 &deno_tool.oauth,
             )?;
             let node_env = fetch_node_environment();
+            // The policy of the node may have changed since the tool was installed
+            node_env.tool_permissions_policy.check(deno_tool.permissions.as_ref())?;
             let node_storage_path = node_env
                 .node_storage_path
                 .clone()
@@ -503,6 +507,46 @@ pub async fn execute_tool_cmd(
                 .map(|result| json!(result.data))
                 .map_err(|e| ToolError::ExecutionError(e.to_string()))
         }
+        HanzoTool::Wasm(wasm_tool, _) => {
+            check_tool(
+                tool_router_key.clone(),
+                wasm_tool.config.clone(),
+                parameters.clone(),
+                wasm_tool.input_args.clone(),
+                &None,
+            )?;
+
+            let node_storage_path = fetch_node_environment()
+                .node_storage_path
+                .ok_or_else(|| ToolError::ExecutionError("Node storage path is not set".to_string()))?;
+            let key = ToolRouterKey::from_string(&tool_router_key)?;
+            let wasm_bytes = wasm_tool.read_module(&node_storage_path, &key)?;
+
+            // The config of the call overrides the one stored with the tool
+            let mut config = wasm_tool.config.clone();
+            config.extend(extra_config);
+
+            execute_wasm_tool(
+                bearer,
+                db,
+                node_name,
+                parameters,
+                config,
+                None,
+                tool_id,
+                app_id,
+                agent_id,
+                llm_provider,
+                wasm_bytes,
+                wasm_tool.entrypoint.clone(),
+                wasm_tool.capabilities.clone(),
+                wasm_tool.asset_paths(&node_storage_path, &key),
+                mounts,
+                None,
+                None,
+            )
+            .await
+        }
         _ => Err(ToolError::ExecutionError(format!("Unsupported tool type: {:?}", tool))),
     }
 }
@@ -613,6 +657,7 @@ pub async fn execute_code(
     mounts: Option<Vec<String>>,
     runner: Option<RunnerType>,
     operating_system: Option<Vec<OperatingSystem>>,
+    permissions: Option<ToolPermissions>,
     identity_manager_clone: Arc<Mutex<IdentityManager>>,
     job_manager_clone: Arc<Mutex<JobManager>>,
     encryption_secret_key_clone: EncryptionStaticKey,
@@ -653,6 +698,7 @@ pub async fn execute_code(
                 mounts,
                 runner,
                 operating_system,
+                permissions,
             )
             .await
         }
@@ -704,6 +750,7 @@ pub async fn check_code(
     app_id: String,
     _tools: Vec<ToolRouterKey>,
     sqlite_manager: Arc<SqliteManager>,
+    permissions: Option<ToolPermissions>,
 ) -> Result<Vec<String>, ToolError> {
     eprintln!("[check_code] tool_type: {}", tool_type);
 
@@ -733,7 +780,7 @@ pub async fn check_code(
                 .await
                 .map_err(|_| ToolError::ExecutionError("Failed to generate tool definitions".to_string()))?;
             // Since `check_deno_tool` is synchronous, run it in a blocking task
-            check_deno_tool(tool_id, app_id, support_files, code_extracted).await
+            check_deno_tool(tool_id, app_id, support_files, code_extracted, permissions).await
         }
         DynamicToolType::PythonDynamic => Err(ToolError::ExecutionError("NYI Python".to_string())),
         DynamicToolType::AgentDynamic => Err(ToolError::ExecutionError("NYI Agent".to_string())),
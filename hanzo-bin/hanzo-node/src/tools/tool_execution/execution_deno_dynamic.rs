@@ -9,6 +9,7 @@ use hanzo_tools::tools::error::ToolError;
 use hanzo_tools::tools::parameters::Parameters;
 use hanzo_tools::tools::tool_config::{OAuth, ToolConfig};
 use hanzo_tools::tools::tool_output_arg::ToolOutputArg;
+use hanzo_tools::tools::tool_permissions::ToolPermissions;
 use hanzo_tools::tools::tool_types::{OperatingSystem, RunnerType, ToolResult};
 
 use super::execution_header_generator::{check_tool, generate_execution_environment};
@@ -32,6 +33,7 @@ pub async fn execute_deno_tool(
     mounts: Option<Vec<String>>,
     runner: Option<RunnerType>,
     operating_system: Option<Vec<OperatingSystem>>,
+    permissions: Option<ToolPermissions>,
 ) -> Result<Value, ToolError> {
     let tool_router_key = ToolRouterKey::new(
         "local".to_string(),
@@ -70,6 +72,7 @@ pub async fn execute_deno_tool(
             OperatingSystem::Windows,
         ]),
         tool_set: None,
+        permissions,
     };
 
     let env = generate_execution_environment(
@@ -94,6 +97,7 @@ pub async fn execute_deno_tool(
     )?;
 
     let node_env = fetch_node_environment();
+    node_env.tool_permissions_policy.check(tool.permissions.as_ref())?;
     let node_storage_path = node_env
         .node_storage_path
         .clone()
@@ -148,6 +152,7 @@ pub async fn check_deno_tool(
     app_id: String,
     support_files: HashMap<String, String>,
     code: String,
+    permissions: Option<ToolPermissions>,
 ) -> Result<Vec<String>, ToolError> {
     let tool_router_key = ToolRouterKey::new(
         "local".to_string(),
@@ -182,9 +187,11 @@ pub async fn check_deno_tool(
         runner: RunnerType::Any,
         operating_system: vec![OperatingSystem::Linux, OperatingSystem::MacOS, OperatingSystem::Windows],
         tool_set: None,
+        permissions,
     };
 
     let node_env = fetch_node_environment();
+    node_env.tool_permissions_policy.check(tool.permissions.as_ref())?;
     let node_storage_path = node_env
         .node_storage_path
         .clone()
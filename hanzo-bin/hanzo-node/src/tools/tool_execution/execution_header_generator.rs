@@ -9,6 +9,7 @@ use hanzo_tools::tools::{
 };
 
 use super::execution_coordinator::handle_oauth;
+use crate::monitoring::telemetry::trace_context_env;
 
 pub async fn generate_execution_environment(
     db: Arc<SqliteManager>,
@@ -31,6 +32,8 @@ pub async fn generate_execution_environment(
     }
     envs.insert("X_HANZO_INSTANCE_ID".to_string(), instance_id.clone());
     envs.insert("X_HANZO_LLM_PROVIDER".to_string(), llm_provider);
+    // TRACEPARENT and TRACESTATE, so the tool spans join the trace of the job
+    envs.extend(trace_context_env());
 
     check_oauth(oauth, &tool_router_key)?;
     let oauth = handle_oauth(oauth, &db, app_id.clone(), tool_id.clone(), tool_router_key.clone()).await?;
@@ -1,5 +1,6 @@
 //! WASM tool execution for hanzo_node
 
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -10,7 +11,13 @@ use hanzo_tools::tools::error::ToolError;
 use hanzo_tools::tools::tool_config::{OAuth, ToolConfig};
 use hanzo_tools::tools::tool_types::{OperatingSystem, RunnerType};
 use hanzo_db_sqlite::SqliteManager;
-use hanzo_wasm_runtime::{WasmRuntime, WasmConfig, WasmModuleInfo};
+use hanzo_tools::tools::wasm_tools::WasmCapabilities;
+use hanzo_tools_runner::tools::execution_context::ExecutionContext;
+use hanzo_wasm_runtime::{is_component, ToolHost, WasiSandbox, WasmRuntime, WasmConfig, WasmModuleInfo};
+use uuid::Uuid;
+
+use super::execution_header_generator::generate_execution_environment;
+use crate::utils::environment::fetch_node_environment;
 
 /// Global WASM runtime instance (lazy initialized)
 static WASM_RUNTIME: once_cell::sync::Lazy<Arc<tokio::sync::RwLock<Option<Arc<WasmRuntime>>>>> =
@@ -59,6 +66,8 @@ pub async fn execute_wasm_tool(
     llm_provider: String,
     wasm_bytes: Vec<u8>,
     function_name: String,
+    capabilities: WasmCapabilities,
+    assets_files: Vec<PathBuf>,
     mounts: Option<Vec<String>>,
     _runner: Option<RunnerType>,
     _operating_system: Option<Vec<OperatingSystem>>,
@@ -73,6 +82,32 @@ pub async fn execute_wasm_tool(
     // Ensure runtime is initialized
     let runtime = ensure_wasm_runtime().await?;
     
+    // The tool only gets the folders and variables its capabilities grant
+    let envs = generate_execution_environment(
+        db.clone(),
+        llm_provider.clone(),
+        app_id.clone(),
+        tool_id.clone(),
+        agent_id.clone(),
+        tool_id.clone(),
+        "".to_string(),
+        &oauth,
+    )
+    .await?;
+    let node_storage_path = fetch_node_environment()
+        .node_storage_path
+        .ok_or_else(|| ToolError::ExecutionError("Node storage path is not set".to_string()))?;
+    // Each call gets its own execution id, as the same tool can run several times at once
+    let execution_context = ExecutionContext {
+        context_id: app_id.clone(),
+        execution_id: Uuid::new_v4().to_string(),
+        code_id: "".to_string(),
+        storage: Path::new(&node_storage_path).join(".tools_storage"),
+        assets_files,
+        mount_files: mounts.unwrap_or_default().into_iter().map(PathBuf::from).collect(),
+    };
+    let sandbox = WasiSandbox::for_execution(&execution_context, &capabilities, &envs)?;
+    
     // Prepare execution context
     let mut context = Map::new();
     context.insert("tool_id".to_string(), Value::String(tool_id.clone()));
@@ -94,48 +129,61 @@ pub async fn execute_wasm_tool(
     }
     
     // Add extra config
-    if !extra_config.is_empty() {
-        let config_map: Map<String, Value> = extra_config
-            .into_iter()
-            .filter_map(|c| {
-                match c {
-                    ToolConfig::BasicConfig(basic) => {
-                        if let Some(value) = basic.key_value {
-                            Some((basic.key_name.clone(), value))
-                        } else {
-                            None
-                        }
-                    },
+    let config_map: Map<String, Value> = extra_config
+        .into_iter()
+        .filter_map(|c| match c {
+            ToolConfig::BasicConfig(basic) => {
+                if let Some(value) = basic.key_value {
+                    Some((basic.key_name.clone(), value))
+                } else {
+                    None
                 }
-            })
-            .collect();
-        context.insert("config".to_string(), Value::Object(config_map));
+            }
+        })
+        .collect();
+    if !config_map.is_empty() {
+        context.insert("config".to_string(), Value::Object(config_map.clone()));
     }
     
-    // Add mount points if specified
-    if let Some(mount_paths) = mounts {
-        context.insert("mounts".to_string(), Value::Array(
-            mount_paths.into_iter().map(Value::String).collect()
-        ));
+    // Add mount points if granted, as the tool sees them
+    let mount_paths = sandbox.guest_mount_files();
+    if !mount_paths.is_empty() {
+        context.insert(
+            "mounts".to_string(),
+            Value::Array(mount_paths.into_iter().map(Value::String).collect()),
+        );
     }
     
-    // Create the full parameters object
-    let mut full_params = Map::new();
-    full_params.insert("context".to_string(), Value::Object(context));
-    full_params.insert("parameters".to_string(), Value::Object(parameters));
-    
-    debug!("🌀 WASM execution parameters prepared");
-    
     // Execute the WASM function
     let start_time = std::time::Instant::now();
     
-    let result = runtime
-        .execute_bytes(wasm_bytes, &function_name, Value::Object(full_params))
-        .await
-        .map_err(|e| {
-            error!("💥 WASM execution failed: {}", e);
-            e
-        })?;
+    // Components of the hanzo:tool world get the parameters as is and reach the config
+    // and the network through their host imports
+    let output = if is_component(&wasm_bytes) {
+        debug!("🌀 Running WASM component");
+        let host = ToolHost {
+            config: config_map,
+            capabilities,
+        };
+        runtime
+            .execute_component(&wasm_bytes, Value::Object(parameters), host, &sandbox)
+            .await
+    } else {
+        // Create the full parameters object
+        let mut full_params = Map::new();
+        full_params.insert("context".to_string(), Value::Object(context));
+        full_params.insert("parameters".to_string(), Value::Object(parameters));
+        
+        debug!("🌀 WASM execution parameters prepared");
+        
+        runtime
+            .execute_bytes(wasm_bytes, &function_name, Value::Object(full_params), &sandbox)
+            .await
+    }
+    .map_err(|e| {
+        error!("💥 WASM execution failed: {}", e);
+        e
+    })?;
     
     let execution_time = start_time.elapsed();
     info!(
@@ -154,6 +202,23 @@ pub async fn execute_wasm_tool(
         warn!("Failed to log WASM execution: {}", e);
     }
     
+    // What the tool printed comes back with its result, like the files created by other runners.
+    // A result that is not an object is wrapped as `result` to carry it.
+    let mut result = output.result;
+    if !output.stdout.is_empty() || !output.stderr.is_empty() {
+        let mut data = match result {
+            Value::Object(data) => data,
+            other => Map::from_iter([("result".to_string(), other)]),
+        };
+        if !output.stdout.is_empty() {
+            data.insert("__stdout__".to_string(), Value::String(output.stdout));
+        }
+        if !output.stderr.is_empty() {
+            data.insert("__stderr__".to_string(), Value::String(output.stderr));
+        }
+        result = Value::Object(data);
+    }
+    
     Ok(result)
 }
 
@@ -5,3 +5,4 @@ pub mod execution_custom;
 pub mod execution_deno_dynamic;
 pub mod execution_header_generator;
 pub mod execution_python_dynamic;
+pub mod execution_wasm;
@@ -97,6 +97,7 @@ impl ToolExecutor for CodeExecutionProcessorTool {
             None,
             None,
             None,
+            None,
             identity_manager_clone,
             job_manager_clone,
             encryption_secret_key_clone,
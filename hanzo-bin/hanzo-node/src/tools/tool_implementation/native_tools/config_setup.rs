@@ -110,9 +110,12 @@ async fn config_update(
         hanzo_tools::tools::hanzo_tool::HanzoTool::Python(python_tool, _) => {
             python_tool.config = new_configs;
         }
+        hanzo_tools::tools::hanzo_tool::HanzoTool::Wasm(wasm_tool, _) => {
+            wasm_tool.config = new_configs;
+        }
         _ => {
             return Err(ToolError::ExecutionError(
-                "Config update is only supported for Deno and Python tools".to_string(),
+                "Config update is only supported for Deno, Python and WASM tools".to_string(),
             ))
         }
     }
@@ -384,6 +387,7 @@ mod tests {
                 runner: RunnerType::Any,
                 operating_system: vec![OperatingSystem::Windows],
                 tool_set: None,
+                permissions: None,
             },
             true,
         );
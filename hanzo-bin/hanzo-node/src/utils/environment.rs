@@ -7,6 +7,8 @@ use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{
     LLMProviderInterface, SerializedLLMProvider,
 };
 use hanzo_messages::schemas::hanzo_name::HanzoName;
+use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
+use hanzo_tools::tools::tool_permissions::ToolPermissionsPolicy;
 
 #[derive(Debug, Clone)]
 pub struct NodeEnvironment {
@@ -22,11 +24,17 @@ pub struct NodeEnvironment {
     pub node_storage_path: Option<String>,
     pub embeddings_server_url: Option<String>,
     pub embeddings_server_api_key: Option<String>,
+    pub reranker_server_url: Option<String>,
+    pub reranker_server_api_key: Option<String>,
+    pub rerank_candidates_multiplier: usize,
     pub _auto_detect_local_llms: bool,
     pub proxy_identity: Option<String>,
     pub default_embedding_model: EmbeddingModelType,
     pub supported_embedding_models: Vec<EmbeddingModelType>,
     pub api_v2_key: Option<String>,
+    pub metrics_enabled: bool,
+    pub x402_facilitator_url: Option<String>,
+    pub tool_permissions_policy: ToolPermissionsPolicy,
 }
 
 #[derive(Debug, Clone)]
@@ -165,6 +173,14 @@ pub fn fetch_node_environment() -> NodeEnvironment {
     // External server env vars
     let embeddings_server_url: Option<String> = env::var("EMBEDDINGS_SERVER_URL").ok();
     let embeddings_server_api_key: Option<String> = env::var("EMBEDDINGS_SERVER_API_KEY").ok();
+    let reranker_server_url: Option<String> = env::var("RERANKER_SERVER_URL").ok();
+    let reranker_server_api_key: Option<String> = env::var("RERANKER_SERVER_API_KEY").ok();
+
+    // How many candidates per requested chunk are fetched for the reranker to choose from
+    let rerank_candidates_multiplier: usize = env::var("RERANK_CANDIDATES_MULTIPLIER")
+        .unwrap_or_else(|_| "4".to_string())
+        .parse()
+        .expect("Failed to parse RERANK_CANDIDATES_MULTIPLIER");
 
     // Fetch the PROXY_IDENTITY environment variable
     let proxy_identity: Option<String> = env::var("PROXY_IDENTITY").ok().and_then(|addr| addr.parse().ok());
@@ -211,6 +227,39 @@ pub fn fetch_node_environment() -> NodeEnvironment {
         .expect("Failed to parse ZAP IP address");
     let zap_address = SocketAddr::new(zap_ip, zap_port);
 
+    // Serve Prometheus metrics on GET /metrics of the API server
+    let metrics_enabled: bool = env::var("METRICS_ENABLED")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .expect("Failed to parse METRICS_ENABLED");
+
+    // Facilitator verifying and settling the x402 payments of the paid routes
+    let x402_facilitator_url: Option<String> = env::var("X402_FACILITATOR_URL").ok().filter(|url| !url.is_empty());
+
+    // Most the Deno tools installed or run on the node can ask for, as JSON. Unrestricted by default,
+    // and tools that don't declare their permissions keep running with the broad runner defaults.
+    // An invalid value is logged and the default is used, so a typo doesn't stop the node
+    let tool_permissions_policy: ToolPermissionsPolicy = match env::var("TOOL_PERMISSIONS_POLICY")
+        .ok()
+        .filter(|policy| !policy.is_empty())
+    {
+        Some(policy) => match serde_json::from_str(&policy) {
+            Ok(policy) => policy,
+            Err(e) => {
+                hanzo_log(
+                    HanzoLogOption::Node,
+                    HanzoLogLevel::Error,
+                    &format!(
+                        "Failed to parse TOOL_PERMISSIONS_POLICY, using the default policy: {}",
+                        e
+                    ),
+                );
+                ToolPermissionsPolicy::default()
+            }
+        },
+        None => ToolPermissionsPolicy::default(),
+    };
+
     NodeEnvironment {
         global_identity_name,
         listen_address,
@@ -222,6 +271,9 @@ pub fn fetch_node_environment() -> NodeEnvironment {
         node_storage_path,
         embeddings_server_url,
         embeddings_server_api_key,
+        reranker_server_url,
+        reranker_server_api_key,
+        rerank_candidates_multiplier,
         _auto_detect_local_llms,
         proxy_identity,
         default_embedding_model,
@@ -229,5 +281,8 @@ pub fn fetch_node_environment() -> NodeEnvironment {
         api_v2_key,
         api_https_listen_address,
         zap_address,
+        metrics_enabled,
+        x402_facilitator_url,
+        tool_permissions_policy,
     }
 }
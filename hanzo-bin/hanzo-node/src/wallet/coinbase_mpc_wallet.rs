@@ -14,13 +14,15 @@ use std::future::Future;
 use std::pin::Pin;
 use std::str::FromStr;
 use std::sync::{Arc, Weak};
+use std::time::Duration;
 
+use super::evm_rpc::EvmRpcClient;
 use super::wallet_manager::WalletEnum;
 use super::wallet_traits::{CommonActions, IsWallet, PaymentWallet, ReceivingWallet, SendActions, TransactionHash};
 use crate::utils::environment::fetch_node_environment;
 use crate::wallet::wallet_error::WalletError;
 use hanzo_messages::schemas::wallet_mixed::{
-    Address, AddressBalanceList, Asset, AssetType, Balance, PublicAddress,
+    Address, AddressBalanceList, Asset, AssetType, Balance, PublicAddress, Transaction, TransactionReceipt,
 };
 use hanzo_messages::schemas::x402_types::{Network, PaymentRequirements};
 
@@ -590,6 +592,41 @@ impl CommonActions for CoinbaseMPCWallet {
             Ok(balance)
         })
     }
+
+    // The MPC wallet sends ordinary transactions, so they are followed through the public RPC
+    // endpoint of the network instead of the Coinbase SDK.
+    fn get_transaction(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Pin<Box<dyn Future<Output = Result<Transaction, WalletError>> + Send + 'static>> {
+        let client = EvmRpcClient::for_network(&self.network);
+        Box::pin(async move { client.get_transaction(&tx_hash).await })
+    }
+
+    fn get_transaction_confirmations(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Pin<Box<dyn Future<Output = Result<u64, WalletError>> + Send + 'static>> {
+        let client = EvmRpcClient::for_network(&self.network);
+        Box::pin(async move { client.get_transaction_confirmations(&tx_hash).await })
+    }
+
+    fn get_transaction_receipt(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<TransactionReceipt>, WalletError>> + Send + 'static>> {
+        let client = EvmRpcClient::for_network(&self.network);
+        Box::pin(async move { client.get_transaction_receipt(&tx_hash).await })
+    }
+
+    fn wait_for_transaction_receipt(
+        &self,
+        tx_hash: TransactionHash,
+        timeout: Duration,
+    ) -> Pin<Box<dyn Future<Output = Result<TransactionReceipt, WalletError>> + Send + 'static>> {
+        let client = EvmRpcClient::for_network(&self.network);
+        Box::pin(async move { client.wait_for_transaction_receipt(&tx_hash, timeout).await })
+    }
 }
 
 impl SendActions for CoinbaseMPCWallet {
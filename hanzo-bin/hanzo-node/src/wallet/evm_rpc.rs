@@ -0,0 +1,304 @@
+use std::time::Duration;
+
+use hanzo_messages::schemas::wallet_mixed::{Transaction, TransactionReceipt, TransactionStatusEnum};
+use hanzo_messages::schemas::x402_types::Network;
+use serde_json::{json, Value};
+
+use super::wallet_error::WalletError;
+
+/// How often `wait_for_transaction_receipt` asks the node for the receipt
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const RPC_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// TODO: move this to a config file or merge it to Network struct
+pub fn rpc_url_for_network(network: &Network) -> String {
+    match network {
+        Network::BaseSepolia => "https://sepolia.base.org".to_string(),
+        Network::Base => "https://mainnet.base.org".to_string(),
+        Network::AvalancheFuji => "https://api.avax-test.network/ext/bc/C/rpc".to_string(),
+        Network::Avalanche => "https://api.avax.network/ext/bc/C/rpc".to_string(),
+    }
+}
+
+fn explorer_tx_url(network: &Network, tx_hash: &str) -> String {
+    let explorer = match network {
+        Network::BaseSepolia => "https://sepolia.basescan.org",
+        Network::Base => "https://basescan.org",
+        Network::AvalancheFuji => "https://testnet.snowtrace.io",
+        Network::Avalanche => "https://snowtrace.io",
+    };
+    format!("{}/tx/{}", explorer, tx_hash)
+}
+
+fn network_id(network: &Network) -> String {
+    match serde_json::to_value(network) {
+        Ok(Value::String(id)) => id,
+        _ => format!("{:?}", network),
+    }
+}
+
+/// Parses a hex encoded quantity (e.g. `0x5208`) found in `value`
+fn parse_quantity(value: &Value, field: &str) -> Result<u64, WalletError> {
+    let quantity = value
+        .as_str()
+        .ok_or_else(|| WalletError::ParsingError(format!("Missing {} in the RPC response", field)))?;
+    u64::from_str_radix(quantity.trim_start_matches("0x"), 16)
+        .map_err(|e| WalletError::ConversionError(format!("Invalid {} '{}': {}", field, quantity, e)))
+}
+
+fn parse_field(value: &Value, field: &str) -> Result<u64, WalletError> {
+    parse_quantity(value.get(field).unwrap_or(&Value::Null), field)
+}
+
+fn parse_address(value: &Value, field: &str) -> Option<String> {
+    value.get(field).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Minimal Ethereum JSON-RPC client used to follow transactions after they are broadcast
+#[derive(Debug, Clone)]
+pub struct EvmRpcClient {
+    rpc_url: String,
+    network: Network,
+    client: reqwest::Client,
+}
+
+impl EvmRpcClient {
+    pub fn new(rpc_url: String, network: Network) -> Self {
+        EvmRpcClient {
+            rpc_url,
+            network,
+            client: reqwest::Client::builder()
+                .timeout(RPC_REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Client for the public RPC endpoint of the network
+    pub fn for_network(network: &Network) -> Self {
+        Self::new(rpc_url_for_network(network), network.clone())
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, WalletError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let response = self
+            .client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| WalletError::ProviderError(e.to_string()))?;
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| WalletError::ProviderError(e.to_string()))?;
+
+        if let Some(error) = body.get("error") {
+            return Err(WalletError::DetailedJsonRpcError {
+                code: error.get("code").and_then(Value::as_i64).unwrap_or_default() as i32,
+                message: error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                data: error.get("data").map(|data| data.to_string()),
+            });
+        }
+        Ok(body.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    pub async fn block_number(&self) -> Result<u64, WalletError> {
+        let result = self.call("eth_blockNumber", json!([])).await?;
+        parse_quantity(&result, "blockNumber")
+    }
+
+    /// The receipt of a transaction, None while it is not included in a block
+    pub async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>, WalletError> {
+        let receipt = self.call("eth_getTransactionReceipt", json!([tx_hash])).await?;
+        if receipt.is_null() {
+            return Ok(None);
+        }
+
+        let status = match parse_field(&receipt, "status")? {
+            1 => TransactionStatusEnum::Complete,
+            _ => TransactionStatusEnum::Failed,
+        };
+        Ok(Some(TransactionReceipt {
+            transaction_hash: tx_hash.to_string(),
+            block_number: parse_field(&receipt, "blockNumber")?,
+            from_address_id: parse_address(&receipt, "from").unwrap_or_default(),
+            to_address_id: parse_address(&receipt, "to"),
+            gas_used: parse_field(&receipt, "gasUsed")?,
+            status,
+        }))
+    }
+
+    pub async fn get_transaction(&self, tx_hash: &str) -> Result<Transaction, WalletError> {
+        let transaction = self.call("eth_getTransactionByHash", json!([tx_hash])).await?;
+        if transaction.is_null() {
+            return Err(WalletError::TransactionFailed(format!(
+                "Transaction {} not found",
+                tx_hash
+            )));
+        }
+
+        let status = if transaction.get("blockNumber").map_or(true, Value::is_null) {
+            TransactionStatusEnum::Broadcast
+        } else {
+            match self.get_transaction_receipt(tx_hash).await? {
+                Some(receipt) => receipt.status,
+                None => TransactionStatusEnum::Broadcast,
+            }
+        };
+
+        Ok(Transaction {
+            network_id: network_id(&self.network),
+            from_address_id: parse_address(&transaction, "from").unwrap_or_default(),
+            to_address_id: parse_address(&transaction, "to"),
+            unsigned_payload: parse_address(&transaction, "input").unwrap_or_default(),
+            signed_payload: None,
+            transaction_hash: Some(tx_hash.to_string()),
+            transaction_link: Some(explorer_tx_url(&self.network, tx_hash)),
+            status,
+        })
+    }
+
+    /// Number of blocks on top of the one including the transaction, counting it. 0 while pending.
+    pub async fn get_transaction_confirmations(&self, tx_hash: &str) -> Result<u64, WalletError> {
+        let Some(receipt) = self.get_transaction_receipt(tx_hash).await? else {
+            return Ok(0);
+        };
+        let latest = self.block_number().await?;
+        Ok(latest.saturating_sub(receipt.block_number) + 1)
+    }
+
+    /// Polls for the receipt of a transaction until it is included in a block or `timeout` passes
+    pub async fn wait_for_transaction_receipt(
+        &self,
+        tx_hash: &str,
+        timeout: Duration,
+    ) -> Result<TransactionReceipt, WalletError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Some(receipt) = self.get_transaction_receipt(tx_hash).await? {
+                return Ok(receipt);
+            }
+            if tokio::time::Instant::now() + RECEIPT_POLL_INTERVAL > deadline {
+                return Err(WalletError::MissingTransactionReceipt);
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TX_HASH: &str = "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060";
+
+    fn rpc_body(method: &str) -> mockito::Matcher {
+        mockito::Matcher::PartialJson(json!({ "method": method }))
+    }
+
+    #[tokio::test]
+    async fn test_transaction_receipt_and_confirmations() {
+        let mut server = mockito::Server::new_async().await;
+        let _receipt = server
+            .mock("POST", "/")
+            .match_body(rpc_body("eth_getTransactionReceipt"))
+            .with_body(
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "transactionHash": TX_HASH,
+                        "blockNumber": "0x10",
+                        "from": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+                        "to": "0x036cbd53842c5426634e7929541ec2318f3dcf7e",
+                        "gasUsed": "0x5208",
+                        "status": "0x1"
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let _block_number = server
+            .mock("POST", "/")
+            .match_body(rpc_body("eth_blockNumber"))
+            .with_body(json!({ "jsonrpc": "2.0", "id": 1, "result": "0x12" }).to_string())
+            .create_async()
+            .await;
+
+        let client = EvmRpcClient::new(server.url(), Network::BaseSepolia);
+        let receipt = client.get_transaction_receipt(TX_HASH).await.unwrap().unwrap();
+        assert_eq!(receipt.block_number, 16);
+        assert_eq!(receipt.gas_used, 21000);
+        assert_eq!(receipt.status, TransactionStatusEnum::Complete);
+        assert_eq!(client.get_transaction_confirmations(TX_HASH).await.unwrap(), 3);
+        assert_eq!(
+            client
+                .wait_for_transaction_receipt(TX_HASH, Duration::from_secs(1))
+                .await
+                .unwrap(),
+            receipt
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_and_reverted_transactions() {
+        let mut server = mockito::Server::new_async().await;
+        let pending = server
+            .mock("POST", "/")
+            .match_body(rpc_body("eth_getTransactionReceipt"))
+            .with_body(json!({ "jsonrpc": "2.0", "id": 1, "result": null }).to_string())
+            .create_async()
+            .await;
+
+        let client = EvmRpcClient::new(server.url(), Network::BaseSepolia);
+        assert_eq!(client.get_transaction_receipt(TX_HASH).await.unwrap(), None);
+        assert_eq!(client.get_transaction_confirmations(TX_HASH).await.unwrap(), 0);
+        assert!(matches!(
+            client.wait_for_transaction_receipt(TX_HASH, Duration::ZERO).await,
+            Err(WalletError::MissingTransactionReceipt)
+        ));
+        pending.remove_async().await;
+
+        let _reverted = server
+            .mock("POST", "/")
+            .match_body(rpc_body("eth_getTransactionReceipt"))
+            .with_body(
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": { "blockNumber": "0x10", "gasUsed": "0x5208", "status": "0x0" }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let receipt = client.get_transaction_receipt(TX_HASH).await.unwrap().unwrap();
+        assert_eq!(receipt.status, TransactionStatusEnum::Failed);
+
+        let _error = server
+            .mock("POST", "/")
+            .match_body(rpc_body("eth_getTransactionByHash"))
+            .with_body(
+                json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32602, "message": "invalid hash" } })
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+        assert!(matches!(
+            client.get_transaction(TX_HASH).await,
+            Err(WalletError::DetailedJsonRpcError { code: -32602, .. })
+        ));
+    }
+}
@@ -2,15 +2,17 @@ use serde::{Deserialize, Serialize};
 use hanzo_messages::schemas::hanzo_name::HanzoName;
 use hanzo_messages::schemas::wallet_complementary::WalletSource;
 use hanzo_messages::schemas::wallet_mixed::{
-    Address, AddressBalanceList, Asset, Balance, PublicAddress, Transaction,
+    Address, AddressBalanceList, Asset, Balance, PublicAddress, Transaction, TransactionReceipt,
 };
 use hanzo_messages::schemas::x402_types::{Network, PaymentRequirements};
 use hanzo_runtime::functions::x402;
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
 
 use crate::wallet::wallet_error::WalletError;
 
+use super::evm_rpc::{rpc_url_for_network, EvmRpcClient};
 use super::wallet_manager::WalletEnum;
 use super::wallet_traits::{CommonActions, IsWallet, PaymentWallet, ReceivingWallet, SendActions, TransactionHash};
 use hanzo_runtime::functions::ethers_wallet::create_wallet::{
@@ -30,6 +32,9 @@ pub struct LocalEthersWallet {
     pub private_key: String,
     pub public_key: String,
     pub mnemonic: Option<String>,
+    /// RPC endpoint used instead of the public one of the network (e.g. a local anvil node).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpc_url: Option<String>,
 }
 
 impl LocalEthersWallet {
@@ -56,6 +61,7 @@ impl LocalEthersWallet {
             private_key: wallet.private_key,
             public_key: wallet.public_key,
             mnemonic: wallet.mnemonic,
+            rpc_url: None,
         })
     }
 
@@ -89,6 +95,7 @@ impl LocalEthersWallet {
             private_key: wallet.private_key,
             public_key: wallet.public_key.unwrap_or_default(),
             mnemonic: wallet.mnemonic,
+            rpc_url: None,
         })
     }
 
@@ -121,14 +128,12 @@ impl LocalEthersWallet {
         unimplemented!()
     }
 
-    // TODO: move this to a config file or merge it to Network struct
-    fn rpc_url_for_network(network: &Network) -> String {
-        match network {
-            Network::BaseSepolia => "https://sepolia.base.org".to_string(),
-            Network::Base => "https://mainnet.base.org".to_string(),
-            Network::AvalancheFuji => "https://api.avax-test.network/ext/bc/C/rpc".to_string(),
-            Network::Avalanche => "https://api.avax.network/ext/bc/C/rpc".to_string(),
-        }
+    fn rpc_url(&self, network: &Network) -> String {
+        self.rpc_url.clone().unwrap_or_else(|| rpc_url_for_network(network))
+    }
+
+    fn rpc_client(&self) -> EvmRpcClient {
+        EvmRpcClient::new(self.rpc_url(&self.network), self.network.clone())
     }
 }
 
@@ -206,10 +211,9 @@ impl CommonActions for LocalEthersWallet {
         _node_name: HanzoName,
     ) -> Pin<Box<dyn Future<Output = Result<f64, WalletError>> + Send + 'static>> {
         let address_id = self.address.address_id.clone();
-        let network = self.network.clone();
+        let rpc_url = self.rpc_url(&self.network);
 
         Box::pin(async move {
-            let rpc_url = Self::rpc_url_for_network(&network);
             let input = get_balance::Input {
                 token_address: None,
                 wallet_address: address_id,
@@ -237,9 +241,9 @@ impl CommonActions for LocalEthersWallet {
     ) -> Pin<Box<dyn Future<Output = Result<AddressBalanceList, WalletError>> + Send + 'static>> {
         let address_id = self.address.address_id.clone();
         let network = self.network.clone();
+        let rpc_url = self.rpc_url(&network);
 
         Box::pin(async move {
-            let rpc_url = Self::rpc_url_for_network(&network);
             let mut data: Vec<Balance> = Vec::new();
 
             // Main token balance (ETH)
@@ -306,8 +310,9 @@ impl CommonActions for LocalEthersWallet {
         asset: Asset,
         _node_name: HanzoName,
     ) -> Pin<Box<dyn Future<Output = Result<Balance, WalletError>> + Send + 'static>> {
+        let rpc_url = self.rpc_url(&asset.network_id);
+
         Box::pin(async move {
-            let rpc_url = Self::rpc_url_for_network(&asset.network_id);
             let token_address = asset.contract_address.clone().unwrap_or_else(|| asset.asset_id.clone());
             let input = get_balance::Input {
                 token_address: Some(token_address),
@@ -330,4 +335,37 @@ impl CommonActions for LocalEthersWallet {
             })
         })
     }
+
+    fn get_transaction(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Pin<Box<dyn Future<Output = Result<Transaction, WalletError>> + Send + 'static>> {
+        let client = self.rpc_client();
+        Box::pin(async move { client.get_transaction(&tx_hash).await })
+    }
+
+    fn get_transaction_confirmations(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Pin<Box<dyn Future<Output = Result<u64, WalletError>> + Send + 'static>> {
+        let client = self.rpc_client();
+        Box::pin(async move { client.get_transaction_confirmations(&tx_hash).await })
+    }
+
+    fn get_transaction_receipt(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<TransactionReceipt>, WalletError>> + Send + 'static>> {
+        let client = self.rpc_client();
+        Box::pin(async move { client.get_transaction_receipt(&tx_hash).await })
+    }
+
+    fn wait_for_transaction_receipt(
+        &self,
+        tx_hash: TransactionHash,
+        timeout: Duration,
+    ) -> Pin<Box<dyn Future<Output = Result<TransactionReceipt, WalletError>> + Send + 'static>> {
+        let client = self.rpc_client();
+        Box::pin(async move { client.wait_for_transaction_receipt(&tx_hash, timeout).await })
+    }
 }
@@ -1,5 +1,7 @@
 pub mod coinbase_mpc_wallet;
+pub mod evm_rpc;
 pub mod local_ether_wallet;
+pub mod payment_reconciler;
 pub mod wallet_error;
 pub mod wallet_manager;
 pub mod wallet_traits;
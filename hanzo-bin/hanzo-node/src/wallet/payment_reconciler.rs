@@ -0,0 +1,297 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Weak;
+use std::time::Duration;
+
+use hanzo_db_sqlite::SqliteManager;
+use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
+use hanzo_messages::schemas::hanzo_name::HanzoName;
+use hanzo_messages::schemas::invoices::{Invoice, InvoiceStatusEnum, PaymentStatusEnum};
+use hanzo_messages::schemas::wallet_mixed::{TransactionReceipt, TransactionStatusEnum};
+use tokio::sync::Mutex;
+
+use super::wallet_error::WalletError;
+use super::wallet_manager::WalletManager;
+use super::wallet_traits::CommonActions;
+
+/// Follows the settlement transaction of every signed payment until it is confirmed or fails,
+/// and updates the payment and its invoice accordingly. Runs on both sides of a payment: the
+/// requester looks the transaction up with its payment wallet, the provider with its receiving wallet.
+pub struct PaymentReconciler;
+
+impl PaymentReconciler {
+    fn reconcile_interval_time() -> u64 {
+        std::env::var("PAYMENT_RECONCILE_INTERVAL_TIME")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()
+            .unwrap_or(30)
+    }
+
+    fn required_confirmations() -> u64 {
+        std::env::var("PAYMENT_REQUIRED_CONFIRMATIONS")
+            .unwrap_or_else(|_| "1".to_string())
+            .parse()
+            .unwrap_or(1)
+    }
+
+    /// Spawns the loop that reconciles the signed payments each `PAYMENT_RECONCILE_INTERVAL_TIME` seconds
+    pub fn start(
+        db: Weak<SqliteManager>,
+        wallet_manager: Weak<Mutex<Option<WalletManager>>>,
+        node_name: HanzoName,
+    ) -> tokio::task::JoinHandle<()> {
+        let interval_time = Self::reconcile_interval_time();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_time));
+            loop {
+                interval.tick().await;
+
+                let (Some(db), Some(wallet_manager)) = (db.upgrade(), wallet_manager.upgrade()) else {
+                    hanzo_log(
+                        HanzoLogOption::Blockchain,
+                        HanzoLogLevel::Error,
+                        "Database or wallet manager is gone, stopping the payment reconciler",
+                    );
+                    return;
+                };
+                if let Err(e) = Self::reconcile_payments(&db, &wallet_manager, &node_name).await {
+                    hanzo_log(
+                        HanzoLogOption::Blockchain,
+                        HanzoLogLevel::Error,
+                        &format!("Failed to reconcile payments: {}", e),
+                    );
+                }
+            }
+        })
+    }
+
+    /// Checks every signed payment with a known transaction once. Returns the number of invoices updated.
+    pub async fn reconcile_payments(
+        db: &SqliteManager,
+        wallet_manager: &Mutex<Option<WalletManager>>,
+        node_name: &HanzoName,
+    ) -> Result<usize, WalletError> {
+        let invoices = db
+            .get_all_invoices()
+            .map_err(|e| WalletError::SqliteManagerError(e.to_string()))?;
+        let required_confirmations = Self::required_confirmations();
+
+        let mut updated = 0;
+        for mut invoice in invoices {
+            let Some(tx_hash) = Self::pending_transaction(&invoice) else {
+                continue;
+            };
+            let paid_by_us = invoice.requester_name.get_node_name_string() == node_name.get_node_name_string();
+
+            // The lock is released before the lookups reach the RPC node
+            let lookups = {
+                let wallet_manager = wallet_manager.lock().await;
+                let Some(wallet_manager) = wallet_manager.as_ref() else {
+                    return Ok(updated);
+                };
+                if paid_by_us {
+                    TransactionLookups::new(wallet_manager.payment_wallet.as_ref(), &tx_hash)
+                } else {
+                    TransactionLookups::new(wallet_manager.receiving_wallet.as_ref(), &tx_hash)
+                }
+            };
+
+            let payment_status = match lookups.payment_status(required_confirmations).await {
+                Ok(Some(payment_status)) => payment_status,
+                Ok(None) => continue,
+                Err(e) => {
+                    hanzo_log(
+                        HanzoLogOption::Blockchain,
+                        HanzoLogLevel::Error,
+                        &format!(
+                            "Failed to check transaction {} of invoice {}: {}",
+                            tx_hash, invoice.invoice_id, e
+                        ),
+                    );
+                    continue;
+                }
+            };
+
+            Self::apply_payment_status(&mut invoice, payment_status);
+            db.set_invoice(&invoice)
+                .map_err(|e| WalletError::SqliteManagerError(e.to_string()))?;
+            hanzo_log(
+                HanzoLogOption::Blockchain,
+                HanzoLogLevel::Info,
+                &format!(
+                    "Payment of invoice {} is {:?} (transaction {})",
+                    invoice.invoice_id, invoice.status, tx_hash
+                ),
+            );
+            updated += 1;
+        }
+        Ok(updated)
+    }
+
+    /// Hash of the transaction of a payment still waiting for confirmation
+    fn pending_transaction(invoice: &Invoice) -> Option<String> {
+        let payment = invoice.payment.as_ref()?;
+        if payment.status != PaymentStatusEnum::Signed {
+            return None;
+        }
+        payment.transaction_hash.clone()
+    }
+
+    /// Looks up a transaction and decides the status of its payment, None while it is not final
+    pub async fn check_transaction<W: CommonActions + ?Sized>(
+        wallet: &W,
+        tx_hash: &str,
+        required_confirmations: u64,
+    ) -> Result<Option<PaymentStatusEnum>, WalletError> {
+        TransactionLookups::new(wallet, tx_hash)
+            .payment_status(required_confirmations)
+            .await
+    }
+
+    /// A reverted transaction fails the payment right away, a successful one confirms it once
+    /// enough blocks are on top of it
+    pub fn payment_status(
+        receipt: Option<&TransactionReceipt>,
+        confirmations: u64,
+        required_confirmations: u64,
+    ) -> Option<PaymentStatusEnum> {
+        match receipt.map(|receipt| &receipt.status) {
+            Some(TransactionStatusEnum::Failed) => Some(PaymentStatusEnum::Failed),
+            Some(TransactionStatusEnum::Complete) if confirmations >= required_confirmations => {
+                Some(PaymentStatusEnum::Confirmed)
+            }
+            _ => None,
+        }
+    }
+
+    /// A confirmed payment marks the invoice paid, unless the provider has already processed it
+    fn apply_payment_status(invoice: &mut Invoice, payment_status: PaymentStatusEnum) {
+        match payment_status {
+            PaymentStatusEnum::Failed => invoice.update_status(InvoiceStatusEnum::Failed),
+            PaymentStatusEnum::Confirmed if invoice.status != InvoiceStatusEnum::Processed => {
+                invoice.update_status(InvoiceStatusEnum::Paid)
+            }
+            _ => {}
+        }
+        if let Some(payment) = invoice.payment.as_mut() {
+            payment.status = payment_status;
+        }
+    }
+}
+
+type WalletLookup<T> = Pin<Box<dyn Future<Output = Result<T, WalletError>> + Send>>;
+
+/// The RPC requests that look a transaction up. They don't borrow the wallet, so they can be
+/// awaited once the wallet manager is unlocked.
+struct TransactionLookups {
+    receipt: WalletLookup<Option<TransactionReceipt>>,
+    confirmations: WalletLookup<u64>,
+}
+
+impl TransactionLookups {
+    fn new<W: CommonActions + ?Sized>(wallet: &W, tx_hash: &str) -> Self {
+        TransactionLookups {
+            receipt: wallet.get_transaction_receipt(tx_hash.to_string()),
+            confirmations: wallet.get_transaction_confirmations(tx_hash.to_string()),
+        }
+    }
+
+    /// The status of the payment, None while it is not final. Confirmations are only looked up
+    /// for a successful transaction.
+    async fn payment_status(self, required_confirmations: u64) -> Result<Option<PaymentStatusEnum>, WalletError> {
+        let receipt = self.receipt.await?;
+        let confirmations = match &receipt {
+            Some(receipt) if receipt.status == TransactionStatusEnum::Complete => self.confirmations.await?,
+            _ => 0,
+        };
+        Ok(PaymentReconciler::payment_status(
+            receipt.as_ref(),
+            confirmations,
+            required_confirmations,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::local_ether_wallet::LocalEthersWallet;
+    use hanzo_messages::schemas::wallet_mixed::Address;
+    use hanzo_messages::schemas::x402_types::Network;
+    use serde_json::json;
+
+    const TX_HASH: &str = "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060";
+
+    fn wallet(rpc_url: String) -> LocalEthersWallet {
+        let address_id = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266".to_string();
+        LocalEthersWallet {
+            id: address_id.clone(),
+            network: Network::BaseSepolia,
+            address: Address {
+                wallet_id: address_id.clone(),
+                network_id: Network::BaseSepolia,
+                public_key: None,
+                address_id,
+            },
+            private_key: String::new(),
+            public_key: String::new(),
+            mnemonic: None,
+            rpc_url: Some(rpc_url),
+        }
+    }
+
+    async fn mock_rpc(server: &mut mockito::ServerGuard, method: &str, result: serde_json::Value) -> mockito::Mock {
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(json!({ "method": method })))
+            .with_body(json!({ "jsonrpc": "2.0", "id": 1, "result": result }).to_string())
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_check_transaction() {
+        let mut server = mockito::Server::new_async().await;
+        let wallet = wallet(server.url());
+
+        let pending = mock_rpc(&mut server, "eth_getTransactionReceipt", serde_json::Value::Null).await;
+        assert_eq!(
+            PaymentReconciler::check_transaction(&wallet, TX_HASH, 2).await.unwrap(),
+            None
+        );
+        pending.remove_async().await;
+
+        let receipt = json!({ "blockNumber": "0x10", "gasUsed": "0x5208", "status": "0x1" });
+        let _receipt = mock_rpc(&mut server, "eth_getTransactionReceipt", receipt).await;
+        let latest = mock_rpc(&mut server, "eth_blockNumber", json!("0x10")).await;
+        assert_eq!(
+            PaymentReconciler::check_transaction(&wallet, TX_HASH, 2).await.unwrap(),
+            None
+        );
+        latest.remove_async().await;
+
+        let _latest = mock_rpc(&mut server, "eth_blockNumber", json!("0x11")).await;
+        assert_eq!(
+            PaymentReconciler::check_transaction(&wallet, TX_HASH, 2).await.unwrap(),
+            Some(PaymentStatusEnum::Confirmed)
+        );
+    }
+
+    #[test]
+    fn test_reverted_transaction_fails_the_payment() {
+        let receipt = TransactionReceipt {
+            transaction_hash: TX_HASH.to_string(),
+            block_number: 16,
+            from_address_id: String::new(),
+            to_address_id: None,
+            gas_used: 21000,
+            status: TransactionStatusEnum::Failed,
+        };
+        assert_eq!(
+            PaymentReconciler::payment_status(Some(&receipt), 0, 1),
+            Some(PaymentStatusEnum::Failed)
+        );
+        assert_eq!(PaymentReconciler::payment_status(None, 0, 1), None);
+    }
+}
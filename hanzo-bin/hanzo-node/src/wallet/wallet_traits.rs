@@ -1,12 +1,13 @@
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
 
 use super::{wallet_error::WalletError, wallet_manager::WalletEnum};
 
 use downcast_rs::{impl_downcast, Downcast};
 use hanzo_messages::schemas::{
     hanzo_name::HanzoName,
-    wallet_mixed::{Address, AddressBalanceList, Asset, Balance, PublicAddress, Transaction},
+    wallet_mixed::{Address, AddressBalanceList, Asset, Balance, PublicAddress, Transaction, TransactionReceipt},
     x402_types::{Network, PaymentRequirements},
 };
 use hanzo_runtime::functions::x402;
@@ -63,11 +64,32 @@ pub trait CommonActions {
         node_name: HanzoName,
     ) -> Pin<Box<dyn Future<Output = Result<Balance, WalletError>> + Send>>;
 
+    fn get_transaction(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Pin<Box<dyn Future<Output = Result<Transaction, WalletError>> + Send>>;
+
+    /// Number of blocks confirming the transaction, 0 while it is not included in a block.
+    fn get_transaction_confirmations(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Pin<Box<dyn Future<Output = Result<u64, WalletError>> + Send>>;
+
+    /// The receipt of the transaction, None while it is not included in a block.
+    fn get_transaction_receipt(
+        &self,
+        tx_hash: TransactionHash,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<TransactionReceipt>, WalletError>> + Send>>;
+
+    /// Waits until the transaction is included in a block, failing with `MissingTransactionReceipt`
+    /// after `timeout`.
+    fn wait_for_transaction_receipt(
+        &self,
+        tx_hash: TransactionHash,
+        timeout: Duration,
+    ) -> Pin<Box<dyn Future<Output = Result<TransactionReceipt, WalletError>> + Send>>;
+
     // fn get_main_balance(&self) -> Result<Balance, WalletError>;
-    // fn get_transaction(&self, tx_hash: String) -> Result<Transaction, WalletError>;
-    // fn get_transaction_confirmations(&self, tx_hash: String) -> Result<u64, WalletError>;
-    // fn get_transaction_receipt(&self, tx_hash: String) -> Result<Transaction, WalletError>;
-    // fn wait_for_transaction_receipt(&self, tx_hash: String) -> Result<Transaction, WalletError>;
     // fn watch_pending_transactions(&self) -> Result<Vec<Transaction>, WalletError>;
     // fn send_raw_transaction(&self, raw_tx: String) -> Result<(), WalletError>;
     // fn prepare_transaction_request(
@@ -0,0 +1,128 @@
+use hanzo_messages::schemas::hanzo_name::HanzoName;
+use hanzo_messages::schemas::job_config::{JobConfig, SpendingBudget};
+use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{
+    LLMProviderInterface, OpenAI, SerializedLLMProvider,
+};
+use hanzo_test_framework::{run_test_one_node_network, TestConfig, TestContext};
+use std::time::Duration;
+
+use super::utils::node_test_api::wait_for_default_tools;
+use mockito::Server;
+
+#[test]
+fn job_over_budget_stops_before_calling_the_provider() {
+    std::env::set_var("WELCOME_MESSAGE", "false");
+    std::env::set_var("SKIP_IMPORT_FROM_DIRECTORY", "true");
+    std::env::set_var("IS_TESTING", "1");
+    let mut server = Server::new();
+
+    // The response doesn't report usage, so the node records an estimate of it
+    let responses_mock = server
+        .mock("POST", "/v1/responses")
+        .match_header("authorization", "Bearer mockapikey")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{
+                "id": "resp_test123",
+                "object": "response",
+                "created_at": 1677652288,
+                "status": "completed",
+                "background": false,
+                "error": null,
+                "model": "gpt-4-turbo",
+                "output": [
+                    {
+                        "id": "msg_test123",
+                        "type": "message",
+                        "status": "completed",
+                        "content": [
+                            {
+                                "type": "output_text",
+                                "annotations": [],
+                                "text": "This is a test response from the mock server"
+                            }
+                        ],
+                        "role": "assistant"
+                    }
+                ]
+            }"#,
+        )
+        .expect(1)
+        .create();
+
+    let _embeddings_mock = server
+        .mock("POST", "/api/embeddings")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{\"embedding\": [0.0,0.0,0.0]}")
+        .create();
+
+    let server_url = server.url();
+
+    let config = TestConfig::default()
+        .with_mock_openai(server_url.clone())
+        .with_mock_embeddings(server_url.clone());
+
+    run_test_one_node_network(config, move |ctx: TestContext| {
+        Box::pin(async move {
+            ctx.register_device().await.unwrap();
+            let tools_ready = wait_for_default_tools(ctx.commands.clone(), ctx.api_key.clone(), 120)
+                .await
+                .unwrap();
+            assert!(tools_ready);
+
+            let agent_name =
+                HanzoName::new(format!("{}/{}/agent/test_agent", ctx.identity_name, ctx.profile_name).to_string())
+                    .unwrap();
+
+            let agent = SerializedLLMProvider {
+                id: "test_agent".to_string(),
+                full_identity_name: agent_name,
+                name: Some("Test Agent".to_string()),
+                description: Some("Test Agent Description".to_string()),
+                external_url: Some(server_url.clone()),
+                api_key: Some("mockapikey".to_string()),
+                model: LLMProviderInterface::OpenAI(OpenAI {
+                    model_type: "gpt-4-turbo".to_string(),
+                }),
+            };
+
+            ctx.register_llm_provider(agent).await.unwrap();
+
+            let agent_sub = format!("{}/agent/test_agent", ctx.profile_name);
+            let job_id = ctx.create_job(&agent_sub).await.unwrap();
+
+            ctx.update_job_config(
+                &job_id,
+                JobConfig {
+                    stream: Some(false),
+                    budget: Some(SpendingBudget {
+                        max_tokens: Some(1),
+                        ..Default::default()
+                    }),
+                    ..JobConfig::empty()
+                },
+            )
+            .await
+            .unwrap();
+
+            // Nothing has been spent yet, so the first message reaches the provider
+            ctx.send_job_message(&job_id, "This is a test message").await.unwrap();
+            let response = ctx.wait_for_response(Duration::from_secs(10)).await.unwrap();
+            assert!(response.contains("This is a test response from the mock server"));
+
+            // The estimated usage of the first call exhausts the budget
+            ctx.send_job_message(&job_id, "This is another test message")
+                .await
+                .unwrap();
+            let response = ctx.wait_for_response(Duration::from_secs(10)).await.unwrap();
+            assert!(response.contains("token budget exhausted"));
+
+            ctx.abort_handle.abort();
+        })
+    });
+
+    // The provider was only called for the first message
+    responses_mock.assert();
+}
@@ -203,6 +203,7 @@ fn tool_duplicate_tests() {
                     runner: RunnerType::OnlyHost,
                     operating_system: vec![OperatingSystem::Windows],
                     tool_set: None,
+                    permissions: None,
                 };
                 eprintln!("\nCreate a tool");
                 let (res_sender, res_receiver) = async_channel::bounded(1);
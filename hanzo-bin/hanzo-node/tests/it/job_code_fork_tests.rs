@@ -221,6 +221,7 @@ fn test_job_code_fork() {
                         runner: RunnerType::Any,
                         operating_system: vec![OperatingSystem::Linux, OperatingSystem::MacOS, OperatingSystem::Windows],
                         tool_set: None,
+                        permissions: None,
                     },
                     tool_router_key: None,
                     job_id: job_id.clone(),
@@ -251,6 +251,7 @@ fn native_tool_test_knowledge() {
                         vector_fs_items: vec![],
                         vector_fs_folders: vec![vector_fs_folder],
                         vector_search_mode: VectorSearchMode::FillUpTo25k,
+                        mcp_resources: vec![],
                     };
 
                     job_id = api_create_job_with_scope(
@@ -168,6 +168,7 @@ fn test_tool_execution_with_config_override() {
                 node1_identity_name.to_string(),
                 None,
                 None,
+                false,
             )
             .await
             {
@@ -69,6 +69,7 @@ pub async fn upload_file(
             file: file_data,
             path: folder_name.to_string(),
             file_datetime: Some(Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap()),
+            chunking_strategy: None,
             res: res_sender,
         })
         .await
@@ -110,6 +111,7 @@ pub async fn upload_file_to_job(
             filename, // Use the extracted filename
             file: file_data,
             file_datetime: Some(Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap()),
+            chunking_strategy: None,
             res: res_sender,
         })
         .await
@@ -10,6 +10,7 @@ mod it {
     mod db_llm_providers_tests;
     mod db_restore_tests;
     mod job_branchs_retries_tests;
+    mod job_budget_tests;
     mod job_code_fork_tests;
     mod job_concurrency_in_seq_tests;
     mod job_fork_messages_tests;
@@ -3,7 +3,9 @@
 //! DuckDB is an embedded analytical database optimized for OLAP workloads.
 //! It provides excellent performance for analytics, aggregations, and complex queries.
 
-use crate::{DatabaseBackend, HanzoDbConfig, HanzoDbError, Result};
+use crate::sql;
+use crate::{DatabaseBackend, HanzoDbConfig, HanzoDbError, Query, QueryResult, Record, Result, Transaction, TransactionHandle};
+use crate::Value as DbValue;
 use async_trait::async_trait;
 use duckdb::{Connection, params};
 use serde_json::Value;
@@ -56,6 +58,25 @@ impl DuckDbBackend {
         })
     }
 
+    /// Begin a transaction on a cloned connection to the same database
+    pub fn begin_transaction(&self) -> Result<Transaction> {
+        let connection = self.connection.lock()
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?
+            .try_clone()
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
+
+        connection.execute_batch("BEGIN TRANSACTION")
+            .map_err(|e| HanzoDbError::TransactionError(e.to_string()))?;
+
+        Ok(Transaction::new(
+            DatabaseBackend::DuckDB,
+            Box::new(DuckDbTransaction {
+                connection: Arc::new(Mutex::new(connection)),
+                finished: false,
+            }),
+        ))
+    }
+
     /// Execute analytical query
     pub fn analyze(&self, query: &str) -> Result<Vec<Value>> {
         let conn = self.connection.lock()
@@ -370,6 +391,126 @@ impl DatabaseBackend for DuckDbBackend {
     }
 }
 
+/// DuckDB transaction on its own connection
+pub struct DuckDbTransaction {
+    connection: Arc<Mutex<Connection>>,
+    finished: bool,
+}
+
+impl DuckDbTransaction {
+    fn finish(&mut self, statement: &str) -> Result<()> {
+        let conn = self.connection.lock()
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
+        conn.execute_batch(statement)
+            .map_err(|e| HanzoDbError::TransactionError(e.to_string()))?;
+        drop(conn);
+
+        self.finished = true;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TransactionHandle for DuckDbTransaction {
+    async fn insert(&mut self, table: &str, data: &[Record]) -> Result<()> {
+        let conn = self.connection.lock()
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
+
+        for record in data {
+            let statement = sql::insert(table, record, sql::dollar_placeholder);
+            let values: Vec<duckdb::types::Value> = statement.params.iter().map(to_duckdb_value).collect();
+            conn.execute(&statement.sql, duckdb::params_from_iter(values))
+                .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    async fn query(&mut self, query: Query) -> Result<QueryResult> {
+        let conn = self.connection.lock()
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
+
+        let statement = sql::select(&query, sql::dollar_placeholder);
+        let values: Vec<duckdb::types::Value> = statement.params.iter().map(to_duckdb_value).collect();
+
+        let mut stmt = conn.prepare(&statement.sql)
+            .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+        let rows = stmt.query_map(duckdb::params_from_iter(values), row_to_record)
+            .map_err(|e| HanzoDbError::QueryError(e.to_string()))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+
+        let columns = rows.first()
+            .map(|row: &Record| row.values.iter().map(|(name, _)| name.clone()).collect())
+            .unwrap_or_default();
+
+        Ok(QueryResult {
+            columns,
+            row_count: rows.len(),
+            rows,
+        })
+    }
+
+    async fn commit(mut self: Box<Self>) -> Result<()> {
+        self.finish("COMMIT")
+    }
+
+    async fn rollback(mut self: Box<Self>) -> Result<()> {
+        self.finish("ROLLBACK")
+    }
+}
+
+impl Drop for DuckDbTransaction {
+    fn drop(&mut self) {
+        if !self.finished {
+            if let Ok(conn) = self.connection.lock() {
+                let _ = conn.execute_batch("ROLLBACK");
+            }
+        }
+    }
+}
+
+fn to_duckdb_value(value: &DbValue) -> duckdb::types::Value {
+    match value {
+        DbValue::Null => duckdb::types::Value::Null,
+        DbValue::Bool(b) => duckdb::types::Value::Boolean(*b),
+        DbValue::Int32(i) => duckdb::types::Value::Int(*i),
+        DbValue::Int64(i) | DbValue::Timestamp(i) => duckdb::types::Value::BigInt(*i),
+        DbValue::Float32(f) => duckdb::types::Value::Float(*f),
+        DbValue::Float64(f) => duckdb::types::Value::Double(*f),
+        DbValue::String(s) => duckdb::types::Value::Text(s.clone()),
+        DbValue::Binary(b) => duckdb::types::Value::Blob(b.clone()),
+        DbValue::Vector(v) => duckdb::types::Value::List(v.iter().map(|f| duckdb::types::Value::Float(*f)).collect()),
+        DbValue::Json(j) => duckdb::types::Value::Text(j.to_string()),
+    }
+}
+
+// Helper function to convert row to a record, probing types like `row_to_json`
+fn row_to_record(row: &duckdb::Row) -> std::result::Result<Record, duckdb::Error> {
+    let mut values = Vec::new();
+
+    for i in 0..row.column_count() {
+        let column_name = row.column_name(i)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|_| format!("column_{}", i));
+
+        let value = if let Ok(val) = row.get::<_, String>(i) {
+            DbValue::String(val)
+        } else if let Ok(val) = row.get::<_, i64>(i) {
+            DbValue::Int64(val)
+        } else if let Ok(val) = row.get::<_, f64>(i) {
+            DbValue::Float64(val)
+        } else if let Ok(val) = row.get::<_, bool>(i) {
+            DbValue::Bool(val)
+        } else {
+            DbValue::Null
+        };
+        values.push((column_name, value));
+    }
+
+    Ok(Record { values })
+}
+
 // Helper function to convert JSON to DuckDB value
 fn json_to_duckdb_value(value: &Value) -> duckdb::types::Value {
     match value {
@@ -18,8 +18,8 @@ use std::path::PathBuf;
 use log::{debug, info};
 
 use crate::{
-    HanzoDatabase, HanzoDbConfig, TableSchema, Query, QueryResult, 
-    VectorQuery, SearchResult, Transaction, DatabaseStats, Record, 
+    HanzoDatabase, HanzoDbConfig, HanzoDbError, TableSchema, Query, QueryResult, 
+    VectorQuery, SearchResult, Transaction, TransactionHandle, DatabaseStats, Record, 
     Value, DataType, Column, Filter, OrderBy, Index, IndexType, DistanceMetric
 };
 
@@ -371,14 +371,15 @@ impl HanzoDatabase for LanceDbBackend {
     }
     
     async fn begin_transaction(&self) -> Result<Transaction> {
-        // LanceDB handles transactions automatically
-        // This is a simplified implementation
-        Ok(Transaction {
-            inner: Arc::new(tokio::sync::RwLock::new(crate::TransactionInner {
-                backend: crate::DatabaseBackend::LanceDB,
-                handle: Box::new(()),
-            })),
-        })
+        let backend = LanceDbBackend {
+            connection: self.connection.clone(),
+            path: self.path.clone(),
+            config: self.config.clone(),
+        };
+        Ok(Transaction::new(
+            crate::DatabaseBackend::LanceDB,
+            Box::new(LanceDbTransaction { backend, table: None, pending: Vec::new() }),
+        ))
     }
     
     async fn optimize(&self) -> Result<()> {
@@ -473,6 +474,56 @@ impl LanceDbBackend {
     }
 }
 
+/// LanceDB transaction
+///
+/// LanceDB commits each write as a new table version and has no multi-statement
+/// transactions, so inserts are buffered and written on commit as a single version.
+/// That is only atomic within one table, so a transaction is bound to the first
+/// table it writes to. Queries inside the transaction only see committed data.
+pub struct LanceDbTransaction {
+    backend: LanceDbBackend,
+    table: Option<String>,
+    pending: Vec<Record>,
+}
+
+#[async_trait]
+impl TransactionHandle for LanceDbTransaction {
+    async fn insert(&mut self, table: &str, data: &[Record]) -> Result<()> {
+        match &self.table {
+            Some(current) if current != table => {
+                return Err(HanzoDbError::NotImplemented(format!(
+                    "LanceDB transactions cannot span tables: already writing to {}, got {}",
+                    current, table
+                ))
+                .into());
+            }
+            Some(_) => {}
+            None => self.table = Some(table.to_string()),
+        }
+        self.pending.extend_from_slice(data);
+        Ok(())
+    }
+
+    async fn query(&mut self, query: Query) -> Result<QueryResult> {
+        self.backend.query(query).await
+    }
+
+    async fn commit(self: Box<Self>) -> Result<()> {
+        if let Some(table) = &self.table {
+            // One insert is one table version, so either all records land or none do
+            self.backend.insert(table, &self.pending).await
+                .context(format!("Failed to commit insert into {}", table))?;
+        }
+        debug!("✅ Committed {} buffered records", self.pending.len());
+        Ok(())
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<()> {
+        debug!("Discarding {} buffered records", self.pending.len());
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,10 +3,12 @@
 //! PostgreSQL is a powerful, open-source relational database with excellent
 //! ACID compliance, rich SQL features, and extension support (including pgvector).
 
-use crate::{DatabaseBackend, HanzoDbConfig, HanzoDbError, Result};
+use crate::sql;
+use crate::{DatabaseBackend, HanzoDbConfig, HanzoDbError, Query, QueryResult, Record, Result, Transaction, TransactionHandle};
+use crate::Value as DbValue;
 use async_trait::async_trait;
 use serde_json::Value;
-use sqlx::{postgres::{PgPoolOptions, PgPool}, Row};
+use sqlx::{postgres::{PgArguments, PgPoolOptions, PgPool, PgRow}, Column, Postgres, Row};
 use std::time::Duration;
 
 /// PostgreSQL backend for transactional workloads
@@ -91,10 +93,17 @@ impl PostgresBackend {
     }
 
     /// Begin transaction
-    pub async fn begin_transaction(&self) -> Result<sqlx::Transaction<'_, sqlx::Postgres>> {
-        self.pool.begin()
+    pub async fn begin_transaction(&self) -> Result<Transaction> {
+        let tx = self.begin().await?;
+        Ok(Transaction::new(DatabaseBackend::PostgreSQL, Box::new(PostgresTransaction { tx })))
+    }
+
+    /// Begin a raw sqlx transaction on a pooled connection
+    async fn begin(&self) -> Result<sqlx::Transaction<'static, Postgres>> {
+        let tx = self.pool.begin()
             .await
-            .map_err(|e| HanzoDbError::TransactionError(e.to_string()))
+            .map_err(|e| HanzoDbError::TransactionError(e.to_string()))?;
+        Ok(tx)
     }
 
     /// Execute raw SQL with parameters
@@ -258,7 +267,7 @@ impl DatabaseBackend for PostgresBackend {
     }
 
     async fn insert(&self, table: &str, records: &[Value]) -> Result<()> {
-        let mut tx = self.begin_transaction().await?;
+        let mut tx = self.begin().await?;
 
         for record in records {
             let obj = record.as_object()
@@ -465,6 +474,113 @@ impl DatabaseBackend for PostgresBackend {
     }
 }
 
+/// PostgreSQL transaction holding a pooled connection until commit or rollback
+///
+/// sqlx rolls the transaction back if it is dropped while still open.
+pub struct PostgresTransaction {
+    tx: sqlx::Transaction<'static, Postgres>,
+}
+
+#[async_trait]
+impl TransactionHandle for PostgresTransaction {
+    async fn insert(&mut self, table: &str, data: &[Record]) -> Result<()> {
+        for record in data {
+            let statement = sql::insert(table, record, sql::dollar_placeholder);
+            let mut query = sqlx::query(&statement.sql);
+            for value in &statement.params {
+                query = bind_value(query, value);
+            }
+            query.execute(&mut *self.tx)
+                .await
+                .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    async fn query(&mut self, query: Query) -> Result<QueryResult> {
+        let statement = sql::select(&query, sql::dollar_placeholder);
+        let mut q = sqlx::query(&statement.sql);
+        for value in &statement.params {
+            q = bind_value(q, value);
+        }
+
+        let rows = q.fetch_all(&mut *self.tx)
+            .await
+            .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+
+        let columns: Vec<String> = rows.first()
+            .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+            .unwrap_or_default();
+        let rows: Vec<Record> = rows.iter().map(row_to_record).collect();
+
+        Ok(QueryResult {
+            columns,
+            row_count: rows.len(),
+            rows,
+        })
+    }
+
+    async fn commit(self: Box<Self>) -> Result<()> {
+        self.tx.commit()
+            .await
+            .map_err(|e| HanzoDbError::TransactionError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<()> {
+        self.tx.rollback()
+            .await
+            .map_err(|e| HanzoDbError::TransactionError(e.to_string()))?;
+        Ok(())
+    }
+}
+
+fn bind_value<'q>(
+    query: sqlx::query::Query<'q, Postgres, PgArguments>,
+    value: &DbValue,
+) -> sqlx::query::Query<'q, Postgres, PgArguments> {
+    match value {
+        DbValue::Null => query.bind(Option::<String>::None),
+        DbValue::Bool(b) => query.bind(*b),
+        DbValue::Int32(i) => query.bind(*i),
+        DbValue::Int64(i) | DbValue::Timestamp(i) => query.bind(*i),
+        DbValue::Float32(f) => query.bind(*f),
+        DbValue::Float64(f) => query.bind(*f),
+        DbValue::String(s) => query.bind(s.clone()),
+        DbValue::Binary(b) => query.bind(b.clone()),
+        DbValue::Vector(v) => query.bind(v.clone()),
+        DbValue::Json(j) => query.bind(j.to_string()),
+    }
+}
+
+fn row_to_record(row: &PgRow) -> Record {
+    let values = row.columns().iter().enumerate().map(|(i, column)| {
+        // Same type probing as `query`
+        let value = if let Ok(val) = row.try_get::<String, _>(i) {
+            DbValue::String(val)
+        } else if let Ok(val) = row.try_get::<i64, _>(i) {
+            DbValue::Int64(val)
+        } else if let Ok(val) = row.try_get::<i32, _>(i) {
+            DbValue::Int32(val)
+        } else if let Ok(val) = row.try_get::<f64, _>(i) {
+            DbValue::Float64(val)
+        } else if let Ok(val) = row.try_get::<f32, _>(i) {
+            DbValue::Float32(val)
+        } else if let Ok(val) = row.try_get::<bool, _>(i) {
+            DbValue::Bool(val)
+        } else if let Ok(val) = row.try_get::<Vec<f32>, _>(i) {
+            DbValue::Vector(val)
+        } else if let Ok(val) = row.try_get::<Vec<u8>, _>(i) {
+            DbValue::Binary(val)
+        } else {
+            DbValue::Null
+        };
+        (column.name().to_string(), value)
+    }).collect();
+
+    Record { values }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -503,15 +619,20 @@ mod tests {
         
         // Test transaction
         let mut tx = backend.begin_transaction().await.unwrap();
-        
-        sqlx::query("INSERT INTO test_items (name) VALUES ($1)")
-            .bind("test")
-            .execute(&mut *tx)
-            .await
-            .unwrap();
-        
+        let record = Record {
+            values: vec![("name".to_string(), DbValue::String("test".to_string()))],
+        };
+        tx.insert("test_items", &[record.clone()]).await.unwrap();
         tx.commit().await.unwrap();
 
+        // Rolled back inserts are discarded
+        let mut tx = backend.begin_transaction().await.unwrap();
+        tx.insert("test_items", &[record]).await.unwrap();
+        tx.rollback().await.unwrap();
+
+        let rows = backend.query("SELECT * FROM test_items").await.unwrap();
+        assert_eq!(rows.len(), 1);
+
         // Clean up
         backend.drop_table("test_items").await.unwrap();
     }
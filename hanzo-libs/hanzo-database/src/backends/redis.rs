@@ -3,7 +3,8 @@
 //! Redis is an in-memory data structure store, perfect for caching,
 //! session management, real-time analytics, and pub/sub messaging.
 
-use crate::{DatabaseBackend, HanzoDbConfig, HanzoDbError, Result};
+use crate::{DatabaseBackend, HanzoDbConfig, HanzoDbError, Query, QueryResult, Record, Result, Transaction, TransactionHandle};
+use crate::Value as DbValue;
 use async_trait::async_trait;
 use redis::{aio::ConnectionManager, AsyncCommands, Client};
 use serde_json::Value;
@@ -38,6 +39,18 @@ impl RedisBackend {
         })
     }
 
+    /// Begin a transaction. Writes are queued and sent as one MULTI/EXEC block on commit.
+    pub fn begin_transaction(&self) -> Result<Transaction> {
+        Ok(Transaction::new(
+            DatabaseBackend::Redis,
+            Box::new(RedisTransaction {
+                connection: self.connection.clone(),
+                pipeline: redis::pipe().atomic().clone(),
+                queued: 0,
+            }),
+        ))
+    }
+
     /// Set value with optional TTL
     pub async fn set_with_ttl(&mut self, key: &str, value: &Value, ttl_seconds: Option<u64>) -> Result<()> {
         let serialized = serde_json::to_string(value)
@@ -459,6 +472,114 @@ impl DatabaseBackend for RedisBackend {
     }
 }
 
+/// Redis transaction
+///
+/// Redis has no read-your-writes inside MULTI, so queries only see committed data.
+/// Records are plain keys without secondary indexes, so queries can only page
+/// through a table: filters and ordering are rejected.
+pub struct RedisTransaction {
+    connection: ConnectionManager,
+    pipeline: redis::Pipeline,
+    queued: usize,
+}
+
+#[async_trait]
+impl TransactionHandle for RedisTransaction {
+    async fn insert(&mut self, table: &str, data: &[Record]) -> Result<()> {
+        for record in data {
+            let id = record.values.iter().find(|(name, _)| name == "id").map(|(_, value)| match value {
+                DbValue::String(s) => s.clone(),
+                other => db_value_to_json(other).to_string(),
+            });
+            let key = format!("{}:{}", table, id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()));
+
+            let object: serde_json::Map<String, Value> = record.values.iter()
+                .map(|(name, value)| (name.clone(), db_value_to_json(value)))
+                .collect();
+            let serialized = serde_json::to_string(&object)
+                .map_err(|e| HanzoDbError::SerializationError(e.to_string()))?;
+
+            // Same layout as `insert`: the record under its key, plus the table index
+            self.pipeline.set(&key, serialized).ignore();
+            self.pipeline.sadd(format!("_index:{}", table), &key).ignore();
+            self.queued += 1;
+        }
+        Ok(())
+    }
+
+    async fn query(&mut self, query: Query) -> Result<QueryResult> {
+        if query.filter.is_some() || !query.order_by.is_empty() {
+            return Err(HanzoDbError::NotImplemented(
+                "Redis transactions do not support filtered or ordered queries".to_string(),
+            ).into());
+        }
+
+        // Sorted so that offset and limit page through a stable order
+        let mut keys: Vec<String> = self.connection.smembers(format!("_index:{}", query.table))
+            .await
+            .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+        keys.sort();
+
+        let mut rows = Vec::new();
+        for key in keys {
+            let value: Option<String> = self.connection.get(&key)
+                .await
+                .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+
+            if let Some(Ok(Value::Object(object))) = value.map(|v| serde_json::from_str::<Value>(&v)) {
+                let values = object.into_iter()
+                    .filter(|(name, _)| query.select.is_empty() || query.select.contains(name))
+                    .map(|(name, value)| (name, DbValue::Json(value)))
+                    .collect();
+                rows.push(Record { values });
+            }
+        }
+
+        let offset = query.offset.unwrap_or(0).min(rows.len());
+        rows.drain(..offset);
+        if let Some(limit) = query.limit {
+            rows.truncate(limit);
+        }
+
+        Ok(QueryResult {
+            columns: query.select,
+            row_count: rows.len(),
+            rows,
+        })
+    }
+
+    async fn commit(mut self: Box<Self>) -> Result<()> {
+        if self.queued == 0 {
+            return Ok(());
+        }
+        let this = &mut *self;
+        let _: () = this.pipeline.query_async(&mut this.connection)
+            .await
+            .map_err(|e| HanzoDbError::TransactionError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<()> {
+        // Nothing was sent yet
+        Ok(())
+    }
+}
+
+fn db_value_to_json(value: &DbValue) -> Value {
+    match value {
+        DbValue::Null => Value::Null,
+        DbValue::Bool(b) => Value::Bool(*b),
+        DbValue::Int32(i) => Value::from(*i),
+        DbValue::Int64(i) | DbValue::Timestamp(i) => Value::from(*i),
+        DbValue::Float32(f) => Value::from(*f as f64),
+        DbValue::Float64(f) => Value::from(*f),
+        DbValue::String(s) => Value::String(s.clone()),
+        DbValue::Binary(b) => Value::Array(b.iter().map(|byte| Value::from(*byte)).collect()),
+        DbValue::Vector(v) => Value::Array(v.iter().map(|f| Value::from(*f as f64)).collect()),
+        DbValue::Json(j) => j.clone(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
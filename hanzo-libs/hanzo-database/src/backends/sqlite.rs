@@ -3,15 +3,21 @@
 //! SQLite is a lightweight, serverless, self-contained SQL database engine,
 //! perfect for embedded applications, testing, and local development.
 
-use crate::{HanzoDbConfig, HanzoDatabase, HanzoDbError, TableSchema, Record, Query, QueryResult, VectorQuery, SearchResult, Transaction, DatabaseStats};
+use crate::sql;
+use crate::{DatabaseBackend, HanzoDbConfig, HanzoDatabase, HanzoDbError, TableSchema, Record, Query, QueryResult, VectorQuery, SearchResult, Transaction, TransactionHandle, DatabaseStats};
+use crate::Value as DbValue;
 use anyhow::Result;
 use async_trait::async_trait;
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::task;
 
+/// How long a connection waits for another writer's lock before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// SQLite backend for embedded database operations
 pub struct SqliteBackend {
     connection: Arc<Mutex<Connection>>,
@@ -22,22 +28,27 @@ pub struct SqliteBackend {
 impl SqliteBackend {
     /// Create a new SQLite backend
     pub fn new(config: HanzoDbConfig) -> Result<Self> {
-        let path = config.path.clone().unwrap_or_else(|| {
+        let mut path = config.path.clone().unwrap_or_else(|| {
             PathBuf::from("./storage/hanzo-sqlite.db")
         });
 
-        // Create parent directory if needed
-        if let Some(parent) = path.parent() {
+        if path.to_str() == Some(":memory:") {
+            // Transactions open their own connection, so in-memory databases live in a
+            // named memdb that all connections of the process share. Unlike a shared
+            // cache, memdb uses regular file locking, so concurrent connections wait
+            // out the busy timeout instead of failing with SQLITE_LOCKED.
+            path = PathBuf::from(format!("file:/hanzo-mem-{}?vfs=memdb", uuid::Uuid::new_v4()));
+        } else if let Some(parent) = path.parent() {
+            // Create parent directory if needed
             std::fs::create_dir_all(parent)
                 .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
         }
 
         // Open SQLite connection
-        let connection = if path.to_str() == Some(":memory:") {
-            Connection::open_in_memory()
-        } else {
-            Connection::open(&path)
-        }.map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
+        let connection = open_connection(&path)?;
+        connection
+            .busy_timeout(BUSY_TIMEOUT)
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
 
         // Configure SQLite for optimal performance
         connection.execute_batch("
@@ -569,9 +580,12 @@ impl HanzoDatabase for SqliteBackend {
     }
 
     async fn begin_transaction(&self) -> Result<Transaction> {
-        Err(anyhow::anyhow!(HanzoDbError::NotImplemented(
-            "Transactions not yet implemented for SQLite backend".to_string()
-        )))
+        let path = self.path.clone();
+        let transaction = task::spawn_blocking(move || SqliteTransaction::begin(&path))
+            .await
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))??;
+
+        Ok(Transaction::new(DatabaseBackend::SQLite, Box::new(transaction)))
     }
 
     async fn optimize(&self) -> Result<()> {
@@ -625,6 +639,176 @@ impl HanzoDatabase for SqliteBackend {
     }
 }
 
+/// SQLite transaction on its own connection
+///
+/// Uses `BEGIN IMMEDIATE` so the write lock is taken up front: a transaction either
+/// starts with exclusive write access or fails right away, instead of failing on its
+/// first write when another connection got there first.
+pub struct SqliteTransaction {
+    connection: Arc<Mutex<Connection>>,
+    finished: bool,
+}
+
+impl SqliteTransaction {
+    fn begin(path: &Path) -> Result<Self, HanzoDbError> {
+        let connection = open_connection(path)?;
+        connection
+            .busy_timeout(BUSY_TIMEOUT)
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
+        connection
+            .execute_batch("PRAGMA foreign_keys = ON; BEGIN IMMEDIATE;")
+            .map_err(|e| HanzoDbError::TransactionError(e.to_string()))?;
+
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+            finished: false,
+        })
+    }
+
+    async fn finish(&mut self, statement: &'static str) -> Result<()> {
+        let conn = self.connection.clone();
+        task::spawn_blocking(move || {
+            let c = conn.lock()
+                .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
+            c.execute_batch(statement)
+                .map_err(|e| HanzoDbError::TransactionError(e.to_string()))
+        }).await
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))??;
+
+        self.finished = true;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TransactionHandle for SqliteTransaction {
+    async fn insert(&mut self, table: &str, data: &[Record]) -> Result<()> {
+        let conn = self.connection.clone();
+        let table = table.to_string();
+        let data = data.to_vec();
+
+        task::spawn_blocking(move || {
+            let c = conn.lock()
+                .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
+
+            for record in &data {
+                let statement = sql::insert(&table, record, sql::sqlite_placeholder);
+                let params: Vec<rusqlite::types::Value> = statement.params.iter().map(to_sqlite_value).collect();
+                c.execute(&statement.sql, rusqlite::params_from_iter(params))
+                    .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+            }
+
+            Ok::<(), HanzoDbError>(())
+        }).await
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))??;
+
+        Ok(())
+    }
+
+    async fn query(&mut self, query: Query) -> Result<QueryResult> {
+        let conn = self.connection.clone();
+
+        let result = task::spawn_blocking(move || {
+            let c = conn.lock()
+                .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))?;
+
+            let statement = sql::select(&query, sql::sqlite_placeholder);
+            let params: Vec<rusqlite::types::Value> = statement.params.iter().map(to_sqlite_value).collect();
+
+            let mut stmt = c.prepare(&statement.sql)
+                .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+            let columns: Vec<String> = stmt.column_names().into_iter().map(|s| s.to_string()).collect();
+
+            let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+                let mut values = Vec::with_capacity(columns.len());
+                for (i, name) in columns.iter().enumerate() {
+                    values.push((name.clone(), from_sqlite_value(row.get_ref(i)?)));
+                }
+                Ok(Record { values })
+            }).map_err(|e| HanzoDbError::QueryError(e.to_string()))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| HanzoDbError::QueryError(e.to_string()))?;
+
+            Ok::<QueryResult, HanzoDbError>(QueryResult {
+                columns,
+                row_count: rows.len(),
+                rows,
+            })
+        }).await
+            .map_err(|e| HanzoDbError::ConnectionError(e.to_string()))??;
+
+        Ok(result)
+    }
+
+    async fn commit(mut self: Box<Self>) -> Result<()> {
+        self.finish("COMMIT").await
+    }
+
+    async fn rollback(mut self: Box<Self>) -> Result<()> {
+        self.finish("ROLLBACK").await
+    }
+}
+
+impl Drop for SqliteTransaction {
+    fn drop(&mut self) {
+        // Dropped without commit (or the commit failed): release the write lock.
+        // The rollback blocks on disk I/O, so keep it off the async runtime's workers.
+        if !self.finished {
+            let conn = self.connection.clone();
+            let rollback = move || {
+                if let Ok(c) = conn.lock() {
+                    let _ = c.execute_batch("ROLLBACK");
+                }
+            };
+            match tokio::runtime::Handle::try_current() {
+                Ok(runtime) => {
+                    runtime.spawn_blocking(rollback);
+                }
+                Err(_) => rollback(),
+            }
+        }
+    }
+}
+
+/// Open a connection, accepting `file:` URIs for shared in-memory databases
+fn open_connection(path: &Path) -> Result<Connection, HanzoDbError> {
+    let connection = match path.to_str() {
+        Some(uri) if uri.starts_with("file:") => {
+            Connection::open_with_flags(uri, OpenFlags::default() | OpenFlags::SQLITE_OPEN_URI)
+        }
+        _ => Connection::open(path),
+    };
+    connection.map_err(|e| HanzoDbError::ConnectionError(e.to_string()))
+}
+
+fn to_sqlite_value(value: &DbValue) -> rusqlite::types::Value {
+    use rusqlite::types::Value as SqlValue;
+    match value {
+        DbValue::Null => SqlValue::Null,
+        DbValue::Bool(b) => SqlValue::Integer(if *b { 1 } else { 0 }),
+        DbValue::Int32(i) => SqlValue::Integer(*i as i64),
+        DbValue::Int64(i) | DbValue::Timestamp(i) => SqlValue::Integer(*i),
+        DbValue::Float32(f) => SqlValue::Real(*f as f64),
+        DbValue::Float64(f) => SqlValue::Real(*f),
+        DbValue::String(s) => SqlValue::Text(s.clone()),
+        DbValue::Binary(b) => SqlValue::Blob(b.clone()),
+        // Same layout as `store_vector`
+        DbValue::Vector(v) => SqlValue::Blob(v.iter().flat_map(|f| f.to_le_bytes()).collect()),
+        DbValue::Json(j) => SqlValue::Text(j.to_string()),
+    }
+}
+
+fn from_sqlite_value(value: rusqlite::types::ValueRef<'_>) -> DbValue {
+    use rusqlite::types::ValueRef;
+    match value {
+        ValueRef::Null => DbValue::Null,
+        ValueRef::Integer(i) => DbValue::Int64(i),
+        ValueRef::Real(f) => DbValue::Float64(f),
+        ValueRef::Text(s) => DbValue::String(String::from_utf8_lossy(s).into_owned()),
+        ValueRef::Blob(b) => DbValue::Binary(b.to_vec()),
+    }
+}
+
 // Helper function for cosine similarity
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() {
@@ -676,6 +860,79 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_sqlite_transaction_commit_and_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = HanzoDbConfig {
+            backend: DatabaseBackend::SQLite,
+            path: Some(dir.path().join("tx.db")),
+            ..Default::default()
+        };
+        let backend = SqliteBackend::new(config).unwrap();
+        backend.create_table("items", &serde_json::json!({"name": "string"})).await.unwrap();
+
+        let record = |name: &str| Record {
+            values: vec![("name".to_string(), DbValue::String(name.to_string()))],
+        };
+        let all_items = Query {
+            table: "items".to_string(),
+            select: vec!["name".to_string()],
+            filter: None,
+            order_by: vec![],
+            limit: None,
+            offset: None,
+        };
+
+        // Writes are visible inside the transaction and persisted on commit
+        let mut tx = backend.begin_transaction().await.unwrap();
+        tx.insert("items", &[record("a"), record("b")]).await.unwrap();
+        assert_eq!(tx.query(all_items.clone()).await.unwrap().row_count, 2);
+        tx.commit().await.unwrap();
+        assert_eq!(backend.query("SELECT * FROM items").await.unwrap().len(), 2);
+
+        // Rolled back and dropped transactions leave no trace
+        let mut tx = backend.begin_transaction().await.unwrap();
+        tx.insert("items", &[record("c")]).await.unwrap();
+        tx.rollback().await.unwrap();
+
+        let mut tx = backend.begin_transaction().await.unwrap();
+        tx.insert("items", &[record("d")]).await.unwrap();
+        drop(tx);
+
+        assert_eq!(backend.query("SELECT * FROM items").await.unwrap().len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sqlite_in_memory_concurrent_transactions() {
+        let config = HanzoDbConfig {
+            backend: DatabaseBackend::SQLite,
+            path: Some(PathBuf::from(":memory:")),
+            ..Default::default()
+        };
+        let backend = Arc::new(SqliteBackend::new(config).unwrap());
+        backend.create_table("items", &serde_json::json!({"name": "string"})).await.unwrap();
+
+        // The second transaction waits for the first one's write lock instead of failing
+        let writers = (0..2).map(|i| {
+            let backend = backend.clone();
+            tokio::spawn(async move {
+                let mut tx = backend.begin_transaction().await.unwrap();
+                let record = Record {
+                    values: vec![("name".to_string(), DbValue::String(format!("item-{}", i)))],
+                };
+                tx.insert("items", &[record]).await.unwrap();
+                // Reads on the backend connection keep working meanwhile
+                backend.query("SELECT * FROM items").await.unwrap();
+                tx.commit().await.unwrap();
+            })
+        });
+        for writer in writers.collect::<Vec<_>>() {
+            writer.await.unwrap();
+        }
+
+        assert_eq!(backend.query("SELECT * FROM items").await.unwrap().len(), 2);
+    }
+
     #[test]
     fn test_cosine_similarity() {
         let a = vec![1.0, 2.0, 3.0];
@@ -18,7 +18,6 @@ use async_trait::async_trait;
 use log::{debug, error, info, warn};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use thiserror::Error;
 
 /// Database error types
@@ -51,6 +50,10 @@ pub enum HanzoDbError {
 
 pub mod models;
 
+// SQL generation shared by the SQL backends
+#[cfg(any(feature = "backend-sqlite", feature = "backend-postgres", feature = "backend-duckdb"))]
+mod sql;
+
 #[cfg(feature = "backend-lancedb")]
 pub mod vector_search;
 
@@ -323,30 +326,62 @@ pub struct SearchResult {
     pub score: f32,
 }
 
-/// Transaction handle
-pub struct Transaction {
-    inner: Arc<RwLock<TransactionInner>>,
+/// Backend-specific side of a transaction, e.g. a dedicated SQLite connection
+/// or an open `sqlx` transaction
+#[async_trait]
+pub trait TransactionHandle: Send + Sync {
+    /// Insert data as part of the transaction
+    async fn insert(&mut self, table: &str, data: &[Record]) -> Result<()>;
+
+    /// Query data, seeing the transaction's own uncommitted writes where the backend allows it
+    async fn query(&mut self, query: Query) -> Result<QueryResult>;
+
+    /// Commit the transaction
+    async fn commit(self: Box<Self>) -> Result<()>;
+
+    /// Rollback the transaction
+    async fn rollback(self: Box<Self>) -> Result<()>;
 }
 
-struct TransactionInner {
+/// Transaction handle
+///
+/// Dropping a transaction without committing it rolls it back.
+pub struct Transaction {
     backend: DatabaseBackend,
-    // Backend-specific transaction handle
-    handle: Box<dyn std::any::Any + Send + Sync>,
+    handle: Box<dyn TransactionHandle>,
 }
 
 impl Transaction {
+    /// Wrap a backend transaction handle
+    pub fn new(backend: DatabaseBackend, handle: Box<dyn TransactionHandle>) -> Self {
+        Self { backend, handle }
+    }
+
+    /// Backend the transaction runs on
+    pub fn backend(&self) -> DatabaseBackend {
+        self.backend
+    }
+
+    /// Insert data as part of the transaction
+    pub async fn insert(&mut self, table: &str, data: &[Record]) -> Result<()> {
+        self.handle.insert(table, data).await
+    }
+
+    /// Query data within the transaction
+    pub async fn query(&mut self, query: Query) -> Result<QueryResult> {
+        self.handle.query(query).await
+    }
+
     /// Commit the transaction
     pub async fn commit(self) -> Result<()> {
-        let inner = self.inner.write().await;
-        // Backend-specific commit logic
-        Ok(())
+        debug!("Committing {:?} transaction", self.backend);
+        self.handle.commit().await
     }
-    
+
     /// Rollback the transaction
     pub async fn rollback(self) -> Result<()> {
-        let inner = self.inner.write().await;
-        // Backend-specific rollback logic
-        Ok(())
+        debug!("Rolling back {:?} transaction", self.backend);
+        self.handle.rollback().await
     }
 }
 
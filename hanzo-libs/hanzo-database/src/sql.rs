@@ -0,0 +1,179 @@
+//! SQL generation for the relational backends
+//!
+//! Turns `Query` and `Record` into parameterized statements. Placeholders differ
+//! between dialects (`?1` for SQLite, `$1` for PostgreSQL and DuckDB), so callers
+//! pass their own.
+
+use crate::{Filter, Query, Record, Value};
+
+/// Placeholder style of a SQL dialect, given the 1-based parameter index
+pub(crate) type Placeholder = fn(usize) -> String;
+
+/// SQLite placeholders (`?1`, `?2`, ...)
+#[cfg(feature = "backend-sqlite")]
+pub(crate) fn sqlite_placeholder(index: usize) -> String {
+    format!("?{}", index)
+}
+
+/// PostgreSQL and DuckDB placeholders (`$1`, `$2`, ...)
+#[cfg(any(feature = "backend-postgres", feature = "backend-duckdb"))]
+pub(crate) fn dollar_placeholder(index: usize) -> String {
+    format!("${}", index)
+}
+
+/// A SQL statement with its bound parameters
+#[derive(Debug, Clone)]
+pub(crate) struct Statement {
+    pub sql: String,
+    pub params: Vec<Value>,
+}
+
+/// Quote an identifier so table and column names can't break out of the statement
+pub(crate) fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Build a `SELECT` for a query
+pub(crate) fn select(query: &Query, placeholder: Placeholder) -> Statement {
+    let mut params = Vec::new();
+
+    let columns = if query.select.is_empty() {
+        "*".to_string()
+    } else {
+        query.select.iter().map(|c| quote_ident(c)).collect::<Vec<_>>().join(", ")
+    };
+    let mut sql = format!("SELECT {} FROM {}", columns, quote_ident(&query.table));
+
+    if let Some(filter) = &query.filter {
+        sql.push_str(" WHERE ");
+        sql.push_str(&filter_sql(filter, &mut params, placeholder));
+    }
+
+    if !query.order_by.is_empty() {
+        let order: Vec<String> = query
+            .order_by
+            .iter()
+            .map(|o| format!("{} {}", quote_ident(&o.column), if o.ascending { "ASC" } else { "DESC" }))
+            .collect();
+        sql.push_str(&format!(" ORDER BY {}", order.join(", ")));
+    }
+
+    // SQLite only accepts OFFSET after a LIMIT
+    match (query.limit, query.offset) {
+        (Some(limit), Some(offset)) => sql.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset)),
+        (Some(limit), None) => sql.push_str(&format!(" LIMIT {}", limit)),
+        (None, Some(offset)) => sql.push_str(&format!(" LIMIT {} OFFSET {}", i64::MAX, offset)),
+        (None, None) => {}
+    }
+
+    Statement { sql, params }
+}
+
+/// Build an `INSERT` for a single record
+pub(crate) fn insert(table: &str, record: &Record, placeholder: Placeholder) -> Statement {
+    let columns: Vec<String> = record.values.iter().map(|(name, _)| quote_ident(name)).collect();
+    let placeholders: Vec<String> = (1..=columns.len()).map(placeholder).collect();
+
+    Statement {
+        sql: format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            quote_ident(table),
+            columns.join(", "),
+            placeholders.join(", ")
+        ),
+        params: record.values.iter().map(|(_, value)| value.clone()).collect(),
+    }
+}
+
+fn filter_sql(filter: &Filter, params: &mut Vec<Value>, placeholder: Placeholder) -> String {
+    let bind = |value: &Value, params: &mut Vec<Value>| {
+        params.push(value.clone());
+        placeholder(params.len())
+    };
+
+    match filter {
+        Filter::Eq(column, value) => format!("{} = {}", quote_ident(column), bind(value, params)),
+        Filter::Ne(column, value) => format!("{} <> {}", quote_ident(column), bind(value, params)),
+        Filter::Gt(column, value) => format!("{} > {}", quote_ident(column), bind(value, params)),
+        Filter::Gte(column, value) => format!("{} >= {}", quote_ident(column), bind(value, params)),
+        Filter::Lt(column, value) => format!("{} < {}", quote_ident(column), bind(value, params)),
+        Filter::Lte(column, value) => format!("{} <= {}", quote_ident(column), bind(value, params)),
+        Filter::In(column, values) => {
+            if values.is_empty() {
+                return "1 = 0".to_string();
+            }
+            let placeholders: Vec<String> = values.iter().map(|v| bind(v, params)).collect();
+            format!("{} IN ({})", quote_ident(column), placeholders.join(", "))
+        }
+        Filter::Like(column, pattern) => {
+            format!("{} LIKE {}", quote_ident(column), bind(&Value::String(pattern.clone()), params))
+        }
+        Filter::And(left, right) => format!(
+            "({} AND {})",
+            filter_sql(left, params, placeholder),
+            filter_sql(right, params, placeholder)
+        ),
+        Filter::Or(left, right) => format!(
+            "({} OR {})",
+            filter_sql(left, params, placeholder),
+            filter_sql(right, params, placeholder)
+        ),
+        Filter::Not(inner) => format!("NOT ({})", filter_sql(inner, params, placeholder)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrderBy;
+
+    fn numbered(index: usize) -> String {
+        format!("?{}", index)
+    }
+
+    #[test]
+    fn test_select_with_filter_order_and_paging() {
+        let query = Query {
+            table: "jobs".to_string(),
+            select: vec!["id".to_string(), "name".to_string()],
+            filter: Some(Filter::And(
+                Box::new(Filter::Eq("status".to_string(), Value::String("done".to_string()))),
+                Box::new(Filter::In(
+                    "kind".to_string(),
+                    vec![Value::Int64(1), Value::Int64(2)],
+                )),
+            )),
+            order_by: vec![OrderBy {
+                column: "created_at".to_string(),
+                ascending: false,
+            }],
+            limit: Some(10),
+            offset: Some(20),
+        };
+
+        let statement = select(&query, numbered);
+        assert_eq!(
+            statement.sql,
+            "SELECT \"id\", \"name\" FROM \"jobs\" WHERE (\"status\" = ?1 AND \"kind\" IN (?2, ?3)) \
+             ORDER BY \"created_at\" DESC LIMIT 10 OFFSET 20"
+        );
+        assert_eq!(statement.params.len(), 3);
+    }
+
+    #[test]
+    fn test_insert_quotes_identifiers() {
+        let record = Record {
+            values: vec![
+                ("name".to_string(), Value::String("a".to_string())),
+                ("we\"ird".to_string(), Value::Int32(1)),
+            ],
+        };
+
+        let statement = insert("items", &record, numbered);
+        assert_eq!(
+            statement.sql,
+            "INSERT INTO \"items\" (\"name\", \"we\"\"ird\") VALUES (?1, ?2)"
+        );
+        assert_eq!(statement.params.len(), 2);
+    }
+}
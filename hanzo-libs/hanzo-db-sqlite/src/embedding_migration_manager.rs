@@ -0,0 +1,735 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use bytemuck::cast_slice;
+use hanzo_embed::embedding_generator::{EmbeddingGenerator, RemoteEmbeddingGenerator};
+use hanzo_embed::model_type::EmbeddingModelType;
+use hanzo_messages::hanzo_message::hanzo_message_schemas::WSTopic;
+use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
+use hanzo_messages::schemas::embedding_migration::{
+    EmbeddingMigrationProgress, EmbeddingMigrationStatus, EmbeddingMigrationTableProgress,
+};
+use hanzo_messages::schemas::ws_types::{WSMessageType, WSUpdateHandler};
+use rusqlite::{params, OptionalExtension, TransactionBehavior};
+use tokio::sync::Mutex;
+
+use crate::{SqliteManager, SqliteManagerError};
+
+const PROMPT_VEC_TABLE: &str = "prompt_vec_items";
+const TOOL_VEC_TABLE: &str = "hanzo_tools_vec_items";
+const CHUNK_VEC_TABLE: &str = "chunk_vec";
+
+/// Vector tables rebuilt by a migration, in the order they are migrated
+const MIGRATED_VEC_TABLES: [&str; 3] = [PROMPT_VEC_TABLE, TOOL_VEC_TABLE, CHUNK_VEC_TABLE];
+
+/// Progress is pushed over WS every time this many items have been migrated
+const PROGRESS_UPDATE_INTERVAL: usize = 25;
+
+/// Embedding generator and default model of the running node, switched to the new model in the
+/// same critical section as the tables
+pub type LiveEmbeddingModel = (Arc<Mutex<RemoteEmbeddingGenerator>>, Arc<Mutex<EmbeddingModelType>>);
+
+/// An item whose embedding with the target model is not in the shadow table yet
+enum PendingVecItem {
+    Prompt {
+        id: i64,
+        prompt: String,
+        is_enabled: bool,
+    },
+    Tool {
+        tool_key: String,
+        version: i64,
+        embedding_seo: String,
+        is_enabled: bool,
+        is_network: bool,
+    },
+    Chunk {
+        id: i64,
+        parsed_file_id: i64,
+        chunk: String,
+    },
+}
+
+impl PendingVecItem {
+    /// Key recorded in `embedding_migration_items` once the item is migrated
+    fn item_key(&self) -> String {
+        match self {
+            PendingVecItem::Prompt { id, .. } => id.to_string(),
+            PendingVecItem::Tool { tool_key, version, .. } => format!("{}:{}", tool_key, version),
+            PendingVecItem::Chunk { id, .. } => id.to_string(),
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            PendingVecItem::Prompt { prompt, .. } => prompt,
+            PendingVecItem::Tool { embedding_seo, .. } => embedding_seo,
+            PendingVecItem::Chunk { chunk, .. } => chunk,
+        }
+    }
+
+    /// Removes the embedding of an item that was migrated before being written again
+    fn delete_embedding(&self, tx: &rusqlite::Transaction, vec_table: &str) -> Result<(), SqliteManagerError> {
+        match self {
+            PendingVecItem::Prompt { id, .. } => {
+                tx.execute(&format!("DELETE FROM {} WHERE prompt_id = ?1", vec_table), params![id])?;
+            }
+            PendingVecItem::Tool { tool_key, .. } => {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE tool_key = ?1", vec_table),
+                    params![tool_key],
+                )?;
+            }
+            PendingVecItem::Chunk { id, .. } => {
+                tx.execute(&format!("DELETE FROM {} WHERE chunk_id = ?1", vec_table), params![id])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_embedding(
+        &self,
+        tx: &rusqlite::Transaction,
+        vec_table: &str,
+        embedding: &[f32],
+    ) -> Result<(), SqliteManagerError> {
+        match self {
+            PendingVecItem::Prompt { id, is_enabled, .. } => {
+                tx.execute(
+                    &format!(
+                        "INSERT INTO {} (prompt_id, embedding, is_enabled) VALUES (?1, ?2, ?3)",
+                        vec_table
+                    ),
+                    params![id, cast_slice(embedding), *is_enabled as i32],
+                )?;
+            }
+            PendingVecItem::Tool {
+                tool_key,
+                is_enabled,
+                is_network,
+                ..
+            } => {
+                tx.execute(
+                    &format!(
+                        "INSERT INTO {} (tool_key, embedding, is_enabled, is_network) VALUES (?1, ?2, ?3, ?4)",
+                        vec_table
+                    ),
+                    params![tool_key, cast_slice(embedding), *is_enabled as i32, *is_network as i32],
+                )?;
+            }
+            PendingVecItem::Chunk { id, parsed_file_id, .. } => {
+                tx.execute(
+                    &format!(
+                        "INSERT INTO {} (chunk_id, parsed_file_id, embedding) VALUES (?1, ?2, ?3)",
+                        vec_table
+                    ),
+                    params![id, parsed_file_id, cast_slice(embedding)],
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SqliteManager {
+    /// Re-embeds prompts, tools and file chunks with a new embedding model.
+    ///
+    /// The new embeddings are written to shadow vector tables and every migrated item is checkpointed,
+    /// so the live tables keep answering queries with the previous model and a migration interrupted
+    /// by a crash or a restart resumes where it stopped. Items written while the migration runs lose
+    /// their checkpoint and are embedded again. Once everything is migrated the shadow tables replace
+    /// the live ones and the default model is updated in a single transaction, while `live_model` is
+    /// locked and switched to the new model.
+    pub async fn migrate_embeddings_to_new_model(
+        &self,
+        embedding_generator: &dyn EmbeddingGenerator,
+        new_model_type: &EmbeddingModelType,
+        force: bool,
+        ws_manager: Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        live_model: Option<LiveEmbeddingModel>,
+    ) -> Result<(), SqliteManagerError> {
+        let resuming = match self.get_embedding_migration_progress()? {
+            Some(progress) => {
+                !force
+                    && progress.target_model == new_model_type.to_string()
+                    && progress.status != EmbeddingMigrationStatus::Completed
+            }
+            None => false,
+        };
+
+        if resuming {
+            hanzo_log(
+                HanzoLogOption::Database,
+                HanzoLogLevel::Info,
+                &format!("Resuming embedding migration to new model: {}", new_model_type),
+            );
+            self.set_embedding_migration_status(EmbeddingMigrationStatus::Running, None)?;
+        } else {
+            if !force && !self.is_embedding_migration_needed(new_model_type)? {
+                hanzo_log(
+                    HanzoLogOption::Database,
+                    HanzoLogLevel::Info,
+                    "Embedding migration not needed, skipping",
+                );
+                return Ok(());
+            }
+
+            hanzo_log(
+                HanzoLogOption::Database,
+                HanzoLogLevel::Info,
+                &format!(
+                    "Starting embedding migration to new model: {} (force: {})",
+                    new_model_type, force
+                ),
+            );
+            self.start_embedding_migration(new_model_type)?;
+        }
+        self.send_embedding_migration_progress(&ws_manager).await;
+
+        let result = self
+            .run_embedding_migration(embedding_generator, new_model_type, &ws_manager, live_model)
+            .await;
+        if let Err(e) = &result {
+            // Checkpoints are kept so triggering the same migration again continues from here
+            self.set_embedding_migration_status(EmbeddingMigrationStatus::Failed, Some(&e.to_string()))?;
+        }
+        self.send_embedding_migration_progress(&ws_manager).await;
+
+        result
+    }
+
+    /// State of the last embedding migration, with the progress of each vector table
+    pub fn get_embedding_migration_progress(&self) -> Result<Option<EmbeddingMigrationProgress>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let migration = conn
+            .query_row(
+                "SELECT target_model, status, started_at, updated_at, error FROM embedding_migration WHERE id = 1",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, Option<String>>(4)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        let Some((target_model, status, started_at, updated_at, error)) = migration else {
+            return Ok(None);
+        };
+        let status = EmbeddingMigrationStatus::from_str(&status).map_err(SqliteManagerError::SerializationError)?;
+
+        let mut tables = Vec::new();
+        for vec_table in MIGRATED_VEC_TABLES {
+            let total: u64 = conn.query_row(
+                &format!("SELECT COUNT(*) FROM {}", Self::embedding_source_table(vec_table)),
+                [],
+                |row| row.get(0),
+            )?;
+            // Checkpoints are cleared when the tables are swapped
+            let migrated = if status == EmbeddingMigrationStatus::Completed {
+                total
+            } else {
+                let migrated: u64 = conn.query_row(
+                    "SELECT COUNT(*) FROM embedding_migration_items WHERE vec_table = ?1",
+                    params![vec_table],
+                    |row| row.get(0),
+                )?;
+                migrated.min(total)
+            };
+            tables.push(EmbeddingMigrationTableProgress {
+                table: vec_table.to_string(),
+                total,
+                migrated,
+            });
+        }
+
+        Ok(Some(EmbeddingMigrationProgress {
+            target_model,
+            status,
+            started_at,
+            updated_at,
+            error,
+            tables,
+        }))
+    }
+
+    /// Clears the checkpoints of any previous migration and creates empty shadow tables at the new dimension
+    fn start_embedding_migration(&self, new_model_type: &EmbeddingModelType) -> Result<(), SqliteManagerError> {
+        let vector_dimensions = Self::migration_vector_dimensions(new_model_type)?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        for vec_table in MIGRATED_VEC_TABLES {
+            let shadow_table = Self::shadow_vec_table(vec_table);
+            tx.execute(&format!("DROP TABLE IF EXISTS {}", shadow_table), [])?;
+            tx.execute(
+                &Self::create_vec_table_sql(vec_table, &shadow_table, vector_dimensions),
+                [],
+            )?;
+        }
+        tx.execute("DELETE FROM embedding_migration_items", [])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO embedding_migration (id, target_model, status, started_at, updated_at, error)
+             VALUES (1, ?1, ?2, ?3, ?3, NULL)",
+            params![
+                new_model_type.to_string(),
+                EmbeddingMigrationStatus::Running.to_string(),
+                now
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn run_embedding_migration(
+        &self,
+        embedding_generator: &dyn EmbeddingGenerator,
+        new_model_type: &EmbeddingModelType,
+        ws_manager: &Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>,
+        live_model: Option<LiveEmbeddingModel>,
+    ) -> Result<(), SqliteManagerError> {
+        let max_input_chars = new_model_type.max_input_token_count();
+
+        // Items written after the last pass are found by the swap, which then waits for another pass
+        loop {
+            for vec_table in MIGRATED_VEC_TABLES {
+                hanzo_log(
+                    HanzoLogOption::Database,
+                    HanzoLogLevel::Info,
+                    &format!("Regenerating embeddings for {}", vec_table),
+                );
+
+                // Items added while a pass runs are picked up by the next one
+                loop {
+                    let pending = self.get_pending_vec_items(vec_table)?;
+                    if pending.is_empty() {
+                        break;
+                    }
+
+                    for (index, item) in pending.iter().enumerate() {
+                        // Only truncate if the text exceeds the new model's token limit
+                        let text: String = item.text().chars().take(max_input_chars).collect();
+                        let embedding = embedding_generator
+                            .generate_embedding_default(&text)
+                            .await
+                            .map_err(|e| {
+                                SqliteManagerError::SerializationError(format!("Embedding generation failed: {}", e))
+                            })?;
+                        self.checkpoint_vec_item(vec_table, item, &embedding)?;
+
+                        if (index + 1) % PROGRESS_UPDATE_INTERVAL == 0 {
+                            self.send_embedding_migration_progress(ws_manager).await;
+                        }
+                    }
+                }
+                self.send_embedding_migration_progress(ws_manager).await;
+            }
+
+            // Queries wait for the node to switch models while the tables are swapped
+            let swapped = match &live_model {
+                Some((live_generator, live_model_type)) => {
+                    let mut live_generator = live_generator.lock().await;
+                    let mut live_model_type = live_model_type.lock().await;
+                    let swapped = self.swap_embedding_migration_tables(new_model_type)?;
+                    if swapped {
+                        live_generator.set_model_type(new_model_type.clone());
+                        *live_model_type = new_model_type.clone();
+                    }
+                    swapped
+                }
+                None => self.swap_embedding_migration_tables(new_model_type)?,
+            };
+            if swapped {
+                break;
+            }
+        }
+
+        hanzo_log(
+            HanzoLogOption::Database,
+            HanzoLogLevel::Info,
+            "Embedding migration completed successfully",
+        );
+        Ok(())
+    }
+
+    fn get_pending_vec_items(&self, vec_table: &str) -> Result<Vec<PendingVecItem>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        Self::pending_vec_items(&conn, vec_table)
+    }
+
+    fn pending_vec_items(
+        conn: &rusqlite::Connection,
+        vec_table: &str,
+    ) -> Result<Vec<PendingVecItem>, SqliteManagerError> {
+        let items = match vec_table {
+            PROMPT_VEC_TABLE => {
+                let mut stmt = conn.prepare(
+                    "SELECT p.id, p.prompt, p.is_enabled FROM hanzo_prompts p
+                     WHERE NOT EXISTS (
+                        SELECT 1 FROM embedding_migration_items m
+                        WHERE m.vec_table = ?1 AND m.item_key = CAST(p.id AS TEXT)
+                     )",
+                )?;
+                let rows = stmt.query_map(params![vec_table], |row| {
+                    Ok(PendingVecItem::Prompt {
+                        id: row.get(0)?,
+                        prompt: row.get(1)?,
+                        is_enabled: row.get::<_, i32>(2)? != 0,
+                    })
+                })?;
+                rows.collect::<Result<Vec<_>, _>>()?
+            }
+            TOOL_VEC_TABLE => {
+                let mut stmt = conn.prepare(
+                    "SELECT t.tool_key, t.version, t.embedding_seo, t.is_enabled, t.is_network FROM hanzo_tools t
+                     WHERE NOT EXISTS (
+                        SELECT 1 FROM embedding_migration_items m
+                        WHERE m.vec_table = ?1 AND m.item_key = t.tool_key || ':' || t.version
+                     )",
+                )?;
+                let rows = stmt.query_map(params![vec_table], |row| {
+                    Ok(PendingVecItem::Tool {
+                        tool_key: row.get(0)?,
+                        version: row.get(1)?,
+                        embedding_seo: row.get(2)?,
+                        is_enabled: row.get::<_, i32>(3)? != 0,
+                        is_network: row.get::<_, i32>(4)? != 0,
+                    })
+                })?;
+                rows.collect::<Result<Vec<_>, _>>()?
+            }
+            _ => {
+                let mut stmt = conn.prepare(
+                    "SELECT c.id, c.parsed_file_id, c.chunk FROM chunks c
+                     WHERE NOT EXISTS (
+                        SELECT 1 FROM embedding_migration_items m
+                        WHERE m.vec_table = ?1 AND m.item_key = CAST(c.id AS TEXT)
+                     )",
+                )?;
+                let rows = stmt.query_map(params![vec_table], |row| {
+                    Ok(PendingVecItem::Chunk {
+                        id: row.get(0)?,
+                        parsed_file_id: row.get(1)?,
+                        chunk: row.get(2)?,
+                    })
+                })?;
+                rows.collect::<Result<Vec<_>, _>>()?
+            }
+        };
+        Ok(items)
+    }
+
+    /// Stores the new embedding of an item in the shadow table together with its checkpoint
+    fn checkpoint_vec_item(
+        &self,
+        vec_table: &str,
+        item: &PendingVecItem,
+        embedding: &[f32],
+    ) -> Result<(), SqliteManagerError> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        let shadow_table = Self::shadow_vec_table(vec_table);
+        item.delete_embedding(&tx, &shadow_table)?;
+        item.insert_embedding(&tx, &shadow_table, embedding)?;
+        tx.execute(
+            "INSERT OR IGNORE INTO embedding_migration_items (vec_table, item_key) VALUES (?1, ?2)",
+            params![vec_table, item.item_key()],
+        )?;
+        tx.execute(
+            "UPDATE embedding_migration SET updated_at = ?1 WHERE id = 1",
+            params![chrono::Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Replaces the live vector tables with the shadow ones and switches the default model.
+    /// Embeddings of items deleted during the migration are left behind. Returns false without
+    /// swapping when items were written since the last pass and still need their new embedding.
+    fn swap_embedding_migration_tables(&self, new_model_type: &EmbeddingModelType) -> Result<bool, SqliteManagerError> {
+        let vector_dimensions = Self::migration_vector_dimensions(new_model_type)?;
+
+        let mut conn = self.get_connection()?;
+        // Immediate, so no item can be written between the check and the swap
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        for vec_table in MIGRATED_VEC_TABLES {
+            if !Self::pending_vec_items(&tx, vec_table)?.is_empty() {
+                return Ok(false);
+            }
+        }
+        for vec_table in MIGRATED_VEC_TABLES {
+            let shadow_table = Self::shadow_vec_table(vec_table);
+            let (columns, source_filter) = match vec_table {
+                PROMPT_VEC_TABLE => (
+                    "prompt_id, embedding, is_enabled",
+                    "prompt_id IN (SELECT id FROM hanzo_prompts)",
+                ),
+                TOOL_VEC_TABLE => (
+                    "tool_key, embedding, is_enabled, is_network",
+                    "tool_key IN (SELECT tool_key FROM hanzo_tools)",
+                ),
+                _ => (
+                    "chunk_id, parsed_file_id, embedding",
+                    "chunk_id IN (SELECT id FROM chunks)",
+                ),
+            };
+
+            tx.execute(&format!("DROP TABLE IF EXISTS {}", vec_table), [])?;
+            tx.execute(&Self::create_vec_table_sql(vec_table, vec_table, vector_dimensions), [])?;
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} ({}) SELECT {} FROM {} WHERE {}",
+                    vec_table, columns, columns, shadow_table, source_filter
+                ),
+                [],
+            )?;
+            tx.execute(&format!("DROP TABLE {}", shadow_table), [])?;
+        }
+
+        tx.execute(
+            "UPDATE parsed_files SET embedding_model_used = ?1",
+            params![new_model_type.to_string()],
+        )?;
+        tx.execute("DELETE FROM embedding_model_type", [])?;
+        tx.execute(
+            "INSERT INTO embedding_model_type (model_type) VALUES (?1)",
+            params![new_model_type.to_string()],
+        )?;
+        tx.execute("DELETE FROM embedding_migration_items", [])?;
+        tx.execute(
+            "UPDATE embedding_migration SET status = ?1, updated_at = ?2, error = NULL WHERE id = 1",
+            params![
+                EmbeddingMigrationStatus::Completed.to_string(),
+                chrono::Utc::now().to_rfc3339()
+            ],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn set_embedding_migration_status(
+        &self,
+        status: EmbeddingMigrationStatus,
+        error: Option<&str>,
+    ) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "UPDATE embedding_migration SET status = ?1, updated_at = ?2, error = ?3 WHERE id = 1",
+            params![status.to_string(), chrono::Utc::now().to_rfc3339(), error],
+        )?;
+        Ok(())
+    }
+
+    async fn send_embedding_migration_progress(&self, ws_manager: &Option<Arc<Mutex<dyn WSUpdateHandler + Send>>>) {
+        let Some(manager) = ws_manager else {
+            return;
+        };
+        if let Ok(Some(progress)) = self.get_embedding_migration_progress() {
+            if let Ok(update) = serde_json::to_string(&progress) {
+                let m = manager.lock().await;
+                m.queue_message(
+                    WSTopic::EmbeddingMigration,
+                    "".to_string(),
+                    update,
+                    WSMessageType::None,
+                    false,
+                )
+                .await;
+            }
+        }
+    }
+
+    fn shadow_vec_table(vec_table: &str) -> String {
+        format!("{}_migration", vec_table)
+    }
+
+    /// Table holding the items embedded into `vec_table`
+    fn embedding_source_table(vec_table: &str) -> &'static str {
+        match vec_table {
+            PROMPT_VEC_TABLE => "hanzo_prompts",
+            TOOL_VEC_TABLE => "hanzo_tools",
+            _ => "chunks",
+        }
+    }
+
+    fn create_vec_table_sql(vec_table: &str, name: &str, vector_dimensions: usize) -> String {
+        let columns = match vec_table {
+            PROMPT_VEC_TABLE => {
+                "is_enabled integer,
+                    +prompt_id integer"
+            }
+            TOOL_VEC_TABLE => {
+                "is_enabled integer,
+                    is_network integer,
+                    +tool_key text"
+            }
+            _ => {
+                "parsed_file_id INTEGER,
+                    +chunk_id INTEGER"
+            }
+        };
+        format!(
+            "CREATE VIRTUAL TABLE {} USING vec0(
+                    embedding float[{}],
+                    {}
+                )",
+            name, vector_dimensions, columns
+        )
+    }
+
+    fn migration_vector_dimensions(model_type: &EmbeddingModelType) -> Result<usize, SqliteManagerError> {
+        model_type.vector_dimensions().map_err(|e| {
+            SqliteManagerError::SerializationError(format!("Cannot get vector dimensions for new model: {}", e))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::mock_generator::MockGenerator;
+    use hanzo_embed::model_type::OllamaTextEmbeddingsInference;
+    use hanzo_messages::schemas::custom_prompt::CustomPrompt;
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    fn add_prompts(db: &SqliteManager, count: usize) {
+        let vector_dimensions = EmbeddingModelType::default().vector_dimensions().unwrap();
+        for i in 0..count {
+            let prompt = CustomPrompt {
+                rowid: None,
+                name: format!("prompt {}", i),
+                prompt: format!("Prompt content {}", i),
+                is_system: false,
+                is_enabled: true,
+                version: "1".to_string(),
+                is_favorite: false,
+            };
+            db.add_prompt_with_vector(&prompt, vec![0.1; vector_dimensions])
+                .unwrap();
+        }
+    }
+
+    /// Distances of the prompts closest to `embedding`, which must have the dimension of the live table
+    fn nearest_prompt_distances(db: &SqliteManager, embedding: Vec<f32>) -> Vec<f64> {
+        let conn = db.get_connection().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT distance FROM prompt_vec_items WHERE embedding MATCH ?1 AND k = 10 ORDER BY distance")
+            .unwrap();
+        let distances = stmt
+            .query_map(params![cast_slice(&embedding)], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<f64>, _>>()
+            .unwrap();
+        distances
+    }
+
+    fn new_model() -> EmbeddingModelType {
+        EmbeddingModelType::OllamaTextEmbeddingsInference(OllamaTextEmbeddingsInference::SnowflakeArcticEmbedM)
+    }
+
+    #[tokio::test]
+    async fn test_embedding_migration_swaps_tables() {
+        let db = setup_test_db();
+        add_prompts(&db, 3);
+
+        let new_model = new_model();
+        let generator = MockGenerator::new(new_model.clone(), 384);
+        db.migrate_embeddings_to_new_model(&generator, &new_model, false, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(db.get_default_embedding_model().unwrap(), new_model);
+        // The live table now has the dimension of the new model
+        assert_eq!(nearest_prompt_distances(&db, vec![0.0; 384]).len(), 3);
+
+        let progress = db.get_embedding_migration_progress().unwrap().unwrap();
+        assert_eq!(progress.status, EmbeddingMigrationStatus::Completed);
+        assert_eq!(progress.tables[0].table, PROMPT_VEC_TABLE);
+        assert_eq!(progress.tables[0].migrated, 3);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_migration_resumes_from_checkpoints() {
+        let db = setup_test_db();
+        add_prompts(&db, 3);
+        let new_model = new_model();
+
+        // Simulate a migration interrupted after its first item
+        db.start_embedding_migration(&new_model).unwrap();
+        let pending = db.get_pending_vec_items(PROMPT_VEC_TABLE).unwrap();
+        db.checkpoint_vec_item(PROMPT_VEC_TABLE, &pending[0], &[1.0; 384])
+            .unwrap();
+
+        let progress = db.get_embedding_migration_progress().unwrap().unwrap();
+        assert_eq!(progress.status, EmbeddingMigrationStatus::Running);
+        assert_eq!(progress.tables[0].total, 3);
+        assert_eq!(progress.tables[0].migrated, 1);
+
+        // Queries keep using the old model until the migration completes
+        let old_dimensions = EmbeddingModelType::default().vector_dimensions().unwrap();
+        assert_eq!(nearest_prompt_distances(&db, vec![0.1; old_dimensions]).len(), 3);
+
+        let generator = MockGenerator::new(new_model.clone(), 384);
+        db.migrate_embeddings_to_new_model(&generator, &new_model, false, None, None)
+            .await
+            .unwrap();
+
+        // The checkpointed item kept the embedding computed before the interruption
+        let distances = nearest_prompt_distances(&db, vec![1.0; 384]);
+        assert_eq!(distances.len(), 3);
+        assert_eq!(distances[0], 0.0);
+        assert!(distances[1] > 0.0);
+        assert_eq!(db.get_default_embedding_model().unwrap(), new_model);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_migration_reembeds_items_written_during_migration() {
+        let db = setup_test_db();
+        add_prompts(&db, 3);
+        let new_model = new_model();
+
+        db.start_embedding_migration(&new_model).unwrap();
+        let pending = db.get_pending_vec_items(PROMPT_VEC_TABLE).unwrap();
+        for item in &pending {
+            db.checkpoint_vec_item(PROMPT_VEC_TABLE, item, &[1.0; 384]).unwrap();
+        }
+        assert!(db.get_pending_vec_items(PROMPT_VEC_TABLE).unwrap().is_empty());
+
+        // An edit after its checkpoint makes the prompt pending again
+        {
+            let conn = db.get_connection().unwrap();
+            conn.execute(
+                "UPDATE hanzo_prompts SET prompt = 'Edited content' WHERE name = 'prompt 0'",
+                [],
+            )
+            .unwrap();
+        }
+        assert_eq!(db.get_pending_vec_items(PROMPT_VEC_TABLE).unwrap().len(), 1);
+        assert!(!db.swap_embedding_migration_tables(&new_model).unwrap());
+
+        let generator = MockGenerator::new(new_model.clone(), 384);
+        db.migrate_embeddings_to_new_model(&generator, &new_model, false, None, None)
+            .await
+            .unwrap();
+
+        // The edited prompt has a single, new embedding
+        let distances = nearest_prompt_distances(&db, vec![1.0; 384]);
+        assert_eq!(distances.len(), 3);
+        assert_eq!(distances.iter().filter(|distance| **distance == 0.0).count(), 2);
+    }
+}
@@ -90,6 +90,14 @@ pub enum SqliteManagerError {
     ValidationError(String),
     #[error("Tool type mismatch")]
     ToolTypeMismatch,
+    #[error("Tool entitlement not found: {0}")]
+    ToolEntitlementNotFound(String),
+    #[error("Tool entitlement exhausted: {0}")]
+    ToolEntitlementExhausted(String),
+    #[error("x402 payment already used: {0}")]
+    X402ReceiptAlreadyExists(String),
+    #[error("x402 price not found: {0}")]
+    X402PriceNotFound(String),
     // Add other error variants as needed
 }
 
@@ -1,7 +1,8 @@
 use crate::{SqliteManager, SqliteManagerError};
 use rusqlite::params;
+use std::collections::{BTreeMap, HashMap};
 use hanzo_messages::{
-    schemas::hanzo_fs::{ParsedFile, HanzoFileChunk},
+    schemas::hanzo_fs::{ChunkingStrategy, ParsedFile, HanzoFileChunk},
     hanzo_utils::hanzo_path::HanzoPath,
 };
 
@@ -28,7 +29,8 @@ impl SqliteManager {
                 created_time INTEGER,
                 tags TEXT,
                 total_tokens INTEGER,
-                total_characters INTEGER
+                total_characters INTEGER,
+                chunking_strategy TEXT
             );",
             [],
         )?;
@@ -38,6 +40,15 @@ impl SqliteManager {
             [],
         )?;
 
+        // Chunking strategy set on a folder, used for files uploaded to it or to its subfolders
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS folder_chunking_strategies (
+                folder_path TEXT PRIMARY KEY,
+                chunking_strategy TEXT NOT NULL
+            );",
+            [],
+        )?;
+
         // chunks table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS chunks (
@@ -57,6 +68,22 @@ impl SqliteManager {
             [],
         )?;
 
+        // Full-text index over chunk text, for the exact identifiers, error codes and names embeddings miss.
+        // The text itself stays in `chunks`; the chunk functions below keep the index in sync.
+        let chunks_fts_exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'chunks_fts')",
+            [],
+            |row| row.get(0),
+        )?;
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS chunks_fts USING fts5(chunk, content='chunks', content_rowid='id');",
+            [],
+        )?;
+        if !chunks_fts_exists {
+            // Index the chunks created before the table existed
+            conn.execute("INSERT INTO chunks_fts(chunks_fts) VALUES('rebuild');", [])?;
+        }
+
         // Create our new virtual table for chunk embeddings using sqlite-vec
         // Using dynamic dimensions based on default embedding model
         let default_model = hanzo_embed::model_type::EmbeddingModelType::default();
@@ -103,8 +130,9 @@ impl SqliteManager {
         let relative_path = Self::normalize_path(&pf.relative_path);
         tx.execute(
             "INSERT INTO parsed_files (relative_path, original_extension, description, source, embedding_model_used, 
-                                       keywords, distribution_info, created_time, tags, total_tokens, total_characters)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                                       keywords, distribution_info, created_time, tags, total_tokens, total_characters,
+                                       chunking_strategy)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             params![
                 relative_path,
                 pf.original_extension,
@@ -116,7 +144,8 @@ impl SqliteManager {
                 pf.created_time,
                 pf.tags,
                 pf.total_tokens,
-                pf.total_characters
+                pf.total_characters,
+                Self::chunking_strategy_to_column(&pf.chunking_strategy)?
             ],
         )?;
 
@@ -132,7 +161,7 @@ impl SqliteManager {
         let mut stmt = conn.prepare(
             "
             SELECT id, relative_path, original_extension, description, source, embedding_model_used, keywords,
-                   distribution_info, created_time, tags, total_tokens, total_characters
+                   distribution_info, created_time, tags, total_tokens, total_characters, chunking_strategy
             FROM parsed_files
             WHERE relative_path = ?",
         )?;
@@ -151,6 +180,7 @@ impl SqliteManager {
                 tags: row.get(9)?,
                 total_tokens: row.get(10)?,
                 total_characters: row.get(11)?,
+                chunking_strategy: Self::chunking_strategy_from_column(row.get(12)?),
             })
         });
 
@@ -179,8 +209,9 @@ impl SqliteManager {
         tx.execute(
             "UPDATE parsed_files
              SET relative_path = ?1, original_extension = ?2, description = ?3, source = ?4, embedding_model_used = ?5,
-                 keywords = ?6, distribution_info = ?7, created_time = ?8, tags = ?9, total_tokens = ?10, total_characters = ?11
-             WHERE id = ?12",
+                 keywords = ?6, distribution_info = ?7, created_time = ?8, tags = ?9, total_tokens = ?10, total_characters = ?11,
+                 chunking_strategy = ?12
+             WHERE id = ?13",
             params![
                 relative_path,
                 pf.original_extension,
@@ -193,6 +224,7 @@ impl SqliteManager {
                 pf.tags,
                 pf.total_tokens,
                 pf.total_characters,
+                Self::chunking_strategy_to_column(&pf.chunking_strategy)?,
                 pf.id,
             ],
         )?;
@@ -215,6 +247,11 @@ impl SqliteManager {
             return Err(SqliteManagerError::DataNotFound);
         }
 
+        // Chunks go with the file (ON DELETE CASCADE), so drop them from the full-text index first
+        tx.execute(
+            "INSERT INTO chunks_fts (chunks_fts, rowid, chunk) SELECT 'delete', id, chunk FROM chunks WHERE parsed_file_id = ?",
+            [parsed_file_id],
+        )?;
         tx.execute("DELETE FROM parsed_files WHERE id = ?", [parsed_file_id])?;
         tx.commit()?;
 
@@ -248,14 +285,25 @@ impl SqliteManager {
 
         // 2) Insert into `chunks` table
         tx.execute(
-            "INSERT INTO chunks (parsed_file_id, position, chunk)
-             VALUES (?1, ?2, ?3)",
-            params![chunk.parsed_file_id, chunk.position, chunk.content],
+            "INSERT INTO chunks (parsed_file_id, position, chunk, metadata)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                chunk.parsed_file_id,
+                chunk.position,
+                chunk.content,
+                Self::chunk_metadata_to_column(&chunk.metadata)?
+            ],
         )?;
 
         // 3) Retrieve the auto-generated `chunk_id`
         let new_chunk_id = tx.last_insert_rowid();
 
+        // Index the text for keyword search
+        tx.execute(
+            "INSERT INTO chunks_fts (rowid, chunk) VALUES (?1, ?2)",
+            params![new_chunk_id, chunk.content],
+        )?;
+
         // 4) If we have an embedding, insert into `chunk_vec`
         if let Some(vec_data) = embedding {
             tx.execute(
@@ -288,6 +336,7 @@ impl SqliteManager {
                 c.parsed_file_id,
                 c.position,
                 c.chunk,
+                c.metadata,
                 cv.embedding AS vec_data
             FROM chunks c
             LEFT JOIN chunk_vec cv
@@ -305,6 +354,7 @@ impl SqliteManager {
             let parsed_file_id: i64 = row.get("parsed_file_id")?;
             let position: i64 = row.get("position")?;
             let content: String = row.get("chunk")?;
+            let metadata: Option<String> = row.get("metadata")?;
 
             // Optional embedding column:
             let maybe_vec_data: Option<Vec<u8>> = row.get("vec_data")?;
@@ -319,6 +369,7 @@ impl SqliteManager {
                 parsed_file_id,
                 position,
                 content,
+                metadata: Self::chunk_metadata_from_column(metadata),
             };
 
             Ok((chunk_struct, embedding_opt))
@@ -334,7 +385,10 @@ impl SqliteManager {
     pub fn get_chunks_for_parsed_file(&self, parsed_file_id: i64) -> Result<Vec<HanzoFileChunk>, SqliteManagerError> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, parsed_file_id, position, chunk FROM chunks WHERE parsed_file_id = ? ORDER BY position",
+            "SELECT id, parsed_file_id, position, chunk, metadata
+             FROM chunks
+             WHERE parsed_file_id = ?
+             ORDER BY position",
         )?;
         let rows = stmt.query_map([parsed_file_id], |row| {
             Ok(HanzoFileChunk {
@@ -342,6 +396,7 @@ impl SqliteManager {
                 parsed_file_id: row.get(1)?,
                 position: row.get(2)?,
                 content: row.get(3)?,
+                metadata: Self::chunk_metadata_from_column(row.get(4)?),
             })
         })?;
 
@@ -369,7 +424,11 @@ impl SqliteManager {
         // 2) Remove embedding from `chunk_vec` (if any)
         tx.execute("DELETE FROM chunk_vec WHERE chunk_id = ?", [chunk_id])?;
 
-        // 3) Remove the chunk itself
+        // 3) Remove the chunk from the full-text index, then the chunk itself
+        tx.execute(
+            "INSERT INTO chunks_fts (chunks_fts, rowid, chunk) SELECT 'delete', id, chunk FROM chunks WHERE id = ?",
+            [chunk_id],
+        )?;
         tx.execute("DELETE FROM chunks WHERE id = ?", [chunk_id])?;
 
         tx.commit()?;
@@ -385,7 +444,7 @@ impl SqliteManager {
     ) -> Result<Vec<HanzoFileChunk>, SqliteManagerError> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
-            "SELECT id, parsed_file_id, position, chunk
+            "SELECT id, parsed_file_id, position, chunk, metadata
              FROM chunks
              WHERE parsed_file_id = ? AND position BETWEEN ? AND ?
              ORDER BY position",
@@ -400,6 +459,7 @@ impl SqliteManager {
                 parsed_file_id: row.get(1)?,
                 position: row.get(2)?,
                 content: row.get(3)?,
+                metadata: Self::chunk_metadata_from_column(row.get(4)?),
             })
         })?;
 
@@ -416,6 +476,116 @@ impl SqliteManager {
         query_embedding: Vec<f32>,
         limit: usize,
     ) -> Result<Vec<(HanzoFileChunk, f64)>, SqliteManagerError> {
+        let chunk_ids_and_distances = self.search_chunk_ids_by_vector(parsed_file_ids, query_embedding, limit)?;
+
+        // Fetch the chunk details for each chunk_id
+        let mut results = Vec::new();
+        for (chunk_id, distance) in chunk_ids_and_distances {
+            if let Some(chunk) = self.get_chunk_with_embedding(chunk_id)? {
+                results.push((chunk.0, distance)); // Assuming get_chunk_with_embedding returns (HanzoFileChunk,
+                                                   // Option<Vec<f32>>)
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Keyword search over chunk text using the FTS5 index. Returns chunks with their BM25 score
+    /// (lower is better, like a distance).
+    pub fn search_chunks_by_keywords(
+        &self,
+        parsed_file_ids: &[i64],
+        query_text: &str,
+        limit: usize,
+    ) -> Result<Vec<(HanzoFileChunk, f64)>, SqliteManagerError> {
+        let mut results = Vec::new();
+        for (chunk_id, score) in self.search_chunk_ids_by_keywords(parsed_file_ids, query_text, limit)? {
+            if let Some((chunk, _)) = self.get_chunk_with_embedding(chunk_id)? {
+                results.push((chunk, score));
+            }
+        }
+        Ok(results)
+    }
+
+    /// Hybrid search: runs the vector and keyword searches and fuses both rankings with
+    /// reciprocal-rank fusion. `keyword_weight` (0.0 to 1.0) is the share of the keyword ranking.
+    /// Returns chunks with their fused score (higher is better).
+    pub fn search_chunks_hybrid(
+        &self,
+        parsed_file_ids: &[i64],
+        query_embedding: Vec<f32>,
+        query_text: &str,
+        limit: usize,
+        keyword_weight: f64,
+    ) -> Result<Vec<(HanzoFileChunk, f64)>, SqliteManagerError> {
+        let keyword_weight = keyword_weight.clamp(0.0, 1.0);
+
+        let vector_ids: Vec<i64> = self
+            .search_chunk_ids_by_vector(parsed_file_ids, query_embedding, limit)?
+            .into_iter()
+            .map(|(chunk_id, _)| chunk_id)
+            .collect();
+        let keyword_ids: Vec<i64> = self
+            .search_chunk_ids_by_keywords(parsed_file_ids, query_text, limit)?
+            .into_iter()
+            .map(|(chunk_id, _)| chunk_id)
+            .collect();
+
+        let fused = reciprocal_rank_fusion(&[(&vector_ids, 1.0 - keyword_weight), (&keyword_ids, keyword_weight)]);
+
+        let mut results = Vec::new();
+        for (chunk_id, score) in fused.into_iter().take(limit) {
+            if let Some((chunk, _)) = self.get_chunk_with_embedding(chunk_id)? {
+                results.push((chunk, score));
+            }
+        }
+        Ok(results)
+    }
+
+    fn search_chunk_ids_by_keywords(
+        &self,
+        parsed_file_ids: &[i64],
+        query_text: &str,
+        limit: usize,
+    ) -> Result<Vec<(i64, f64)>, SqliteManagerError> {
+        let match_query = fts_match_query(query_text);
+        if match_query.is_empty() || parsed_file_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.get_connection()?;
+        let placeholders = parsed_file_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let sql = format!(
+            r#"
+            SELECT c.id, bm25(chunks_fts) AS score
+            FROM chunks_fts
+            JOIN chunks c ON c.id = chunks_fts.rowid
+            WHERE chunks_fts MATCH ?
+            AND c.parsed_file_id IN ({})
+            ORDER BY score
+            LIMIT ?
+        "#,
+            placeholders
+        );
+
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![&match_query];
+        params.extend(parsed_file_ids.iter().map(|id| id as &dyn rusqlite::ToSql));
+        let limit_binding = limit as i64;
+        params.push(&limit_binding);
+
+        let mut stmt = conn.prepare(&sql)?;
+        let results = stmt
+            .query_map(params.as_slice(), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(results)
+    }
+
+    fn search_chunk_ids_by_vector(
+        &self,
+        parsed_file_ids: &[i64],
+        query_embedding: Vec<f32>,
+        limit: usize,
+    ) -> Result<Vec<(i64, f64)>, SqliteManagerError> {
         let conn = self.get_connection()?;
 
         // Serialize the vector to a JSON array string
@@ -458,16 +628,104 @@ impl SqliteManager {
             .query_map(params_slice.as_slice(), |row| Ok((row.get(0)?, row.get(1)?)))?
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Fetch the chunk details for each chunk_id
-        let mut results = Vec::new();
-        for (chunk_id, distance) in chunk_ids_and_distances {
-            if let Some(chunk) = self.get_chunk_with_embedding(chunk_id)? {
-                results.push((chunk.0, distance)); // Assuming get_chunk_with_embedding returns (HanzoFileChunk,
-                                                   // Option<Vec<f32>>)
+        Ok(chunk_ids_and_distances)
+    }
+
+    // -------------------------
+    // Chunking Strategies
+    // -------------------------
+
+    /// Sets the chunking strategy of files added to `folder_path` or its subfolders.
+    /// `None` clears it, so the folder goes back to the strategy of its parents.
+    pub fn set_folder_chunking_strategy(
+        &self,
+        folder_path: &str,
+        strategy: Option<&ChunkingStrategy>,
+    ) -> Result<(), SqliteManagerError> {
+        let folder_path = Self::normalize_folder_path(folder_path);
+        let conn = self.get_connection()?;
+
+        match strategy {
+            Some(strategy) => {
+                conn.execute(
+                    "INSERT INTO folder_chunking_strategies (folder_path, chunking_strategy) VALUES (?1, ?2)
+                     ON CONFLICT(folder_path) DO UPDATE SET chunking_strategy = excluded.chunking_strategy",
+                    params![folder_path, serde_json::to_string(strategy)?],
+                )?;
+            }
+            None => {
+                conn.execute(
+                    "DELETE FROM folder_chunking_strategies WHERE folder_path = ?",
+                    [folder_path],
+                )?;
             }
         }
 
-        Ok(results)
+        Ok(())
+    }
+
+    /// The chunking strategy set on `folder_path` itself, ignoring its parents.
+    pub fn get_folder_chunking_strategy(
+        &self,
+        folder_path: &str,
+    ) -> Result<Option<ChunkingStrategy>, SqliteManagerError> {
+        let folder_path = Self::normalize_folder_path(folder_path);
+        let conn = self.get_connection()?;
+
+        let res = conn.query_row(
+            "SELECT chunking_strategy FROM folder_chunking_strategies WHERE folder_path = ?",
+            [folder_path],
+            |row| row.get::<_, String>(0),
+        );
+
+        match res {
+            Ok(strategy) => Ok(Some(serde_json::from_str(&strategy)?)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(SqliteManagerError::DatabaseError(e)),
+        }
+    }
+
+    /// The chunking strategy that applies to the file at `rel_path`: the one set on its closest folder.
+    pub fn get_chunking_strategy_for_path(
+        &self,
+        rel_path: &str,
+    ) -> Result<Option<ChunkingStrategy>, SqliteManagerError> {
+        let rel_path = Self::normalize_folder_path(rel_path);
+
+        let mut folder = rel_path.as_str();
+        while let Some(index) = folder.rfind('/') {
+            folder = &folder[..index];
+            if let Some(strategy) = self.get_folder_chunking_strategy(folder)? {
+                return Ok(Some(strategy));
+            }
+        }
+
+        // Strategy of the root folder, if any
+        self.get_folder_chunking_strategy("")
+    }
+
+    /// Folder paths are stored without leading or trailing slashes; the root folder is "".
+    fn normalize_folder_path(path: &str) -> String {
+        Self::normalize_path(path).trim_matches('/').to_string()
+    }
+
+    fn chunking_strategy_to_column(strategy: &Option<ChunkingStrategy>) -> Result<Option<String>, SqliteManagerError> {
+        Ok(strategy.as_ref().map(serde_json::to_string).transpose()?)
+    }
+
+    /// An unreadable strategy is treated like a missing one: the file was chunked by the parser.
+    fn chunking_strategy_from_column(value: Option<String>) -> Option<ChunkingStrategy> {
+        value.and_then(|value| serde_json::from_str(&value).ok())
+    }
+
+    fn chunk_metadata_to_column(
+        metadata: &Option<BTreeMap<String, String>>,
+    ) -> Result<Option<String>, SqliteManagerError> {
+        Ok(metadata.as_ref().map(serde_json::to_string).transpose()?)
+    }
+
+    fn chunk_metadata_from_column(value: Option<String>) -> Option<BTreeMap<String, String>> {
+        value.and_then(|value| serde_json::from_str(&value).ok())
     }
 
     // -------------------------
@@ -491,6 +749,15 @@ impl SqliteManager {
             params![old_prefix, new_prefix, like_pattern],
         )?;
 
+        let old_folder = Self::normalize_folder_path(&old_prefix);
+        let new_folder = Self::normalize_folder_path(&new_prefix);
+        tx.execute(
+            "UPDATE folder_chunking_strategies
+             SET folder_path = ?2 || SUBSTR(folder_path, LENGTH(?1) + 1)
+             WHERE folder_path = ?1 OR folder_path LIKE ?3",
+            params![old_folder, new_folder, format!("{}/%", old_folder)],
+        )?;
+
         tx.commit()?;
         Ok(())
     }
@@ -503,7 +770,7 @@ impl SqliteManager {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
             "SELECT id, relative_path, original_extension, description, source, embedding_model_used, keywords,
-                    distribution_info, created_time, tags, total_tokens, total_characters
+                    distribution_info, created_time, tags, total_tokens, total_characters, chunking_strategy
              FROM parsed_files
              WHERE relative_path LIKE ? AND relative_path NOT LIKE ?",
         )?;
@@ -525,6 +792,7 @@ impl SqliteManager {
                 tags: row.get(9)?,
                 total_tokens: row.get(10)?,
                 total_characters: row.get(11)?,
+                chunking_strategy: Self::chunking_strategy_from_column(row.get(12)?),
             })
         })?;
 
@@ -548,7 +816,7 @@ impl SqliteManager {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
             "SELECT id, relative_path, original_extension, description, source, embedding_model_used, keywords,
-                    distribution_info, created_time, tags, total_tokens, total_characters
+                    distribution_info, created_time, tags, total_tokens, total_characters, chunking_strategy
              FROM parsed_files",
         )?;
 
@@ -566,6 +834,7 @@ impl SqliteManager {
                 tags: row.get(9)?,
                 total_tokens: row.get(10)?,
                 total_characters: row.get(11)?,
+                chunking_strategy: Self::chunking_strategy_from_column(row.get(12)?),
             })
         })?;
 
@@ -582,7 +851,7 @@ impl SqliteManager {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare(
             "SELECT id, relative_path, original_extension, description, source, embedding_model_used, keywords,
-                    distribution_info, created_time, tags, total_tokens, total_characters
+                    distribution_info, created_time, tags, total_tokens, total_characters, chunking_strategy
              FROM parsed_files
              WHERE relative_path LIKE ?",
         )?;
@@ -603,6 +872,7 @@ impl SqliteManager {
                 tags: row.get(9)?,
                 total_tokens: row.get(10)?,
                 total_characters: row.get(11)?,
+                chunking_strategy: Self::chunking_strategy_from_column(row.get(12)?),
             })
         })?;
 
@@ -614,6 +884,34 @@ impl SqliteManager {
     }
 }
 
+/// Smoothing constant of reciprocal-rank fusion; 60 is the value from the original RRF paper
+const RRF_K: f64 = 60.0;
+
+/// Fuses ranked lists of chunk ids: each id scores `weight / (RRF_K + rank)` per list it appears in.
+/// Returns ids ordered by fused score, best first.
+fn reciprocal_rank_fusion(rankings: &[(&[i64], f64)]) -> Vec<(i64, f64)> {
+    let mut scores: HashMap<i64, f64> = HashMap::new();
+    for (ranking, weight) in rankings {
+        for (rank, chunk_id) in ranking.iter().enumerate() {
+            *scores.entry(*chunk_id).or_default() += weight / (RRF_K + rank as f64 + 1.0);
+        }
+    }
+
+    let mut fused: Vec<(i64, f64)> = scores.into_iter().collect();
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    fused
+}
+
+/// Turns free text into an FTS5 query matching any of its words. Each word is quoted so
+/// punctuation and FTS5 operators in the text are taken literally.
+fn fts_match_query(text: &str) -> String {
+    text.split_whitespace()
+        .filter(|word| word.chars().any(|c| c.is_alphanumeric()))
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -644,6 +942,7 @@ mod tests {
             tags: None,
             total_tokens: None,
             total_characters: None,
+            chunking_strategy: None,
         }
     }
 
@@ -791,6 +1090,7 @@ mod tests {
             parsed_file_id: parsed_file.id.unwrap(),
             position: 1,
             content: "This is a test chunk.".to_string(),
+            metadata: None,
         };
 
         // Add the chunk to the database
@@ -804,6 +1104,32 @@ mod tests {
         assert_eq!(chunks[0].content, "This is a test chunk.");
     }
 
+    #[test]
+    fn test_chunk_metadata_round_trip() {
+        let db = setup_test_db();
+
+        let parsed_file = create_test_parsed_file(1, "src/main.rs");
+        db.add_parsed_file(&parsed_file).unwrap();
+
+        let metadata = BTreeMap::from([
+            (HanzoFileChunk::SYMBOL_METADATA_KEY.to_string(), "fn main".to_string()),
+            (HanzoFileChunk::LINE_RANGE_METADATA_KEY.to_string(), "3-7".to_string()),
+        ]);
+        let chunk = HanzoFileChunk {
+            chunk_id: None,
+            parsed_file_id: parsed_file.id.unwrap(),
+            position: 0,
+            content: "fn main() {}".to_string(),
+            metadata: Some(metadata.clone()),
+        };
+        let chunk_id = db.create_chunk_with_embedding(&chunk, None).unwrap();
+
+        let (stored_chunk, _) = db.get_chunk_with_embedding(chunk_id).unwrap().unwrap();
+        assert_eq!(stored_chunk.metadata, Some(metadata.clone()));
+        let chunks = db.get_chunks_for_parsed_file(parsed_file.id.unwrap()).unwrap();
+        assert_eq!(chunks[0].metadata, Some(metadata));
+    }
+
     #[test]
     fn test_vector_search_on_specific_parsed_file() {
         let db = setup_test_db();
@@ -820,12 +1146,14 @@ mod tests {
             parsed_file_id: parsed_file1.id.unwrap(),
             position: 1,
             content: "This is the first chunk of file1.".to_string(),
+            metadata: None,
         };
         let chunk2_file1 = HanzoFileChunk {
             chunk_id: None,
             parsed_file_id: parsed_file1.id.unwrap(),
             position: 2,
             content: "This is the second chunk of file1.".to_string(),
+            metadata: None,
         };
         db.create_chunk_with_embedding(&chunk1_file1, Some(&SqliteManager::generate_vector_for_testing(0.9)))
             .unwrap();
@@ -838,12 +1166,14 @@ mod tests {
             parsed_file_id: parsed_file2.id.unwrap(),
             position: 1,
             content: "This is the first chunk of file2.".to_string(),
+            metadata: None,
         };
         let chunk2_file2 = HanzoFileChunk {
             chunk_id: None,
             parsed_file_id: parsed_file2.id.unwrap(),
             position: 2,
             content: "This is the second chunk of file2.".to_string(),
+            metadata: None,
         };
         db.create_chunk_with_embedding(&chunk1_file2, Some(&SqliteManager::generate_vector_for_testing(0.9)))
             .unwrap();
@@ -886,6 +1216,115 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_keyword_search_on_chunks() {
+        let db = setup_test_db();
+
+        let parsed_file = create_test_parsed_file(1, "errors.txt");
+        db.add_parsed_file(&parsed_file).unwrap();
+
+        let contents = [
+            "The request failed with ERR_CONN_RESET after the handshake.",
+            "Retries are configured with max_retries in the client config.",
+            "Nothing to see here.",
+        ];
+        for (position, content) in contents.iter().enumerate() {
+            let chunk = HanzoFileChunk {
+                chunk_id: None,
+                parsed_file_id: parsed_file.id.unwrap(),
+                position: position as i64,
+                content: content.to_string(),
+                metadata: None,
+            };
+            db.create_chunk_with_embedding(&chunk, Some(&SqliteManager::generate_vector_for_testing(0.5)))
+                .unwrap();
+        }
+
+        // Exact identifiers are matched, punctuation in the query is harmless
+        let results = db
+            .search_chunks_by_keywords(&[parsed_file.id.unwrap()], "what is ERR_CONN_RESET?", 10)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.content, contents[0]);
+
+        // Other files are not searched
+        let results = db.search_chunks_by_keywords(&[42], "max_retries", 10).unwrap();
+        assert!(results.is_empty());
+
+        // Removed chunks leave the index
+        let chunk_id = db.get_chunks_for_parsed_file(parsed_file.id.unwrap()).unwrap()[1]
+            .chunk_id
+            .unwrap();
+        db.remove_chunk_with_embedding(chunk_id).unwrap();
+        let results = db
+            .search_chunks_by_keywords(&[parsed_file.id.unwrap()], "max_retries", 10)
+            .unwrap();
+        assert!(results.is_empty());
+
+        // And so do the chunks of removed files
+        db.remove_parsed_file(parsed_file.id.unwrap()).unwrap();
+        let results = db
+            .search_chunks_by_keywords(&[parsed_file.id.unwrap()], "ERR_CONN_RESET", 10)
+            .unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_hybrid_search_on_chunks() {
+        let db = setup_test_db();
+
+        let parsed_file = create_test_parsed_file(1, "file.txt");
+        db.add_parsed_file(&parsed_file).unwrap();
+
+        // The keyword match is the furthest chunk from the query embedding
+        let chunks = [
+            ("A chunk close to the query embedding.", 0.1),
+            ("Another chunk close to the query embedding.", 0.2),
+            ("The only chunk mentioning ERR_QUOTA_EXCEEDED.", 0.9),
+        ];
+        for (position, (content, value)) in chunks.iter().enumerate() {
+            let chunk = HanzoFileChunk {
+                chunk_id: None,
+                parsed_file_id: parsed_file.id.unwrap(),
+                position: position as i64,
+                content: content.to_string(),
+                metadata: None,
+            };
+            db.create_chunk_with_embedding(&chunk, Some(&SqliteManager::generate_vector_for_testing(*value)))
+                .unwrap();
+        }
+
+        let query_embedding = SqliteManager::generate_vector_for_testing(0.1);
+        let search = |keyword_weight: f64| {
+            db.search_chunks_hybrid(
+                &[parsed_file.id.unwrap()],
+                query_embedding.clone(),
+                "ERR_QUOTA_EXCEEDED",
+                3,
+                keyword_weight,
+            )
+            .unwrap()
+        };
+
+        let results = search(0.0);
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[2].0.content, chunks[2].0);
+
+        let results = search(0.7);
+        assert_eq!(results[0].0.content, chunks[2].0);
+        assert!(results.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let fused = reciprocal_rank_fusion(&[(&[1, 2, 3], 0.5), (&[3, 4], 0.5)]);
+        let ids: Vec<i64> = fused.iter().map(|(chunk_id, _)| *chunk_id).collect();
+
+        // 3 is in both lists so it comes first, then the heads of each list
+        assert_eq!(ids, vec![3, 1, 2, 4]);
+        assert!((fused[0].1 - (0.5 / 63.0 + 0.5 / 61.0)).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_get_neighboring_chunks() {
         let db = setup_test_db();
@@ -901,6 +1340,7 @@ mod tests {
                 parsed_file_id: parsed_file.id.unwrap(),
                 position: i,
                 content: format!("This is chunk number {}.", i),
+                metadata: None,
             };
             db.create_chunk_with_embedding(&chunk, None).unwrap();
         }
@@ -943,4 +1383,55 @@ mod tests {
             .iter()
             .any(|pf| pf.relative_path == "docs/other/2024/march.txt"));
     }
+
+    #[test]
+    fn test_parsed_file_keeps_chunking_strategy() {
+        let db = setup_test_db();
+
+        let mut parsed_file = create_test_parsed_file(1, "docs/manual.md");
+        parsed_file.chunking_strategy = Some(ChunkingStrategy::Section { chunk_size: 800 });
+        db.add_parsed_file(&parsed_file).unwrap();
+
+        let fetched = db.get_parsed_file_by_rel_path("docs/manual.md").unwrap().unwrap();
+        assert_eq!(
+            fetched.chunking_strategy,
+            Some(ChunkingStrategy::Section { chunk_size: 800 })
+        );
+    }
+
+    #[test]
+    fn test_folder_chunking_strategy_is_inherited() {
+        let db = setup_test_db();
+
+        let root_strategy = ChunkingStrategy::FixedSize { chunk_size: 500 };
+        let docs_strategy = ChunkingStrategy::Sentence {
+            chunk_size: 1000,
+            overlap: 1,
+        };
+        db.set_folder_chunking_strategy("/", Some(&root_strategy)).unwrap();
+        db.set_folder_chunking_strategy("docs/", Some(&docs_strategy)).unwrap();
+
+        assert_eq!(
+            db.get_chunking_strategy_for_path("docs/reports/january.txt").unwrap(),
+            Some(docs_strategy.clone())
+        );
+        assert_eq!(
+            db.get_chunking_strategy_for_path("other/march.txt").unwrap(),
+            Some(root_strategy.clone())
+        );
+        assert_eq!(db.get_folder_chunking_strategy("docs/reports").unwrap(), None);
+
+        // Moving the folder moves its strategy
+        db.update_folder_paths("docs/", "archive/").unwrap();
+        assert_eq!(
+            db.get_chunking_strategy_for_path("archive/january.txt").unwrap(),
+            Some(docs_strategy)
+        );
+
+        db.set_folder_chunking_strategy("archive", None).unwrap();
+        assert_eq!(
+            db.get_chunking_strategy_for_path("archive/january.txt").unwrap(),
+            Some(root_strategy)
+        );
+    }
 }
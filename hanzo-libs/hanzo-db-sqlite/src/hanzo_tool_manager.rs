@@ -300,6 +300,36 @@ impl SqliteManager {
                 new_python.config = merged_config;
                 (old_config, HanzoTool::Python(new_python, is_enabled))
             }
+            (HanzoTool::Wasm(old_wasm, _), HanzoTool::Wasm(mut new_wasm, is_enabled)) => {
+                let old_config = old_wasm.config.clone();
+
+                // Merge configuration
+                let merged_config: Vec<ToolConfig> = new_wasm
+                    .config
+                    .into_iter()
+                    .map(|new_entry| match new_entry {
+                        ToolConfig::BasicConfig(new_basic) => {
+                            let preserved_value = old_config.iter().find_map(|old_entry| {
+                                let ToolConfig::BasicConfig(old_basic) = old_entry;
+                                if old_basic.key_name == new_basic.key_name {
+                                    return old_basic.key_value.clone();
+                                }
+                                None
+                            });
+                            ToolConfig::BasicConfig(BasicConfig {
+                                key_name: new_basic.key_name,
+                                description: new_basic.description,
+                                required: new_basic.required,
+                                type_name: new_basic.type_name,
+                                key_value: preserved_value,
+                            })
+                        }
+                    })
+                    .collect();
+
+                new_wasm.config = merged_config;
+                (old_config, HanzoTool::Wasm(new_wasm, is_enabled))
+            }
             (HanzoTool::Rust(_old_rust, _), HanzoTool::Rust(new_rust, is_enabled)) => {
                 (Vec::new(), HanzoTool::Rust(new_rust, is_enabled))
             }
@@ -1195,6 +1225,18 @@ impl SqliteManager {
                         }
                     }
                 }
+                HanzoTool::Wasm(wasm_tool, _) => {
+                    for (key_to_set, value_to_set) in &values {
+                        for config_entry in &mut wasm_tool.config {
+                            let ToolConfig::BasicConfig(basic_config) = config_entry;
+                            if &basic_config.key_name == key_to_set {
+                                basic_config.key_value = Some(value_to_set.clone());
+                                config_updated = true;
+                                break;
+                            }
+                        }
+                    }
+                }
                 // Handle other tool types if they have configurations in the future
                 _ => continue, // Skip tools without applicable config structures
             }
@@ -1267,6 +1309,7 @@ mod tests {
     use hanzo_tools::tools::tool_types::OperatingSystem;
     use hanzo_tools::tools::tool_types::RunnerType;
     use hanzo_tools::tools::tool_types::ToolResult;
+    use hanzo_tools::tools::wasm_tools::{WasmCapabilities, WasmTool};
     use std::collections::HashMap;
     use std::path::PathBuf;
     use tempfile::NamedTempFile;
@@ -1323,6 +1366,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         // Wrap the DenoTool in a HanzoTool::Deno variant
@@ -1407,6 +1451,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_router_key = ToolRouterKey::new(
@@ -1441,6 +1486,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_router_key = ToolRouterKey::new(
@@ -1475,6 +1521,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let hanzo_tool_1 = HanzoTool::Deno(deno_tool_1, true);
@@ -1546,6 +1593,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_router_key = ToolRouterKey::new(
@@ -1580,6 +1628,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_router_key = ToolRouterKey::new(
@@ -1614,6 +1663,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         // Wrap the DenoTools in HanzoTool::Deno variants
@@ -1715,6 +1765,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         // Wrap the DenoTool in a HanzoTool::Deno variant
@@ -1787,6 +1838,7 @@ mod tests {
                 runner: RunnerType::OnlyHost,
                 operating_system: vec![OperatingSystem::Windows],
                 tool_set: None,
+                permissions: None,
             },
             DenoTool {
                 name: "Text Analysis Helper".to_string(),
@@ -1813,6 +1865,7 @@ mod tests {
                 runner: RunnerType::OnlyHost,
                 operating_system: vec![OperatingSystem::Windows],
                 tool_set: None,
+                permissions: None,
             },
             DenoTool {
                 name: "Data Visualization Tool".to_string(),
@@ -1839,6 +1892,7 @@ mod tests {
                 runner: RunnerType::OnlyHost,
                 operating_system: vec![OperatingSystem::Windows],
                 tool_set: None,
+                permissions: None,
             },
         ];
 
@@ -1915,6 +1969,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_router_key = ToolRouterKey::new(
@@ -1949,6 +2004,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         // Add both tools to the database
@@ -2050,6 +2106,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_router_key = ToolRouterKey::new(
@@ -2084,6 +2141,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let usage_type = UsageType::PerUse(ToolPrice::Payment(vec![PaymentRequirements {
@@ -2257,6 +2315,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_router_key = ToolRouterKey::new(
@@ -2291,6 +2350,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_router_key = ToolRouterKey::new(
@@ -2325,6 +2385,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         // Add tools to database with specific vectors
@@ -2419,6 +2480,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let tool_router_key = ToolRouterKey::new(
@@ -2453,6 +2515,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         // Wrap the DenoTools in HanzoTool::Deno variants
@@ -2559,6 +2622,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
         let hanzo_tool_v1 = HanzoTool::Deno(deno_tool_v1.clone(), true);
         let vector_v1 = SqliteManager::generate_vector_for_testing(0.1);
@@ -2605,6 +2669,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
         let hanzo_tool_v2 = HanzoTool::Deno(deno_tool_v2.clone(), true);
 
@@ -2756,6 +2821,72 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_add_search_and_upgrade_wasm_tool() {
+        let manager = setup_test_db().await;
+        let wasm_tool = |version: &str, key_value: Option<serde_json::Value>| WasmTool {
+            version: version.to_string(),
+            name: "Wasm Word Count".to_string(),
+            tool_router_key: None,
+            homepage: None,
+            author: "Test Author".to_string(),
+            mcp_enabled: Some(false),
+            description: "Counts the words of a text in a WASM sandbox".to_string(),
+            keywords: vec!["wasm".to_string()],
+            config: vec![ToolConfig::BasicConfig(BasicConfig {
+                key_name: "separator".to_string(),
+                description: "Word separator".to_string(),
+                required: false,
+                type_name: Some("string".to_string()),
+                key_value,
+            })],
+            input_args: Parameters::new(),
+            output_arg: ToolOutputArg::empty(),
+            activated: true,
+            embedding: None,
+            result: ToolResult::new("object".to_string(), serde_json::Value::Null, vec![]),
+            module: "word_count.wasm".to_string(),
+            entrypoint: "run".to_string(),
+            capabilities: WasmCapabilities::default(),
+            assets: Some(vec!["word_count.wasm".to_string()]),
+            tool_set: None,
+        };
+
+        let tool = HanzoTool::Wasm(wasm_tool("1.0.0", Some(json!(" "))), true);
+        manager
+            .add_tool_with_vector(tool.clone(), SqliteManager::generate_vector_for_testing(0.3))
+            .unwrap();
+
+        let results = manager
+            .tool_vector_search_with_vector(SqliteManager::generate_vector_for_testing(0.3), 1, false, false)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0.tool_type, "Wasm");
+        assert_eq!(
+            results[0].0.tool_router_key,
+            tool.tool_router_key().to_string_without_version()
+        );
+
+        let upgraded = manager
+            .upgrade_tool_with_vector(
+                HanzoTool::Wasm(wasm_tool("2.0.0", None), true),
+                SqliteManager::generate_vector_for_testing(0.3),
+            )
+            .unwrap();
+        match manager
+            .get_tool_by_key(&upgraded.tool_router_key().to_string_without_version())
+            .unwrap()
+        {
+            HanzoTool::Wasm(wasm_tool, _) => {
+                assert_eq!(wasm_tool.version, "2.0.0");
+                assert_eq!(wasm_tool.module, "word_count.wasm");
+                let ToolConfig::BasicConfig(config) = &wasm_tool.config[0];
+                assert_eq!(config.key_value, Some(json!(" ")));
+            }
+            other => panic!("Retrieved tool is not a WasmTool: {:?}", other.tool_type()),
+        }
+    }
+
     #[tokio::test]
     async fn test_get_tools_by_tool_set() {
         let manager = setup_test_db().await;
@@ -2786,6 +2917,7 @@ mod tests {
             assets: None,
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
+            permissions: None,
         };
 
         // Tool 2: Part of "Set B"
@@ -2814,6 +2946,7 @@ mod tests {
             assets: None,
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
+            permissions: None,
         };
 
         // Tool 3: Part of "Set A"
@@ -2870,6 +3003,7 @@ mod tests {
             assets: None,
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
+            permissions: None,
         };
 
         // Add tools to the database
@@ -2959,6 +3093,7 @@ mod tests {
             assets: None,
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
+            permissions: None,
         };
 
         // Tool 2: Part of "MySet" with different config
@@ -3049,6 +3184,7 @@ mod tests {
             assets: None,
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
+            permissions: None,
         };
 
         // Add tools
@@ -3246,6 +3382,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
         HanzoTool::Deno(deno_tool_data, true)
     }
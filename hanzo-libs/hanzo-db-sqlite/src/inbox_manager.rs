@@ -1694,6 +1694,7 @@ mod tests {
                 vector_fs_items: vec![],
                 vector_fs_folders: vec![],
                 vector_search_mode: VectorSearchMode::FillUpTo25k,
+                mcp_resources: vec![],
             };
             let job_config = JobConfig::empty();
             let agent_id = "test_agent".to_string();
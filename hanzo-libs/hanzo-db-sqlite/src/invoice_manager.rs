@@ -1,11 +1,87 @@
-use rusqlite::params;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
 use hanzo_messages::schemas::{
     invoices::{Invoice, InvoiceRequestNetworkError},
     hanzo_name::HanzoName,
+    x402_paywall::{PaywallResource, X402Receipt, X402ReceiptStatus},
 };
 
 use crate::{SqliteManager, SqliteManagerError};
 
+fn x402_receipt_status_to_text(status: X402ReceiptStatus) -> &'static str {
+    match status {
+        X402ReceiptStatus::Verified => "verified",
+        X402ReceiptStatus::Settled => "settled",
+        X402ReceiptStatus::Failed => "failed",
+    }
+}
+
+fn x402_receipt_status_from_text(index: usize, text: &str) -> rusqlite::Result<X402ReceiptStatus> {
+    match text {
+        "verified" => Ok(X402ReceiptStatus::Verified),
+        "settled" => Ok(X402ReceiptStatus::Settled),
+        "failed" => Ok(X402ReceiptStatus::Failed),
+        _ => Err(rusqlite::Error::FromSqlConversionFailure(
+            index,
+            rusqlite::types::Type::Text,
+            format!("Unknown receipt status {}", text).into(),
+        )),
+    }
+}
+
+fn json_column<T: serde::de::DeserializeOwned>(index: usize, text: String) -> rusqlite::Result<T> {
+    serde_json::from_str(&text)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn date_time_column(index: usize, text: String) -> rusqlite::Result<DateTime<Utc>> {
+    text.parse::<DateTime<Utc>>()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn x402_receipt_from_row(row: &rusqlite::Row) -> rusqlite::Result<X402Receipt> {
+    let resource: String = row.get(1)?;
+    let status: String = row.get(5)?;
+    Ok(X402Receipt {
+        receipt_id: row.get(0)?,
+        resource: PaywallResource::from_key(&resource)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into()))?,
+        payer: row.get(2)?,
+        payment: json_column(3, row.get(3)?)?,
+        requirements: json_column(4, row.get(4)?)?,
+        status: x402_receipt_status_from_text(5, &status)?,
+        transaction: row.get(6)?,
+        error: row.get(7)?,
+        created_at: date_time_column(8, row.get(8)?)?,
+        updated_at: date_time_column(9, row.get(9)?)?,
+    })
+}
+
+fn insert_x402_receipt(conn: &rusqlite::Connection, receipt: &X402Receipt) -> Result<(), SqliteManagerError> {
+    let payment =
+        serde_json::to_string(&receipt.payment).map_err(|e| SqliteManagerError::SerializationError(e.to_string()))?;
+    let requirements = serde_json::to_string(&receipt.requirements)
+        .map_err(|e| SqliteManagerError::SerializationError(e.to_string()))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO invoice_x402_receipts (
+            receipt_id, resource, payer, payment, requirements, status, transaction_hash, error, created_at, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            receipt.receipt_id,
+            receipt.resource.key(),
+            receipt.payer,
+            payment,
+            requirements,
+            x402_receipt_status_to_text(receipt.status),
+            receipt.transaction,
+            receipt.error,
+            receipt.created_at.to_rfc3339(),
+            receipt.updated_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
 impl SqliteManager {
     pub fn set_invoice(&self, invoice: &Invoice) -> Result<(), SqliteManagerError> {
         println!("set_invoice: {:?}", invoice);
@@ -296,9 +372,16 @@ impl SqliteManager {
                 request_date_time,
                 response_date_time,
                 user_error_message,
-                error_message
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                error_message,
+                error_kind
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
         )?;
+        let error_kind = error
+            .error_kind
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| SqliteManagerError::SerializationError(e.to_string()))?;
 
         stmt.execute(params![
             error.invoice_id,
@@ -308,6 +391,7 @@ impl SqliteManager {
             error.response_date_time.to_rfc3339(),
             error.user_error_message,
             error.error_message,
+            error_kind,
         ])?;
 
         Ok(())
@@ -352,6 +436,15 @@ impl SqliteManager {
                         })?,
                     user_error_message: row.get(5)?,
                     error_message: row.get(6)?,
+                    error_kind: row
+                        .get::<_, Option<String>>(7)?
+                        .map(|error_kind| serde_json::from_str(&error_kind))
+                        .transpose()
+                        .map_err(|e| {
+                            rusqlite::Error::ToSqlConversionFailure(Box::new(SqliteManagerError::SerializationError(
+                                e.to_string(),
+                            )))
+                        })?,
                 })
             })
             .map_err(|e| {
@@ -401,6 +494,15 @@ impl SqliteManager {
                         })?,
                     user_error_message: row.get(5)?,
                     error_message: row.get(6)?,
+                    error_kind: row
+                        .get::<_, Option<String>>(7)?
+                        .map(|error_kind| serde_json::from_str(&error_kind))
+                        .transpose()
+                        .map_err(|e| {
+                            rusqlite::Error::ToSqlConversionFailure(Box::new(SqliteManagerError::SerializationError(
+                                e.to_string(),
+                            )))
+                        })?,
                 })
             })
             .map_err(SqliteManagerError::DatabaseError)?;
@@ -420,6 +522,72 @@ impl SqliteManager {
 
         Ok(())
     }
+
+    /// Records a payment received over x402. A payment, identified by the nonce of its
+    /// authorization, can only be recorded once.
+    pub fn add_x402_receipt(&self, receipt: &X402Receipt) -> Result<(), SqliteManagerError> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM invoice_x402_receipts WHERE receipt_id = ?1)",
+            params![receipt.receipt_id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Err(SqliteManagerError::X402ReceiptAlreadyExists(receipt.receipt_id.clone()));
+        }
+        insert_x402_receipt(&tx, receipt)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn update_x402_receipt(&self, receipt: &X402Receipt) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        insert_x402_receipt(&conn, receipt)
+    }
+
+    pub fn get_x402_receipt(&self, receipt_id: &str) -> Result<Option<X402Receipt>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        Ok(conn
+            .query_row(
+                "SELECT receipt_id, resource, payer, payment, requirements, status, transaction_hash, error,
+                    created_at, updated_at
+                 FROM invoice_x402_receipts WHERE receipt_id = ?1",
+                params![receipt_id],
+                x402_receipt_from_row,
+            )
+            .optional()?)
+    }
+
+    /// Receipts of the newest payments first, optionally only those of one resource
+    pub fn get_x402_receipts(
+        &self,
+        resource: Option<&PaywallResource>,
+        limit: u64,
+    ) -> Result<Vec<X402Receipt>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT receipt_id, resource, payer, payment, requirements, status, transaction_hash, error,
+                created_at, updated_at
+             FROM invoice_x402_receipts
+             WHERE (?1 IS NULL OR resource = ?1)
+             ORDER BY created_at DESC
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                resource.map(|resource| resource.key()),
+                limit.min(i64::MAX as u64) as i64
+            ],
+            x402_receipt_from_row,
+        )?;
+
+        let mut receipts = Vec::new();
+        for row in rows {
+            receipts.push(row?);
+        }
+        Ok(receipts)
+    }
 }
 
 #[cfg(test)]
@@ -427,11 +595,11 @@ mod tests {
     use super::*;
     use hanzo_embed::model_type::{EmbeddingModelType, OllamaTextEmbeddingsInference};
     use hanzo_messages::schemas::{
-        invoices::InvoiceStatusEnum,
+        invoices::{InvoiceNetworkErrorKind, InvoiceStatusEnum},
         hanzo_name::HanzoName,
         hanzo_tool_offering::{HanzoToolOffering, ToolPrice, UsageType, UsageTypeInquiry},
         wallet_mixed::{NetworkIdentifier, PublicAddress},
-        x402_types::Network,
+        x402_types::{Network, PaymentPayload, PaymentRequirements},
     };
     use std::path::PathBuf;
     use tempfile::NamedTempFile;
@@ -585,6 +753,7 @@ mod tests {
             response_date_time: chrono::Utc::now(),
             user_error_message: Some("user_error_message".to_string()),
             error_message: "error_message".to_string(),
+            error_kind: Some(InvoiceNetworkErrorKind::ToolEntitlementExhausted),
         };
 
         db.set_invoice_network_error(&error).unwrap();
@@ -603,6 +772,7 @@ mod tests {
             response_date_time: chrono::Utc::now(),
             user_error_message: Some("user_error_message".to_string()),
             error_message: "error_message".to_string(),
+            error_kind: None,
         };
 
         let error2 = InvoiceRequestNetworkError {
@@ -613,6 +783,7 @@ mod tests {
             response_date_time: chrono::Utc::now(),
             user_error_message: Some("user_error_message".to_string()),
             error_message: "error_message".to_string(),
+            error_kind: None,
         };
 
         db.set_invoice_network_error(&error1).unwrap();
@@ -635,6 +806,7 @@ mod tests {
             response_date_time: chrono::Utc::now(),
             user_error_message: Some("user_error_message".to_string()),
             error_message: "error_message".to_string(),
+            error_kind: None,
         };
 
         db.set_invoice_network_error(&error).unwrap();
@@ -642,4 +814,76 @@ mod tests {
         let errors = db.get_all_invoice_network_errors().unwrap();
         assert!(errors.is_empty());
     }
+
+    #[test]
+    fn test_x402_receipts() {
+        let db = setup_test_db();
+        let payment: PaymentPayload = serde_json::from_value(serde_json::json!({
+            "scheme": "exact",
+            "network": "base-sepolia",
+            "x402Version": 1,
+            "payload": {
+                "signature": "0x00",
+                "authorization": {
+                    "from": "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+                    "to": "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+                    "value": "1000",
+                    "validAfter": "0",
+                    "validBefore": "1",
+                    "nonce": "0x11"
+                }
+            }
+        }))
+        .unwrap();
+        let now = chrono::DateTime::parse_from_rfc3339("2024-05-20T18:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let tool = PaywallResource::Tool("local:::__official_hanzo:::echo".to_string());
+        let mut receipt = X402Receipt {
+            receipt_id: "0x11".to_string(),
+            resource: tool.clone(),
+            payer: "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
+            payment,
+            requirements: PaymentRequirements::new(
+                Network::BaseSepolia,
+                "1000".to_string(),
+                "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+                "0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string(),
+                "https://hanzo.ai".to_string(),
+            ),
+            status: X402ReceiptStatus::Verified,
+            transaction: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        db.add_x402_receipt(&receipt).unwrap();
+        // The same payment can't pay twice
+        assert!(matches!(
+            db.add_x402_receipt(&receipt),
+            Err(SqliteManagerError::X402ReceiptAlreadyExists(_))
+        ));
+
+        receipt.status = X402ReceiptStatus::Settled;
+        receipt.transaction = Some("0xabc".to_string());
+        db.update_x402_receipt(&receipt).unwrap();
+        let stored = db.get_x402_receipt("0x11").unwrap().unwrap();
+        assert_eq!(stored.status, X402ReceiptStatus::Settled);
+        assert_eq!(stored.transaction.as_deref(), Some("0xabc"));
+
+        let mut other = receipt.clone();
+        other.receipt_id = "0x22".to_string();
+        other.resource = PaywallResource::agent_chat("my_agent");
+        other.created_at = now + chrono::Duration::seconds(1);
+        db.add_x402_receipt(&other).unwrap();
+
+        let receipts = db.get_x402_receipts(None, 10).unwrap();
+        assert_eq!(
+            receipts.iter().map(|r| r.receipt_id.as_str()).collect::<Vec<_>>(),
+            vec!["0x22", "0x11"]
+        );
+        assert_eq!(db.get_x402_receipts(Some(&tool), 10).unwrap().len(), 1);
+        assert!(db.get_x402_receipt("0x33").unwrap().is_none());
+    }
 }
@@ -9,15 +9,18 @@ use hanzo_messages::schemas::hanzo_name::HanzoName;
 use sqlite_vec::sqlite3_vec_init;
 use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
 /// Counter to generate unique shared in-memory FTS database URIs per SqliteManager instance.
 static FTS_INSTANCE_COUNTER: AtomicU64 = AtomicU64::new(0);
+// Receives the operation, table and duration of every statement once set, see `set_query_observer`
+static QUERY_OBSERVER: OnceLock<fn(&str, &str, Duration)> = OnceLock::new();
 
 pub mod agent_manager;
 pub mod cron_task_manager;
 pub mod embedding_function;
+pub mod embedding_migration_manager;
 pub mod errors;
 pub mod file_inbox_manager;
 pub mod file_system;
@@ -31,6 +34,7 @@ pub mod job_manager;
 pub mod job_queue_manager;
 pub mod keys_manager;
 pub mod llm_provider_manager;
+pub mod llm_usage_manager;
 pub mod mcp_server_manager;
 pub mod oauth_manager;
 pub mod preferences;
@@ -40,10 +44,14 @@ pub mod retry_manager;
 pub mod settings_manager;
 pub mod hanzo_tool_manager;
 pub mod source_file_manager;
+pub mod tool_entitlement_manager;
 pub mod tool_payment_req_manager;
 pub mod tool_playground;
 pub mod tracing;
 pub mod wallet_manager;
+pub mod wallet_spending_manager;
+pub mod watched_folder_manager;
+pub mod x402_paywall_manager;
 
 // Updated struct to manage SQLite connections using a connection pool
 pub struct SqliteManager {
@@ -83,7 +91,7 @@ impl SqliteManager {
                 .map_err(|e| rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(1), Some(e.to_string())))?;
         }
 
-        let manager = SqliteConnectionManager::file(db_path);
+        let manager = SqliteConnectionManager::file(db_path).with_init(Self::init_connection);
         let pool = Pool::builder()
             .max_size(10)
             .connection_timeout(Duration::from_secs(60))
@@ -115,11 +123,13 @@ impl SqliteManager {
         // which gives each connection its own isolated in-memory database).
         let fts_id = FTS_INSTANCE_COUNTER.fetch_add(1, Ordering::Relaxed);
         let fts_uri = format!("file:fts_{}?mode=memory&cache=shared", fts_id);
-        let fts_manager = SqliteConnectionManager::file(&fts_uri).with_flags(
-            rusqlite::OpenFlags::SQLITE_OPEN_URI
-                | rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE
-                | rusqlite::OpenFlags::SQLITE_OPEN_CREATE,
-        );
+        let fts_manager = SqliteConnectionManager::file(&fts_uri)
+            .with_flags(
+                rusqlite::OpenFlags::SQLITE_OPEN_URI
+                    | rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE
+                    | rusqlite::OpenFlags::SQLITE_OPEN_CREATE,
+            )
+            .with_init(Self::init_connection);
         let fts_pool = Pool::builder()
             .max_size(10)
             .connection_timeout(Duration::from_secs(60))
@@ -158,6 +168,12 @@ impl SqliteManager {
         Ok(manager)
     }
 
+    // Runs on every connection opened by the pools
+    fn init_connection(conn: &mut rusqlite::Connection) -> Result<()> {
+        conn.profile(Some(observe_query));
+        Ok(())
+    }
+
     // There might be old agents with partial full_identity_name.
     // This function migrates them to the new format.
     fn migrate_agents_full_identity_name(manager: &SqliteManager) -> Result<(), SqliteManagerError> {
@@ -190,6 +206,7 @@ impl SqliteManager {
         Self::initialize_invoice_network_errors_table(conn)?;
         Self::initialize_invoice_requests_table(conn)?;
         Self::initialize_invoice_table(conn)?;
+        Self::initialize_invoice_x402_receipts_table(conn)?;
         Self::initialize_jobs_table(conn)?;
         Self::initialize_forked_jobs_table(conn)?;
         Self::initialize_job_queue_table(conn)?;
@@ -220,6 +237,14 @@ impl SqliteManager {
         Self::initialize_embedding_model_type_table(conn)?;
         // Initialize MCP servers table
         Self::initialize_mcp_servers_table(conn)?;
+        Self::initialize_mcp_server_sampling_policies_table(conn)?;
+        Self::initialize_watched_folders_tables(conn)?;
+        Self::initialize_embedding_migration_tables(conn)?;
+        Self::initialize_llm_usage_tables(conn)?;
+        Self::initialize_wallet_spending_tables(conn)?;
+        Self::initialize_tool_entitlements_table(conn)?;
+        Self::initialize_purchased_tool_entitlements_table(conn)?;
+        Self::initialize_x402_paywall_prices_table(conn)?;
         Ok(())
     }
 
@@ -231,6 +256,8 @@ impl SqliteManager {
         Self::migrate_invoice_requests_table(conn)?;
         Self::migrate_mcp_servers_table(conn)?;
         Self::migrate_embedding_model_type_table(conn)?;
+        Self::migrate_parsed_files_table(conn)?;
+        Self::migrate_invoice_network_errors_table(conn)?;
         Ok(())
     }
 
@@ -275,6 +302,34 @@ impl SqliteManager {
         Ok(())
     }
 
+    fn migrate_parsed_files_table(conn: &rusqlite::Connection) -> Result<()> {
+        // Check if chunking_strategy column exists
+        let mut stmt =
+            conn.prepare("SELECT COUNT(*) FROM pragma_table_info('parsed_files') WHERE name = 'chunking_strategy'")?;
+        let column_exists: i64 = stmt.query_row([], |row| row.get(0))?;
+
+        // Add the column if it doesn't exist. Files parsed before it keep NULL (chunked by the parser).
+        if column_exists == 0 {
+            conn.execute("ALTER TABLE parsed_files ADD COLUMN chunking_strategy TEXT", [])?;
+        }
+
+        Ok(())
+    }
+
+    fn migrate_invoice_network_errors_table(conn: &rusqlite::Connection) -> Result<()> {
+        // Check if error_kind column exists
+        let mut stmt =
+            conn.prepare("SELECT COUNT(*) FROM pragma_table_info('invoice_network_errors') WHERE name = 'error_kind'")?;
+        let column_exists: i64 = stmt.query_row([], |row| row.get(0))?;
+
+        // Errors received before it keep NULL (not actionable by the requester)
+        if column_exists == 0 {
+            conn.execute("ALTER TABLE invoice_network_errors ADD COLUMN error_kind TEXT", [])?;
+        }
+
+        Ok(())
+    }
+
     fn migrate_invoices_table(conn: &rusqlite::Connection) -> Result<()> {
         // Check if we need to make hanzo_offering_key nullable
         // We do this by checking if the table has the NOT NULL constraint
@@ -925,6 +980,77 @@ impl SqliteManager {
         Ok(())
     }
 
+    fn initialize_wallet_spending_tables(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS wallet_spending_policy (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                policy TEXT NOT NULL
+            );",
+            [],
+        )?;
+        // Append only: every decision taken on a payment is a new entry
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS wallet_spending_ledger (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                invoice_id TEXT NOT NULL,
+                counterparty TEXT NOT NULL,
+                tool_key TEXT NOT NULL,
+                asset_type TEXT,
+                asset TEXT NOT NULL,
+                network TEXT NOT NULL,
+                amount TEXT NOT NULL,
+                decision TEXT NOT NULL,
+                reason TEXT,
+                created_at TEXT NOT NULL
+            );",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_wallet_spending_ledger_invoice_id ON wallet_spending_ledger (invoice_id);",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_wallet_spending_ledger_asset_created_at
+                ON wallet_spending_ledger (asset_type, created_at);",
+            [],
+        )?;
+        Ok(())
+    }
+
+    // Subscriptions and prepaid credits bought from this node, one row per buyer and tool
+    fn initialize_tool_entitlements_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tool_entitlements (
+                buyer_name TEXT NOT NULL,
+                tool_key TEXT NOT NULL,
+                expires_at TEXT,
+                remaining_credits INTEGER,
+                last_invoice_id TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (buyer_name, tool_key)
+            );",
+            [],
+        )?;
+        Ok(())
+    }
+
+    // Entitlements this node bought from other nodes
+    fn initialize_purchased_tool_entitlements_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS purchased_tool_entitlements (
+                buyer_name TEXT NOT NULL,
+                tool_key TEXT NOT NULL,
+                expires_at TEXT,
+                remaining_credits INTEGER,
+                last_invoice_id TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (buyer_name, tool_key)
+            );",
+            [],
+        )?;
+        Ok(())
+    }
+
     fn initialize_tool_micropayments_requirements_table(conn: &rusqlite::Connection) -> Result<()> {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS tool_micropayments_requirements (
@@ -982,6 +1108,43 @@ impl SqliteManager {
         Ok(())
     }
 
+    // Payments received over x402 from plain HTTP clients, next to the invoices of the network.
+    // They can't go in the invoices table: the payer is a wallet address instead of a node
+    // identity, and a paid agent chat or MCP tool call has no tool offering to reference.
+    fn initialize_invoice_x402_receipts_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS invoice_x402_receipts (
+                receipt_id TEXT PRIMARY KEY,
+                resource TEXT NOT NULL,
+                payer TEXT NOT NULL,
+                payment TEXT NOT NULL,
+                requirements TEXT NOT NULL,
+                status TEXT NOT NULL,
+                transaction_hash TEXT,
+                error TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_invoice_x402_receipts_resource_created_at ON invoice_x402_receipts (resource, created_at);",
+            [],
+        )?;
+        Ok(())
+    }
+
+    fn initialize_x402_paywall_prices_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS x402_paywall_prices (
+                resource TEXT PRIMARY KEY,
+                price TEXT NOT NULL
+            );",
+            [],
+        )?;
+        Ok(())
+    }
+
     fn initialize_invoice_network_errors_table(conn: &rusqlite::Connection) -> Result<()> {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS invoice_network_errors (
@@ -991,7 +1154,8 @@ impl SqliteManager {
                 request_date_time TEXT NOT NULL,
                 response_date_time TEXT NOT NULL,
                 user_error_message TEXT,
-                error_message TEXT NOT NULL
+                error_message TEXT NOT NULL,
+                error_kind TEXT
             );",
             [],
         )?;
@@ -1152,6 +1316,148 @@ impl SqliteManager {
         Ok(())
     }
 
+    // Sampling policy of each MCP server, servers without a row deny sampling
+    fn initialize_mcp_server_sampling_policies_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS mcp_server_sampling_policies (
+                mcp_server_id INTEGER PRIMARY KEY,
+                policy TEXT NOT NULL CHECK(policy IN ('DENY', 'ALLOW_DURING_JOB', 'ALLOW')) DEFAULT 'DENY',
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );",
+            [],
+        )?;
+        Ok(())
+    }
+
+    // Host directories mirrored into the VecFS, with the content hash of each file they synced
+    fn initialize_watched_folders_tables(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS watched_folders (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                host_path TEXT NOT NULL UNIQUE,
+                vecfs_path TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                last_synced_at TEXT,
+                last_sync_error TEXT
+            );",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS watched_folder_files (
+                watched_folder_id INTEGER NOT NULL REFERENCES watched_folders(id) ON DELETE CASCADE,
+                relative_path TEXT NOT NULL,
+                data_hash TEXT NOT NULL,
+                PRIMARY KEY (watched_folder_id, relative_path)
+            );",
+            [],
+        )?;
+        Ok(())
+    }
+
+    fn initialize_embedding_migration_tables(conn: &rusqlite::Connection) -> Result<()> {
+        // State of the last embedding migration (a single row)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS embedding_migration (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                target_model TEXT NOT NULL,
+                status TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                error TEXT
+            );",
+            [],
+        )?;
+        // Items already embedded into the shadow vector tables by the running migration
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS embedding_migration_items (
+                vec_table TEXT NOT NULL,
+                item_key TEXT NOT NULL,
+                PRIMARY KEY (vec_table, item_key)
+            );",
+            [],
+        )?;
+        // Items written while a migration runs lose their checkpoint, so the migration embeds
+        // them again before it swaps the tables
+        for (source_table, vec_table, item_key) in [
+            ("hanzo_prompts", "prompt_vec_items", "CAST({row}.id AS TEXT)"),
+            (
+                "hanzo_tools",
+                "hanzo_tools_vec_items",
+                "{row}.tool_key || ':' || {row}.version",
+            ),
+            ("chunks", "chunk_vec", "CAST({row}.id AS TEXT)"),
+        ] {
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER IF NOT EXISTS {source_table}_embedding_migration_insert
+                     AFTER INSERT ON {source_table}
+                     BEGIN
+                        DELETE FROM embedding_migration_items
+                        WHERE vec_table = '{vec_table}' AND item_key = {new_key};
+                     END;",
+                    new_key = item_key.replace("{row}", "NEW"),
+                ),
+                [],
+            )?;
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER IF NOT EXISTS {source_table}_embedding_migration_update
+                     AFTER UPDATE ON {source_table}
+                     BEGIN
+                        DELETE FROM embedding_migration_items
+                        WHERE vec_table = '{vec_table}' AND item_key IN ({old_key}, {new_key});
+                     END;",
+                    old_key = item_key.replace("{row}", "OLD"),
+                    new_key = item_key.replace("{row}", "NEW"),
+                ),
+                [],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn initialize_llm_usage_tables(conn: &rusqlite::Connection) -> Result<()> {
+        // Token usage of every inference call, with its cost when the model had a price
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS llm_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                job_id TEXT,
+                message_id TEXT,
+                agent_id TEXT,
+                llm_provider_id TEXT NOT NULL,
+                provider TEXT NOT NULL,
+                model TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cached_tokens INTEGER NOT NULL,
+                reasoning_tokens INTEGER NOT NULL,
+                tool_calls INTEGER NOT NULL DEFAULT 0,
+                cost REAL,
+                created_at TEXT NOT NULL
+            );",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_llm_usage_job_id ON llm_usage (job_id);",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_llm_usage_created_at ON llm_usage (created_at);",
+            [],
+        )?;
+        // Prices in USD per million tokens
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS llm_model_prices (
+                model TEXT PRIMARY KEY,
+                input_price_per_million REAL NOT NULL,
+                output_price_per_million REAL NOT NULL,
+                cached_input_price_per_million REAL
+            );",
+            [],
+        )?;
+        Ok(())
+    }
+
     // New method to update the embedding model type
     pub fn update_default_embedding_model(&self, model_type: EmbeddingModelType) -> Result<(), SqliteManagerError> {
         let conn = self.get_connection()?;
@@ -1282,273 +1588,6 @@ impl SqliteManager {
         }
     }
 
-    // Perform complete embedding migration to new model
-    pub async fn migrate_embeddings_to_new_model(
-        &self,
-        embedding_generator: &dyn hanzo_embed::embedding_generator::EmbeddingGenerator,
-        new_model_type: &EmbeddingModelType,
-        force: bool,
-    ) -> Result<(), SqliteManagerError> {
-        use bytemuck::cast_slice;
-        use hanzo_messages::schemas::custom_prompt::CustomPrompt;
-        use hanzo_messages::hanzo_utils::hanzo_logging::{
-            hanzo_log, HanzoLogLevel, HanzoLogOption,
-        };
-
-        hanzo_log(
-            HanzoLogOption::Database,
-            HanzoLogLevel::Info,
-            &format!(
-                "Starting embedding migration to new model: {} (force: {})",
-                new_model_type, force
-            ),
-        );
-
-        if !force && !self.is_embedding_migration_needed(new_model_type)? {
-            hanzo_log(
-                HanzoLogOption::Database,
-                HanzoLogLevel::Info,
-                "Embedding migration not needed, skipping",
-            );
-            return Ok(());
-        }
-
-        if force {
-            hanzo_log(
-                HanzoLogOption::Database,
-                HanzoLogLevel::Info,
-                "Forcing embedding migration regardless of current state",
-            );
-        }
-
-        // Step 1: Drop and recreate vector tables with new dimensions
-        let vector_dimensions = new_model_type.vector_dimensions().map_err(|e| {
-            SqliteManagerError::SerializationError(format!("Cannot get vector dimensions for new model: {}", e))
-        })?;
-
-        hanzo_log(
-            HanzoLogOption::Database,
-            HanzoLogLevel::Info,
-            &format!("Recreating vector tables with {} dimensions", vector_dimensions),
-        );
-
-        // Drop and recreate vector tables synchronously (no await points)
-        {
-            let conn = self.get_connection()?;
-            // Drop existing vector tables
-            conn.execute("DROP TABLE IF EXISTS prompt_vec_items;", [])?;
-            conn.execute("DROP TABLE IF EXISTS hanzo_tools_vec_items;", [])?;
-            conn.execute("DROP TABLE IF EXISTS chunk_vec;", [])?;
-
-            // Recreate vector tables with new model's dimensions
-            conn.execute(
-                &format!(
-                    "CREATE VIRTUAL TABLE prompt_vec_items USING vec0(
-                        embedding float[{}],
-                        is_enabled integer,
-                        +prompt_id integer
-                    )",
-                    vector_dimensions
-                ),
-                [],
-            )?;
-
-            conn.execute(
-                &format!(
-                    "CREATE VIRTUAL TABLE hanzo_tools_vec_items USING vec0(
-                        embedding float[{}],
-                        is_enabled integer,
-                        is_network integer,
-                        +tool_key text
-                    )",
-                    vector_dimensions
-                ),
-                [],
-            )?;
-
-            conn.execute(
-                &format!(
-                    "CREATE VIRTUAL TABLE chunk_vec USING vec0(
-                        embedding float[{}],
-                        parsed_file_id INTEGER,
-                        +chunk_id INTEGER
-                    )",
-                    vector_dimensions
-                ),
-                [],
-            )?;
-        }
-
-        // Step 2: Regenerate embeddings for prompts
-        hanzo_log(
-            HanzoLogOption::Database,
-            HanzoLogLevel::Info,
-            "Regenerating embeddings for prompts",
-        );
-
-        let prompts: Vec<CustomPrompt> = {
-            let conn = self.get_connection()?;
-            let mut stmt = conn
-                .prepare("SELECT id, name, is_system, is_enabled, version, prompt, is_favorite FROM hanzo_prompts")?;
-            let prompt_iter = stmt.query_map([], |row| {
-                Ok(CustomPrompt {
-                    rowid: Some(row.get(0)?),
-                    name: row.get(1)?,
-                    is_system: row.get::<_, i32>(2)? != 0,
-                    is_enabled: row.get::<_, i32>(3)? != 0,
-                    version: row.get(4)?,
-                    prompt: row.get(5)?,
-                    is_favorite: row.get::<_, i32>(6)? != 0,
-                })
-            })?;
-            prompt_iter.collect::<Result<Vec<_>, _>>()?
-        };
-
-        for prompt in prompts {
-            if let Some(id) = prompt.rowid {
-                let embedding = embedding_generator
-                    .generate_embedding_default(&prompt.prompt)
-                    .await
-                    .map_err(|e| {
-                        SqliteManagerError::SerializationError(format!("Embedding generation failed: {}", e))
-                    })?;
-
-                let conn = self.get_connection()?;
-                conn.execute(
-                    "INSERT INTO prompt_vec_items (prompt_id, embedding, is_enabled) VALUES (?1, ?2, ?3)",
-                    rusqlite::params![id, cast_slice(&embedding), prompt.is_enabled as i32],
-                )?;
-            }
-        }
-
-        // Step 3: Regenerate embeddings for tools
-        hanzo_log(
-            HanzoLogOption::Database,
-            HanzoLogLevel::Info,
-            "Regenerating embeddings for tools",
-        );
-
-        let tools: Vec<(String, String, bool, bool)> = {
-            let conn = self.get_connection()?;
-            let mut stmt = conn.prepare("SELECT tool_key, embedding_seo, is_enabled, is_network FROM hanzo_tools")?;
-            let tool_iter = stmt.query_map([], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,   // tool_key
-                    row.get::<_, String>(1)?,   // embedding_seo
-                    row.get::<_, i32>(2)? != 0, // is_enabled
-                    row.get::<_, i32>(3)? != 0, // is_network
-                ))
-            })?;
-            tool_iter.collect::<Result<Vec<_>, _>>()?
-        };
-
-        for (tool_key, embedding_seo, is_enabled, is_network) in tools {
-            let embedding = embedding_generator
-                .generate_embedding_default(&embedding_seo)
-                .await
-                .map_err(|e| {
-                    SqliteManagerError::SerializationError(format!("Tool embedding generation failed: {}", e))
-                })?;
-
-            let conn = self.get_connection()?;
-            conn.execute(
-                "INSERT INTO hanzo_tools_vec_items (tool_key, embedding, is_enabled, is_network) VALUES (?1, ?2, ?3, ?4)",
-                rusqlite::params![tool_key, cast_slice(&embedding), is_enabled as i32, is_network as i32],
-            )?;
-        }
-
-        // Step 4: Regenerate embeddings for file chunks with smart truncation
-        // Keep existing chunks but truncate only if new model has smaller context window
-        hanzo_log(
-            HanzoLogOption::Database,
-            HanzoLogLevel::Info,
-            "Regenerating embeddings for file chunks",
-        );
-
-        let chunks: Vec<(i64, i64, String)> = {
-            let conn = self.get_connection()?;
-            let mut stmt = conn.prepare("SELECT id, parsed_file_id, chunk FROM chunks")?;
-            let chunk_iter = stmt.query_map([], |row| {
-                Ok((
-                    row.get::<_, i64>(0)?,    // id (chunk_id)
-                    row.get::<_, i64>(1)?,    // parsed_file_id
-                    row.get::<_, String>(2)?, // chunk (text content)
-                ))
-            })?;
-            chunk_iter.collect::<Result<Vec<_>, _>>()?
-        };
-
-        let new_model_max_tokens = new_model_type.max_input_token_count();
-        let mut truncated_count = 0;
-        let total_chunks = chunks.len();
-
-        for (chunk_id, parsed_file_id, text) in chunks {
-            // Only truncate if the chunk exceeds new model's token limit
-            let processed_text = if text.chars().count() > new_model_max_tokens {
-                truncated_count += 1;
-                text.chars().take(new_model_max_tokens).collect()
-            } else {
-                text
-            };
-
-            let embedding = embedding_generator
-                .generate_embedding_default(&processed_text)
-                .await
-                .map_err(|e| {
-                    SqliteManagerError::SerializationError(format!("Chunk embedding generation failed: {}", e))
-                })?;
-
-            let conn = self.get_connection()?;
-            conn.execute(
-                "INSERT INTO chunk_vec (chunk_id, parsed_file_id, embedding) VALUES (?1, ?2, ?3)",
-                rusqlite::params![chunk_id, parsed_file_id, cast_slice(&embedding)],
-            )?;
-        }
-
-        if truncated_count > 0 {
-            hanzo_log(
-                HanzoLogOption::Database,
-                HanzoLogLevel::Info,
-                &format!(
-                    "Truncated {} out of {} chunks to fit new model's {} token limit",
-                    truncated_count, total_chunks, new_model_max_tokens
-                ),
-            );
-        } else {
-            hanzo_log(
-                HanzoLogOption::Database,
-                HanzoLogLevel::Info,
-                "No chunks needed truncation - all fit within new model's token limit",
-            );
-        }
-
-        // Step 5: Update parsed_files.embedding_model_used column for consistency
-        hanzo_log(
-            HanzoLogOption::Database,
-            HanzoLogLevel::Info,
-            "Updating parsed_files.embedding_model_used column",
-        );
-
-        {
-            let conn = self.get_connection()?;
-            conn.execute(
-                "UPDATE parsed_files SET embedding_model_used = ?1",
-                rusqlite::params![new_model_type.to_string()],
-            )?;
-        }
-
-        // Step 6: Update the database with the new model type
-        self.update_default_embedding_model(new_model_type.clone())?;
-
-        hanzo_log(
-            HanzoLogOption::Database,
-            HanzoLogLevel::Info,
-            "Embedding migration completed successfully",
-        );
-
-        Ok(())
-    }
-
     // Returns a connection from the pool
     pub fn get_connection(&self) -> Result<r2d2::PooledConnection<SqliteConnectionManager>> {
         self.pool.get().map_err(|e| {
@@ -1559,6 +1598,11 @@ impl SqliteManager {
         })
     }
 
+    // Returns the state (open and idle connections) of the main and FTS connection pools
+    pub fn pool_states(&self) -> (r2d2::State, r2d2::State) {
+        (self.pool.state(), self.fts_pool.state())
+    }
+
     // Execute a SQL query with parameters
     pub fn execute(&self, sql: &str, params: &[&dyn ToSql]) -> Result<usize> {
         let conn = self.get_connection()?;
@@ -1660,6 +1704,40 @@ impl SqliteManager {
     }
 }
 
+/// Reports the operation (e.g. `select`), table and duration of every SQL statement run by any
+/// `SqliteManager`, e.g. to feed metrics. Only the first observer set is kept.
+pub fn set_query_observer(observer: fn(&str, &str, Duration)) {
+    let _ = QUERY_OBSERVER.set(observer);
+}
+
+fn observe_query(sql: &str, duration: Duration) {
+    if let Some(observer) = QUERY_OBSERVER.get() {
+        let (operation, table) = query_labels(sql);
+        observer(&operation, &table, duration);
+    }
+}
+
+// Operation and table of a statement, "other" when the table can't be told from the SQL
+fn query_labels(sql: &str) -> (String, String) {
+    let words: Vec<&str> = sql.split_whitespace().collect();
+    let operation = words.first().map(|word| word.to_lowercase()).unwrap_or_default();
+    let table = words
+        .iter()
+        .position(|word| matches!(word.to_uppercase().as_str(), "FROM" | "INTO" | "UPDATE" | "TABLE"))
+        .and_then(|position| {
+            words[position + 1..]
+                .iter()
+                .find(|word| !matches!(word.to_uppercase().as_str(), "IF" | "NOT" | "EXISTS"))
+        })
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric() && c != '_')
+                .to_lowercase()
+        })
+        .filter(|table| !table.is_empty())
+        .unwrap_or_else(|| "other".to_string());
+    (operation, table)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1678,6 +1756,30 @@ mod tests {
         SqliteManager::new(db_path, api_url, model_type).unwrap()
     }
 
+    #[test]
+    fn test_query_labels() {
+        assert_eq!(
+            query_labels("SELECT * FROM jobs WHERE job_id = ?1"),
+            ("select".to_string(), "jobs".to_string())
+        );
+        assert_eq!(
+            query_labels("INSERT OR REPLACE INTO preferences (key, value) VALUES (?1, ?2)"),
+            ("insert".to_string(), "preferences".to_string())
+        );
+        assert_eq!(
+            query_labels("UPDATE inbox_messages SET read = 1"),
+            ("update".to_string(), "inbox_messages".to_string())
+        );
+        assert_eq!(
+            query_labels("CREATE TABLE IF NOT EXISTS llm_usage (id INTEGER)"),
+            ("create".to_string(), "llm_usage".to_string())
+        );
+        assert_eq!(
+            query_labels("PRAGMA foreign_keys = ON;"),
+            ("pragma".to_string(), "other".to_string())
+        );
+    }
+
     #[tokio::test]
     async fn test_set_version_no_reset_needed() {
         let manager = setup_test_db().await;
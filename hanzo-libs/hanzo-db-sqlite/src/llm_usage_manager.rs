@@ -0,0 +1,348 @@
+use crate::{SqliteManager, SqliteManagerError};
+use hanzo_messages::schemas::llm_usage::{
+    LLMModelPrice, LLMUsageGroupBy, LLMUsageQuery, LLMUsageRecord, LLMUsageSummary, TokenUsage,
+};
+use rusqlite::{params, OptionalExtension};
+
+impl SqliteManager {
+    /// Persists the usage of one inference call. A record without cost is priced with the price
+    /// of its model, if one is configured. Returns the record as stored.
+    pub fn add_llm_usage(&self, mut record: LLMUsageRecord) -> Result<LLMUsageRecord, SqliteManagerError> {
+        if record.cost.is_none() {
+            record.cost = self
+                .get_llm_model_price(&record.model)?
+                .map(|price| price.cost(&record.usage));
+        }
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO llm_usage (
+                job_id, message_id, agent_id, llm_provider_id, provider, model,
+                input_tokens, output_tokens, cached_tokens, reasoning_tokens, tool_calls, cost, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                record.job_id,
+                record.message_id,
+                record.agent_id,
+                record.llm_provider_id,
+                record.provider,
+                record.model,
+                record.usage.input_tokens as i64,
+                record.usage.output_tokens as i64,
+                record.usage.cached_tokens as i64,
+                record.usage.reasoning_tokens as i64,
+                record.tool_calls as i64,
+                record.cost,
+                record.created_at,
+            ],
+        )?;
+        Ok(record)
+    }
+
+    /// Usage of every inference call of a job, oldest first
+    pub fn get_llm_usage_for_job(&self, job_id: &str) -> Result<Vec<LLMUsageRecord>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT job_id, message_id, agent_id, llm_provider_id, provider, model,
+                    input_tokens, output_tokens, cached_tokens, reasoning_tokens, tool_calls, cost, created_at
+             FROM llm_usage WHERE job_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![job_id], |row| {
+            Ok(LLMUsageRecord {
+                job_id: row.get(0)?,
+                message_id: row.get(1)?,
+                agent_id: row.get(2)?,
+                llm_provider_id: row.get(3)?,
+                provider: row.get(4)?,
+                model: row.get(5)?,
+                usage: TokenUsage {
+                    input_tokens: row.get::<_, i64>(6)? as u64,
+                    output_tokens: row.get::<_, i64>(7)? as u64,
+                    cached_tokens: row.get::<_, i64>(8)? as u64,
+                    reasoning_tokens: row.get::<_, i64>(9)? as u64,
+                },
+                tool_calls: row.get::<_, i64>(10)? as u64,
+                cost: row.get(11)?,
+                created_at: row.get(12)?,
+            })
+        })?;
+
+        let mut records = Vec::new();
+        for row in rows {
+            records.push(row?);
+        }
+        Ok(records)
+    }
+
+    /// Adds up the recorded usage matching the filters of the query, one summary per group.
+    /// Calls to models without a price count as free.
+    pub fn get_llm_usage_summary(&self, query: &LLMUsageQuery) -> Result<Vec<LLMUsageSummary>, SqliteManagerError> {
+        let group_key = match query.group_by {
+            LLMUsageGroupBy::Job => "job_id",
+            LLMUsageGroupBy::Agent => "agent_id",
+            LLMUsageGroupBy::Provider => "llm_provider_id",
+            LLMUsageGroupBy::Model => "model",
+            // created_at is RFC 3339 in UTC, so its first 10 characters are the day
+            LLMUsageGroupBy::Day => "substr(created_at, 1, 10)",
+        };
+        let sql = format!(
+            "SELECT {group_key} AS group_key, COUNT(*),
+                    COALESCE(SUM(input_tokens), 0), COALESCE(SUM(output_tokens), 0),
+                    COALESCE(SUM(cached_tokens), 0), COALESCE(SUM(reasoning_tokens), 0),
+                    COALESCE(SUM(tool_calls), 0), COALESCE(SUM(cost), 0.0)
+             FROM llm_usage
+             WHERE (?1 IS NULL OR job_id = ?1)
+               AND (?2 IS NULL OR agent_id = ?2)
+               AND (?3 IS NULL OR llm_provider_id = ?3)
+               AND (?4 IS NULL OR created_at >= ?4)
+               AND (?5 IS NULL OR created_at < ?5)
+             GROUP BY group_key
+             ORDER BY group_key"
+        );
+
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            params![
+                query.job_id,
+                query.agent_id,
+                query.llm_provider_id,
+                query.start_date,
+                query.end_date
+            ],
+            |row| {
+                Ok(LLMUsageSummary {
+                    key: row.get(0)?,
+                    requests: row.get::<_, i64>(1)? as u64,
+                    input_tokens: row.get::<_, i64>(2)? as u64,
+                    output_tokens: row.get::<_, i64>(3)? as u64,
+                    cached_tokens: row.get::<_, i64>(4)? as u64,
+                    reasoning_tokens: row.get::<_, i64>(5)? as u64,
+                    tool_calls: row.get::<_, i64>(6)? as u64,
+                    cost: row.get(7)?,
+                })
+            },
+        )?;
+
+        let mut summaries = Vec::new();
+        for row in rows {
+            summaries.push(row?);
+        }
+        Ok(summaries)
+    }
+
+    /// Adds or replaces the price of a model. Usage recorded before keeps its cost.
+    pub fn set_llm_model_price(&self, price: &LLMModelPrice) -> Result<(), SqliteManagerError> {
+        let prices = [
+            Some(price.input_price_per_million),
+            Some(price.output_price_per_million),
+            price.cached_input_price_per_million,
+        ];
+        if price.model.is_empty() || prices.iter().flatten().any(|p| !p.is_finite() || *p < 0.0) {
+            return Err(SqliteManagerError::ValidationError(format!(
+                "Invalid price for model '{}'",
+                price.model
+            )));
+        }
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO llm_model_prices (
+                model, input_price_per_million, output_price_per_million, cached_input_price_per_million
+            ) VALUES (?1, ?2, ?3, ?4)
+            ON CONFLICT(model) DO UPDATE SET
+                input_price_per_million = excluded.input_price_per_million,
+                output_price_per_million = excluded.output_price_per_million,
+                cached_input_price_per_million = excluded.cached_input_price_per_million",
+            params![
+                price.model,
+                price.input_price_per_million,
+                price.output_price_per_million,
+                price.cached_input_price_per_million
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_llm_model_price(&self, model: &str) -> Result<Option<LLMModelPrice>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let price = conn
+            .query_row(
+                "SELECT model, input_price_per_million, output_price_per_million, cached_input_price_per_million
+                 FROM llm_model_prices WHERE model = ?1",
+                params![model],
+                Self::map_llm_model_price_row,
+            )
+            .optional()?;
+        Ok(price)
+    }
+
+    pub fn get_all_llm_model_prices(&self) -> Result<Vec<LLMModelPrice>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT model, input_price_per_million, output_price_per_million, cached_input_price_per_million
+             FROM llm_model_prices ORDER BY model",
+        )?;
+        let rows = stmt.query_map([], Self::map_llm_model_price_row)?;
+
+        let mut prices = Vec::new();
+        for row in rows {
+            prices.push(row?);
+        }
+        Ok(prices)
+    }
+
+    pub fn remove_llm_model_price(&self, model: &str) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let deleted = conn.execute("DELETE FROM llm_model_prices WHERE model = ?1", params![model])?;
+        if deleted == 0 {
+            return Err(SqliteManagerError::DataNotFound);
+        }
+        Ok(())
+    }
+
+    fn map_llm_model_price_row(row: &rusqlite::Row) -> rusqlite::Result<LLMModelPrice> {
+        Ok(LLMModelPrice {
+            model: row.get(0)?,
+            input_price_per_million: row.get(1)?,
+            output_price_per_million: row.get(2)?,
+            cached_input_price_per_million: row.get(3)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    fn usage_record(job_id: &str, agent_id: Option<&str>, model: &str, created_at: &str) -> LLMUsageRecord {
+        LLMUsageRecord {
+            job_id: Some(job_id.to_string()),
+            message_id: None,
+            agent_id: agent_id.map(|id| id.to_string()),
+            llm_provider_id: format!("{}_provider", model),
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens: 1_000,
+                output_tokens: 500,
+                cached_tokens: 0,
+                reasoning_tokens: 100,
+            },
+            tool_calls: 2,
+            cost: None,
+            created_at: created_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_llm_model_prices() {
+        let manager = setup_test_db();
+        let mut price = LLMModelPrice {
+            model: "gpt-4o".to_string(),
+            input_price_per_million: 2.5,
+            output_price_per_million: 10.0,
+            cached_input_price_per_million: None,
+        };
+
+        manager.set_llm_model_price(&price).unwrap();
+        price.cached_input_price_per_million = Some(1.25);
+        manager.set_llm_model_price(&price).unwrap();
+        assert_eq!(manager.get_all_llm_model_prices().unwrap(), vec![price.clone()]);
+
+        price.output_price_per_million = -1.0;
+        assert!(manager.set_llm_model_price(&price).is_err());
+
+        manager.remove_llm_model_price("gpt-4o").unwrap();
+        assert_eq!(manager.get_llm_model_price("gpt-4o").unwrap(), None);
+        assert!(matches!(
+            manager.remove_llm_model_price("gpt-4o"),
+            Err(SqliteManagerError::DataNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_llm_usage_summary() {
+        let manager = setup_test_db();
+        manager
+            .set_llm_model_price(&LLMModelPrice {
+                model: "gpt-4o".to_string(),
+                input_price_per_million: 2.0,
+                output_price_per_million: 10.0,
+                cached_input_price_per_million: None,
+            })
+            .unwrap();
+
+        let record = manager
+            .add_llm_usage(usage_record(
+                "job1",
+                Some("agent1"),
+                "gpt-4o",
+                "2026-10-01T10:00:00+00:00",
+            ))
+            .unwrap();
+        assert_eq!(record.cost, Some(0.007));
+        manager
+            .add_llm_usage(usage_record(
+                "job1",
+                Some("agent1"),
+                "gpt-4o",
+                "2026-10-02T10:00:00+00:00",
+            ))
+            .unwrap();
+        // No price for this model, so no cost
+        let record = manager
+            .add_llm_usage(usage_record("job2", None, "llama3", "2026-10-02T11:00:00+00:00"))
+            .unwrap();
+        assert_eq!(record.cost, None);
+        assert_eq!(manager.get_llm_usage_for_job("job1").unwrap().len(), 2);
+
+        let query = |group_by| LLMUsageQuery {
+            group_by,
+            job_id: None,
+            agent_id: None,
+            llm_provider_id: None,
+            start_date: None,
+            end_date: None,
+        };
+
+        let by_job = manager.get_llm_usage_summary(&query(LLMUsageGroupBy::Job)).unwrap();
+        assert_eq!(by_job.len(), 2);
+        assert_eq!(by_job[0].key, Some("job1".to_string()));
+        assert_eq!(by_job[0].requests, 2);
+        assert_eq!(by_job[0].input_tokens, 2_000);
+        assert_eq!(by_job[0].reasoning_tokens, 200);
+        assert_eq!(by_job[0].tool_calls, 4);
+        assert!((by_job[0].cost - 0.014).abs() < 1e-9);
+        assert_eq!(by_job[1].cost, 0.0);
+
+        let by_agent = manager.get_llm_usage_summary(&query(LLMUsageGroupBy::Agent)).unwrap();
+        assert_eq!(by_agent[0].key, None);
+        assert_eq!(by_agent[1].key, Some("agent1".to_string()));
+
+        let mut day_query = query(LLMUsageGroupBy::Day);
+        day_query.start_date = Some("2026-10-02".to_string());
+        let by_day = manager.get_llm_usage_summary(&day_query).unwrap();
+        assert_eq!(by_day.len(), 1);
+        assert_eq!(by_day[0].key, Some("2026-10-02".to_string()));
+        assert_eq!(by_day[0].requests, 2);
+
+        let mut provider_query = query(LLMUsageGroupBy::Provider);
+        provider_query.end_date = Some("2026-10-02".to_string());
+        let by_provider = manager.get_llm_usage_summary(&provider_query).unwrap();
+        assert_eq!(by_provider.len(), 1);
+        assert_eq!(by_provider[0].key, Some("gpt-4o_provider".to_string()));
+    }
+}
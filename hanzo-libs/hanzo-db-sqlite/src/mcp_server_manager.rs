@@ -1,4 +1,4 @@
-use hanzo_messages::schemas::mcp_server::{MCPServer, MCPServerEnv, MCPServerType};
+use hanzo_messages::schemas::mcp_server::{MCPSamplingPolicy, MCPServer, MCPServerEnv, MCPServerType};
 
 use crate::{errors::SqliteManagerError, SqliteManager};
 
@@ -162,6 +162,34 @@ impl SqliteManager {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare("DELETE FROM mcp_servers WHERE id = ?")?;
         stmt.execute([id])?;
+        conn.execute("DELETE FROM mcp_server_sampling_policies WHERE mcp_server_id = ?", [id])?;
+        Ok(())
+    }
+
+    pub fn get_mcp_server_sampling_policy(&self, id: i64) -> Result<MCPSamplingPolicy, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare("SELECT policy FROM mcp_server_sampling_policies WHERE mcp_server_id = ?")?;
+        let mut rows = stmt.query([id])?;
+        match rows.next()? {
+            Some(row) => {
+                let policy: String = row.get(0)?;
+                MCPSamplingPolicy::from_str(&policy).map_err(SqliteManagerError::SerializationError)
+            }
+            None => Ok(MCPSamplingPolicy::default()),
+        }
+    }
+
+    pub fn set_mcp_server_sampling_policy(
+        &self,
+        id: i64,
+        policy: &MCPSamplingPolicy,
+    ) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO mcp_server_sampling_policies (mcp_server_id, policy) VALUES (?1, ?2)
+             ON CONFLICT(mcp_server_id) DO UPDATE SET policy = ?2, updated_at = CURRENT_TIMESTAMP",
+            rusqlite::params![id, policy.to_string()],
+        )?;
         Ok(())
     }
 
@@ -608,4 +636,50 @@ mod tests {
         assert!(re_enabled_server.is_enabled);
         assert_eq!(re_enabled_server.name, "Updated Lifecycle Server"); // All other properties should remain unchanged
     }
+
+    #[tokio::test]
+    async fn test_mcp_server_sampling_policy() {
+        let manager = setup_test_db().await;
+        let server = manager
+            .add_mcp_server(
+                None,
+                "Sampling Server".to_string(),
+                MCPServerType::Command,
+                None,
+                Some("npx sampling-server".to_string()),
+                None,
+                true,
+            )
+            .unwrap();
+        let server_id = server.id.unwrap();
+
+        // Servers deny sampling until a policy is set
+        assert_eq!(
+            manager.get_mcp_server_sampling_policy(server_id).unwrap(),
+            MCPSamplingPolicy::Deny
+        );
+
+        manager
+            .set_mcp_server_sampling_policy(server_id, &MCPSamplingPolicy::AllowDuringJob)
+            .unwrap();
+        assert_eq!(
+            manager.get_mcp_server_sampling_policy(server_id).unwrap(),
+            MCPSamplingPolicy::AllowDuringJob
+        );
+
+        manager
+            .set_mcp_server_sampling_policy(server_id, &MCPSamplingPolicy::Allow)
+            .unwrap();
+        assert_eq!(
+            manager.get_mcp_server_sampling_policy(server_id).unwrap(),
+            MCPSamplingPolicy::Allow
+        );
+
+        // Deleting the server drops its policy
+        manager.delete_mcp_server(server_id).unwrap();
+        assert_eq!(
+            manager.get_mcp_server_sampling_policy(server_id).unwrap(),
+            MCPSamplingPolicy::Deny
+        );
+    }
 }
@@ -0,0 +1,406 @@
+use crate::{SqliteManager, SqliteManagerError};
+use chrono::{DateTime, Utc};
+use hanzo_messages::schemas::hanzo_tool_offering::UsageType;
+use hanzo_messages::schemas::tool_entitlement::ToolEntitlement;
+use rusqlite::{params, Connection, OptionalExtension};
+
+fn parse_date_time(index: usize, text: String) -> rusqlite::Result<DateTime<Utc>> {
+    text.parse::<DateTime<Utc>>()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn entitlement_from_row(row: &rusqlite::Row) -> rusqlite::Result<ToolEntitlement> {
+    Ok(ToolEntitlement {
+        buyer_name: row.get(0)?,
+        tool_key: row.get(1)?,
+        expires_at: row
+            .get::<_, Option<String>>(2)?
+            .map(|expires_at| parse_date_time(2, expires_at))
+            .transpose()?,
+        remaining_credits: row.get::<_, Option<i64>>(3)?.map(|credits| credits.max(0) as u64),
+        last_invoice_id: row.get(4)?,
+        updated_at: parse_date_time(5, row.get(5)?)?,
+    })
+}
+
+// Entitlements granted by this node to its buyers
+const GRANTED_TABLE: &str = "tool_entitlements";
+// Entitlements this node bought from other nodes
+const PURCHASED_TABLE: &str = "purchased_tool_entitlements";
+
+fn select_tool_entitlement(
+    conn: &Connection,
+    table: &str,
+    buyer_name: &str,
+    tool_key: &str,
+) -> Result<Option<ToolEntitlement>, SqliteManagerError> {
+    Ok(conn
+        .query_row(
+            &format!(
+                "SELECT buyer_name, tool_key, expires_at, remaining_credits, last_invoice_id, updated_at
+                 FROM {} WHERE buyer_name = ?1 AND tool_key = ?2",
+                table
+            ),
+            params![buyer_name, tool_key],
+            entitlement_from_row,
+        )
+        .optional()?)
+}
+
+fn upsert_tool_entitlement(
+    conn: &Connection,
+    table: &str,
+    entitlement: &ToolEntitlement,
+) -> Result<(), SqliteManagerError> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO {} (
+                buyer_name, tool_key, expires_at, remaining_credits, last_invoice_id, updated_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            table
+        ),
+        params![
+            entitlement.buyer_name,
+            entitlement.tool_key,
+            entitlement.expires_at.map(|expires_at| expires_at.to_rfc3339()),
+            entitlement
+                .remaining_credits
+                .map(|credits| credits.min(i64::MAX as u64) as i64),
+            entitlement.last_invoice_id,
+            entitlement.updated_at.to_rfc3339(),
+        ],
+    )?;
+    Ok(())
+}
+
+fn delete_tool_entitlement(
+    conn: &Connection,
+    table: &str,
+    buyer_name: &str,
+    tool_key: &str,
+) -> Result<(), SqliteManagerError> {
+    let deleted = conn.execute(
+        &format!("DELETE FROM {} WHERE buyer_name = ?1 AND tool_key = ?2", table),
+        params![buyer_name, tool_key],
+    )?;
+    if deleted == 0 {
+        return Err(SqliteManagerError::ToolEntitlementNotFound(format!(
+            "{} for {}",
+            tool_key, buyer_name
+        )));
+    }
+    Ok(())
+}
+
+/// Takes one use from an entitlement, returning it with whether a credit was taken
+fn consume_from_table(
+    conn: &mut Connection,
+    table: &str,
+    buyer_name: &str,
+    tool_key: &str,
+    now: DateTime<Utc>,
+) -> Result<(ToolEntitlement, bool), SqliteManagerError> {
+    let tx = conn.transaction()?;
+    let mut entitlement = select_tool_entitlement(&tx, table, buyer_name, tool_key)?
+        .ok_or_else(|| SqliteManagerError::ToolEntitlementNotFound(format!("{} for {}", tool_key, buyer_name)))?;
+    let credit_taken = entitlement
+        .consume(now)
+        .map_err(SqliteManagerError::ToolEntitlementExhausted)?;
+    upsert_tool_entitlement(&tx, table, &entitlement)?;
+    tx.commit()?;
+    Ok((entitlement, credit_taken))
+}
+
+impl SqliteManager {
+    pub fn get_tool_entitlement(
+        &self,
+        buyer_name: &str,
+        tool_key: &str,
+    ) -> Result<Option<ToolEntitlement>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        select_tool_entitlement(&conn, GRANTED_TABLE, buyer_name, tool_key)
+    }
+
+    /// All the entitlements granted by this node, optionally only those of one buyer
+    pub fn get_tool_entitlements(&self, buyer_name: Option<&str>) -> Result<Vec<ToolEntitlement>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT buyer_name, tool_key, expires_at, remaining_credits, last_invoice_id, updated_at
+             FROM tool_entitlements
+             WHERE (?1 IS NULL OR buyer_name = ?1)
+             ORDER BY buyer_name, tool_key",
+        )?;
+        let rows = stmt.query_map(params![buyer_name], entitlement_from_row)?;
+
+        let mut entitlements = Vec::new();
+        for row in rows {
+            entitlements.push(row?);
+        }
+        Ok(entitlements)
+    }
+
+    pub fn set_tool_entitlement(&self, entitlement: &ToolEntitlement) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        upsert_tool_entitlement(&conn, GRANTED_TABLE, entitlement)
+    }
+
+    pub fn remove_tool_entitlement(&self, buyer_name: &str, tool_key: &str) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        delete_tool_entitlement(&conn, GRANTED_TABLE, buyer_name, tool_key)
+    }
+
+    /// Adds a paid subscription period or pack of credits to the entitlement of a buyer,
+    /// creating it on the first purchase
+    pub fn grant_tool_entitlement(
+        &self,
+        buyer_name: &str,
+        tool_key: &str,
+        usage_type: &UsageType,
+        invoice_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ToolEntitlement, SqliteManagerError> {
+        if !usage_type.grants_entitlement() {
+            return Err(SqliteManagerError::ValidationError(format!(
+                "Usage type of {} does not grant an entitlement",
+                tool_key
+            )));
+        }
+
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        let mut entitlement = select_tool_entitlement(&tx, GRANTED_TABLE, buyer_name, tool_key)?.unwrap_or_else(|| {
+            ToolEntitlement::new(
+                buyer_name.to_string(),
+                tool_key.to_string(),
+                invoice_id.to_string(),
+                now,
+            )
+        });
+        entitlement.top_up(usage_type, invoice_id, now);
+        upsert_tool_entitlement(&tx, GRANTED_TABLE, &entitlement)?;
+        tx.commit()?;
+        Ok(entitlement)
+    }
+
+    /// Takes one use of a tool from the entitlement of a buyer, returning whether a credit was taken
+    pub fn consume_tool_entitlement(
+        &self,
+        buyer_name: &str,
+        tool_key: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(ToolEntitlement, bool), SqliteManagerError> {
+        let mut conn = self.get_connection()?;
+        consume_from_table(&mut conn, GRANTED_TABLE, buyer_name, tool_key, now)
+    }
+
+    /// Gives back the credit taken for a call of a buyer that failed
+    pub fn refund_tool_entitlement(
+        &self,
+        buyer_name: &str,
+        tool_key: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ToolEntitlement, SqliteManagerError> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        let mut entitlement = select_tool_entitlement(&tx, GRANTED_TABLE, buyer_name, tool_key)?
+            .ok_or_else(|| SqliteManagerError::ToolEntitlementNotFound(format!("{} for {}", tool_key, buyer_name)))?;
+        entitlement.refund_credit(now);
+        upsert_tool_entitlement(&tx, GRANTED_TABLE, &entitlement)?;
+        tx.commit()?;
+        Ok(entitlement)
+    }
+
+    // Entitlements bought by this node
+
+    pub fn get_purchased_tool_entitlement(
+        &self,
+        buyer_name: &str,
+        tool_key: &str,
+    ) -> Result<Option<ToolEntitlement>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        select_tool_entitlement(&conn, PURCHASED_TABLE, buyer_name, tool_key)
+    }
+
+    /// Adds a subscription period or pack of credits bought by this node, creating the entitlement on the
+    /// first purchase. The call that bought it counts as its first use.
+    pub fn top_up_purchased_tool_entitlement(
+        &self,
+        buyer_name: &str,
+        tool_key: &str,
+        usage_type: &UsageType,
+        invoice_id: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ToolEntitlement, SqliteManagerError> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+        let mut entitlement =
+            select_tool_entitlement(&tx, PURCHASED_TABLE, buyer_name, tool_key)?.unwrap_or_else(|| {
+                ToolEntitlement::new(
+                    buyer_name.to_string(),
+                    tool_key.to_string(),
+                    invoice_id.to_string(),
+                    now,
+                )
+            });
+        entitlement.top_up(usage_type, invoice_id, now);
+        // Same accounting as the provider, which takes the first use on purchase
+        let _ = entitlement.consume(now);
+        upsert_tool_entitlement(&tx, PURCHASED_TABLE, &entitlement)?;
+        tx.commit()?;
+        Ok(entitlement)
+    }
+
+    /// Takes one use from an entitlement bought by this node, once the provider processed the call
+    pub fn consume_purchased_tool_entitlement(
+        &self,
+        buyer_name: &str,
+        tool_key: &str,
+        now: DateTime<Utc>,
+    ) -> Result<ToolEntitlement, SqliteManagerError> {
+        let mut conn = self.get_connection()?;
+        consume_from_table(&mut conn, PURCHASED_TABLE, buyer_name, tool_key, now).map(|(entitlement, _)| entitlement)
+    }
+
+    pub fn remove_purchased_tool_entitlement(
+        &self,
+        buyer_name: &str,
+        tool_key: &str,
+    ) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        delete_tool_entitlement(&conn, PURCHASED_TABLE, buyer_name, tool_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use hanzo_messages::schemas::hanzo_tool_offering::{PrepaidCreditsTerms, SubscriptionTerms, ToolPrice};
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    const BUYER: &str = "@@buyer.hanzo";
+    const TOOL_KEY: &str = "local:::provider:::tool";
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    #[test]
+    fn test_prepaid_credits_entitlement() {
+        let manager = setup_test_db();
+        let now = Utc::now();
+        let credits = UsageType::PrepaidCredits(PrepaidCreditsTerms {
+            price: ToolPrice::Free,
+            credits: 2,
+        });
+
+        assert!(matches!(
+            manager.consume_tool_entitlement(BUYER, TOOL_KEY, now),
+            Err(SqliteManagerError::ToolEntitlementNotFound(_))
+        ));
+
+        manager
+            .grant_tool_entitlement(BUYER, TOOL_KEY, &credits, "inv_1", now)
+            .unwrap();
+        let (entitlement, credit_taken) = manager.consume_tool_entitlement(BUYER, TOOL_KEY, now).unwrap();
+        assert_eq!(entitlement.remaining_credits, Some(1));
+        assert!(credit_taken);
+        manager.consume_tool_entitlement(BUYER, TOOL_KEY, now).unwrap();
+
+        // The credit of a failed call is given back
+        let entitlement = manager.refund_tool_entitlement(BUYER, TOOL_KEY, now).unwrap();
+        assert_eq!(entitlement.remaining_credits, Some(1));
+        manager.consume_tool_entitlement(BUYER, TOOL_KEY, now).unwrap();
+        assert!(matches!(
+            manager.consume_tool_entitlement(BUYER, TOOL_KEY, now),
+            Err(SqliteManagerError::ToolEntitlementExhausted(_))
+        ));
+
+        let entitlement = manager
+            .grant_tool_entitlement(BUYER, TOOL_KEY, &credits, "inv_2", now)
+            .unwrap();
+        assert_eq!(entitlement.remaining_credits, Some(2));
+        assert_eq!(
+            manager.get_tool_entitlement(BUYER, TOOL_KEY).unwrap(),
+            Some(entitlement)
+        );
+    }
+
+    #[test]
+    fn test_subscription_entitlement() {
+        let manager = setup_test_db();
+        let now = DateTime::parse_from_rfc3339("2024-05-20T18:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let subscription = UsageType::Subscription(SubscriptionTerms {
+            price: ToolPrice::Free,
+            period_days: 30,
+        });
+
+        let entitlement = manager
+            .grant_tool_entitlement(BUYER, TOOL_KEY, &subscription, "inv_1", now)
+            .unwrap();
+        assert_eq!(entitlement.expires_at, Some(now + Duration::days(30)));
+        let (_, credit_taken) = manager
+            .consume_tool_entitlement(BUYER, TOOL_KEY, now + Duration::days(1))
+            .unwrap();
+        assert!(!credit_taken);
+        assert!(matches!(
+            manager.consume_tool_entitlement(BUYER, TOOL_KEY, now + Duration::days(31)),
+            Err(SqliteManagerError::ToolEntitlementExhausted(_))
+        ));
+
+        manager
+            .grant_tool_entitlement("@@other.hanzo", TOOL_KEY, &subscription, "inv_2", now)
+            .unwrap();
+        assert_eq!(manager.get_tool_entitlements(None).unwrap().len(), 2);
+        assert_eq!(manager.get_tool_entitlements(Some(BUYER)).unwrap().len(), 1);
+
+        manager.remove_tool_entitlement(BUYER, TOOL_KEY).unwrap();
+        assert_eq!(manager.get_tool_entitlement(BUYER, TOOL_KEY).unwrap(), None);
+        assert!(matches!(
+            manager.remove_tool_entitlement(BUYER, TOOL_KEY),
+            Err(SqliteManagerError::ToolEntitlementNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_purchased_entitlement() {
+        let manager = setup_test_db();
+        let now = Utc::now();
+        let credits = UsageType::PrepaidCredits(PrepaidCreditsTerms {
+            price: ToolPrice::Free,
+            credits: 2,
+        });
+
+        // The purchase is the first use
+        let entitlement = manager
+            .top_up_purchased_tool_entitlement(BUYER, TOOL_KEY, &credits, "inv_1", now)
+            .unwrap();
+        assert_eq!(entitlement.remaining_credits, Some(1));
+        assert_eq!(
+            manager.get_purchased_tool_entitlement(BUYER, TOOL_KEY).unwrap(),
+            Some(entitlement)
+        );
+        // Kept apart from the entitlements granted by this node
+        assert_eq!(manager.get_tool_entitlement(BUYER, TOOL_KEY).unwrap(), None);
+
+        manager
+            .consume_purchased_tool_entitlement(BUYER, TOOL_KEY, now)
+            .unwrap();
+        assert!(matches!(
+            manager.consume_purchased_tool_entitlement(BUYER, TOOL_KEY, now),
+            Err(SqliteManagerError::ToolEntitlementExhausted(_))
+        ));
+
+        manager.remove_purchased_tool_entitlement(BUYER, TOOL_KEY).unwrap();
+        assert_eq!(manager.get_purchased_tool_entitlement(BUYER, TOOL_KEY).unwrap(), None);
+    }
+}
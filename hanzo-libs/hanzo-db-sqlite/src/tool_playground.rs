@@ -219,6 +219,7 @@ impl SqliteManager {
                     vec![OperatingSystem::Linux, OperatingSystem::MacOS, OperatingSystem::Windows];
                 let mut runner = RunnerType::Any;
                 let mut tool_set = None;
+                let mut permissions = None;
                 if let Ok(tool_data) = self.get_tool_by_key(tool_router_key) {
                     // found data
                     sql_queries = tool_data.sql_queries();
@@ -230,6 +231,7 @@ impl SqliteManager {
                     operating_system = tool_data.get_operating_system();
                     runner = tool_data.get_runner();
                     tool_set = tool_data.get_tool_set();
+                    permissions = tool_data.get_permissions();
                 }
 
                 Ok(ToolPlayground {
@@ -251,6 +253,7 @@ impl SqliteManager {
                         operating_system,
                         runner,
                         tool_set,
+                        permissions,
                     },
                     tool_router_key: row.get(7)?,
                     job_id: row.get(8)?,
@@ -324,6 +327,7 @@ impl SqliteManager {
                     operating_system: vec![OperatingSystem::Linux, OperatingSystem::MacOS, OperatingSystem::Windows],
                     runner: RunnerType::Any,
                     tool_set: None,
+                    permissions: None,
                 },
                 tool_router_key: row.get(7)?,
                 job_id: row.get(8)?,
@@ -435,6 +439,7 @@ mod tests {
             runner: RunnerType::Any,
             operating_system: vec![OperatingSystem::Linux],
             tool_set: None,
+            permissions: None,
         };
 
         let hanzo_tool = HanzoTool::Deno(deno_tool, true);
@@ -467,6 +472,7 @@ mod tests {
                 operating_system: vec![OperatingSystem::Linux],
                 runner: RunnerType::Any,
                 tool_set: None,
+                permissions: None,
             },
             tool_router_key: Some(tool_router_key),
             job_id: "job_123".to_string(),
@@ -574,6 +580,7 @@ mod tests {
             runner: RunnerType::Any,
             operating_system: vec![OperatingSystem::Linux],
             tool_set: None,
+            permissions: None,
         };
 
         let hanzo_tool = HanzoTool::Deno(deno_tool, true);
@@ -659,6 +666,7 @@ mod tests {
             runner: RunnerType::Any,
             operating_system: vec![OperatingSystem::Linux],
             tool_set: None,
+            permissions: None,
         };
 
         let hanzo_tool = HanzoTool::Deno(deno_tool, true);
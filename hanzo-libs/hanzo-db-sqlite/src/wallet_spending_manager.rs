@@ -0,0 +1,293 @@
+use crate::{SqliteManager, SqliteManagerError};
+use chrono::{DateTime, Utc};
+use hanzo_messages::schemas::wallet_mixed::AssetType;
+use hanzo_messages::schemas::wallet_spending_policy::{
+    SpendingDecision, SpendingLedgerEntry, SpentAmounts, WalletSpendingPolicy,
+};
+use rusqlite::{params, OptionalExtension};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+/// Text of a unit enum as serialized by serde (e.g. `base-sepolia` or `USDC`)
+fn enum_to_text<T: Serialize>(value: &T) -> Result<String, SqliteManagerError> {
+    match serde_json::to_value(value) {
+        Ok(Value::String(text)) => Ok(text),
+        Ok(other) => Err(SqliteManagerError::SerializationError(format!(
+            "Expected a string, got {}",
+            other
+        ))),
+        Err(e) => Err(SqliteManagerError::SerializationError(e.to_string())),
+    }
+}
+
+fn enum_from_text<T: DeserializeOwned>(text: String) -> rusqlite::Result<T> {
+    serde_json::from_value(Value::String(text))
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+impl SqliteManager {
+    /// The spending policy of the node. Without a stored policy every payment waits for approval.
+    pub fn get_wallet_spending_policy(&self) -> Result<WalletSpendingPolicy, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let policy: Option<String> = conn
+            .query_row("SELECT policy FROM wallet_spending_policy WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+
+        match policy {
+            Some(policy) => {
+                serde_json::from_str(&policy).map_err(|e| SqliteManagerError::SerializationError(e.to_string()))
+            }
+            None => Ok(WalletSpendingPolicy::default()),
+        }
+    }
+
+    pub fn set_wallet_spending_policy(&self, policy: &WalletSpendingPolicy) -> Result<(), SqliteManagerError> {
+        policy.validate().map_err(SqliteManagerError::ValidationError)?;
+        let policy =
+            serde_json::to_string(policy).map_err(|e| SqliteManagerError::SerializationError(e.to_string()))?;
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO wallet_spending_policy (id, policy) VALUES (1, ?1)
+            ON CONFLICT(id) DO UPDATE SET policy = excluded.policy",
+            params![policy],
+        )?;
+        Ok(())
+    }
+
+    pub fn add_spending_ledger_entry(&self, entry: &SpendingLedgerEntry) -> Result<(), SqliteManagerError> {
+        let asset_type = entry.asset_type.as_ref().map(enum_to_text).transpose()?;
+        let network = enum_to_text(&entry.network)?;
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO wallet_spending_ledger (
+                invoice_id, counterparty, tool_key, asset_type, asset, network, amount, decision, reason, created_at
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                entry.invoice_id,
+                entry.counterparty,
+                entry.tool_key,
+                asset_type,
+                entry.asset,
+                network,
+                entry.amount,
+                entry.decision.to_string(),
+                entry.reason,
+                entry.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Entries of the spending ledger, newest first, optionally only those of one invoice
+    pub fn get_spending_ledger(
+        &self,
+        invoice_id: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<SpendingLedgerEntry>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT invoice_id, counterparty, tool_key, asset_type, asset, network, amount, decision, reason, created_at
+             FROM wallet_spending_ledger
+             WHERE (?1 IS NULL OR invoice_id = ?1)
+             ORDER BY id DESC
+             LIMIT ?2",
+        )?;
+        let limit = limit.map_or(-1, |limit| limit as i64);
+        let rows = stmt.query_map(params![invoice_id, limit], |row| {
+            let decision: String = row.get(7)?;
+            Ok(SpendingLedgerEntry {
+                invoice_id: row.get(0)?,
+                counterparty: row.get(1)?,
+                tool_key: row.get(2)?,
+                asset_type: row.get::<_, Option<String>>(3)?.map(enum_from_text).transpose()?,
+                asset: row.get(4)?,
+                network: enum_from_text(row.get(5)?)?,
+                amount: row.get(6)?,
+                decision: decision.parse().map_err(|e: String| {
+                    rusqlite::Error::FromSqlConversionFailure(7, rusqlite::types::Type::Text, e.into())
+                })?,
+                reason: row.get(8)?,
+                created_at: row.get(9)?,
+            })
+        })?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            entries.push(row?);
+        }
+        Ok(entries)
+    }
+
+    /// What was paid in an asset during the UTC day and month of `now`: the approved payments
+    /// minus the ones the wallet failed to make
+    pub fn get_spent_amounts(
+        &self,
+        asset_type: &AssetType,
+        now: DateTime<Utc>,
+    ) -> Result<SpentAmounts, SqliteManagerError> {
+        let today = now.format("%Y-%m-%d").to_string();
+        let this_month = now.format("%Y-%m").to_string();
+
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT amount, decision, created_at FROM wallet_spending_ledger
+             WHERE asset_type = ?1
+               AND substr(created_at, 1, 7) = ?2
+               AND decision IN ('approved', 'approved_by_user', 'payment_failed')",
+        )?;
+        let rows = stmt.query_map(params![enum_to_text(asset_type)?, this_month], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut paid = SpentAmounts::default();
+        let mut failed = SpentAmounts::default();
+        for row in rows {
+            let (amount, decision, created_at) = row?;
+            let amount = amount.parse::<u128>().map_err(|_| {
+                SqliteManagerError::ValidationError(format!("Invalid amount in the spending ledger: {}", amount))
+            })?;
+            let totals = if decision == SpendingDecision::PaymentFailed.to_string() {
+                &mut failed
+            } else {
+                &mut paid
+            };
+            totals.this_month = totals.this_month.saturating_add(amount);
+            if created_at.starts_with(&today) {
+                totals.today = totals.today.saturating_add(amount);
+            }
+        }
+
+        Ok(SpentAmounts {
+            today: paid.today.saturating_sub(failed.today),
+            this_month: paid.this_month.saturating_sub(failed.this_month),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use hanzo_messages::schemas::wallet_spending_policy::AssetSpendingLimits;
+    use hanzo_messages::schemas::x402_types::Network;
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    fn ledger_entry(
+        invoice_id: &str,
+        amount: &str,
+        decision: SpendingDecision,
+        created_at: &str,
+    ) -> SpendingLedgerEntry {
+        SpendingLedgerEntry {
+            invoice_id: invoice_id.to_string(),
+            counterparty: "@@provider.hanzo".to_string(),
+            tool_key: "local:::provider:::tool".to_string(),
+            asset_type: Some(AssetType::USDC),
+            asset: "0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string(),
+            network: Network::BaseSepolia,
+            amount: amount.to_string(),
+            decision,
+            reason: None,
+            created_at: created_at.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_wallet_spending_policy() {
+        let manager = setup_test_db();
+        assert_eq!(
+            manager.get_wallet_spending_policy().unwrap(),
+            WalletSpendingPolicy::default()
+        );
+
+        let mut policy = WalletSpendingPolicy {
+            allowed_counterparties: Some(vec!["@@provider.hanzo".to_string()]),
+            ..Default::default()
+        };
+        policy.limits.insert(
+            AssetType::USDC,
+            AssetSpendingLimits {
+                daily_cap: Some("1000".to_string()),
+                ..Default::default()
+            },
+        );
+        manager.set_wallet_spending_policy(&policy).unwrap();
+        assert_eq!(manager.get_wallet_spending_policy().unwrap(), policy);
+
+        // Amounts must be in atomic units
+        policy.limits.get_mut(&AssetType::USDC).unwrap().daily_cap = Some("1.5".to_string());
+        assert!(matches!(
+            manager.set_wallet_spending_policy(&policy),
+            Err(SqliteManagerError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_spending_ledger() {
+        let manager = setup_test_db();
+        let entries = [
+            ledger_entry("inv_1", "100", SpendingDecision::Approved, "2024-05-01T10:00:00+00:00"),
+            ledger_entry("inv_2", "200", SpendingDecision::Approved, "2024-05-20T10:00:00+00:00"),
+            ledger_entry(
+                "inv_3",
+                "300",
+                SpendingDecision::PendingApproval,
+                "2024-05-20T11:00:00+00:00",
+            ),
+            ledger_entry(
+                "inv_3",
+                "300",
+                SpendingDecision::ApprovedByUser,
+                "2024-05-20T12:00:00+00:00",
+            ),
+            ledger_entry("inv_4", "400", SpendingDecision::Approved, "2024-05-20T13:00:00+00:00"),
+            ledger_entry(
+                "inv_4",
+                "400",
+                SpendingDecision::PaymentFailed,
+                "2024-05-20T13:00:01+00:00",
+            ),
+            ledger_entry("inv_5", "500", SpendingDecision::Denied, "2024-05-20T14:00:00+00:00"),
+            ledger_entry("inv_6", "600", SpendingDecision::Approved, "2024-04-30T23:00:00+00:00"),
+        ];
+        for entry in &entries {
+            manager.add_spending_ledger_entry(entry).unwrap();
+        }
+
+        let now = DateTime::parse_from_rfc3339("2024-05-20T18:00:00+00:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let spent = manager.get_spent_amounts(&AssetType::USDC, now).unwrap();
+        assert_eq!(spent.today, 500);
+        assert_eq!(spent.this_month, 600);
+        assert_eq!(
+            manager.get_spent_amounts(&AssetType::ETH, now).unwrap(),
+            SpentAmounts::default()
+        );
+
+        let ledger = manager.get_spending_ledger(None, None).unwrap();
+        assert_eq!(ledger.len(), entries.len());
+        assert_eq!(ledger[0], entries[entries.len() - 1]);
+
+        let invoice_ledger = manager.get_spending_ledger(Some("inv_3"), Some(1)).unwrap();
+        assert_eq!(invoice_ledger, vec![entries[3].clone()]);
+    }
+}
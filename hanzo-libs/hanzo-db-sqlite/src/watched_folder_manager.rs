@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use crate::{SqliteManager, SqliteManagerError};
+use hanzo_messages::schemas::hanzo_fs::WatchedFolder;
+use rusqlite::params;
+
+impl SqliteManager {
+    /// Registers a host directory to be mirrored into a VecFS folder. Returns the id of the registration.
+    pub fn add_watched_folder(&self, host_path: &str, vecfs_path: &str) -> Result<i64, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let created_at = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO watched_folders (host_path, vecfs_path, created_at) VALUES (?1, ?2, ?3)",
+            params![host_path, Self::normalize_path(vecfs_path), created_at],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Removes the registration and the hashes of the files it synced
+    pub fn remove_watched_folder(&self, id: i64) -> Result<(), SqliteManagerError> {
+        let mut conn = self.get_connection()?;
+        let tx = conn.transaction()?;
+
+        tx.execute(
+            "DELETE FROM watched_folder_files WHERE watched_folder_id = ?1",
+            params![id],
+        )?;
+        let deleted = tx.execute("DELETE FROM watched_folders WHERE id = ?1", params![id])?;
+        if deleted == 0 {
+            return Err(SqliteManagerError::DataNotFound);
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_watched_folder(&self, id: i64) -> Result<Option<WatchedFolder>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, host_path, vecfs_path, created_at, last_synced_at, last_sync_error
+             FROM watched_folders WHERE id = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![id], Self::map_watched_folder_row)?;
+
+        Ok(rows.next().transpose()?)
+    }
+
+    pub fn get_all_watched_folders(&self) -> Result<Vec<WatchedFolder>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, host_path, vecfs_path, created_at, last_synced_at, last_sync_error
+             FROM watched_folders ORDER BY id",
+        )?;
+        let rows = stmt.query_map([], Self::map_watched_folder_row)?;
+
+        let mut folders = Vec::new();
+        for row in rows {
+            folders.push(row?);
+        }
+        Ok(folders)
+    }
+
+    /// Records the end of a sync of the folder, with its error if it failed
+    pub fn set_watched_folder_sync_result(&self, id: i64, error: Option<&str>) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let synced_at = chrono::Utc::now().to_rfc3339();
+        conn.execute(
+            "UPDATE watched_folders SET last_synced_at = ?1, last_sync_error = ?2 WHERE id = ?3",
+            params![synced_at, error, id],
+        )?;
+        Ok(())
+    }
+
+    /// Content hashes of the files synced from the folder, by path relative to the watched directory
+    pub fn get_watched_folder_file_hashes(
+        &self,
+        watched_folder_id: i64,
+    ) -> Result<HashMap<String, String>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt =
+            conn.prepare("SELECT relative_path, data_hash FROM watched_folder_files WHERE watched_folder_id = ?1")?;
+        let rows = stmt.query_map(params![watched_folder_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        let mut hashes = HashMap::new();
+        for row in rows {
+            let (relative_path, data_hash) = row?;
+            hashes.insert(relative_path, data_hash);
+        }
+        Ok(hashes)
+    }
+
+    pub fn set_watched_folder_file_hash(
+        &self,
+        watched_folder_id: i64,
+        relative_path: &str,
+        data_hash: &str,
+    ) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT INTO watched_folder_files (watched_folder_id, relative_path, data_hash) VALUES (?1, ?2, ?3)
+             ON CONFLICT(watched_folder_id, relative_path) DO UPDATE SET data_hash = excluded.data_hash",
+            params![watched_folder_id, relative_path, data_hash],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_watched_folder_file_hash(
+        &self,
+        watched_folder_id: i64,
+        relative_path: &str,
+    ) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        conn.execute(
+            "DELETE FROM watched_folder_files WHERE watched_folder_id = ?1 AND relative_path = ?2",
+            params![watched_folder_id, relative_path],
+        )?;
+        Ok(())
+    }
+
+    fn map_watched_folder_row(row: &rusqlite::Row) -> rusqlite::Result<WatchedFolder> {
+        Ok(WatchedFolder {
+            id: row.get(0)?,
+            host_path: row.get(1)?,
+            vecfs_path: row.get(2)?,
+            created_at: row.get(3)?,
+            last_synced_at: row.get(4)?,
+            last_sync_error: row.get(5)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    #[test]
+    fn test_watched_folder_registration() {
+        let manager = setup_test_db();
+
+        let id = manager.add_watched_folder("/home/me/docs", "project/docs").unwrap();
+        assert!(manager.add_watched_folder("/home/me/docs", "other").is_err());
+
+        let folder = manager.get_watched_folder(id).unwrap().unwrap();
+        assert_eq!(folder.host_path, "/home/me/docs");
+        assert_eq!(folder.vecfs_path, "project/docs");
+        assert_eq!(folder.last_synced_at, None);
+
+        manager.set_watched_folder_sync_result(id, Some("boom")).unwrap();
+        let folder = manager.get_watched_folder(id).unwrap().unwrap();
+        assert!(folder.last_synced_at.is_some());
+        assert_eq!(folder.last_sync_error, Some("boom".to_string()));
+
+        manager.remove_watched_folder(id).unwrap();
+        assert!(manager.get_all_watched_folders().unwrap().is_empty());
+        assert!(matches!(
+            manager.remove_watched_folder(id),
+            Err(SqliteManagerError::DataNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_watched_folder_file_hashes() {
+        let manager = setup_test_db();
+        let id = manager.add_watched_folder("/home/me/docs", "docs").unwrap();
+
+        manager.set_watched_folder_file_hash(id, "a.md", "hash1").unwrap();
+        manager.set_watched_folder_file_hash(id, "b/c.md", "hash2").unwrap();
+        manager.set_watched_folder_file_hash(id, "a.md", "hash3").unwrap();
+        manager.remove_watched_folder_file_hash(id, "b/c.md").unwrap();
+
+        let hashes = manager.get_watched_folder_file_hashes(id).unwrap();
+        assert_eq!(hashes, HashMap::from([("a.md".to_string(), "hash3".to_string())]));
+
+        // Removing the registration forgets its files
+        manager.remove_watched_folder(id).unwrap();
+        assert!(manager.get_watched_folder_file_hashes(id).unwrap().is_empty());
+    }
+}
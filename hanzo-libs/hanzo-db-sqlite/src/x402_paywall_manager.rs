@@ -0,0 +1,119 @@
+use crate::{SqliteManager, SqliteManagerError};
+use hanzo_messages::schemas::x402_paywall::{PaywallPrice, PaywallResource};
+use rusqlite::{params, OptionalExtension};
+
+impl SqliteManager {
+    /// Sets the price of a resource sold over x402, replacing its previous price
+    pub fn set_paywall_price(&self, price: &PaywallPrice) -> Result<(), SqliteManagerError> {
+        price.validate().map_err(SqliteManagerError::ValidationError)?;
+        let serialized =
+            serde_json::to_string(price).map_err(|e| SqliteManagerError::SerializationError(e.to_string()))?;
+
+        let conn = self.get_connection()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO x402_paywall_prices (resource, price) VALUES (?1, ?2)",
+            params![price.resource.key(), serialized],
+        )?;
+        Ok(())
+    }
+
+    /// The price of a resource, None when it is not for sale
+    pub fn get_paywall_price(&self, resource: &PaywallResource) -> Result<Option<PaywallPrice>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let price: Option<String> = conn
+            .query_row(
+                "SELECT price FROM x402_paywall_prices WHERE resource = ?1",
+                params![resource.key()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        price
+            .map(|price| {
+                serde_json::from_str(&price).map_err(|e| SqliteManagerError::SerializationError(e.to_string()))
+            })
+            .transpose()
+    }
+
+    pub fn get_all_paywall_prices(&self) -> Result<Vec<PaywallPrice>, SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let mut stmt = conn.prepare("SELECT price FROM x402_paywall_prices ORDER BY resource")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        let mut prices = Vec::new();
+        for row in rows {
+            prices
+                .push(serde_json::from_str(&row?).map_err(|e| SqliteManagerError::SerializationError(e.to_string()))?);
+        }
+        Ok(prices)
+    }
+
+    pub fn remove_paywall_price(&self, resource: &PaywallResource) -> Result<(), SqliteManagerError> {
+        let conn = self.get_connection()?;
+        let deleted = conn.execute(
+            "DELETE FROM x402_paywall_prices WHERE resource = ?1",
+            params![resource.key()],
+        )?;
+        if deleted == 0 {
+            return Err(SqliteManagerError::X402PriceNotFound(resource.key()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use hanzo_messages::schemas::x402_types::{Network, Price};
+    use std::path::PathBuf;
+    use tempfile::NamedTempFile;
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    fn price(resource: PaywallResource, amount: f64) -> PaywallPrice {
+        PaywallPrice {
+            resource,
+            price: Price::Money(amount),
+            network: Network::BaseSepolia,
+            pay_to: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_paywall_prices() {
+        let db = setup_test_db();
+        let chat = PaywallResource::agent_chat("my_agent");
+        let tool = PaywallResource::Tool("local:::__official_hanzo:::echo".to_string());
+        assert!(db.get_paywall_price(&chat).unwrap().is_none());
+
+        db.set_paywall_price(&price(chat.clone(), 0.01)).unwrap();
+        db.set_paywall_price(&price(tool.clone(), 0.02)).unwrap();
+        db.set_paywall_price(&price(tool.clone(), 0.03)).unwrap();
+        assert!(matches!(
+            db.get_paywall_price(&tool).unwrap().unwrap().price,
+            Price::Money(amount) if amount == 0.03
+        ));
+        assert_eq!(db.get_all_paywall_prices().unwrap().len(), 2);
+
+        assert!(matches!(
+            db.set_paywall_price(&price(chat.clone(), 0.0)),
+            Err(SqliteManagerError::ValidationError(_))
+        ));
+
+        db.remove_paywall_price(&chat).unwrap();
+        assert!(db.get_paywall_price(&chat).unwrap().is_none());
+        assert!(matches!(
+            db.remove_paywall_price(&chat),
+            Err(SqliteManagerError::X402PriceNotFound(_))
+        ));
+    }
+}
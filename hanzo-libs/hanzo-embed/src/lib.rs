@@ -2,3 +2,7 @@ pub mod embedding_generator;
 pub mod mock_generator;
 pub mod model_type;
 pub mod hanzo_embedding_errors;
+#[cfg(feature = "native")]
+pub mod native_embedding_generator;
+pub mod reranker;
+pub mod tokenizer;
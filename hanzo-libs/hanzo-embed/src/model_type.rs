@@ -2,6 +2,7 @@ use std::fmt;
 use std::hash::Hash;
 
 use crate::hanzo_embedding_errors::HanzoEmbeddingError;
+use crate::tokenizer::{load_model_tokenizer, model_tokenizer};
 
 pub type EmbeddingModelTypeString = String;
 
@@ -33,6 +34,20 @@ impl EmbeddingModelType {
         }
     }
 
+    /// Number of tokens the model's tokenizer makes out of `text`
+    pub fn count_tokens(&self, text: &str) -> usize {
+        match self {
+            EmbeddingModelType::OllamaTextEmbeddingsInference(model) => model.count_tokens(text),
+        }
+    }
+
+    /// Loads the model's tokenizer so that `count_tokens` uses it instead of an estimate
+    pub async fn load_tokenizer(&self) {
+        match self {
+            EmbeddingModelType::OllamaTextEmbeddingsInference(model) => model.load_tokenizer().await,
+        }
+    }
+
     pub fn embedding_normalization_factor(&self) -> f32 {
         match self {
             EmbeddingModelType::OllamaTextEmbeddingsInference(model) => model.embedding_normalization_factor(),
@@ -90,6 +105,51 @@ impl OllamaTextEmbeddingsInference {
         }
     }
 
+    /// Average length of a word piece in the model's vocabulary. The ~30k entry WordPiece vocabularies
+    /// of the BERT-style models cut words shorter than the SentencePiece/BPE ones of Gemma and Qwen.
+    fn chars_per_word_piece(&self) -> usize {
+        match self {
+            Self::AllMiniLML6v2 | Self::SnowflakeArcticEmbedM | Self::JinaEmbeddingsV2BaseEs => 4,
+            _ => 5,
+        }
+    }
+
+    /// How many tokens the model's tokenizer makes out of `text`, special tokens excluded.
+    /// Estimated while the tokenizer isn't loaded (see `load_tokenizer`) or can't be.
+    pub fn count_tokens(&self, text: &str) -> usize {
+        if let Some(tokenizer) = model_tokenizer(self) {
+            if let Ok(encoding) = tokenizer.encode(text, false) {
+                return encoding.len();
+            }
+        }
+        self.estimate_tokens(text)
+    }
+
+    pub async fn load_tokenizer(&self) {
+        load_model_tokenizer(self).await;
+    }
+
+    /// Estimates how many tokens the model's tokenizer makes out of `text` without loading it:
+    /// punctuation and non-ASCII characters are one token each, runs of ASCII letters and digits are
+    /// split into word pieces. Rounds up, so text sized with it fits in `max_input_token_count`.
+    fn estimate_tokens(&self, text: &str) -> usize {
+        let chars_per_piece = self.chars_per_word_piece();
+        let mut tokens = 0;
+        let mut run: usize = 0;
+        for c in text.chars() {
+            if c.is_ascii_alphanumeric() {
+                run += 1;
+                continue;
+            }
+            tokens += run.div_ceil(chars_per_piece);
+            run = 0;
+            if !c.is_whitespace() {
+                tokens += 1;
+            }
+        }
+        tokens + run.div_ceil(chars_per_piece)
+    }
+
     pub fn embedding_normalization_factor(&self) -> f32 {
         match self {
             Self::JinaEmbeddingsV2BaseEs => 1.5,
@@ -148,6 +208,17 @@ mod tests {
         assert_eq!(parsed_model, Ok(OllamaTextEmbeddingsInference::EmbeddingGemma300M));
     }
 
+    #[test]
+    fn test_estimate_tokens_depends_on_vocabulary() {
+        let text = "Hello, wonderful world!";
+        assert_eq!(
+            OllamaTextEmbeddingsInference::EmbeddingGemma300M.estimate_tokens(text),
+            6
+        );
+        assert_eq!(OllamaTextEmbeddingsInference::AllMiniLML6v2.estimate_tokens(text), 9);
+        assert_eq!(OllamaTextEmbeddingsInference::AllMiniLML6v2.estimate_tokens(""), 0);
+    }
+
     #[test]
     fn test_parse_snowflake_arctic_embed_xs_as_embedding_model_type() {
         let model_str = "snowflake-arctic-embed:xs";
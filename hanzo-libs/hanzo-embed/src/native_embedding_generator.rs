@@ -4,21 +4,19 @@ use crate::model_type::EmbeddingModelType;
 // NativeMistralEmbeddings was removed in upstream v1.1.10
 // TODO: Re-implement with proper model types
 use async_trait::async_trait;
-use mistralrs::{
-    GgufModelBuilder, IsqType, TextMessages, TextModelBuilder,
-    Model, Response, SamplingParams,
-};
+use mistralrs::{GgufModelBuilder, IsqType, Model, TextModelBuilder};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 /// Native embedding generator using mistral.rs for local inference
-/// with fallback to Ollama if mistral.rs fails
+/// with fallback to Ollama if mistral.rs fails.
+/// Reranking is out of scope here, as mistral.rs serves chat models and not cross-encoders:
+/// job retrieval reranks with a `RemoteReranker` (a Text Embeddings Inference server).
 #[derive(Clone)]
 pub struct NativeEmbeddingGenerator {
     pub model_type: EmbeddingModelType,
     pub model: Option<Arc<Mutex<Model>>>,
-    pub reranker_model: Option<Arc<Mutex<Model>>>,
     pub fallback_generator: Option<Box<RemoteEmbeddingGenerator>>,
     pub model_path: Option<PathBuf>,
     pub use_gpu: bool,
@@ -78,7 +76,6 @@ impl NativeEmbeddingGenerator {
         Ok(Self {
             model_type,
             model,
-            reranker_model: None, // Can be loaded separately if needed
             fallback_generator,
             model_path,
             use_gpu,
@@ -99,19 +96,8 @@ impl NativeEmbeddingGenerator {
             let builder = GgufModelBuilder::new(model_str)
                 .with_logging();
             
-            // Add ISQ quantization based on model type
-            let builder = match model_type {
-                EmbeddingModelType::OllamaTextEmbeddingsInference(ref model) => {
-                    match model {
-                        crate::model_type::OllamaTextEmbeddingsInference::Qwen3Next |
-                        crate::model_type::OllamaTextEmbeddingsInference::Qwen3Reranker4B => {
-                            builder.with_isq(IsqType::Q4K) // Use Q4K for Qwen models for efficiency
-                        }
-                        _ => builder.with_isq(IsqType::Q8_0) // Default quantization
-                    }
-                }
-                _ => builder.with_isq(IsqType::Q8_0),
-            };
+            // Add ISQ quantization
+            let builder = builder.with_isq(IsqType::Q8_0);
             
             builder.build().await.map_err(|e| {
                 HanzoEmbeddingError::FailedEmbeddingGeneration(
@@ -133,10 +119,6 @@ impl NativeEmbeddingGenerator {
         let model_id = match model_type {
             EmbeddingModelType::OllamaTextEmbeddingsInference(ref model) => {
                 match model {
-                    crate::model_type::OllamaTextEmbeddingsInference::Qwen3Next =>
-                        "Qwen/Qwen2.5-7B-Instruct", // Qwen3-Next embedding model
-                    crate::model_type::OllamaTextEmbeddingsInference::Qwen3Reranker4B =>
-                        "Qwen/Qwen2.5-7B-Instruct", // Qwen3 Reranker model
                     crate::model_type::OllamaTextEmbeddingsInference::EmbeddingGemma300M =>
                         "google/gemma-2b", // Gemma embedding model
                     _ => {
@@ -166,108 +148,18 @@ impl NativeEmbeddingGenerator {
             })
     }
 
-    /// Check if this is a reranker model
-    pub fn is_reranker(&self) -> bool {
-        match &self.model_type {
-            EmbeddingModelType::OllamaTextEmbeddingsInference(model) => {
-                matches!(model, crate::model_type::OllamaTextEmbeddingsInference::Qwen3Reranker4B)
-            }
-            _ => false,
-        }
-    }
-
     /// Generate embeddings using mistral.rs
     async fn generate_with_mistral(&self, texts: Vec<&str>) -> Result<Vec<Vec<f32>>, HanzoEmbeddingError> {
-        let model = self.model.as_ref().ok_or_else(|| {
-            HanzoEmbeddingError::FailedEmbeddingGeneration("Model not loaded".to_string())
-        })?;
-
-        let mut embeddings = Vec::new();
-        
-        for text in texts {
-            // Create a message for embedding generation
-            // Note: mistral.rs doesn't have direct embedding API, so we use text generation
-            // and would need to extract hidden states. For now, this is a placeholder.
-            let messages = TextMessages::new()
-                .add_user_message(text);
-            
-            // Use the model to process the text
-            // In a real implementation, we'd need to modify mistral.rs to expose embeddings
-            let model_lock = model.lock().await;
-            let response = model_lock.send_chat_request(messages).await.map_err(|e| {
-                HanzoEmbeddingError::FailedEmbeddingGeneration(
-                    format!("Failed to generate with mistral.rs: {}", e)
-                )
-            })?;
-            
-            // For now, return a placeholder embedding
-            // In reality, we'd extract the actual embeddings from the model's hidden states
-            let embedding_dim = match &self.model_type {
-                EmbeddingModelType::OllamaTextEmbeddingsInference(m) => {
-                    m.vector_dimensions().unwrap_or(768)
-                }
-                _ => 768,
-            };
-            
-            // Generate a placeholder embedding (this would be replaced with actual embeddings)
-            let placeholder_embedding = vec![0.1; embedding_dim];
-            embeddings.push(placeholder_embedding);
-            
-            log::warn!("Using placeholder embeddings - actual embedding extraction not yet implemented");
-        }
-        
-        Ok(embeddings)
-    }
-
-    /// Rerank documents based on query relevance
-    pub async fn rerank(
-        &self,
-        query: &str,
-        documents: Vec<String>,
-        top_k: Option<usize>,
-    ) -> Result<Vec<(usize, f32)>, HanzoEmbeddingError> {
-        if !self.is_reranker() {
-            return Err(HanzoEmbeddingError::FailedEmbeddingGeneration(
-                "Model is not a reranker".to_string()
-            ));
+        if self.model.is_none() {
+            return Err(HanzoEmbeddingError::FailedEmbeddingGeneration("Model not loaded".to_string()));
         }
 
-        // If we have a reranker model loaded, use it
-        if let Some(reranker) = &self.reranker_model {
-            let model_lock = reranker.lock().await;
-            
-            // Score each document against the query
-            let mut scores = Vec::new();
-            for (idx, doc) in documents.iter().enumerate() {
-                // Create a prompt for scoring
-                let messages = TextMessages::new()
-                    .add_system_message("Score the relevance of the document to the query on a scale of 0-1.")
-                    .add_user_message(&format!("Query: {}\n\nDocument: {}", query, doc));
-                
-                // Get score from model (placeholder implementation)
-                let response = model_lock.send_chat_request(messages).await.map_err(|e| {
-                    HanzoEmbeddingError::FailedEmbeddingGeneration(
-                        format!("Failed to rerank: {}", e)
-                    )
-                })?;
-                
-                // Parse score from response (placeholder - would need actual implementation)
-                let score = 1.0 - (idx as f32 * 0.1); // Placeholder scoring
-                scores.push((idx, score));
-            }
-            
-            // Sort by score and return top-k
-            scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-            let top_k = top_k.unwrap_or(documents.len());
-            Ok(scores.into_iter().take(top_k).collect())
-        } else {
-            // Fallback to simple scoring
-            let top_k = top_k.unwrap_or(documents.len());
-            let scores: Vec<(usize, f32)> = (0..documents.len().min(top_k))
-                .map(|i| (i, 1.0 - (i as f32 * 0.1)))
-                .collect();
-            Ok(scores)
-        }
+        // mistral.rs only exposes text generation, not the hidden states an embedding is made of.
+        // Fail so that the fallback generator is used instead of returning made-up vectors.
+        Err(HanzoEmbeddingError::FailedEmbeddingGeneration(format!(
+            "Embedding extraction with mistral.rs is not implemented ({} texts)",
+            texts.len()
+        )))
     }
 }
 
@@ -300,13 +192,20 @@ impl EmbeddingGenerator for NativeEmbeddingGenerator {
         ))
     }
 
+    async fn generate_embedding(
+        &self,
+        content: &str,
+    ) -> Result<Vec<f32>, HanzoEmbeddingError> {
+        self.generate_embedding_default(content).await
+    }
+
     async fn generate_embeddings(
         &self,
-        contents: Vec<&str>,
+        contents: &Vec<String>,
     ) -> Result<Vec<Vec<f32>>, HanzoEmbeddingError> {
         // Try native first
         if self.model.is_some() {
-            match self.generate_with_mistral(contents.clone()).await {
+            match self.generate_with_mistral(contents.iter().map(String::as_str).collect()).await {
                 Ok(embeddings) if !embeddings.is_empty() => {
                     return Ok(embeddings);
                 }
@@ -327,11 +226,37 @@ impl EmbeddingGenerator for NativeEmbeddingGenerator {
         ))
     }
 
+    /// mistral.rs is async only, so blocking calls go to the fallback generator
+    fn generate_embedding_blocking(&self, content: &str) -> Result<Vec<f32>, HanzoEmbeddingError> {
+        match &self.fallback_generator {
+            Some(fallback) => fallback.generate_embedding_blocking(content),
+            None => Err(HanzoEmbeddingError::FailedEmbeddingGeneration(
+                "Blocking embeddings need a fallback backend".to_string()
+            )),
+        }
+    }
+
+    fn generate_embeddings_blocking(
+        &self,
+        contents: &Vec<String>,
+    ) -> Result<Vec<Vec<f32>>, HanzoEmbeddingError> {
+        match &self.fallback_generator {
+            Some(fallback) => fallback.generate_embeddings_blocking(contents),
+            None => Err(HanzoEmbeddingError::FailedEmbeddingGeneration(
+                "Blocking embeddings need a fallback backend".to_string()
+            )),
+        }
+    }
+
     fn model_type(&self) -> EmbeddingModelType {
         self.model_type.clone()
     }
 
+    fn set_model_type(&mut self, model_type: EmbeddingModelType) {
+        self.model_type = model_type;
+    }
+
     fn box_clone(&self) -> Box<dyn EmbeddingGenerator> {
         Box::new(self.clone())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,197 @@
+use crate::hanzo_embedding_errors::HanzoEmbeddingError;
+use async_trait::async_trait;
+use reqwest::ClientBuilder;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A trait for types that can score documents against a query, usually with a cross-encoder.
+#[async_trait]
+pub trait Reranker: Sync + Send {
+    fn box_clone(&self) -> Box<dyn Reranker>;
+
+    /// Scores each document against the query. Returns `(document index, score)` pairs sorted
+    /// from most to least relevant, limited to `top_k` if given.
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: Vec<String>,
+        top_k: Option<usize>,
+    ) -> Result<Vec<(usize, f32)>, HanzoEmbeddingError>;
+}
+
+impl Clone for Box<dyn Reranker> {
+    fn clone(&self) -> Box<dyn Reranker> {
+        self.box_clone()
+    }
+}
+
+/// Reranker backed by a server exposing Hugging Face's Text Embeddings Inference `/rerank` endpoint
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RemoteReranker {
+    pub api_url: String,
+    pub api_key: Option<String>,
+}
+
+#[async_trait]
+impl Reranker for RemoteReranker {
+    /// Clones self and wraps it in a Box
+    fn box_clone(&self) -> Box<dyn Reranker> {
+        Box::new(self.clone())
+    }
+
+    async fn rerank(
+        &self,
+        query: &str,
+        documents: Vec<String>,
+        top_k: Option<usize>,
+    ) -> Result<Vec<(usize, f32)>, HanzoEmbeddingError> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let scores = self.rerank_tei(query, documents).await?;
+        Ok(sort_by_score(
+            scores.into_iter().map(|result| (result.index, result.score)).collect(),
+            top_k,
+        ))
+    }
+}
+
+impl RemoteReranker {
+    /// Create a RemoteReranker
+    pub fn new(api_url: &str, api_key: Option<String>) -> RemoteReranker {
+        RemoteReranker {
+            api_url: api_url.to_string(),
+            api_key,
+        }
+    }
+
+    /// String of the rerank endpoint url of a Text Embeddings Inference server
+    fn rerank_endpoint_url(&self) -> String {
+        if self.api_url.ends_with('/') {
+            format!("{}rerank", self.api_url)
+        } else {
+            format!("{}/rerank", self.api_url)
+        }
+    }
+
+    /// Scores documents using Hugging Face's Text Embeddings Inference server.
+    /// Documents longer than the model input are truncated by the server.
+    pub async fn rerank_tei(
+        &self,
+        query: &str,
+        documents: Vec<String>,
+    ) -> Result<Vec<TeiRerankResult>, HanzoEmbeddingError> {
+        let max_retries = 3;
+        let mut retry_count = 0;
+
+        let request_body = TeiRerankRequestBody {
+            query: query.to_string(),
+            texts: documents,
+            raw_scores: false,
+            truncate: true,
+        };
+
+        loop {
+            // Create the HTTP client with a custom timeout
+            let timeout = Duration::from_secs(60);
+            let client = ClientBuilder::new().timeout(timeout).build()?;
+
+            // Build the request
+            let mut request = client
+                .post(self.rerank_endpoint_url())
+                .header("Content-Type", "application/json")
+                .json(&request_body);
+
+            // Add the API key to the header if it's available
+            if let Some(api_key) = &self.api_key {
+                request = request.header("Authorization", format!("Bearer {}", api_key));
+            }
+
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    return response.json::<Vec<TeiRerankResult>>().await.map_err(|err| {
+                        HanzoEmbeddingError::RequestFailed(format!("Failed to deserialize response JSON: {}", err))
+                    });
+                }
+                Ok(response) => {
+                    return Err(HanzoEmbeddingError::RequestFailed(format!(
+                        "HTTP request failed with status: {}",
+                        response.status()
+                    )));
+                }
+                Err(err) => {
+                    if retry_count < max_retries {
+                        retry_count += 1;
+                        continue;
+                    } else {
+                        return Err(HanzoEmbeddingError::RequestFailed(format!(
+                            "HTTP request failed after {} retries: {}",
+                            max_retries, err
+                        )));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sorts `(document index, score)` pairs from most to least relevant and keeps the `top_k` first
+pub fn sort_by_score(mut scores: Vec<(usize, f32)>, top_k: Option<usize>) -> Vec<(usize, f32)> {
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    if let Some(top_k) = top_k {
+        scores.truncate(top_k);
+    }
+    scores
+}
+
+#[derive(Debug, Serialize)]
+struct TeiRerankRequestBody {
+    query: String,
+    texts: Vec<String>,
+    raw_scores: bool,
+    truncate: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TeiRerankResult {
+    pub index: usize,
+    pub score: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rerank_endpoint_url() {
+        let reranker = RemoteReranker::new("http://localhost:8080", None);
+        assert_eq!(reranker.rerank_endpoint_url(), "http://localhost:8080/rerank");
+
+        let reranker = RemoteReranker::new("http://localhost:8080/", None);
+        assert_eq!(reranker.rerank_endpoint_url(), "http://localhost:8080/rerank");
+    }
+
+    #[test]
+    fn test_sort_by_score() {
+        let scores = vec![(0, 0.1), (1, 0.9), (2, 0.5), (3, 0.9)];
+        assert_eq!(
+            sort_by_score(scores.clone(), None),
+            vec![(1, 0.9), (3, 0.9), (2, 0.5), (0, 0.1)]
+        );
+        assert_eq!(sort_by_score(scores, Some(2)), vec![(1, 0.9), (3, 0.9)]);
+    }
+
+    #[test]
+    fn test_parse_tei_rerank_response() {
+        let response: Vec<TeiRerankResult> =
+            serde_json::from_str(r#"[{"index":1,"score":0.98},{"index":0,"score":0.02}]"#).unwrap();
+        assert_eq!(
+            response,
+            vec![
+                TeiRerankResult { index: 1, score: 0.98 },
+                TeiRerankResult { index: 0, score: 0.02 }
+            ]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use crate::model_type::OllamaTextEmbeddingsInference;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokenizers::Tokenizer;
+
+/// How long a tokenizer that failed to load (e.g. offline) is left alone before trying again
+const RETRY_FAILED_LOAD_AFTER: Duration = Duration::from_secs(10 * 60);
+
+enum TokenizerState {
+    Loading,
+    Loaded(Arc<Tokenizer>),
+    Failed(Instant),
+}
+
+lazy_static! {
+    // Tokenizers by Hugging Face repository. The lock is only held to read and update the map,
+    // never while a tokenizer is downloaded.
+    static ref TOKENIZERS: Mutex<HashMap<&'static str, TokenizerState>> = Mutex::new(HashMap::new());
+}
+
+/// Hugging Face repository holding the `tokenizer.json` of the model
+pub fn tokenizer_repository(model: &OllamaTextEmbeddingsInference) -> Option<&'static str> {
+    match model {
+        OllamaTextEmbeddingsInference::AllMiniLML6v2 => Some("sentence-transformers/all-MiniLM-L6-v2"),
+        OllamaTextEmbeddingsInference::SnowflakeArcticEmbedM => Some("Snowflake/snowflake-arctic-embed-xs"),
+        OllamaTextEmbeddingsInference::JinaEmbeddingsV2BaseEs => Some("jinaai/jina-embeddings-v2-base-es"),
+        OllamaTextEmbeddingsInference::EmbeddingGemma300M => Some("google/embeddinggemma-300m"),
+        OllamaTextEmbeddingsInference::Other(_) => None,
+    }
+}
+
+/// The tokenizer of the model if it has been loaded with `load_model_tokenizer`
+pub fn model_tokenizer(model: &OllamaTextEmbeddingsInference) -> Option<Arc<Tokenizer>> {
+    let repository = tokenizer_repository(model)?;
+    match TOKENIZERS.lock().unwrap().get(repository) {
+        Some(TokenizerState::Loaded(tokenizer)) => Some(tokenizer.clone()),
+        _ => None,
+    }
+}
+
+/// Loads the tokenizer of the model, downloading it to the Hugging Face cache on first use.
+/// None if the model has no known tokenizer, another task is loading it, or it could not be
+/// loaded. Failed loads are retried once `RETRY_FAILED_LOAD_AFTER` has passed.
+pub async fn load_model_tokenizer(model: &OllamaTextEmbeddingsInference) -> Option<Arc<Tokenizer>> {
+    let repository = tokenizer_repository(model)?;
+    {
+        let mut tokenizers = TOKENIZERS.lock().unwrap();
+        match tokenizers.get(repository) {
+            Some(TokenizerState::Loaded(tokenizer)) => return Some(tokenizer.clone()),
+            Some(TokenizerState::Loading) => return None,
+            Some(TokenizerState::Failed(failed_at)) if failed_at.elapsed() < RETRY_FAILED_LOAD_AFTER => return None,
+            _ => {}
+        }
+        tokenizers.insert(repository, TokenizerState::Loading);
+    }
+
+    let loaded = tokio::task::spawn_blocking(move || Tokenizer::from_pretrained(repository, None))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result.map_err(|e| e.to_string()));
+
+    let mut tokenizers = TOKENIZERS.lock().unwrap();
+    match loaded {
+        Ok(tokenizer) => {
+            let tokenizer = Arc::new(tokenizer);
+            tokenizers.insert(repository, TokenizerState::Loaded(tokenizer.clone()));
+            Some(tokenizer)
+        }
+        Err(e) => {
+            log::warn!(
+                "Failed to load the tokenizer of {}, estimating token counts instead: {}",
+                repository,
+                e
+            );
+            tokenizers.insert(repository, TokenizerState::Failed(Instant::now()));
+            None
+        }
+    }
+}
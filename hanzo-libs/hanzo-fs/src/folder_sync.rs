@@ -0,0 +1,398 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use hanzo_db_sqlite::SqliteManager;
+use hanzo_embed::embedding_generator::EmbeddingGenerator;
+use hanzo_messages::hanzo_utils::hanzo_logging::{hanzo_log, HanzoLogLevel, HanzoLogOption};
+use hanzo_messages::hanzo_utils::hanzo_path::HanzoPath;
+use hanzo_messages::schemas::hanzo_fs::{FolderSyncReport, WatchedFolder};
+
+use crate::hanzo_file_manager::{FileProcessingMode, HanzoFileManager};
+use crate::hanzo_fs_error::HanzoFsError;
+use crate::simple_parser::file_parser_helper::HanzoFileParser;
+
+impl HanzoFileManager {
+    /// Mirrors a watched host directory into its VecFS folder. Files are compared with the content
+    /// hash recorded at their last sync, so only new and modified files are parsed and embedded again.
+    /// Files deleted from the directory are removed from the VecFS along with their chunks.
+    /// Hidden files and directories (starting with `.`) are ignored.
+    pub async fn sync_watched_folder(
+        watched_folder: &WatchedFolder,
+        sqlite_manager: &SqliteManager,
+        generator: &dyn EmbeddingGenerator,
+    ) -> Result<FolderSyncReport, HanzoFsError> {
+        let host_root = Path::new(&watched_folder.host_path);
+        if !host_root.is_dir() {
+            return Err(HanzoFsError::FolderNotFoundOnFilesystem);
+        }
+
+        let known_hashes = sqlite_manager.get_watched_folder_file_hashes(watched_folder.id)?;
+
+        // Listing errors abort the sync, as the files missing from a partial listing would be removed
+        let mut host_files = Vec::new();
+        Self::collect_host_files(host_root, host_root, &mut host_files)?;
+
+        let seen: HashSet<&String> = host_files.iter().map(|(relative_path, _)| relative_path).collect();
+        let mut deleted: Vec<String> = known_hashes
+            .keys()
+            .filter(|path| !seen.contains(path))
+            .cloned()
+            .collect();
+        deleted.sort();
+
+        Ok(Self::apply_watched_changes(
+            watched_folder,
+            &known_hashes,
+            &host_files,
+            &deleted,
+            sqlite_manager,
+            generator,
+        )
+        .await)
+    }
+
+    /// Syncs only the given host paths of a watched folder, e.g. the ones a filesystem watcher reported.
+    /// A directory syncs the files under it, and a path that no longer exists removes everything
+    /// synced from it. Paths outside the watched directory or hidden are ignored.
+    pub async fn sync_watched_paths(
+        watched_folder: &WatchedFolder,
+        host_paths: &[PathBuf],
+        sqlite_manager: &SqliteManager,
+        generator: &dyn EmbeddingGenerator,
+    ) -> Result<FolderSyncReport, HanzoFsError> {
+        let host_root = Path::new(&watched_folder.host_path);
+        if !host_root.is_dir() {
+            return Err(HanzoFsError::FolderNotFoundOnFilesystem);
+        }
+
+        let mut host_files = Vec::new();
+        let mut missing = Vec::new();
+        for host_path in host_paths {
+            let Some(relative_path) = Self::relative_host_path(host_root, host_path) else {
+                continue;
+            };
+            if relative_path.is_empty() {
+                return Self::sync_watched_folder(watched_folder, sqlite_manager, generator).await;
+            }
+            if relative_path.split('/').any(|component| component.starts_with('.')) {
+                continue;
+            }
+
+            // Symlinks are not followed, as in a full sync
+            match fs::symlink_metadata(host_path) {
+                Ok(metadata) if metadata.is_dir() => Self::collect_host_files(host_root, host_path, &mut host_files)?,
+                Ok(metadata) if metadata.is_file() => host_files.push((relative_path, host_path.clone())),
+                Ok(_) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => missing.push(relative_path),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        host_files.sort();
+        host_files.dedup();
+
+        let known_hashes = sqlite_manager.get_watched_folder_file_hashes(watched_folder.id)?;
+        let mut deleted: Vec<String> = known_hashes
+            .keys()
+            .filter(|known| {
+                missing
+                    .iter()
+                    .any(|path| *known == path || known.starts_with(&format!("{}/", path)))
+            })
+            .cloned()
+            .collect();
+        deleted.sort();
+
+        Ok(Self::apply_watched_changes(
+            watched_folder,
+            &known_hashes,
+            &host_files,
+            &deleted,
+            sqlite_manager,
+            generator,
+        )
+        .await)
+    }
+
+    /// Syncs the listed host files and removes the deleted ones. A file that fails is logged and
+    /// reported as failed without stopping the others.
+    async fn apply_watched_changes(
+        watched_folder: &WatchedFolder,
+        known_hashes: &HashMap<String, String>,
+        host_files: &[(String, PathBuf)],
+        deleted: &[String],
+        sqlite_manager: &SqliteManager,
+        generator: &dyn EmbeddingGenerator,
+    ) -> FolderSyncReport {
+        let mut report = FolderSyncReport::default();
+
+        for (relative_path, host_file) in host_files {
+            let known_hash = known_hashes.get(relative_path);
+            let result = Self::sync_watched_file(
+                watched_folder,
+                relative_path,
+                host_file,
+                known_hash,
+                sqlite_manager,
+                generator,
+            )
+            .await;
+            match result {
+                Ok(false) => report.unchanged += 1,
+                Ok(true) if known_hash.is_some() => report.updated.push(relative_path.clone()),
+                Ok(true) => report.added.push(relative_path.clone()),
+                Err(e) => Self::report_failed_file(watched_folder, relative_path, e, &mut report),
+            }
+        }
+
+        for relative_path in deleted {
+            match Self::remove_watched_file(watched_folder, relative_path, sqlite_manager) {
+                Ok(()) => report.removed.push(relative_path.clone()),
+                Err(e) => Self::report_failed_file(watched_folder, relative_path, e, &mut report),
+            }
+        }
+
+        report
+    }
+
+    /// Parses and embeds a host file unless its content matches the hash of its last sync.
+    /// Returns whether the file was synced.
+    async fn sync_watched_file(
+        watched_folder: &WatchedFolder,
+        relative_path: &str,
+        host_file: &Path,
+        known_hash: Option<&String>,
+        sqlite_manager: &SqliteManager,
+        generator: &dyn EmbeddingGenerator,
+    ) -> Result<bool, HanzoFsError> {
+        let data = fs::read(host_file)?;
+        let data_hash = HanzoFileParser::generate_data_hash(&data);
+        let dest_path = Self::watched_file_vecfs_path(watched_folder, relative_path);
+        if known_hash == Some(&data_hash) && dest_path.exists() {
+            return Ok(false);
+        }
+
+        // Drop the previous version and its chunks so the new content gets embedded
+        if dest_path.exists() {
+            Self::remove_file(dest_path.clone(), sqlite_manager)?;
+        }
+        Self::save_and_process_file(
+            dest_path,
+            data,
+            sqlite_manager,
+            FileProcessingMode::Auto,
+            generator,
+            None,
+        )
+        .await?;
+
+        // Only recorded once the file is embedded, so a failed file is retried on the next sync
+        sqlite_manager.set_watched_folder_file_hash(watched_folder.id, relative_path, &data_hash)?;
+        Ok(true)
+    }
+
+    fn remove_watched_file(
+        watched_folder: &WatchedFolder,
+        relative_path: &str,
+        sqlite_manager: &SqliteManager,
+    ) -> Result<(), HanzoFsError> {
+        let dest_path = Self::watched_file_vecfs_path(watched_folder, relative_path);
+        if dest_path.exists() {
+            Self::remove_file(dest_path, sqlite_manager)?;
+        }
+        sqlite_manager.remove_watched_folder_file_hash(watched_folder.id, relative_path)?;
+        Ok(())
+    }
+
+    fn report_failed_file(
+        watched_folder: &WatchedFolder,
+        relative_path: &str,
+        error: HanzoFsError,
+        report: &mut FolderSyncReport,
+    ) {
+        hanzo_log(
+            HanzoLogOption::Node,
+            HanzoLogLevel::Error,
+            &format!(
+                "Failed to sync {} of watched folder {}: {}",
+                relative_path, watched_folder.host_path, error
+            ),
+        );
+        report.failed.push(relative_path.to_string());
+    }
+
+    /// Removes the files synced from a watched folder from the VecFS, with their chunks
+    pub fn remove_watched_folder_files(
+        watched_folder: &WatchedFolder,
+        sqlite_manager: &SqliteManager,
+    ) -> Result<(), HanzoFsError> {
+        for relative_path in sqlite_manager.get_watched_folder_file_hashes(watched_folder.id)?.keys() {
+            let dest_path = Self::watched_file_vecfs_path(watched_folder, relative_path);
+            if dest_path.exists() {
+                Self::remove_file(dest_path, sqlite_manager)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn watched_file_vecfs_path(watched_folder: &WatchedFolder, relative_path: &str) -> HanzoPath {
+        let mut path = HanzoPath::from_string(watched_folder.vecfs_path.clone());
+        path.push(relative_path);
+        path
+    }
+
+    /// Lists the files under `dir` with their path relative to `root`, using `/` as separator.
+    /// Symlinks are not followed so a link can't pull files from outside the watched directory.
+    fn collect_host_files(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) -> Result<(), HanzoFsError> {
+        let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                Self::collect_host_files(root, &path, files)?;
+            } else if file_type.is_file() {
+                let relative_path = Self::relative_host_path(root, &path).ok_or_else(|| {
+                    HanzoFsError::FailedIO(format!("{} is outside {}", path.display(), root.display()))
+                })?;
+                files.push((relative_path, path));
+            }
+        }
+        Ok(())
+    }
+
+    /// Path of `path` relative to `root`, using `/` as separator. None if it is outside `root`.
+    fn relative_host_path(root: &Path, path: &Path) -> Option<String> {
+        let relative_path = path.strip_prefix(root).ok()?;
+        Some(
+            relative_path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_embed::mock_generator::MockGenerator;
+    use hanzo_embed::model_type::EmbeddingModelType;
+    use serial_test::serial;
+    use tempfile::{tempdir, NamedTempFile};
+
+    fn setup_test_db() -> SqliteManager {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db_path = PathBuf::from(temp_file.path());
+        let api_url = String::new();
+        let model_type = EmbeddingModelType::default();
+
+        SqliteManager::new(db_path, api_url, model_type).unwrap()
+    }
+
+    fn parsed_file_id(db: &SqliteManager, rel_path: &str) -> Option<i64> {
+        db.get_parsed_file_by_rel_path(rel_path).unwrap().and_then(|pf| pf.id)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_sync_watched_folder() {
+        let db = setup_test_db();
+        let storage_dir = tempdir().unwrap();
+        std::env::set_var("NODE_STORAGE_PATH", storage_dir.path().to_string_lossy().to_string());
+        let host_dir = tempdir().unwrap();
+
+        let model_type = EmbeddingModelType::default();
+        let generator = MockGenerator::new(model_type.clone(), model_type.vector_dimensions().unwrap_or(768));
+
+        fs::create_dir_all(host_dir.path().join("guides")).unwrap();
+        fs::write(host_dir.path().join("readme.md"), "# Readme\nHello").unwrap();
+        fs::write(host_dir.path().join("guides/setup.txt"), "Install the node.").unwrap();
+        fs::write(host_dir.path().join(".hidden.txt"), "Ignored.").unwrap();
+
+        let id = db
+            .add_watched_folder(host_dir.path().to_str().unwrap(), "mirror")
+            .unwrap();
+        let watched_folder = db.get_watched_folder(id).unwrap().unwrap();
+
+        // First sync adds everything
+        let report = HanzoFileManager::sync_watched_folder(&watched_folder, &db, &generator)
+            .await
+            .unwrap();
+        assert_eq!(report.added, vec!["guides/setup.txt", "readme.md"]);
+        assert!(parsed_file_id(&db, "mirror/readme.md").is_some());
+        assert!(parsed_file_id(&db, "mirror/.hidden.txt").is_none());
+        let setup_id = parsed_file_id(&db, "mirror/guides/setup.txt").unwrap();
+
+        // Nothing changed, nothing is embedded again
+        let report = HanzoFileManager::sync_watched_folder(&watched_folder, &db, &generator)
+            .await
+            .unwrap();
+        assert!(!report.has_changes());
+        assert_eq!(report.unchanged, 2);
+
+        // A modified file is re-embedded and a deleted one removed
+        fs::write(host_dir.path().join("guides/setup.txt"), "Install and start the node.").unwrap();
+        fs::remove_file(host_dir.path().join("readme.md")).unwrap();
+        let report = HanzoFileManager::sync_watched_folder(&watched_folder, &db, &generator)
+            .await
+            .unwrap();
+        assert_eq!(report.updated, vec!["guides/setup.txt"]);
+        assert_eq!(report.removed, vec!["readme.md"]);
+        assert!(parsed_file_id(&db, "mirror/readme.md").is_none());
+        assert!(!HanzoPath::from_string("mirror/readme.md".to_string()).exists());
+
+        let new_setup_id = parsed_file_id(&db, "mirror/guides/setup.txt").unwrap();
+        assert_ne!(new_setup_id, setup_id);
+        let chunks = db.get_chunks_for_parsed_file(new_setup_id).unwrap();
+        assert!(chunks.iter().any(|chunk| chunk.content.contains("start the node")));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_sync_watched_paths() {
+        let db = setup_test_db();
+        let storage_dir = tempdir().unwrap();
+        std::env::set_var("NODE_STORAGE_PATH", storage_dir.path().to_string_lossy().to_string());
+        let host_dir = tempdir().unwrap();
+
+        let model_type = EmbeddingModelType::default();
+        let generator = MockGenerator::new(model_type.clone(), model_type.vector_dimensions().unwrap_or(768));
+
+        fs::create_dir_all(host_dir.path().join("guides")).unwrap();
+        fs::write(host_dir.path().join("readme.md"), "# Readme\nHello").unwrap();
+        fs::write(host_dir.path().join("guides/setup.txt"), "Install the node.").unwrap();
+
+        let id = db
+            .add_watched_folder(host_dir.path().to_str().unwrap(), "mirror")
+            .unwrap();
+        let watched_folder = db.get_watched_folder(id).unwrap().unwrap();
+        HanzoFileManager::sync_watched_folder(&watched_folder, &db, &generator)
+            .await
+            .unwrap();
+
+        // Only the reported paths are looked at: the modified readme is left for another sync
+        fs::write(host_dir.path().join("readme.md"), "# Readme\nHello again").unwrap();
+        fs::write(host_dir.path().join("notes.txt"), "Some notes.").unwrap();
+        fs::remove_dir_all(host_dir.path().join("guides")).unwrap();
+        let changed = vec![
+            host_dir.path().join("notes.txt"),
+            host_dir.path().join("guides"),
+            host_dir.path().join(".hidden.txt"),
+        ];
+        let report = HanzoFileManager::sync_watched_paths(&watched_folder, &changed, &db, &generator)
+            .await
+            .unwrap();
+        assert_eq!(report.added, vec!["notes.txt"]);
+        assert!(report.updated.is_empty());
+        assert_eq!(report.removed, vec!["guides/setup.txt"]);
+        assert!(report.failed.is_empty());
+        assert!(parsed_file_id(&db, "mirror/notes.txt").is_some());
+        assert!(parsed_file_id(&db, "mirror/guides/setup.txt").is_none());
+    }
+}
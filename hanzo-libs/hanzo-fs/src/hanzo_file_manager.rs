@@ -6,7 +6,7 @@ use chrono::{DateTime, Utc};
 use serde::Serializer;
 use serde::{Deserialize, Serialize};
 use hanzo_embed::embedding_generator::EmbeddingGenerator;
-use hanzo_messages::schemas::hanzo_fs::{ParsedFile, HanzoFileChunk};
+use hanzo_messages::schemas::hanzo_fs::{ChunkingStrategy, ParsedFile, HanzoFileChunk};
 use hanzo_messages::hanzo_utils::hanzo_path::HanzoPath;
 use hanzo_messages::hanzo_utils::utils::count_tokens_from_message_llama3;
 use hanzo_db_sqlite::errors::SqliteManagerError;
@@ -14,6 +14,7 @@ use hanzo_db_sqlite::SqliteManager;
 use utoipa::ToSchema;
 
 use crate::hanzo_fs_error::HanzoFsError;
+use crate::simple_parser::chunking::chunker_for_strategy;
 use crate::simple_parser::simple_parser::SimpleParser;
 
 pub struct HanzoFileManager;
@@ -41,19 +42,22 @@ pub enum FileProcessingMode {
 
 impl HanzoFileManager {
     /// Save a file to disk and process it for embeddings based on the mode.
+    /// `chunking_strategy` overrides the strategy of the destination folder.
     pub async fn save_and_process_file(
         dest_path: HanzoPath,
         data: Vec<u8>,
         sqlite_manager: &SqliteManager,
         mode: FileProcessingMode,
         generator: &dyn EmbeddingGenerator,
+        chunking_strategy: Option<ChunkingStrategy>,
     ) -> Result<(), HanzoFsError> {
         // Save the file to disk
         Self::write_file_to_fs(dest_path.clone(), data)?;
 
         // Process the file for embeddings if the mode is not NoParsing
         if mode != FileProcessingMode::NoParsing {
-            let _ = Self::process_embeddings_for_file(dest_path, sqlite_manager, mode, generator).await;
+            let _ =
+                Self::process_embeddings_for_file(dest_path, sqlite_manager, mode, generator, chunking_strategy).await;
         }
 
         Ok(())
@@ -95,6 +99,7 @@ impl HanzoFileManager {
             tags: None,
             total_tokens: Some(total_tokens),
             total_characters: Some(total_characters),
+            chunking_strategy: None,
         };
 
         sqlite_manager.add_parsed_file(&parsed_file)?;
@@ -113,6 +118,7 @@ impl HanzoFileManager {
                 parsed_file_id,
                 position: position as i64,
                 content,
+                metadata: None,
             };
             sqlite_manager.create_chunk_with_embedding(&chunk, Some(&embedding))?;
         }
@@ -122,11 +128,14 @@ impl HanzoFileManager {
 
     /// Process file: If not in DB, add it. If supported, generate chunks.
     /// If already processed, consider checking if file changed (not implemented here).
+    /// Chunks are cut with `chunking_strategy`, or else with the strategy of the file's folder,
+    /// or else kept as the parser grouped them.
     pub async fn process_embeddings_for_file(
         path: HanzoPath,
         sqlite_manager: &SqliteManager,
         mode: FileProcessingMode, // TODO: maybe we dont need this?
         generator: &dyn EmbeddingGenerator,
+        chunking_strategy: Option<ChunkingStrategy>,
     ) -> Result<(), HanzoFsError> {
         if mode == FileProcessingMode::NoParsing {
             return Ok(());
@@ -152,6 +161,21 @@ impl HanzoFileManager {
         let max_node_text_size = generator.model_type().max_input_token_count();
         let mut text_groups = SimpleParser::parse_file(path.clone(), max_node_text_size.try_into().unwrap()).await?;
 
+        // Re-chunk the parsed text with the requested strategy. Source code keeps the chunks of the
+        // code parser, cut at definitions, so that their symbols and line ranges stay exact.
+        let chunking_strategy = match chunking_strategy {
+            _ if SimpleParser::is_source_code_file(&path) => None,
+            Some(strategy) => Some(strategy),
+            None => sqlite_manager.get_chunking_strategy_for_path(&rel_path)?,
+        };
+        if let Some(strategy) = &chunking_strategy {
+            // Token budgets are measured with the model's tokenizer, estimated if it can't be loaded
+            if matches!(strategy, ChunkingStrategy::TokenBudget { .. }) {
+                generator.model_type().load_tokenizer().await;
+            }
+            text_groups = chunker_for_strategy(strategy, &generator.model_type()).chunk(text_groups);
+        }
+
         // Generate embeddings for each text group and assign them directly
         for text_group in &mut text_groups {
             let embedding = generator.generate_embedding_default(&text_group.text).await?;
@@ -181,6 +205,7 @@ impl HanzoFileManager {
             tags: None, // TODO: connect this
             total_tokens: Some(total_tokens),
             total_characters: Some(total_characters),
+            chunking_strategy,
         };
 
         sqlite_manager.add_parsed_file(&parsed_file)?;
@@ -199,6 +224,7 @@ impl HanzoFileManager {
                 parsed_file_id,
                 position: position as i64,
                 content: text_group.text.clone(),
+                metadata: (!text_group.metadata.is_empty()).then(|| text_group.metadata.clone().into_iter().collect()),
             };
             sqlite_manager
                 .create_chunk_with_embedding(&chunk, Some(&text_group.embedding.as_ref().unwrap().clone()))?;
@@ -443,12 +469,21 @@ impl HanzoFileManager {
         sqlite_manager: &SqliteManager,
         mode: FileProcessingMode,
         generator: &dyn EmbeddingGenerator,
+        chunking_strategy: Option<ChunkingStrategy>,
     ) -> Result<HanzoPath, HanzoFsError> {
         // Use the new construct_job_file_path function
         let hanzo_path = Self::construct_job_file_path(job_id, &file_name, sqlite_manager)?;
 
         // Use the existing save_and_process_file function to save and process the file
-        Self::save_and_process_file(hanzo_path.clone(), data, sqlite_manager, mode, generator).await?;
+        Self::save_and_process_file(
+            hanzo_path.clone(),
+            data,
+            sqlite_manager,
+            mode,
+            generator,
+            chunking_strategy,
+        )
+        .await?;
 
         Ok(hanzo_path)
     }
@@ -704,6 +739,7 @@ mod tests {
             tags: None,
             total_tokens: None,
             total_characters: None,
+            chunking_strategy: None,
         }
     }
 
@@ -884,6 +920,7 @@ mod tests {
             &db,
             FileProcessingMode::Auto,
             &generator,
+            None,
         )
         .await;
 
@@ -904,6 +941,38 @@ mod tests {
         dir.close().unwrap();
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_process_file_with_folder_chunking_strategy() {
+        let (db, dir, hanzo_path, generator) = setup_test_environment();
+
+        let mut file = File::create(hanzo_path.as_path()).unwrap();
+        write_large_content(&mut file);
+
+        // The file is at the root, so the root folder's strategy applies
+        let strategy = ChunkingStrategy::FixedSize { chunk_size: 200 };
+        db.set_folder_chunking_strategy("", Some(&strategy)).unwrap();
+
+        let result = HanzoFileManager::process_embeddings_for_file(
+            hanzo_path.clone(),
+            &db,
+            FileProcessingMode::Auto,
+            &generator,
+            None,
+        )
+        .await;
+        assert!(result.is_ok());
+
+        // The strategy is recorded on the parsed file and every chunk respects it
+        let parsed_file = db.get_parsed_file_by_rel_path("test_file.txt").unwrap().unwrap();
+        assert_eq!(parsed_file.chunking_strategy, Some(strategy));
+        let chunks = db.get_chunks_for_parsed_file(parsed_file.id.unwrap()).unwrap();
+        assert!(chunks.len() > 1, "Expected several chunks, found {}", chunks.len());
+        assert!(chunks.iter().all(|chunk| chunk.content.len() <= 200));
+
+        dir.close().unwrap();
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_save_and_process_file() {
@@ -921,6 +990,7 @@ mod tests {
             &db,
             FileProcessingMode::Auto,
             &generator,
+            None,
         )
         .await;
 
@@ -973,6 +1043,7 @@ mod tests {
             &db,
             FileProcessingMode::Auto,
             &generator,
+            None,
         )
         .await;
 
@@ -247,6 +247,7 @@ mod tests {
             tags: None,
             total_tokens: None,
             total_characters: None,
+            chunking_strategy: None,
         }
     }
 
@@ -299,6 +300,7 @@ mod tests {
             parsed_file_id: parsed_file.id.unwrap(),
             position: 1,
             content: "This is a test chunk.".to_string(),
+            metadata: None,
         };
         sqlite_manager.create_chunk_with_embedding(&chunk, None).unwrap();
 
@@ -396,6 +398,7 @@ mod tests {
             &sqlite_manager,
             FileProcessingMode::Auto,
             &mock_generator,
+            None,
         )
         .await;
 
@@ -483,6 +486,7 @@ mod tests {
             parsed_file_id: parsed_file.id.unwrap(),
             position: 1,
             content: "This is a test chunk.".to_string(),
+            metadata: None,
         };
         sqlite_manager.create_chunk_with_embedding(&chunk, None).unwrap();
 
@@ -605,6 +609,7 @@ mod tests {
             parsed_file_id: parsed_file1.id.unwrap(),
             position: 1,
             content: "This is a test chunk for file 1.".to_string(),
+            metadata: None,
         };
         sqlite_manager.create_chunk_with_embedding(&chunk1, None).unwrap();
 
@@ -613,6 +618,7 @@ mod tests {
             parsed_file_id: parsed_file2.id.unwrap(),
             position: 1,
             content: "This is a test chunk for file 2.".to_string(),
+            metadata: None,
         };
         sqlite_manager.create_chunk_with_embedding(&chunk2, None).unwrap();
 
@@ -51,6 +51,8 @@ pub enum HanzoFsError {
     FailedMDParsing,
     #[error("Failed TXT parsing")]
     FailedTXTParsing,
+    #[error("Failed source code parsing")]
+    FailedCodeParsing,
     #[error("Failed XLSX parsing")]
     FailedXLSXParsing,
     #[error("Failed XLS parsing")]
@@ -1,3 +1,4 @@
+pub mod folder_sync;
 pub mod hanzo_file_manager;
 pub mod hanzo_file_manager_ops;
 pub mod hanzo_fs_error;
@@ -0,0 +1,368 @@
+use hanzo_embed::model_type::EmbeddingModelType;
+use hanzo_messages::schemas::hanzo_fs::ChunkingStrategy;
+
+use super::{file_parser_helper::HanzoFileParser, local_parsing::LocalFileParser, text_group::TextGroup};
+
+/// Cuts the text groups produced by a parser into the chunks that get embedded.
+/// Chunks keep the metadata of the groups their text comes from.
+pub trait Chunker: Send + Sync {
+    fn chunk(&self, text_groups: Vec<TextGroup>) -> Vec<TextGroup>;
+}
+
+/// Returns the chunker implementing `strategy`. `model_type` is the embedding model the chunks
+/// are for, whose tokenizer sizes token-budgeted chunks.
+pub fn chunker_for_strategy(strategy: &ChunkingStrategy, model_type: &EmbeddingModelType) -> Box<dyn Chunker> {
+    match strategy {
+        ChunkingStrategy::FixedSize { chunk_size } => Box::new(FixedSizeChunker {
+            chunk_size: *chunk_size,
+        }),
+        ChunkingStrategy::Sentence { chunk_size, overlap } => Box::new(SentenceChunker {
+            chunk_size: *chunk_size,
+            overlap: *overlap,
+        }),
+        ChunkingStrategy::Section { chunk_size } => Box::new(SectionChunker {
+            chunk_size: *chunk_size,
+        }),
+        ChunkingStrategy::TokenBudget { max_tokens } => Box::new(TokenBudgetChunker {
+            max_tokens: *max_tokens,
+            model_type: model_type.clone(),
+        }),
+    }
+}
+
+/// Chunks of up to `chunk_size` characters, cut at whitespace
+pub struct FixedSizeChunker {
+    pub chunk_size: usize,
+}
+
+impl Chunker for FixedSizeChunker {
+    fn chunk(&self, text_groups: Vec<TextGroup>) -> Vec<TextGroup> {
+        let pieces = split_into_pieces(&text_groups, |text| {
+            text.split_whitespace().map(|word| word.to_string()).collect()
+        });
+        let pieces = split_oversized_pieces(pieces, self.chunk_size, &str::len);
+        pack_pieces(&pieces, &text_groups, self.chunk_size, 0, None, &str::len)
+    }
+}
+
+/// Whole sentences packed into chunks of up to `chunk_size` characters, each chunk repeating
+/// the last `overlap` sentences of the previous one
+pub struct SentenceChunker {
+    pub chunk_size: usize,
+    pub overlap: usize,
+}
+
+impl Chunker for SentenceChunker {
+    fn chunk(&self, text_groups: Vec<TextGroup>) -> Vec<TextGroup> {
+        let pieces = split_into_pieces(&text_groups, LocalFileParser::process_into_sentences);
+        let pieces = split_oversized_pieces(pieces, self.chunk_size, &str::len);
+        pack_pieces(&pieces, &text_groups, self.chunk_size, self.overlap, None, &str::len)
+    }
+}
+
+/// Sentences packed into chunks of up to `chunk_size` characters that never span two sections.
+/// A section starts at every Markdown heading and at every PDF page; its chunks start with its heading.
+pub struct SectionChunker {
+    pub chunk_size: usize,
+}
+
+impl Chunker for SectionChunker {
+    fn chunk(&self, text_groups: Vec<TextGroup>) -> Vec<TextGroup> {
+        let heading_key = HanzoFileParser::heading_metadata_key();
+        let mut chunks = Vec::new();
+
+        // Each section is (heading group index, body group indices)
+        let mut sections: Vec<(Option<usize>, Vec<usize>)> = Vec::new();
+        let mut section_pages: Vec<u32> = Vec::new();
+        for (index, group) in text_groups.iter().enumerate() {
+            let pages = page_numbers(group);
+            let new_page = !pages.is_empty() && !section_pages.is_empty() && pages != section_pages;
+            if group.metadata.contains_key(&heading_key) {
+                sections.push((Some(index), Vec::new()));
+                section_pages = pages;
+            } else if sections.is_empty() || new_page {
+                sections.push((None, vec![index]));
+                section_pages = pages;
+            } else if let Some((_, body)) = sections.last_mut() {
+                body.push(index);
+                if section_pages.is_empty() {
+                    section_pages = pages;
+                }
+            }
+        }
+
+        for (heading, body) in sections {
+            let heading_text = heading.map(|index| text_groups[index].text.trim().to_string());
+            let body_groups: Vec<TextGroup> = body.iter().map(|index| text_groups[*index].clone()).collect();
+            let pieces = split_into_pieces(&body_groups, LocalFileParser::process_into_sentences);
+
+            if pieces.is_empty() {
+                // A heading with no text under it is a chunk of its own
+                if let Some(index) = heading {
+                    chunks.push(text_groups[index].clone());
+                }
+                continue;
+            }
+
+            let mut section_chunks = pack_pieces(
+                &split_oversized_pieces(pieces, self.chunk_size, &str::len),
+                &body_groups,
+                self.chunk_size,
+                0,
+                heading_text.as_deref(),
+                &str::len,
+            );
+            if let Some(index) = heading {
+                for chunk in &mut section_chunks {
+                    merge_metadata(chunk, &text_groups[index]);
+                }
+            }
+            chunks.extend(section_chunks);
+        }
+
+        chunks
+    }
+}
+
+/// Whole sentences packed into chunks of up to `max_tokens` tokens of the embedding model
+pub struct TokenBudgetChunker {
+    pub max_tokens: usize,
+    pub model_type: EmbeddingModelType,
+}
+
+impl Chunker for TokenBudgetChunker {
+    fn chunk(&self, text_groups: Vec<TextGroup>) -> Vec<TextGroup> {
+        let count_tokens = |text: &str| self.model_type.count_tokens(text);
+        let pieces = split_into_pieces(&text_groups, LocalFileParser::process_into_sentences);
+        let pieces = split_oversized_pieces(pieces, self.max_tokens, &count_tokens);
+        pack_pieces(&pieces, &text_groups, self.max_tokens, 0, None, &count_tokens)
+    }
+}
+
+/// Text that is never cut by the chunk packing, with the index of the group it comes from
+struct Piece {
+    text: String,
+    group: usize,
+}
+
+fn split_into_pieces(text_groups: &[TextGroup], split: impl Fn(String) -> Vec<String>) -> Vec<Piece> {
+    text_groups
+        .iter()
+        .enumerate()
+        .flat_map(|(group, text_group)| {
+            split(text_group.text.clone())
+                .into_iter()
+                .filter(|text| !text.trim().is_empty())
+                .map(move |text| Piece { text, group })
+        })
+        .collect()
+}
+
+/// Breaks pieces larger than `max_size` into words, and words larger than that at `max_size` characters
+fn split_oversized_pieces(pieces: Vec<Piece>, max_size: usize, size_of: &dyn Fn(&str) -> usize) -> Vec<Piece> {
+    let mut result = Vec::with_capacity(pieces.len());
+    for piece in pieces {
+        if size_of(&piece.text) <= max_size {
+            result.push(piece);
+            continue;
+        }
+        for word in piece.text.split_whitespace() {
+            let word_pieces = if size_of(word) > max_size {
+                HanzoFileParser::split_into_chunks(word, max_size.max(1))
+            } else {
+                vec![word.to_string()]
+            };
+            result.extend(word_pieces.into_iter().map(|text| Piece {
+                text,
+                group: piece.group,
+            }));
+        }
+    }
+    result
+}
+
+/// Packs consecutive pieces into chunks of up to `max_size`, as measured by `size_of`.
+/// Every chunk starts with `prefix` if given, and with up to `overlap` pieces of the previous chunk.
+/// Each piece is measured once: a chunk is as large as its prefix, pieces and the separators between them.
+fn pack_pieces(
+    pieces: &[Piece],
+    text_groups: &[TextGroup],
+    max_size: usize,
+    overlap: usize,
+    prefix: Option<&str>,
+    size_of: &dyn Fn(&str) -> usize,
+) -> Vec<TextGroup> {
+    let piece_sizes: Vec<usize> = pieces.iter().map(|piece| size_of(&piece.text)).collect();
+    let separator_size = size_of(" ");
+    let prefix_size = match prefix {
+        Some(prefix) if !prefix.is_empty() => size_of(prefix) + size_of("\n"),
+        _ => 0,
+    };
+    let chunk_size =
+        |pieces_size: usize, count: usize| prefix_size + pieces_size + separator_size * count.saturating_sub(1);
+
+    let mut chunks = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+    let mut current_size = 0;
+
+    for (index, size) in piece_sizes.iter().enumerate() {
+        if !current.is_empty() && chunk_size(current_size + size, current.len() + 1) > max_size {
+            chunks.push(build_chunk(pieces, &current, text_groups, prefix));
+
+            // Repeat the tail of the previous chunk, as long as the new piece still fits
+            let keep = overlap.min(current.len() - 1);
+            for dropped in current.drain(..current.len() - keep) {
+                current_size -= piece_sizes[dropped];
+            }
+            while !current.is_empty() && chunk_size(current_size + size, current.len() + 1) > max_size {
+                current_size -= piece_sizes[current.remove(0)];
+            }
+        }
+        current.push(index);
+        current_size += size;
+    }
+
+    if !current.is_empty() {
+        chunks.push(build_chunk(pieces, &current, text_groups, prefix));
+    }
+
+    chunks
+}
+
+fn chunk_text(pieces: &[Piece], indices: &[usize], prefix: Option<&str>) -> String {
+    let body = indices
+        .iter()
+        .map(|index| pieces[*index].text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    match prefix {
+        Some(prefix) if !prefix.is_empty() => format!("{}\n{}", prefix, body),
+        _ => body,
+    }
+}
+
+fn build_chunk(pieces: &[Piece], indices: &[usize], text_groups: &[TextGroup], prefix: Option<&str>) -> TextGroup {
+    let mut chunk = TextGroup::new_empty();
+    chunk.text = chunk_text(pieces, indices, prefix);
+
+    let mut groups: Vec<usize> = indices.iter().map(|index| pieces[*index].group).collect();
+    groups.dedup();
+    for group in groups {
+        merge_metadata(&mut chunk, &text_groups[group]);
+    }
+    chunk
+}
+
+/// Adds the metadata of `source` to `chunk`. Page numbers are merged, other keys keep their first value.
+fn merge_metadata(chunk: &mut TextGroup, source: &TextGroup) {
+    let page_numbers_key = HanzoFileParser::page_numbers_metadata_key();
+    for (key, value) in &source.metadata {
+        if *key != page_numbers_key {
+            chunk.metadata.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+    for page_number in page_numbers(source) {
+        chunk.push_page_number(page_number);
+    }
+}
+
+fn page_numbers(text_group: &TextGroup) -> Vec<u32> {
+    let mut page_numbers: Vec<u32> = text_group
+        .metadata
+        .get(&HanzoFileParser::page_numbers_metadata_key())
+        .map(|value| {
+            value
+                .trim_matches(|c| c == '[' || c == ']')
+                .split(',')
+                .filter_map(|n| n.trim().parse::<u32>().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    page_numbers.sort_unstable();
+    page_numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn text_group(text: &str, metadata: &[(String, &str)]) -> TextGroup {
+        let metadata = metadata
+            .iter()
+            .map(|(key, value)| (key.clone(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+        TextGroup::new(text.to_string(), metadata, None)
+    }
+
+    #[test]
+    fn test_fixed_size_chunks_cut_at_whitespace() {
+        let chunker = FixedSizeChunker { chunk_size: 12 };
+        let chunks = chunker.chunk(vec![text_group("one two three four five", &[])]);
+        let texts: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(texts, vec!["one two", "three four", "five"]);
+    }
+
+    #[test]
+    fn test_sentence_chunks_overlap() {
+        let chunker = SentenceChunker {
+            chunk_size: 40,
+            overlap: 1,
+        };
+        let chunks = chunker.chunk(vec![text_group(
+            "The first sentence. The second sentence. The third sentence.",
+            &[],
+        )]);
+        let texts: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "The first sentence. The second sentence.",
+                "The second sentence. The third sentence."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_section_chunks_do_not_span_headings_or_pages() {
+        let heading_key = HanzoFileParser::heading_metadata_key();
+        let pages_key = HanzoFileParser::page_numbers_metadata_key();
+        let chunker = SectionChunker { chunk_size: 100 };
+        let chunks = chunker.chunk(vec![
+            text_group("Intro", &[(heading_key.clone(), "1")]),
+            text_group("Short intro text.", &[]),
+            text_group("Usage", &[(heading_key, "2")]),
+            text_group("Run it on page one.", &[(pages_key.clone(), "[1]")]),
+            text_group("Then on page two.", &[(pages_key.clone(), "[2]")]),
+        ]);
+
+        let texts: Vec<&str> = chunks.iter().map(|chunk| chunk.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Intro\nShort intro text.",
+                "Usage\nRun it on page one.",
+                "Then on page two."
+            ]
+        );
+        assert_eq!(chunks[1].metadata.get(&pages_key), Some(&"[1]".to_string()));
+        assert_eq!(chunks[2].metadata.get(&pages_key), Some(&"[2]".to_string()));
+    }
+
+    #[test]
+    fn test_token_budget_chunks_fit_the_model() {
+        let model_type = EmbeddingModelType::default();
+        let chunker = TokenBudgetChunker {
+            max_tokens: 8,
+            model_type: model_type.clone(),
+        };
+        let chunks = chunker.chunk(vec![text_group(
+            "A short sentence. Another short sentence. And one more sentence here.",
+            &[],
+        )]);
+        assert!(chunks.len() > 1);
+        for chunk in chunks {
+            assert!(model_type.count_tokens(&chunk.text) <= 8, "{:?}", chunk.text);
+        }
+    }
+}
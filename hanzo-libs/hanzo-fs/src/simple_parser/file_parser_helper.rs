@@ -1,5 +1,6 @@
 use blake3::Hasher;
 use chrono::{TimeZone, Utc};
+use hanzo_messages::schemas::hanzo_fs::HanzoFileChunk;
 use regex::{Captures, Regex};
 use reqwest::Url;
 use std::collections::HashMap;
@@ -18,6 +19,26 @@ impl HanzoFileParser {
         "pg_nums".to_string()
     }
 
+    /// Key of heading level metadata, set on the text groups of Markdown headings
+    pub fn heading_metadata_key() -> String {
+        "heading".to_string()
+    }
+
+    /// Key of programming language metadata, set on the text groups of source code files
+    pub fn language_metadata_key() -> String {
+        HanzoFileChunk::LANGUAGE_METADATA_KEY.to_string()
+    }
+
+    /// Key of the symbol metadata (e.g. `fn main`), set on the text groups of source code files
+    pub fn symbol_metadata_key() -> String {
+        HanzoFileChunk::SYMBOL_METADATA_KEY.to_string()
+    }
+
+    /// Key of line range metadata (e.g. `10-42`), set on the text groups of source code files
+    pub fn line_range_metadata_key() -> String {
+        HanzoFileChunk::LINE_RANGE_METADATA_KEY.to_string()
+    }
+
     /// Key of datetime metadata
     pub fn datetime_metadata_key() -> String {
         "datetime".to_string()
@@ -0,0 +1,549 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use super::LocalFileParser;
+use crate::{
+    hanzo_fs_error::HanzoFsError,
+    simple_parser::{file_parser_helper::HanzoFileParser, text_group::TextGroup},
+};
+
+/// Programming languages of the source files the code parser understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    Java,
+    Kotlin,
+    CSharp,
+    C,
+    Cpp,
+    Ruby,
+    Php,
+    Swift,
+    Shell,
+}
+
+/// Words that look like a definition name to the loose C-like patterns but never are one
+const CONTROL_KEYWORDS: [&str; 8] = ["if", "else", "for", "while", "switch", "return", "catch", "sizeof"];
+
+impl SourceLanguage {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "rs" => Some(SourceLanguage::Rust),
+            "py" | "pyi" => Some(SourceLanguage::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(SourceLanguage::JavaScript),
+            "ts" | "tsx" | "mts" | "cts" => Some(SourceLanguage::TypeScript),
+            "go" => Some(SourceLanguage::Go),
+            "java" => Some(SourceLanguage::Java),
+            "kt" | "kts" => Some(SourceLanguage::Kotlin),
+            "cs" => Some(SourceLanguage::CSharp),
+            "c" | "h" => Some(SourceLanguage::C),
+            "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => Some(SourceLanguage::Cpp),
+            "rb" => Some(SourceLanguage::Ruby),
+            "php" => Some(SourceLanguage::Php),
+            "swift" => Some(SourceLanguage::Swift),
+            "sh" | "bash" | "zsh" => Some(SourceLanguage::Shell),
+            _ => None,
+        }
+    }
+
+    /// Name of the language, stored in the language metadata of the chunks
+    pub fn name(&self) -> &'static str {
+        match self {
+            SourceLanguage::Rust => "rust",
+            SourceLanguage::Python => "python",
+            SourceLanguage::JavaScript => "javascript",
+            SourceLanguage::TypeScript => "typescript",
+            SourceLanguage::Go => "go",
+            SourceLanguage::Java => "java",
+            SourceLanguage::Kotlin => "kotlin",
+            SourceLanguage::CSharp => "csharp",
+            SourceLanguage::C => "c",
+            SourceLanguage::Cpp => "cpp",
+            SourceLanguage::Ruby => "ruby",
+            SourceLanguage::Php => "php",
+            SourceLanguage::Swift => "swift",
+            SourceLanguage::Shell => "shell",
+        }
+    }
+
+    /// Patterns matching the (unindented) first line of a definition, paired with the kind of definition
+    /// they find. Every pattern captures the `name` of the definition, and may capture a more precise `kind`.
+    fn definition_patterns(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            SourceLanguage::Rust => &[
+                (
+                    "fn",
+                    r#"^(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|async|unsafe|default|extern\s+"[^"]*")\s+)*(?P<kind>fn|struct|enum|union|trait|mod|impl)\b(?:<[^{]*?>)?\s*(?P<name>[^{;(=]+)"#,
+                ),
+                ("macro", r"^(?P<kind>macro_rules)!\s*(?P<name>\w+)"),
+            ],
+            SourceLanguage::Python => &[("def", r"^(?:async\s+)?(?P<kind>def|class)\s+(?P<name>\w+)")],
+            SourceLanguage::JavaScript | SourceLanguage::TypeScript => &[
+                (
+                    "function",
+                    r"^(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(?P<kind>function|class|interface|enum|namespace)\*?\s+(?P<name>[\w$]+)",
+                ),
+                (
+                    "const",
+                    r"^(?:export\s+)?(?P<kind>const|let)\s+(?P<name>[\w$]+)\s*(?::[^=]*)?=\s*(?:async\s+)?(?:function\b|\(|[\w$]+\s*=>)",
+                ),
+            ],
+            SourceLanguage::Go => &[("func", r"^(?P<kind>func|type)\s+(?P<name>(?:\([^)]*\)\s*)?\w+)")],
+            SourceLanguage::Java => &[
+                (
+                    "class",
+                    r"^(?:(?:public|protected|private|static|final|abstract|sealed|non-sealed|strictfp)\s+)*(?P<kind>class|interface|enum|record)\s+(?P<name>\w+)",
+                ),
+                (
+                    "method",
+                    r"^(?:(?:public|protected|private|static|final|abstract|synchronized|native|default)\s+)+(?:<[^>]+>\s+)?[\w<>\[\],.? ]+?\s+(?P<name>\w+)\s*\(",
+                ),
+            ],
+            SourceLanguage::Kotlin => &[(
+                "fun",
+                r"^(?:(?:public|private|protected|internal|open|abstract|override|suspend|inline|data|sealed|enum|inner|annotation|companion)\s+)*(?P<kind>fun|class|interface|object)\s+(?:<[^>]*>\s*)?(?:[\w.]+\.)?(?P<name>\w+)",
+            )],
+            SourceLanguage::CSharp => &[
+                (
+                    "class",
+                    r"^(?:(?:public|private|protected|internal|static|sealed|abstract|partial|readonly|ref|unsafe)\s+)*(?P<kind>class|interface|struct|enum|record|namespace)\s+(?P<name>[\w.]+)",
+                ),
+                (
+                    "method",
+                    r"^(?:(?:public|private|protected|internal|static|virtual|override|abstract|async|sealed|extern|unsafe|new)\s+)+[\w<>\[\],.? ]+?\s+(?P<name>\w+)\s*\(",
+                ),
+            ],
+            SourceLanguage::C | SourceLanguage::Cpp => &[
+                (
+                    "struct",
+                    r"^(?:typedef\s+)?(?:template\s*<[^>]*>\s*)?(?P<kind>class|struct|union|enum|namespace)\s+(?P<name>[\w:]+)[^;]*$",
+                ),
+                (
+                    "function",
+                    r"^[\w:<>,*&\s]*?[\w>*&]\s*[\s*&]\s*(?P<name>[\w:~]+)\s*\([^;]*$",
+                ),
+            ],
+            SourceLanguage::Ruby => &[("def", r"^(?P<kind>def|class|module)\s+(?P<name>[\w.:?!=]+)")],
+            SourceLanguage::Php => &[(
+                "function",
+                r"^(?:(?:abstract|final|public|private|protected|static|readonly)\s+)*(?P<kind>function|class|interface|trait|enum)\s+&?(?P<name>\w+)",
+            )],
+            SourceLanguage::Swift => &[(
+                "func",
+                r"^(?:@\w+\s+)*(?:(?:public|private|fileprivate|internal|open|final|static|class|override|mutating|convenience|required)\s+)*(?P<kind>func|class|struct|enum|protocol|extension|actor)\s+(?P<name>[\w.]+)",
+            )],
+            SourceLanguage::Shell => &[
+                ("function", r"^function\s+(?P<name>[\w:-]+)"),
+                ("function", r"^(?P<name>[\w:-]+)\s*\(\)"),
+            ],
+        }
+    }
+
+    /// Prefixes of the lines that belong to the definition written right below them:
+    /// doc comments, attributes, annotations and decorators.
+    fn leading_line_prefixes(&self) -> &'static [&'static str] {
+        match self {
+            SourceLanguage::Rust => &["//", "#[", "/*", "*"],
+            SourceLanguage::Python | SourceLanguage::Ruby | SourceLanguage::Shell => &["#", "@"],
+            SourceLanguage::Go => &["//"],
+            SourceLanguage::CSharp => &["//", "/*", "*", "["],
+            SourceLanguage::C | SourceLanguage::Cpp => &["//", "/*", "*", "template"],
+            SourceLanguage::Php => &["//", "/*", "*", "#["],
+            SourceLanguage::JavaScript
+            | SourceLanguage::TypeScript
+            | SourceLanguage::Java
+            | SourceLanguage::Kotlin
+            | SourceLanguage::Swift => &["//", "/*", "*", "@"],
+        }
+    }
+}
+
+/// Finds the definitions of a source language in lines of code
+struct DefinitionMatcher {
+    language: SourceLanguage,
+    patterns: Vec<(&'static str, Regex)>,
+}
+
+impl DefinitionMatcher {
+    fn new(language: SourceLanguage) -> Result<Self, HanzoFsError> {
+        let patterns = language
+            .definition_patterns()
+            .iter()
+            .map(|(kind, pattern)| {
+                Regex::new(pattern)
+                    .map(|regex| (*kind, regex))
+                    .map_err(|_| HanzoFsError::FailedCodeParsing)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(DefinitionMatcher { language, patterns })
+    }
+
+    /// The symbol (e.g. `fn main` or `class Parser`) defined by the line, if it starts a definition
+    fn symbol_of(&self, line: &str) -> Option<String> {
+        let trimmed = line.trim();
+        for (kind, regex) in &self.patterns {
+            let Some(captures) = regex.captures(trimmed) else {
+                continue;
+            };
+            let Some(name) = captures.name("name") else {
+                continue;
+            };
+            let name = name.as_str();
+            let name = name.split(" where ").next().unwrap_or(name).trim();
+            if name.is_empty() || CONTROL_KEYWORDS.contains(&name) {
+                continue;
+            }
+            let kind = captures.name("kind").map(|kind| kind.as_str()).unwrap_or(kind);
+            return Some(format!("{} {}", kind, name));
+        }
+        None
+    }
+
+    /// Whether the line belongs to the definition right below it
+    fn is_leading_line(&self, line: &str) -> bool {
+        let trimmed = line.trim_start();
+        self.language
+            .leading_line_prefixes()
+            .iter()
+            .any(|prefix| trimmed.starts_with(prefix))
+    }
+}
+
+/// A range of lines of a source file, end excluded, with the symbol it defines
+#[derive(Debug, Clone, PartialEq)]
+struct CodeSegment {
+    start: usize,
+    end: usize,
+    symbol: Option<String>,
+}
+
+impl LocalFileParser {
+    /// Attempts to process the provided source code file into a list of TextGroups, one per top-level
+    /// definition. Definitions larger than `max_node_text_size` are split at their nested definitions
+    /// (methods of a class or impl block), then by lines. Every TextGroup holds the language, the symbol
+    /// and the 1-based line range it covers in its metadata.
+    pub fn process_code_file(
+        file_buffer: Vec<u8>,
+        language: SourceLanguage,
+        max_node_text_size: u64,
+    ) -> Result<Vec<TextGroup>, HanzoFsError> {
+        let source = String::from_utf8(file_buffer).map_err(|_| HanzoFsError::FailedCodeParsing)?;
+        let lines: Vec<&str> = source.lines().collect();
+        let matcher = DefinitionMatcher::new(language)?;
+        let max_size = (max_node_text_size as usize).max(1);
+
+        let mut text_groups = Vec::new();
+        for segment in Self::split_code_segments(&lines, &matcher, 0, lines.len(), None, true) {
+            Self::push_code_segment(&lines, &matcher, segment, max_size, &mut text_groups);
+        }
+
+        Ok(text_groups)
+    }
+
+    /// Splits the lines `start..end` at the definitions found in them. Top-level definitions are the ones
+    /// without indentation, nested ones are the least indented definitions after the first line.
+    fn split_code_segments(
+        lines: &[&str],
+        matcher: &DefinitionMatcher,
+        start: usize,
+        end: usize,
+        parent_symbol: Option<&str>,
+        top_level: bool,
+    ) -> Vec<CodeSegment> {
+        // Nested definitions are searched after the line defining the segment itself
+        let search_start = if top_level {
+            start
+        } else {
+            (start..end)
+                .find(|index| matcher.symbol_of(lines[*index]).is_some())
+                .map_or(start + 1, |index| index + 1)
+        };
+        let mut definitions: Vec<(usize, usize, String)> = (search_start..end)
+            .filter_map(|index| {
+                let line = lines[index];
+                let indent = line.len() - line.trim_start().len();
+                if top_level && indent > 0 {
+                    return None;
+                }
+                matcher.symbol_of(line).map(|symbol| (index, indent, symbol))
+            })
+            .collect();
+        if let Some(min_indent) = definitions.iter().map(|(_, indent, _)| *indent).min() {
+            definitions.retain(|(_, indent, _)| *indent == min_indent);
+        }
+
+        let symbol_for = |symbol: &str| match parent_symbol {
+            Some(parent) => format!("{} > {}", parent, symbol),
+            None => symbol.to_string(),
+        };
+
+        let mut segments = Vec::new();
+        let mut segment_start = start;
+        let mut segment_symbol = parent_symbol.map(|symbol| symbol.to_string());
+        let mut previous_definition = None;
+        for (index, _, symbol) in definitions {
+            // Doc comments, attributes and decorators go with the definition they describe
+            let floor = previous_definition.map(|line| line + 1).unwrap_or(segment_start);
+            let mut definition_start = index;
+            while definition_start > floor.max(search_start) && matcher.is_leading_line(lines[definition_start - 1]) {
+                definition_start -= 1;
+            }
+
+            segments.push(CodeSegment {
+                start: segment_start,
+                end: definition_start,
+                symbol: segment_symbol,
+            });
+            segment_start = definition_start;
+            segment_symbol = Some(symbol_for(&symbol));
+            previous_definition = Some(index);
+        }
+        segments.push(CodeSegment {
+            start: segment_start,
+            end,
+            symbol: segment_symbol,
+        });
+
+        segments
+            .into_iter()
+            .filter_map(|segment| Self::trim_blank_lines(lines, segment))
+            .collect()
+    }
+
+    /// Removes the blank lines around a segment, or drops it if it has no code
+    fn trim_blank_lines(lines: &[&str], mut segment: CodeSegment) -> Option<CodeSegment> {
+        while segment.start < segment.end && lines[segment.start].trim().is_empty() {
+            segment.start += 1;
+        }
+        while segment.end > segment.start && lines[segment.end - 1].trim().is_empty() {
+            segment.end -= 1;
+        }
+        (segment.start < segment.end).then_some(segment)
+    }
+
+    /// Turns a segment into TextGroups, splitting it first if it is larger than `max_size`
+    fn push_code_segment(
+        lines: &[&str],
+        matcher: &DefinitionMatcher,
+        segment: CodeSegment,
+        max_size: usize,
+        text_groups: &mut Vec<TextGroup>,
+    ) {
+        let text = lines[segment.start..segment.end].join("\n");
+        if text.len() <= max_size {
+            text_groups.push(Self::code_text_group(
+                text,
+                matcher.language,
+                segment.symbol.as_deref(),
+                segment.start,
+                segment.end,
+            ));
+            return;
+        }
+
+        let nested = Self::split_code_segments(
+            lines,
+            matcher,
+            segment.start,
+            segment.end,
+            segment.symbol.as_deref(),
+            false,
+        );
+        if nested.len() > 1 {
+            for nested_segment in nested {
+                Self::push_code_segment(lines, matcher, nested_segment, max_size, text_groups);
+            }
+            return;
+        }
+
+        // No nested definitions to split at, pack as many lines as possible in each TextGroup
+        let mut current_text = String::new();
+        let mut current_start = segment.start;
+        for (index, line) in lines.iter().enumerate().take(segment.end).skip(segment.start) {
+            if !current_text.is_empty() && current_text.len() + 1 + line.len() > max_size {
+                text_groups.push(Self::code_text_group(
+                    std::mem::take(&mut current_text),
+                    matcher.language,
+                    segment.symbol.as_deref(),
+                    current_start,
+                    index,
+                ));
+            }
+            if current_text.is_empty() {
+                current_start = index;
+            }
+
+            if line.len() > max_size {
+                for part in Self::split_long_line(line, max_size) {
+                    text_groups.push(Self::code_text_group(
+                        part,
+                        matcher.language,
+                        segment.symbol.as_deref(),
+                        index,
+                        index + 1,
+                    ));
+                }
+                continue;
+            }
+
+            if !current_text.is_empty() {
+                current_text.push('\n');
+            }
+            current_text.push_str(line);
+        }
+        if !current_text.trim().is_empty() {
+            text_groups.push(Self::code_text_group(
+                current_text,
+                matcher.language,
+                segment.symbol.as_deref(),
+                current_start,
+                segment.end,
+            ));
+        }
+    }
+
+    /// Splits a line longer than `max_size` (e.g. minified code) on char boundaries
+    fn split_long_line(line: &str, max_size: usize) -> Vec<String> {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+        for c in line.chars() {
+            if !current.is_empty() && current.len() + c.len_utf8() > max_size {
+                parts.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        if !current.is_empty() {
+            parts.push(current);
+        }
+        parts
+    }
+
+    /// Creates the TextGroup of the lines `start..end` (0-based, end excluded)
+    fn code_text_group(
+        text: String,
+        language: SourceLanguage,
+        symbol: Option<&str>,
+        start: usize,
+        end: usize,
+    ) -> TextGroup {
+        let mut metadata = HashMap::new();
+        metadata.insert(HanzoFileParser::language_metadata_key(), language.name().to_string());
+        if let Some(symbol) = symbol {
+            metadata.insert(HanzoFileParser::symbol_metadata_key(), symbol.to_string());
+        }
+        metadata.insert(
+            HanzoFileParser::line_range_metadata_key(),
+            format!("{}-{}", start + 1, end.max(start + 1)),
+        );
+        TextGroup::new(text, metadata, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata_of(text_group: &TextGroup) -> (Option<&str>, &str) {
+        (
+            text_group
+                .metadata
+                .get(&HanzoFileParser::symbol_metadata_key())
+                .map(|symbol| symbol.as_str()),
+            text_group.metadata[&HanzoFileParser::line_range_metadata_key()].as_str(),
+        )
+    }
+
+    #[test]
+    fn test_process_rust_file_by_definition() {
+        let source = r#"use std::fmt;
+
+/// A point
+#[derive(Debug)]
+pub struct Point {
+    x: i32,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.x)
+    }
+}
+
+pub(crate) async fn run() {}
+"#;
+
+        let text_groups = LocalFileParser::process_code_file(source.into(), SourceLanguage::Rust, 1024).unwrap();
+
+        let metadata: Vec<_> = text_groups.iter().map(metadata_of).collect();
+        assert_eq!(
+            metadata,
+            vec![
+                (None, "1-1"),
+                (Some("struct Point"), "3-7"),
+                (Some("impl fmt::Display for Point"), "9-13"),
+                (Some("fn run"), "15-15"),
+            ]
+        );
+        assert!(text_groups[1].text.starts_with("/// A point\n#[derive(Debug)]"));
+        assert_eq!(
+            text_groups[0].metadata[&HanzoFileParser::language_metadata_key()],
+            "rust"
+        );
+    }
+
+    #[test]
+    fn test_process_large_python_class_by_method() {
+        let source = r#"import os
+
+
+class Store:
+    """Keeps values"""
+
+    def get(self, key):
+        return self.values.get(key)
+
+    @property
+    def size(self):
+        return len(self.values)
+"#;
+
+        let text_groups = LocalFileParser::process_code_file(source.into(), SourceLanguage::Python, 80).unwrap();
+
+        let metadata: Vec<_> = text_groups.iter().map(metadata_of).collect();
+        assert_eq!(
+            metadata,
+            vec![
+                (None, "1-1"),
+                (Some("class Store"), "4-5"),
+                (Some("class Store > def get"), "7-8"),
+                (Some("class Store > def size"), "10-12"),
+            ]
+        );
+        assert!(text_groups[3].text.trim_start().starts_with("@property"));
+    }
+
+    #[test]
+    fn test_process_code_file_without_definitions_by_lines() {
+        let source = "echo one\necho two\necho three\n";
+
+        let text_groups = LocalFileParser::process_code_file(source.into(), SourceLanguage::Shell, 18).unwrap();
+
+        let metadata: Vec<_> = text_groups.iter().map(metadata_of).collect();
+        assert_eq!(metadata, vec![(None, "1-2"), (None, "3-3")]);
+        assert_eq!(text_groups[0].text, "echo one\necho two");
+    }
+
+    #[test]
+    fn test_source_language_from_extension() {
+        assert_eq!(SourceLanguage::from_extension("rs"), Some(SourceLanguage::Rust));
+        assert_eq!(SourceLanguage::from_extension("TSX"), Some(SourceLanguage::TypeScript));
+        assert_eq!(SourceLanguage::from_extension("go"), Some(SourceLanguage::Go));
+        assert_eq!(SourceLanguage::from_extension("md"), None);
+    }
+}
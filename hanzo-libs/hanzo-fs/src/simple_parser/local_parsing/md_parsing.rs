@@ -98,6 +98,7 @@ impl LocalFileParser {
 
                     // Create a new text group for the heading
                     // Upcoming content will be added to its subgroups
+                    let heading_start = text_groups.len();
                     HanzoFileParser::push_text_group_by_depth(
                         &mut text_groups,
                         heading_depth,
@@ -105,6 +106,12 @@ impl LocalFileParser {
                         max_node_text_size,
                         None,
                     );
+                    // Mark the heading so section-aware chunking can tell where sections start
+                    for heading_group in &mut text_groups[heading_start..] {
+                        heading_group
+                            .metadata
+                            .insert(HanzoFileParser::heading_metadata_key(), level.to_string());
+                    }
                 }
                 NodeValue::Paragraph => {
                     current_text.push_str(text);
@@ -1,3 +1,4 @@
+pub mod code_parsing;
 pub mod csv_parsing;
 pub mod docx_parsing;
 pub mod html_parsing;
@@ -1,3 +1,4 @@
+pub mod chunking;
 pub mod file_parser_grouping;
 pub mod file_parser_helper;
 pub mod local_parsing;
@@ -19,7 +19,10 @@ use std::{
     path::{self, PathBuf},
 };
 
-use super::{local_parsing::LocalFileParser, text_group::TextGroup};
+use super::{
+    local_parsing::{code_parsing::SourceLanguage, LocalFileParser},
+    text_group::TextGroup,
+};
 
 pub struct SimpleParser;
 
@@ -34,6 +37,7 @@ enum SupportedFileType {
     Xlsx,
     Xls,
     Docx,
+    Code(SourceLanguage),
 }
 
 impl SupportedFileType {
@@ -48,7 +52,7 @@ impl SupportedFileType {
             "xlsx" => Some(SupportedFileType::Xlsx),
             "xls" => Some(SupportedFileType::Xls),
             "docx" => Some(SupportedFileType::Docx),
-            _ => None,
+            _ => SourceLanguage::from_extension(extension).map(SupportedFileType::Code),
         }
     }
 }
@@ -65,12 +69,21 @@ impl fmt::Display for SupportedFileType {
             SupportedFileType::Xlsx => "xlsx",
             SupportedFileType::Xls => "xls",
             SupportedFileType::Docx => "docx",
+            SupportedFileType::Code(language) => language.name(),
         };
         write!(f, "{}", file_type_str)
     }
 }
 
 impl SimpleParser {
+    /// Whether the file is source code, parsed into one chunk per definition
+    pub fn is_source_code_file(filepath: &HanzoPath) -> bool {
+        matches!(
+            filepath.extension().and_then(SupportedFileType::from_extension),
+            Some(SupportedFileType::Code(_))
+        )
+    }
+
     pub async fn parse_file(filepath: HanzoPath, max_node_text_size: u64) -> Result<Vec<TextGroup>, HanzoFsError> {
         // check if file exists
         if !filepath.exists() {
@@ -119,6 +132,9 @@ impl SimpleParser {
             SupportedFileType::Xls => {
                 LocalFileParser::process_xlsx_file(file_path.as_path().to_path_buf(), max_node_text_size).await
             }
+            SupportedFileType::Code(language) => {
+                LocalFileParser::process_code_file(file_buffer, language, max_node_text_size)
+            }
         }
     }
 }
@@ -128,6 +144,7 @@ mod tests {
     use crate::test_utils::testing_create_tempdir_and_set_env_var;
 
     use super::*;
+    use crate::simple_parser::file_parser_helper::HanzoFileParser;
     use std::fs;
     use std::io::Write;
 
@@ -151,6 +168,23 @@ mod tests {
         assert!(!text_groups.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_parse_source_code_file() {
+        let _dir = testing_create_tempdir_and_set_env_var();
+
+        let hanzo_path = HanzoPath::from_string("main.go".to_string());
+        let mut file = fs::File::create(&hanzo_path.as_path()).unwrap();
+        writeln!(file, "package main\n\nfunc main() {{\n\tprintln(1)\n}}").unwrap();
+
+        assert!(SimpleParser::is_source_code_file(&hanzo_path));
+        let text_groups = SimpleParser::parse_file(hanzo_path, 1024).await.unwrap();
+
+        let symbol = &text_groups[1].metadata[&HanzoFileParser::symbol_metadata_key()];
+        let lines = &text_groups[1].metadata[&HanzoFileParser::line_range_metadata_key()];
+        assert_eq!(symbol, "func main");
+        assert_eq!(lines, "3-5");
+    }
+
     #[tokio::test]
     async fn test_parse_large_csv_file() {
         let _dir = testing_create_tempdir_and_set_env_var();
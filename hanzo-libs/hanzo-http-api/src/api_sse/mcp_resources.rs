@@ -0,0 +1,332 @@
+use hanzo_messages::schemas::custom_prompt::CustomPrompt;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rmcp::{
+    model::{
+        AnnotateAble, GetPromptResult, Prompt, PromptArgument, PromptMessage, PromptMessageRole, RawResource,
+        Resource, ResourceUpdatedNotificationParam,
+    },
+    service::Peer,
+    RoleServer,
+};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::broadcast;
+
+/// Scheme used for VecFS items published as MCP resources, e.g. `hanzo://vecfs/docs/readme.md`
+pub const VECFS_RESOURCE_URI_PREFIX: &str = "hanzo://vecfs/";
+
+static PROMPT_ARGUMENT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap());
+
+/// Changes the node reports so connected MCP clients can be notified.
+#[derive(Debug, Clone, PartialEq)]
+pub enum McpServerEvent {
+    /// The content of a VecFS item changed. Holds the VecFS path.
+    ResourceUpdated(String),
+    /// VecFS items were created, moved or removed.
+    ResourceListChanged,
+    /// Custom prompts were added, updated or removed.
+    PromptListChanged,
+}
+
+pub static MCP_SERVER_EVENTS: Lazy<broadcast::Sender<McpServerEvent>> = Lazy::new(|| broadcast::channel(256).0);
+
+/// Notifies MCP clients subscribed to `path` that its content changed.
+pub fn notify_mcp_resource_updated(path: &str) {
+    let _ = MCP_SERVER_EVENTS.send(McpServerEvent::ResourceUpdated(path.to_string()));
+}
+
+/// Notifies MCP clients that the VecFS tree changed.
+pub fn notify_mcp_resource_list_changed() {
+    let _ = MCP_SERVER_EVENTS.send(McpServerEvent::ResourceListChanged);
+}
+
+/// Notifies MCP clients that the prompt library changed.
+pub fn notify_mcp_prompt_list_changed() {
+    let _ = MCP_SERVER_EVENTS.send(McpServerEvent::PromptListChanged);
+}
+
+pub fn vecfs_path_to_resource_uri(path: &str) -> String {
+    format!("{}{}", VECFS_RESOURCE_URI_PREFIX, path.trim_start_matches('/'))
+}
+
+/// Returns the VecFS path for a resource URI, or None if the URI is not a VecFS resource
+/// or tries to escape the VecFS root.
+pub fn resource_uri_to_vecfs_path(uri: &str) -> Option<String> {
+    let path = uri.strip_prefix(VECFS_RESOURCE_URI_PREFIX)?;
+    if path.split('/').any(|component| component == "..") {
+        return None;
+    }
+    Some(format!("/{}", path.trim_start_matches('/')))
+}
+
+/// Flattens a VecFS listing (as returned by `list_directory_contents_with_depth`) into MCP resources.
+pub fn vecfs_listing_to_resources(listing: &Value) -> Vec<Resource> {
+    let mut resources = Vec::new();
+    collect_resources(listing, &mut resources);
+    resources
+}
+
+fn collect_resources(listing: &Value, resources: &mut Vec<Resource>) {
+    let Some(entries) = listing.as_array() else {
+        return;
+    };
+    for entry in entries {
+        let (Some(path), Some(name)) = (
+            entry.get("path").and_then(Value::as_str),
+            entry.get("name").and_then(Value::as_str),
+        ) else {
+            continue;
+        };
+        let is_directory = entry.get("is_directory").and_then(Value::as_bool).unwrap_or(false);
+
+        let mut resource = RawResource::new(vecfs_path_to_resource_uri(path), name.to_string());
+        if is_directory {
+            resource.description = Some(format!("VecFS folder /{}", path.trim_start_matches('/')));
+            resource.mime_type = Some("inode/directory".to_string());
+        } else {
+            resource.description = Some(format!("VecFS file /{}", path.trim_start_matches('/')));
+            resource.mime_type = Some(guess_mime_type(name).to_string());
+            resource.size = entry.get("size").and_then(Value::as_u64).map(|s| s as u32);
+        }
+        resources.push(resource.no_annotation());
+
+        if let Some(children) = entry.get("children") {
+            collect_resources(children, resources);
+        }
+    }
+}
+
+pub fn guess_mime_type(file_name: &str) -> &'static str {
+    let extension = file_name.rsplit('.').next().unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "json" => "application/json",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "pdf" => "application/pdf",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "xls" => "application/vnd.ms-excel",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+/// MCP prompt names follow the same sanitizing as tool names.
+pub fn prompt_mcp_name(prompt: &CustomPrompt) -> String {
+    prompt
+        .name
+        .replace(' ', "_")
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || *c == '_')
+        .collect()
+}
+
+/// Arguments of a prompt are the `{{placeholder}}` names found in its text, in order of appearance.
+pub fn prompt_arguments(prompt_text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    PROMPT_ARGUMENT_REGEX
+        .captures_iter(prompt_text)
+        .map(|c| c[1].to_string())
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+pub fn custom_prompt_to_mcp_prompt(prompt: &CustomPrompt) -> Prompt {
+    let arguments: Vec<PromptArgument> = prompt_arguments(&prompt.prompt)
+        .into_iter()
+        .map(|name| PromptArgument {
+            description: Some(format!("Value for {{{{{}}}}}", name)),
+            name,
+            title: None,
+            required: Some(true),
+        })
+        .collect();
+    Prompt {
+        name: prompt_mcp_name(prompt),
+        title: Some(prompt.name.clone()),
+        description: Some(prompt.name.clone()),
+        arguments: if arguments.is_empty() { None } else { Some(arguments) },
+        icons: None,
+    }
+}
+
+/// Fills the `{{placeholder}}` arguments of a prompt. Missing arguments are reported by name.
+pub fn render_custom_prompt(
+    prompt: &CustomPrompt,
+    arguments: Option<&Map<String, Value>>,
+) -> Result<GetPromptResult, Vec<String>> {
+    let missing: Vec<String> = prompt_arguments(&prompt.prompt)
+        .into_iter()
+        .filter(|name| arguments.and_then(|args| args.get(name)).is_none())
+        .collect();
+    if !missing.is_empty() {
+        return Err(missing);
+    }
+
+    let text = PROMPT_ARGUMENT_REGEX.replace_all(&prompt.prompt, |caps: &regex::Captures| {
+        match arguments.and_then(|args| args.get(&caps[1])) {
+            Some(Value::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+            None => String::new(),
+        }
+    });
+
+    Ok(GetPromptResult {
+        description: Some(prompt.name.clone()),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text.to_string())],
+    })
+}
+
+/// Per-connection state: the client's peer and the resource URIs it subscribed to.
+pub struct McpClientSession {
+    peer: Peer<RoleServer>,
+    subscriptions: Mutex<HashSet<String>>,
+}
+
+/// Connected MCP clients, used to fan out resource and prompt notifications.
+#[derive(Default)]
+pub struct McpClientRegistry {
+    sessions: Mutex<Vec<Weak<McpClientSession>>>,
+}
+
+impl McpClientRegistry {
+    pub fn register(&self, peer: Peer<RoleServer>) -> Arc<McpClientSession> {
+        let session = Arc::new(McpClientSession {
+            peer,
+            subscriptions: Mutex::new(HashSet::new()),
+        });
+        self.sessions.lock().unwrap().push(Arc::downgrade(&session));
+        session
+    }
+
+    fn live_sessions(&self) -> Vec<Arc<McpClientSession>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|s| s.upgrade().map(|s| !s.peer.is_transport_closed()).unwrap_or(false));
+        sessions.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    pub async fn dispatch(&self, event: McpServerEvent) {
+        for session in self.live_sessions() {
+            let result = match &event {
+                McpServerEvent::ResourceUpdated(path) => {
+                    let uri = vecfs_path_to_resource_uri(path);
+                    if !session.is_subscribed(&uri) {
+                        continue;
+                    }
+                    session
+                        .peer
+                        .notify_resource_updated(ResourceUpdatedNotificationParam { uri })
+                        .await
+                }
+                McpServerEvent::ResourceListChanged => session.peer.notify_resource_list_changed().await,
+                McpServerEvent::PromptListChanged => session.peer.notify_prompt_list_changed().await,
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to send MCP notification {:?}: {:?}", event, e);
+            }
+        }
+    }
+}
+
+impl McpClientSession {
+    pub fn subscribe(&self, uri: String) {
+        self.subscriptions.lock().unwrap().insert(uri);
+    }
+
+    pub fn unsubscribe(&self, uri: &str) {
+        self.subscriptions.lock().unwrap().remove(uri);
+    }
+
+    /// Subscribing to a folder also covers everything below it.
+    pub fn is_subscribed(&self, uri: &str) -> bool {
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|s| s == uri || uri.starts_with(&format!("{}/", s.trim_end_matches('/'))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn prompt(text: &str) -> CustomPrompt {
+        CustomPrompt {
+            rowid: Some(1),
+            name: "Summarize Doc".to_string(),
+            prompt: text.to_string(),
+            is_system: false,
+            is_enabled: true,
+            version: "1".to_string(),
+            is_favorite: false,
+        }
+    }
+
+    #[test]
+    fn test_prompt_arguments_are_unique_and_ordered() {
+        let args = prompt_arguments("Summarize {{doc}} in {{ language }}, keep {{doc}} short");
+        assert_eq!(args, vec!["doc".to_string(), "language".to_string()]);
+    }
+
+    #[test]
+    fn test_custom_prompt_to_mcp_prompt() {
+        let mcp_prompt = custom_prompt_to_mcp_prompt(&prompt("Summarize {{doc}}"));
+        assert_eq!(mcp_prompt.name, "summarize_doc");
+        let arguments = mcp_prompt.arguments.unwrap();
+        assert_eq!(arguments.len(), 1);
+        assert_eq!(arguments[0].name, "doc");
+    }
+
+    #[test]
+    fn test_render_custom_prompt() {
+        let args = json!({ "doc": "the report", "n": 3 });
+        let result = render_custom_prompt(&prompt("Summarize {{doc}} in {{n}} bullets"), args.as_object()).unwrap();
+        match &result.messages[0].content {
+            rmcp::model::PromptMessageContent::Text { text } => {
+                assert_eq!(text, "Summarize the report in 3 bullets")
+            }
+            _ => panic!("Expected text content"),
+        }
+
+        let missing = render_custom_prompt(&prompt("Summarize {{doc}}"), None).unwrap_err();
+        assert_eq!(missing, vec!["doc".to_string()]);
+    }
+
+    #[test]
+    fn test_resource_uri_round_trip() {
+        let uri = vecfs_path_to_resource_uri("docs/readme.md");
+        assert_eq!(uri, "hanzo://vecfs/docs/readme.md");
+        assert_eq!(resource_uri_to_vecfs_path(&uri), Some("/docs/readme.md".to_string()));
+        assert_eq!(resource_uri_to_vecfs_path("hanzo://vecfs/../secrets"), None);
+        assert_eq!(resource_uri_to_vecfs_path("file:///etc/passwd"), None);
+    }
+
+    #[test]
+    fn test_vecfs_listing_to_resources() {
+        let listing = json!([
+            {
+                "path": "docs",
+                "name": "docs",
+                "is_directory": true,
+                "children": [
+                    { "path": "docs/readme.md", "name": "readme.md", "is_directory": false, "size": 12 }
+                ]
+            }
+        ]);
+        let resources = vecfs_listing_to_resources(&listing);
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0].raw.uri, "hanzo://vecfs/docs");
+        assert_eq!(resources[0].raw.mime_type.as_deref(), Some("inode/directory"));
+        assert_eq!(resources[1].raw.uri, "hanzo://vecfs/docs/readme.md");
+        assert_eq!(resources[1].raw.mime_type.as_deref(), Some("text/markdown"));
+        assert_eq!(resources[1].raw.size, Some(12));
+    }
+}
@@ -1,13 +1,22 @@
+use crate::api_sse::mcp_resources::{
+    custom_prompt_to_mcp_prompt, prompt_mcp_name, render_custom_prompt, resource_uri_to_vecfs_path,
+    vecfs_listing_to_resources, McpClientRegistry, McpClientSession, MCP_SERVER_EVENTS,
+};
+use crate::api_v2::api_v2_handlers_x402::{check_x402_payment, settle_x402_payment, settlement_failed_response};
 use crate::node_commands::NodeCommand;
 use async_channel::Sender;
 use async_trait::async_trait;
+use base64::Engine;
+use hanzo_messages::schemas::custom_prompt::CustomPrompt;
+use hanzo_messages::schemas::x402_paywall::{PaymentRequiredResponse, PaywallDecision, PaywallResource};
 use once_cell::sync::Lazy;
 use rmcp::{
     model::ErrorData as McpError,
     model::{
-        CallToolRequestParam, CallToolResult, Content, ErrorData, Implementation, InitializeRequestParam,
-        InitializeResult, ListPromptsResult, ListResourcesResult, ListToolsResult, PaginatedRequestParam,
-        ProtocolVersion, ServerCapabilities, ServerInfo, Tool,
+        CallToolRequestParam, CallToolResult, Content, ErrorCode, ErrorData, GetPromptRequestParam, GetPromptResult,
+        Implementation, InitializeRequestParam, InitializeResult, ListPromptsResult, ListResourcesResult,
+        ListToolsResult, Meta, PaginatedRequestParam, ProtocolVersion, ReadResourceRequestParam, ReadResourceResult,
+        ResourceContents, ServerCapabilities, ServerInfo, SubscribeRequestParam, Tool, UnsubscribeRequestParam,
     },
     service::RequestContext,
     RoleServer, ServerHandler,
@@ -16,17 +25,29 @@ use serde_json::{Map, Value};
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::future::{self, Future};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, OnceLock, RwLock};
+use tokio::sync::broadcast::error::RecvError;
 
 // Singleton for the tools cache using once_cell::sync::Lazy
 pub static TOOLS_CACHE: Lazy<RwLock<Vec<Tool>>> = Lazy::new(|| RwLock::new(Vec::new()));
 // Singleton map from user-facing tool name to internal tool_router_key
 pub static TOOL_NAME_TO_KEY_MAP: Lazy<RwLock<HashMap<String, String>>> = Lazy::new(|| RwLock::new(HashMap::new()));
 
+// _meta keys carrying x402 payments for priced tools, mirroring the X-PAYMENT and X-PAYMENT-RESPONSE headers
+const X402_PAYMENT_META: &str = "x402/payment";
+const X402_PAYMENT_RESPONSE_META: &str = "x402/payment-response";
+// x402 answers with HTTP 402, reused as the MCP error code
+const PAYMENT_REQUIRED: ErrorCode = ErrorCode(402);
+
 #[derive(Clone)]
 pub struct McpToolsService {
     node_commands_sender: Sender<NodeCommand>,
     node_name: String,
+    // Shared by all connections, used to fan out resource and prompt notifications
+    clients: Arc<McpClientRegistry>,
+    // Per connection: every SSE session serves its own clone of the service, registered when
+    // the client initializes it. Clones made afterwards keep the registration.
+    client_session: OnceLock<Arc<McpClientSession>>,
 }
 
 impl McpToolsService {
@@ -34,6 +55,8 @@ impl McpToolsService {
         let service = Self {
             node_commands_sender,
             node_name,
+            clients: Arc::new(McpClientRegistry::default()),
+            client_session: OnceLock::new(),
         };
 
         // Spawn a task to update the cache
@@ -44,9 +67,71 @@ impl McpToolsService {
             }
         });
 
+        // Forward VecFS and prompt changes reported by the node to the connected clients
+        let clients = service.clients.clone();
+        let mut events = MCP_SERVER_EVENTS.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => clients.dispatch(event).await,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("MCP notification forwarder skipped {} events", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
         service
     }
 
+    fn client_session(&self, context: &RequestContext<RoleServer>) -> Arc<McpClientSession> {
+        self.client_session
+            .get_or_init(|| self.clients.register(context.peer.clone()))
+            .clone()
+    }
+
+    fn capabilities() -> ServerCapabilities {
+        ServerCapabilities::builder()
+            .enable_prompts()
+            .enable_prompts_list_changed()
+            .enable_resources()
+            .enable_resources_subscribe()
+            .enable_resources_list_changed()
+            .enable_tools()
+            .enable_tool_list_changed()
+            .build()
+    }
+
+    /// Fetch the enabled custom prompts through the node commands
+    async fn fetch_prompts(&self) -> Result<Vec<CustomPrompt>, McpError> {
+        let (tx, rx) = async_channel::bounded(1);
+        self.node_commands_sender
+            .send(NodeCommand::V2ApiListAllMcpPrompts { res: tx })
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to send list prompts command: {:?}", e), None))?;
+        rx.recv()
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to receive prompts response: {:?}", e), None))?
+            .map_err(|e| McpError::internal_error(format!("Failed to get prompts: {}", e.message), None))
+    }
+
+    /// The x402 payment of a tool call, either the base64 X-PAYMENT value or the payment payload itself
+    fn x402_payment(context: &RequestContext<RoleServer>) -> Option<String> {
+        match context.meta.get(X402_PAYMENT_META)? {
+            Value::String(payment) => Some(payment.clone()),
+            payment => Some(base64::engine::general_purpose::STANDARD.encode(payment.to_string())),
+        }
+    }
+
+    fn payment_required_error(response: &PaymentRequiredResponse) -> McpError {
+        McpError::new(
+            PAYMENT_REQUIRED,
+            format!("Payment required: {}", response.error),
+            serde_json::to_value(response).ok(),
+        )
+    }
+
     /// Get the current list of tools from the cache
     pub fn list_tools(&self) -> Vec<Tool> {
         TOOLS_CACHE.read().expect("Failed to read tools cache").clone()
@@ -221,10 +306,7 @@ impl ServerHandler for McpToolsService {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::default(),
-            capabilities: ServerCapabilities::builder()
-                .enable_tools()
-                .enable_tool_list_changed()
-                .build(),
+            capabilities: Self::capabilities(),
             server_info: Implementation {
                 name: "Hanzo MCP Server".to_string(),
                 version: "1.0.0".to_string(),
@@ -239,20 +321,20 @@ impl ServerHandler for McpToolsService {
     fn initialize(
         &self,
         param: InitializeRequestParam,
-        _ctx: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> impl Future<Output = Result<InitializeResult, ErrorData>> + Send + '_ {
         tracing::info!(
             "Handling initialize request with protocol version: {:?}",
             param.protocol_version
         );
 
+        // Register the client right away so it gets list_changed notifications from the start
+        self.client_session(&context);
+
         // Wrap existing logic in std::future::ready
         let result = InitializeResult {
             protocol_version: ProtocolVersion::default(),
-            capabilities: ServerCapabilities::builder()
-                .enable_tools()
-                .enable_tool_list_changed()
-                .build(),
+            capabilities: Self::capabilities(),
             server_info: Implementation {
                 name: "Hanzo MCP Server".to_string(),
                 version: "1.0.0".to_string(),
@@ -271,8 +353,33 @@ impl ServerHandler for McpToolsService {
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> impl Future<Output = Result<ListPromptsResult, ErrorData>> + Send + '_ {
-        // Use ErrorData and ListPromptsResult::default()
-        future::ready(Ok(ListPromptsResult::default()))
+        async move {
+            let prompts = self.fetch_prompts().await?;
+            Ok(ListPromptsResult {
+                prompts: prompts.iter().map(custom_prompt_to_mcp_prompt).collect(),
+                next_cursor: None,
+            })
+        }
+    }
+
+    fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<GetPromptResult, ErrorData>> + Send + '_ {
+        async move {
+            let prompts = self.fetch_prompts().await?;
+            let prompt = prompts
+                .iter()
+                .find(|p| prompt_mcp_name(p) == request.name)
+                .ok_or_else(|| McpError::invalid_params(format!("Prompt '{}' not found", request.name), None))?;
+            render_custom_prompt(prompt, request.arguments.as_ref()).map_err(|missing| {
+                McpError::invalid_params(
+                    format!("Missing arguments for prompt '{}': {}", request.name, missing.join(", ")),
+                    None,
+                )
+            })
+        }
     }
 
     fn list_resources(
@@ -280,15 +387,94 @@ impl ServerHandler for McpToolsService {
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> impl Future<Output = Result<ListResourcesResult, ErrorData>> + Send + '_ {
-        // Use ErrorData and ListResourcesResult::default()
-        future::ready(Ok(ListResourcesResult::default()))
+        async move {
+            let (tx, rx) = async_channel::bounded(1);
+            self.node_commands_sender
+                .send(NodeCommand::V2ApiListAllMcpResources { res: tx })
+                .await
+                .map_err(|e| {
+                    McpError::internal_error(format!("Failed to send list resources command: {:?}", e), None)
+                })?;
+            let listing = rx
+                .recv()
+                .await
+                .map_err(|e| McpError::internal_error(format!("Failed to receive resources response: {:?}", e), None))?
+                .map_err(|e| McpError::internal_error(format!("Failed to list resources: {}", e.message), None))?;
+            Ok(ListResourcesResult {
+                resources: vecfs_listing_to_resources(&listing),
+                next_cursor: None,
+            })
+        }
+    }
+
+    fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<ReadResourceResult, ErrorData>> + Send + '_ {
+        async move {
+            let path = resource_uri_to_vecfs_path(&request.uri)
+                .ok_or_else(|| McpError::resource_not_found(format!("Unknown resource '{}'", request.uri), None))?;
+            let (tx, rx) = async_channel::bounded(1);
+            self.node_commands_sender
+                .send(NodeCommand::V2ApiReadMcpResource { path, res: tx })
+                .await
+                .map_err(|e| McpError::internal_error(format!("Failed to send read resource command: {:?}", e), None))?;
+            let content = rx
+                .recv()
+                .await
+                .map_err(|e| McpError::internal_error(format!("Failed to receive resource response: {:?}", e), None))?
+                .map_err(|e| McpError::resource_not_found(e.message, None))?;
+
+            let mime_type = content.get("mime_type").and_then(Value::as_str).map(String::from);
+            let contents = if let Some(text) = content.get("text").and_then(Value::as_str) {
+                ResourceContents::TextResourceContents {
+                    uri: request.uri,
+                    mime_type,
+                    text: text.to_string(),
+                    meta: None,
+                }
+            } else {
+                ResourceContents::BlobResourceContents {
+                    uri: request.uri,
+                    mime_type,
+                    blob: content.get("blob").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    meta: None,
+                }
+            };
+            Ok(ReadResourceResult { contents: vec![contents] })
+        }
+    }
+
+    fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<(), ErrorData>> + Send + '_ {
+        if resource_uri_to_vecfs_path(&request.uri).is_none() {
+            return future::ready(Err(McpError::resource_not_found(
+                format!("Unknown resource '{}'", request.uri),
+                None,
+            )));
+        }
+        self.client_session(&context).subscribe(request.uri);
+        future::ready(Ok(()))
+    }
+
+    fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<(), ErrorData>> + Send + '_ {
+        self.client_session(&context).unsubscribe(&request.uri);
+        future::ready(Ok(()))
     }
 
     // Override the call_tool method
     fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> impl Future<Output = Result<CallToolResult, McpError>> + Send + '_ {
         async move {
             let tool_name = request.name.to_string(); // Get the requested tool name (e.g., "network__echo")
@@ -313,17 +499,61 @@ impl ServerHandler for McpToolsService {
                     // Found the key, proceed to execute directly
                     tracing::debug!(target: "mcp_tools_service", "Found tool_router_key '{}' for name '{}'", key, tool_name);
 
+                    // Tools priced with x402 only run once their payment is verified
+                    let payment = Self::x402_payment(&context);
+                    let receipt = match check_x402_payment(
+                        &self.node_commands_sender,
+                        PaywallResource::Tool(key.clone()),
+                        payment,
+                    )
+                    .await
+                    {
+                        Ok(PaywallDecision::NotPriced) => None,
+                        Ok(PaywallDecision::Verified(receipt)) => Some(receipt),
+                        Ok(PaywallDecision::PaymentRequired(response)) => {
+                            return Err(Self::payment_required_error(&response))
+                        }
+                        Err(e) => {
+                            return Err(McpError::internal_error(
+                                format!("Failed to check the payment for tool '{}': {}", tool_name, e.message),
+                                None,
+                            ))
+                        }
+                    };
+
                     // Convert arguments JsonObject into the Value expected by execute_hanzo_tool
                     let params_value = Value::Object(arguments);
 
-                    match self.execute_hanzo_tool(key, params_value).await {
+                    let result = self.execute_hanzo_tool(key, params_value).await;
+                    // A tool that failed is not charged, its payment is only marked as failed
+                    let mut payment_response = None;
+                    if let Some(receipt) = &receipt {
+                        let sender = &self.node_commands_sender;
+                        match settle_x402_payment(sender, receipt.receipt_id.clone(), result.is_ok()).await {
+                            Ok(response) => payment_response = response,
+                            Err(e) if result.is_ok() => {
+                                return Err(Self::payment_required_error(&settlement_failed_response(
+                                    receipt, e.message,
+                                )))
+                            }
+                            Err(_) => {}
+                        }
+                    }
+
+                    match result {
                         Ok(output_str) => {
                             tracing::debug!(
                                 "call_tool: execution successful for '{}', result: {}",
                                 tool_name,
                                 output_str
                             );
-                            Ok(CallToolResult::success(vec![Content::text(output_str)]))
+                            let mut result = CallToolResult::success(vec![Content::text(output_str)]);
+                            if let Some(payment_response) = payment_response {
+                                let mut meta = Meta::new();
+                                meta.insert(X402_PAYMENT_RESPONSE_META.to_string(), Value::String(payment_response));
+                                result.meta = Some(meta);
+                            }
+                            Ok(result)
                         }
                         Err(err_str) => {
                             tracing::error!("call_tool: execution failed for '{}': {}", tool_name, err_str);
@@ -4,6 +4,7 @@
 
 mod api_sse_handlers;
 pub mod api_sse_routes;
+mod mcp_resources;
 mod mcp_tools_service;
 
 // Re-export the public components
@@ -11,3 +12,8 @@ pub use api_sse_routes::{mcp_sse_routes, SessionQuery};
 
 // Re-export the state for custom integrations
 pub use api_sse_handlers::McpState;
+
+// Re-export the hooks the node uses to notify MCP clients about VecFS and prompt changes
+pub use mcp_resources::{
+    notify_mcp_prompt_list_changed, notify_mcp_resource_list_changed, notify_mcp_resource_updated,
+};
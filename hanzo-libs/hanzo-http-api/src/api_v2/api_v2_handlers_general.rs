@@ -9,6 +9,7 @@ use hanzo_messages::schemas::llm_providers::serialized_llm_provider::{
     Exo, Gemini, Groq, LLMProviderInterface, Ollama, OpenAI, HanzoBackend,
 };
 use hanzo_messages::schemas::llm_providers::hanzo_backend::QuotaResponse;
+use hanzo_messages::schemas::llm_usage::{LLMModelPrice, LLMUsageGroupBy, LLMUsageQuery, LLMUsageSummary, TokenUsage};
 use hanzo_messages::schemas::hanzo_name::{HanzoName, HanzoSubidentityType};
 use hanzo_messages::hanzo_message::hanzo_message::{
     EncryptedHanzoBody, EncryptedHanzoData, ExternalMetadata, InternalMetadata, MessageBody, MessageData,
@@ -155,6 +156,33 @@ pub fn general_routes(
         .and(warp::header::<String>("authorization"))
         .and_then(get_migration_status_handler);
 
+    let get_llm_usage_route = warp::path("llm_usage")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(get_llm_usage_handler);
+
+    let get_llm_model_prices_route = warp::path("llm_model_prices")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_llm_model_prices_handler);
+
+    let set_llm_model_price_route = warp::path("llm_model_prices")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_llm_model_price_handler);
+
+    let remove_llm_model_price_route = warp::path("remove_llm_model_price")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(remove_llm_model_price_handler);
+
     let add_ollama_models_route = warp::path("add_ollama_models")
         .and(warp::post())
         .and(with_sender(node_commands_sender.clone()))
@@ -319,6 +347,10 @@ pub fn general_routes(
         .or(scan_ollama_models_route)
         .or(trigger_embedding_migration_route)
         .or(get_migration_status_route)
+        .or(get_llm_usage_route)
+        .or(get_llm_model_prices_route)
+        .or(set_llm_model_price_route)
+        .or(remove_llm_model_price_route)
         .or(add_ollama_models_route)
         .or(stop_llm_route)
         .or(add_agent_route)
@@ -898,6 +930,144 @@ pub async fn get_migration_status_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v2/llm_usage",
+    request_body = LLMUsageQuery,
+    responses(
+        (status = 200, description = "Successfully retrieved LLM usage", body = Vec<LLMUsageSummary>),
+        (status = 401, description = "Unauthorized", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_llm_usage_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: LLMUsageQuery,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetLLMUsage {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/llm_model_prices",
+    responses(
+        (status = 200, description = "Successfully retrieved model prices", body = Vec<LLMModelPrice>),
+        (status = 401, description = "Unauthorized", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_llm_model_prices_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetLLMModelPrices {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/llm_model_prices",
+    request_body = LLMModelPrice,
+    responses(
+        (status = 200, description = "Successfully set model price", body = String),
+        (status = 400, description = "Invalid price", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_llm_model_price_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: LLMModelPrice,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetLLMModelPrice {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(_) => Ok(warp::reply::json(&json!({"status": "success"}))),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RemoveLLMModelPriceRequest {
+    pub model: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/remove_llm_model_price",
+    request_body = RemoveLLMModelPriceRequest,
+    responses(
+        (status = 200, description = "Successfully removed model price", body = String),
+        (status = 404, description = "Model has no price", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn remove_llm_model_price_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: RemoveLLMModelPriceRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRemoveLLMModelPrice {
+            bearer,
+            model: payload.model,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(_) => Ok(warp::reply::json(&json!({"status": "success"}))),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/v2/add_ollama_models",
@@ -1704,6 +1874,10 @@ pub async fn docker_status_handler(sender: Sender<NodeCommand>) -> Result<impl w
         scan_ollama_models_handler,
         trigger_embedding_migration_handler,
         get_migration_status_handler,
+        get_llm_usage_handler,
+        get_llm_model_prices_handler,
+        set_llm_model_price_handler,
+        remove_llm_model_price_handler,
         add_ollama_models_handler,
         stop_llm_handler,
         add_agent_handler,
@@ -1730,7 +1904,8 @@ pub async fn docker_status_handler(sender: Sender<NodeCommand>) -> Result<impl w
             HanzoSubidentityType, HanzoBackend, InternalMetadata, MessageData, StopLLMRequest,
             NodeApiData, EncryptedHanzoData, HanzoData, MessageSchemaType,
             APIUseRegistrationCodeSuccessResponse, GetPublicKeysResponse, APIError, Agent,
-            AddRegexPatternRequest, QuotaResponse)
+            AddRegexPatternRequest, QuotaResponse, TokenUsage, LLMModelPrice, LLMUsageGroupBy,
+            LLMUsageQuery, LLMUsageSummary, RemoveLLMModelPriceRequest)
     ),
     tags(
         (name = "general", description = "General API endpoints")
@@ -6,7 +6,8 @@ use serde::Deserialize;
 use serde_json::json;
 use hanzo_messages::{
     schemas::{
-        job_config::JobConfig,
+        hanzo_fs::ChunkingStrategy,
+        job_config::{JobConfig, SpendingBudget},
         llm_providers::serialized_llm_provider::{
             Exo, Gemini, Groq, LLMProviderInterface, Ollama, OpenAI, SerializedLLMProvider, HanzoBackend,
         },
@@ -250,6 +251,8 @@ pub struct AddFileToFolder {
     pub path: String,
     pub filename: String,
     pub file: Vec<u8>,
+    /// Overrides the chunking strategy of the folder for this file
+    pub chunking_strategy: Option<ChunkingStrategy>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -257,6 +260,8 @@ pub struct AddFileToJob {
     pub job_id: String,
     pub filename: String,
     pub file: Vec<u8>,
+    /// Overrides the chunking strategy of the job folder for this file
+    pub chunking_strategy: Option<ChunkingStrategy>,
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -1400,7 +1405,7 @@ pub async fn get_job_provider_handler(
         remove_job_handler,
     ),
     components(
-        schemas(AddFileToFolder, V2SmartInbox, APIChangeJobAgentRequest, CreateJobRequest, JobConfig,
+        schemas(AddFileToFolder, V2SmartInbox, APIChangeJobAgentRequest, CreateJobRequest, JobConfig, SpendingBudget,
             JobMessageRequest, GetLastMessagesRequest, V2ChatMessage, GetLastMessagesWithBranchesRequest,
             UpdateJobConfigRequest, UpdateSmartInboxNameRequest, SerializedLLMProvider, JobCreationInfo,
             JobMessage, NodeApiData, LLMProviderSubset, AssociatedUI, MinimalJobScope, CallbackAction, HanzoName,
@@ -1,6 +1,6 @@
 use async_channel::Sender;
 use serde::{Deserialize, Serialize};
-use hanzo_messages::schemas::mcp_server::{MCPServer, MCPServerEnv, MCPServerType};
+use hanzo_messages::schemas::mcp_server::{MCPSamplingPolicy, MCPServer, MCPServerEnv, MCPServerType};
 use utoipa::{OpenApi, ToSchema};
 use warp::Filter;
 
@@ -57,6 +57,27 @@ pub struct SetEnableMCPServerRequest {
     pub is_enabled: bool,
 }
 
+#[derive(Deserialize, ToSchema, Debug)]
+pub struct ImportMCPServerPromptsRequest {
+    pub mcp_server_id: i64,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+pub struct GetMCPServerResourcesRequest {
+    pub mcp_server_id: i64,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+pub struct GetMCPServerSamplingPolicyRequest {
+    pub mcp_server_id: i64,
+}
+
+#[derive(Deserialize, ToSchema, Debug)]
+pub struct SetMCPServerSamplingPolicyRequest {
+    pub mcp_server_id: i64,
+    pub policy: MCPSamplingPolicy,
+}
+
 pub fn mcp_server_routes(
     node_commands_sender: Sender<NodeCommand>,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
@@ -108,6 +129,34 @@ pub fn mcp_server_routes(
         .and(warp::body::json())
         .and_then(set_enable_mcp_server_handler);
 
+    let import_mcp_server_prompts_route = warp::path("import_mcp_server_prompts")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(import_mcp_server_prompts_handler);
+
+    let get_mcp_server_resources_route = warp::path("mcp_server_resources")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<GetMCPServerResourcesRequest>())
+        .and_then(get_mcp_server_resources_handler);
+
+    let get_mcp_server_sampling_policy_route = warp::path("mcp_server_sampling_policy")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<GetMCPServerSamplingPolicyRequest>())
+        .and_then(get_mcp_server_sampling_policy_handler);
+
+    let set_mcp_server_sampling_policy_route = warp::path("set_mcp_server_sampling_policy")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_mcp_server_sampling_policy_handler);
+
     list_mcp_servers_route
         .or(add_mcp_server_route)
         .or(get_all_mcp_server_tools_route)
@@ -115,6 +164,10 @@ pub fn mcp_server_routes(
         .or(import_mcp_server_from_github_url_route)
         .or(set_enable_mcp_server_route)
         .or(update_mcp_server_route)
+        .or(import_mcp_server_prompts_route)
+        .or(get_mcp_server_resources_route)
+        .or(get_mcp_server_sampling_policy_route)
+        .or(set_mcp_server_sampling_policy_route)
 }
 
 #[utoipa::path(
@@ -351,6 +404,147 @@ pub async fn update_mcp_server_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v2/import_mcp_server_prompts",
+    request_body = ImportMCPServerPromptsRequest,
+    responses(
+        (status = 200, description = "Successfully imported MCP server prompts into the prompt library", body = Vec<CustomPrompt>),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn import_mcp_server_prompts_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: ImportMCPServerPromptsRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiImportMCPServerPrompts {
+            bearer,
+            mcp_server_id: payload.mcp_server_id,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/mcp_server_resources",
+    params(
+        ("mcp_server_id" = i64, Query, description = "ID of the MCP server")
+    ),
+    responses(
+        (status = 200, description = "Successfully retrieved MCP server resources", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_mcp_server_resources_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: GetMCPServerResourcesRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiListMCPServerResources {
+            bearer,
+            mcp_server_id: payload.mcp_server_id,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/mcp_server_sampling_policy",
+    params(
+        ("mcp_server_id" = i64, Query, description = "ID of the MCP server")
+    ),
+    responses(
+        (status = 200, description = "Successfully retrieved MCP server sampling policy", body = MCPSamplingPolicy),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_mcp_server_sampling_policy_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: GetMCPServerSamplingPolicyRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetMCPServerSamplingPolicy {
+            bearer,
+            mcp_server_id: payload.mcp_server_id,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/set_mcp_server_sampling_policy",
+    request_body = SetMCPServerSamplingPolicyRequest,
+    responses(
+        (status = 200, description = "Successfully set MCP server sampling policy", body = MCPSamplingPolicy),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_mcp_server_sampling_policy_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: SetMCPServerSamplingPolicyRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetMCPServerSamplingPolicy {
+            bearer,
+            mcp_server_id: payload.mcp_server_id,
+            policy: payload.policy,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -361,9 +555,14 @@ pub async fn update_mcp_server_handler(
         delete_mcp_server_handler,
         set_enable_mcp_server_handler,
         update_mcp_server_handler,
+        import_mcp_server_prompts_handler,
+        get_mcp_server_resources_handler,
+        get_mcp_server_sampling_policy_handler,
+        set_mcp_server_sampling_policy_handler,
     ),
     components(
-        schemas(AddMCPServerRequest, MCPServer, APIError)
+        schemas(AddMCPServerRequest, MCPServer, MCPSamplingPolicy, ImportMCPServerPromptsRequest,
+            SetMCPServerSamplingPolicyRequest, APIError)
     ),
     tags(
         (name = "mcp_servers", description = "MCP Server API endpoints")
@@ -14,7 +14,7 @@ use warp::{
 use super::{
     api_v2_handlers_ext_agent_offers::ToolOfferingsApiDoc, api_v2_handlers_general::GeneralApiDoc,
     api_v2_handlers_jobs::JobsApiDoc, api_v2_handlers_mcp_servers::MCPServerApiDoc, api_v2_handlers_tools::ToolsApiDoc,
-    api_v2_handlers_vecfs::VecFsApiDoc, api_v2_handlers_wallets::WalletApiDoc,
+    api_v2_handlers_vecfs::VecFsApiDoc, api_v2_handlers_wallets::WalletApiDoc, api_v2_handlers_x402::X402ApiDoc,
 };
 
 pub fn swagger_ui_routes() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
@@ -25,6 +25,7 @@ pub fn swagger_ui_routes() -> impl Filter<Extract = impl warp::Reply, Error = wa
         "/v2/openapi/wallet.json",
         "/v2/openapi/tools.json",
         "/v2/openapi/ext_agent_offers.json",
+        "/v2/openapi/x402.json",
     ]));
 
     let general_schema_route = warp::path!("openapi" / "general.json")
@@ -62,6 +63,10 @@ pub fn swagger_ui_routes() -> impl Filter<Extract = impl warp::Reply, Error = wa
         .and(warp::get())
         .map(|| warp::reply::json(&MCPServerApiDoc::openapi()));
 
+    let x402_schema_route = warp::path!("openapi" / "x402.json")
+        .and(warp::get())
+        .map(|| warp::reply::json(&X402ApiDoc::openapi()));
+
     general_schema_route
         .or(jobs_schema_route)
         .or(vecfs_schema_route)
@@ -69,6 +74,7 @@ pub fn swagger_ui_routes() -> impl Filter<Extract = impl warp::Reply, Error = wa
         .or(tools_schema_route)
         .or(ext_agent_offers_schema_route)
         .or(mcp_servers_schema_route)
+        .or(x402_schema_route)
         .or(swagger_ui)
 }
 
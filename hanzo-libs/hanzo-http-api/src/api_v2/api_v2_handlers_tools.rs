@@ -14,6 +14,7 @@ use hanzo_messages::{
 use hanzo_tools::tools::{
     hanzo_tool::HanzoToolWithAssets,
     tool_config::OAuth,
+    tool_permissions::ToolPermissions,
     tool_playground::ToolPlayground,
     tool_types::{OperatingSystem, RunnerType},
 };
@@ -1260,6 +1261,8 @@ pub struct CodeExecutionRequest {
     pub mounts: Option<Vec<String>>,
     pub runner: Option<RunnerType>,
     pub operating_system: Option<Vec<OperatingSystem>>,
+    #[serde(default)]
+    pub permissions: Option<ToolPermissions>,
 }
 
 // Define a custom default function for oauth
@@ -1338,6 +1341,7 @@ pub async fn code_execution_handler(
             mounts: payload.mounts,
             runner: payload.runner,
             operating_system: payload.operating_system,
+            permissions: payload.permissions,
             res: res_sender,
         })
         .await
@@ -2,10 +2,12 @@ use async_channel::Sender;
 use chrono::{DateTime, Utc};
 use reqwest::StatusCode;
 use hanzo_messages::hanzo_message::hanzo_message_schemas::{
-    APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder, APIVecFsDeleteItem,
-    APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsRetrieveSourceFile,
-    APIVecFsSearchItems,
+    APIVecFsAddWatchedFolder, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder,
+    APIVecFsDeleteItem, APIVecFsGetFolderChunkingStrategy, APIVecFsMoveFolder, APIVecFsMoveItem,
+    APIVecFsRemoveWatchedFolder, APIVecFsRetrievePathSimplifiedJson, APIVecFsRetrieveSourceFile, APIVecFsSearchItems,
+    APIVecFsSetFolderChunkingStrategy, APIVecFsSyncWatchedFolder,
 };
+use hanzo_messages::schemas::hanzo_fs::{ChunkingStrategy, FolderSyncReport, WatchedFolder};
 
 use crate::api_v2::api_v2_handlers_jobs::AddFileToJob;
 use crate::node_commands::NodeCommand;
@@ -128,6 +130,47 @@ pub fn vecfs_routes(
         .and(warp::multipart::form().max_length(1024 * 1024 * 1024)) // Set max length to 1024 MB
         .and_then(upload_file_to_job_handler);
 
+    let set_folder_chunking_strategy_route = warp::path("set_folder_chunking_strategy")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_folder_chunking_strategy_handler);
+
+    let get_folder_chunking_strategy_route = warp::path("get_folder_chunking_strategy")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<APIVecFsGetFolderChunkingStrategy>())
+        .and_then(get_folder_chunking_strategy_handler);
+
+    let add_watched_folder_route = warp::path("add_watched_folder")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(add_watched_folder_handler);
+
+    let remove_watched_folder_route = warp::path("remove_watched_folder")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(remove_watched_folder_handler);
+
+    let list_watched_folders_route = warp::path("list_watched_folders")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(list_watched_folders_handler);
+
+    let sync_watched_folder_route = warp::path("sync_watched_folder")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(sync_watched_folder_handler);
+
     let search_files_by_name_route = warp::path("search_files_by_name")
         .and(warp::get())
         .and(with_sender(node_commands_sender.clone()))
@@ -151,6 +194,12 @@ pub fn vecfs_routes(
         .or(get_folder_name_for_job_route)
         .or(upload_file_to_job_route)
         .or(search_files_by_name_route)
+        .or(set_folder_chunking_strategy_route)
+        .or(get_folder_chunking_strategy_route)
+        .or(add_watched_folder_route)
+        .or(remove_watched_folder_route)
+        .or(list_watched_folders_route)
+        .or(sync_watched_folder_route)
 }
 
 #[utoipa::path(
@@ -542,6 +591,7 @@ pub async fn upload_file_to_folder_handler(
     let mut file_data = Vec::new();
     let mut path = String::new();
     let mut file_datetime: Option<DateTime<Utc>> = None;
+    let mut chunking_strategy: Option<ChunkingStrategy> = None;
 
     while let Some(part) = form.next().await {
         let mut part = part.map_err(|e| {
@@ -637,6 +687,26 @@ pub async fn upload_file_to_folder_handler(
                     );
                 }
             }
+            "chunking_strategy" => {
+                if let Some(content) = part.data().await {
+                    let mut content = content.map_err(|e| {
+                        warp::reject::custom(APIError::new(
+                            StatusCode::BAD_REQUEST,
+                            "Bad Request",
+                            format!("Failed to read chunking_strategy: {:?}", e).as_str(),
+                        ))
+                    })?;
+                    chunking_strategy = Some(
+                        serde_json::from_slice(&content.copy_to_bytes(content.remaining())).map_err(|e| {
+                            warp::reject::custom(APIError::new(
+                                StatusCode::BAD_REQUEST,
+                                "Bad Request",
+                                format!("Invalid chunking_strategy: {}", e).as_str(),
+                            ))
+                        })?,
+                    );
+                }
+            }
             _ => {}
         }
     }
@@ -657,6 +727,7 @@ pub async fn upload_file_to_folder_handler(
             file: file_data,
             path,
             file_datetime,
+            chunking_strategy,
             res: res_sender,
         })
         .await
@@ -839,6 +910,7 @@ pub async fn upload_file_to_job_handler(
     let mut filename = String::new();
     let mut file_data = Vec::new();
     let mut file_datetime: Option<DateTime<Utc>> = None;
+    let mut chunking_strategy: Option<ChunkingStrategy> = None;
 
     while let Some(part) = form.next().await {
         let mut part = part.map_err(|e| {
@@ -934,6 +1006,26 @@ pub async fn upload_file_to_job_handler(
                     );
                 }
             }
+            "chunking_strategy" => {
+                if let Some(content) = part.data().await {
+                    let mut content = content.map_err(|e| {
+                        warp::reject::custom(APIError::new(
+                            StatusCode::BAD_REQUEST,
+                            "Bad Request",
+                            format!("Failed to read chunking_strategy: {:?}", e).as_str(),
+                        ))
+                    })?;
+                    chunking_strategy = Some(
+                        serde_json::from_slice(&content.copy_to_bytes(content.remaining())).map_err(|e| {
+                            warp::reject::custom(APIError::new(
+                                StatusCode::BAD_REQUEST,
+                                "Bad Request",
+                                format!("Invalid chunking_strategy: {}", e).as_str(),
+                            ))
+                        })?,
+                    );
+                }
+            }
             _ => {}
         }
     }
@@ -957,6 +1049,7 @@ pub async fn upload_file_to_job_handler(
             filename,
             file: file_data,
             file_datetime: Some(file_datetime),
+            chunking_strategy,
             res: res_sender,
         })
         .await
@@ -1041,6 +1134,221 @@ pub async fn search_files_by_name_handler(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/v2/set_folder_chunking_strategy",
+    request_body = APIVecFsSetFolderChunkingStrategy,
+    responses(
+        (status = 200, description = "Successfully set the chunking strategy of the folder", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_folder_chunking_strategy_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFsSetFolderChunkingStrategy,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSetFolderChunkingStrategy {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/get_folder_chunking_strategy",
+    params(
+        ("path" = String, Query, description = "Folder path")
+    ),
+    responses(
+        (status = 200, description = "Successfully retrieved the chunking strategy of the folder", body = Value),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_folder_chunking_strategy_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFsGetFolderChunkingStrategy,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiGetFolderChunkingStrategy {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/add_watched_folder",
+    request_body = APIVecFsAddWatchedFolder,
+    responses(
+        (status = 200, description = "Successfully registered the watched folder", body = WatchedFolder),
+        (status = 400, description = "Bad request", body = APIError),
+        (status = 409, description = "Directory already watched", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn add_watched_folder_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFsAddWatchedFolder,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiAddWatchedFolder {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/remove_watched_folder",
+    request_body = APIVecFsRemoveWatchedFolder,
+    responses(
+        (status = 200, description = "Successfully removed the watched folder", body = Value),
+        (status = 404, description = "Watched folder not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn remove_watched_folder_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFsRemoveWatchedFolder,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiRemoveWatchedFolder {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/list_watched_folders",
+    responses(
+        (status = 200, description = "Successfully listed the watched folders", body = Vec<WatchedFolder>),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn list_watched_folders_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiListWatchedFolders {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/sync_watched_folder",
+    request_body = APIVecFsSyncWatchedFolder,
+    responses(
+        (status = 200, description = "Successfully synced the watched folder", body = FolderSyncReport),
+        (status = 404, description = "Watched folder not found", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn sync_watched_folder_handler(
+    node_commands_sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: APIVecFsSyncWatchedFolder,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    node_commands_sender
+        .send(NodeCommand::V2ApiSyncWatchedFolder {
+            bearer,
+            payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::with_status(warp::reply::json(&response), StatusCode::OK)),
+        Err(error) => Ok(warp::reply::with_status(
+            warp::reply::json(&error),
+            StatusCode::from_u16(error.code).unwrap(),
+        )),
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -1060,10 +1368,18 @@ pub async fn search_files_by_name_handler(
         get_folder_name_for_job_handler,
         upload_file_to_job_handler,
         search_files_by_name_handler,
+        set_folder_chunking_strategy_handler,
+        get_folder_chunking_strategy_handler,
+        add_watched_folder_handler,
+        remove_watched_folder_handler,
+        list_watched_folders_handler,
+        sync_watched_folder_handler,
     ),
     components(
         schemas(APIError, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder, APIVecFsDeleteFolder, APIVecFsDeleteItem,
-            APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsSearchItems, AddFileToFolder, AddFileToJob)
+            APIVecFsMoveFolder, APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsSearchItems, AddFileToFolder, AddFileToJob,
+            APIVecFsSetFolderChunkingStrategy, APIVecFsGetFolderChunkingStrategy, ChunkingStrategy,
+            APIVecFsAddWatchedFolder, APIVecFsRemoveWatchedFolder, APIVecFsSyncWatchedFolder, WatchedFolder, FolderSyncReport)
     ),
     tags(
         (name = "vecfs", description = "VecFS API endpoints")
@@ -1,10 +1,11 @@
 use async_channel::Sender;
 use serde::Deserialize;
-use serde_json::Value;
+use serde_json::{json, Value};
 use hanzo_messages::schemas::{
     coinbase_mpc_config::CoinbaseMPCWalletConfig,
     wallet_complementary::{WalletRole, WalletSource},
-    wallet_mixed::{Address, Asset, NetworkProtocolFamilyEnum},
+    wallet_mixed::{Address, Asset, AssetType, NetworkProtocolFamilyEnum},
+    wallet_spending_policy::{AssetSpendingLimits, SpendingDecision, SpendingLedgerEntry, WalletSpendingPolicy},
     x402_types::Network,
 };
 use utoipa::{OpenApi, ToSchema};
@@ -64,6 +65,26 @@ pub fn wallet_routes(
         .and(warp::header::<String>("authorization"))
         .and_then(get_wallet_balance_handler);
 
+    let get_wallet_spending_policy_route = warp::path("wallet_spending_policy")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_wallet_spending_policy_handler);
+
+    let set_wallet_spending_policy_route = warp::path("wallet_spending_policy")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_wallet_spending_policy_handler);
+
+    let get_wallet_spending_ledger_route = warp::path("wallet_spending_ledger")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(get_wallet_spending_ledger_handler);
+
     restore_local_wallet_route
         .or(create_local_wallet_route)
         .or(pay_invoice_route)
@@ -71,6 +92,9 @@ pub fn wallet_routes(
         .or(restore_coinbase_mpc_wallet_route)
         .or(list_wallets_route)
         .or(get_wallet_balance_route)
+        .or(get_wallet_spending_policy_route)
+        .or(set_wallet_spending_policy_route)
+        .or(get_wallet_spending_ledger_route)
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -339,6 +363,110 @@ pub async fn get_wallet_balance_handler(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/v2/wallet_spending_policy",
+    responses(
+        (status = 200, description = "Successfully retrieved the wallet spending policy", body = WalletSpendingPolicy),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_wallet_spending_policy_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetWalletSpendingPolicy {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/wallet_spending_policy",
+    request_body = WalletSpendingPolicy,
+    responses(
+        (status = 200, description = "Successfully set the wallet spending policy", body = String),
+        (status = 400, description = "Invalid policy", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_wallet_spending_policy_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: WalletSpendingPolicy,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetWalletSpendingPolicy {
+            bearer,
+            policy: payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(_) => Ok(warp::reply::json(&json!({"status": "success"}))),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SpendingLedgerRequest {
+    pub invoice_id: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/wallet_spending_ledger",
+    request_body = SpendingLedgerRequest,
+    responses(
+        (status = 200, description = "Successfully retrieved the spending ledger", body = Vec<SpendingLedgerEntry>),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_wallet_spending_ledger_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: SpendingLedgerRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetWalletSpendingLedger {
+            bearer,
+            invoice_id: payload.invoice_id,
+            limit: payload.limit,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -347,10 +475,15 @@ pub async fn get_wallet_balance_handler(
         pay_invoice_handler,
         reject_invoice_handler,
         restore_coinbase_mpc_wallet_handler,
+        get_wallet_spending_policy_handler,
+        set_wallet_spending_policy_handler,
+        get_wallet_spending_ledger_handler,
     ),
     components(
         schemas(APIError, CreateLocalWalletRequest, PayInvoiceRequest, RejectInvoiceRequest, RestoreCoinbaseMPCWalletRequest, RestoreLocalWalletRequest,
-            NetworkProtocolFamilyEnum, WalletRole, WalletSource, CoinbaseMPCWalletConfig, Address, Asset)
+            NetworkProtocolFamilyEnum, WalletRole, WalletSource, CoinbaseMPCWalletConfig, Address, Asset,
+            AssetType, AssetSpendingLimits, WalletSpendingPolicy, SpendingDecision, SpendingLedgerEntry,
+            SpendingLedgerRequest)
     ),
     tags(
         (name = "wallet", description = "Wallet API endpoints")
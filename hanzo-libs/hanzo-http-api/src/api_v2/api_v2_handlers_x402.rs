@@ -0,0 +1,447 @@
+use std::future::Future;
+
+use async_channel::Sender;
+use hanzo_messages::schemas::x402_paywall::{
+    PaymentRequiredResponse, PaywallDecision, PaywallPrice, PaywallResource, X402Receipt, X402ReceiptStatus,
+};
+use hanzo_messages::schemas::x402_types::PaymentRequirements;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use utoipa::{OpenApi, ToSchema};
+use warp::Filter;
+use warp::Reply;
+
+use crate::{node_api_router::APIError, node_commands::NodeCommand};
+
+use super::api_v2_router::{create_success_response, with_sender};
+
+pub const X_PAYMENT_HEADER: &str = "x-payment";
+pub const X_PAYMENT_RESPONSE_HEADER: &str = "x-payment-response";
+
+pub fn x402_routes(
+    node_commands_sender: Sender<NodeCommand>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    let get_x402_prices_route = warp::path("x402_prices")
+        .and(warp::get())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and_then(get_x402_prices_handler);
+
+    let set_x402_price_route = warp::path("x402_prices")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(set_x402_price_handler);
+
+    let remove_x402_price_route = warp::path("remove_x402_price")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(remove_x402_price_handler);
+
+    let get_x402_receipts_route = warp::path("x402_receipts")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and_then(get_x402_receipts_handler);
+
+    // Paid routes don't take a bearer, the X-PAYMENT header is what grants access
+    let x402_agent_chat_route = warp::path!("x402" / "agents" / String / "chat")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::optional::<String>(X_PAYMENT_HEADER))
+        .and(warp::body::json())
+        .and_then(x402_agent_chat_handler);
+
+    let x402_tool_execution_route = warp::path!("x402" / "tool_execution")
+        .and(warp::post())
+        .and(with_sender(node_commands_sender.clone()))
+        .and(warp::header::optional::<String>(X_PAYMENT_HEADER))
+        .and(warp::body::json())
+        .and_then(x402_tool_execution_handler);
+
+    get_x402_prices_route
+        .or(set_x402_price_route)
+        .or(remove_x402_price_route)
+        .or(get_x402_receipts_route)
+        .or(x402_agent_chat_route)
+        .or(x402_tool_execution_route)
+}
+
+/// Checks the payment sent for a resource, a verified payment has to be settled with
+/// `settle_x402_payment` once the resource is served (or not)
+pub async fn check_x402_payment(
+    sender: &Sender<NodeCommand>,
+    resource: PaywallResource,
+    payment: Option<String>,
+) -> Result<PaywallDecision, APIError> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiCheckX402Payment {
+            resource,
+            payment,
+            res: res_sender,
+        })
+        .await
+        .map_err(internal_error)?;
+    res_receiver.recv().await.map_err(internal_error)?
+}
+
+/// Settles the payment of a receipt, returning the X-PAYMENT-RESPONSE header if it was charged
+pub async fn settle_x402_payment(
+    sender: &Sender<NodeCommand>,
+    receipt_id: String,
+    served: bool,
+) -> Result<Option<String>, APIError> {
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSettleX402Payment {
+            receipt_id,
+            served,
+            res: res_sender,
+        })
+        .await
+        .map_err(internal_error)?;
+    res_receiver.recv().await.map_err(internal_error)?
+}
+
+/// The answer to a settlement that failed: the payment is refused, so the client has to pay again
+pub fn settlement_failed_response(receipt: &X402Receipt, error: String) -> PaymentRequiredResponse {
+    PaymentRequiredResponse {
+        x402_version: receipt.payment.x402_version,
+        error,
+        accepts: vec![receipt.requirements.clone()],
+        payer: Some(receipt.payer.clone()),
+    }
+}
+
+fn internal_error(e: impl ToString) -> APIError {
+    APIError::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Internal Server Error",
+        &e.to_string(),
+    )
+}
+
+fn error_reply(error: APIError) -> warp::reply::Response {
+    let status = StatusCode::from_u16(error.code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    warp::reply::with_status(warp::reply::json(&error), status).into_response()
+}
+
+fn payment_required_reply(response: &PaymentRequiredResponse) -> warp::reply::Response {
+    warp::reply::with_status(warp::reply::json(response), StatusCode::PAYMENT_REQUIRED).into_response()
+}
+
+/// Serves a resource only once its payment is verified, then settles the payment
+async fn serve_paid<F, Fut>(
+    sender: Sender<NodeCommand>,
+    resource: PaywallResource,
+    payment: Option<String>,
+    serve: F,
+) -> warp::reply::Response
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Value, APIError>>,
+{
+    let receipt = match check_x402_payment(&sender, resource.clone(), payment).await {
+        Ok(PaywallDecision::Verified(receipt)) => receipt,
+        Ok(PaywallDecision::PaymentRequired(response)) => return payment_required_reply(&response),
+        Ok(PaywallDecision::NotPriced) => {
+            return error_reply(APIError::new(
+                StatusCode::NOT_FOUND,
+                "Not Found",
+                &format!("{} is not available over x402", resource),
+            ))
+        }
+        Err(error) => return error_reply(error),
+    };
+
+    let result = serve().await;
+    let settlement = settle_x402_payment(&sender, receipt.receipt_id.clone(), result.is_ok()).await;
+    match (result, settlement) {
+        (Ok(response), Ok(payment_response)) => {
+            let reply = warp::reply::json(&create_success_response(response));
+            match payment_response {
+                Some(payment_response) => {
+                    warp::reply::with_header(reply, X_PAYMENT_RESPONSE_HEADER, payment_response).into_response()
+                }
+                None => reply.into_response(),
+            }
+        }
+        (Ok(_), Err(error)) => payment_required_reply(&settlement_failed_response(&receipt, error.message)),
+        (Err(error), _) => error_reply(error),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/v2/x402_prices",
+    responses(
+        (status = 200, description = "Successfully retrieved the x402 prices", body = Vec<PaywallPrice>),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_x402_prices_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetX402Prices {
+            bearer,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/x402_prices",
+    request_body = PaywallPrice,
+    responses(
+        (status = 200, description = "Successfully set the x402 price", body = String),
+        (status = 400, description = "Invalid price", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn set_x402_price_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: PaywallPrice,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiSetX402Price {
+            bearer,
+            price: payload,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(_) => Ok(warp::reply::json(&json!({"status": "success"}))),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RemoveX402PriceRequest {
+    pub resource: PaywallResource,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/remove_x402_price",
+    request_body = RemoveX402PriceRequest,
+    responses(
+        (status = 200, description = "Successfully removed the x402 price", body = String),
+        (status = 404, description = "Resource not priced", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn remove_x402_price_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: RemoveX402PriceRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiRemoveX402Price {
+            bearer,
+            resource: payload.resource,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(_) => Ok(warp::reply::json(&json!({"status": "success"}))),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct X402ReceiptsRequest {
+    pub resource: Option<PaywallResource>,
+    pub limit: Option<u64>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/x402_receipts",
+    request_body = X402ReceiptsRequest,
+    responses(
+        (status = 200, description = "Successfully retrieved the x402 receipts", body = Vec<X402Receipt>),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn get_x402_receipts_handler(
+    sender: Sender<NodeCommand>,
+    authorization: String,
+    payload: X402ReceiptsRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let bearer = authorization.strip_prefix("Bearer ").unwrap_or("").to_string();
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::V2ApiGetX402Receipts {
+            bearer,
+            resource: payload.resource,
+            limit: payload.limit,
+            res: res_sender,
+        })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(response) => Ok(warp::reply::json(&response)),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct X402AgentChatRequest {
+    pub message: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/x402/agents/{agent_id}/chat",
+    request_body = X402AgentChatRequest,
+    params(
+        ("agent_id" = String, Path, description = "Agent to chat with"),
+        ("X-PAYMENT" = Option<String>, Header, description = "Base64 encoded x402 payment")
+    ),
+    responses(
+        (status = 200, description = "Answer of the agent, the settlement is in the X-PAYMENT-RESPONSE header", body = Value),
+        (status = 402, description = "Payment required", body = PaymentRequiredResponse),
+        (status = 404, description = "Agent chat not available over x402", body = APIError),
+        (status = 500, description = "Internal server error", body = APIError)
+    )
+)]
+pub async fn x402_agent_chat_handler(
+    agent_id: String,
+    sender: Sender<NodeCommand>,
+    payment: Option<String>,
+    payload: X402AgentChatRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let resource = PaywallResource::agent_chat(&agent_id);
+    let chat_sender = sender.clone();
+    let reply = serve_paid(sender, resource, payment, || async move {
+        let (res_sender, res_receiver) = async_channel::bounded(1);
+        chat_sender
+            .send(NodeCommand::V2ApiX402AgentChat {
+                agent_id,
+                message: payload.message,
+                res: res_sender,
+            })
+            .await
+            .map_err(internal_error)?;
+        res_receiver.recv().await.map_err(internal_error)?
+    })
+    .await;
+    Ok(reply)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct X402ToolExecutionRequest {
+    pub tool_router_key: String,
+    pub parameters: Value,
+}
+
+#[utoipa::path(
+    post,
+    path = "/v2/x402/tool_execution",
+    request_body = X402ToolExecutionRequest,
+    params(
+        ("X-PAYMENT" = Option<String>, Header, description = "Base64 encoded x402 payment")
+    ),
+    responses(
+        (status = 200, description = "Output of the tool, the settlement is in the X-PAYMENT-RESPONSE header", body = Value),
+        (status = 400, description = "Invalid request parameters", body = APIError),
+        (status = 402, description = "Payment required", body = PaymentRequiredResponse),
+        (status = 404, description = "Tool not available over x402", body = APIError),
+        (status = 500, description = "Tool execution failed", body = APIError)
+    )
+)]
+pub async fn x402_tool_execution_handler(
+    sender: Sender<NodeCommand>,
+    payment: Option<String>,
+    payload: X402ToolExecutionRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    // Checked before the payment so a malformed request is never charged
+    let parameters = match payload.parameters {
+        Value::Object(map) => map,
+        _ => {
+            return Ok(error_reply(APIError::new(
+                StatusCode::BAD_REQUEST,
+                "Invalid Parameters",
+                "Parameters must be an object",
+            )))
+        }
+    };
+
+    let resource = PaywallResource::Tool(payload.tool_router_key.clone());
+    let tool_sender = sender.clone();
+    let reply = serve_paid(sender, resource, payment, || async move {
+        let (res_sender, res_receiver) = async_channel::bounded(1);
+        tool_sender
+            .send(NodeCommand::V2ApiExecuteMcpTool {
+                tool_router_key: payload.tool_router_key,
+                parameters,
+                tool_id: "".to_string(),
+                app_id: "".to_string(),
+                agent_id: None,
+                extra_config: Map::new(),
+                mounts: None,
+                res: res_sender,
+            })
+            .await
+            .map_err(internal_error)?;
+        res_receiver.recv().await.map_err(internal_error)?
+    })
+    .await;
+    Ok(reply)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_x402_prices_handler,
+        set_x402_price_handler,
+        remove_x402_price_handler,
+        get_x402_receipts_handler,
+        x402_agent_chat_handler,
+        x402_tool_execution_handler,
+    ),
+    components(
+        schemas(APIError, PaywallPrice, PaywallResource, PaymentRequiredResponse, PaymentRequirements, X402Receipt,
+            X402ReceiptStatus, RemoveX402PriceRequest, X402ReceiptsRequest, X402AgentChatRequest,
+            X402ToolExecutionRequest)
+    ),
+    tags(
+        (name = "x402", description = "x402 paywall API endpoints")
+    )
+)]
+pub struct X402ApiDoc;
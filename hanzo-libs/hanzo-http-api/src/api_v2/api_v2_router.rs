@@ -12,6 +12,7 @@ use super::api_v2_handlers_swagger_ui::swagger_ui_routes;
 use super::api_v2_handlers_tools::tool_routes;
 use super::api_v2_handlers_vecfs::vecfs_routes;
 use super::api_v2_handlers_wallets::wallet_routes;
+use super::api_v2_handlers_x402::x402_routes;
 use super::{api_v2_handlers_cron::cron_routes, api_v2_handlers_mcp_servers::add_mcp_server_handler};
 use async_channel::Sender;
 use serde::Serialize;
@@ -36,6 +37,7 @@ pub fn v2_routes(
     let oauth_routes = oauth_routes(node_commands_sender.clone());
     let mcp_server_routes = mcp_server_routes(node_commands_sender.clone());
     let ngrok_routes = ngrok_routes(node_commands_sender.clone());
+    let x402_routes = x402_routes(node_commands_sender.clone());
 
     #[cfg(feature = "swagger-ui")]
     return general_routes
@@ -49,7 +51,8 @@ pub fn v2_routes(
         .or(cron_routes)
         .or(oauth_routes)
         .or(mcp_server_routes)
-        .or(ngrok_routes);
+        .or(ngrok_routes)
+        .or(x402_routes);
 
     #[cfg(not(feature = "swagger-ui"))]
     return general_routes
@@ -62,7 +65,8 @@ pub fn v2_routes(
         .or(cron_routes)
         .or(oauth_routes)
         .or(mcp_server_routes)
-        .or(ngrok_routes);
+        .or(ngrok_routes)
+        .or(x402_routes);
 }
 
 pub fn with_sender(
@@ -110,6 +110,7 @@ pub async fn run_api(
     node_name: String,
     private_https_certificate: Option<String>,
     public_https_certificate: Option<String>,
+    metrics_enabled: bool,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     hanzo_log(
         HanzoLogOption::Api,
@@ -143,7 +144,9 @@ pub async fn run_api(
             "x-hanzo-llm-provider",
             "x-hanzo-original-tool-router-key",
             "ngrok-skip-browser-warning",
-        ]);
+            "x-payment",
+        ])
+        .expose_headers(vec!["x-payment-response"]);
 
     let v1_routes = warp::path("v1").and(
         api_v1::v1_routes(node_commands_sender.clone())
@@ -175,8 +178,18 @@ pub async fn run_api(
             .with(cors.clone()),
     );
 
+    let metrics_routes = metrics_route(node_commands_sender.clone(), metrics_enabled)
+        .recover(handle_rejection)
+        .with(log);
+
     // Combine all routes (avoid applying gzip compression globally so SSE is not compressed)
-    let routes = v1_routes.or(v2_routes).or(mcp_routes).or(ws_routes).with(log).with(cors);
+    let routes = v1_routes
+        .or(v2_routes)
+        .or(mcp_routes)
+        .or(ws_routes)
+        .or(metrics_routes)
+        .with(log)
+        .with(cors);
 
     // Wrap the HTTP server in an async block that returns a Result
     let http_server = async {
@@ -258,6 +271,44 @@ pub async fn run_api(
     Ok(())
 }
 
+/// GET /metrics in the Prometheus text format. Answers 404 unless metrics are enabled.
+fn metrics_route(
+    node_commands_sender: Sender<NodeCommand>,
+    metrics_enabled: bool,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::path("metrics")
+        .and(warp::path::end())
+        .and(warp::get())
+        .and(warp::any().map(move || node_commands_sender.clone()))
+        .and_then(move |sender: Sender<NodeCommand>| metrics_handler(sender, metrics_enabled))
+}
+
+async fn metrics_handler(
+    sender: Sender<NodeCommand>,
+    metrics_enabled: bool,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !metrics_enabled {
+        return Err(warp::reject::not_found());
+    }
+
+    let (res_sender, res_receiver) = async_channel::bounded(1);
+    sender
+        .send(NodeCommand::GetMetrics { res: res_sender })
+        .await
+        .map_err(|_| warp::reject::reject())?;
+
+    let result = res_receiver.recv().await.map_err(|_| warp::reject::reject())?;
+
+    match result {
+        Ok(metrics) => Ok(warp::reply::with_header(
+            metrics,
+            "Content-Type",
+            "text/plain; version=0.0.4",
+        )),
+        Err(error) => Err(warp::reject::custom(error)),
+    }
+}
+
 pub async fn handle_node_command<T, U, V>(
     node_commands_sender: Sender<NodeCommand>,
     message: V,
@@ -340,3 +391,32 @@ async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, warp
         Ok(warp::reply::with_status(json, StatusCode::INTERNAL_SERVER_ERROR))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metrics_route_is_gated() {
+        let (sender, receiver) = async_channel::unbounded();
+
+        let disabled = warp::test::request()
+            .path("/metrics")
+            .reply(&metrics_route(sender.clone(), false))
+            .await;
+        assert_eq!(disabled.status().as_u16(), 404);
+        assert!(receiver.is_empty());
+
+        tokio::spawn(async move {
+            if let Ok(NodeCommand::GetMetrics { res }) = receiver.recv().await {
+                let _ = res.send(Ok("hanzo_up 1\n".to_string())).await;
+            }
+        });
+        let enabled = warp::test::request()
+            .path("/metrics")
+            .reply(&metrics_route(sender, true))
+            .await;
+        assert_eq!(enabled.status().as_u16(), 200);
+        assert_eq!(enabled.body().as_ref(), b"hanzo_up 1\n");
+    }
+}
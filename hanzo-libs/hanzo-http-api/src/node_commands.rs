@@ -9,10 +9,12 @@ use hanzo_messages::{
         coinbase_mpc_config::CoinbaseMPCWalletConfig,
         crontab::{CronTask, CronTaskAction},
         custom_prompt::CustomPrompt,
+        hanzo_fs::ChunkingStrategy,
         identity::{Identity, StandardIdentity},
         job_config::JobConfig,
         llm_providers::{agent::Agent, serialized_llm_provider::SerializedLLMProvider, hanzo_backend::QuotaResponse},
-        mcp_server::MCPServer,
+        llm_usage::{LLMModelPrice, LLMUsageQuery, LLMUsageSummary},
+        mcp_server::{MCPSamplingPolicy, MCPServer},
         hanzo_name::HanzoName,
         hanzo_tool_offering::{HanzoToolOffering, UsageTypeInquiry},
         hanzo_tools::{CodeLanguage, DynamicToolType},
@@ -20,14 +22,18 @@ use hanzo_messages::{
         tool_router_key::ToolRouterKey,
         wallet_complementary::{WalletRole, WalletSource},
         wallet_mixed::NetworkIdentifier,
+        wallet_spending_policy::{SpendingLedgerEntry, WalletSpendingPolicy},
+        x402_paywall::{PaywallDecision, PaywallPrice, PaywallResource, X402Receipt},
         x402_types::Network,
     },
     hanzo_message::{
         hanzo_message::HanzoMessage,
         hanzo_message_schemas::{
             APIAddOllamaModels, APIChangeJobAgentRequest, APIVecFsCopyFolder, APIVecFsCopyItem, APIVecFsCreateFolder,
-            APIVecFsDeleteFolder, APIVecFsDeleteItem, APIVecFsMoveFolder, APIVecFsMoveItem,
-            APIVecFsRetrievePathSimplifiedJson, APIVecFsRetrieveSourceFile, APIVecFsSearchItems,
+            APIVecFsDeleteFolder, APIVecFsDeleteItem, APIVecFsGetFolderChunkingStrategy, APIVecFsMoveFolder,
+            APIVecFsMoveItem, APIVecFsRetrievePathSimplifiedJson, APIVecFsRetrieveSourceFile, APIVecFsSearchItems,
+            APIVecFsSetFolderChunkingStrategy, APIVecFsAddWatchedFolder, APIVecFsRemoveWatchedFolder,
+            APIVecFsSyncWatchedFolder,
             ExportInboxMessagesFormat, IdentityPermissions, JobCreationInfo, JobMessage, RegistrationCodeType,
             V2ChatMessage,
         },
@@ -39,6 +45,7 @@ use hanzo_tools::tools::{
     mcp_server_tool::MCPServerTool,
     hanzo_tool::{HanzoTool, HanzoToolHeader, HanzoToolWithAssets},
     tool_config::OAuth,
+    tool_permissions::ToolPermissions,
     tool_playground::ToolPlayground,
     tool_types::{OperatingSystem, RunnerType},
 };
@@ -79,6 +86,10 @@ pub enum NodeCommand {
     GetNodeName {
         res: Sender<String>,
     },
+    // Command to export the node metrics in the Prometheus text format
+    GetMetrics {
+        res: Sender<Result<String, APIError>>,
+    },
     // Command to make the node create a registration code through the API. The sender will receive the code.
     APICreateRegistrationCode {
         msg: HanzoMessage,
@@ -371,6 +382,7 @@ pub enum NodeCommand {
         file: Vec<u8>,
         path: String,
         file_datetime: Option<DateTime<Utc>>,
+        chunking_strategy: Option<ChunkingStrategy>,
         res: Sender<Result<Value, APIError>>,
     },
     V2ApiUploadFileToJob {
@@ -379,6 +391,36 @@ pub enum NodeCommand {
         filename: String,
         file: Vec<u8>,
         file_datetime: Option<DateTime<Utc>>,
+        chunking_strategy: Option<ChunkingStrategy>,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiSetFolderChunkingStrategy {
+        bearer: String,
+        payload: APIVecFsSetFolderChunkingStrategy,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetFolderChunkingStrategy {
+        bearer: String,
+        payload: APIVecFsGetFolderChunkingStrategy,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiAddWatchedFolder {
+        bearer: String,
+        payload: APIVecFsAddWatchedFolder,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiRemoveWatchedFolder {
+        bearer: String,
+        payload: APIVecFsRemoveWatchedFolder,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiListWatchedFolders {
+        bearer: String,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiSyncWatchedFolder {
+        bearer: String,
+        payload: APIVecFsSyncWatchedFolder,
         res: Sender<Result<Value, APIError>>,
     },
     V2ApiRetrieveFile {
@@ -406,6 +448,16 @@ pub enum NodeCommand {
         category: Option<String>,
         res: Sender<Result<Value, APIError>>,
     },
+    V2ApiListAllMcpPrompts {
+        res: Sender<Result<Vec<CustomPrompt>, APIError>>,
+    },
+    V2ApiListAllMcpResources {
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiReadMcpResource {
+        path: String,
+        res: Sender<Result<Value, APIError>>,
+    },
     V2ApiListAllNetworkHanzoTools {
         bearer: String,
         res: Sender<Result<Value, APIError>>,
@@ -516,6 +568,25 @@ pub enum NodeCommand {
         bearer: String,
         res: Sender<Result<serde_json::Value, APIError>>,
     },
+    V2ApiGetLLMUsage {
+        bearer: String,
+        payload: LLMUsageQuery,
+        res: Sender<Result<Vec<LLMUsageSummary>, APIError>>,
+    },
+    V2ApiGetLLMModelPrices {
+        bearer: String,
+        res: Sender<Result<Vec<LLMModelPrice>, APIError>>,
+    },
+    V2ApiSetLLMModelPrice {
+        bearer: String,
+        payload: LLMModelPrice,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiRemoveLLMModelPrice {
+        bearer: String,
+        model: String,
+        res: Sender<Result<(), APIError>>,
+    },
     V2ApiDockerStatus {
         res: Sender<Result<serde_json::Value, APIError>>,
     },
@@ -626,6 +697,57 @@ pub enum NodeCommand {
         bearer: String,
         res: Sender<Result<Value, APIError>>,
     },
+    V2ApiGetWalletSpendingPolicy {
+        bearer: String,
+        res: Sender<Result<WalletSpendingPolicy, APIError>>,
+    },
+    V2ApiSetWalletSpendingPolicy {
+        bearer: String,
+        policy: WalletSpendingPolicy,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiGetWalletSpendingLedger {
+        bearer: String,
+        invoice_id: Option<String>,
+        limit: Option<u64>,
+        res: Sender<Result<Vec<SpendingLedgerEntry>, APIError>>,
+    },
+    V2ApiGetX402Prices {
+        bearer: String,
+        res: Sender<Result<Vec<PaywallPrice>, APIError>>,
+    },
+    V2ApiSetX402Price {
+        bearer: String,
+        price: PaywallPrice,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiRemoveX402Price {
+        bearer: String,
+        resource: PaywallResource,
+        res: Sender<Result<(), APIError>>,
+    },
+    V2ApiGetX402Receipts {
+        bearer: String,
+        resource: Option<PaywallResource>,
+        limit: Option<u64>,
+        res: Sender<Result<Vec<X402Receipt>, APIError>>,
+    },
+    // The x402 commands below are sent for plain HTTP and MCP clients, which pay instead of using a bearer
+    V2ApiCheckX402Payment {
+        resource: PaywallResource,
+        payment: Option<String>,
+        res: Sender<Result<PaywallDecision, APIError>>,
+    },
+    V2ApiSettleX402Payment {
+        receipt_id: String,
+        served: bool,
+        res: Sender<Result<Option<String>, APIError>>,
+    },
+    V2ApiX402AgentChat {
+        agent_id: String,
+        message: String,
+        res: Sender<Result<Value, APIError>>,
+    },
     V2ApiAddCustomPrompt {
         bearer: String,
         prompt: CustomPrompt,
@@ -756,6 +878,7 @@ pub enum NodeCommand {
         mounts: Option<Vec<String>>,
         runner: Option<RunnerType>,
         operating_system: Option<Vec<OperatingSystem>>,
+        permissions: Option<ToolPermissions>,
         res: Sender<Result<Value, APIError>>,
     },
     V2ApiGenerateToolDefinitions {
@@ -1138,6 +1261,27 @@ pub enum NodeCommand {
         is_enabled: bool,
         res: Sender<Result<MCPServer, APIError>>,
     },
+    V2ApiImportMCPServerPrompts {
+        bearer: String,
+        mcp_server_id: i64,
+        res: Sender<Result<Vec<CustomPrompt>, APIError>>,
+    },
+    V2ApiListMCPServerResources {
+        bearer: String,
+        mcp_server_id: i64,
+        res: Sender<Result<Value, APIError>>,
+    },
+    V2ApiGetMCPServerSamplingPolicy {
+        bearer: String,
+        mcp_server_id: i64,
+        res: Sender<Result<MCPSamplingPolicy, APIError>>,
+    },
+    V2ApiSetMCPServerSamplingPolicy {
+        bearer: String,
+        mcp_server_id: i64,
+        policy: MCPSamplingPolicy,
+        res: Sender<Result<MCPSamplingPolicy, APIError>>,
+    },
     V2ApiSetNgrokAuthToken {
         bearer: String,
         auth_token: String,
@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use rmcp::{
+    model::{
+        ClientCapabilities, ClientInfo, CreateMessageRequestMethod, CreateMessageRequestParam, CreateMessageResult,
+        ErrorData, Implementation, JsonObject,
+    },
+    service::RequestContext,
+    ClientHandler, RoleClient,
+};
+use std::sync::Arc;
+
+/// A `sampling/createMessage` request received from an MCP server.
+#[derive(Debug, Clone)]
+pub struct McpSamplingRequest {
+    /// Session key of the server asking, i.e. `MCPServer::get_command_hash()`.
+    pub server_key: String,
+    /// Job the session belongs to, if any. Sessions opened for a job's tool calls are never
+    /// shared with other jobs, so the request can only come from that job's calls.
+    pub job_id: Option<String>,
+    pub params: CreateMessageRequestParam,
+}
+
+/// Answers sampling requests sent by MCP servers, usually by running them through an LLM provider.
+/// Deciding whether a server is allowed to sample is up to the implementation.
+#[async_trait]
+pub trait McpSamplingHandler: Send + Sync {
+    async fn create_message(&self, request: McpSamplingRequest) -> Result<CreateMessageResult, ErrorData>;
+}
+
+/// Client side of an MCP session. Besides identifying the node, it forwards sampling requests
+/// to the registered `McpSamplingHandler`, if any.
+pub struct HanzoMcpClient {
+    server_key: String,
+    job_id: Option<String>,
+    sampling_handler: Option<Arc<dyn McpSamplingHandler>>,
+}
+
+impl HanzoMcpClient {
+    pub fn new(
+        server_key: String,
+        job_id: Option<String>,
+        sampling_handler: Option<Arc<dyn McpSamplingHandler>>,
+    ) -> Self {
+        Self {
+            server_key,
+            job_id,
+            sampling_handler,
+        }
+    }
+}
+
+impl ClientHandler for HanzoMcpClient {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo {
+            protocol_version: Default::default(),
+            capabilities: ClientCapabilities {
+                // Only advertise sampling when someone can answer it
+                sampling: self.sampling_handler.as_ref().map(|_| JsonObject::new()),
+                ..Default::default()
+            },
+            client_info: Implementation {
+                name: "hanzo_node_client".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                icons: None,
+                title: None,
+                website_url: None,
+            },
+        }
+    }
+
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        let Some(handler) = self.sampling_handler.clone() else {
+            return Err(ErrorData::method_not_found::<CreateMessageRequestMethod>());
+        };
+        log::info!(
+            "mcp server {} requested sampling (job: {:?})",
+            self.server_key,
+            self.job_id
+        );
+        handler
+            .create_message(McpSamplingRequest {
+                server_key: self.server_key.clone(),
+                job_id: self.job_id.clone(),
+                params,
+            })
+            .await
+    }
+}
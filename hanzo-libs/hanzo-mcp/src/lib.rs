@@ -1,4 +1,6 @@
+pub mod client_handler;
 mod command;
 pub mod error;
 pub mod mcp_methods;
+pub mod session_manager;
 mod utils;
@@ -0,0 +1,689 @@
+use crate::{
+    client_handler::{HanzoMcpClient, McpSamplingHandler},
+    command::CommandWrappedInShellBuilder,
+    error::McpError,
+    utils::disect_command,
+};
+
+type Result<T> = std::result::Result<T, McpError>;
+use once_cell::sync::Lazy;
+use rmcp::{
+    model::{
+        CallToolRequestParam, CallToolResult, ClientRequest, GetPromptRequestParam, GetPromptResult, PingRequest,
+        Prompt, ReadResourceRequestParam, ReadResourceResult, Resource, Tool,
+    },
+    service::{Peer, RoleClient, RunningService, ServiceError},
+    transport::{SseClientTransport, StreamableHttpClientTransport, TokioChildProcess},
+    ServiceExt,
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, RwLock, Weak,
+    },
+    time::{Duration, Instant},
+};
+use tokio::process::Command;
+
+pub type McpClientService = RunningService<RoleClient, HanzoMcpClient>;
+
+/// Process-wide session manager used by the node to talk to MCP servers.
+pub static MCP_SESSION_MANAGER: Lazy<Arc<McpSessionManager>> =
+    Lazy::new(|| Arc::new(McpSessionManager::new(McpSessionConfig::default())));
+
+/// How to reach an MCP server. Two targets are the same session only if they compare equal,
+/// so changing the env of a command server restarts its process.
+#[derive(Debug, Clone, PartialEq)]
+pub enum McpSessionTarget {
+    Command {
+        command: String,
+        env: HashMap<String, String>,
+    },
+    Sse {
+        url: String,
+    },
+    Http {
+        url: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct McpSessionConfig {
+    /// Sessions not used for this long are shut down by the health check loop.
+    pub idle_timeout: Duration,
+    /// How often idle sessions are reaped and live ones are pinged.
+    pub health_check_interval: Duration,
+    /// Maximum time a ping may take before the session is considered unhealthy.
+    pub ping_timeout: Duration,
+    /// Maximum number of sessions kept alive at the same time.
+    pub max_sessions: usize,
+    /// How many consecutive times the health check restarts a crashed session before giving up.
+    /// A session given up on is restarted again on its next use.
+    pub max_restarts: u32,
+}
+
+impl Default for McpSessionConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Duration::from_secs(10 * 60),
+            health_check_interval: Duration::from_secs(30),
+            ping_timeout: Duration::from_secs(10),
+            max_sessions: 16,
+            max_restarts: 3,
+        }
+    }
+}
+
+struct McpSession {
+    service: McpClientService,
+    target: McpSessionTarget,
+    // Server key and job the session was opened for, see `McpSessionManager::session_key`
+    server_key: String,
+    job_id: Option<String>,
+    last_used: Instant,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl McpSession {
+    fn is_busy(&self) -> bool {
+        self.in_flight.load(Ordering::SeqCst) > 0
+    }
+
+    fn is_closed(&self) -> bool {
+        self.service.peer().is_transport_closed()
+    }
+}
+
+#[derive(Default)]
+struct SessionSlot {
+    session: Option<McpSession>,
+    restarts: u32,
+}
+
+type Slot = Arc<tokio::sync::Mutex<SessionSlot>>;
+
+/// A peer handed out for a single request. While alive, the session it belongs to is neither
+/// reaped for idleness nor evicted to make room for another session.
+struct SessionLease {
+    peer: Peer<RoleClient>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for SessionLease {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Keeps MCP client sessions alive between calls, keyed by `MCPServer::get_command_hash()`.
+/// Tool calls made for a job get a session of their own, so sampling requests the server sends
+/// during them are attributed to that job.
+///
+/// Sessions are created lazily on first use, shared by concurrent callers, restarted when
+/// their transport dies and shut down after `idle_timeout` without use.
+pub struct McpSessionManager {
+    config: McpSessionConfig,
+    slots: Mutex<HashMap<String, Slot>>,
+    health_check_started: AtomicBool,
+    sampling_handler: RwLock<Option<Arc<dyn McpSamplingHandler>>>,
+}
+
+impl McpSessionManager {
+    pub fn new(config: McpSessionConfig) -> Self {
+        Self {
+            config,
+            slots: Mutex::new(HashMap::new()),
+            health_check_started: AtomicBool::new(false),
+            sampling_handler: RwLock::new(None),
+        }
+    }
+
+    /// Sets who answers `sampling/createMessage` requests. Only sessions connected afterwards
+    /// advertise sampling, so this is meant to be called once at startup.
+    pub fn set_sampling_handler(&self, handler: Arc<dyn McpSamplingHandler>) {
+        *self.sampling_handler.write().unwrap() = Some(handler);
+    }
+
+    pub fn config(&self) -> &McpSessionConfig {
+        &self.config
+    }
+
+    /// Number of sessions currently connected.
+    pub async fn active_sessions(&self) -> usize {
+        let mut count = 0;
+        for (_, slot) in self.all_slots() {
+            if slot.lock().await.session.is_some() {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    pub async fn list_tools(self: &Arc<Self>, key: &str, target: &McpSessionTarget) -> Result<Vec<Tool>> {
+        self.with_peer(key, target, |peer| async move { peer.list_all_tools().await })
+            .await
+    }
+
+    /// Calls a tool on the session of `job_id`, if set. Not retried on a lost transport, as the tool may already have run.
+    pub async fn call_tool(
+        self: &Arc<Self>,
+        key: &str,
+        target: &McpSessionTarget,
+        tool: String,
+        parameters: serde_json::Map<String, serde_json::Value>,
+        job_id: Option<String>,
+    ) -> Result<CallToolResult> {
+        self.with_job_peer(key, target, job_id, false, |peer| {
+            let request = CallToolRequestParam {
+                name: tool.clone().into(),
+                arguments: Some(parameters.clone()),
+            };
+            async move { peer.call_tool(request).await }
+        })
+        .await
+    }
+
+    pub async fn list_prompts(self: &Arc<Self>, key: &str, target: &McpSessionTarget) -> Result<Vec<Prompt>> {
+        self.with_peer(key, target, |peer| async move { peer.list_all_prompts().await })
+            .await
+    }
+
+    pub async fn get_prompt(
+        self: &Arc<Self>,
+        key: &str,
+        target: &McpSessionTarget,
+        name: String,
+        arguments: Option<serde_json::Map<String, serde_json::Value>>,
+    ) -> Result<GetPromptResult> {
+        self.with_peer(key, target, |peer| {
+            let request = GetPromptRequestParam {
+                name: name.clone(),
+                arguments: arguments.clone(),
+            };
+            async move { peer.get_prompt(request).await }
+        })
+        .await
+    }
+
+    pub async fn list_resources(self: &Arc<Self>, key: &str, target: &McpSessionTarget) -> Result<Vec<Resource>> {
+        self.with_peer(key, target, |peer| async move { peer.list_all_resources().await })
+            .await
+    }
+
+    pub async fn read_resource(
+        self: &Arc<Self>,
+        key: &str,
+        target: &McpSessionTarget,
+        uri: String,
+    ) -> Result<ReadResourceResult> {
+        self.with_peer(key, target, |peer| {
+            let request = ReadResourceRequestParam { uri: uri.clone() };
+            async move { peer.read_resource(request).await }
+        })
+        .await
+    }
+
+    /// Runs `f` against the session for `key`, connecting it first if needed. If the session
+    /// turns out to be dead, it is restarted and `f` is retried once, so `f` must be safe to
+    /// send twice.
+    pub async fn with_peer<F, Fut, T>(self: &Arc<Self>, key: &str, target: &McpSessionTarget, f: F) -> Result<T>
+    where
+        F: Fn(Peer<RoleClient>) -> Fut,
+        Fut: Future<Output = std::result::Result<T, ServiceError>>,
+    {
+        self.with_job_peer(key, target, None, true, f).await
+    }
+
+    async fn with_job_peer<F, Fut, T>(
+        self: &Arc<Self>,
+        key: &str,
+        target: &McpSessionTarget,
+        job_id: Option<String>,
+        retry: bool,
+        f: F,
+    ) -> Result<T>
+    where
+        F: Fn(Peer<RoleClient>) -> Fut,
+        Fut: Future<Output = std::result::Result<T, ServiceError>>,
+    {
+        let lease = self.acquire(key, target, job_id.as_deref()).await?;
+        match f(lease.peer.clone()).await {
+            Err(e @ (ServiceError::TransportClosed | ServiceError::TransportSend(_))) => {
+                // The transport may be broken without being reported closed yet, drop the session anyway
+                let in_flight = lease.in_flight.clone();
+                drop(lease);
+                let session_key = Self::session_key(key, job_id.as_deref());
+                self.invalidate(&session_key, &in_flight).await;
+                if !retry {
+                    return Err(McpError {
+                        message: format!("{}", e),
+                    });
+                }
+                log::warn!("mcp session {} lost its transport, restarting it", key);
+                let lease = self.acquire(key, target, job_id.as_deref()).await?;
+                f(lease.peer.clone()).await.map_err(|e| McpError {
+                    message: format!("{}", e),
+                })
+            }
+            result => result.map_err(|e| McpError {
+                message: format!("{}", e),
+            }),
+        }
+    }
+
+    /// Shuts down the sessions for `key`, including the ones opened for jobs. Used when a server
+    /// is updated, disabled or removed.
+    pub async fn close(&self, key: &str) {
+        let job_prefix = Self::session_key(key, Some(""));
+        let slots: Vec<(String, Slot)> = {
+            let mut slots = self.slots.lock().unwrap();
+            let keys: Vec<String> = slots
+                .keys()
+                .filter(|k| *k == key || k.starts_with(&job_prefix))
+                .cloned()
+                .collect();
+            keys.into_iter()
+                .filter_map(|k| slots.remove(&k).map(|slot| (k, slot)))
+                .collect()
+        };
+        for (session_key, slot) in slots {
+            let session = slot.lock().await.session.take();
+            if let Some(session) = session {
+                Self::shutdown(&session_key, session).await;
+            }
+        }
+    }
+
+    pub async fn close_all(&self) {
+        let keys: Vec<String> = self.slots.lock().unwrap().keys().cloned().collect();
+        for key in keys {
+            self.close(&key).await;
+        }
+    }
+
+    /// Key of the slot holding the session for server `key`, or for its calls made by `job_id`.
+    fn session_key(key: &str, job_id: Option<&str>) -> String {
+        match job_id {
+            Some(job_id) => format!("{}#job:{}", key, job_id),
+            None => key.to_string(),
+        }
+    }
+
+    fn slot(&self, key: &str) -> Slot {
+        self.slots.lock().unwrap().entry(key.to_string()).or_default().clone()
+    }
+
+    fn all_slots(&self) -> Vec<(String, Slot)> {
+        self.slots
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key, slot)| (key.clone(), slot.clone()))
+            .collect()
+    }
+
+    async fn acquire(
+        self: &Arc<Self>,
+        key: &str,
+        target: &McpSessionTarget,
+        job_id: Option<&str>,
+    ) -> Result<SessionLease> {
+        self.start_health_checks();
+
+        let server_key = key;
+        let key = &Self::session_key(server_key, job_id);
+        let slot = self.slot(key);
+        let mut state = slot.lock().await;
+
+        let reusable = state
+            .session
+            .as_ref()
+            .map(|s| &s.target == target && !s.is_closed())
+            .unwrap_or(false);
+        if !reusable {
+            if let Some(stale) = state.session.take() {
+                if stale.target == *target {
+                    log::warn!("mcp session {} crashed, restarting it", key);
+                    state.restarts += 1;
+                } else {
+                    log::info!("mcp session {} configuration changed, restarting it", key);
+                }
+                Self::shutdown(key, stale).await;
+            }
+            self.make_room(key).await?;
+            let service = self.connect(server_key, job_id, target).await?;
+            state.session = Some(McpSession {
+                service,
+                target: target.clone(),
+                server_key: server_key.to_string(),
+                job_id: job_id.map(str::to_string),
+                last_used: Instant::now(),
+                in_flight: Arc::new(AtomicUsize::new(0)),
+            });
+        }
+
+        let session = state.session.as_mut().expect("session was just ensured");
+        session.last_used = Instant::now();
+        session.in_flight.fetch_add(1, Ordering::SeqCst);
+        Ok(SessionLease {
+            peer: session.service.peer().clone(),
+            in_flight: session.in_flight.clone(),
+        })
+    }
+
+    /// Drops a session whose transport failed so that the next `acquire` reconnects it. `in_flight`
+    /// identifies the failed session, which another caller may already have replaced.
+    async fn invalidate(&self, key: &str, in_flight: &Arc<AtomicUsize>) {
+        let slot = self.slot(key);
+        let mut state = slot.lock().await;
+        let failed = state
+            .session
+            .as_ref()
+            .map(|s| Arc::ptr_eq(&s.in_flight, in_flight))
+            .unwrap_or(false);
+        if failed {
+            if let Some(session) = state.session.take() {
+                state.restarts += 1;
+                Self::shutdown(key, session).await;
+            }
+        }
+    }
+
+    /// Evicts the least recently used idle session when the cap is reached.
+    async fn make_room(&self, requesting_key: &str) -> Result<()> {
+        let mut live = 0;
+        let mut lru: Option<(Instant, String, Slot)> = None;
+        for (key, slot) in self.all_slots() {
+            if key == requesting_key {
+                continue;
+            }
+            // A locked slot is connecting or being used, so it counts as live but is not evictable
+            let Ok(state) = slot.try_lock() else {
+                live += 1;
+                continue;
+            };
+            let Some(session) = state.session.as_ref() else {
+                continue;
+            };
+            live += 1;
+            if session.is_busy() {
+                continue;
+            }
+            if lru.as_ref().map(|(t, _, _)| session.last_used < *t).unwrap_or(true) {
+                lru = Some((session.last_used, key.clone(), slot.clone()));
+            }
+        }
+
+        if live < self.config.max_sessions {
+            return Ok(());
+        }
+        match lru {
+            Some((_, key, slot)) => {
+                log::info!("mcp session limit reached, closing least recently used session {}", key);
+                if let Some(session) = slot.lock().await.session.take() {
+                    Self::shutdown(&key, session).await;
+                }
+                Ok(())
+            }
+            None => Err(McpError {
+                message: format!(
+                    "MCP session limit reached ({} sessions in use)",
+                    self.config.max_sessions
+                ),
+            }),
+        }
+    }
+
+    async fn connect(&self, key: &str, job_id: Option<&str>, target: &McpSessionTarget) -> Result<McpClientService> {
+        let sampling_handler = self.sampling_handler.read().unwrap().clone();
+        let client_info = HanzoMcpClient::new(key.to_string(), job_id.map(str::to_string), sampling_handler);
+        let service = match target {
+            McpSessionTarget::Command { command, env } => {
+                let (command_envs, cmd_executable, cmd_args) = disect_command(command.clone());
+                let (adapted_program, adapted_args, adapted_envs) =
+                    CommandWrappedInShellBuilder::wrap_in_shell_as_values(
+                        cmd_executable,
+                        Some(cmd_args),
+                        Some(command_envs),
+                    );
+                let mut cmd = Command::new(adapted_program);
+                cmd.kill_on_drop(true);
+                cmd.envs(adapted_envs);
+                cmd.envs(env.clone());
+                cmd.args(adapted_args);
+                let transport = TokioChildProcess::new(cmd).map_err(|e| McpError {
+                    message: format!("{}", e),
+                })?;
+                client_info.serve(transport).await
+            }
+            McpSessionTarget::Sse { url } => {
+                let transport = SseClientTransport::start(url.clone()).await.map_err(|e| McpError {
+                    message: format!("{}", e),
+                })?;
+                client_info.serve(transport).await
+            }
+            McpSessionTarget::Http { url } => {
+                let transport = StreamableHttpClientTransport::from_uri(url.clone());
+                client_info.serve(transport).await
+            }
+        };
+        let service = service.map_err(|e| McpError {
+            message: format!("MCP client connection error: {:?}", e),
+        })?;
+        log::info!("connected to mcp server: {:?}", service.peer_info());
+        Ok(service)
+    }
+
+    async fn shutdown(key: &str, session: McpSession) {
+        let _ = session
+            .service
+            .cancel()
+            .await
+            .inspect_err(|e| log::error!("error cancelling mcp session {}: {:?}", key, e));
+    }
+
+    fn start_health_checks(self: &Arc<Self>) {
+        if self.health_check_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let manager: Weak<Self> = Arc::downgrade(self);
+        let interval = self.config.health_check_interval;
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                manager.run_health_check().await;
+            }
+        });
+    }
+
+    /// Reaps idle sessions, pings live ones and restarts the ones that crashed.
+    pub async fn run_health_check(&self) {
+        for (key, slot) in self.all_slots() {
+            let Ok(mut state) = slot.try_lock() else {
+                continue;
+            };
+            let Some(session) = state.session.as_ref() else {
+                continue;
+            };
+            if session.is_busy() {
+                continue;
+            }
+
+            if session.last_used.elapsed() >= self.config.idle_timeout {
+                log::info!("closing idle mcp session {}", key);
+                if let Some(session) = state.session.take() {
+                    Self::shutdown(&key, session).await;
+                }
+                continue;
+            }
+
+            let healthy = !session.is_closed()
+                && matches!(
+                    tokio::time::timeout(
+                        self.config.ping_timeout,
+                        session
+                            .service
+                            .peer()
+                            .send_request(ClientRequest::PingRequest(PingRequest::default())),
+                    )
+                    .await,
+                    Ok(Ok(_))
+                );
+            if healthy {
+                state.restarts = 0;
+                continue;
+            }
+
+            let session = state.session.take().expect("session checked above");
+            let target = session.target.clone();
+            let server_key = session.server_key.clone();
+            let job_id = session.job_id.clone();
+            Self::shutdown(&key, session).await;
+            if state.restarts >= self.config.max_restarts {
+                log::error!(
+                    "mcp session {} is unhealthy and exceeded {} restarts, leaving it down until next use",
+                    key,
+                    self.config.max_restarts
+                );
+                continue;
+            }
+            state.restarts += 1;
+            log::warn!("mcp session {} is unhealthy, restart attempt {}", key, state.restarts);
+            match self.connect(&server_key, job_id.as_deref(), &target).await {
+                Ok(service) => {
+                    state.session = Some(McpSession {
+                        service,
+                        target,
+                        server_key,
+                        job_id,
+                        last_used: Instant::now(),
+                        in_flight: Arc::new(AtomicUsize::new(0)),
+                    });
+                }
+                Err(e) => log::error!("failed to restart mcp session {}: {}", key, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_handler::McpSamplingRequest;
+    use async_trait::async_trait;
+    use rmcp::model::{Content, CreateMessageResult, ErrorData, Role, SamplingMessage};
+    use serde_json::json;
+
+    struct RecordingSamplingHandler {
+        requests: Mutex<Vec<McpSamplingRequest>>,
+    }
+
+    #[async_trait]
+    impl McpSamplingHandler for RecordingSamplingHandler {
+        async fn create_message(&self, request: McpSamplingRequest) -> std::result::Result<CreateMessageResult, ErrorData> {
+            self.requests.lock().unwrap().push(request);
+            Ok(CreateMessageResult {
+                model: "test-model".to_string(),
+                stop_reason: Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+                message: SamplingMessage {
+                    role: Role::Assistant,
+                    content: Content::text("sampled answer"),
+                },
+            })
+        }
+    }
+
+    fn everything_target() -> McpSessionTarget {
+        McpSessionTarget::Command {
+            command: "npx -y @modelcontextprotocol/server-everything@2025.9.12".to_string(),
+            env: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_is_reused_between_calls() {
+        let manager = Arc::new(McpSessionManager::new(McpSessionConfig::default()));
+        let target = everything_target();
+        let params = json!({ "a": 1, "b": 2 }).as_object().unwrap().clone();
+
+        let first = manager
+            .call_tool("everything", &target, "add".to_string(), params.clone(), None)
+            .await
+            .unwrap();
+        let second = manager
+            .call_tool("everything", &target, "add".to_string(), params, None)
+            .await
+            .unwrap();
+
+        assert!(first.content[0].as_text().unwrap().text.contains("3"));
+        assert!(second.content[0].as_text().unwrap().text.contains("3"));
+        assert_eq!(manager.active_sessions().await, 1);
+
+        manager.close("everything").await;
+        assert_eq!(manager.active_sessions().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_cap_evicts_least_recently_used() {
+        let manager = Arc::new(McpSessionManager::new(McpSessionConfig {
+            max_sessions: 1,
+            ..Default::default()
+        }));
+        let target = everything_target();
+
+        let tools = manager.list_tools("first", &target).await.unwrap();
+        assert!(!tools.is_empty());
+        let tools = manager.list_tools("second", &target).await.unwrap();
+        assert!(!tools.is_empty());
+
+        assert_eq!(manager.active_sessions().await, 1);
+        manager.close_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_idle_session_is_reaped() {
+        let manager = Arc::new(McpSessionManager::new(McpSessionConfig {
+            idle_timeout: Duration::from_millis(0),
+            ..Default::default()
+        }));
+        let target = everything_target();
+
+        manager.list_tools("everything", &target).await.unwrap();
+        manager.run_health_check().await;
+
+        assert_eq!(manager.active_sessions().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_sampling_request_is_answered_for_the_calling_job() {
+        let manager = Arc::new(McpSessionManager::new(McpSessionConfig::default()));
+        let handler = Arc::new(RecordingSamplingHandler {
+            requests: Mutex::new(Vec::new()),
+        });
+        manager.set_sampling_handler(handler.clone());
+        let target = everything_target();
+        let params = json!({ "prompt": "Say hi", "maxTokens": 20 }).as_object().unwrap().clone();
+
+        let result = manager
+            .call_tool("everything", &target, "sampleLLM".to_string(), params, Some("job-1".to_string()))
+            .await
+            .unwrap();
+
+        assert!(result.content[0].as_text().unwrap().text.contains("sampled answer"));
+        let requests = handler.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].server_key, "everything");
+        assert_eq!(requests[0].job_id.as_deref(), Some("job-1"));
+
+        // The job's session belongs to the server and goes away with it
+        manager.close("everything").await;
+        assert_eq!(manager.active_sessions().await, 0);
+    }
+}
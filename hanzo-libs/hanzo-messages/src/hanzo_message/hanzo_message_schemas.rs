@@ -1,3 +1,4 @@
+use crate::schemas::hanzo_fs::ChunkingStrategy;
 use crate::schemas::hanzo_tools::DynamicToolType;
 use crate::schemas::tool_router_key::ToolRouterKey;
 use crate::schemas::{inbox_name::InboxName, llm_providers::serialized_llm_provider::SerializedLLMProvider};
@@ -368,6 +369,39 @@ pub struct APIVecFsCreateFolder {
     pub folder_name: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct APIVecFsSetFolderChunkingStrategy {
+    pub path: String,
+    /// `None` clears the folder's strategy, so it uses the one of its parent folders again
+    pub chunking_strategy: Option<ChunkingStrategy>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct APIVecFsGetFolderChunkingStrategy {
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct APIVecFsAddWatchedFolder {
+    /// Absolute path of the directory on the host to mirror
+    pub host_path: String,
+    /// VecFS folder the directory is mirrored into
+    pub path: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct APIVecFsRemoveWatchedFolder {
+    pub id: i64,
+    /// Also delete the mirrored files (and their embeddings) from the VecFS
+    #[serde(default)]
+    pub remove_files: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct APIVecFsSyncWatchedFolder {
+    pub id: i64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
 pub struct APIVecFsDeleteFolder {
     pub path: String,
@@ -489,6 +523,7 @@ pub enum WSTopic {
     Sheet,
     SheetList,
     Widget,
+    EmbeddingMigration,
 }
 
 impl fmt::Display for WSTopic {
@@ -499,6 +534,7 @@ impl fmt::Display for WSTopic {
             WSTopic::Sheet => write!(f, "sheet"),
             WSTopic::SheetList => write!(f, "sheet_list"),
             WSTopic::Widget => write!(f, "widget"),
+            WSTopic::EmbeddingMigration => write!(f, "embedding_migration"),
         }
     }
 }
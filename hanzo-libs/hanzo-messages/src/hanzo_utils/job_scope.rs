@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use super::{search_mode::VectorSearchMode, hanzo_path::HanzoPath};
+use crate::schemas::mcp_server::MCPResourceRef;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
 pub struct MinimalJobScope {
@@ -9,6 +10,9 @@ pub struct MinimalJobScope {
     pub vector_fs_folders: Vec<HanzoPath>,
     #[serde(default = "default_vector_search_mode")]
     pub vector_search_mode: VectorSearchMode,
+    /// Resources of remote MCP servers, read when the job runs and added to its context.
+    #[serde(default)]
+    pub mcp_resources: Vec<MCPResourceRef>,
 }
 
 // Function to provide the default value for vector_search_mode
@@ -27,9 +31,9 @@ impl MinimalJobScope {
         serde_json::from_slice(bytes)
     }
 
-    /// Checks if both vector_fs_items and vector_fs_folders are empty.
+    /// Checks if vector_fs_items, vector_fs_folders and mcp_resources are all empty.
     pub fn is_empty(&self) -> bool {
-        self.vector_fs_items.is_empty() && self.vector_fs_folders.is_empty()
+        self.vector_fs_items.is_empty() && self.vector_fs_folders.is_empty() && self.mcp_resources.is_empty()
     }
 }
 
@@ -39,6 +43,7 @@ impl Default for MinimalJobScope {
             vector_fs_items: Vec::new(),
             vector_fs_folders: Vec::new(),
             vector_search_mode: VectorSearchMode::FillUpTo25k,
+            mcp_resources: Vec::new(),
         }
     }
 }
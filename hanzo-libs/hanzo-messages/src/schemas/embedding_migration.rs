@@ -0,0 +1,56 @@
+use core::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingMigrationStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+impl fmt::Display for EmbeddingMigrationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmbeddingMigrationStatus::Running => write!(f, "running"),
+            EmbeddingMigrationStatus::Completed => write!(f, "completed"),
+            EmbeddingMigrationStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl FromStr for EmbeddingMigrationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(EmbeddingMigrationStatus::Running),
+            "completed" => Ok(EmbeddingMigrationStatus::Completed),
+            "failed" => Ok(EmbeddingMigrationStatus::Failed),
+            _ => Err(format!("Invalid embedding migration status: {}", s)),
+        }
+    }
+}
+
+/// How many items of a vector table were already embedded with the target model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingMigrationTableProgress {
+    pub table: String,
+    pub total: u64,
+    pub migrated: u64,
+}
+
+/// State of the last embedding migration. While it runs, the vector tables keep serving the
+/// previous model and the new embeddings are written to shadow tables.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct EmbeddingMigrationProgress {
+    pub target_model: String,
+    pub status: EmbeddingMigrationStatus,
+    pub started_at: String,
+    pub updated_at: String,
+    pub error: Option<String>,
+    pub tables: Vec<EmbeddingMigrationTableProgress>,
+}
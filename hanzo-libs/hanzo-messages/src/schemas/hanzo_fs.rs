@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
 
 use crate::hanzo_utils::hanzo_path::HanzoPath;
 
@@ -32,6 +33,63 @@ pub struct ParsedFile {
     pub total_tokens: Option<i64>,
     /// The total number of characters in the file (if known).
     pub total_characters: Option<i64>,
+    /// The strategy the file was chunked with, so it can be re-indexed the same way.
+    /// `None` means the chunks are the text groups produced by the parser.
+    #[serde(default)]
+    pub chunking_strategy: Option<ChunkingStrategy>,
+}
+
+/// How the text of a file is cut into chunks before embedding.
+/// Sizes are in characters, except for `TokenBudget`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChunkingStrategy {
+    /// Chunks of up to `chunk_size` characters, cut at whitespace regardless of sentences or sections.
+    FixedSize { chunk_size: usize },
+    /// Whole sentences packed into chunks of up to `chunk_size` characters. Each chunk repeats the
+    /// last `overlap` sentences of the previous one.
+    Sentence { chunk_size: usize, overlap: usize },
+    /// Chunks never span two sections (Markdown headings, PDF pages). Every chunk of a section
+    /// starts with the section heading.
+    Section { chunk_size: usize },
+    /// Whole sentences packed into chunks of up to `max_tokens` tokens, counted with the tokenizer
+    /// of the embedding model.
+    TokenBudget { max_tokens: usize },
+}
+
+/// A directory of the host mirrored into a VecFS folder. The node keeps the folder in sync with the
+/// directory, adding, re-embedding and removing files as they change.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct WatchedFolder {
+    pub id: i64,
+    /// Absolute path of the directory on the host (e.g. "/home/me/project/docs").
+    pub host_path: String,
+    /// VecFS folder the directory is mirrored into (e.g. "project/docs").
+    pub vecfs_path: String,
+    /// RFC 3339 time the folder was registered.
+    pub created_at: String,
+    /// RFC 3339 time of the last sync, if any.
+    pub last_synced_at: Option<String>,
+    /// Error of the last sync, if it failed.
+    pub last_sync_error: Option<String>,
+}
+
+/// Outcome of a sync of a watched folder. Paths are relative to the watched directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct FolderSyncReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+    /// Files that could not be synced. They are retried on the next sync.
+    #[serde(default)]
+    pub failed: Vec<String>,
+}
+
+impl FolderSyncReport {
+    pub fn has_changes(&self) -> bool {
+        !self.added.is_empty() || !self.updated.is_empty() || !self.removed.is_empty()
+    }
 }
 
 /// Represents a chunk of a processed file.
@@ -45,6 +103,32 @@ pub struct HanzoFileChunk {
     pub position: i64,
     /// The text content of this particular chunk.
     pub content: String,
+    /// Metadata of the chunk set by the parser, e.g. the language, symbol and line range of source code.
+    #[serde(default)]
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+impl HanzoFileChunk {
+    /// Key of the programming language metadata of source code chunks
+    pub const LANGUAGE_METADATA_KEY: &'static str = "language";
+    /// Key of the symbol metadata of source code chunks (e.g. `fn main` or `class Parser > def parse`)
+    pub const SYMBOL_METADATA_KEY: &'static str = "symbol";
+    /// Key of the 1-based, inclusive line range metadata of source code chunks (e.g. `10-42`)
+    pub const LINE_RANGE_METADATA_KEY: &'static str = "lines";
+
+    /// Where the chunk comes from, e.g. `src/main.rs:10-42 (fn main)`, when the path of its file and its
+    /// line range are known. Used to let responses cite exact lines.
+    pub fn source_reference(&self, paths: Option<&HashMap<i64, HanzoPath>>) -> Option<String> {
+        let metadata = self.metadata.as_ref()?;
+        let lines = metadata.get(Self::LINE_RANGE_METADATA_KEY)?;
+        let path = paths?.get(&self.parsed_file_id)?;
+
+        let mut reference = format!("{}:{}", path.relative_path(), lines);
+        if let Some(symbol) = metadata.get(Self::SYMBOL_METADATA_KEY) {
+            reference.push_str(&format!(" ({})", symbol));
+        }
+        Some(reference)
+    }
 }
 
 /// Represents an embedding of a file chunk.
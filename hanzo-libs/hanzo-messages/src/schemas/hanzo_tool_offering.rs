@@ -8,12 +8,16 @@ use super::x402_types::PaymentRequirements;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq)]
 pub enum UsageTypeInquiry {
     PerUse,
+    Subscription,
+    PrepaidCredits,
 }
 
 impl fmt::Display for UsageTypeInquiry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             UsageTypeInquiry::PerUse => write!(f, "PerUse"),
+            UsageTypeInquiry::Subscription => write!(f, "Subscription"),
+            UsageTypeInquiry::PrepaidCredits => write!(f, "PrepaidCredits"),
         }
     }
 }
@@ -27,9 +31,10 @@ pub struct HanzoToolOffering {
 
 impl HanzoToolOffering {
     pub fn get_price_for_usage(&self, usage_type_inquiry: &UsageTypeInquiry) -> Option<&ToolPrice> {
-        match (usage_type_inquiry, &self.usage_type) {
-            (UsageTypeInquiry::PerUse, UsageType::PerUse(price)) => Some(price),
-            _ => None,
+        if &self.usage_type.inquiry() == usage_type_inquiry {
+            Some(self.usage_type.price())
+        } else {
+            None
         }
     }
 
@@ -51,12 +56,61 @@ impl HanzoToolOffering {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub enum UsageType {
     PerUse(ToolPrice),
+    /// Unlimited use of the tool for a period, paid up front
+    Subscription(SubscriptionTerms),
+    /// A bundle of calls paid up front, each use takes one credit
+    PrepaidCredits(PrepaidCreditsTerms),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SubscriptionTerms {
+    /// Price of one period
+    pub price: ToolPrice,
+    pub period_days: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct PrepaidCreditsTerms {
+    /// Price of the whole bundle
+    pub price: ToolPrice,
+    /// Number of calls in the bundle
+    pub credits: u64,
 }
 
 impl UsageType {
+    /// What a buyer pays: one call, one subscription period or one bundle of credits
+    pub fn price(&self) -> &ToolPrice {
+        match self {
+            UsageType::PerUse(price) => price,
+            UsageType::Subscription(terms) => &terms.price,
+            UsageType::PrepaidCredits(terms) => &terms.price,
+        }
+    }
+
+    pub fn inquiry(&self) -> UsageTypeInquiry {
+        match self {
+            UsageType::PerUse(_) => UsageTypeInquiry::PerUse,
+            UsageType::Subscription(_) => UsageTypeInquiry::Subscription,
+            UsageType::PrepaidCredits(_) => UsageTypeInquiry::PrepaidCredits,
+        }
+    }
+
+    pub fn is_free(&self) -> bool {
+        matches!(self.price(), ToolPrice::Free)
+    }
+
+    /// Whether buying it gives an entitlement covering later calls
+    pub fn grants_entitlement(&self) -> bool {
+        !matches!(self, UsageType::PerUse(_))
+    }
+
+    /// Price of a single call. For a subscription it is the price of the whole period, as the number
+    /// of calls is not known up front.
     pub fn per_use_usd_price(&self) -> f32 {
         match self {
             UsageType::PerUse(price) => price.to_usd_float(),
+            UsageType::Subscription(terms) => terms.price.to_usd_float(),
+            UsageType::PrepaidCredits(terms) => terms.price.to_usd_float() / terms.credits.max(1) as f32,
         }
     }
 }
@@ -135,4 +189,34 @@ mod tests {
         let result = offering.convert_tool_to_local();
         assert_eq!(result.unwrap(), "local:::toolkit1:::tool1");
     }
+
+    #[test]
+    fn test_subscription_and_prepaid_credits_usage_types() {
+        let offering = HanzoToolOffering {
+            tool_key: "node1:::toolkit1:::tool1".to_string(),
+            usage_type: UsageType::PrepaidCredits(PrepaidCreditsTerms {
+                price: ToolPrice::DirectDelegation("10".to_string()),
+                credits: 100,
+            }),
+            meta_description: None,
+        };
+
+        let json = serde_json::to_string(&offering).unwrap();
+        assert!(json.contains("\"PrepaidCredits\":{\"price\":{\"DirectDelegation\":\"10\"},\"credits\":100}"));
+        assert_eq!(serde_json::from_str::<HanzoToolOffering>(&json).unwrap(), offering);
+
+        assert!(offering
+            .get_price_for_usage(&UsageTypeInquiry::PrepaidCredits)
+            .is_some());
+        assert!(offering.get_price_for_usage(&UsageTypeInquiry::PerUse).is_none());
+        assert!(offering.usage_type.grants_entitlement());
+
+        let subscription = UsageType::Subscription(SubscriptionTerms {
+            price: ToolPrice::Free,
+            period_days: 30,
+        });
+        assert_eq!(subscription.inquiry(), UsageTypeInquiry::Subscription);
+        assert!(subscription.is_free());
+        assert!(!UsageType::PerUse(ToolPrice::Free).grants_entitlement());
+    }
 }
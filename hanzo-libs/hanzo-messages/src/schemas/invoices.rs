@@ -189,6 +189,8 @@ impl InternalInvoiceRequest {
 pub enum PaymentStatusEnum {
     Pending,
     Signed,
+    /// The settlement transaction is included in a block with enough confirmations.
+    Confirmed,
     Failed,
 }
 
@@ -203,6 +205,9 @@ pub struct Payment {
     pub date_paid: Option<String>,
     /// The status of the payment.
     pub status: PaymentStatusEnum,
+    /// The hash of the onchain transaction that settled the payment, once it is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
 }
 
 impl Payment {
@@ -218,6 +223,7 @@ impl Payment {
             invoice_id,
             date_paid,
             status,
+            transaction_hash: None,
         }
     }
 }
@@ -231,4 +237,16 @@ pub struct InvoiceRequestNetworkError {
     pub response_date_time: DateTime<Utc>,
     pub user_error_message: Option<String>,
     pub error_message: String,
+    /// Set when the requester can act on the error, None for any other failure
+    #[serde(default)]
+    pub error_kind: Option<InvoiceNetworkErrorKind>,
+}
+
+/// Failures of an invoice that a requester handles by itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq)]
+pub enum InvoiceNetworkErrorKind {
+    /// The provider has no entitlement of the requester to the tool
+    ToolEntitlementNotFound,
+    /// The subscription of the requester expired or its prepaid credits ran out
+    ToolEntitlementExhausted,
 }
@@ -17,6 +17,11 @@ pub struct JobConfig {
     pub thinking: Option<bool>,
     pub reasoning_effort: Option<String>,
     pub web_search_enabled: Option<bool>,
+    /// Weight (0.0 to 1.0) of keyword (BM25) matches against vector matches when retrieving file chunks.
+    /// Unset or 0.0 keeps plain vector search.
+    pub keyword_search_weight: Option<f64>,
+    /// Spending limits enforced before each LLM call of the inference chain
+    pub budget: Option<SpendingBudget>,
     // TODO: add ctx_...
 }
 
@@ -40,6 +45,8 @@ impl JobConfig {
             thinking: self.thinking.or(other.thinking),
             reasoning_effort: self.reasoning_effort.clone().or_else(|| other.reasoning_effort.clone()),
             web_search_enabled: self.web_search_enabled.or(other.web_search_enabled),
+            keyword_search_weight: self.keyword_search_weight.or(other.keyword_search_weight),
+            budget: self.budget.clone().or_else(|| other.budget.clone()),
             other_model_params: self
                 .other_model_params
                 .clone()
@@ -63,6 +70,8 @@ impl JobConfig {
             thinking: None,
             reasoning_effort: None,
             web_search_enabled: None,
+            keyword_search_weight: None,
+            budget: None,
         }
     }
 
@@ -81,10 +90,57 @@ impl JobConfig {
             thinking: None,
             reasoning_effort: None,
             web_search_enabled: None,
+            keyword_search_weight: None,
+            budget: None,
         }
     }
 }
 
+/// Spending limits of a job or an agent. Limits left unset are not enforced.
+/// Every limit of a job budget counts the usage of the whole job, and every limit of an agent
+/// budget the usage of the agent during the current UTC day, so agents woken up by cron tasks
+/// can't spend more than their daily allowance.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SpendingBudget {
+    /// Input plus output tokens, estimated for providers that don't report their usage
+    pub max_tokens: Option<u64>,
+    /// Cost in USD. Calls to models without a configured price count as free.
+    pub max_cost: Option<f64>,
+    /// Tool calls requested by the LLM
+    pub max_tool_calls: Option<u64>,
+}
+
+impl SpendingBudget {
+    /// Returns which limit has been reached, or None while there is budget left
+    pub fn exhausted_reason(&self, tokens: u64, cost: f64, tool_calls: u64) -> Option<String> {
+        if let Some(max_tokens) = self.max_tokens {
+            if tokens >= max_tokens {
+                return Some(format!(
+                    "token budget exhausted ({} of {} tokens used)",
+                    tokens, max_tokens
+                ));
+            }
+        }
+        if let Some(max_cost) = self.max_cost {
+            if cost >= max_cost {
+                return Some(format!(
+                    "cost budget exhausted (${:.4} of ${:.4} spent)",
+                    cost, max_cost
+                ));
+            }
+        }
+        if let Some(max_tool_calls) = self.max_tool_calls {
+            if tool_calls >= max_tool_calls {
+                return Some(format!(
+                    "tool call budget exhausted ({} of {} tool calls made)",
+                    tool_calls, max_tool_calls
+                ));
+            }
+        }
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,5 +179,33 @@ mod tests {
         assert_eq!(job_config.thinking, Some(true));
         assert_eq!(job_config.reasoning_effort, Some("medium".to_string()));
         assert_eq!(job_config.web_search_enabled, Some(false));
+        assert_eq!(job_config.keyword_search_weight, None);
+        assert_eq!(job_config.budget, None);
+    }
+
+    #[test]
+    fn test_spending_budget_exhausted_reason() {
+        let budget = SpendingBudget {
+            max_tokens: Some(10_000),
+            max_cost: Some(0.5),
+            max_tool_calls: None,
+        };
+
+        assert_eq!(budget.exhausted_reason(9_999, 0.49, 1_000), None);
+        assert!(budget
+            .exhausted_reason(10_000, 0.0, 0)
+            .unwrap()
+            .starts_with("token budget exhausted"));
+        assert!(budget
+            .exhausted_reason(0, 0.5, 0)
+            .unwrap()
+            .starts_with("cost budget exhausted"));
+
+        // A config without a budget inherits the one of the config it is merged with
+        let agent_config = JobConfig {
+            budget: Some(budget.clone()),
+            ..JobConfig::empty()
+        };
+        assert_eq!(JobConfig::empty().merge(&agent_config).budget, Some(budget));
     }
 }
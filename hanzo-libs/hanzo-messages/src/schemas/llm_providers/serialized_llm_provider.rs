@@ -33,6 +33,7 @@ impl SerializedLLMProvider {
             LLMProviderInterface::Claude(_) => "claude",
             LLMProviderInterface::DeepSeek(_) => "deepseek",
             LLMProviderInterface::LocalRegex(_) => "local-regex",
+            LLMProviderInterface::ProviderGroup(_) => "provider-group",
         }
         .to_string()
     }
@@ -52,6 +53,7 @@ impl SerializedLLMProvider {
             LLMProviderInterface::Claude(_) => "claude".to_string(),
             LLMProviderInterface::DeepSeek(_) => "openai-generic".to_string(),
             LLMProviderInterface::LocalRegex(_) => "local-regex".to_string(),
+            LLMProviderInterface::ProviderGroup(_) => "provider-group".to_string(),
         }
     }
 
@@ -70,6 +72,7 @@ impl SerializedLLMProvider {
             LLMProviderInterface::Claude(claude) => claude.model_type.clone(),
             LLMProviderInterface::DeepSeek(deepseek) => deepseek.model_type.clone(),
             LLMProviderInterface::LocalRegex(local_regex) => local_regex.model_type.clone(),
+            LLMProviderInterface::ProviderGroup(provider_group) => provider_group.model_type(),
         }
     }
 
@@ -133,6 +136,7 @@ pub enum LLMProviderInterface {
     Claude(Claude),
     DeepSeek(DeepSeek),
     LocalRegex(LocalRegex),
+    ProviderGroup(ProviderGroup),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
@@ -258,10 +262,106 @@ impl LocalRegex {
     }
 }
 
+/// A provider that wraps several configured providers, referenced by their ids, and picks the one
+/// that serves each inference call. Its model type is `<strategy>:<member>[=weight],...`, e.g.
+/// `weighted-round-robin:openai_gpt_4o=3,claude_sonnet=1`. Weights default to 1 and only matter
+/// for the weighted round-robin strategy.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct ProviderGroup {
+    pub strategy: ProviderGroupStrategy,
+    pub members: Vec<ProviderGroupMember>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProviderGroupStrategy {
+    /// Members are tried in the configured order
+    Failover,
+    /// Calls are spread over the members proportionally to their weight
+    WeightedRoundRobin,
+    /// The member with the lowest recent response time goes first
+    LowestLatency,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct ProviderGroupMember {
+    pub llm_provider_id: String,
+    pub weight: u32,
+}
+
+impl ProviderGroupStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderGroupStrategy::Failover => "failover",
+            ProviderGroupStrategy::WeightedRoundRobin => "weighted-round-robin",
+            ProviderGroupStrategy::LowestLatency => "lowest-latency",
+        }
+    }
+}
+
+impl ProviderGroup {
+    pub fn from_model_type(model_type: &str) -> Result<Self, String> {
+        let (strategy, members) = model_type
+            .split_once(':')
+            .ok_or_else(|| format!("Missing the members of the provider group: {}", model_type))?;
+        let strategy = match strategy.trim().to_lowercase().as_str() {
+            "failover" => ProviderGroupStrategy::Failover,
+            "weighted-round-robin" => ProviderGroupStrategy::WeightedRoundRobin,
+            "lowest-latency" => ProviderGroupStrategy::LowestLatency,
+            other => return Err(format!("Unknown provider group strategy: {}", other)),
+        };
+
+        let mut group_members = Vec::new();
+        for member in members.split(',').map(str::trim).filter(|member| !member.is_empty()) {
+            let (llm_provider_id, weight) = match member.split_once('=') {
+                Some((llm_provider_id, weight)) => {
+                    let weight = weight
+                        .trim()
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|weight| *weight > 0)
+                        .ok_or_else(|| format!("Invalid weight for provider group member: {}", member))?;
+                    (llm_provider_id.trim(), weight)
+                }
+                None => (member, 1),
+            };
+            group_members.push(ProviderGroupMember {
+                llm_provider_id: llm_provider_id.to_string(),
+                weight,
+            });
+        }
+        if group_members.is_empty() {
+            return Err("A provider group needs at least one member".to_string());
+        }
+
+        Ok(ProviderGroup {
+            strategy,
+            members: group_members,
+        })
+    }
+
+    pub fn model_type(&self) -> String {
+        let members = self
+            .members
+            .iter()
+            .map(|member| {
+                if member.weight == 1 {
+                    member.llm_provider_id.clone()
+                } else {
+                    format!("{}={}", member.llm_provider_id, member.weight)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+        format!("{}:{}", self.strategy.as_str(), members)
+    }
+}
+
 impl FromStr for LLMProviderInterface {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let original = s;
         let s = s.to_lowercase();
         if s.starts_with("openai:") {
             let model_type = s.strip_prefix("openai:").unwrap_or("").to_string();
@@ -302,6 +402,11 @@ impl FromStr for LLMProviderInterface {
         } else if s.starts_with("local-regex:") {
             let model_type = s.strip_prefix("local-regex:").unwrap_or("").to_string();
             Ok(LLMProviderInterface::LocalRegex(LocalRegex { model_type }))
+        } else if s.starts_with("provider-group:") {
+            // Member ids are case sensitive, so they are read from the original string
+            ProviderGroup::from_model_type(&original["provider-group:".len()..])
+                .map(LLMProviderInterface::ProviderGroup)
+                .map_err(|_| ())
         } else {
             Err(())
         }
@@ -366,6 +471,10 @@ impl Serialize for LLMProviderInterface {
                 let model_type = format!("local-regex:{}", local_regex.model_type);
                 serializer.serialize_str(&model_type)
             }
+            LLMProviderInterface::ProviderGroup(provider_group) => {
+                let model_type = format!("provider-group:{}", provider_group.model_type());
+                serializer.serialize_str(&model_type)
+            }
         }
     }
 }
@@ -424,6 +533,9 @@ impl<'de> Visitor<'de> for LLMProviderInterfaceVisitor {
             "local-regex" => Ok(LLMProviderInterface::LocalRegex(LocalRegex {
                 model_type: parts.get(1).unwrap_or(&"").to_string(),
             })),
+            "provider-group" => ProviderGroup::from_model_type(parts.get(1).unwrap_or(&""))
+                .map(LLMProviderInterface::ProviderGroup)
+                .map_err(de::Error::custom),
             _ => Err(de::Error::unknown_variant(
                 value,
                 &[
@@ -441,6 +553,7 @@ impl<'de> Visitor<'de> for LLMProviderInterfaceVisitor {
                     "claude",
                     "deepseek",
                     "local-regex",
+                    "provider-group",
                 ],
             )),
         }
@@ -455,3 +568,43 @@ impl<'de> Deserialize<'de> for LLMProviderInterface {
         deserializer.deserialize_str(LLMProviderInterfaceVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_group_serialization() {
+        let model: LLMProviderInterface =
+            serde_json::from_str("\"provider-group:weighted-round-robin:OpenAI_main=3, claude_backup\"").unwrap();
+        let LLMProviderInterface::ProviderGroup(group) = &model else {
+            panic!("expected a provider group, got {:?}", model);
+        };
+        assert_eq!(group.strategy, ProviderGroupStrategy::WeightedRoundRobin);
+        assert_eq!(
+            group.members,
+            vec![
+                ProviderGroupMember {
+                    llm_provider_id: "OpenAI_main".to_string(),
+                    weight: 3,
+                },
+                ProviderGroupMember {
+                    llm_provider_id: "claude_backup".to_string(),
+                    weight: 1,
+                },
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&model).unwrap(),
+            "\"provider-group:weighted-round-robin:OpenAI_main=3,claude_backup\""
+        );
+        assert_eq!(
+            "provider-group:weighted-round-robin:OpenAI_main=3,claude_backup".parse::<LLMProviderInterface>(),
+            Ok(model)
+        );
+
+        assert!(ProviderGroup::from_model_type("failover:").is_err());
+        assert!(ProviderGroup::from_model_type("random:a,b").is_err());
+        assert!(ProviderGroup::from_model_type("weighted-round-robin:a=0").is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Token counts of one inference call, normalized across providers.
+/// `input_tokens` includes the prompt tokens served from the provider cache (`cached_tokens`)
+/// and `output_tokens` includes the tokens spent on reasoning (`reasoning_tokens`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_tokens: u64,
+    pub reasoning_tokens: u64,
+}
+
+fn token_count(value: &Value, path: &[&str]) -> u64 {
+    path.iter()
+        .try_fold(value, |value, key| value.get(key))
+        .and_then(|count| count.as_u64())
+        .unwrap_or(0)
+}
+
+impl TokenUsage {
+    /// Parses the `usage` block of OpenAI compatible APIs (chat completions and responses)
+    pub fn from_openai_usage(usage: &Value) -> Option<Self> {
+        if usage.get("prompt_tokens").is_some() || usage.get("completion_tokens").is_some() {
+            Some(TokenUsage {
+                input_tokens: token_count(usage, &["prompt_tokens"]),
+                output_tokens: token_count(usage, &["completion_tokens"]),
+                cached_tokens: token_count(usage, &["prompt_tokens_details", "cached_tokens"]),
+                reasoning_tokens: token_count(usage, &["completion_tokens_details", "reasoning_tokens"]),
+            })
+        } else if usage.get("input_tokens").is_some() || usage.get("output_tokens").is_some() {
+            Some(TokenUsage {
+                input_tokens: token_count(usage, &["input_tokens"]),
+                output_tokens: token_count(usage, &["output_tokens"]),
+                cached_tokens: token_count(usage, &["input_tokens_details", "cached_tokens"]),
+                reasoning_tokens: token_count(usage, &["output_tokens_details", "reasoning_tokens"]),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Parses the `usage` block of the Anthropic messages API. Anthropic reports the tokens read
+    /// from and written to the prompt cache apart from `input_tokens`, so they are added back.
+    pub fn from_anthropic_usage(usage: &Value) -> Option<Self> {
+        if !usage.is_object() {
+            return None;
+        }
+        let cached_tokens = token_count(usage, &["cache_read_input_tokens"]);
+        Some(TokenUsage {
+            input_tokens: token_count(usage, &["input_tokens"])
+                + token_count(usage, &["cache_creation_input_tokens"])
+                + cached_tokens,
+            output_tokens: token_count(usage, &["output_tokens"]),
+            cached_tokens,
+            reasoning_tokens: 0,
+        })
+    }
+
+    /// Parses the `usageMetadata` block of the Gemini API, where thoughts are not part of the candidates count
+    pub fn from_gemini_usage_metadata(usage_metadata: &Value) -> Option<Self> {
+        if !usage_metadata.is_object() {
+            return None;
+        }
+        let reasoning_tokens = token_count(usage_metadata, &["thoughtsTokenCount"]);
+        Some(TokenUsage {
+            input_tokens: token_count(usage_metadata, &["promptTokenCount"]),
+            output_tokens: token_count(usage_metadata, &["candidatesTokenCount"]) + reasoning_tokens,
+            cached_tokens: token_count(usage_metadata, &["cachedContentTokenCount"]),
+            reasoning_tokens,
+        })
+    }
+
+    /// Parses the counts of an Ollama response, sent in the last message of a stream (`done: true`)
+    pub fn from_ollama_response(response: &Value) -> Option<Self> {
+        if response.get("prompt_eval_count").is_none() && response.get("eval_count").is_none() {
+            return None;
+        }
+        Some(TokenUsage {
+            input_tokens: token_count(response, &["prompt_eval_count"]),
+            output_tokens: token_count(response, &["eval_count"]),
+            cached_tokens: 0,
+            reasoning_tokens: 0,
+        })
+    }
+
+    /// Combines the usage reported by several events of the same stream. Streaming APIs report
+    /// running totals, so each count keeps the largest value seen.
+    pub fn merge(&mut self, other: &TokenUsage) {
+        self.input_tokens = self.input_tokens.max(other.input_tokens);
+        self.output_tokens = self.output_tokens.max(other.output_tokens);
+        self.cached_tokens = self.cached_tokens.max(other.cached_tokens);
+        self.reasoning_tokens = self.reasoning_tokens.max(other.reasoning_tokens);
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+/// Price of a model in USD per million tokens. Cached input tokens are charged at the input
+/// price when the model has no cached price.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LLMModelPrice {
+    pub model: String,
+    pub input_price_per_million: f64,
+    pub output_price_per_million: f64,
+    pub cached_input_price_per_million: Option<f64>,
+}
+
+impl LLMModelPrice {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cached_tokens = usage.cached_tokens.min(usage.input_tokens);
+        let uncached_tokens = usage.input_tokens - cached_tokens;
+        let cached_price = self
+            .cached_input_price_per_million
+            .unwrap_or(self.input_price_per_million);
+
+        (uncached_tokens as f64 * self.input_price_per_million
+            + cached_tokens as f64 * cached_price
+            + usage.output_tokens as f64 * self.output_price_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Usage of one inference call, as persisted by the node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LLMUsageRecord {
+    pub job_id: Option<String>,
+    pub message_id: Option<String>,
+    pub agent_id: Option<String>,
+    pub llm_provider_id: String,
+    pub provider: String,
+    pub model: String,
+    pub usage: TokenUsage,
+    /// Tool calls the model asked for in its response
+    #[serde(default)]
+    pub tool_calls: u64,
+    /// None when the model has no price configured
+    pub cost: Option<f64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LLMUsageGroupBy {
+    Job,
+    Agent,
+    Provider,
+    Model,
+    Day,
+}
+
+/// Filters and grouping of a usage report. Dates are `YYYY-MM-DD` (UTC), `start_date` is
+/// inclusive and `end_date` exclusive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LLMUsageQuery {
+    pub group_by: LLMUsageGroupBy,
+    pub job_id: Option<String>,
+    pub agent_id: Option<String>,
+    pub llm_provider_id: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+/// Usage added up for one group of a report. `key` is None for the calls that don't belong to
+/// a job or agent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LLMUsageSummary {
+    pub key: Option<String>,
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub tool_calls: u64,
+    pub cost: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_provider_usage() {
+        let openai = json!({
+            "prompt_tokens": 120,
+            "completion_tokens": 80,
+            "total_tokens": 200,
+            "prompt_tokens_details": {"cached_tokens": 100},
+            "completion_tokens_details": {"reasoning_tokens": 30}
+        });
+        let usage = TokenUsage::from_openai_usage(&openai).unwrap();
+        assert_eq!(
+            usage,
+            TokenUsage {
+                input_tokens: 120,
+                output_tokens: 80,
+                cached_tokens: 100,
+                reasoning_tokens: 30
+            }
+        );
+
+        let anthropic = json!({
+            "input_tokens": 93,
+            "cache_creation_input_tokens": 7,
+            "cache_read_input_tokens": 200,
+            "output_tokens": 4
+        });
+        let usage = TokenUsage::from_anthropic_usage(&anthropic).unwrap();
+        assert_eq!(usage.input_tokens, 300);
+        assert_eq!(usage.cached_tokens, 200);
+
+        let gemini = json!({
+            "promptTokenCount": 41,
+            "candidatesTokenCount": 17,
+            "thoughtsTokenCount": 10,
+            "totalTokenCount": 68
+        });
+        let usage = TokenUsage::from_gemini_usage_metadata(&gemini).unwrap();
+        assert_eq!(usage.output_tokens, 27);
+        assert_eq!(usage.reasoning_tokens, 10);
+
+        let ollama = json!({
+            "model": "llama3.1",
+            "done": true,
+            "prompt_eval_count": 26,
+            "eval_count": 290
+        });
+        let usage = TokenUsage::from_ollama_response(&ollama).unwrap();
+        assert_eq!(usage.input_tokens, 26);
+        assert_eq!(usage.output_tokens, 290);
+
+        assert!(TokenUsage::from_openai_usage(&json!({})).is_none());
+        assert!(TokenUsage::from_ollama_response(&json!({ "done": false })).is_none());
+    }
+
+    #[test]
+    fn test_model_price_cost() {
+        let price = LLMModelPrice {
+            model: "gpt-4o".to_string(),
+            input_price_per_million: 2.5,
+            output_price_per_million: 10.0,
+            cached_input_price_per_million: Some(1.25),
+        };
+        let usage = TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 500_000,
+            cached_tokens: 400_000,
+            reasoning_tokens: 0,
+        };
+        // 600k uncached at 2.5, 400k cached at 1.25, 500k output at 10
+        assert!((price.cost(&usage) - 7.0).abs() < 1e-9);
+    }
+}
@@ -94,6 +94,45 @@ impl MCPServerType {
     }
 }
 
+/// Whether an MCP server may ask the node to run `sampling/createMessage` requests through an LLM.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, ToSchema)]
+pub enum MCPSamplingPolicy {
+    /// Sampling requests are rejected.
+    #[default]
+    Deny,
+    /// Only requests made while the server is running a tool call for a job are approved,
+    /// and they are answered by that job's LLM provider.
+    AllowDuringJob,
+    /// Every request is approved. Outside of a job the default LLM provider answers it.
+    Allow,
+}
+
+impl MCPSamplingPolicy {
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_uppercase().as_str() {
+            "DENY" => Ok(MCPSamplingPolicy::Deny),
+            "ALLOW_DURING_JOB" => Ok(MCPSamplingPolicy::AllowDuringJob),
+            "ALLOW" => Ok(MCPSamplingPolicy::Allow),
+            _ => Err(format!("Invalid MCP sampling policy: {}", s)),
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            MCPSamplingPolicy::Deny => "DENY".to_string(),
+            MCPSamplingPolicy::AllowDuringJob => "ALLOW_DURING_JOB".to_string(),
+            MCPSamplingPolicy::Allow => "ALLOW".to_string(),
+        }
+    }
+}
+
+/// A resource of a remote MCP server, attached to a job scope by URI.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MCPResourceRef {
+    pub mcp_server_id: i64,
+    pub uri: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,4 +239,17 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_sampling_policy_round_trip() {
+        for policy in [
+            MCPSamplingPolicy::Deny,
+            MCPSamplingPolicy::AllowDuringJob,
+            MCPSamplingPolicy::Allow,
+        ] {
+            assert_eq!(MCPSamplingPolicy::from_str(&policy.to_string()).unwrap(), policy);
+        }
+        assert!(MCPSamplingPolicy::from_str("sometimes").is_err());
+        assert_eq!(MCPSamplingPolicy::default(), MCPSamplingPolicy::Deny);
+    }
 }
@@ -3,6 +3,7 @@ pub mod coinbase_mpc_config;
 pub mod cron_task;
 pub mod crontab;
 pub mod custom_prompt;
+pub mod embedding_migration;
 pub mod identity;
 pub mod identity_registration;
 pub mod inbox_name;
@@ -13,6 +14,7 @@ pub mod job;
 pub mod job_config;
 pub mod llm_message;
 pub mod llm_providers;
+pub mod llm_usage;
 pub mod mcp_server;
 pub mod prompts;
 pub mod registration_code;
@@ -26,8 +28,11 @@ pub mod hanzo_tool_offering;
 pub mod hanzo_tools;
 pub mod smart_inbox;
 pub mod subprompts;
+pub mod tool_entitlement;
 pub mod tool_router_key;
 pub mod wallet_complementary;
 pub mod wallet_mixed;
+pub mod wallet_spending_policy;
 pub mod ws_types;
+pub mod x402_paywall;
 pub mod x402_types;
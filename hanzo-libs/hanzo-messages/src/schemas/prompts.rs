@@ -158,6 +158,7 @@ impl Prompt {
     }
 
     /// Adds RetrievedNode content into the prompt if it is a Text-holding node. Otherwise skips.
+    /// Chunks of source code are prefixed with their file, line range and symbol so they can be cited.
     pub fn add_ret_node_content(
         &mut self,
         retrieved_node: HanzoFileChunkCollection,
@@ -167,6 +168,10 @@ impl Prompt {
         for chunk in retrieved_node.chunks.iter() {
             let content = chunk.content.clone();
             if !content.trim().is_empty() {
+                let content = match chunk.source_reference(retrieved_node.paths.as_ref()) {
+                    Some(reference) => format!("{}:\n{}", reference, content),
+                    None => content,
+                };
                 self.add_content(content, prompt_type.clone(), priority_value);
             }
         }
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::hanzo_tool_offering::UsageType;
+
+/// What a buyer may still use of a tool bought with a subscription or with prepaid credits.
+/// The provider keeps the authoritative copy, the buyer caches its own to skip the invoice
+/// round-trip while the entitlement lasts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolEntitlement {
+    /// Node name of the buyer (e.g. `@@buyer.hanzo`)
+    pub buyer_name: String,
+    pub tool_key: String,
+    /// End of the subscription, None without a subscription
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Calls left, None without prepaid credits
+    pub remaining_credits: Option<u64>,
+    /// Invoice of the last purchase or top-up
+    pub last_invoice_id: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ToolEntitlement {
+    pub fn new(buyer_name: String, tool_key: String, invoice_id: String, now: DateTime<Utc>) -> Self {
+        ToolEntitlement {
+            buyer_name,
+            tool_key,
+            expires_at: None,
+            remaining_credits: None,
+            last_invoice_id: invoice_id,
+            updated_at: now,
+        }
+    }
+
+    /// Adds a purchase. A subscription is extended by one period from its current end, or from now
+    /// once it expired, and credits are added to the ones left.
+    pub fn top_up(&mut self, usage_type: &UsageType, invoice_id: &str, now: DateTime<Utc>) {
+        match usage_type {
+            UsageType::Subscription(terms) => {
+                let start = match self.expires_at {
+                    Some(expires_at) if expires_at > now => expires_at,
+                    _ => now,
+                };
+                self.expires_at = Some(start + Duration::days(terms.period_days as i64));
+            }
+            UsageType::PrepaidCredits(terms) => {
+                let remaining = self.remaining_credits.unwrap_or(0);
+                self.remaining_credits = Some(remaining.saturating_add(terms.credits));
+            }
+            UsageType::PerUse(_) => return,
+        }
+        self.last_invoice_id = invoice_id.to_string();
+        self.updated_at = now;
+    }
+
+    pub fn has_active_subscription(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at > now)
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.has_active_subscription(now) || self.remaining_credits.unwrap_or(0) > 0
+    }
+
+    /// Takes one use: free under an active subscription, otherwise one credit.
+    /// Returns whether a credit was taken, to give it back if the call fails.
+    pub fn consume(&mut self, now: DateTime<Utc>) -> Result<bool, String> {
+        if self.has_active_subscription(now) {
+            self.updated_at = now;
+            return Ok(false);
+        }
+        match self.remaining_credits {
+            Some(remaining) if remaining > 0 => {
+                self.remaining_credits = Some(remaining - 1);
+                self.updated_at = now;
+                Ok(true)
+            }
+            _ => Err(format!(
+                "{} has no active subscription or credits left for {}",
+                self.buyer_name, self.tool_key
+            )),
+        }
+    }
+
+    /// Gives back a credit taken by `consume` for a call that failed
+    pub fn refund_credit(&mut self, now: DateTime<Utc>) {
+        let remaining = self.remaining_credits.unwrap_or(0);
+        self.remaining_credits = Some(remaining.saturating_add(1));
+        self.updated_at = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::hanzo_tool_offering::{PrepaidCreditsTerms, SubscriptionTerms, ToolPrice};
+
+    fn entitlement(now: DateTime<Utc>) -> ToolEntitlement {
+        ToolEntitlement::new(
+            "@@buyer.hanzo".to_string(),
+            "@@provider.hanzo:::toolkit:::tool".to_string(),
+            "inv_1".to_string(),
+            now,
+        )
+    }
+
+    #[test]
+    fn test_prepaid_credits() {
+        let now = Utc::now();
+        let credits = UsageType::PrepaidCredits(PrepaidCreditsTerms {
+            price: ToolPrice::Free,
+            credits: 2,
+        });
+        let mut entitlement = entitlement(now);
+        assert!(!entitlement.is_active(now));
+
+        entitlement.top_up(&credits, "inv_1", now);
+        assert_eq!(entitlement.consume(now), Ok(true));
+        assert_eq!(entitlement.consume(now), Ok(true));
+        assert!(entitlement.consume(now).is_err());
+
+        // A failed call gets its credit back
+        entitlement.refund_credit(now);
+        assert_eq!(entitlement.consume(now), Ok(true));
+        assert!(entitlement.consume(now).is_err());
+
+        // A top-up adds to the credits left
+        entitlement.top_up(&credits, "inv_2", now);
+        entitlement.top_up(&credits, "inv_3", now);
+        assert_eq!(entitlement.remaining_credits, Some(4));
+        assert_eq!(entitlement.last_invoice_id, "inv_3");
+    }
+
+    #[test]
+    fn test_subscription_expiry_and_renewal() {
+        let now = Utc::now();
+        let subscription = UsageType::Subscription(SubscriptionTerms {
+            price: ToolPrice::Free,
+            period_days: 30,
+        });
+        let mut entitlement = entitlement(now);
+        entitlement.top_up(&subscription, "inv_1", now);
+        assert_eq!(entitlement.consume(now + Duration::days(29)), Ok(false));
+        assert!(entitlement.consume(now + Duration::days(31)).is_err());
+
+        // Renewing before the end extends the current period
+        entitlement.top_up(&subscription, "inv_2", now + Duration::days(10));
+        assert_eq!(entitlement.expires_at, Some(now + Duration::days(60)));
+
+        // Renewing after the end starts a new period
+        let later = now + Duration::days(90);
+        entitlement.top_up(&subscription, "inv_3", later);
+        assert_eq!(entitlement.expires_at, Some(later + Duration::days(30)));
+    }
+}
@@ -82,13 +82,29 @@ pub struct Asset {
     pub contract_address: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
 pub enum AssetType {
     ETH,
     USDC,
     KAI,
 }
 
+impl AssetType {
+    /// Finds the asset type of an asset named by its symbol or its contract address on `network`
+    pub fn from_asset(asset: &str, network: &x402_types::Network) -> Option<Self> {
+        [AssetType::ETH, AssetType::USDC, AssetType::KAI]
+            .into_iter()
+            .find(|asset_type| {
+                Asset::new(asset_type.clone(), network).map_or(false, |known| {
+                    known.asset_id.eq_ignore_ascii_case(asset)
+                        || known
+                            .contract_address
+                            .map_or(false, |address| address.eq_ignore_ascii_case(asset))
+                })
+            })
+    }
+}
+
 impl Asset {
     pub fn new(asset_type: AssetType, network: &x402_types::Network) -> Option<Self> {
         match (asset_type, network) {
@@ -355,6 +371,23 @@ pub enum TransactionStatusEnum {
     Failed,
 }
 
+/// Represents the receipt of a transaction included in a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    /// The hash of the transaction.
+    pub transaction_hash: String,
+    /// The number of the block that includes the transaction.
+    pub block_number: u64,
+    /// The onchain address of the sender.
+    pub from_address_id: String,
+    /// The onchain address of the recipient. None for contract creations.
+    pub to_address_id: Option<String>,
+    /// The gas used by the transaction.
+    pub gas_used: u64,
+    /// Complete if the transaction succeeded, Failed if it reverted.
+    pub status: TransactionStatusEnum,
+}
+
 /// Enum representing the type of transaction.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TransactionType {
@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::wallet_mixed::AssetType;
+use super::x402_types::Network;
+
+/// Limits on the payments made in one asset. Amounts are in the atomic units of the asset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AssetSpendingLimits {
+    /// Largest amount a single payment may have
+    pub max_per_transaction: Option<String>,
+    /// Total that may be paid per UTC day
+    pub daily_cap: Option<String>,
+    /// Total that may be paid per UTC calendar month
+    pub monthly_cap: Option<String>,
+    /// Payments of this amount or more wait for a user to approve them.
+    /// Without a threshold every payment waits for approval.
+    pub approval_threshold: Option<String>,
+}
+
+/// Rules applied by the node before it pays an invoice of another node
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct WalletSpendingPolicy {
+    /// Node names (e.g. `@@provider.hanzo`) that may be paid. None allows any node.
+    pub allowed_counterparties: Option<Vec<String>>,
+    /// Limits per asset. Payments in an asset without limits always wait for approval.
+    pub limits: HashMap<AssetType, AssetSpendingLimits>,
+}
+
+/// A payment the node is about to make
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpendingRequest {
+    pub counterparty: String,
+    pub asset: Option<AssetType>,
+    pub amount: u128,
+    /// Whether a user already approved this payment through the API
+    pub approved_by_user: bool,
+}
+
+/// What was already paid in the asset of a request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpentAmounts {
+    pub today: u128,
+    pub this_month: u128,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendingDecision {
+    /// Paid without asking, the payment is within the policy
+    Approved,
+    /// Paid after a user approved it
+    ApprovedByUser,
+    /// Waiting for a user to approve it
+    PendingApproval,
+    /// Refused by the policy
+    Denied,
+    /// Refused by a user
+    Rejected,
+    /// The payment was approved but the wallet failed to make it
+    PaymentFailed,
+}
+
+impl SpendingDecision {
+    /// Whether the payment may go ahead
+    pub fn is_approved(&self) -> bool {
+        matches!(self, SpendingDecision::Approved | SpendingDecision::ApprovedByUser)
+    }
+}
+
+impl fmt::Display for SpendingDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decision = match self {
+            SpendingDecision::Approved => "approved",
+            SpendingDecision::ApprovedByUser => "approved_by_user",
+            SpendingDecision::PendingApproval => "pending_approval",
+            SpendingDecision::Denied => "denied",
+            SpendingDecision::Rejected => "rejected",
+            SpendingDecision::PaymentFailed => "payment_failed",
+        };
+        write!(f, "{}", decision)
+    }
+}
+
+impl std::str::FromStr for SpendingDecision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "approved" => Ok(SpendingDecision::Approved),
+            "approved_by_user" => Ok(SpendingDecision::ApprovedByUser),
+            "pending_approval" => Ok(SpendingDecision::PendingApproval),
+            "denied" => Ok(SpendingDecision::Denied),
+            "rejected" => Ok(SpendingDecision::Rejected),
+            "payment_failed" => Ok(SpendingDecision::PaymentFailed),
+            _ => Err(format!("Unknown spending decision: {}", s)),
+        }
+    }
+}
+
+/// One entry of the spending ledger. Entries are never updated, an invoice gets a new entry for
+/// every decision taken on it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SpendingLedgerEntry {
+    pub invoice_id: String,
+    pub counterparty: String,
+    pub tool_key: String,
+    /// None when the asset is not one of the known asset types
+    pub asset_type: Option<AssetType>,
+    /// Asset as named in the invoice (symbol or contract address)
+    pub asset: String,
+    #[schema(value_type = String)]
+    pub network: Network,
+    /// Amount in atomic units
+    pub amount: String,
+    pub decision: SpendingDecision,
+    pub reason: Option<String>,
+    pub created_at: String,
+}
+
+fn parse_amount(amount: &Option<String>, name: &str) -> Result<Option<u128>, String> {
+    amount
+        .as_ref()
+        .map(|amount| {
+            amount
+                .parse::<u128>()
+                .map_err(|_| format!("Invalid {}: '{}' is not an amount in atomic units", name, amount))
+        })
+        .transpose()
+}
+
+/// Limits of an asset parsed into amounts
+struct ParsedLimits {
+    max_per_transaction: Option<u128>,
+    daily_cap: Option<u128>,
+    monthly_cap: Option<u128>,
+    approval_threshold: Option<u128>,
+}
+
+impl AssetSpendingLimits {
+    fn parse(&self) -> Result<ParsedLimits, String> {
+        Ok(ParsedLimits {
+            max_per_transaction: parse_amount(&self.max_per_transaction, "max_per_transaction")?,
+            daily_cap: parse_amount(&self.daily_cap, "daily_cap")?,
+            monthly_cap: parse_amount(&self.monthly_cap, "monthly_cap")?,
+            approval_threshold: parse_amount(&self.approval_threshold, "approval_threshold")?,
+        })
+    }
+
+    /// Checks that every amount set is a valid amount in atomic units
+    pub fn validate(&self) -> Result<(), String> {
+        self.parse().map(|_| ())
+    }
+}
+
+impl WalletSpendingPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        for (asset, limits) in &self.limits {
+            limits.validate().map_err(|e| format!("{:?}: {}", asset, e))?;
+        }
+        Ok(())
+    }
+
+    /// Decides whether a payment may be made. The allowlist and the caps apply to every payment,
+    /// the approval threshold only to payments not yet approved by a user.
+    /// Returns the decision and, unless the payment is approved, the reason.
+    pub fn evaluate(&self, request: &SpendingRequest, spent: SpentAmounts) -> (SpendingDecision, Option<String>) {
+        if let Some(allowed) = &self.allowed_counterparties {
+            if !allowed.iter().any(|name| name == &request.counterparty) {
+                return (
+                    SpendingDecision::Denied,
+                    Some(format!("{} is not an allowed counterparty", request.counterparty)),
+                );
+            }
+        }
+
+        let approved = if request.approved_by_user {
+            SpendingDecision::ApprovedByUser
+        } else {
+            SpendingDecision::Approved
+        };
+        let needs_approval = |reason: String| {
+            if request.approved_by_user {
+                (approved, None)
+            } else {
+                (SpendingDecision::PendingApproval, Some(reason))
+            }
+        };
+
+        let limits = match request.asset.as_ref().and_then(|asset| self.limits.get(asset)) {
+            Some(limits) => match limits.parse() {
+                Ok(limits) => limits,
+                Err(e) => return (SpendingDecision::Denied, Some(e)),
+            },
+            None => return needs_approval("No spending limits for this asset".to_string()),
+        };
+
+        if let Some(max) = limits.max_per_transaction {
+            if request.amount > max {
+                return (
+                    SpendingDecision::Denied,
+                    Some(format!(
+                        "Amount {} is over the per transaction cap of {}",
+                        request.amount, max
+                    )),
+                );
+            }
+        }
+        if let Some(cap) = limits.daily_cap {
+            if spent.today.saturating_add(request.amount) > cap {
+                return (
+                    SpendingDecision::Denied,
+                    Some(format!("Daily cap of {} reached ({} spent today)", cap, spent.today)),
+                );
+            }
+        }
+        if let Some(cap) = limits.monthly_cap {
+            if spent.this_month.saturating_add(request.amount) > cap {
+                return (
+                    SpendingDecision::Denied,
+                    Some(format!(
+                        "Monthly cap of {} reached ({} spent this month)",
+                        cap, spent.this_month
+                    )),
+                );
+            }
+        }
+
+        match limits.approval_threshold {
+            Some(threshold) if request.amount < threshold => (approved, None),
+            Some(threshold) => needs_approval(format!(
+                "Amount {} is at or over the approval threshold of {}",
+                request.amount, threshold
+            )),
+            None => needs_approval("No approval threshold for this asset".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> WalletSpendingPolicy {
+        let mut limits = HashMap::new();
+        limits.insert(
+            AssetType::USDC,
+            AssetSpendingLimits {
+                max_per_transaction: Some("1000".to_string()),
+                daily_cap: Some("2500".to_string()),
+                monthly_cap: Some("10000".to_string()),
+                approval_threshold: Some("500".to_string()),
+            },
+        );
+        WalletSpendingPolicy {
+            allowed_counterparties: Some(vec!["@@provider.hanzo".to_string()]),
+            limits,
+        }
+    }
+
+    fn request(amount: u128) -> SpendingRequest {
+        SpendingRequest {
+            counterparty: "@@provider.hanzo".to_string(),
+            asset: Some(AssetType::USDC),
+            amount,
+            approved_by_user: false,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_spending_policy() {
+        let policy = policy();
+        let spent = SpentAmounts::default();
+
+        assert_eq!(
+            policy.evaluate(&request(100), spent),
+            (SpendingDecision::Approved, None)
+        );
+        assert_eq!(
+            policy.evaluate(&request(500), spent).0,
+            SpendingDecision::PendingApproval
+        );
+        assert_eq!(policy.evaluate(&request(1001), spent).0, SpendingDecision::Denied);
+
+        let stranger = SpendingRequest {
+            counterparty: "@@stranger.hanzo".to_string(),
+            ..request(100)
+        };
+        assert_eq!(policy.evaluate(&stranger, spent).0, SpendingDecision::Denied);
+
+        // Caps count what was already spent
+        let spent = SpentAmounts {
+            today: 2450,
+            this_month: 2450,
+        };
+        assert_eq!(policy.evaluate(&request(100), spent).0, SpendingDecision::Denied);
+        let spent = SpentAmounts {
+            today: 0,
+            this_month: 9950,
+        };
+        assert_eq!(policy.evaluate(&request(100), spent).0, SpendingDecision::Denied);
+    }
+
+    #[test]
+    fn test_user_approval_skips_only_the_threshold() {
+        let policy = policy();
+        let approved = SpendingRequest {
+            approved_by_user: true,
+            ..request(800)
+        };
+        assert_eq!(
+            policy.evaluate(&approved, SpentAmounts::default()),
+            (SpendingDecision::ApprovedByUser, None)
+        );
+
+        let over_cap = SpendingRequest {
+            approved_by_user: true,
+            ..request(2000)
+        };
+        assert_eq!(
+            policy.evaluate(&over_cap, SpentAmounts::default()).0,
+            SpendingDecision::Denied
+        );
+
+        // Assets without limits are never paid without approval
+        let eth = SpendingRequest {
+            asset: Some(AssetType::ETH),
+            ..request(1)
+        };
+        assert_eq!(
+            policy.evaluate(&eth, SpentAmounts::default()).0,
+            SpendingDecision::PendingApproval
+        );
+        assert_eq!(
+            WalletSpendingPolicy::default()
+                .evaluate(&request(1), SpentAmounts::default())
+                .0,
+            SpendingDecision::PendingApproval
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::x402_types::{Network, PaymentPayload, PaymentRequirements, Price};
+
+/// Something the node sells to plain HTTP clients over x402
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum PaywallResource {
+    /// An API route, by path (e.g. `/v2/x402/agents/my_agent/chat`)
+    Route(String),
+    /// A tool by tool router key, whether called over HTTP or as an MCP tool
+    Tool(String),
+}
+
+impl PaywallResource {
+    /// Path of the paid chat endpoint of an agent
+    pub fn agent_chat(agent_id: &str) -> Self {
+        PaywallResource::Route(format!("/v2/x402/agents/{}/chat", agent_id))
+    }
+
+    /// Key under which the price of the resource is stored
+    pub fn key(&self) -> String {
+        match self {
+            PaywallResource::Route(path) => format!("route:{}", path),
+            PaywallResource::Tool(tool_router_key) => format!("tool:{}", tool_router_key),
+        }
+    }
+
+    pub fn from_key(key: &str) -> Result<Self, String> {
+        match key.split_once(':') {
+            Some(("route", path)) => Ok(PaywallResource::Route(path.to_string())),
+            Some(("tool", tool_router_key)) => Ok(PaywallResource::Tool(tool_router_key.to_string())),
+            _ => Err(format!("Invalid paywall resource key: {}", key)),
+        }
+    }
+}
+
+impl fmt::Display for PaywallResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.key())
+    }
+}
+
+/// Price of a resource and where its payments go
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PaywallPrice {
+    pub resource: PaywallResource,
+    /// Amount in USD (paid in USDC) or in the atomic units of an ERC20 token
+    #[schema(value_type = Object)]
+    pub price: Price,
+    #[schema(value_type = String)]
+    pub network: Network,
+    /// Address receiving the payments
+    pub pay_to: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl PaywallPrice {
+    pub fn validate(&self) -> Result<(), String> {
+        let pay_to = self.pay_to.trim_start_matches("0x");
+        if pay_to.len() != 40 || !pay_to.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid pay_to address: {}", self.pay_to));
+        }
+        match &self.price {
+            Price::Money(amount) if !(0.0001..=999999999.0).contains(amount) => {
+                Err(format!("Price {} is not between 0.0001 and 999999999", amount))
+            }
+            Price::ERC20TokenAmount(token_amount) if token_amount.amount.parse::<u128>().is_err() => Err(format!(
+                "Token amount {} is not an integer",
+                token_amount.amount
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Body of a `402 Payment Required` answer, as defined by x402
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentRequiredResponse {
+    pub x402_version: u32,
+    pub error: String,
+    pub accepts: Vec<PaymentRequirements>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum X402ReceiptStatus {
+    /// Verified, the resource is being served
+    Verified,
+    /// Served and settled onchain by the facilitator
+    Settled,
+    /// Verified but the facilitator could not settle it
+    Failed,
+}
+
+/// A payment received over x402 for a resource of the node. Its id is the nonce of the
+/// authorization, so a payment can't be used twice.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct X402Receipt {
+    pub receipt_id: String,
+    pub resource: PaywallResource,
+    pub payer: String,
+    #[schema(value_type = Object)]
+    pub payment: PaymentPayload,
+    pub requirements: PaymentRequirements,
+    pub status: X402ReceiptStatus,
+    /// Hash of the settlement transaction
+    pub transaction: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Answer of the node to a request for a resource
+#[derive(Debug, Clone)]
+pub enum PaywallDecision {
+    /// The resource is not for sale
+    NotPriced,
+    PaymentRequired(PaymentRequiredResponse),
+    /// The payment is verified, it is settled once the resource is served
+    Verified(X402Receipt),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_keys() {
+        let resources = vec![
+            PaywallResource::agent_chat("my_agent"),
+            PaywallResource::Tool("local:::__official_hanzo:::echo".to_string()),
+        ];
+        for resource in resources {
+            assert_eq!(PaywallResource::from_key(&resource.key()).unwrap(), resource);
+        }
+        assert_eq!(
+            PaywallResource::agent_chat("my_agent").key(),
+            "route:/v2/x402/agents/my_agent/chat"
+        );
+        assert!(PaywallResource::from_key("agent:my_agent").is_err());
+    }
+
+    #[test]
+    fn test_validate_price() {
+        let mut price = PaywallPrice {
+            resource: PaywallResource::agent_chat("my_agent"),
+            price: Price::Money(0.01),
+            network: Network::BaseSepolia,
+            pay_to: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+            description: String::new(),
+        };
+        assert!(price.validate().is_ok());
+
+        price.price = Price::Money(0.0);
+        assert!(price.validate().is_err());
+
+        price.price = Price::Money(0.01);
+        price.pay_to = "0x1234".to_string();
+        assert!(price.validate().is_err());
+    }
+}
@@ -3,13 +3,13 @@ use utoipa::ToSchema;
 
 pub type Money = f64;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct EIP712 {
     pub name: String,
     pub version: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct ERC20Asset {
     pub address: String,
     pub decimals: u32,
@@ -117,7 +117,7 @@ impl PaymentRequirements {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct PaymentPayload {
     pub scheme: String,
     pub network: Network,
@@ -126,13 +126,13 @@ pub struct PaymentPayload {
     pub payload: PaymentPayloadData,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct PaymentPayloadData {
     pub signature: String,
     pub authorization: PaymentAuthorization,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct PaymentAuthorization {
     pub from: String,
     pub to: String,
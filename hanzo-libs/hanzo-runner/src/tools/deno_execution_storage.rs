@@ -9,7 +9,8 @@ impl ExecutionStorage {
     }
     pub fn deno_cache_folder_path(&self, runner_type: RunnerType) -> std::path::PathBuf {
         match runner_type {
-            RunnerType::Host => self.deno_cache_folder_path_host(),
+            // The sandbox runs the same deno binary as the host
+            RunnerType::Host | RunnerType::Sandbox => self.deno_cache_folder_path_host(),
             RunnerType::Docker => self.deno_cache_folder_path_docker(),
         }
     }
@@ -0,0 +1,48 @@
+/// Permissions declared by a tool, granted in place of the broad defaults of the runner
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DenoPermissions {
+    /// Hosts the tool can reach, optionally with a port, or `*` for any host
+    pub net: Vec<String>,
+    /// Paths the tool can read
+    pub read: Vec<String>,
+    /// Paths the tool can write
+    pub write: Vec<String>,
+    /// Binaries the tool can spawn
+    pub run: Vec<String>,
+    /// Environment variables the tool can read
+    pub env: Vec<String>,
+}
+
+impl DenoPermissions {
+    /// Entry of `net` letting the tool reach any host
+    pub const ANY_HOST: &'static str = "*";
+
+    /// Deno flags granting the declared permissions. The tool can always reach the node at
+    /// `node_host` and read the variables in `env_keys`, which the runner sets for it.
+    pub fn to_deno_flags(&self, node_host: &str, env_keys: &[String]) -> Vec<String> {
+        let mut net = vec![node_host.to_string()];
+        net.extend(self.net.iter().cloned());
+
+        let mut env = env_keys.to_vec();
+        env.extend(self.env.iter().cloned());
+        env.sort();
+        env.dedup();
+
+        let net_flag = if self.net.iter().any(|host| host == Self::ANY_HOST) {
+            "--allow-net".to_string()
+        } else {
+            format!("--allow-net={}", net.join(","))
+        };
+        let mut flags = vec![
+            "--allow-import".to_string(),
+            net_flag,
+            format!("--allow-env={}", env.join(",")),
+        ];
+        if !self.run.is_empty() {
+            flags.push(format!("--allow-run={}", self.run.join(",")));
+        }
+        flags.extend(self.read.iter().map(|path| format!("--allow-read={}", path)));
+        flags.extend(self.write.iter().map(|path| format!("--allow-write={}", path)));
+        flags
+    }
+}
@@ -11,6 +11,7 @@ use crate::tools::{
     file_name_utils::{adapt_paths_in_value, normalize_for_docker_path},
     path_buf_ext::PathBufExt,
     runner_type::{resolve_runner_type, RunnerType},
+    sandbox::Sandbox,
 };
 
 use super::{
@@ -24,6 +25,18 @@ use std::{
     time::Duration,
 };
 
+/// Variables the runner sets for every tool
+const RUNNER_ENV_KEYS: [&str; 8] = [
+    "NO_COLOR",
+    "DENO_DIR",
+    "HANZO_NODE_LOCATION",
+    "HANZO_HOME",
+    "HANZO_ASSETS",
+    "HANZO_MOUNT",
+    "HANZO_CONTEXT_ID",
+    "HANZO_EXECUTION_ID",
+];
+
 #[derive(Default)]
 pub struct DenoRunner {
     code: CodeFiles,
@@ -192,7 +205,10 @@ impl DenoRunner {
         }
 
         let result = match resolved_runner_type {
-            RunnerType::Host => self.run_in_host(code, envs, max_execution_timeout).await,
+            RunnerType::Host | RunnerType::Sandbox => {
+                self.run_in_host(code, envs, max_execution_timeout, resolved_runner_type)
+                    .await
+            }
             RunnerType::Docker => self.run_in_docker(code, envs, max_execution_timeout).await,
         }
         .map_err(|e| ExecutionError::new(e.to_string(), None))?;
@@ -289,6 +305,7 @@ impl DenoRunner {
             mount_params.extend([String::from("--mount"), mount_param]);
         }
 
+        let env_keys = Self::env_keys(&envs);
         let mut container_envs = Vec::<String>::new();
 
         container_envs.push(String::from("-e"));
@@ -361,6 +378,7 @@ impl DenoRunner {
                     PathBuf::from(path_in_docker)
                 })
                 .collect::<Vec<_>>(),
+            &env_keys,
         );
 
         let code_entrypoint =
@@ -468,9 +486,10 @@ impl DenoRunner {
         code_files: CodeFiles,
         envs: Option<HashMap<String, String>>,
         max_execution_timeout: Option<Duration>,
+        runner_type: RunnerType,
     ) -> anyhow::Result<Vec<String>> {
         let execution_storage = ExecutionStorage::new(code_files, self.options.context.clone());
-        execution_storage.init_for_deno(None, RunnerType::Host)?;
+        execution_storage.init_for_deno(None, runner_type.clone())?;
 
         let binary_path = path::absolute(self.options.deno_binary_path.clone())
             .unwrap()
@@ -479,7 +498,7 @@ impl DenoRunner {
         log::info!("using deno from host at path: {:?}", binary_path.clone());
 
         let deno_permissions: Vec<String> = self.get_deno_permissions(
-            RunnerType::Host,
+            runner_type.clone(),
             binary_path.clone().as_str(),
             execution_storage
                 .home_folder_path
@@ -500,6 +519,7 @@ impl DenoRunner {
                 .iter()
                 .map(|p| path::absolute(p).unwrap())
                 .collect::<Vec<_>>(),
+            &Self::env_keys(&envs),
         );
 
         let mut command = tokio::process::Command::new(binary_path);
@@ -512,11 +532,22 @@ impl DenoRunner {
             .stderr(std::process::Stdio::piped())
             .kill_on_drop(true);
 
+        if matches!(runner_type, RunnerType::Sandbox) {
+            let mut writable_paths = vec![
+                execution_storage.root_folder_path.clone(),
+                execution_storage.deno_cache_folder_path(runner_type.clone()),
+            ];
+            for mount_file in &self.options.context.mount_files {
+                writable_paths.push(path::absolute(mount_file)?);
+            }
+            Sandbox::new(writable_paths, self.options.sandbox.clone()).apply(command)?;
+        }
+
         command.env("NO_COLOR", "true");
         command.env(
             "DENO_DIR",
             execution_storage
-                .deno_cache_folder_path(RunnerType::Host)
+                .deno_cache_folder_path(runner_type.clone())
                 .clone(),
         );
         command.env(
@@ -639,6 +670,15 @@ impl DenoRunner {
         Ok(stdout)
     }
 
+    /// Variables the tool can read when it declares its permissions
+    fn env_keys(envs: &Option<HashMap<String, String>>) -> Vec<String> {
+        let mut env_keys: Vec<String> = RUNNER_ENV_KEYS.iter().map(|key| key.to_string()).collect();
+        if let Some(envs) = envs {
+            env_keys.extend(envs.keys().cloned());
+        }
+        env_keys
+    }
+
     fn get_deno_permissions(
         &self,
         runner_type: RunnerType,
@@ -646,50 +686,72 @@ impl DenoRunner {
         home_path: &str,
         mount_files: &[PathBuf],
         assets_files: &[PathBuf],
+        env_keys: &[String],
     ) -> Vec<String> {
         log::info!("mount files: {:?}", mount_files);
         log::info!("assets files: {:?}", assets_files);
-        let mut deno_permissions: Vec<String> = vec![
-            // Basically all non-file related permissions
-            "--allow-env".to_string(),
-            "--allow-run".to_string(),
-            "--allow-net".to_string(),
-            "--allow-sys".to_string(),
-            "--allow-scripts".to_string(),
-            "--allow-ffi".to_string(),
-            "--allow-import".to_string(),
-
-            // Engine folders
-            "--allow-read=.".to_string(),
-            format!("--allow-write={}", home_path.to_string()),
-
-            // Playwright/Chrome folders
-            format!("--allow-read={}", exec_path.to_string()),
-            "--allow-write=/var/folders".to_string(),
-            "--allow-read=/var/folders".to_string(),
-            "--allow-read=/tmp".to_string(),
-            "--allow-write=/tmp".to_string(),
-            format!("--allow-read={}", std::env::temp_dir().to_string_lossy()),
-            format!("--allow-write={}", std::env::temp_dir().to_string_lossy()),
-            "--allow-read=/Applications/Google Chrome.app/Contents/MacOS/Google Chrome".to_string(),
-            "--allow-read=/Applications/Google Chrome Canary.app/Contents/MacOS/Google Chrome Canary".to_string(),
-            "--allow-read=/Applications/Chromium.app/Contents/MacOS/Chromium".to_string(),
-            "--allow-read=C:\\Program Files (x86)\\Google\\Chrome\\Application\\chrome.exe".to_string(),
-            "--allow-read=C:\\Program Files (x86)\\Google\\Chrome SxS\\Application\\chrome.exe".to_string(),
-            "--allow-read=C:\\Program Files (x86)\\Chromium\\Application\\chrome.exe".to_string(),
-            "--allow-read=C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe".to_string(),
-            "--allow-read=C:\\Program Files\\Google\\Chrome SxS\\Application\\chrome.exe".to_string(),
-            "--allow-read=C:\\Program Files\\Chromium\\Application\\chrome.exe".to_string(),
-            "--allow-read=/usr/bin/chromium".to_string(),
-        ];
+        let mut deno_permissions: Vec<String> = match &self.options.permissions {
+            Some(permissions) => {
+                let node_host = match runner_type {
+                    RunnerType::Host | RunnerType::Sandbox => format!(
+                        "{}:{}",
+                        self.options.hanzo_node_location.host, self.options.hanzo_node_location.port
+                    ),
+                    RunnerType::Docker => {
+                        format!("host.docker.internal:{}", self.options.hanzo_node_location.port)
+                    }
+                };
+                let mut deno_permissions = permissions.to_deno_flags(&node_host, env_keys);
+                deno_permissions.extend([
+                    // Engine folders
+                    "--allow-read=.".to_string(),
+                    format!("--allow-write={}", home_path),
+                    format!("--allow-read={}", exec_path),
+                ]);
+                deno_permissions
+            }
+            None => vec![
+                // Basically all non-file related permissions
+                "--allow-env".to_string(),
+                "--allow-run".to_string(),
+                "--allow-net".to_string(),
+                "--allow-sys".to_string(),
+                "--allow-scripts".to_string(),
+                "--allow-ffi".to_string(),
+                "--allow-import".to_string(),
+
+                // Engine folders
+                "--allow-read=.".to_string(),
+                format!("--allow-write={}", home_path.to_string()),
+
+                // Playwright/Chrome folders
+                format!("--allow-read={}", exec_path.to_string()),
+                "--allow-write=/var/folders".to_string(),
+                "--allow-read=/var/folders".to_string(),
+                "--allow-read=/tmp".to_string(),
+                "--allow-write=/tmp".to_string(),
+                format!("--allow-read={}", std::env::temp_dir().to_string_lossy()),
+                format!("--allow-write={}", std::env::temp_dir().to_string_lossy()),
+                "--allow-read=/Applications/Google Chrome.app/Contents/MacOS/Google Chrome".to_string(),
+                "--allow-read=/Applications/Google Chrome Canary.app/Contents/MacOS/Google Chrome Canary".to_string(),
+                "--allow-read=/Applications/Chromium.app/Contents/MacOS/Chromium".to_string(),
+                "--allow-read=C:\\Program Files (x86)\\Google\\Chrome\\Application\\chrome.exe".to_string(),
+                "--allow-read=C:\\Program Files (x86)\\Google\\Chrome SxS\\Application\\chrome.exe".to_string(),
+                "--allow-read=C:\\Program Files (x86)\\Chromium\\Application\\chrome.exe".to_string(),
+                "--allow-read=C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe".to_string(),
+                "--allow-read=C:\\Program Files\\Google\\Chrome SxS\\Application\\chrome.exe".to_string(),
+                "--allow-read=C:\\Program Files\\Chromium\\Application\\chrome.exe".to_string(),
+                "--allow-read=/usr/bin/chromium".to_string(),
+            ],
+        };
 
-        if matches!(runner_type, RunnerType::Docker) {
+        if matches!(runner_type, RunnerType::Docker) && self.options.permissions.is_none() {
             deno_permissions.push("--allow-read=/".to_string());
         }
 
         for file in mount_files {
             let path = match runner_type {
-                RunnerType::Host => file.to_string_lossy().to_string(),
+                RunnerType::Host | RunnerType::Sandbox => file.to_string_lossy().to_string(),
                 RunnerType::Docker => normalize_for_docker_path(file.to_path_buf()),
             };
             let mount_param = format!(r#"--allow-read={},--allow-write={}"#, path, path);
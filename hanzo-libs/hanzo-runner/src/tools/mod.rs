@@ -2,6 +2,7 @@ pub mod check_utils;
 pub mod code_files;
 pub mod container_utils;
 pub mod deno_execution_storage;
+pub mod deno_permissions;
 pub mod deno_runner;
 pub mod deno_runner_options;
 pub mod execution_context;
@@ -14,5 +15,6 @@ pub mod python_runner;
 pub mod python_runner_options;
 pub mod run_result;
 pub mod runner_type;
+pub mod sandbox;
 pub mod hanzo_node_location;
 pub mod tool_definition;
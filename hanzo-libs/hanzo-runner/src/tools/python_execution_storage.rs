@@ -10,6 +10,9 @@ impl ExecutionStorage {
     pub fn python_run_docker_uv_cache_folder_path(&self) -> std::path::PathBuf {
         self.global_cache_folder_path.join("uv-cache-docker")
     }
+    pub fn python_run_sandbox_uv_cache_folder_path(&self) -> std::path::PathBuf {
+        self.global_cache_folder_path.join("uv-cache-sandbox")
+    }
 
     pub fn python_check_venv_folder_path(&self) -> std::path::PathBuf {
         self.cache_folder_path.join("python-check-venv")
@@ -34,6 +37,10 @@ impl ExecutionStorage {
             log::error!("failed to create uv cache directory: {}", e);
             e
         })?;
+        std::fs::create_dir_all(self.python_run_sandbox_uv_cache_folder_path()).map_err(|e| {
+            log::error!("failed to create uv sandbox cache directory: {}", e);
+            e
+        })?;
         Ok(())
     }
 }
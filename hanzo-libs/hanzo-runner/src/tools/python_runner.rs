@@ -22,7 +22,7 @@ use crate::tools::{
 
 use super::{
     code_files::CodeFiles, execution_storage::ExecutionStorage,
-    python_runner_options::PythonRunnerOptions, runner_type::RunnerType,
+    python_runner_options::PythonRunnerOptions, runner_type::RunnerType, sandbox::Sandbox,
 };
 
 pub struct PythonRunner {
@@ -521,7 +521,10 @@ print("</hanzo-code-result>")
             .insert(self.code.entrypoint.clone(), adapted_entrypoint_code);
 
         let result = match resolved_runner_type {
-            RunnerType::Host => self.run_in_host(code, envs, max_execution_timeout).await,
+            RunnerType::Host | RunnerType::Sandbox => {
+                self.run_in_host(code, envs, max_execution_timeout, resolved_runner_type)
+                    .await
+            }
             RunnerType::Docker => self.run_in_docker(code, envs, max_execution_timeout).await,
         }
         .map_err(|e| ExecutionError::new(e.to_string(), None))?;
@@ -797,6 +800,7 @@ print("</hanzo-code-result>")
         code_files: CodeFiles,
         envs: Option<HashMap<String, String>>,
         max_execution_timeout: Option<Duration>,
+        runner_type: RunnerType,
     ) -> anyhow::Result<Vec<String>> {
         let execution_storage = ExecutionStorage::new(code_files, self.options.context.clone());
         execution_storage.init_for_python(None)?;
@@ -827,6 +831,22 @@ print("</hanzo-code-result>")
             .stderr(std::process::Stdio::piped())
             .kill_on_drop(true);
 
+        if matches!(runner_type, RunnerType::Sandbox) {
+            let uv_cache_folder_path = execution_storage.python_run_sandbox_uv_cache_folder_path();
+            let mut writable_paths = vec![
+                execution_storage.root_folder_path.clone(),
+                uv_cache_folder_path.clone(),
+            ];
+            for mount_file in &self.options.context.mount_files {
+                writable_paths.push(path::absolute(mount_file)?);
+            }
+            Sandbox::new(writable_paths, self.options.sandbox.clone()).apply(command)?;
+
+            // The uv cache and pythons of the user are read-only in the sandbox
+            command.env("UV_CACHE_DIR", uv_cache_folder_path.join("cache"));
+            command.env("UV_PYTHON_INSTALL_DIR", uv_cache_folder_path.join("python"));
+        }
+
         command.env(
             "VIRTUAL_ENV",
             execution_storage
@@ -0,0 +1,143 @@
+use std::path::PathBuf;
+
+use super::{is_sandbox_available, Sandbox, SandboxOptions};
+
+async fn run_in_sandbox(
+    writable_paths: Vec<PathBuf>,
+    options: SandboxOptions,
+    script: &str,
+) -> bool {
+    let mut command = tokio::process::Command::new("/bin/sh");
+    command.args(["-c", script]);
+    Sandbox::new(writable_paths, options)
+        .apply(&mut command)
+        .unwrap();
+    command.status().await.unwrap().success()
+}
+
+#[tokio::test]
+async fn write_only_to_writable_paths() {
+    if !is_sandbox_available() {
+        eprintln!("Skipping sandbox test: the sandbox is not available");
+        return;
+    }
+
+    let writable = tempfile::tempdir().unwrap();
+    let read_only = tempfile::tempdir().unwrap();
+    std::fs::write(read_only.path().join("file.txt"), "hello").unwrap();
+
+    let script = format!(
+        "echo hello > {writable}/file.txt && cat {read_only}/file.txt && ! echo hello > {read_only}/other.txt",
+        writable = writable.path().display(),
+        read_only = read_only.path().display(),
+    );
+    assert!(
+        run_in_sandbox(
+            vec![writable.path().to_path_buf()],
+            SandboxOptions::default(),
+            &script
+        )
+        .await
+    );
+    assert!(writable.path().join("file.txt").exists());
+    assert!(!read_only.path().join("other.txt").exists());
+}
+
+#[tokio::test]
+async fn tmp_is_private() {
+    if !is_sandbox_available() {
+        eprintln!("Skipping sandbox test: the sandbox is not available");
+        return;
+    }
+
+    let file_name = format!("hanzo-sandbox-{}", nanoid::nanoid!());
+    let script = format!("echo hello > /tmp/{}", file_name);
+    assert!(run_in_sandbox(vec![], SandboxOptions::default(), &script).await);
+    assert!(!std::env::temp_dir().join(file_name).exists());
+}
+
+#[tokio::test]
+async fn run_without_network() {
+    if !is_sandbox_available() {
+        eprintln!("Skipping sandbox test: the sandbox is not available");
+        return;
+    }
+
+    // Only the loopback interface is left, after the two header lines
+    let script = "test \"$(tail -n +3 /proc/net/dev | wc -l)\" = 1";
+    let options = SandboxOptions {
+        network: false,
+        ..Default::default()
+    };
+    assert!(run_in_sandbox(vec![], options, script).await);
+}
+
+#[tokio::test]
+async fn rlimits_are_applied() {
+    if !is_sandbox_available() {
+        eprintln!("Skipping sandbox test: the sandbox is not available");
+        return;
+    }
+
+    let options = SandboxOptions {
+        max_cpu_time_secs: Some(7),
+        max_memory_bytes: Some(1024 * 1024 * 1024),
+        ..Default::default()
+    };
+    let script = "grep -q '^Max cpu time *7 *7 ' /proc/self/limits \
+        && grep -q '^Max address space *1073741824 *1073741824 ' /proc/self/limits \
+        && grep -q '^Max core file size *0 *0 ' /proc/self/limits";
+    assert!(run_in_sandbox(vec![], options, script).await);
+}
+
+/// Whether `syscall`, made in the sandbox right before exec, fails with EPERM
+#[cfg(target_os = "linux")]
+async fn syscall_is_denied(syscall: fn() -> libc::c_long) -> bool {
+    let mut command = tokio::process::Command::new("/bin/sh");
+    command.args(["-c", "exit 0"]);
+    Sandbox::new(vec![], SandboxOptions::default())
+        .apply(&mut command)
+        .unwrap();
+    // SAFETY: runs after the sandbox is entered, and only makes syscalls
+    unsafe {
+        command.pre_exec(move || {
+            let result = syscall();
+            match std::io::Error::last_os_error().raw_os_error() {
+                Some(libc::EPERM) if result == -1 => Ok(()),
+                _ => Err(std::io::Error::other("the syscall was allowed")),
+            }
+        });
+    }
+    command.status().await.is_ok()
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn seccomp_denies_new_namespaces() {
+    if !is_sandbox_available() {
+        eprintln!("Skipping sandbox test: the sandbox is not available");
+        return;
+    }
+
+    assert!(
+        syscall_is_denied(|| unsafe { libc::unshare(libc::CLONE_NEWUSER) as libc::c_long }).await
+    );
+    assert!(
+        syscall_is_denied(|| unsafe {
+            let pid = libc::syscall(
+                libc::SYS_clone,
+                libc::CLONE_NEWUSER | libc::SIGCHLD,
+                0,
+                0,
+                0,
+                0,
+            );
+            if pid == 0 {
+                // The clone was allowed, the new process leaves right away
+                libc::_exit(0);
+            }
+            pid
+        })
+        .await
+    );
+}
@@ -1,8 +1,8 @@
+use hanzo_messages::schemas::x402_types::{Network, PaymentRequirements};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use hanzo_messages::schemas::x402_types::PaymentRequirements;
 
-use crate::{NonRustCodeRunnerFactory, NonRustRuntime, RunError};
+use super::error::X402Error;
+use super::exact_evm::{self, SCHEME};
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -18,44 +18,96 @@ pub struct Output {
     pub payment: String,
 }
 
-pub async fn create_payment(input: Input) -> Result<Output, RunError> {
-    let code = include_str!("createPaymentDenoImpl.ts");
-    let runner = NonRustCodeRunnerFactory::new("create_payment", code, vec![])
-        .with_runtime(NonRustRuntime::Deno)
-        .create_runner(json!({}));
-    runner.run::<_, Output>(input, None).await
+/// Picks the requirements to pay among the accepted ones: the `exact` scheme on the network,
+/// preferably in USDC
+pub fn select_payment_requirements<'a>(
+    accepts: &'a [PaymentRequirements],
+    network: Option<&Network>,
+    scheme: &str,
+) -> Option<&'a PaymentRequirements> {
+    let mut broadly_accepted = accepts
+        .iter()
+        .filter(|requirements| requirements.scheme == scheme)
+        .filter(|requirements| network.map_or(true, |network| &requirements.network == network))
+        .peekable();
+    let first_accepted = broadly_accepted.peek().copied();
+
+    broadly_accepted
+        .find(|requirements| requirements.asset == exact_evm::usdc_asset(&requirements.network).address)
+        .or(first_accepted)
+        .or_else(|| accepts.first())
+}
+
+/// Creates the X-PAYMENT header of a payment signed at `now` with the given nonce
+pub fn create_payment_header(input: &Input, now: u64, nonce: String) -> Result<String, X402Error> {
+    let requirements =
+        select_payment_requirements(&input.accepts, None, SCHEME).ok_or(X402Error::NoMatchingPaymentRequirements)?;
+    let payment = exact_evm::create_payment_payload(&input.private_key, input.x402_version, requirements, now, nonce)?;
+    exact_evm::encode_payment(&payment)
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use hanzo_messages::schemas::x402_types::Network;
-
-//     use super::*;
-//     use crate::test_utils::testing_create_tempdir_and_set_env_var;
-
-//     #[tokio::test]
-//     async fn test_create_payment() {
-//         let _dir = testing_create_tempdir_and_set_env_var();
-//         let price_in_raw_usd = 0.001;
-//         let input = Input {
-//             accepts: vec![PaymentRequirements {
-//                 scheme: "exact".to_string(),
-//                 description: "Test payment".to_string(),
-//                 network: Network::BaseSepolia,
-//                 max_amount_required: (price_in_raw_usd * 1000000.0).to_string(),
-//                 resource: "https://hanzo.ai".to_string(),
-//                 mime_type: "".to_string(),
-//                 pay_to: std::env::var("X402_PAY_TO").expect("X402_PAY_TO must be set"),
-//                 max_timeout_seconds: 300,
-//                 asset: "0x6e7907fbcEe166bd4000a22e0eBaA63B2c977534".to_string(),
-//                 output_schema: Some(json!({})),
-//                 extra: Some(json!({})),
-//             }],
-//             x402_version: 1,
-//             private_key: std::env::var("X402_PRIVATE_KEY").expect("X402_PRIVATE_KEY must be set"),
-//         };
-
-//         let output = create_payment(input).await.unwrap();
-//         assert!(!output.payment.is_empty());
-//     }
-// }
+pub async fn create_payment(input: Input) -> Result<Output, X402Error> {
+    let payment = create_payment_header(&input, exact_evm::unix_timestamp(), exact_evm::create_nonce())?;
+    Ok(Output { payment })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const PAY_TO: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    fn requirements(network: Network, asset: &str) -> PaymentRequirements {
+        PaymentRequirements::new(
+            network,
+            "1000".to_string(),
+            PAY_TO.to_string(),
+            asset.to_string(),
+            "https://hanzo.ai".to_string(),
+        )
+        .with_extra(json!({ "name": "USDC", "version": "2" }))
+    }
+
+    #[test]
+    fn test_select_payment_requirements_prefers_usdc() {
+        let other_token = requirements(Network::BaseSepolia, "0x0000000000000000000000000000000000000001");
+        let usdc = requirements(Network::Base, &exact_evm::usdc_asset(&Network::Base).address);
+        let accepts = vec![other_token.clone(), usdc.clone()];
+
+        assert_eq!(select_payment_requirements(&accepts, None, SCHEME), Some(&usdc));
+        assert_eq!(
+            select_payment_requirements(&accepts, Some(&Network::BaseSepolia), SCHEME),
+            Some(&other_token)
+        );
+        // Falls back to the first requirements when none matches
+        assert_eq!(
+            select_payment_requirements(&accepts, Some(&Network::Avalanche), SCHEME),
+            Some(&other_token)
+        );
+        assert_eq!(select_payment_requirements(&[], None, SCHEME), None);
+    }
+
+    #[tokio::test]
+    async fn test_create_payment() {
+        let accepts = vec![requirements(
+            Network::BaseSepolia,
+            &exact_evm::usdc_asset(&Network::BaseSepolia).address,
+        )];
+        let input = Input {
+            accepts: accepts.clone(),
+            x402_version: 1,
+            private_key: PRIVATE_KEY.to_string(),
+        };
+
+        let now = exact_evm::unix_timestamp();
+        let output = create_payment(input).await.unwrap();
+        let payment = exact_evm::decode_payment(&output.payment).unwrap();
+        assert_eq!(payment.payload.authorization.to, PAY_TO);
+        assert_eq!(
+            exact_evm::verify_payment_payload(&payment, &accepts[0], now).unwrap(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+    }
+}
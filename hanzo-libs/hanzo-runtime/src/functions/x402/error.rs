@@ -0,0 +1,36 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum X402Error {
+    InvalidPrice(String),
+    InvalidPrivateKey(String),
+    InvalidAddress(String),
+    InvalidAmount(String),
+    InvalidSignature(String),
+    // The name and version of the EIP-712 domain of the asset are unknown
+    MissingEip712Domain(String),
+    InvalidPaymentHeader(String),
+    NoMatchingPaymentRequirements,
+    // The payment is well formed but does not satisfy the requirements, holds the x402 reason code
+    InvalidPayment(String),
+    FacilitatorError(String),
+}
+
+impl fmt::Display for X402Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            X402Error::InvalidPrice(err) => write!(f, "invalid price: {}", err),
+            X402Error::InvalidPrivateKey(err) => write!(f, "invalid private key: {}", err),
+            X402Error::InvalidAddress(err) => write!(f, "invalid address: {}", err),
+            X402Error::InvalidAmount(err) => write!(f, "invalid amount: {}", err),
+            X402Error::InvalidSignature(err) => write!(f, "invalid signature: {}", err),
+            X402Error::MissingEip712Domain(asset) => write!(f, "unknown EIP-712 domain for asset {}", asset),
+            X402Error::InvalidPaymentHeader(err) => write!(f, "invalid payment header: {}", err),
+            X402Error::NoMatchingPaymentRequirements => write!(f, "Unable to find matching payment requirements"),
+            X402Error::InvalidPayment(reason) => write!(f, "{}", reason),
+            X402Error::FacilitatorError(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for X402Error {}
@@ -0,0 +1,505 @@
+//! The `exact` x402 scheme on EVM networks: the payer signs an EIP-3009 `TransferWithAuthorization`
+//! of the asset (EIP-712 typed data) that the facilitator then submits onchain.
+
+use base64::Engine;
+use hanzo_messages::schemas::x402_types::{
+    ERC20Asset, Network, PaymentAuthorization, PaymentPayload, PaymentPayloadData, PaymentRequirements, EIP712,
+};
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::error::X402Error;
+
+pub const SCHEME: &str = "exact";
+
+const EIP712_DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const TRANSFER_WITH_AUTHORIZATION_TYPE: &str = "TransferWithAuthorization(address from,address to,uint256 value,uint256 validAfter,uint256 validBefore,bytes32 nonce)";
+
+/// A signed authorization must stay valid at least this long for the facilitator to settle it
+const MIN_VALIDITY_SECONDS: u64 = 6;
+
+pub fn chain_id(network: &Network) -> u64 {
+    match network {
+        Network::BaseSepolia => 84532,
+        Network::Base => 8453,
+        Network::AvalancheFuji => 43113,
+        Network::Avalanche => 43114,
+    }
+}
+
+/// USDC of the network, the asset of prices given in money
+pub fn usdc_asset(network: &Network) -> ERC20Asset {
+    let (address, name) = match network {
+        Network::BaseSepolia => ("0x036CbD53842c5426634e7929541eC2318f3dCF7e", "USDC"),
+        Network::Base => ("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913", "USD Coin"),
+        Network::AvalancheFuji => ("0x5425890298aed601595a70AB815c96711a31Bc65", "USD Coin"),
+        Network::Avalanche => ("0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E", "USD Coin"),
+    };
+    ERC20Asset {
+        address: address.to_string(),
+        decimals: 6,
+        eip712: EIP712 {
+            name: name.to_string(),
+            version: "2".to_string(),
+        },
+    }
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+pub fn parse_address(address: &str) -> Result<[u8; 20], X402Error> {
+    let bytes = hex::decode(address.trim_start_matches("0x")).map_err(|e| X402Error::InvalidAddress(e.to_string()))?;
+    bytes
+        .try_into()
+        .map_err(|_| X402Error::InvalidAddress(format!("{} is not 20 bytes long", address)))
+}
+
+/// EIP-55 mixed-case encoding of an address
+pub fn to_checksum_address(address: &[u8; 20]) -> String {
+    let lower = hex::encode(address);
+    let hash = hex::encode(keccak256(lower.as_bytes()));
+    let checksummed: String = lower
+        .chars()
+        .zip(hash.chars())
+        .map(|(c, h)| {
+            if c.is_ascii_alphabetic() && h.to_digit(16).unwrap_or(0) >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", checksummed)
+}
+
+pub fn same_address(a: &str, b: &str) -> bool {
+    a.trim_start_matches("0x")
+        .eq_ignore_ascii_case(b.trim_start_matches("0x"))
+}
+
+/// Decimal string as an ABI encoded uint256
+fn encode_uint256(value: &str, field: &str) -> Result<[u8; 32], X402Error> {
+    let value = value
+        .parse::<u128>()
+        .map_err(|e| X402Error::InvalidAmount(format!("{} '{}': {}", field, value, e)))?;
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    Ok(word)
+}
+
+fn encode_address(address: &str) -> Result<[u8; 32], X402Error> {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&parse_address(address)?);
+    Ok(word)
+}
+
+fn encode_bytes32(value: &str, field: &str) -> Result<[u8; 32], X402Error> {
+    let bytes = hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| X402Error::InvalidAmount(format!("{} '{}': {}", field, value, e)))?;
+    bytes
+        .try_into()
+        .map_err(|_| X402Error::InvalidAmount(format!("{} '{}' is not 32 bytes long", field, value)))
+}
+
+/// Name and version of the EIP-712 domain of the asset, from the `extra` of the requirements
+/// or, for USDC, from the known deployments
+pub fn eip712_domain(requirements: &PaymentRequirements) -> Result<EIP712, X402Error> {
+    let extra_field = |field: &str| {
+        requirements
+            .extra
+            .as_ref()
+            .and_then(|extra| extra.get(field))
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string())
+    };
+    if let (Some(name), Some(version)) = (extra_field("name"), extra_field("version")) {
+        return Ok(EIP712 { name, version });
+    }
+
+    let usdc = usdc_asset(&requirements.network);
+    if same_address(&usdc.address, &requirements.asset) {
+        return Ok(usdc.eip712);
+    }
+    Err(X402Error::MissingEip712Domain(requirements.asset.clone()))
+}
+
+pub fn domain_separator(domain: &EIP712, chain_id: u64, verifying_contract: &str) -> Result<[u8; 32], X402Error> {
+    let mut encoded = Vec::with_capacity(5 * 32);
+    encoded.extend_from_slice(&keccak256(EIP712_DOMAIN_TYPE.as_bytes()));
+    encoded.extend_from_slice(&keccak256(domain.name.as_bytes()));
+    encoded.extend_from_slice(&keccak256(domain.version.as_bytes()));
+    encoded.extend_from_slice(&encode_uint256(&chain_id.to_string(), "chainId")?);
+    encoded.extend_from_slice(&encode_address(verifying_contract)?);
+    Ok(keccak256(&encoded))
+}
+
+pub fn authorization_struct_hash(authorization: &PaymentAuthorization) -> Result<[u8; 32], X402Error> {
+    let mut encoded = Vec::with_capacity(7 * 32);
+    encoded.extend_from_slice(&keccak256(TRANSFER_WITH_AUTHORIZATION_TYPE.as_bytes()));
+    encoded.extend_from_slice(&encode_address(&authorization.from)?);
+    encoded.extend_from_slice(&encode_address(&authorization.to)?);
+    encoded.extend_from_slice(&encode_uint256(&authorization.value, "value")?);
+    encoded.extend_from_slice(&encode_uint256(&authorization.valid_after, "validAfter")?);
+    encoded.extend_from_slice(&encode_uint256(&authorization.valid_before, "validBefore")?);
+    encoded.extend_from_slice(&encode_bytes32(&authorization.nonce, "nonce")?);
+    Ok(keccak256(&encoded))
+}
+
+/// The EIP-712 hash signed by the payer
+pub fn authorization_digest(
+    authorization: &PaymentAuthorization,
+    requirements: &PaymentRequirements,
+) -> Result<[u8; 32], X402Error> {
+    let domain = eip712_domain(requirements)?;
+    let domain_separator = domain_separator(&domain, chain_id(&requirements.network), &requirements.asset)?;
+
+    let mut encoded = Vec::with_capacity(2 + 2 * 32);
+    encoded.extend_from_slice(b"\x19\x01");
+    encoded.extend_from_slice(&domain_separator);
+    encoded.extend_from_slice(&authorization_struct_hash(authorization)?);
+    Ok(keccak256(&encoded))
+}
+
+fn signing_key(private_key: &str) -> Result<SigningKey, X402Error> {
+    let bytes =
+        hex::decode(private_key.trim_start_matches("0x")).map_err(|e| X402Error::InvalidPrivateKey(e.to_string()))?;
+    SigningKey::from_slice(&bytes).map_err(|e| X402Error::InvalidPrivateKey(e.to_string()))
+}
+
+fn address_of(verifying_key: &VerifyingKey) -> String {
+    let public_key = verifying_key.to_encoded_point(false);
+    let hash = keccak256(&public_key.as_bytes()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    to_checksum_address(&address)
+}
+
+/// Checksummed address of the account of a private key
+pub fn address_from_private_key(private_key: &str) -> Result<String, X402Error> {
+    Ok(address_of(signing_key(private_key)?.verifying_key()))
+}
+
+/// Signs an authorization, returning the 65 bytes `r || s || v` signature in hex
+pub fn sign_authorization(
+    private_key: &str,
+    authorization: &PaymentAuthorization,
+    requirements: &PaymentRequirements,
+) -> Result<String, X402Error> {
+    let digest = authorization_digest(authorization, requirements)?;
+    let (signature, recovery_id) = signing_key(private_key)?
+        .sign_prehash_recoverable(&digest)
+        .map_err(|e| X402Error::InvalidSignature(e.to_string()))?;
+
+    let mut bytes = signature.to_bytes().to_vec();
+    bytes.push(27 + recovery_id.to_byte());
+    Ok(format!("0x{}", hex::encode(bytes)))
+}
+
+/// Checksummed address of the account that signed an authorization
+pub fn recover_signer(
+    signature: &str,
+    authorization: &PaymentAuthorization,
+    requirements: &PaymentRequirements,
+) -> Result<String, X402Error> {
+    let bytes =
+        hex::decode(signature.trim_start_matches("0x")).map_err(|e| X402Error::InvalidSignature(e.to_string()))?;
+    if bytes.len() != 65 {
+        return Err(X402Error::InvalidSignature(format!(
+            "expected 65 bytes, got {}",
+            bytes.len()
+        )));
+    }
+    let signature = Signature::from_slice(&bytes[..64]).map_err(|e| X402Error::InvalidSignature(e.to_string()))?;
+    // The token contracts refuse malleable signatures
+    if signature.normalize_s().is_some() {
+        return Err(X402Error::InvalidSignature("high s value".to_string()));
+    }
+    let v = bytes[64];
+    let recovery_id = RecoveryId::from_byte(if v >= 27 { v - 27 } else { v })
+        .ok_or_else(|| X402Error::InvalidSignature(format!("invalid recovery id {}", v)))?;
+
+    let digest = authorization_digest(authorization, requirements)?;
+    let verifying_key = VerifyingKey::recover_from_prehash(&digest, &signature, recovery_id)
+        .map_err(|e| X402Error::InvalidSignature(e.to_string()))?;
+    Ok(address_of(&verifying_key))
+}
+
+/// Seconds since the epoch, the unit of the validity window of authorizations
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Random nonce of an authorization
+pub fn create_nonce() -> String {
+    format!("0x{}", hex::encode(rand::random::<[u8; 32]>()))
+}
+
+/// Signs a transfer of the amount required to the payee, valid from 10 minutes before `now`
+/// until the timeout of the requirements
+pub fn create_payment_payload(
+    private_key: &str,
+    x402_version: u32,
+    requirements: &PaymentRequirements,
+    now: u64,
+    nonce: String,
+) -> Result<PaymentPayload, X402Error> {
+    let authorization = PaymentAuthorization {
+        from: address_from_private_key(private_key)?,
+        to: requirements.pay_to.clone(),
+        value: requirements.max_amount_required.clone(),
+        valid_after: now.saturating_sub(600).to_string(),
+        valid_before: (now + requirements.max_timeout_seconds).to_string(),
+        nonce,
+    };
+    let signature = sign_authorization(private_key, &authorization, requirements)?;
+
+    Ok(PaymentPayload {
+        scheme: SCHEME.to_string(),
+        network: requirements.network.clone(),
+        x402_version,
+        payload: PaymentPayloadData {
+            signature,
+            authorization,
+        },
+    })
+}
+
+/// Payload as serialized in the X-PAYMENT header, fields in the order of the reference client
+#[derive(Serialize, Deserialize)]
+struct EncodedPayment {
+    #[serde(rename = "x402Version")]
+    x402_version: u32,
+    scheme: String,
+    network: Network,
+    payload: PaymentPayloadData,
+}
+
+/// Base64 encoded JSON of a payment, the value of the X-PAYMENT header
+pub fn encode_payment(payment: &PaymentPayload) -> Result<String, X402Error> {
+    let encoded = EncodedPayment {
+        x402_version: payment.x402_version,
+        scheme: payment.scheme.clone(),
+        network: payment.network.clone(),
+        payload: payment.payload.clone(),
+    };
+    let json = serde_json::to_string(&encoded).map_err(|e| X402Error::InvalidPaymentHeader(e.to_string()))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(json))
+}
+
+pub fn decode_payment(header: &str) -> Result<PaymentPayload, X402Error> {
+    let json = base64::engine::general_purpose::STANDARD
+        .decode(header.trim())
+        .map_err(|e| X402Error::InvalidPaymentHeader(e.to_string()))?;
+    let decoded: EncodedPayment =
+        serde_json::from_slice(&json).map_err(|e| X402Error::InvalidPaymentHeader(e.to_string()))?;
+    Ok(PaymentPayload {
+        scheme: decoded.scheme,
+        network: decoded.network,
+        x402_version: decoded.x402_version,
+        payload: decoded.payload,
+    })
+}
+
+fn parse_timestamp(value: &str, field: &str) -> Result<u64, X402Error> {
+    value
+        .parse::<u64>()
+        .map_err(|e| X402Error::InvalidAmount(format!("{} '{}': {}", field, value, e)))
+}
+
+/// Offchain checks of a payment against its requirements: the signature, payee, validity window
+/// and amount. The balance of the payer and the use of the nonce are left to the facilitator.
+/// Returns the payer.
+pub fn verify_payment_payload(
+    payment: &PaymentPayload,
+    requirements: &PaymentRequirements,
+    now: u64,
+) -> Result<String, X402Error> {
+    let invalid = |reason: &str| X402Error::InvalidPayment(reason.to_string());
+    if payment.scheme != SCHEME || requirements.scheme != SCHEME {
+        return Err(invalid("unsupported_scheme"));
+    }
+    if payment.network != requirements.network {
+        return Err(invalid("invalid_network"));
+    }
+
+    let authorization = &payment.payload.authorization;
+    let signer = recover_signer(&payment.payload.signature, authorization, requirements)
+        .map_err(|_| invalid("invalid_exact_evm_payload_signature"))?;
+    if !same_address(&signer, &authorization.from) {
+        return Err(invalid("invalid_exact_evm_payload_signature"));
+    }
+    if !same_address(&authorization.to, &requirements.pay_to) {
+        return Err(invalid("invalid_exact_evm_payload_recipient_mismatch"));
+    }
+    if parse_timestamp(&authorization.valid_before, "validBefore")? < now + MIN_VALIDITY_SECONDS {
+        return Err(invalid("invalid_exact_evm_payload_authorization_valid_before"));
+    }
+    if parse_timestamp(&authorization.valid_after, "validAfter")? > now {
+        return Err(invalid("invalid_exact_evm_payload_authorization_valid_after"));
+    }
+
+    let value = authorization
+        .value
+        .parse::<u128>()
+        .map_err(|_| invalid("invalid_exact_evm_payload_authorization_value"))?;
+    let required = requirements
+        .max_amount_required
+        .parse::<u128>()
+        .map_err(|e| X402Error::InvalidAmount(format!("maxAmountRequired: {}", e)))?;
+    if value < required {
+        return Err(invalid("invalid_exact_evm_payload_authorization_value"));
+    }
+
+    Ok(authorization.from.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Vectors produced with the reference x402 client (viem), for the first anvil account
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const PAYER: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const PAY_TO: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+    const NONCE: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+    const SIGNATURE: &str = "0x0d9593603ea5f5d8108810c3b7af539600cb90963822b44f974eda55f94221f1661569ee084f0d48595b16ba2b27f764b1c9c88d9f74a1825e18c3e380f84e8f1b";
+    const HEADER: &str = "eyJ4NDAyVmVyc2lvbiI6MSwic2NoZW1lIjoiZXhhY3QiLCJuZXR3b3JrIjoiYmFzZS1zZXBvbGlhIiwicGF5bG9hZCI6eyJzaWduYXR1cmUiOiIweDBkOTU5MzYwM2VhNWY1ZDgxMDg4MTBjM2I3YWY1Mzk2MDBjYjkwOTYzODIyYjQ0Zjk3NGVkYTU1Zjk0MjIxZjE2NjE1NjllZTA4NGYwZDQ4NTk1YjE2YmEyYjI3Zjc2NGIxYzljODhkOWY3NGExODI1ZTE4YzNlMzgwZjg0ZThmMWIiLCJhdXRob3JpemF0aW9uIjp7ImZyb20iOiIweGYzOUZkNmU1MWFhZDg4RjZGNGNlNmFCODgyNzI3OWNmZkZiOTIyNjYiLCJ0byI6IjB4NzA5OTc5NzBDNTE4MTJkYzNBMDEwQzdkMDFiNTBlMGQxN2RjNzlDOCIsInZhbHVlIjoiMTAwMCIsInZhbGlkQWZ0ZXIiOiIxNzE3MDAwMDAwIiwidmFsaWRCZWZvcmUiOiIxNzE3MDAwOTAwIiwibm9uY2UiOiIweDExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTEifX19";
+    const NOW: u64 = 1717000600;
+
+    fn requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            Network::BaseSepolia,
+            "1000".to_string(),
+            PAY_TO.to_string(),
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string(),
+            "https://hanzo.ai".to_string(),
+        )
+        .with_timeout(900)
+        .with_extra(json!({ "name": "USDC", "version": "2" }))
+    }
+
+    #[test]
+    fn test_eip712_spec_vector() {
+        // The "Mail" example of EIP-712, only its domain has the shape used by the tokens
+        let domain = EIP712 {
+            name: "Ether Mail".to_string(),
+            version: "1".to_string(),
+        };
+        let separator = domain_separator(&domain, 1, "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC").unwrap();
+        assert_eq!(
+            hex::encode(separator),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        assert_eq!(
+            address_from_private_key(&hex::encode(keccak256(b"cow"))).unwrap(),
+            "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+        );
+    }
+
+    #[test]
+    fn test_transfer_with_authorization_vector() {
+        let payment = create_payment_payload(PRIVATE_KEY, 1, &requirements(), NOW, NONCE.to_string()).unwrap();
+        let authorization = &payment.payload.authorization;
+        assert_eq!(authorization.from, PAYER);
+        assert_eq!(authorization.valid_after, "1717000000");
+        assert_eq!(authorization.valid_before, "1717001500");
+
+        // Same authorization as the reference vector, valid until 1717000900
+        let authorization = PaymentAuthorization {
+            valid_before: "1717000900".to_string(),
+            ..authorization.clone()
+        };
+        assert_eq!(
+            hex::encode(authorization_struct_hash(&authorization).unwrap()),
+            "e36785ad79038651e7908bdc90f26944e7e6ac133cbdd03590c4de73e913fa83"
+        );
+        assert_eq!(
+            hex::encode(authorization_digest(&authorization, &requirements()).unwrap()),
+            "3f34195955a73dca633c1f436ebf11e59663e5a05552b02e1fcf54a68edb74fe"
+        );
+        assert_eq!(
+            sign_authorization(PRIVATE_KEY, &authorization, &requirements()).unwrap(),
+            SIGNATURE
+        );
+        assert_eq!(
+            recover_signer(SIGNATURE, &authorization, &requirements()).unwrap(),
+            PAYER
+        );
+    }
+
+    #[test]
+    fn test_payment_header_vector() {
+        let payment = decode_payment(HEADER).unwrap();
+        assert_eq!(payment.payload.signature, SIGNATURE);
+        assert_eq!(payment.payload.authorization.nonce, NONCE);
+        assert_eq!(encode_payment(&payment).unwrap(), HEADER);
+
+        assert_eq!(verify_payment_payload(&payment, &requirements(), NOW).unwrap(), PAYER);
+    }
+
+    #[test]
+    fn test_verify_payment_payload_failures() {
+        let payment = decode_payment(HEADER).unwrap();
+        let reason =
+            |payment: &PaymentPayload, requirements: &PaymentRequirements, now: u64| match verify_payment_payload(
+                payment,
+                requirements,
+                now,
+            ) {
+                Err(X402Error::InvalidPayment(reason)) => reason,
+                other => panic!("unexpected result {:?}", other),
+            };
+
+        let mut tampered = payment.clone();
+        tampered.payload.authorization.value = "1".to_string();
+        assert_eq!(
+            reason(&tampered, &requirements(), NOW),
+            "invalid_exact_evm_payload_signature"
+        );
+
+        let mut other_payee = requirements();
+        other_payee.pay_to = PAYER.to_string();
+        assert_eq!(
+            reason(&payment, &other_payee, NOW),
+            "invalid_exact_evm_payload_recipient_mismatch"
+        );
+
+        assert_eq!(
+            reason(&payment, &requirements(), 1717000899),
+            "invalid_exact_evm_payload_authorization_valid_before"
+        );
+        assert_eq!(
+            reason(&payment, &requirements(), 1716999999),
+            "invalid_exact_evm_payload_authorization_valid_after"
+        );
+
+        let mut more_expensive = requirements();
+        more_expensive.max_amount_required = "1001".to_string();
+        assert_eq!(
+            reason(&payment, &more_expensive, NOW),
+            "invalid_exact_evm_payload_authorization_value"
+        );
+    }
+
+    #[test]
+    fn test_eip712_domain_defaults_to_usdc() {
+        let mut requirements = requirements();
+        requirements.extra = None;
+        assert_eq!(eip712_domain(&requirements).unwrap().name, "USDC");
+
+        requirements.asset = PAY_TO.to_string();
+        assert_eq!(
+            eip712_domain(&requirements),
+            Err(X402Error::MissingEip712Domain(PAY_TO.to_string()))
+        );
+    }
+}
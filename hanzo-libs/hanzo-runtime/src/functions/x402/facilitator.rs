@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use hanzo_messages::schemas::x402_types::{FacilitatorConfig, PaymentPayload, PaymentRequirements};
+
+use super::error::X402Error;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyResponse {
+    pub is_valid: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invalid_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SettleResponse {
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_reason: Option<String>,
+    #[serde(default)]
+    pub transaction: String,
+    #[serde(default)]
+    pub network: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
+}
+
+/// Base64 encoded JSON of a settlement, the value of the X-PAYMENT-RESPONSE header
+pub fn settle_response_header(response: &SettleResponse) -> Result<String, X402Error> {
+    let json = serde_json::to_string(response).map_err(|e| X402Error::FacilitatorError(e.to_string()))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(json))
+}
+
+/// Settling waits for the transaction to be submitted on chain, so it gets more than a plain request
+const FACILITATOR_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Client of an x402 facilitator, which checks payments against the chain and submits them
+pub struct FacilitatorClient {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl FacilitatorClient {
+    pub fn new(config: &FacilitatorConfig) -> Self {
+        FacilitatorClient {
+            url: config.url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::builder()
+                .timeout(FACILITATOR_REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub async fn verify(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse, X402Error> {
+        self.post("verify", payment, requirements).await
+    }
+
+    pub async fn settle(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse, X402Error> {
+        self.post("settle", payment, requirements).await
+    }
+
+    async fn post<T: for<'de> Deserialize<'de>>(
+        &self,
+        action: &str,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<T, X402Error> {
+        let body = json!({
+            "x402Version": payment.x402_version,
+            "paymentPayload": payment,
+            "paymentRequirements": requirements,
+        });
+        let response = self
+            .client
+            .post(format!("{}/{}", self.url, action))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| X402Error::FacilitatorError(e.to_string()))?;
+
+        if response.status() != reqwest::StatusCode::OK {
+            return Err(X402Error::FacilitatorError(format!(
+                "Failed to {} payment: {}",
+                action,
+                response.status()
+            )));
+        }
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| X402Error::FacilitatorError(e.to_string()))
+    }
+}
@@ -1,4 +1,7 @@
 pub mod create_payment;
+pub mod error;
+pub mod exact_evm;
+pub mod facilitator;
 pub mod payment_requirements;
 pub mod settle_payment;
 pub mod verify_payment;
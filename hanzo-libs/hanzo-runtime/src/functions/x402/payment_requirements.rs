@@ -1,57 +1,125 @@
+use hanzo_messages::schemas::x402_types::{ERC20Asset, Network, PaymentRequirements, Price};
 use serde::Deserialize;
-use serde_json::json;
-use hanzo_messages::schemas::x402_types::PaymentRequirements;
-
-use crate::{NonRustCodeRunnerFactory, NonRustRuntime, RunError};
 
+use super::error::X402Error;
+use super::exact_evm;
 use super::verify_payment::Input;
 
 pub type PaymentRequirementsInput = Input;
 
+const RESOURCE: &str = "https://hanzo.ai";
+const MAX_TIMEOUT_SECONDS: u64 = 300;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PaymentRequirementsOutput {
     pub payment_requirements: Vec<PaymentRequirements>,
 }
 
-pub async fn get_payment_requirements(input: PaymentRequirementsInput) -> Result<PaymentRequirementsOutput, RunError> {
-    let code = include_str!("paymentRequirementsDenoImpl.ts");
-    let runner = NonRustCodeRunnerFactory::new("payment_requirements", code, vec![])
-        .with_runtime(NonRustRuntime::Deno)
-        .create_runner(json!({}));
-    runner.run::<_, PaymentRequirementsOutput>(input, None).await
+/// Amount in the smallest unit of the asset and the asset of a price. Prices in money are paid
+/// in USDC of the network.
+pub fn process_price_to_atomic_amount(price: &Price, network: &Network) -> Result<(String, ERC20Asset), X402Error> {
+    match price {
+        Price::Money(amount) => {
+            if !(0.0001..=999999999.0).contains(amount) {
+                return Err(X402Error::InvalidPrice(format!(
+                    "{} is not between 0.0001 and 999999999",
+                    amount
+                )));
+            }
+            let asset = exact_evm::usdc_asset(network);
+            let atomic_amount = amount * 10f64.powi(asset.decimals as i32);
+            Ok((format!("{}", atomic_amount), asset))
+        }
+        Price::ERC20TokenAmount(token_amount) => Ok((token_amount.amount.clone(), token_amount.asset.clone())),
+    }
+}
+
+/// What a client has to pay to the payee of the input
+pub fn build_payment_requirements(input: &PaymentRequirementsInput) -> Result<Vec<PaymentRequirements>, X402Error> {
+    let (max_amount_required, asset) = process_price_to_atomic_amount(&input.price, &input.network)?;
+    let extra = serde_json::to_value(&asset.eip712).map_err(|e| X402Error::InvalidPrice(e.to_string()))?;
+
+    Ok(vec![PaymentRequirements::new(
+        input.network.clone(),
+        max_amount_required,
+        input.pay_to.clone(),
+        asset.address,
+        RESOURCE.to_string(),
+    )
+    .with_timeout(MAX_TIMEOUT_SECONDS)
+    .with_extra(extra)])
+}
+
+pub async fn get_payment_requirements(input: PaymentRequirementsInput) -> Result<PaymentRequirementsOutput, X402Error> {
+    Ok(PaymentRequirementsOutput {
+        payment_requirements: build_payment_requirements(&input)?,
+    })
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use hanzo_messages::schemas::x402_types::{FacilitatorConfig, Network, Price};
-
-//     use super::*;
-//     use crate::test_utils::testing_create_tempdir_and_set_env_var;
-
-//     #[tokio::test]
-//     async fn test_payment_requirements() {
-//         let _dir = testing_create_tempdir_and_set_env_var();
-
-//         let price_in_raw_usd = 0.001;
-//         let input = PaymentRequirementsInput {
-//             price: Price::Money(price_in_raw_usd),
-//             network: Network::BaseSepolia,
-//             pay_to: std::env::var("X402_PAY_TO").expect("X402_PAY_TO must be set"),
-//             payment: None,
-//             x402_version: 1,
-//             facilitator: FacilitatorConfig::default(),
-//         };
-
-//         let output = get_payment_requirements(input).await.unwrap();
-//         assert!(!output.payment_requirements.is_empty());
-
-//         let requirements = &output.payment_requirements[0];
-//         assert_eq!(
-//             requirements.max_amount_required,
-//             (price_in_raw_usd * 1000000.0).to_string()
-//         );
-//         assert_eq!(requirements.scheme, "exact");
-//         assert_eq!(requirements.network, Network::BaseSepolia);
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hanzo_messages::schemas::x402_types::{ERC20TokenAmount, FacilitatorConfig, EIP712};
+    use serde_json::json;
+
+    fn input(price: Price) -> PaymentRequirementsInput {
+        PaymentRequirementsInput {
+            price,
+            network: Network::BaseSepolia,
+            pay_to: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+            payment: None,
+            x402_version: 1,
+            facilitator: FacilitatorConfig::default(),
+        }
+    }
+
+    #[test]
+    fn test_money_price_requirements() {
+        let requirements = build_payment_requirements(&input(Price::Money(0.001))).unwrap();
+        assert_eq!(
+            serde_json::to_value(&requirements).unwrap(),
+            json!([{
+                "scheme": "exact",
+                "description": "",
+                "network": "base-sepolia",
+                "maxAmountRequired": "1000",
+                "resource": "https://hanzo.ai",
+                "mimeType": "",
+                "payTo": "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+                "maxTimeoutSeconds": 300,
+                "asset": "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                "outputSchema": {},
+                "extra": { "name": "USDC", "version": "2" }
+            }])
+        );
+
+        // Same float formatting as the reference implementation
+        let (amount, _) = process_price_to_atomic_amount(&Price::Money(0.0001), &Network::Base).unwrap();
+        assert_eq!(amount, "100.00000000000001");
+        assert!(matches!(
+            process_price_to_atomic_amount(&Price::Money(0.00001), &Network::Base),
+            Err(X402Error::InvalidPrice(_))
+        ));
+    }
+
+    #[test]
+    fn test_token_amount_requirements() {
+        let asset = ERC20Asset {
+            address: "0x0000000000000000000000000000000000000001".to_string(),
+            decimals: 18,
+            eip712: EIP712 {
+                name: "Token".to_string(),
+                version: "1".to_string(),
+            },
+        };
+        let price = Price::ERC20TokenAmount(ERC20TokenAmount {
+            amount: "5000".to_string(),
+            asset: asset.clone(),
+        });
+        let requirements = build_payment_requirements(&input(price)).unwrap();
+        assert_eq!(requirements[0].max_amount_required, "5000");
+        assert_eq!(requirements[0].asset, asset.address);
+        assert_eq!(requirements[0].extra, Some(json!({ "name": "Token", "version": "1" })));
+    }
+}
@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-
-use crate::{NonRustCodeRunnerFactory, NonRustRuntime, RunError};
 
 use hanzo_messages::schemas::x402_types::{FacilitatorConfig, PaymentPayload, PaymentRequirements};
 
+use super::error::X402Error;
+use super::facilitator::{settle_response_header, FacilitatorClient};
+use super::verify_payment::find_matching_payment_requirements;
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Input {
@@ -33,84 +34,111 @@ pub struct Output {
     pub valid: Option<ValidOutput>,
 }
 
-pub async fn settle_payment(input: Input) -> Result<Output, RunError> {
-    let code = include_str!("settlePaymentDenoImpl.ts");
-    let runner = NonRustCodeRunnerFactory::new("settle_payment", code, vec![])
-        .with_runtime(NonRustRuntime::Deno)
-        .create_runner(json!({}));
-    runner.run::<_, Output>(input, None).await
+/// Has the facilitator submit a verified payment, returning the X-PAYMENT-RESPONSE header
+pub async fn settle_payment(input: Input) -> Result<Output, X402Error> {
+    let invalid = |error: String| Output {
+        invalid: Some(InvalidOutput {
+            error,
+            accepts: input.accepts.clone(),
+            x402_version: input.payment.x402_version,
+        }),
+        valid: None,
+    };
+
+    let Some(selected_payment_requirements) = find_matching_payment_requirements(&input.accepts, &input.payment) else {
+        return Ok(invalid(X402Error::NoMatchingPaymentRequirements.to_string()));
+    };
+
+    let settlement = FacilitatorClient::new(&input.facilitator)
+        .settle(&input.payment, selected_payment_requirements)
+        .await
+        .and_then(|settlement| {
+            if settlement.success {
+                Ok(settlement)
+            } else {
+                Err(X402Error::FacilitatorError(settlement.error_reason.unwrap_or_default()))
+            }
+        })
+        .and_then(|settlement| settle_response_header(&settlement));
+
+    match settlement {
+        Ok(payment_response) => Ok(Output {
+            invalid: None,
+            valid: Some(ValidOutput { payment_response }),
+        }),
+        Err(e) => Ok(invalid(format!("Failed to settle payment - error: Error: {}", e))),
+    }
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use hanzo_messages::schemas::x402_types::{Network, Price};
-
-//     use super::*;
-//     use crate::{
-//         functions::x402::{create_payment, verify_payment}, test_utils::testing_create_tempdir_and_set_env_var
-//     };
-
-//     #[tokio::test]
-//     async fn test_settle_payment() {
-//         let _dir = testing_create_tempdir_and_set_env_var();
-//         let price_in_raw_usd = 0.1;
-
-//         let pay_to = std::env::var("X402_PAY_TO").expect("X402_PAY_TO must be set");
-//         let private_key = std::env::var("X402_PRIVATE_KEY").expect("X402_PRIVATE_KEY must be set");
-
-//         // First verify with no payment to get accepts
-//         let verify_input = verify_payment::Input {
-//             price: Price::Money(price_in_raw_usd),
-//             network: Network::BaseSepolia,
-//             pay_to: pay_to.clone(),
-//             payment: None,
-//             x402_version: 1,
-//             facilitator: FacilitatorConfig::default(),
-//         };
-
-//         let verify_output = verify_payment::verify_payment(verify_input.clone()).await.unwrap();
-//         assert!(verify_output.invalid.is_some());
-//         let invalid_verify = verify_output.invalid.unwrap();
-
-//         // Create payment using the accepts from verify
-//         let create_input = create_payment::Input {
-//             accepts: invalid_verify.accepts.clone(),
-//             x402_version: invalid_verify.x402_version,
-//             private_key,
-//         };
-
-//         let payment = create_payment::create_payment(create_input).await.unwrap().payment;
-//         assert!(!payment.is_empty());
-
-//         // Verify the created payment
-//         let mut verify_input = verify_input.clone();
-//         verify_input.payment = Some(payment.clone());
-
-//         let verify_output = verify_payment::verify_payment(verify_input).await.unwrap();
-
-//         // Check for insufficient funds error
-//         if let Some(invalid) = &verify_output.invalid {
-//             if invalid.error == "Invalid payment - insufficient_funds" {
-//                 let payment_req = &invalid.accepts[0];
-//                 panic!(
-//                     "Insufficient funds error detected: Required {} {} on {:?} network",
-//                     payment_req.max_amount_required, payment_req.asset, payment_req.network
-//                 );
-//             }
-//         }
-
-//         assert!(verify_output.valid.is_some());
-//         let decoded_payment = verify_output.valid.unwrap().decoded_payment;
-
-//         // Finally settle the payment
-//         let settle_input = Input {
-//             payment: decoded_payment,
-//             accepts: invalid_verify.accepts,
-//             facilitator: FacilitatorConfig::default(),
-//         };
-
-//         let settle_output = settle_payment(settle_input).await.unwrap();
-//         assert!(settle_output.valid.is_some());
-//         assert!(!settle_output.valid.unwrap().payment_response.is_empty());
-//     }
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::x402::exact_evm;
+    use crate::functions::x402::facilitator::SettleResponse;
+    use base64::Engine;
+    use hanzo_messages::schemas::x402_types::Network;
+    use serde_json::json;
+
+    // Reference X-PAYMENT header of a 1000 units USDC payment on base-sepolia
+    const PAYMENT: &str = "eyJ4NDAyVmVyc2lvbiI6MSwic2NoZW1lIjoiZXhhY3QiLCJuZXR3b3JrIjoiYmFzZS1zZXBvbGlhIiwicGF5bG9hZCI6eyJzaWduYXR1cmUiOiIweDBkOTU5MzYwM2VhNWY1ZDgxMDg4MTBjM2I3YWY1Mzk2MDBjYjkwOTYzODIyYjQ0Zjk3NGVkYTU1Zjk0MjIxZjE2NjE1NjllZTA4NGYwZDQ4NTk1YjE2YmEyYjI3Zjc2NGIxYzljODhkOWY3NGExODI1ZTE4YzNlMzgwZjg0ZThmMWIiLCJhdXRob3JpemF0aW9uIjp7ImZyb20iOiIweGYzOUZkNmU1MWFhZDg4RjZGNGNlNmFCODgyNzI3OWNmZkZiOTIyNjYiLCJ0byI6IjB4NzA5OTc5NzBDNTE4MTJkYzNBMDEwQzdkMDFiNTBlMGQxN2RjNzlDOCIsInZhbHVlIjoiMTAwMCIsInZhbGlkQWZ0ZXIiOiIxNzE3MDAwMDAwIiwidmFsaWRCZWZvcmUiOiIxNzE3MDAwOTAwIiwibm9uY2UiOiIweDExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTExMTEifX19";
+
+    fn input(facilitator_url: String) -> Input {
+        let payment = exact_evm::decode_payment(PAYMENT).unwrap();
+        let accepts = vec![PaymentRequirements::new(
+            Network::BaseSepolia,
+            "1000".to_string(),
+            payment.payload.authorization.to.clone(),
+            exact_evm::usdc_asset(&Network::BaseSepolia).address,
+            "https://hanzo.ai".to_string(),
+        )];
+        Input {
+            payment,
+            accepts,
+            facilitator: FacilitatorConfig { url: facilitator_url },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_settle_payment() {
+        let mut server = mockito::Server::new_async().await;
+        let settlement = r#"{"success":true,"transaction":"0xabc","network":"base-sepolia","payer":"0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"}"#;
+        let facilitator = server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_body(settlement)
+            .create_async()
+            .await;
+
+        let output = settle_payment(input(server.url())).await.unwrap();
+        facilitator.assert_async().await;
+        let header = base64::engine::general_purpose::STANDARD
+            .decode(output.valid.unwrap().payment_response)
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<SettleResponse>(&header).unwrap().transaction,
+            "0xabc"
+        );
+        assert_eq!(String::from_utf8(header).unwrap(), settlement);
+    }
+
+    #[tokio::test]
+    async fn test_settle_payment_failures() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_body(json!({ "success": false, "errorReason": "insufficient_funds", "transaction": "", "network": "base-sepolia" }).to_string())
+            .create_async()
+            .await;
+        let invalid = settle_payment(input(server.url())).await.unwrap().invalid.unwrap();
+        assert_eq!(
+            invalid.error,
+            "Failed to settle payment - error: Error: insufficient_funds"
+        );
+
+        let mut unreachable = input(server.url());
+        unreachable.accepts[0].network = Network::Base;
+        let invalid = settle_payment(unreachable).await.unwrap().invalid.unwrap();
+        assert_eq!(invalid.error, "Unable to find matching payment requirements");
+    }
+}
@@ -1,11 +1,11 @@
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 
-use crate::{NonRustCodeRunnerFactory, NonRustRuntime, RunError};
+use hanzo_messages::schemas::x402_types::{FacilitatorConfig, Network, PaymentPayload, PaymentRequirements, Price};
 
-use hanzo_messages::schemas::x402_types::{
-    FacilitatorConfig, Network, PaymentPayload, PaymentRequirements, Price,
-};
+use super::error::X402Error;
+use super::exact_evm;
+use super::facilitator::FacilitatorClient;
+use super::payment_requirements::build_payment_requirements;
 
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -41,41 +41,195 @@ pub struct Output {
     pub valid: Option<ValidOutput>,
 }
 
-pub async fn verify_payment(input: Input) -> Result<Output, RunError> {
-    let code = include_str!("verifyPaymentDenoImpl.ts");
-    let runner = NonRustCodeRunnerFactory::new("verify_payment", code, vec![])
-        .with_runtime(NonRustRuntime::Deno)
-        .create_runner(json!({}));
-    runner.run::<_, Output>(input, None).await
+/// Requirements of the same scheme and network as a payment
+pub fn find_matching_payment_requirements<'a>(
+    accepts: &'a [PaymentRequirements],
+    payment: &PaymentPayload,
+) -> Option<&'a PaymentRequirements> {
+    accepts
+        .iter()
+        .find(|requirements| requirements.scheme == payment.scheme && requirements.network == payment.network)
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//     use crate::test_utils::testing_create_tempdir_and_set_env_var;
-
-//     #[tokio::test]
-//     async fn test_verify_payment() {
-//         let _dir = testing_create_tempdir_and_set_env_var();
-
-//         let price_in_raw_usd = 0.001;
-//         let input = Input {
-//             price: Price::Money(price_in_raw_usd),
-//             network: Network::BaseSepolia,
-//             // This is Hanzo Faucet address
-//             pay_to: std::env::var("X402_PAY_TO").expect("X402_PAY_TO must be set"),
-//             x402_version: 1,
-//             facilitator: FacilitatorConfig::default(),
-//             payment: None,
-//         };
-
-//         let output = verify_payment(input).await.unwrap();
-//         println!("{:?}", output);
-//         assert!(output.valid.is_none());
-//         assert!(output.invalid.is_some());
-//         assert_eq!(
-//             output.invalid.unwrap().accepts.first().unwrap().max_amount_required,
-//             (price_in_raw_usd * 1000000.0).to_string()
-//         );
-//     }
-// }
+/// Checks the X-PAYMENT header of a request against the price, offchain first and then with the
+/// facilitator. A missing or unacceptable payment is an invalid output carrying the requirements
+/// to answer with, errors are left to inputs that can't be priced.
+pub async fn verify_payment(input: Input) -> Result<Output, X402Error> {
+    let accepts = build_payment_requirements(&input)?;
+    let invalid = |error: String, payer: Option<String>| Output {
+        invalid: Some(InvalidOutput {
+            error,
+            accepts: accepts.clone(),
+            x402_version: input.x402_version,
+            payer,
+        }),
+        valid: None,
+    };
+
+    let Some(payment) = input.payment.as_deref() else {
+        return Ok(invalid("No payment provided".to_string(), None));
+    };
+    let mut decoded_payment = match exact_evm::decode_payment(payment) {
+        Ok(decoded_payment) => decoded_payment,
+        Err(e) => {
+            return Ok(invalid(
+                format!("Invalid or malformed payment header - error: {}", e),
+                None,
+            ))
+        }
+    };
+    decoded_payment.x402_version = input.x402_version;
+
+    let Some(selected_payment_requirements) = find_matching_payment_requirements(&accepts, &decoded_payment) else {
+        return Ok(invalid(X402Error::NoMatchingPaymentRequirements.to_string(), None));
+    };
+
+    let payer = Some(decoded_payment.payload.authorization.from.clone());
+    if let Err(e) = exact_evm::verify_payment_payload(
+        &decoded_payment,
+        selected_payment_requirements,
+        exact_evm::unix_timestamp(),
+    ) {
+        let error = match e {
+            X402Error::InvalidPayment(reason) => format!("Invalid payment - {}", reason),
+            e => format!("Invalid or malformed payment header - error: {}", e),
+        };
+        return Ok(invalid(error, payer));
+    }
+
+    // The balance of the payer and the nonce are only known onchain
+    let verification = match FacilitatorClient::new(&input.facilitator)
+        .verify(&decoded_payment, selected_payment_requirements)
+        .await
+    {
+        Ok(verification) => verification,
+        Err(e) => {
+            return Ok(invalid(
+                format!(
+                    "unhandled error verifying payment - error: {} - status: 500 - {}",
+                    e,
+                    serde_json::to_string(&decoded_payment).unwrap_or_default()
+                ),
+                None,
+            ))
+        }
+    };
+    if !verification.is_valid {
+        return Ok(invalid(
+            format!("Invalid payment - {}", verification.invalid_reason.unwrap_or_default()),
+            verification.payer,
+        ));
+    }
+
+    Ok(Output {
+        invalid: None,
+        valid: Some(ValidOutput {
+            selected_payment_requirements: selected_payment_requirements.clone(),
+            decoded_payment,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::functions::x402::create_payment;
+    use serde_json::json;
+
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const PAYER: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+    fn input(facilitator_url: String) -> Input {
+        Input {
+            price: Price::Money(0.001),
+            network: Network::BaseSepolia,
+            pay_to: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+            payment: None,
+            x402_version: 1,
+            facilitator: FacilitatorConfig { url: facilitator_url },
+        }
+    }
+
+    async fn signed_payment(input: &Input) -> String {
+        let accepts = build_payment_requirements(input).unwrap();
+        create_payment::create_payment(create_payment::Input {
+            accepts,
+            x402_version: 1,
+            private_key: PRIVATE_KEY.to_string(),
+        })
+        .await
+        .unwrap()
+        .payment
+    }
+
+    #[tokio::test]
+    async fn test_verify_payment_without_payment() {
+        let output = verify_payment(input("http://localhost".to_string())).await.unwrap();
+        assert!(output.valid.is_none());
+        let invalid = output.invalid.unwrap();
+        assert_eq!(invalid.error, "No payment provided");
+        assert_eq!(invalid.accepts[0].max_amount_required, "1000");
+
+        let mut malformed = input("http://localhost".to_string());
+        malformed.payment = Some("not a payment".to_string());
+        let output = verify_payment(malformed).await.unwrap();
+        assert!(output
+            .invalid
+            .unwrap()
+            .error
+            .starts_with("Invalid or malformed payment header - error: "));
+    }
+
+    #[tokio::test]
+    async fn test_verify_payment_with_facilitator() {
+        let mut server = mockito::Server::new_async().await;
+        let facilitator = server
+            .mock("POST", "/verify")
+            .match_body(mockito::Matcher::PartialJson(json!({ "x402Version": 1 })))
+            .with_status(200)
+            .with_body(json!({ "isValid": true, "payer": PAYER }).to_string())
+            .create_async()
+            .await;
+
+        let mut input = input(server.url());
+        input.payment = Some(signed_payment(&input).await);
+        let output = verify_payment(input).await.unwrap();
+        facilitator.assert_async().await;
+        let valid = output.valid.unwrap();
+        assert_eq!(valid.decoded_payment.payload.authorization.from, PAYER);
+        assert_eq!(valid.selected_payment_requirements.max_amount_required, "1000");
+    }
+
+    #[tokio::test]
+    async fn test_verify_payment_rejected_by_facilitator() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(json!({ "isValid": false, "invalidReason": "insufficient_funds", "payer": PAYER }).to_string())
+            .create_async()
+            .await;
+
+        let mut input = input(server.url());
+        input.payment = Some(signed_payment(&input).await);
+        let invalid = verify_payment(input).await.unwrap().invalid.unwrap();
+        assert_eq!(invalid.error, "Invalid payment - insufficient_funds");
+        assert_eq!(invalid.payer.as_deref(), Some(PAYER));
+    }
+
+    #[tokio::test]
+    async fn test_verify_payment_checks_amount_before_facilitator() {
+        let mut server = mockito::Server::new_async().await;
+        let facilitator = server.mock("POST", "/verify").expect(0).create_async().await;
+
+        let mut input = input(server.url());
+        input.payment = Some(signed_payment(&input).await);
+        input.price = Price::Money(0.002);
+        let invalid = verify_payment(input).await.unwrap().invalid.unwrap();
+        facilitator.assert_async().await;
+        assert_eq!(
+            invalid.error,
+            "Invalid payment - invalid_exact_evm_payload_authorization_value"
+        );
+    }
+}
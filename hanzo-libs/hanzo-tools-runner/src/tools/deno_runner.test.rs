@@ -3,9 +3,10 @@ use serde_json::json;
 use serde_json::Value;
 
 use crate::tools::{
-    code_files::CodeFiles, deno_runner::DenoRunner, deno_runner_options::DenoRunnerOptions,
-    execution_context::ExecutionContext, execution_storage::ExecutionStorage,
-    runner_type::RunnerType, hanzo_node_location::HanzoNodeLocation,
+    code_files::CodeFiles, deno_permissions::DenoPermissions, deno_runner::DenoRunner,
+    deno_runner_options::DenoRunnerOptions, execution_context::ExecutionContext,
+    execution_storage::ExecutionStorage, runner_type::RunnerType,
+    hanzo_node_location::HanzoNodeLocation,
 };
 
 use std::collections::HashMap;
@@ -137,6 +138,94 @@ async fn write_forbidden_folder(#[case] runner_type: RunnerType) {
     assert!(result.is_err());
 }
 
+#[rstest]
+#[case::host(RunnerType::Host)]
+#[case::docker(RunnerType::Docker)]
+#[tokio::test]
+async fn run_with_declared_permissions(#[case] runner_type: RunnerType) {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let code = CodeFiles {
+        files: HashMap::from([(
+            "main.ts".to_string(),
+            r#"
+                async function run(configurations, params) {
+                    let home = "granted";
+                    try {
+                        Deno.env.get("HOME");
+                    } catch (e) {
+                        home = "denied";
+                    }
+                    return { hello: Deno.env.get("HELLO_WORLD"), path: typeof Deno.env.get("PATH"), home };
+                }
+            "#
+            .to_string(),
+        )]),
+        entrypoint: "main.ts".to_string(),
+    };
+    let deno_runner = DenoRunner::new(
+        code,
+        json!({}),
+        Some(DenoRunnerOptions {
+            force_runner_type: Some(runner_type),
+            permissions: Some(DenoPermissions {
+                env: vec!["PATH".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+    );
+
+    let envs = HashMap::from([("HELLO_WORLD".to_string(), "hello world!".to_string())]);
+    let result = deno_runner.run(Some(envs), json!({}), None).await.unwrap();
+
+    assert_eq!(result.data["hello"], "hello world!");
+    assert_eq!(result.data["path"], "string");
+    assert_eq!(result.data["home"], "denied");
+}
+
+#[rstest]
+#[case::host(RunnerType::Host)]
+#[case::docker(RunnerType::Docker)]
+#[tokio::test]
+async fn run_undeclared_tool_spawning_subprocess(#[case] runner_type: RunnerType) {
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    // Tools that don't declare permissions, like ones launching a browser, keep the broad defaults
+    let code = CodeFiles {
+        files: HashMap::from([(
+            "main.ts".to_string(),
+            r#"
+                async function run(configurations, params) {
+                    const output = await new Deno.Command("echo", { args: ["spawned"] }).output();
+                    return { stdout: new TextDecoder().decode(output.stdout).trim() };
+                }
+            "#
+            .to_string(),
+        )]),
+        entrypoint: "main.ts".to_string(),
+    };
+    let deno_runner = DenoRunner::new(
+        code,
+        json!({}),
+        Some(DenoRunnerOptions {
+            force_runner_type: Some(runner_type),
+            permissions: None,
+            ..Default::default()
+        }),
+    );
+
+    let result = deno_runner.run(None, json!({}), None).await.unwrap();
+
+    assert_eq!(result.data["stdout"], "spawned");
+}
+
 #[rstest]
 #[case::host(RunnerType::Host)]
 #[case::docker(RunnerType::Docker)]
@@ -1453,3 +1542,104 @@ export async function run(config: Config, inputs: Inputs): Promise<Output> {
         .unwrap()
         .contains("Hello, world parameters!"));
 }
+
+#[tokio::test]
+async fn run_in_sandbox_cannot_write_outside_storage() {
+    if !crate::tools::sandbox::is_sandbox_available() {
+        log::info!("sandbox is not available, skipping test");
+        return;
+    }
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let code_files = CodeFiles {
+        files: HashMap::from([(
+            "main.ts".to_string(),
+            r#"
+                async function run(configurations, params) {
+                    await Deno.writeTextFile(`${Deno.env.get("HANZO_HOME")}/inside.txt`, "hello");
+                    try {
+                        await Deno.writeTextFile(params.outside_file_path, "hello");
+                        return { written_outside: true };
+                    } catch (error) {
+                        return { written_outside: false };
+                    }
+                }
+            "#
+            .to_string(),
+        )]),
+        entrypoint: "main.ts".to_string(),
+    };
+
+    // Broad permissions let deno write next to the execution storage, the sandbox doesn't. Not in
+    // /tmp, which is a private tmpfs inside the sandbox.
+    let temp_dir = tempfile::tempdir_in(".").unwrap();
+    let outside_file_path = temp_dir.path().join("outside.txt");
+
+    let deno_runner = DenoRunner::new(
+        code_files,
+        json!({}),
+        Some(DenoRunnerOptions {
+            force_runner_type: Some(RunnerType::Sandbox),
+            ..Default::default()
+        }),
+    );
+
+    let result = deno_runner
+        .run(
+            None,
+            json!({ "outside_file_path": outside_file_path.to_string_lossy() }),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.data["written_outside"], false);
+    assert!(!outside_file_path.exists());
+}
+
+#[tokio::test]
+async fn run_in_sandbox_with_webassembly_memory() {
+    if !crate::tools::sandbox::is_sandbox_available() {
+        log::info!("sandbox is not available, skipping test");
+        return;
+    }
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    // V8 reserves several GiB of address space for each memory, which the default sandbox
+    // options have to allow
+    let code_files = CodeFiles {
+        files: HashMap::from([(
+            "main.ts".to_string(),
+            r#"
+                async function run(configurations, params) {
+                    const memory = new WebAssembly.Memory({ initial: 1, maximum: 1024 });
+                    memory.grow(15);
+                    return { pages: memory.buffer.byteLength / 65536 };
+                }
+            "#
+            .to_string(),
+        )]),
+        entrypoint: "main.ts".to_string(),
+    };
+
+    let deno_runner = DenoRunner::new(
+        code_files,
+        json!({}),
+        Some(DenoRunnerOptions {
+            force_runner_type: Some(RunnerType::Sandbox),
+            ..Default::default()
+        }),
+    );
+
+    let result = deno_runner.run(None, json!({}), None).await.unwrap();
+
+    assert_eq!(result.data["pages"], 16);
+}
@@ -1,8 +1,8 @@
 use std::path::PathBuf;
 
 use super::{
-    execution_context::ExecutionContext, runner_type::RunnerType,
-    hanzo_node_location::HanzoNodeLocation,
+    deno_permissions::DenoPermissions, execution_context::ExecutionContext,
+    runner_type::RunnerType, sandbox::SandboxOptions, hanzo_node_location::HanzoNodeLocation,
 };
 
 #[derive(Clone)]
@@ -12,6 +12,10 @@ pub struct DenoRunnerOptions {
     pub code_runner_docker_image_name: String,
     pub force_runner_type: Option<RunnerType>,
     pub hanzo_node_location: HanzoNodeLocation,
+    /// Permissions declared by the tool, when none the tool runs with broad permissions
+    pub permissions: Option<DenoPermissions>,
+    /// Limits of the execution when it runs in the sandbox
+    pub sandbox: SandboxOptions,
 }
 
 impl Default for DenoRunnerOptions {
@@ -30,6 +34,8 @@ impl Default for DenoRunnerOptions {
                 host: String::from("127.0.0.1"),
                 port: 3690,
             },
+            permissions: None,
+            sandbox: SandboxOptions::default(),
         }
     }
 }
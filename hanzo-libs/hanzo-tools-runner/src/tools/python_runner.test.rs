@@ -1905,3 +1905,62 @@ async def run(config: CONFIG, inputs: INPUTS) -> OUTPUT:
         .unwrap()
         .contains("Hello, world parameters!"));
 }
+
+#[tokio::test]
+async fn run_in_sandbox_cannot_write_outside_storage() {
+    if !crate::tools::sandbox::is_sandbox_available() {
+        log::info!("sandbox is not available, skipping test");
+        return;
+    }
+
+    let _ = env_logger::builder()
+        .filter_level(log::LevelFilter::Info)
+        .is_test(true)
+        .try_init();
+
+    let code_files = CodeFiles {
+        files: HashMap::from([(
+            "main.py".to_string(),
+            r#"
+import os
+
+def run(configurations, parameters):
+    with open(os.path.join(os.getenv('HANZO_HOME'), 'inside.txt'), 'w') as f:
+        f.write('hello')
+    try:
+        with open(parameters['outside_file_path'], 'w') as f:
+            f.write('hello')
+        return { 'written_outside': True }
+    except OSError:
+        return { 'written_outside': False }
+            "#
+            .to_string(),
+        )]),
+        entrypoint: "main.py".to_string(),
+    };
+
+    // Next to the execution storage rather than in /tmp, which is a private tmpfs inside the sandbox
+    let temp_dir = tempfile::tempdir_in(".").unwrap();
+    let outside_file_path = temp_dir.path().join("outside.txt");
+
+    let python_runner = PythonRunner::new(
+        code_files,
+        Value::Null,
+        Some(PythonRunnerOptions {
+            force_runner_type: Some(RunnerType::Sandbox),
+            ..Default::default()
+        }),
+    );
+
+    let result = python_runner
+        .run(
+            None,
+            json!({ "outside_file_path": outside_file_path.to_string_lossy() }),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.data["written_outside"], false);
+    assert!(!outside_file_path.exists());
+}
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use super::{
-    execution_context::ExecutionContext, runner_type::RunnerType,
+    execution_context::ExecutionContext, runner_type::RunnerType, sandbox::SandboxOptions,
     hanzo_node_location::HanzoNodeLocation,
 };
 
@@ -12,6 +12,8 @@ pub struct PythonRunnerOptions {
     pub code_runner_docker_image_name: String,
     pub force_runner_type: Option<RunnerType>,
     pub hanzo_node_location: HanzoNodeLocation,
+    /// Limits of the execution when it runs in the sandbox
+    pub sandbox: SandboxOptions,
 }
 
 impl Default for PythonRunnerOptions {
@@ -30,6 +32,7 @@ impl Default for PythonRunnerOptions {
                 host: String::from("127.0.0.1"),
                 port: 3690,
             },
+            sandbox: SandboxOptions::default(),
         }
     }
 }
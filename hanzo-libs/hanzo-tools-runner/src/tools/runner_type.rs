@@ -1,9 +1,12 @@
 use super::container_utils::{is_docker_available, DockerStatus};
+use super::sandbox::is_sandbox_available;
 
 #[derive(Clone)]
 pub enum RunnerType {
     Host,
     Docker,
+    /// Runs on the host, isolated by the Linux sandbox
+    Sandbox,
 }
 
 pub fn resolve_runner_type(force_runner_type: Option<RunnerType>) -> RunnerType {
@@ -12,6 +15,8 @@ pub fn resolve_runner_type(force_runner_type: Option<RunnerType>) -> RunnerType
     }
     if is_docker_available() == DockerStatus::Running {
         RunnerType::Docker
+    } else if is_sandbox_available() {
+        RunnerType::Sandbox
     } else {
         RunnerType::Host
     }
@@ -35,15 +40,27 @@ mod tests {
         assert!(matches!(runner_type, RunnerType::Docker));
     }
 
+    #[tokio::test]
+    async fn test_resolve_runner_type_sandbox() {
+        let force_runner_type = Some(RunnerType::Sandbox);
+        let runner_type = resolve_runner_type(force_runner_type);
+        assert!(matches!(runner_type, RunnerType::Sandbox));
+    }
+
     #[tokio::test]
     async fn test_resolve_runner_type_docker_not_running() {
         let force_runner_type = None;
         let runner_type = resolve_runner_type(force_runner_type);
         let is_docker_available = is_docker_available();
+        let fallback_runner_type_matches = if is_sandbox_available() {
+            matches!(runner_type, RunnerType::Sandbox)
+        } else {
+            matches!(runner_type, RunnerType::Host)
+        };
         assert!(
             ((is_docker_available == DockerStatus::NotRunning
                 || is_docker_available == DockerStatus::NotInstalled)
-                && matches!(runner_type, RunnerType::Host))
+                && fallback_runner_type_matches)
                 || (is_docker_available == DockerStatus::Running
                     && matches!(runner_type, RunnerType::Docker))
         );
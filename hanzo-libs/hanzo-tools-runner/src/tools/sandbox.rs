@@ -0,0 +1,511 @@
+//! Lightweight sandbox to run tools on Linux hosts without Docker.
+//!
+//! The tool runs in its own user and mount namespaces, where every mount is remounted
+//! read-only except the folders of the execution, which are bind mounted writable, and
+//! fresh `/tmp` and `/dev/shm`. Landlock enforces the same rules on kernels that support it,
+//! a seccomp filter denies the syscalls that could escape the sandbox, and rlimits cap the CPU
+//! time and, when set, the address space of the tool. Without network the tool runs in an empty network namespace.
+
+use std::path::PathBuf;
+
+use once_cell::sync::Lazy;
+
+/// Limits of a sandboxed execution
+#[derive(Clone, Debug)]
+pub struct SandboxOptions {
+    /// Whether the tool can use the network, without it the tool can't reach the node either
+    pub network: bool,
+    /// CPU time the tool can use, in seconds
+    pub max_cpu_time_secs: Option<u64>,
+    /// Address space the tool can map, in bytes. Unlimited by default, as runtimes reserve far
+    /// more than they use: V8 maps several GiB for each `WebAssembly.Memory`.
+    pub max_memory_bytes: Option<u64>,
+}
+
+impl Default for SandboxOptions {
+    fn default() -> Self {
+        Self {
+            network: true,
+            max_cpu_time_secs: Some(300),
+            max_memory_bytes: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Sandbox {
+    writable_paths: Vec<PathBuf>,
+    options: SandboxOptions,
+}
+
+static SANDBOX_AVAILABLE: Lazy<bool> = Lazy::new(|| {
+    let available = Sandbox::new(vec![], SandboxOptions::default()).probe();
+    match &available {
+        Ok(()) => log::info!("sandbox is available"),
+        Err(e) => log::info!("sandbox is not available: {}", e),
+    }
+    available.is_ok()
+});
+
+/// Checks if tools can run in the sandbox, by running a shell in it once.
+///
+/// # Returns
+///
+/// * `true` - On Linux, when unprivileged user namespaces are enabled
+/// * `false` - On other systems, or when the kernel or its configuration doesn't allow it
+pub fn is_sandbox_available() -> bool {
+    *SANDBOX_AVAILABLE
+}
+
+impl Sandbox {
+    /// Sandbox where only `writable_paths`, folders or files, can be written
+    pub fn new(writable_paths: Vec<PathBuf>, options: SandboxOptions) -> Self {
+        Sandbox {
+            writable_paths,
+            options,
+        }
+    }
+
+    /// Makes the command run in the sandbox once spawned
+    #[cfg(target_os = "linux")]
+    pub fn apply(&self, command: &mut tokio::process::Command) -> anyhow::Result<()> {
+        let mut setup = linux::SandboxSetup::new(&self.writable_paths, &self.options)?;
+        // SAFETY: the setup is prepared here, the child only makes syscalls with it
+        unsafe {
+            command.pre_exec(move || setup.enter());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn apply(&self, _command: &mut tokio::process::Command) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("the sandbox is only available on linux"))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn probe(&self) -> anyhow::Result<()> {
+        use std::os::unix::process::CommandExt;
+
+        let mut setup = linux::SandboxSetup::new(&self.writable_paths, &self.options)?;
+        let mut command = std::process::Command::new("/bin/sh");
+        command.args(["-c", "exit 0"]);
+        // SAFETY: the setup is prepared here, the child only makes syscalls with it
+        unsafe {
+            command.pre_exec(move || setup.enter());
+        }
+        let status = command.status()?;
+        if !status.success() {
+            return Err(anyhow::anyhow!("probe exited with {}", status));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn probe(&self) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("the sandbox is only available on linux"))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        collections::BTreeMap,
+        ffi::{CStr, CString},
+        io,
+        os::unix::ffi::OsStrExt,
+        path::{Path, PathBuf},
+        sync::Mutex,
+    };
+
+    use landlock::{
+        path_beneath_rules, Access, AccessFs, Ruleset, RulesetAttr, RulesetCreated,
+        RulesetCreatedAttr, ABI,
+    };
+    use seccompiler::{
+        BpfProgram, SeccompAction, SeccompCmpArgLen, SeccompCmpOp, SeccompCondition, SeccompFilter,
+        SeccompRule,
+    };
+
+    use super::SandboxOptions;
+
+    /// Folders replaced by an empty tmpfs in the sandbox
+    const TMPFS_FOLDERS: [&CStr; 2] = [c"/tmp", c"/dev/shm"];
+
+    /// Devices the tool can write to
+    const WRITABLE_DEVICES: [&str; 3] = ["/dev/null", "/dev/zero", "/dev/full"];
+
+    /// Mounts left as they are, remounting them read-only breaks devices and procfs
+    const KEPT_MOUNTS: [&str; 3] = ["/dev", "/proc", "/sys"];
+
+    #[cfg(target_env = "gnu")]
+    type RlimitResource = libc::__rlimit_resource_t;
+    #[cfg(not(target_env = "gnu"))]
+    type RlimitResource = libc::c_int;
+
+    /// Namespace flags `clone` is denied with, as in the default seccomp profile of Docker
+    const CLONE_NAMESPACE_FLAGS: [libc::c_int; 7] = [
+        libc::CLONE_NEWCGROUP,
+        libc::CLONE_NEWIPC,
+        libc::CLONE_NEWNET,
+        libc::CLONE_NEWNS,
+        libc::CLONE_NEWPID,
+        libc::CLONE_NEWUSER,
+        libc::CLONE_NEWUTS,
+    ];
+
+    /// Syscalls denied to the tool, following the default seccomp profile of Docker
+    const DENIED_SYSCALLS: [libc::c_long; 31] = [
+        libc::SYS_acct,
+        libc::SYS_add_key,
+        libc::SYS_bpf,
+        libc::SYS_chroot,
+        libc::SYS_clock_settime,
+        libc::SYS_delete_module,
+        libc::SYS_finit_module,
+        libc::SYS_init_module,
+        libc::SYS_kexec_file_load,
+        libc::SYS_kexec_load,
+        libc::SYS_keyctl,
+        libc::SYS_mount,
+        libc::SYS_name_to_handle_at,
+        libc::SYS_open_by_handle_at,
+        libc::SYS_perf_event_open,
+        libc::SYS_pivot_root,
+        libc::SYS_process_vm_readv,
+        libc::SYS_process_vm_writev,
+        libc::SYS_ptrace,
+        libc::SYS_quotactl,
+        libc::SYS_reboot,
+        libc::SYS_request_key,
+        libc::SYS_setdomainname,
+        libc::SYS_sethostname,
+        libc::SYS_setns,
+        libc::SYS_settimeofday,
+        libc::SYS_swapoff,
+        libc::SYS_swapon,
+        libc::SYS_syslog,
+        libc::SYS_umount2,
+        libc::SYS_unshare,
+    ];
+
+    /// Everything the child needs to enter the sandbox, prepared before forking as the child
+    /// can't safely allocate
+    pub(super) struct SandboxSetup {
+        namespaces: libc::c_int,
+        uid_map: Vec<u8>,
+        gid_map: Vec<u8>,
+        writable_paths: Vec<CString>,
+        tmpfs_folders: Vec<&'static CStr>,
+        read_only_mounts: Vec<(CString, libc::c_ulong)>,
+        rlimits: Vec<(RlimitResource, libc::rlim_t)>,
+        ruleset: Mutex<Option<RulesetCreated>>,
+        seccomp_filters: Vec<BpfProgram>,
+    }
+
+    impl SandboxSetup {
+        pub(super) fn new(
+            writable_paths: &[PathBuf],
+            options: &SandboxOptions,
+        ) -> anyhow::Result<Self> {
+            let mut namespaces = libc::CLONE_NEWUSER | libc::CLONE_NEWNS;
+            if !options.network {
+                namespaces |= libc::CLONE_NEWNET;
+            }
+
+            // The tool keeps its user and group inside the namespaces
+            let uid = unsafe { libc::geteuid() };
+            let gid = unsafe { libc::getegid() };
+
+            // The child already runs in its working directory, and bind mounting a missing path
+            // would fail the execution
+            let writable_paths = writable_paths
+                .iter()
+                .filter_map(|path| std::path::absolute(path).ok())
+                .filter(|path| path.exists())
+                .collect::<Vec<_>>();
+            // A tmpfs would hide the writable paths below it, the folder is then read-only
+            let tmpfs_folders = TMPFS_FOLDERS
+                .into_iter()
+                .filter(|folder| {
+                    !writable_paths
+                        .iter()
+                        .any(|path| path.starts_with(cstr_to_path(folder)))
+                })
+                .collect::<Vec<_>>();
+
+            let read_only_mounts = mount_points()?
+                .into_iter()
+                .filter(|(mount_point, _)| {
+                    !KEPT_MOUNTS
+                        .iter()
+                        .map(Path::new)
+                        .chain(tmpfs_folders.iter().map(|folder| cstr_to_path(folder)))
+                        .any(|kept| mount_point.starts_with(kept))
+                        && !writable_paths
+                            .iter()
+                            .any(|path| mount_point.starts_with(path))
+                })
+                .map(|(mount_point, flags)| Ok((path_to_cstring(&mount_point)?, flags)))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            let mut rlimits = vec![(libc::RLIMIT_CORE, 0)];
+            if let Some(max_cpu_time_secs) = options.max_cpu_time_secs {
+                rlimits.push((libc::RLIMIT_CPU, max_cpu_time_secs as libc::rlim_t));
+            }
+            // Unlike RLIMIT_DATA, also covers anonymous and file mappings
+            if let Some(max_memory_bytes) = options.max_memory_bytes {
+                rlimits.push((libc::RLIMIT_AS, max_memory_bytes as libc::rlim_t));
+            }
+
+            Ok(Self {
+                namespaces,
+                uid_map: format!("{} {} 1\n", uid, uid).into_bytes(),
+                gid_map: format!("{} {} 1\n", gid, gid).into_bytes(),
+                writable_paths: writable_paths
+                    .iter()
+                    .map(|path| path_to_cstring(path))
+                    .collect::<anyhow::Result<Vec<_>>>()?,
+                read_only_mounts,
+                rlimits,
+                ruleset: Mutex::new(Some(landlock_ruleset(&writable_paths, &tmpfs_folders)?)),
+                tmpfs_folders,
+                seccomp_filters: seccomp_filters()?,
+            })
+        }
+
+        /// Runs in the child, between fork and exec
+        pub(super) fn enter(&mut self) -> io::Result<()> {
+            check(unsafe { libc::unshare(self.namespaces) })?;
+            write_file(c"/proc/self/setgroups", b"deny")?;
+            write_file(c"/proc/self/uid_map", &self.uid_map)?;
+            write_file(c"/proc/self/gid_map", &self.gid_map)?;
+
+            // Nothing mounted in the sandbox propagates to the host
+            mount(None, c"/", None, libc::MS_REC | libc::MS_PRIVATE, None)?;
+            for path in &self.writable_paths {
+                mount(Some(path), path, None, libc::MS_BIND | libc::MS_REC, None)?;
+            }
+            for folder in &self.tmpfs_folders {
+                // Not every system has both
+                let _ = mount(
+                    Some(c"tmpfs"),
+                    folder,
+                    Some(c"tmpfs"),
+                    libc::MS_NOSUID | libc::MS_NODEV,
+                    Some(c"mode=1777"),
+                );
+            }
+            for (mount_point, flags) in &self.read_only_mounts {
+                let remounted = mount(
+                    None,
+                    mount_point,
+                    None,
+                    libc::MS_REMOUNT | libc::MS_BIND | libc::MS_RDONLY | flags,
+                    None,
+                );
+                // Other mounts are still protected by landlock
+                if mount_point.as_bytes() == b"/" {
+                    remounted?;
+                }
+            }
+            // The working directory still points to the mount below the writable bind mounts
+            let mut cwd = [0 as libc::c_char; libc::PATH_MAX as usize];
+            if !unsafe { libc::getcwd(cwd.as_mut_ptr(), cwd.len()) }.is_null() {
+                check(unsafe { libc::chdir(cwd.as_ptr()) })?;
+            }
+
+            for (resource, limit) in &self.rlimits {
+                let rlimit = libc::rlimit {
+                    rlim_cur: *limit,
+                    rlim_max: *limit,
+                };
+                check(unsafe { libc::setrlimit(*resource, &rlimit) })?;
+            }
+
+            let ruleset = self
+                .ruleset
+                .get_mut()
+                .map_err(|_| io::Error::other("landlock ruleset is poisoned"))?
+                .take();
+            if let Some(ruleset) = ruleset {
+                ruleset.restrict_self().map_err(io::Error::other)?;
+            }
+
+            // Last, as it denies the syscalls used above
+            for filter in &self.seccomp_filters {
+                seccompiler::apply_filter(filter).map_err(io::Error::other)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Read access everywhere, write access to the writable paths, the tmpfs folders and a
+    /// few devices. Best effort, older kernels enforce part of it or nothing.
+    fn landlock_ruleset(
+        writable_paths: &[PathBuf],
+        tmpfs_folders: &[&CStr],
+    ) -> anyhow::Result<RulesetCreated> {
+        let abi = ABI::V3;
+        let writable = writable_paths
+            .iter()
+            .map(PathBuf::as_path)
+            .chain(tmpfs_folders.iter().map(|folder| cstr_to_path(folder)))
+            .chain(WRITABLE_DEVICES.iter().map(Path::new))
+            .filter(|path| path.exists())
+            .collect::<Vec<_>>();
+
+        Ok(Ruleset::default()
+            .handle_access(AccessFs::from_all(abi))?
+            .create()?
+            .add_rules(path_beneath_rules(["/"], AccessFs::from_read(abi)))?
+            .add_rules(path_beneath_rules(writable, AccessFs::from_all(abi)))?)
+    }
+
+    /// The denied syscalls fail with EPERM. `clone3` fails with ENOSYS instead, as its flags
+    /// can't be inspected, so that libc falls back to `clone`.
+    fn seccomp_filters() -> anyhow::Result<Vec<BpfProgram>> {
+        let mut rules: BTreeMap<i64, Vec<SeccompRule>> = DENIED_SYSCALLS
+            .iter()
+            .map(|syscall| (*syscall, vec![]))
+            .collect();
+        // Creating namespaces, any of the flags is enough
+        rules.insert(
+            libc::SYS_clone,
+            CLONE_NAMESPACE_FLAGS
+                .iter()
+                .map(|flag| {
+                    SeccompRule::new(vec![SeccompCondition::new(
+                        0,
+                        SeccompCmpArgLen::Qword,
+                        SeccompCmpOp::MaskedEq(*flag as u64),
+                        *flag as u64,
+                    )?])
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        );
+        // Pushing characters to the terminal of the node
+        rules.insert(
+            libc::SYS_ioctl,
+            vec![SeccompRule::new(vec![SeccompCondition::new(
+                1,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Eq,
+                libc::TIOCSTI as u64,
+            )?])?],
+        );
+
+        let denied = SeccompFilter::new(
+            rules,
+            SeccompAction::Allow,
+            SeccompAction::Errno(libc::EPERM as u32),
+            std::env::consts::ARCH.try_into()?,
+        )?;
+        let clone3 = SeccompFilter::new(
+            BTreeMap::from([(libc::SYS_clone3, vec![])]),
+            SeccompAction::Allow,
+            SeccompAction::Errno(libc::ENOSYS as u32),
+            std::env::consts::ARCH.try_into()?,
+        )?;
+        Ok(vec![denied.try_into()?, clone3.try_into()?])
+    }
+
+    /// Mount points of the node with the flags that have to be kept when remounting them
+    fn mount_points() -> anyhow::Result<Vec<(PathBuf, libc::c_ulong)>> {
+        let mountinfo = std::fs::read_to_string("/proc/self/mountinfo")?;
+        Ok(mountinfo
+            .lines()
+            .filter_map(|line| {
+                let fields = line.split(' ').collect::<Vec<_>>();
+                let mount_point = unescape_mount_point(fields.get(4)?);
+                let flags = fields
+                    .get(5)?
+                    .split(',')
+                    .map(|option| match option {
+                        "nosuid" => libc::MS_NOSUID,
+                        "nodev" => libc::MS_NODEV,
+                        "noexec" => libc::MS_NOEXEC,
+                        "noatime" => libc::MS_NOATIME,
+                        "nodiratime" => libc::MS_NODIRATIME,
+                        "relatime" => libc::MS_RELATIME,
+                        "strictatime" => libc::MS_STRICTATIME,
+                        _ => 0,
+                    })
+                    .fold(0, |flags, flag| flags | flag);
+                Some((mount_point, flags))
+            })
+            .collect())
+    }
+
+    /// Mountinfo escapes spaces, tabs, newlines and backslashes as octal
+    fn unescape_mount_point(field: &str) -> PathBuf {
+        let bytes = field.as_bytes();
+        let mut unescaped = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let octal = bytes
+                .get(i + 1..i + 4)
+                .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+            match (bytes[i], octal) {
+                (b'\\', Some(byte)) => {
+                    unescaped.push(byte);
+                    i += 4;
+                }
+                (byte, _) => {
+                    unescaped.push(byte);
+                    i += 1;
+                }
+            }
+        }
+        PathBuf::from(std::ffi::OsStr::from_bytes(&unescaped))
+    }
+
+    fn path_to_cstring(path: &Path) -> anyhow::Result<CString> {
+        Ok(CString::new(path.as_os_str().as_bytes())?)
+    }
+
+    fn cstr_to_path(cstr: &CStr) -> &Path {
+        Path::new(std::ffi::OsStr::from_bytes(cstr.to_bytes()))
+    }
+
+    fn check(result: libc::c_int) -> io::Result<()> {
+        if result == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn mount(
+        source: Option<&CStr>,
+        target: &CStr,
+        fstype: Option<&CStr>,
+        flags: libc::c_ulong,
+        data: Option<&CStr>,
+    ) -> io::Result<()> {
+        check(unsafe {
+            libc::mount(
+                source.map_or(std::ptr::null(), CStr::as_ptr),
+                target.as_ptr(),
+                fstype.map_or(std::ptr::null(), CStr::as_ptr),
+                flags,
+                data.map_or(std::ptr::null(), |data| data.as_ptr().cast()),
+            )
+        })
+    }
+
+    fn write_file(path: &CStr, content: &[u8]) -> io::Result<()> {
+        let fd = unsafe { libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC) };
+        check(fd)?;
+        let written = unsafe { libc::write(fd, content.as_ptr().cast(), content.len()) };
+        let error = io::Error::last_os_error();
+        unsafe { libc::close(fd) };
+        if written != content.len() as isize {
+            return Err(error);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "sandbox.test.rs"]
+mod tests;
@@ -1,6 +1,7 @@
 use super::parameters::Parameters;
 use super::tool_config::{OAuth, ToolConfig};
 use super::tool_output_arg::ToolOutputArg;
+use super::tool_permissions::ToolPermissions;
 use super::tool_playground::ToolPlaygroundMetadata;
 use super::tool_playground::{SqlQuery, SqlTable};
 use super::tool_types::{OperatingSystem, RunnerType, ToolResult};
@@ -10,6 +11,7 @@ use serde_json::Map;
 use hanzo_messages::schemas::hanzo_name::HanzoName;
 use hanzo_messages::schemas::tool_router_key::ToolRouterKey;
 use hanzo_tools_runner::tools::code_files::CodeFiles;
+use hanzo_tools_runner::tools::deno_permissions::DenoPermissions;
 use hanzo_tools_runner::tools::deno_runner::DenoRunner;
 use hanzo_tools_runner::tools::deno_runner_options::DenoRunnerOptions;
 use hanzo_tools_runner::tools::execution_context::ExecutionContext;
@@ -49,6 +51,7 @@ pub struct DenoTool {
     pub runner: RunnerType,
     pub operating_system: Vec<OperatingSystem>,
     pub tool_set: Option<String>,
+    pub permissions: Option<ToolPermissions>,
 }
 
 impl<'de> serde::Deserialize<'de> for DenoTool {
@@ -86,6 +89,8 @@ impl<'de> serde::Deserialize<'de> for DenoTool {
             runner: RunnerType,
             operating_system: Vec<OperatingSystem>,
             tool_set: Option<String>,
+            #[serde(default)]
+            permissions: Option<ToolPermissions>,
         }
 
         let helper = Helper::deserialize(deserializer)?;
@@ -125,6 +130,7 @@ impl<'de> serde::Deserialize<'de> for DenoTool {
             runner: helper.runner,
             operating_system: helper.operating_system,
             tool_set: helper.tool_set,
+            permissions: helper.permissions,
         })
     }
 }
@@ -135,7 +141,7 @@ impl serde::Serialize for DenoTool {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("DenoTool", 24)?;
+        let mut state = serializer.serialize_struct("DenoTool", 25)?;
         state.serialize_field("name", &self.name)?;
         if let Some(key) = &self.tool_router_key {
             state.serialize_field("tool_router_key", &key.to_string_with_version())?;
@@ -165,6 +171,7 @@ impl serde::Serialize for DenoTool {
         state.serialize_field("runner", &self.runner)?;
         state.serialize_field("operating_system", &self.operating_system)?;
         state.serialize_field("tool_set", &self.tool_set)?;
+        state.serialize_field("permissions", &self.permissions)?;
         state.end()
     }
 }
@@ -222,6 +229,7 @@ impl DenoTool {
             runner,
             operating_system,
             tool_set,
+            permissions: None,
         }
     }
 
@@ -396,6 +404,7 @@ impl DenoTool {
                     host: api_ip,
                     port: api_port,
                 },
+                permissions: self.deno_permissions(),
                 ..Default::default()
             }),
         );
@@ -667,6 +676,12 @@ impl DenoTool {
         true
     }
 
+    /// Permissions the runner grants the tool. Tools that don't declare theirs get None, and keep the
+    /// broad defaults of the runner they were written for.
+    pub fn deno_permissions(&self) -> Option<DenoPermissions> {
+        self.permissions.as_ref().map(Into::into)
+    }
+
     pub fn get_metadata(&self) -> ToolPlaygroundMetadata {
         ToolPlaygroundMetadata {
             name: self.name.clone(),
@@ -685,6 +700,7 @@ impl DenoTool {
             runner: self.runner.clone(),
             operating_system: self.operating_system.clone(),
             tool_set: self.tool_set.clone(),
+            permissions: self.permissions.clone(),
         }
     }
 }
@@ -806,6 +822,7 @@ mod tests {
         assert_eq!(deserialized.homepage, Some("http://example.com".to_string()));
         assert_eq!(deserialized.runner, RunnerType::Any);
         assert_eq!(deserialized.tool_set, None);
+        assert_eq!(deserialized.permissions, None);
         assert_eq!(
             deserialized.tool_router_key,
             Some(ToolRouterKey::new(
@@ -899,6 +916,7 @@ mod tests {
             runner: RunnerType::Any,
             operating_system: vec![OperatingSystem::Linux],
             tool_set: None,
+            permissions: None,
         };
 
         let serialized = serde_json::to_string_pretty(&tool).expect("Failed to serialize DenoTool");
@@ -1025,6 +1043,7 @@ mod tests {
             runner: RunnerType::OnlyDocker,
             operating_system: vec![],
             tool_set: None,
+            permissions: None,
         };
 
         // Test serialization/deserialization with RunnerType
@@ -1073,6 +1092,7 @@ mod tests {
             runner: RunnerType::Any,
             operating_system: vec![OperatingSystem::Linux, OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         // Test serialization/deserialization with operating systems
@@ -1116,6 +1136,7 @@ mod tests {
             runner: RunnerType::Any,
             operating_system: vec![],
             tool_set: Some("test-tool-set".to_string()),
+            permissions: None,
         };
 
         // Test serialization/deserialization with tool_set
@@ -1131,4 +1152,58 @@ mod tests {
         let deserialized: DenoTool = serde_json::from_str(&serialized).expect("Failed to deserialize DenoTool");
         assert_eq!(deserialized.tool_set, None);
     }
+
+    #[test]
+    fn test_deno_tool_permissions() {
+        let tool = DenoTool {
+            tool_router_key: Some(ToolRouterKey::new(
+                "local".to_string(),
+                "Test Author".to_string(),
+                "Test Tool".to_string(),
+                None,
+            )),
+            name: "Test Tool".to_string(),
+            homepage: None,
+            author: "Test Author".to_string(),
+            version: "1.0.0".to_string(),
+            js_code: "".to_string(),
+            tools: vec![],
+            config: vec![],
+            description: "Test description".to_string(),
+            keywords: vec![],
+            input_args: Parameters::new(),
+            output_arg: ToolOutputArg { json: "".to_string() },
+            activated: false,
+            mcp_enabled: Some(false),
+            embedding: None,
+            result: ToolResult::new("object".to_string(), json!({}), vec![]),
+            sql_tables: None,
+            sql_queries: None,
+            file_inbox: None,
+            oauth: None,
+            assets: None,
+            runner: RunnerType::Any,
+            operating_system: vec![],
+            tool_set: None,
+            permissions: Some(ToolPermissions {
+                net: vec!["api.example.com".to_string()],
+                env: vec!["API_KEY".to_string()],
+                ..Default::default()
+            }),
+        };
+        let serialized = serde_json::to_string(&tool).expect("Failed to serialize DenoTool");
+        let deserialized: DenoTool = serde_json::from_str(&serialized).expect("Failed to deserialize DenoTool");
+        assert_eq!(deserialized.permissions, tool.permissions);
+        assert_eq!(deserialized.get_metadata().permissions, tool.permissions);
+        assert_eq!(
+            tool.deno_permissions().map(|permissions| permissions.net),
+            Some(vec!["api.example.com".to_string()])
+        );
+
+        let undeclared = DenoTool {
+            permissions: None,
+            ..tool
+        };
+        assert_eq!(undeclared.deno_permissions(), None);
+    }
 }
@@ -176,6 +176,7 @@ impl DockerTool {
             runner: self.runner,
             operating_system: self.operating_system.clone(),
             tool_set: self.tool_set.clone(),
+            permissions: None,
         }
     }
     
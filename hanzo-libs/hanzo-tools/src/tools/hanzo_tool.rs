@@ -12,11 +12,12 @@ use hanzo_messages::schemas::{
 
 use super::agent_tool_wrapper::AgentToolWrapper;
 use super::tool_config::OAuth;
+use super::tool_permissions::ToolPermissions;
 use super::tool_playground::{SqlQuery, SqlTable, ToolPlaygroundMetadata};
 use super::tool_types::{OperatingSystem, RunnerType};
 use super::{
     deno_tools::DenoTool, mcp_server_tool::MCPServerTool, network_tool::NetworkTool, parameters::Parameters,
-    python_tools::PythonTool, tool_config::ToolConfig, tool_output_arg::ToolOutputArg,
+    python_tools::PythonTool, tool_config::ToolConfig, tool_output_arg::ToolOutputArg, wasm_tools::WasmTool,
 };
 
 pub type IsEnabled = bool;
@@ -30,6 +31,7 @@ pub enum HanzoTool {
     Python(PythonTool, IsEnabled),
     Agent(AgentToolWrapper, IsEnabled),
     MCPServer(MCPServerTool, IsEnabled),
+    Wasm(WasmTool, IsEnabled),
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -120,6 +122,13 @@ impl HanzoTool {
             HanzoTool::MCPServer(m, _) => {
                 MCPServerTool::create_tool_router_key(m.mcp_server_command_hash.clone(), m.mcp_server_tool.clone())
             }
+            HanzoTool::Wasm(w, _) => {
+                if let Some(key) = &w.tool_router_key {
+                    key.clone()
+                } else {
+                    ToolRouterKey::new("local".to_string(), w.author.clone(), w.name.clone(), None)
+                }
+            }
         }
     }
 
@@ -135,6 +144,9 @@ impl HanzoTool {
             HanzoTool::MCPServer(m, _) => {
                 m.config = m.config.clone().iter().map(|config| config.sanitize()).collect();
             }
+            HanzoTool::Wasm(w, _) => {
+                w.config = w.config.clone().iter().map(|config| config.sanitize()).collect();
+            }
             _ => (),
         }
     }
@@ -148,6 +160,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => p.name.clone(),
             HanzoTool::Agent(a, _) => a.name.clone(),
             HanzoTool::MCPServer(m, _) => m.name.clone(),
+            HanzoTool::Wasm(w, _) => w.name.clone(),
         }
     }
     /// Tool description
@@ -159,6 +172,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => p.description.clone(),
             HanzoTool::Agent(a, _) => a.description.clone(),
             HanzoTool::MCPServer(m, _) => m.description.clone(),
+            HanzoTool::Wasm(w, _) => w.description.clone(),
         }
     }
 
@@ -171,6 +185,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => p.input_args.clone(),
             HanzoTool::Agent(a, _) => a.input_args.clone(),
             HanzoTool::MCPServer(m, _) => m.input_args.clone(),
+            HanzoTool::Wasm(w, _) => w.input_args.clone(),
         }
     }
 
@@ -183,6 +198,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => p.output_arg.clone(),
             HanzoTool::Agent(a, _) => a.output_arg.clone(),
             HanzoTool::MCPServer(m, _) => m.output_arg.clone(),
+            HanzoTool::Wasm(w, _) => w.output_arg.clone(),
         }
     }
 
@@ -195,6 +211,7 @@ impl HanzoTool {
             HanzoTool::Python(_, _) => "Python",
             HanzoTool::Agent(_, _) => "Agent",
             HanzoTool::MCPServer(_, _) => "MCPServer",
+            HanzoTool::Wasm(_, _) => "Wasm",
         }
     }
 
@@ -236,6 +253,7 @@ impl HanzoTool {
         match self {
             HanzoTool::Deno(d, _) => d.assets.clone(),
             HanzoTool::Python(p, _) => p.assets.clone(),
+            HanzoTool::Wasm(w, _) => w.assets.clone(),
             _ => None,
         }
     }
@@ -244,6 +262,7 @@ impl HanzoTool {
         match self {
             HanzoTool::Deno(d, _) => d.homepage.clone(),
             HanzoTool::Python(p, _) => p.homepage.clone(),
+            HanzoTool::Wasm(w, _) => w.homepage.clone(),
             _ => None,
         }
     }
@@ -271,6 +290,7 @@ impl HanzoTool {
             HanzoTool::Deno(d, _) => d.name = name,
             HanzoTool::Python(p, _) => p.name = name,
             HanzoTool::MCPServer(m, _) => m.name = name,
+            HanzoTool::Wasm(w, _) => w.name = name,
             _ => unreachable!(),
         }
     }
@@ -280,6 +300,7 @@ impl HanzoTool {
             HanzoTool::Deno(d, _) => d.author = author,
             HanzoTool::Python(p, _) => p.author = author,
             HanzoTool::MCPServer(m, _) => m.author = author,
+            HanzoTool::Wasm(w, _) => w.author = author,
             _ => unreachable!(),
         }
     }
@@ -288,6 +309,7 @@ impl HanzoTool {
         match self {
             HanzoTool::Deno(d, _) => d.runner.clone(),
             HanzoTool::Python(p, _) => p.runner.clone(),
+            HanzoTool::Wasm(_, _) => RunnerType::OnlyHost,
             _ => RunnerType::Any,
         }
     }
@@ -305,6 +327,15 @@ impl HanzoTool {
             HanzoTool::Deno(d, _) => d.tool_set.clone(),
             HanzoTool::Python(p, _) => p.tool_set.clone(),
             HanzoTool::MCPServer(m, _) => m.tool_set.clone(),
+            HanzoTool::Wasm(w, _) => w.tool_set.clone(),
+            _ => None,
+        }
+    }
+
+    /// Permissions the tool declares, only Deno tools declare them
+    pub fn get_permissions(&self) -> Option<ToolPermissions> {
+        match self {
+            HanzoTool::Deno(d, _) => d.permissions.clone(),
             _ => None,
         }
     }
@@ -318,6 +349,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => p.embedding = Some(embedding),
             HanzoTool::Agent(a, _) => a.embedding = Some(embedding),
             HanzoTool::MCPServer(m, _) => m.embedding = Some(embedding),
+            HanzoTool::Wasm(w, _) => w.embedding = Some(embedding),
         }
     }
 
@@ -362,6 +394,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => p.embedding.clone(),
             HanzoTool::Agent(a, _) => a.embedding.clone(),
             HanzoTool::MCPServer(m, _) => m.embedding.clone(),
+            HanzoTool::Wasm(w, _) => w.embedding.clone(),
         }
     }
 
@@ -391,6 +424,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => p.author.clone(),
             HanzoTool::Agent(a, _) => a.author.clone(),
             HanzoTool::MCPServer(m, _) => m.author.clone(),
+            HanzoTool::Wasm(w, _) => w.author.clone(),
         }
     }
 
@@ -403,6 +437,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => p.version.clone(),
             HanzoTool::Agent(_a, _) => "1.0.0".to_string(),
             HanzoTool::MCPServer(m, _) => m.version.clone(),
+            HanzoTool::Wasm(w, _) => w.version.clone(),
         }
     }
 
@@ -424,6 +459,7 @@ impl HanzoTool {
             HanzoTool::Python(_, enabled) => *enabled,
             HanzoTool::Agent(_a, enabled) => *enabled,
             HanzoTool::MCPServer(_, enabled) => *enabled,
+            HanzoTool::Wasm(_, enabled) => *enabled,
         }
     }
 
@@ -436,6 +472,7 @@ impl HanzoTool {
             HanzoTool::Python(tool, is_enabled) => *is_enabled && tool.mcp_enabled.unwrap_or(false),
             HanzoTool::Agent(a, is_enabled) => *is_enabled && a.mcp_enabled.unwrap_or(false),
             HanzoTool::MCPServer(a, is_enabled) => *is_enabled && a.mcp_enabled.unwrap_or(false),
+            HanzoTool::Wasm(w, is_enabled) => *is_enabled && w.mcp_enabled.unwrap_or(false),
         }
     }
 
@@ -448,6 +485,7 @@ impl HanzoTool {
             HanzoTool::Python(_, enabled) => *enabled = true,
             HanzoTool::Agent(_, enabled) => *enabled = true,
             HanzoTool::MCPServer(_, enabled) => *enabled = true,
+            HanzoTool::Wasm(_, enabled) => *enabled = true,
         }
     }
 
@@ -459,6 +497,7 @@ impl HanzoTool {
             HanzoTool::Python(tool, _) => tool.mcp_enabled = Some(true),
             HanzoTool::Agent(tool, _) => tool.mcp_enabled = Some(true),
             HanzoTool::MCPServer(tool, _) => tool.mcp_enabled = Some(true),
+            HanzoTool::Wasm(tool, _) => tool.mcp_enabled = Some(true),
         }
     }
 
@@ -471,6 +510,7 @@ impl HanzoTool {
             HanzoTool::Python(_, enabled) => *enabled = false,
             HanzoTool::Agent(_, enabled) => *enabled = false,
             HanzoTool::MCPServer(_, enabled) => *enabled = false,
+            HanzoTool::Wasm(_, enabled) => *enabled = false,
         }
     }
 
@@ -482,6 +522,7 @@ impl HanzoTool {
             HanzoTool::Python(tool, _) => tool.mcp_enabled = Some(false),
             HanzoTool::Agent(tool, _) => tool.mcp_enabled = Some(false),
             HanzoTool::MCPServer(tool, _) => tool.mcp_enabled = Some(false),
+            HanzoTool::Wasm(tool, _) => tool.mcp_enabled = Some(false),
         }
     }
 
@@ -502,6 +543,7 @@ impl HanzoTool {
             HanzoTool::Python(python_tool, _) => python_tool.config.clone(),
             HanzoTool::Agent(_a, _) => vec![],
             HanzoTool::MCPServer(mcp_tool, _) => mcp_tool.config.clone(),
+            HanzoTool::Wasm(wasm_tool, _) => wasm_tool.config.clone(),
         }
     }
 
@@ -514,6 +556,7 @@ impl HanzoTool {
             HanzoTool::Python(_, _) => true,
             HanzoTool::Agent(_, _) => true,
             HanzoTool::MCPServer(mcp_tool, _) => mcp_tool.check_required_config_fields(),
+            HanzoTool::Wasm(wasm_tool, _) => wasm_tool.check_required_config_fields(),
         }
     }
 
@@ -588,6 +631,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => p.keywords.clone(),
             HanzoTool::Agent(_a, _) => vec![],
             HanzoTool::MCPServer(m, _) => m.keywords.clone(),
+            HanzoTool::Wasm(w, _) => w.keywords.clone(),
         }
     }
 
@@ -597,6 +641,7 @@ impl HanzoTool {
             HanzoTool::Python(p, _) => Some(p.get_metadata()),
             HanzoTool::Rust(r, _) => Some(r.get_metadata()),
             HanzoTool::MCPServer(r, _) => Some(r.get_metadata()),
+            HanzoTool::Wasm(w, _) => Some(w.get_metadata()),
             _ => None,
         }
     }
@@ -626,6 +671,12 @@ impl From<MCPServerTool> for HanzoTool {
     }
 }
 
+impl From<WasmTool> for HanzoTool {
+    fn from(tool: WasmTool) -> Self {
+        HanzoTool::Wasm(tool, true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -677,6 +728,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Linux],
             tool_set: None,
+            permissions: None,
         };
 
         // Create a HanzoTool instance
@@ -774,6 +826,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let hanzo_tool = HanzoTool::Deno(deno_tool, true);
@@ -306,6 +306,7 @@ impl KubernetesTool {
             runner: self.runner,
             operating_system: self.operating_system.clone(),
             tool_set: self.tool_set.clone(),
+            permissions: None,
         }
     }
 }
\ No newline at end of file
@@ -6,7 +6,7 @@ use super::tool_types::{OperatingSystem, RunnerType, ToolResult};
 use crate::tools::error::ToolError;
 use rmcp::model::{CallToolResult, Content};
 use serde_json::Value;
-use hanzo_mcp::mcp_methods::{run_tool_via_command, run_tool_via_http, run_tool_via_sse};
+use hanzo_mcp::session_manager::{McpSessionTarget, MCP_SESSION_MANAGER};
 use hanzo_messages::schemas::mcp_server::{MCPServer, MCPServerType};
 use hanzo_messages::schemas::tool_router_key::ToolRouterKey;
 use hanzo_tools_runner::tools::run_result::RunResult;
@@ -72,6 +72,7 @@ impl MCPServerTool {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Linux, OperatingSystem::MacOS, OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         }
     }
 
@@ -86,27 +87,21 @@ impl MCPServerTool {
         Ok(deserialized)
     }
 
+    /// Runs the tool on its MCP server. `job_id` is the job the call is made for, if any; sampling
+    /// requests the server sends during the call are answered with that job's LLM provider.
+    /// The tool config, with `extra_config` taking precedence, is passed to a command server as
+    /// env vars.
     pub async fn run(
         &self,
         mcp_server: MCPServer,
         parameters: serde_json::Map<String, serde_json::Value>,
         extra_config: Vec<ToolConfig>,
+        job_id: Option<String>,
     ) -> Result<RunResult, ToolError> {
-        let mut env: HashMap<String, String> = MCPServerTool::tool_config_to_env_vars(self.config.clone());
-
-        // Merge extra_config into the config hashmap
-        for c in extra_config {
-            let ToolConfig::BasicConfig(basic_config) = c;
-            if let Some(value) = basic_config.key_value {
-                let mut parsed_value = value.to_string();
-                if let Some(value) = value.as_str() {
-                    parsed_value = value.to_string();
-                }
-                env.insert(basic_config.key_name.clone(), parsed_value);
-            }
-        }
-
-        let value = MCPServerTool::run_tool(mcp_server, self.mcp_server_tool.clone(), env, parameters).await?;
+        let mut config = self.config.clone();
+        config.extend(extra_config);
+        let value =
+            MCPServerTool::run_tool(mcp_server, self.mcp_server_tool.clone(), parameters, config, job_id).await?;
         if value.is_error.unwrap_or(false) {
             let error = MCPServerTool::map_content_to_error_message(value.content).await;
             return Err(ToolError::ExecutionError(error));
@@ -122,18 +117,44 @@ impl MCPServerTool {
     pub async fn run_tool(
         mcp_server: MCPServer,
         tool: String,
-        env: HashMap<String, String>,
         parameters: serde_json::Map<String, serde_json::Value>,
+        config: Vec<ToolConfig>,
+        job_id: Option<String>,
     ) -> Result<CallToolResult, hanzo_mcp::error::McpError> {
+        let session_key = mcp_server.get_command_hash();
+        let target = MCPServerTool::session_target_with_config(&mcp_server, config);
+        MCP_SESSION_MANAGER
+            .call_tool(&session_key, &target, tool, parameters, job_id)
+            .await
+    }
+
+    /// Describes how to reach the server so the session manager can reuse an open session.
+    /// The process env is the one of the server, as used to list its tools and prompts.
+    pub fn session_target(mcp_server: &MCPServer) -> McpSessionTarget {
         match mcp_server.r#type {
-            MCPServerType::Command => {
-                run_tool_via_command(mcp_server.command.unwrap_or_default(), tool, env, parameters).await
-            }
-            MCPServerType::Sse => run_tool_via_sse(mcp_server.url.unwrap_or_default(), tool, parameters).await,
-            MCPServerType::Http => run_tool_via_http(mcp_server.url.unwrap_or_default(), tool, parameters).await,
+            MCPServerType::Command => McpSessionTarget::Command {
+                command: mcp_server.command.clone().unwrap_or_default(),
+                env: mcp_server.env.clone().unwrap_or_default(),
+            },
+            MCPServerType::Sse => McpSessionTarget::Sse {
+                url: mcp_server.url.clone().unwrap_or_default(),
+            },
+            MCPServerType::Http => McpSessionTarget::Http {
+                url: mcp_server.url.clone().unwrap_or_default(),
+            },
         }
     }
 
+    /// Like `session_target`, with the tool config added to the env of a command server. Without
+    /// config the target is the server's own, so tool calls keep sharing its session.
+    pub fn session_target_with_config(mcp_server: &MCPServer, config: Vec<ToolConfig>) -> McpSessionTarget {
+        let mut target = MCPServerTool::session_target(mcp_server);
+        if let McpSessionTarget::Command { env, .. } = &mut target {
+            env.extend(MCPServerTool::tool_config_to_env_vars(config));
+        }
+        target
+    }
+
     pub async fn map_content_to_error_message(content: Vec<Content>) -> String {
         content
             .iter()
@@ -177,22 +198,98 @@ mod tests {
 
     use super::*;
 
+    use crate::tools::tool_config::BasicConfig;
+
+    fn everything_server(env: Option<HashMap<String, String>>) -> MCPServer {
+        MCPServer {
+            id: Some(1),
+            created_at: Some(String::from("2021-01-01T00:00:00Z")),
+            updated_at: Some(String::from("2021-01-01T00:00:00Z")),
+            name: "@modelcontextprotocol/server-everything".to_string(),
+            r#type: MCPServerType::Command,
+            url: None,
+            command: Some("npx -y @modelcontextprotocol/server-everything@2025.9.12".to_string()),
+            is_enabled: true,
+            env,
+        }
+    }
+
+    fn basic_config(key_name: &str, key_value: &str) -> ToolConfig {
+        ToolConfig::BasicConfig(BasicConfig {
+            key_name: key_name.to_string(),
+            description: "".to_string(),
+            required: false,
+            type_name: Some("string".to_string()),
+            key_value: Some(json!(key_value)),
+        })
+    }
+
+    #[test]
+    fn test_session_target_with_config() {
+        let server = everything_server(Some(HashMap::from([
+            ("SERVER_ONLY".to_string(), "server".to_string()),
+            ("API_KEY".to_string(), "from-server".to_string()),
+        ])));
+
+        let target = MCPServerTool::session_target_with_config(&server, vec![basic_config("API_KEY", "from-config")]);
+
+        let McpSessionTarget::Command { env, .. } = target else {
+            panic!("expected a command target");
+        };
+        assert_eq!(env.get("SERVER_ONLY").map(String::as_str), Some("server"));
+        assert_eq!(env.get("API_KEY").map(String::as_str), Some("from-config"));
+        assert_eq!(
+            MCPServerTool::session_target_with_config(&server, vec![]),
+            MCPServerTool::session_target(&server)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_passes_config_overrides_to_the_server() {
+        let tool = MCPServerTool {
+            version: "1.0.0".to_string(),
+            name: "printEnv".to_string(),
+            author: "Test Author".to_string(),
+            mcp_server_ref: "1".to_string(),
+            mcp_server_command_hash: None,
+            description: "Prints all environment variables".to_string(),
+            mcp_server_url: "".to_string(),
+            mcp_server_tool: "printEnv".to_string(),
+            tool_router_key: None,
+            config: vec![basic_config("HANZO_TEST_TOOL_CONFIG", "from-tool")],
+            keywords: vec![],
+            input_args: Parameters::new(),
+            output_arg: ToolOutputArg { json: "".to_string() },
+            activated: true,
+            embedding: None,
+            result: ToolResult::new("object".to_string(), Value::Null, vec![]),
+            tool_set: None,
+            mcp_enabled: None,
+        };
+
+        let result = tool
+            .run(
+                everything_server(None),
+                serde_json::Map::new(),
+                vec![basic_config("HANZO_TEST_TOOL_CONFIG", "from-agent-override")],
+                None,
+            )
+            .await
+            .unwrap();
+
+        let printed = result.data["text"].as_str().unwrap().to_string();
+        assert!(printed.contains("from-agent-override"));
+        assert!(!printed.contains("from-tool"));
+        MCP_SESSION_MANAGER
+            .close(&everything_server(None).get_command_hash())
+            .await;
+    }
+
     #[tokio::test]
     async fn test_run_tool() {
         let result = MCPServerTool::run_tool(
-            MCPServer {
-                id: Some(1),
-                created_at: Some(String::from("2021-01-01T00:00:00Z")),
-                updated_at: Some(String::from("2021-01-01T00:00:00Z")),
-                name: "@modelcontextprotocol/server-everything".to_string(),
-                r#type: MCPServerType::Command,
-                url: None,
-                command: Some("npx -y @modelcontextprotocol/server-everything@2025.9.12".to_string()),
-                is_enabled: true,
-                env: None,
-            },
+            everything_server(None),
             "add".to_string(),
-            HashMap::new(),
             json!({
                 "a": 1,
                 "b": 2,
@@ -200,6 +297,8 @@ mod tests {
             .as_object()
             .unwrap()
             .clone(),
+            vec![],
+            None,
         )
         .await
         .inspect_err(|e| {
@@ -11,6 +11,8 @@ pub mod shared_execution;
 pub mod hanzo_tool;
 pub mod tool_config;
 pub mod tool_output_arg;
+pub mod tool_permissions;
 pub mod tool_playground;
 pub mod tool_router_dep;
 pub mod tool_types;
+pub mod wasm_tools;
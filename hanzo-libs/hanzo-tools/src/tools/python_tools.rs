@@ -409,6 +409,7 @@ impl PythonTool {
             runner: self.runner.clone(),
             operating_system: self.operating_system.clone(),
             tool_set: self.tool_set.clone(),
+            permissions: None,
         }
     }
 }
@@ -0,0 +1,215 @@
+use std::path::{Component, Path};
+
+use hanzo_tools_runner::tools::deno_permissions::DenoPermissions;
+use serde::{Deserialize, Serialize};
+
+use super::error::ToolError;
+
+/// Permissions a tool declares it needs. The runner grants these instead of its broad
+/// defaults, and the node shows them before installing the tool.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolPermissions {
+    /// Hosts the tool can reach, optionally with a port like `api.example.com:443`
+    #[serde(default)]
+    pub net: Vec<String>,
+    /// Paths the tool can read, besides its home, assets and mounted files
+    #[serde(default)]
+    pub read: Vec<String>,
+    /// Paths the tool can write, besides its home and mounted files
+    #[serde(default)]
+    pub write: Vec<String>,
+    /// Binaries the tool can spawn
+    #[serde(default)]
+    pub run: Vec<String>,
+    /// Environment variables the tool can read, besides the ones the node sets for it
+    #[serde(default)]
+    pub env: Vec<String>,
+}
+
+impl From<&ToolPermissions> for DenoPermissions {
+    fn from(permissions: &ToolPermissions) -> Self {
+        DenoPermissions {
+            net: permissions.net.clone(),
+            read: permissions.read.clone(),
+            write: permissions.write.clone(),
+            run: permissions.run.clone(),
+            env: permissions.env.clone(),
+        }
+    }
+}
+
+/// Most a node lets tools ask for. A list set to `None` leaves that kind of permission
+/// unrestricted, `*` matches anything and `*.example.com`, `/data` or `APP_*` match the
+/// subdomains, subpaths or variables they cover.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolPermissionsPolicy {
+    #[serde(default)]
+    pub net: Option<Vec<String>>,
+    #[serde(default)]
+    pub read: Option<Vec<String>>,
+    #[serde(default)]
+    pub write: Option<Vec<String>>,
+    #[serde(default)]
+    pub run: Option<Vec<String>>,
+    #[serde(default)]
+    pub env: Option<Vec<String>>,
+    /// Whether tools that don't declare their permissions are accepted. They run with the broad
+    /// defaults of the runner, which the lists above don't restrict.
+    #[serde(default = "ToolPermissionsPolicy::default_allow_undeclared")]
+    pub allow_undeclared: bool,
+}
+
+impl Default for ToolPermissionsPolicy {
+    fn default() -> Self {
+        Self {
+            net: None,
+            read: None,
+            write: None,
+            run: None,
+            env: None,
+            allow_undeclared: true,
+        }
+    }
+}
+
+impl ToolPermissionsPolicy {
+    fn default_allow_undeclared() -> bool {
+        true
+    }
+
+    /// Refuses the tool if it asks for more than the policy allows
+    pub fn check(&self, permissions: Option<&ToolPermissions>) -> Result<(), ToolError> {
+        let Some(permissions) = permissions else {
+            if self.allow_undeclared {
+                return Ok(());
+            }
+            return Err(ToolError::ToolNotRunnable(
+                "The tool does not declare its permissions, which the node requires".to_string(),
+            ));
+        };
+
+        let exceeding = self.exceeding(permissions);
+        if exceeding.is_empty() {
+            Ok(())
+        } else {
+            Err(ToolError::ToolNotRunnable(format!(
+                "The tool asks for permissions the node does not allow: {}",
+                exceeding.join(", ")
+            )))
+        }
+    }
+
+    /// Permissions asked for that the policy does not allow, as `kind value`
+    pub fn exceeding(&self, permissions: &ToolPermissions) -> Vec<String> {
+        let kinds: [(&str, &Vec<String>, &Option<Vec<String>>, fn(&str, &str) -> bool); 5] = [
+            ("net", &permissions.net, &self.net, host_matches),
+            ("read", &permissions.read, &self.read, path_matches),
+            ("write", &permissions.write, &self.write, path_matches),
+            ("run", &permissions.run, &self.run, run_matches),
+            ("env", &permissions.env, &self.env, env_matches),
+        ];
+
+        let mut exceeding = Vec::new();
+        for (kind, requested, allowed, matches) in kinds {
+            let Some(allowed) = allowed else {
+                continue;
+            };
+            for value in requested {
+                if !allowed.iter().any(|allowed| allowed == "*" || matches(allowed, value)) {
+                    exceeding.push(format!("{} {}", kind, value));
+                }
+            }
+        }
+        exceeding
+    }
+}
+
+fn host_matches(allowed: &str, requested: &str) -> bool {
+    let allowed = allowed.to_lowercase();
+    let requested = requested.to_lowercase();
+    if allowed == requested {
+        return true;
+    }
+    // A host without port allows all of its ports
+    let host = requested.split(':').next().unwrap_or_default();
+    match allowed.strip_prefix("*.") {
+        Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+        None => !allowed.contains(':') && host == allowed,
+    }
+}
+
+fn run_matches(allowed: &str, requested: &str) -> bool {
+    allowed == requested
+}
+
+fn path_matches(allowed: &str, requested: &str) -> bool {
+    let requested = Path::new(requested);
+    // `..` could leave the allowed folder
+    !requested.components().any(|c| c == Component::ParentDir) && requested.starts_with(allowed)
+}
+
+fn env_matches(allowed: &str, requested: &str) -> bool {
+    match allowed.strip_suffix('*') {
+        Some(prefix) => requested.starts_with(prefix),
+        None => allowed == requested,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn permissions() -> ToolPermissions {
+        ToolPermissions {
+            net: vec!["api.example.com:443".to_string(), "cdn.hanzo.ai".to_string()],
+            read: vec!["/data/reports".to_string()],
+            write: vec!["/data/../etc".to_string()],
+            run: vec!["ffmpeg".to_string()],
+            env: vec!["APP_TOKEN".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_policy_exceeding_permissions() {
+        let policy = ToolPermissionsPolicy {
+            net: Some(vec!["api.example.com".to_string(), "*.hanzo.ai".to_string()]),
+            read: Some(vec!["/data".to_string()]),
+            write: Some(vec!["/data".to_string()]),
+            run: Some(vec![]),
+            env: Some(vec!["APP_*".to_string()]),
+            allow_undeclared: true,
+        };
+        assert_eq!(
+            policy.exceeding(&permissions()),
+            vec!["write /data/../etc", "run ffmpeg"]
+        );
+        assert!(matches!(
+            policy.check(Some(&permissions())),
+            Err(ToolError::ToolNotRunnable(_))
+        ));
+
+        let allowed = ToolPermissions {
+            write: vec!["/data/out".to_string()],
+            run: vec![],
+            ..permissions()
+        };
+        assert!(policy.check(Some(&allowed)).is_ok());
+    }
+
+    #[test]
+    fn test_policy_undeclared_permissions() {
+        assert!(ToolPermissionsPolicy::default().check(None).is_ok());
+        assert!(ToolPermissionsPolicy::default().check(Some(&permissions())).is_ok());
+
+        // Undeclared tools keep the runner defaults while the policy accepts them
+        let net_policy = ToolPermissionsPolicy {
+            net: Some(vec!["api.example.com".to_string()]),
+            ..Default::default()
+        };
+        assert!(net_policy.check(None).is_ok());
+
+        let policy: ToolPermissionsPolicy = serde_json::from_str(r#"{"allow_undeclared": false}"#).unwrap();
+        assert!(policy.check(None).is_err());
+        assert!(policy.check(Some(&ToolPermissions::default())).is_ok());
+    }
+}
@@ -1,6 +1,7 @@
 use super::{
     parameters::Parameters,
     tool_config::{BasicConfig, OAuth, ToolConfig},
+    tool_permissions::ToolPermissions,
     tool_types::{OperatingSystem, RunnerType, ToolResult},
 };
 use serde::{Deserialize, Deserializer, Serialize};
@@ -63,6 +64,8 @@ pub struct ToolPlaygroundMetadata {
     pub runner: RunnerType,
     pub operating_system: Vec<OperatingSystem>,
     pub tool_set: Option<String>,
+    #[serde(default)]
+    pub permissions: Option<ToolPermissions>,
 }
 
 fn deserialize_configurations<'de, D>(deserializer: D) -> Result<Vec<ToolConfig>, D::Error>
@@ -656,6 +659,7 @@ mod tests {
             runner: RunnerType::OnlyHost,
             operating_system: vec![OperatingSystem::Linux],
             tool_set: None,
+            permissions: None,
         };
 
         let serialized = serde_json::to_value(&metadata).unwrap();
@@ -795,6 +799,7 @@ mod tests {
             runner: RunnerType::Any,
             operating_system: vec![OperatingSystem::Windows],
             tool_set: None,
+            permissions: None,
         };
 
         let serialized = serde_json::to_value(&metadata).unwrap();
@@ -880,6 +885,7 @@ mod tests {
             runner: RunnerType::Any,
             operating_system: vec![OperatingSystem::Linux, OperatingSystem::MacOS],
             tool_set: Some("some cool set".to_string()),
+            permissions: None,
         };
 
         let serialized = serde_json::to_value(&metadata).unwrap();
@@ -0,0 +1,218 @@
+use super::parameters::Parameters;
+use super::tool_config::ToolConfig;
+use super::tool_output_arg::ToolOutputArg;
+use super::tool_playground::ToolPlaygroundMetadata;
+use super::tool_types::{OperatingSystem, RunnerType, ToolResult};
+use crate::tools::error::ToolError;
+use hanzo_messages::schemas::tool_router_key::ToolRouterKey;
+use std::path::PathBuf;
+
+/// What a WASM tool is allowed to reach outside of its sandbox
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WasmCapabilities {
+    /// Hosts the tool can send HTTP requests to, `*.example.com` also matches the subdomains
+    #[serde(default)]
+    pub http_hosts: Vec<String>,
+    /// Whether the tool can read and write the home folder of the job, at `/app/home`
+    #[serde(default)]
+    pub home: bool,
+    /// Whether the tool can read and write the files mounted for the job, at `/app/mount`
+    #[serde(default)]
+    pub mounts: bool,
+    /// Environment variables of the execution the tool can read, like `X_HANZO_APP_ID`
+    #[serde(default)]
+    pub env: Vec<String>,
+}
+
+impl WasmCapabilities {
+    pub fn allows_http_host(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        self.http_hosts.iter().any(|allowed| {
+            let allowed = allowed.to_lowercase();
+            match allowed.strip_prefix("*.") {
+                Some(domain) => host == domain || host.ends_with(&format!(".{}", domain)),
+                None => host == allowed,
+            }
+        })
+    }
+
+    pub fn allows_env(&self, key: &str) -> bool {
+        self.env.iter().any(|allowed| allowed == key)
+    }
+}
+
+/// A tool compiled to WebAssembly. The module is stored with the tool assets and runs
+/// in the node WASM runtime, so it needs neither Deno nor Python. Components built against
+/// the `hanzo:tool` WIT world are called through their `run` export, plain modules through
+/// `entrypoint`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WasmTool {
+    pub version: String,
+    pub name: String,
+    #[serde(
+        default,
+        serialize_with = "ToolRouterKey::serialize_tool_router_key",
+        deserialize_with = "ToolRouterKey::deserialize_tool_router_key"
+    )]
+    pub tool_router_key: Option<ToolRouterKey>,
+    pub homepage: Option<String>,
+    pub author: String,
+    pub mcp_enabled: Option<bool>,
+    pub description: String,
+    pub keywords: Vec<String>,
+    pub config: Vec<ToolConfig>,
+    pub input_args: Parameters,
+    pub output_arg: ToolOutputArg,
+    pub activated: bool,
+    pub embedding: Option<Vec<f32>>,
+    pub result: ToolResult,
+    /// File name of the module among the assets of the tool
+    pub module: String,
+    /// Exported function called with the tool input, for modules that are not components
+    #[serde(default = "WasmTool::default_entrypoint")]
+    pub entrypoint: String,
+    #[serde(default)]
+    pub capabilities: WasmCapabilities,
+    pub assets: Option<Vec<String>>,
+    pub tool_set: Option<String>,
+}
+
+impl WasmTool {
+    fn default_entrypoint() -> String {
+        "run".to_string()
+    }
+
+    /// Convert to json
+    pub fn to_json(&self) -> Result<String, ToolError> {
+        serde_json::to_string(self).map_err(|_| ToolError::FailedJSONParsing)
+    }
+
+    /// Convert from json
+    pub fn from_json(json: &str) -> Result<Self, ToolError> {
+        let deserialized: Self = serde_json::from_str(json)?;
+        Ok(deserialized)
+    }
+
+    pub fn check_required_config_fields(&self) -> bool {
+        for config in &self.config {
+            let ToolConfig::BasicConfig(basic_config) = config;
+            if basic_config.required && basic_config.key_value.is_none() {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn assets_folder(node_storage_path: &str, tool_router_key: &ToolRouterKey) -> PathBuf {
+        PathBuf::from(node_storage_path)
+            .join(".tools_storage")
+            .join("tools")
+            .join(tool_router_key.convert_to_path())
+    }
+
+    /// Path of the module in the tool storage of the node
+    pub fn module_path(&self, node_storage_path: &str, tool_router_key: &ToolRouterKey) -> PathBuf {
+        Self::assets_folder(node_storage_path, tool_router_key).join(&self.module)
+    }
+
+    /// Paths of the assets of the tool in the tool storage of the node
+    pub fn asset_paths(&self, node_storage_path: &str, tool_router_key: &ToolRouterKey) -> Vec<PathBuf> {
+        let folder = Self::assets_folder(node_storage_path, tool_router_key);
+        self.assets.iter().flatten().map(|asset| folder.join(asset)).collect()
+    }
+
+    /// Reads the module bytes from the tool storage of the node
+    pub fn read_module(&self, node_storage_path: &str, tool_router_key: &ToolRouterKey) -> Result<Vec<u8>, ToolError> {
+        let path = self.module_path(node_storage_path, tool_router_key);
+        std::fs::read(&path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read WASM module {:?}: {}", path, e)))
+    }
+
+    pub fn get_metadata(&self) -> ToolPlaygroundMetadata {
+        ToolPlaygroundMetadata {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            keywords: self.keywords.clone(),
+            homepage: self.homepage.clone(),
+            author: self.author.clone(),
+            version: self.version.clone(),
+            configurations: self.config.clone(),
+            parameters: self.input_args.clone(),
+            result: self.result.clone(),
+            sql_tables: vec![],
+            sql_queries: vec![],
+            tools: None,
+            oauth: None,
+            runner: RunnerType::OnlyHost,
+            operating_system: vec![OperatingSystem::Linux, OperatingSystem::MacOS, OperatingSystem::Windows],
+            tool_set: self.tool_set.clone(),
+            permissions: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_wasm_tool_serialization() {
+        let tool_router_key = ToolRouterKey::new(
+            "local".to_string(),
+            "@@official.hanzo".to_string(),
+            "word_count".to_string(),
+            None,
+        );
+        let tool = WasmTool {
+            version: "1.0.0".to_string(),
+            name: "word_count".to_string(),
+            tool_router_key: Some(tool_router_key.clone()),
+            homepage: None,
+            author: "@@official.hanzo".to_string(),
+            mcp_enabled: Some(false),
+            description: "Counts the words of a text".to_string(),
+            keywords: vec!["text".to_string()],
+            config: vec![],
+            input_args: Parameters::new(),
+            output_arg: ToolOutputArg { json: "{}".to_string() },
+            activated: true,
+            embedding: None,
+            result: ToolResult::new("object".to_string(), json!({}), vec![]),
+            module: "word_count.wasm".to_string(),
+            entrypoint: "run".to_string(),
+            capabilities: WasmCapabilities::default(),
+            assets: Some(vec!["word_count.wasm".to_string()]),
+            tool_set: None,
+        };
+
+        let json = tool.to_json().unwrap();
+        assert_eq!(WasmTool::from_json(&json).unwrap(), tool);
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value.as_object_mut().unwrap().remove("entrypoint");
+        let deserialized: WasmTool = serde_json::from_value(value).unwrap();
+        assert_eq!(deserialized.entrypoint, "run");
+
+        let path = tool.module_path("/storage", &tool_router_key);
+        assert!(path.starts_with("/storage/.tools_storage/tools"));
+        assert!(path.ends_with("word_count.wasm"));
+        assert_eq!(tool.asset_paths("/storage", &tool_router_key), vec![path]);
+    }
+
+    #[test]
+    fn test_allows_http_host() {
+        let capabilities = WasmCapabilities {
+            http_hosts: vec!["api.example.com".to_string(), "*.hanzo.ai".to_string()],
+            ..Default::default()
+        };
+        assert!(capabilities.allows_http_host("api.example.com"));
+        assert!(capabilities.allows_http_host("API.Example.com"));
+        assert!(!capabilities.allows_http_host("example.com"));
+        assert!(!capabilities.allows_http_host("api.example.com.evil.io"));
+        assert!(capabilities.allows_http_host("hanzo.ai"));
+        assert!(capabilities.allows_http_host("llm.hanzo.ai"));
+        assert!(!capabilities.allows_http_host("nothanzo.ai"));
+        assert!(!WasmCapabilities::default().allows_http_host("api.example.com"));
+    }
+}
@@ -0,0 +1,195 @@
+//! Tools built as WebAssembly components against the `hanzo:tool` WIT world.
+//!
+//! A component exports `run`, which takes the tool parameters as a JSON object and returns
+//! the result as JSON, and can import the `host` interface for logging, the tool config and
+//! HTTP requests to the hosts the tool declared. Any language with a component toolchain
+//! (cargo-component, TinyGo, jco, componentize-py) can target it.
+
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tokio::runtime::Handle;
+use tracing::{debug, error, info, trace, warn};
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Store, StoreLimits, StoreLimitsBuilder};
+use wasmtime_wasi::{ResourceTable, WasiCtx, WasiView};
+
+use hanzo_tools::tools::error::ToolError;
+use hanzo_tools::tools::wasm_tools::WasmCapabilities;
+
+use crate::{call_error, WasiSandbox, WasmOutput, WasmRuntime};
+
+wasmtime::component::bindgen!({
+    path: "wit",
+    world: "tool",
+});
+
+use hanzo::tool::host::{self, HttpRequest, HttpResponse, LogLevel};
+
+/// The `hanzo:tool` WIT package, for tool authors to build against
+pub const TOOL_WIT: &str = include_str!("../wit/tool.wit");
+
+/// Whether the bytes are a component rather than a core module. Both start with the
+/// `\0asm` magic, the layer field that follows it is 1 for components.
+pub fn is_component(wasm_bytes: &[u8]) -> bool {
+    wasm_bytes.len() >= 8 && wasm_bytes[0..4] == *b"\0asm" && wasm_bytes[6..8] == [1, 0]
+}
+
+/// What the host gives to a single run of a tool
+#[derive(Debug, Clone, Default)]
+pub struct ToolHost {
+    /// Config of the tool, by key
+    pub config: Map<String, Value>,
+    pub capabilities: WasmCapabilities,
+}
+
+struct ToolState {
+    wasi_ctx: WasiCtx,
+    table: ResourceTable,
+    limits: StoreLimits,
+    host: ToolHost,
+    http_client: reqwest::Client,
+    handle: Handle,
+}
+
+impl WasiView for ToolState {
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.wasi_ctx
+    }
+}
+
+impl host::Host for ToolState {
+    fn log(&mut self, level: LogLevel, message: String) {
+        match level {
+            LogLevel::Trace => trace!("WASM tool: {}", message),
+            LogLevel::Debug => debug!("WASM tool: {}", message),
+            LogLevel::Info => info!("WASM tool: {}", message),
+            LogLevel::Warn => warn!("WASM tool: {}", message),
+            LogLevel::Error => error!("WASM tool: {}", message),
+        }
+    }
+
+    fn get_config(&mut self, key: String) -> Option<String> {
+        self.host.config.get(&key).map(|value| value.to_string())
+    }
+
+    fn http_fetch(&mut self, request: HttpRequest) -> Result<HttpResponse, String> {
+        let url = reqwest::Url::parse(&request.url).map_err(|e| format!("Invalid url {}: {}", request.url, e))?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(format!("Unsupported scheme {}", url.scheme()));
+        }
+        let host = url.host_str().unwrap_or_default().to_string();
+        if !self.host.capabilities.allows_http_host(&host) {
+            return Err(format!("The tool is not allowed to reach {}", host));
+        }
+        let method = reqwest::Method::from_bytes(request.method.to_uppercase().as_bytes())
+            .map_err(|_| format!("Invalid method {}", request.method))?;
+
+        let mut builder = self.http_client.request(method, url);
+        for (name, value) in request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        // Tools run on a blocking thread, the request itself runs on the node runtime
+        self.handle.block_on(async move {
+            let response = builder.send().await.map_err(|e| e.to_string())?;
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect();
+            let body = response.bytes().await.map_err(|e| e.to_string())?.to_vec();
+            Ok(HttpResponse { status, headers, body })
+        })
+    }
+}
+
+impl WasmRuntime {
+    /// Compiles a component, reusing the compilation of the same bytes. Keyed by SHA-256 so
+    /// that crafted bytes can't collide with another tool and run as its cached compilation.
+    async fn load_component(&self, wasm_bytes: &[u8]) -> Result<Component, ToolError> {
+        let hash = format!("{:x}", Sha256::digest(wasm_bytes));
+        let mut components = self.components.lock().await;
+        if let Some(component) = components.get(&hash) {
+            return Ok(component.clone());
+        }
+
+        info!("Compiling WASM component {}", hash);
+        let component = Component::new(&self.engine, wasm_bytes)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to compile component: {}", e)))?;
+        components.insert(hash, component.clone());
+        Ok(component)
+    }
+
+    /// Runs a component built against the `hanzo:tool` world with the tool parameters
+    pub async fn execute_component(
+        &self,
+        wasm_bytes: &[u8],
+        input: Value,
+        host: ToolHost,
+        sandbox: &WasiSandbox,
+    ) -> Result<WasmOutput, ToolError> {
+        let component = self.load_component(wasm_bytes).await?;
+        let input = serde_json::to_string(&input).map_err(|e| ToolError::SerializationError(e.to_string()))?;
+        let (mut wasi, pipes) = sandbox.builder()?;
+        let wasi_ctx = wasi.build();
+
+        let engine = self.engine.clone();
+        let config = self.config.clone();
+        let epoch_deadline = self.epoch_deadline();
+        let handle = Handle::current();
+        let run = tokio::task::spawn_blocking(move || -> wasmtime::Result<Result<String, String>> {
+            let mut linker: Linker<ToolState> = Linker::new(&engine);
+            wasmtime_wasi::add_to_linker_sync(&mut linker)?;
+            Tool::add_to_linker(&mut linker, |state: &mut ToolState| state)?;
+
+            // Epoch interruption can't stop a tool waiting on a host call, so requests get the
+            // same wall-clock limit as the run
+            let http_client = reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .timeout(config.max_execution_time)
+                .build()?;
+            let mut store = Store::new(
+                &engine,
+                ToolState {
+                    wasi_ctx,
+                    table: ResourceTable::new(),
+                    limits: StoreLimitsBuilder::new()
+                        .memory_size(config.max_memory_bytes as usize)
+                        .build(),
+                    host,
+                    http_client,
+                    handle,
+                },
+            );
+            store.limiter(|state| &mut state.limits);
+            store.set_epoch_deadline(epoch_deadline);
+            if let Some(fuel) = config.fuel_limit {
+                store.set_fuel(fuel)?;
+            }
+
+            let tool = Tool::instantiate(&mut store, &component, &linker)?;
+            tool.call_run(&mut store, &input)
+        });
+
+        let output = tokio::time::timeout(self.config.max_execution_time, run)
+            .await
+            .map_err(|_| ToolError::ExecutionError("Execution timeout".to_string()))
+            .and_then(|joined| joined.map_err(|e| ToolError::ExecutionError(format!("Execution failed: {}", e))))
+            .and_then(|called| called.map_err(call_error))
+            .and_then(|output| output.map_err(ToolError::ExecutionError));
+
+        // Mount files are written back even when the execution failed
+        sandbox.sync_mounts()?;
+        let result = serde_json::from_str(&output?)
+            .map_err(|e| ToolError::ParseError(format!("The tool did not return JSON: {}", e)))?;
+        Ok(pipes.into_output(result))
+    }
+}
@@ -2,6 +2,9 @@
 //!
 //! Provides sandboxed execution of WebAssembly modules as tools,
 //! enabling language-agnostic compute with deterministic execution.
+//! Components built against the `hanzo:tool` WIT world (see `wit/tool.wit`)
+//! are run through the component model, see [`WasmRuntime::execute_component`].
+//! Tools only reach the files and environment their capabilities grant, see [`WasiSandbox`].
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -13,10 +16,19 @@ use serde_json::Value;
 use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, info, error};
 use wasmtime::*;
-use wasmtime_wasi::{ResourceTable, WasiCtx, WasiCtxBuilder, WasiView};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
 
 use hanzo_tools::tools::error::ToolError;
 
+mod component;
+mod sandbox;
+pub use component::{is_component, ToolHost, TOOL_WIT};
+pub use sandbox::{PreopenedDir, WasiSandbox, WasmOutput};
+
+/// Interval at which the runtime advances the epoch of its engine, the wall-clock limit
+/// of an execution is counted in these ticks
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
 /// Configuration for WASM runtime
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WasmConfig {
@@ -53,23 +65,12 @@ pub struct WasmModuleInfo {
 
 /// Store data for WASM instances with shared memory for host-guest communication
 struct StoreData {
-    wasi_ctx: WasiCtx,
-    table: ResourceTable,
+    wasi: WasiP1Ctx,
     limits: StoreLimitsBuilder,
     /// Shared data for host-guest communication
     host_data: Arc<RwLock<HostData>>,
 }
 
-impl WasiView for StoreData {
-    fn table(&mut self) -> &mut ResourceTable {
-        &mut self.table
-    }
-
-    fn ctx(&mut self) -> &mut WasiCtx {
-        &mut self.wasi_ctx
-    }
-}
-
 /// Host data shared between host and WASM guest
 #[derive(Debug, Default)]
 struct HostData {
@@ -88,6 +89,8 @@ pub struct WasmRuntime {
     engine: Engine,
     config: WasmConfig,
     modules: Arc<Mutex<HashMap<String, Module>>>,
+    /// Compiled components, by hash of their bytes
+    components: Arc<Mutex<HashMap<String, component::Component>>>,
 }
 
 impl WasmRuntime {
@@ -100,6 +103,8 @@ impl WasmRuntime {
         engine_config.wasm_bulk_memory(true);
         engine_config.wasm_multi_value(true);
         engine_config.wasm_reference_types(true);
+        engine_config.wasm_component_model(true);
+        engine_config.epoch_interruption(true);
 
         // Set resource limits
         // Note: In wasmtime 23.0, allocation_strategy takes no arguments
@@ -112,10 +117,24 @@ impl WasmRuntime {
 
         let engine = Engine::new(&engine_config)?;
 
+        // Stores trap once the epoch passes their deadline, which stops guests that run past
+        // the execution time even after the caller stopped waiting for them
+        let weak_engine = engine.weak();
+        std::thread::Builder::new()
+            .name("wasm-epoch".to_string())
+            .spawn(move || {
+                while let Some(engine) = weak_engine.upgrade() {
+                    engine.increment_epoch();
+                    drop(engine);
+                    std::thread::sleep(EPOCH_TICK);
+                }
+            })?;
+
         Ok(Self {
             engine,
             config,
             modules: Arc::new(Mutex::new(HashMap::new())),
+            components: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
@@ -156,16 +175,29 @@ impl WasmRuntime {
         Ok(info)
     }
 
-    /// Execute a function from a loaded WASM module
+    /// Deadline of a store, in epoch ticks from now
+    fn epoch_deadline(&self) -> u64 {
+        (self.config.max_execution_time.as_millis() / EPOCH_TICK.as_millis()) as u64 + 1
+    }
+
+    /// Execute a function from a loaded WASM module, without access to files or environment
+    pub async fn execute(&self, module_name: &str, function_name: &str, params: Value) -> Result<Value, ToolError> {
+        self.execute_with_wasi(module_name, function_name, params, &WasiSandbox::default())
+            .await
+            .map(|output| output.result)
+    }
+
+    /// Execute a function from a loaded WASM module, with the WASI resources of the sandbox
     ///
     /// Special conventions:
     /// - Functions ending with "_str" or named "hello" are expected to return string pointers
-    pub async fn execute(
+    pub async fn execute_with_wasi(
         &self,
         module_name: &str,
         function_name: &str,
         params: Value,
-    ) -> Result<Value, ToolError> {
+        sandbox: &WasiSandbox,
+    ) -> Result<WasmOutput, ToolError> {
         info!("Executing WASM function: {}::{}", module_name, function_name);
 
         // Get the module
@@ -180,15 +212,16 @@ impl WasmRuntime {
         let host_data = Arc::new(RwLock::new(HostData::default()));
 
         // Create store with limits
+        let (mut wasi, pipes) = sandbox.builder()?;
         let mut store = Store::new(
             &self.engine,
             StoreData {
-                wasi_ctx: WasiCtxBuilder::new().build(),
-                table: ResourceTable::new(),
+                wasi: wasi.build_p1(),
                 limits: StoreLimitsBuilder::new(),
                 host_data: host_data.clone(),
             },
         );
+        store.set_epoch_deadline(self.epoch_deadline());
 
         // Set fuel if configured
         if let Some(fuel) = self.config.fuel_limit {
@@ -212,6 +245,10 @@ impl WasmRuntime {
         // Add custom host functions
         self.add_host_functions(&mut linker)
             .map_err(|e| ToolError::ExecutionError(format!("Failed to add host functions: {}", e)))?;
+        if self.config.enable_wasi {
+            preview1::add_to_linker_sync(&mut linker, |data: &mut StoreData| &mut data.wasi)
+                .map_err(|e| ToolError::ExecutionError(format!("Failed to add WASI: {}", e)))?;
+        }
 
         // Instantiate the module
         let instance = linker.instantiate(&mut store, &module).map_err(|e| {
@@ -255,8 +292,7 @@ impl WasmRuntime {
             tokio::task::spawn_blocking(move || {
                 // Call the WASM function
                 let mut results = vec![Val::I32(0); result_types.len()];
-                func.call(&mut store, &wasm_params, &mut results)
-                    .map_err(|e| ToolError::ExecutionError(format!("Function call failed: {}", e)))?;
+                func.call(&mut store, &wasm_params, &mut results).map_err(call_error)?;
 
                 // Process the results based on return type
                 let result = if results.is_empty() {
@@ -313,8 +349,9 @@ impl WasmRuntime {
             }),
         )
         .await
-        .map_err(|_| ToolError::ExecutionError("Execution timeout".to_string()))?
-        .map_err(|e| ToolError::ExecutionError(format!("Execution failed: {}", e)))?;
+        .map_err(|_| ToolError::ExecutionError("Execution timeout".to_string()))
+        .and_then(|joined| joined.map_err(|e| ToolError::ExecutionError(format!("Execution failed: {}", e))))
+        .and_then(|result| result);
 
         // Add any logs to the result
         let logs = host_data.read().await.logs.clone();
@@ -322,7 +359,9 @@ impl WasmRuntime {
             debug!("WASM execution logs: {:?}", logs);
         }
 
-        result
+        // Mount files are written back even when the execution failed
+        sandbox.sync_mounts()?;
+        result.map(|result| pipes.into_output(result))
     }
 
     /// Prepare WASM parameters based on function signature
@@ -473,11 +512,14 @@ impl WasmRuntime {
         wasm_bytes: Vec<u8>,
         function_name: &str,
         params: Value,
-    ) -> Result<Value, ToolError> {
+        sandbox: &WasiSandbox,
+    ) -> Result<WasmOutput, ToolError> {
         let module_name = format!("temp_{}", uuid::Uuid::new_v4());
         self.load_module(module_name.clone(), wasm_bytes).await
             .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-        let result = self.execute(&module_name, function_name, params).await;
+        let result = self
+            .execute_with_wasi(&module_name, function_name, params, sandbox)
+            .await;
 
         // Clean up temporary module
         let mut modules = self.modules.lock().await;
@@ -671,6 +713,16 @@ impl WasmRuntime {
     pub async fn clear_modules(&self) {
         let mut modules = self.modules.lock().await;
         modules.clear();
+        self.components.lock().await.clear();
+    }
+}
+
+/// Error of a call into the guest, a trap on the epoch deadline is reported as a timeout
+fn call_error(e: anyhow::Error) -> ToolError {
+    if matches!(e.downcast_ref::<Trap>(), Some(Trap::Interrupt)) {
+        ToolError::ExecutionError("Execution timeout".to_string())
+    } else {
+        ToolError::ExecutionError(format!("Function call failed: {}", e))
     }
 }
 
@@ -0,0 +1,217 @@
+//! WASI context of a tool execution, built from the capabilities the tool declared.
+//!
+//! Guests see the same layout as tools run by the Deno and Python runners: the home folder
+//! of the job at `/app/home`, the tool assets at `/app/assets` and the mounted files at
+//! `/app/mount`, with `HANZO_HOME`, `HANZO_ASSETS` and `HANZO_MOUNT` pointing at them.
+//! Nothing is preopened and no environment variable is passed unless the capabilities
+//! allow it. The home folder is kept between the executions of a job, the assets and mount
+//! folders are copies private to each execution and removed with it.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tempfile::TempDir;
+use wasmtime_wasi::pipe::MemoryOutputPipe;
+use wasmtime_wasi::{DirPerms, FilePerms, WasiCtxBuilder};
+
+use hanzo_tools::tools::error::ToolError;
+use hanzo_tools::tools::wasm_tools::WasmCapabilities;
+use hanzo_tools_runner::tools::execution_context::ExecutionContext;
+use hanzo_tools_runner::tools::file_name_utils::sanitize_for_file_name;
+
+/// Bytes kept of each of stdout and stderr, a guest writing more traps
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// Directory of the host made visible to the guest
+#[derive(Debug, Clone)]
+pub struct PreopenedDir {
+    pub host_path: PathBuf,
+    pub guest_path: String,
+    pub writable: bool,
+}
+
+/// WASI resources of a single execution
+#[derive(Debug, Clone, Default)]
+pub struct WasiSandbox {
+    pub preopened_dirs: Vec<PreopenedDir>,
+    pub env: Vec<(String, String)>,
+    /// Mount files with their copy in the mount folder, written back after the execution
+    mounted_files: Vec<(PathBuf, PathBuf)>,
+    /// Folder holding the assets and mount copies, removed once the last clone is dropped
+    execution_folder: Option<Arc<TempDir>>,
+}
+
+/// Result of an execution, with what the guest wrote to stdout and stderr
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmOutput {
+    pub result: Value,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Captured stdout and stderr of an execution
+pub(crate) struct OutputPipes {
+    stdout: MemoryOutputPipe,
+    stderr: MemoryOutputPipe,
+}
+
+impl OutputPipes {
+    pub(crate) fn into_output(self, result: Value) -> WasmOutput {
+        WasmOutput {
+            result,
+            stdout: String::from_utf8_lossy(&self.stdout.contents()).to_string(),
+            stderr: String::from_utf8_lossy(&self.stderr.contents()).to_string(),
+        }
+    }
+}
+
+impl WasiSandbox {
+    /// Prepares the folders of the execution in its storage and picks the allowed variables
+    /// out of `envs`
+    pub fn for_execution(
+        context: &ExecutionContext,
+        capabilities: &WasmCapabilities,
+        envs: &HashMap<String, String>,
+    ) -> Result<Self, ToolError> {
+        let root = context.storage.join(sanitize_for_file_name(context.context_id.clone()));
+        let mut sandbox = WasiSandbox::default();
+        sandbox.set_env("HANZO_CONTEXT_ID", &context.context_id);
+        sandbox.set_env("HANZO_EXECUTION_ID", &context.execution_id);
+
+        let mut allowed_envs: Vec<_> = envs.iter().filter(|(key, _)| capabilities.allows_env(key)).collect();
+        allowed_envs.sort();
+        for (key, value) in allowed_envs {
+            sandbox.set_env(key, value);
+        }
+
+        if capabilities.home {
+            let home = root.join("home");
+            std::fs::create_dir_all(&home).map_err(|e| io_error(&home, e))?;
+            sandbox.preopen(home, "/app/home", true);
+            sandbox.set_env("HANZO_HOME", "/app/home");
+        }
+
+        let mounts = capabilities.mounts && !context.mount_files.is_empty();
+        if context.assets_files.is_empty() && !mounts {
+            return Ok(sandbox);
+        }
+        // Concurrent executions of the same job must not share their copies
+        let executions = root.join("executions");
+        std::fs::create_dir_all(&executions).map_err(|e| io_error(&executions, e))?;
+        let execution_folder = tempfile::Builder::new()
+            .prefix(&format!("{}-", sanitize_for_file_name(context.execution_id.clone())))
+            .tempdir_in(&executions)
+            .map_err(|e| io_error(&executions, e))?;
+
+        if !context.assets_files.is_empty() {
+            let assets = execution_folder.path().join("assets");
+            let copies = copy_into(&assets, &context.assets_files)?;
+            sandbox.preopen(assets, "/app/assets", false);
+            sandbox.set_env("HANZO_ASSETS", &guest_paths("/app/assets", &copies));
+        }
+
+        if mounts {
+            let mount = execution_folder.path().join("mount");
+            let copies = copy_into(&mount, &context.mount_files)?;
+            sandbox.preopen(mount, "/app/mount", true);
+            sandbox.set_env("HANZO_MOUNT", &guest_paths("/app/mount", &copies));
+            sandbox.mounted_files = context.mount_files.iter().cloned().zip(copies).collect();
+        }
+
+        sandbox.execution_folder = Some(Arc::new(execution_folder));
+        Ok(sandbox)
+    }
+
+    fn set_env(&mut self, key: &str, value: &str) {
+        self.env.push((key.to_string(), value.to_string()));
+    }
+
+    fn preopen(&mut self, host_path: PathBuf, guest_path: &str, writable: bool) {
+        self.preopened_dirs.push(PreopenedDir {
+            host_path,
+            guest_path: guest_path.to_string(),
+            writable,
+        });
+    }
+
+    /// Paths of the mounted files as the guest sees them
+    pub fn guest_mount_files(&self) -> Vec<String> {
+        self.mounted_files
+            .iter()
+            .filter_map(|(_, copy)| Some(format!("/app/mount/{}", copy.file_name()?.to_string_lossy())))
+            .collect()
+    }
+
+    /// Builder of the WASI context of the guest, with stdout and stderr captured
+    pub(crate) fn builder(&self) -> Result<(WasiCtxBuilder, OutputPipes), ToolError> {
+        let pipes = OutputPipes {
+            stdout: MemoryOutputPipe::new(MAX_OUTPUT_BYTES),
+            stderr: MemoryOutputPipe::new(MAX_OUTPUT_BYTES),
+        };
+
+        let mut builder = WasiCtxBuilder::new();
+        builder.stdout(pipes.stdout.clone()).stderr(pipes.stderr.clone());
+        for (key, value) in &self.env {
+            builder.env(key, value);
+        }
+        for dir in &self.preopened_dirs {
+            let (dir_perms, file_perms) = if dir.writable {
+                (DirPerms::all(), FilePerms::all())
+            } else {
+                (DirPerms::READ, FilePerms::READ)
+            };
+            builder
+                .preopened_dir(&dir.host_path, &dir.guest_path, dir_perms, file_perms)
+                .map_err(|e| ToolError::ExecutionError(format!("Failed to preopen {:?}: {}", dir.host_path, e)))?;
+        }
+        Ok((builder, pipes))
+    }
+
+    /// Writes the mount files changed by the guest back to their original location
+    pub(crate) fn sync_mounts(&self) -> Result<(), ToolError> {
+        for (original, copy) in &self.mounted_files {
+            let content = std::fs::read(copy).map_err(|e| io_error(copy, e))?;
+            if std::fs::read(original).ok().as_ref() != Some(&content) {
+                std::fs::write(original, content).map_err(|e| io_error(original, e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copies the files in a new folder, returning the paths of the copies
+fn copy_into(folder: &Path, files: &[PathBuf]) -> Result<Vec<PathBuf>, ToolError> {
+    std::fs::create_dir_all(folder).map_err(|e| io_error(folder, e))?;
+
+    let mut copies = Vec::new();
+    for file in files {
+        let name = file
+            .file_name()
+            .ok_or_else(|| ToolError::ExecutionError(format!("Invalid file path {:?}", file)))?;
+        let copy = folder.join(name);
+        if copy.exists() {
+            return Err(ToolError::ExecutionError(format!(
+                "Two files named {} cannot be mounted together",
+                name.to_string_lossy()
+            )));
+        }
+        std::fs::copy(file, &copy).map_err(|e| io_error(file, e))?;
+        copies.push(copy);
+    }
+    Ok(copies)
+}
+
+fn guest_paths(guest_folder: &str, copies: &[PathBuf]) -> String {
+    copies
+        .iter()
+        .filter_map(|copy| Some(format!("{}/{}", guest_folder, copy.file_name()?.to_string_lossy())))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> ToolError {
+    ToolError::ExecutionError(format!("Failed to prepare {:?} for the execution: {}", path, e))
+}
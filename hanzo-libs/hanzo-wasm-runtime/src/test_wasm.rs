@@ -241,8 +241,11 @@ mod tests {
         let runtime = WasmRuntime::new(config).unwrap();
 
         // Execute without explicitly loading
-        let result = runtime.execute_bytes(wasm_bytes, "multiply", json!([6, 7])).await.unwrap();
-        assert_eq!(result, json!(42));
+        let output = runtime
+            .execute_bytes(wasm_bytes, "multiply", json!([6, 7]), &WasiSandbox::default())
+            .await
+            .unwrap();
+        assert_eq!(output.result, json!(42));
 
         // Module should not be in the list
         assert_eq!(runtime.list_modules().await.len(), 0);
@@ -320,4 +323,145 @@ mod tests {
         runtime.clear_modules().await;
         assert_eq!(runtime.list_modules().await.len(), 0);
     }
-}
\ No newline at end of file
+    /// Test running a component of the hanzo:tool world
+    #[tokio::test]
+    async fn test_execute_component() {
+        // Echoes its input back as the ok case of the result
+        let wat = r#"
+            (component
+                (core module $m
+                    (memory (export "memory") 1)
+                    (global $heap (mut i32) (i32.const 1024))
+                    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                        (local $ptr i32)
+                        global.get $heap
+                        local.set $ptr
+                        global.get $heap
+                        local.get 3
+                        i32.add
+                        global.set $heap
+                        local.get $ptr
+                    )
+                    (func (export "run") (param i32 i32) (result i32)
+                        (i32.store8 (i32.const 16) (i32.const 0))
+                        (i32.store (i32.const 20) (local.get 0))
+                        (i32.store (i32.const 24) (local.get 1))
+                        i32.const 16
+                    )
+                )
+                (core instance $i (instantiate $m))
+                (func $run (param "input" string) (result (result string (error string)))
+                    (canon lift (core func $i "run") (memory $i "memory") (realloc (func $i "realloc")))
+                )
+                (export "run" (func $run))
+            )
+        "#;
+
+        let wasm_bytes = wat::parse_str(wat).unwrap();
+        assert!(is_component(&wasm_bytes));
+        assert!(!is_component(&wat::parse_str("(module)").unwrap()));
+
+        let runtime = WasmRuntime::new(WasmConfig::default()).unwrap();
+        let output = runtime
+            .execute_component(
+                &wasm_bytes,
+                json!({"text": "hi"}),
+                ToolHost::default(),
+                &WasiSandbox::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.result, json!({"text": "hi"}));
+    }
+
+    /// Test capturing what a module writes to stdout through WASI
+    #[tokio::test]
+    async fn test_wasi_stdout_capture() {
+        let wat = r#"
+            (module
+                (import "wasi_snapshot_preview1" "fd_write"
+                    (func $fd_write (param i32 i32 i32 i32) (result i32)))
+                (memory (export "memory") 1)
+                (data (i32.const 16) "hello")
+
+                (func $run (export "run") (result i32)
+                    ;; A single iovec pointing at "hello"
+                    (i32.store (i32.const 0) (i32.const 16))
+                    (i32.store (i32.const 4) (i32.const 5))
+                    (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8))
+                )
+            )
+        "#;
+
+        let wasm_bytes = wat::parse_str(wat).unwrap();
+        let runtime = WasmRuntime::new(WasmConfig::default()).unwrap();
+
+        let output = runtime
+            .execute_bytes(wasm_bytes, "run", json!([]), &WasiSandbox::default())
+            .await
+            .unwrap();
+        assert_eq!(output.result, json!(0));
+        assert_eq!(output.stdout, "hello");
+        assert_eq!(output.stderr, "");
+    }
+
+    /// Test the sandbox only grants what the capabilities allow
+    #[test]
+    fn test_wasi_sandbox_for_execution() {
+        use hanzo_tools::tools::wasm_tools::WasmCapabilities;
+        use hanzo_tools_runner::tools::execution_context::ExecutionContext;
+        use std::collections::HashMap;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mount_file = dir.path().join("notes.txt");
+        std::fs::write(&mount_file, "before").unwrap();
+        let context = ExecutionContext {
+            context_id: "job-1".to_string(),
+            execution_id: "exec-1".to_string(),
+            storage: dir.path().join("storage"),
+            mount_files: vec![mount_file.clone()],
+            ..Default::default()
+        };
+        let envs = HashMap::from([
+            ("BEARER".to_string(), "secret".to_string()),
+            ("X_HANZO_APP_ID".to_string(), "job-1".to_string()),
+        ]);
+
+        // Nothing is granted by default
+        let sandbox = WasiSandbox::for_execution(&context, &WasmCapabilities::default(), &envs).unwrap();
+        assert!(sandbox.preopened_dirs.is_empty());
+        let granted_env = |sandbox: &WasiSandbox, key: &str| sandbox.env.iter().any(|(granted, _)| granted == key);
+        assert!(!granted_env(&sandbox, "BEARER"));
+        assert!(!granted_env(&sandbox, "X_HANZO_APP_ID"));
+
+        let capabilities = WasmCapabilities {
+            home: true,
+            mounts: true,
+            env: vec!["X_HANZO_APP_ID".to_string()],
+            ..Default::default()
+        };
+        let sandbox = WasiSandbox::for_execution(&context, &capabilities, &envs).unwrap();
+        assert_eq!(sandbox.preopened_dirs.len(), 2);
+        assert_eq!(sandbox.preopened_dirs[0].guest_path, "/app/home");
+        assert_eq!(sandbox.preopened_dirs[1].guest_path, "/app/mount");
+        let mount_env = ("HANZO_MOUNT".to_string(), "/app/mount/notes.txt".to_string());
+        assert!(sandbox.env.contains(&mount_env));
+        assert!(granted_env(&sandbox, "X_HANZO_APP_ID"));
+        assert!(!granted_env(&sandbox, "BEARER"));
+        assert_eq!(sandbox.guest_mount_files(), vec!["/app/mount/notes.txt"]);
+
+        // Changes to the copy in the mount folder reach the original file
+        let mount_folder = &sandbox.preopened_dirs[1].host_path;
+        std::fs::write(mount_folder.join("notes.txt"), "after").unwrap();
+        sandbox.sync_mounts().unwrap();
+        assert_eq!(std::fs::read_to_string(&mount_file).unwrap(), "after");
+
+        // Each execution gets its own copies, removed with the sandbox
+        let other = WasiSandbox::for_execution(&context, &capabilities, &envs).unwrap();
+        assert_ne!(other.preopened_dirs[1].host_path, *mount_folder);
+        let mount_folder = mount_folder.clone();
+        drop(sandbox);
+        assert!(!mount_folder.exists());
+        assert!(other.preopened_dirs[1].host_path.exists());
+    }
+}
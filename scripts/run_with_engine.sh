@@ -30,7 +30,6 @@ export EMBEDDINGS_SERVER_URL="http://localhost:3690"
 export USE_NATIVE_EMBEDDINGS="true"
 export USE_GPU="true"
 export DEFAULT_EMBEDDING_MODEL="qwen3-embedding-8b"
-export RERANKER_MODEL="qwen3-reranker-4b"
 
 # Enable all logging
 export LOG_ALL=1